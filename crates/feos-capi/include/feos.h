@@ -0,0 +1,108 @@
+/*
+ * C interface to the cubic equations of state in FeOs.
+ *
+ * All quantities are in SI units (K, Pa, mol/m^3, J/mol, J/mol/K).
+ * Handles returned by the library have to be released with the
+ * corresponding *_free function. All fallible functions return a
+ * FeosStatus; feos_last_error_message returns a description of the last
+ * error on the calling thread.
+ */
+#ifndef FEOS_H
+#define FEOS_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum FeosStatus {
+    FEOS_OK = 0,
+    FEOS_NULL_POINTER = 1,
+    FEOS_INVALID_INPUT = 2,
+    FEOS_NOT_CONVERGED = 3,
+    FEOS_NO_PHASE_SPLIT = 4,
+    FEOS_ERROR = 5,
+    FEOS_PANIC = 6,
+} FeosStatus;
+
+typedef enum FeosCubicModel {
+    FEOS_PENG_ROBINSON = 0,
+    FEOS_REDLICH_KWONG = 1,
+} FeosCubicModel;
+
+typedef enum FeosPhase {
+    FEOS_PHASE_STABLE = 0,
+    FEOS_PHASE_LIQUID = 1,
+    FEOS_PHASE_VAPOR = 2,
+} FeosPhase;
+
+typedef enum FeosProperty {
+    FEOS_TEMPERATURE = 0,
+    FEOS_PRESSURE = 1,
+    FEOS_DENSITY = 2,
+    FEOS_COMPRESSIBILITY = 3,
+    FEOS_RESIDUAL_MOLAR_ENTHALPY = 4,
+    FEOS_RESIDUAL_MOLAR_ENTROPY = 5,
+    FEOS_RESIDUAL_MOLAR_GIBBS_ENERGY = 6,
+} FeosProperty;
+
+typedef struct FeosEos FeosEos;
+typedef struct FeosState FeosState;
+
+const char *feos_last_error_message(void);
+
+/*
+ * Enumerations are passed as int; values that do not correspond to an
+ * enumerator are rejected with FEOS_INVALID_INPUT.
+ */
+
+/* Equation of state; binary_records may be NULL, model is a FeosCubicModel. */
+FeosStatus feos_cubic_from_json(const char *pure_records,
+                                const char *binary_records,
+                                int model,
+                                FeosEos **eos);
+void feos_eos_free(FeosEos *eos);
+size_t feos_eos_components(const FeosEos *eos);
+
+/* States; phase is a FeosPhase and property a FeosProperty. */
+FeosStatus feos_state_new_tp(const FeosEos *eos,
+                             double temperature,
+                             double pressure,
+                             const double *molefracs,
+                             size_t n,
+                             int phase,
+                             FeosState **state);
+void feos_state_free(FeosState *state);
+FeosStatus feos_state_property(const FeosState *state,
+                               int property,
+                               double *value);
+FeosStatus feos_state_ln_phi(const FeosState *state, double *ln_phi, size_t n);
+
+/* Phase equilibria */
+FeosStatus feos_tp_flash(const FeosEos *eos,
+                         double temperature,
+                         double pressure,
+                         const double *feed,
+                         size_t n,
+                         double *x,
+                         double *y,
+                         double *vapor_fraction);
+FeosStatus feos_bubble_point_pressure(const FeosEos *eos,
+                                      double temperature,
+                                      const double *x,
+                                      size_t n,
+                                      double *pressure,
+                                      double *y);
+FeosStatus feos_dew_point_pressure(const FeosEos *eos,
+                                   double temperature,
+                                   const double *y,
+                                   size_t n,
+                                   double *pressure,
+                                   double *x);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FEOS_H */
@@ -0,0 +1,769 @@
+//! C interface to the cubic equations of state in FeOs.
+//!
+//! All quantities are passed in SI units (K, Pa, mol/m³, J/mol, J/mol/K).
+//! Equations of state and states are handed out as opaque pointers that
+//! have to be released with the corresponding `*_free` function. Every
+//! fallible function returns a [`FeosStatus`]; the message of the last
+//! error on the current thread is available via [`feos_last_error_message`].
+//! Panics are caught at the boundary and never unwind into the caller.
+//!
+//! The corresponding C header is `include/feos.h`.
+#![expect(clippy::missing_safety_doc)]
+use feos::cubic::{Cubic, CubicBinaryRecord, CubicParameters, CubicRecord};
+use feos_core::parameter::{BinaryRecord, IdentifierOption, Parameter, PureRecord};
+use feos_core::{
+    Components, Contributions, DensityInitialization, FeosError, PhaseEquilibrium, State,
+};
+use ndarray::Array1;
+use quantity::{JOULE, KELVIN, METER, MOL, PASCAL};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use typenum::P3;
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+/// Status codes returned by all fallible functions.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeosStatus {
+    /// The function succeeded.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// The input (e.g. a json string or a composition) is invalid.
+    InvalidInput = 2,
+    /// An iterative solver did not converge.
+    NotConverged = 3,
+    /// A flash calculation did not result in a phase split.
+    NoPhaseSplit = 4,
+    /// Any other error.
+    Error = 5,
+    /// The library panicked; the state of the handles involved is unspecified.
+    Panic = 6,
+}
+
+impl From<FeosError> for FeosStatus {
+    fn from(error: FeosError) -> Self {
+        let status = match &error {
+            FeosError::NotConverged(_) | FeosError::IterationFailed(_) => Self::NotConverged,
+            FeosError::NoPhaseSplit | FeosError::TrivialSolution => Self::NoPhaseSplit,
+            FeosError::IncompatibleComponents(_, _)
+            | FeosError::IncompatibleParameters(_)
+            | FeosError::InvalidParameters(_)
+            | FeosError::UnsupportedSchemaVersion(_)
+            | FeosError::InvalidRecord(_, _)
+            | FeosError::ComponentsNotFound(_)
+            | FeosError::Serde(_)
+            | FeosError::InvalidState(_, _, _) => Self::InvalidInput,
+            _ => Self::Error,
+        };
+        set_last_error(error.to_string());
+        status
+    }
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = message);
+}
+
+/// Convert a `Result` into a status code, storing the error message.
+fn status(result: Result<(), FeosError>) -> FeosStatus {
+    match result {
+        Ok(()) => FeosStatus::Ok,
+        Err(e) => e.into(),
+    }
+}
+
+/// Status for a null pointer argument, storing the name of the argument.
+fn null_pointer(argument: &str) -> FeosStatus {
+    set_last_error(format!("`{argument}` is a null pointer."));
+    FeosStatus::NullPointer
+}
+
+/// Run `f` and return `on_panic` if it panics, storing the panic message.
+fn catch_unwind<T>(on_panic: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|m| m.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".into());
+        set_last_error(format!("panic: {message}"));
+        on_panic
+    })
+}
+
+/// Run `f` and return [`FeosStatus::Panic`] if it panics.
+fn guard(f: impl FnOnce() -> FeosStatus) -> FeosStatus {
+    catch_unwind(FeosStatus::Panic, f)
+}
+
+/// Return the message of the last error that occurred on the current thread.
+///
+/// The pointer is valid until the next call into the library on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn feos_last_error_message() -> *const c_char {
+    catch_unwind(std::ptr::null(), || {
+        LAST_ERROR.with(|e| e.borrow().as_ptr())
+    })
+}
+
+/// Implement the checked conversion of an integer passed by the caller
+/// into an enum, since out-of-range enum values are undefined behavior.
+macro_rules! impl_try_from_c_int {
+    ($enum:ident, $description:literal, [$($variant:ident),*]) => {
+        impl TryFrom<c_int> for $enum {
+            type Error = FeosError;
+
+            fn try_from(value: c_int) -> Result<Self, FeosError> {
+                $(if value == Self::$variant as c_int {
+                    return Ok(Self::$variant);
+                })*
+                Err(FeosError::IncompatibleParameters(format!(
+                    "{value} is not a valid {}.",
+                    $description
+                )))
+            }
+        }
+    };
+}
+
+/// Type of the cubic equation of state.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeosCubicModel {
+    PengRobinson = 0,
+    RedlichKwong = 1,
+}
+
+impl_try_from_c_int!(FeosCubicModel, "cubic model", [PengRobinson, RedlichKwong]);
+
+/// Phase used to initialize the density iteration of a state.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeosPhase {
+    /// Most stable phase.
+    Stable = 0,
+    Liquid = 1,
+    Vapor = 2,
+}
+
+impl_try_from_c_int!(FeosPhase, "phase", [Stable, Liquid, Vapor]);
+
+impl From<FeosPhase> for DensityInitialization {
+    fn from(phase: FeosPhase) -> Self {
+        match phase {
+            FeosPhase::Stable => DensityInitialization::None,
+            FeosPhase::Liquid => DensityInitialization::Liquid,
+            FeosPhase::Vapor => DensityInitialization::Vapor,
+        }
+    }
+}
+
+/// Scalar properties of a state.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeosProperty {
+    /// Temperature in K.
+    Temperature = 0,
+    /// Pressure in Pa.
+    Pressure = 1,
+    /// Molar density in mol/m³.
+    Density = 2,
+    /// Compressibility factor.
+    Compressibility = 3,
+    /// Residual molar enthalpy in J/mol.
+    ResidualMolarEnthalpy = 4,
+    /// Residual molar entropy in J/mol/K.
+    ResidualMolarEntropy = 5,
+    /// Residual molar Gibbs energy in J/mol.
+    ResidualMolarGibbsEnergy = 6,
+}
+
+impl_try_from_c_int!(
+    FeosProperty,
+    "property",
+    [
+        Temperature,
+        Pressure,
+        Density,
+        Compressibility,
+        ResidualMolarEnthalpy,
+        ResidualMolarEntropy,
+        ResidualMolarGibbsEnergy
+    ]
+);
+
+/// Opaque handle of an equation of state.
+pub struct FeosEos(Arc<Cubic>);
+
+/// Opaque handle of a thermodynamic state.
+pub struct FeosState(State<Cubic>);
+
+unsafe fn str_from_ptr<'a>(ptr: *const c_char) -> Result<&'a str, FeosError> {
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|e| FeosError::IncompatibleParameters(e.to_string()))
+}
+
+unsafe fn array_from_ptr(ptr: *const f64, n: usize) -> Array1<f64> {
+    Array1::from_vec(unsafe { std::slice::from_raw_parts(ptr, n) }.to_vec())
+}
+
+fn cubic_from_json(
+    pure_records: &str,
+    binary_records: Option<&str>,
+    model: FeosCubicModel,
+) -> Result<Cubic, FeosError> {
+    let pure_records: Vec<PureRecord<CubicRecord>> = serde_json::from_str(pure_records)?;
+    let binary_records = binary_records
+        .map(serde_json::from_str::<Vec<BinaryRecord<CubicBinaryRecord>>>)
+        .transpose()?
+        .and_then(|br| {
+            CubicParameters::binary_matrix_from_records(&pure_records, &br, IdentifierOption::Name)
+        });
+    let parameters = Arc::new(CubicParameters::from_records(pure_records, binary_records)?);
+    match model {
+        FeosCubicModel::PengRobinson => Cubic::peng_robinson(parameters, None, None),
+        FeosCubicModel::RedlichKwong => Cubic::redlich_kwong(parameters, None, None),
+    }
+}
+
+/// Create a cubic equation of state from json strings.
+///
+/// `pure_records` is a json list of pure component records. `binary_records`
+/// is an optional (nullable) json list of binary records that are matched
+/// with the pure records by name. `model` is a [`FeosCubicModel`]. On
+/// success, the equation of state is written to `eos` and has to be
+/// released with [`feos_eos_free`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn feos_cubic_from_json(
+    pure_records: *const c_char,
+    binary_records: *const c_char,
+    model: c_int,
+    eos: *mut *mut FeosEos,
+) -> FeosStatus {
+    guard(|| {
+        if pure_records.is_null() {
+            return null_pointer("pure_records");
+        }
+        if eos.is_null() {
+            return null_pointer("eos");
+        }
+        status((|| {
+            let model = FeosCubicModel::try_from(model)?;
+            let pure_records = unsafe { str_from_ptr(pure_records) }?;
+            let binary_records = if binary_records.is_null() {
+                None
+            } else {
+                Some(unsafe { str_from_ptr(binary_records) }?)
+            };
+            let cubic = cubic_from_json(pure_records, binary_records, model)?;
+            unsafe { *eos = Box::into_raw(Box::new(FeosEos(Arc::new(cubic)))) };
+            Ok(())
+        })())
+    })
+}
+
+/// Release an equation of state.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn feos_eos_free(eos: *mut FeosEos) {
+    catch_unwind((), || {
+        if !eos.is_null() {
+            drop(unsafe { Box::from_raw(eos) });
+        }
+    })
+}
+
+/// Return the number of components of an equation of state.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn feos_eos_components(eos: *const FeosEos) -> usize {
+    catch_unwind(0, || {
+        unsafe { eos.as_ref() }.map_or(0, |eos| eos.0.components())
+    })
+}
+
+fn check_composition(eos: &FeosEos, n: usize) -> Result<(), FeosError> {
+    let components = eos.0.components();
+    if n != components {
+        return Err(FeosError::IncompatibleComponents(components, n));
+    }
+    Ok(())
+}
+
+/// Create a state from temperature (K), pressure (Pa) and mole fractions.
+///
+/// `molefracs` has to point to `n` values, where `n` is the number of
+/// components. `phase` is a [`FeosPhase`]. On success, the state is written
+/// to `state` and has to be released with [`feos_state_free`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn feos_state_new_tp(
+    eos: *const FeosEos,
+    temperature: f64,
+    pressure: f64,
+    molefracs: *const f64,
+    n: usize,
+    phase: c_int,
+    state: *mut *mut FeosState,
+) -> FeosStatus {
+    guard(|| {
+        let Some(eos) = (unsafe { eos.as_ref() }) else {
+            return null_pointer("eos");
+        };
+        if molefracs.is_null() {
+            return null_pointer("molefracs");
+        }
+        if state.is_null() {
+            return null_pointer("state");
+        }
+        status((|| {
+            let phase = FeosPhase::try_from(phase)?;
+            check_composition(eos, n)?;
+            let moles = unsafe { array_from_ptr(molefracs, n) } * MOL;
+            let s = State::new_npt(
+                &eos.0,
+                temperature * KELVIN,
+                pressure * PASCAL,
+                &moles,
+                phase.into(),
+            )?;
+            unsafe { *state = Box::into_raw(Box::new(FeosState(s))) };
+            Ok(())
+        })())
+    })
+}
+
+/// Release a state.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn feos_state_free(state: *mut FeosState) {
+    catch_unwind((), || {
+        if !state.is_null() {
+            drop(unsafe { Box::from_raw(state) });
+        }
+    })
+}
+
+/// Evaluate a scalar property of a state, given as [`FeosProperty`], and
+/// write it to `value`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn feos_state_property(
+    state: *const FeosState,
+    property: c_int,
+    value: *mut f64,
+) -> FeosStatus {
+    guard(|| {
+        let Some(state) = (unsafe { state.as_ref() }) else {
+            return null_pointer("state");
+        };
+        let Some(value) = (unsafe { value.as_mut() }) else {
+            return null_pointer("value");
+        };
+        let property = match FeosProperty::try_from(property) {
+            Ok(property) => property,
+            Err(e) => return e.into(),
+        };
+        let s = &state.0;
+        *value = match property {
+            FeosProperty::Temperature => s.temperature.convert_to(KELVIN),
+            FeosProperty::Pressure => s.pressure(Contributions::Total).convert_to(PASCAL),
+            FeosProperty::Density => s.density.convert_to(MOL / METER.powi::<P3>()),
+            FeosProperty::Compressibility => s.compressibility(Contributions::Total),
+            FeosProperty::ResidualMolarEnthalpy => {
+                s.residual_molar_enthalpy().convert_to(JOULE / MOL)
+            }
+            FeosProperty::ResidualMolarEntropy => {
+                s.residual_molar_entropy().convert_to(JOULE / MOL / KELVIN)
+            }
+            FeosProperty::ResidualMolarGibbsEnergy => {
+                s.residual_molar_gibbs_energy().convert_to(JOULE / MOL)
+            }
+        };
+        FeosStatus::Ok
+    })
+}
+
+/// Write the logarithms of the fugacity coefficients of a state to `ln_phi`,
+/// which has to provide space for `n` values.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn feos_state_ln_phi(
+    state: *const FeosState,
+    ln_phi: *mut f64,
+    n: usize,
+) -> FeosStatus {
+    guard(|| {
+        let Some(state) = (unsafe { state.as_ref() }) else {
+            return null_pointer("state");
+        };
+        if ln_phi.is_null() {
+            return null_pointer("ln_phi");
+        }
+        let values = state.0.ln_phi();
+        if values.len() != n {
+            return FeosError::IncompatibleComponents(values.len(), n).into();
+        }
+        unsafe { std::slice::from_raw_parts_mut(ln_phi, n) }
+            .copy_from_slice(values.as_slice().unwrap());
+        FeosStatus::Ok
+    })
+}
+
+/// Perform a Tp-flash for the given temperature (K), pressure (Pa) and feed
+/// mole fractions.
+///
+/// The compositions of the liquid and vapor phases are written to `x` and
+/// `y` (each with space for `n` values) and the molar vapor fraction to
+/// `vapor_fraction`. Returns [`FeosStatus::NoPhaseSplit`] if the feed is stable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn feos_tp_flash(
+    eos: *const FeosEos,
+    temperature: f64,
+    pressure: f64,
+    feed: *const f64,
+    n: usize,
+    x: *mut f64,
+    y: *mut f64,
+    vapor_fraction: *mut f64,
+) -> FeosStatus {
+    guard(|| {
+        let Some(eos) = (unsafe { eos.as_ref() }) else {
+            return null_pointer("eos");
+        };
+        if feed.is_null() {
+            return null_pointer("feed");
+        }
+        if x.is_null() {
+            return null_pointer("x");
+        }
+        if y.is_null() {
+            return null_pointer("y");
+        }
+        if vapor_fraction.is_null() {
+            return null_pointer("vapor_fraction");
+        }
+        status((|| {
+            check_composition(eos, n)?;
+            let feed = unsafe { array_from_ptr(feed, n) } * MOL;
+            let vle = PhaseEquilibrium::tp_flash(
+                &eos.0,
+                temperature * KELVIN,
+                pressure * PASCAL,
+                &feed,
+                None,
+                Default::default(),
+                None,
+            )?;
+            let (liquid, vapor) = (vle.liquid(), vle.vapor());
+            unsafe {
+                std::slice::from_raw_parts_mut(x, n)
+                    .copy_from_slice(liquid.molefracs.as_slice().unwrap());
+                std::slice::from_raw_parts_mut(y, n)
+                    .copy_from_slice(vapor.molefracs.as_slice().unwrap());
+                *vapor_fraction =
+                    (vapor.total_moles / (vapor.total_moles + liquid.total_moles)).into_value();
+            }
+            Ok(())
+        })())
+    })
+}
+
+/// Calculate the bubble point pressure (Pa) and the composition of the
+/// incipient vapor phase for the given temperature (K) and liquid mole fractions.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn feos_bubble_point_pressure(
+    eos: *const FeosEos,
+    temperature: f64,
+    x: *const f64,
+    n: usize,
+    pressure: *mut f64,
+    y: *mut f64,
+) -> FeosStatus {
+    guard(|| {
+        let Some(eos) = (unsafe { eos.as_ref() }) else {
+            return null_pointer("eos");
+        };
+        if x.is_null() {
+            return null_pointer("x");
+        }
+        if pressure.is_null() {
+            return null_pointer("pressure");
+        }
+        if y.is_null() {
+            return null_pointer("y");
+        }
+        status((|| {
+            check_composition(eos, n)?;
+            let x = unsafe { array_from_ptr(x, n) };
+            let vle = PhaseEquilibrium::bubble_point(
+                &eos.0,
+                temperature * KELVIN,
+                &x,
+                None,
+                None,
+                Default::default(),
+            )?;
+            let vapor = vle.vapor();
+            unsafe {
+                *pressure = vapor.pressure(Contributions::Total).convert_to(PASCAL);
+                std::slice::from_raw_parts_mut(y, n)
+                    .copy_from_slice(vapor.molefracs.as_slice().unwrap());
+            }
+            Ok(())
+        })())
+    })
+}
+
+/// Calculate the dew point pressure (Pa) and the composition of the
+/// incipient liquid phase for the given temperature (K) and vapor mole fractions.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn feos_dew_point_pressure(
+    eos: *const FeosEos,
+    temperature: f64,
+    y: *const f64,
+    n: usize,
+    pressure: *mut f64,
+    x: *mut f64,
+) -> FeosStatus {
+    guard(|| {
+        let Some(eos) = (unsafe { eos.as_ref() }) else {
+            return null_pointer("eos");
+        };
+        if y.is_null() {
+            return null_pointer("y");
+        }
+        if pressure.is_null() {
+            return null_pointer("pressure");
+        }
+        if x.is_null() {
+            return null_pointer("x");
+        }
+        status((|| {
+            check_composition(eos, n)?;
+            let y = unsafe { array_from_ptr(y, n) };
+            let vle = PhaseEquilibrium::dew_point(
+                &eos.0,
+                temperature * KELVIN,
+                &y,
+                None,
+                None,
+                Default::default(),
+            )?;
+            let liquid = vle.liquid();
+            unsafe {
+                *pressure = liquid.pressure(Contributions::Total).convert_to(PASCAL);
+                std::slice::from_raw_parts_mut(x, n)
+                    .copy_from_slice(liquid.molefracs.as_slice().unwrap());
+            }
+            Ok(())
+        })())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    const PURE_RECORDS: &str = r#"[
+        {"identifier": {"name": "propane"}, "molarweight": 44.0962, "model_record": {"tc": 369.96, "pc": 4250000.0, "acentric_factor": 0.153}},
+        {"identifier": {"name": "butane"}, "molarweight": 58.123, "model_record": {"tc": 425.2, "pc": 3800000.0, "acentric_factor": 0.199}}
+    ]"#;
+    const BINARY_RECORDS: &str = r#"[
+        {"id1": {"name": "propane"}, "id2": {"name": "butane"}, "model_record": {"k_ij": 0.01}}
+    ]"#;
+
+    fn eos() -> *mut FeosEos {
+        let pure = CString::new(PURE_RECORDS).unwrap();
+        let binary = CString::new(BINARY_RECORDS).unwrap();
+        let mut eos = ptr::null_mut();
+        let status = unsafe {
+            feos_cubic_from_json(
+                pure.as_ptr(),
+                binary.as_ptr(),
+                FeosCubicModel::PengRobinson as c_int,
+                &mut eos,
+            )
+        };
+        assert_eq!(status, FeosStatus::Ok);
+        eos
+    }
+
+    #[test]
+    fn state_properties() {
+        let eos = eos();
+        assert_eq!(unsafe { feos_eos_components(eos) }, 2);
+        let mut state = ptr::null_mut();
+        let z = [0.5, 0.5];
+        let status = unsafe {
+            feos_state_new_tp(
+                eos,
+                300.0,
+                1e5,
+                z.as_ptr(),
+                2,
+                FeosPhase::Vapor as c_int,
+                &mut state,
+            )
+        };
+        assert_eq!(status, FeosStatus::Ok);
+        let mut p = 0.0;
+        let mut z_factor = 0.0;
+        unsafe {
+            feos_state_property(state, FeosProperty::Pressure as c_int, &mut p);
+            feos_state_property(state, FeosProperty::Compressibility as c_int, &mut z_factor);
+        }
+        assert!((p - 1e5).abs() < 1e-6);
+        assert!(z_factor < 1.0 && z_factor > 0.9);
+        let mut ln_phi = [0.0; 2];
+        assert_eq!(
+            unsafe { feos_state_ln_phi(state, ln_phi.as_mut_ptr(), 2) },
+            FeosStatus::Ok
+        );
+        assert!(ln_phi.iter().all(|l| *l < 0.0));
+
+        // wrong number of components
+        let status = unsafe {
+            feos_state_new_tp(
+                eos,
+                300.0,
+                1e5,
+                z.as_ptr(),
+                1,
+                FeosPhase::Vapor as c_int,
+                &mut state,
+            )
+        };
+        assert_eq!(status, FeosStatus::InvalidInput);
+        let message = unsafe { CStr::from_ptr(feos_last_error_message()) };
+        assert!(!message.to_str().unwrap().is_empty());
+
+        unsafe {
+            feos_state_free(state);
+            feos_eos_free(eos);
+        }
+    }
+
+    #[test]
+    fn phase_equilibria() {
+        let eos = eos();
+        let x = [0.5, 0.5];
+        let mut p_bubble = 0.0;
+        let mut y = [0.0; 2];
+        let status = unsafe {
+            feos_bubble_point_pressure(eos, 300.0, x.as_ptr(), 2, &mut p_bubble, y.as_mut_ptr())
+        };
+        assert_eq!(status, FeosStatus::Ok);
+        assert!(y[0] > x[0]);
+
+        let mut p_dew = 0.0;
+        let mut x_dew = [0.0; 2];
+        let status = unsafe {
+            feos_dew_point_pressure(eos, 300.0, x.as_ptr(), 2, &mut p_dew, x_dew.as_mut_ptr())
+        };
+        assert_eq!(status, FeosStatus::Ok);
+        assert!(p_dew < p_bubble);
+
+        let (mut x_flash, mut y_flash, mut beta) = ([0.0; 2], [0.0; 2], 0.0);
+        let status = unsafe {
+            feos_tp_flash(
+                eos,
+                300.0,
+                0.5 * (p_bubble + p_dew),
+                x.as_ptr(),
+                2,
+                x_flash.as_mut_ptr(),
+                y_flash.as_mut_ptr(),
+                &mut beta,
+            )
+        };
+        assert_eq!(status, FeosStatus::Ok);
+        assert!(beta > 0.0 && beta < 1.0);
+        assert!(y_flash[0] > x_flash[0]);
+
+        // single phase feed
+        let status = unsafe {
+            feos_tp_flash(
+                eos,
+                300.0,
+                1e4,
+                x.as_ptr(),
+                2,
+                x_flash.as_mut_ptr(),
+                y_flash.as_mut_ptr(),
+                &mut beta,
+            )
+        };
+        assert_eq!(status, FeosStatus::NoPhaseSplit);
+        unsafe { feos_eos_free(eos) };
+    }
+
+    #[test]
+    fn invalid_json() {
+        let pure = CString::new("[{").unwrap();
+        let mut eos = ptr::null_mut();
+        let status = unsafe {
+            feos_cubic_from_json(
+                pure.as_ptr(),
+                ptr::null(),
+                FeosCubicModel::RedlichKwong as c_int,
+                &mut eos,
+            )
+        };
+        assert_eq!(status, FeosStatus::InvalidInput);
+        assert!(eos.is_null());
+    }
+
+    #[test]
+    fn invalid_enum_values() {
+        let pure = CString::new(PURE_RECORDS).unwrap();
+        let mut eos = ptr::null_mut();
+        let status = unsafe { feos_cubic_from_json(pure.as_ptr(), ptr::null(), 2, &mut eos) };
+        assert_eq!(status, FeosStatus::InvalidInput);
+        assert!(eos.is_null());
+
+        let eos = self::eos();
+        let z = [0.5, 0.5];
+        let mut state = ptr::null_mut();
+        let status = unsafe { feos_state_new_tp(eos, 300.0, 1e5, z.as_ptr(), 2, -1, &mut state) };
+        assert_eq!(status, FeosStatus::InvalidInput);
+        assert!(state.is_null());
+
+        let status = unsafe { feos_state_new_tp(eos, 300.0, 1e5, z.as_ptr(), 2, 0, &mut state) };
+        assert_eq!(status, FeosStatus::Ok);
+        let mut value = 0.0;
+        let status = unsafe { feos_state_property(state, 7, &mut value) };
+        assert_eq!(status, FeosStatus::InvalidInput);
+        let message = unsafe { CStr::from_ptr(feos_last_error_message()) };
+        assert!(message.to_str().unwrap().contains("property"));
+
+        unsafe {
+            feos_state_free(state);
+            feos_eos_free(eos);
+        }
+    }
+
+    #[test]
+    fn null_pointer_and_panic() {
+        let mut eos = ptr::null_mut();
+        let status = unsafe {
+            feos_cubic_from_json(
+                ptr::null(),
+                ptr::null(),
+                FeosCubicModel::PengRobinson as c_int,
+                &mut eos,
+            )
+        };
+        assert_eq!(status, FeosStatus::NullPointer);
+        let message = unsafe { CStr::from_ptr(feos_last_error_message()) };
+        assert!(message.to_str().unwrap().contains("pure_records"));
+
+        let status = guard(|| panic!("boom"));
+        assert_eq!(status, FeosStatus::Panic);
+        let message = unsafe { CStr::from_ptr(feos_last_error_message()) };
+        assert_eq!(message.to_str().unwrap(), "panic: boom");
+    }
+}
@@ -0,0 +1,523 @@
+//! Property and flash calculations with cubic equations of state that are
+//! created from parameter files.
+//!
+//! This crate provides the `feos-cubics` command-line tool. All quantities
+//! are given in SI units (K, Pa, mol/m³, m³/mol, J/mol, J/mol/K). The cubic
+//! equations of state do not include an ideal gas contribution, therefore,
+//! all caloric properties are residual properties.
+//!
+//! With the `server` feature, the calculations are also available via a
+//! lightweight HTTP/json server ([PropertyServer]).
+use feos::cubic::{Cubic, CubicParameters, PengRobinson1978};
+use feos_core::parameter::{IdentifierOption, Parameter};
+use feos_core::{
+    Components, Contributions, DensityInitialization, FeosError, FeosResult, PhaseEquilibrium,
+    Residual, State, StateExpression,
+};
+use indexmap::IndexMap;
+use ndarray::{Array1, arr1};
+use quantity::{JOULE, KELVIN, METER, MOL, PASCAL};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use typenum::P3;
+
+#[cfg(feature = "server")]
+mod server;
+#[cfg(feature = "server")]
+pub use server::PropertyServer;
+
+/// Cubic equations of state that can be created from parameter files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CubicModel {
+    /// Peng-Robinson equation of state.
+    Pr,
+    /// Peng-Robinson equation of state with the revised (1978) polynomial
+    /// for the acentric factor dependence of the alpha function.
+    Pr78,
+    /// Soave-Redlich-Kwong equation of state.
+    Srk,
+}
+
+impl CubicModel {
+    /// Short names of all models.
+    pub const NAMES: [&'static str; 3] = ["pr", "pr78", "srk"];
+
+    /// Create the equation of state for the given parameters.
+    pub fn build(self, parameters: Arc<CubicParameters>) -> FeosResult<Cubic> {
+        match self {
+            Self::Pr => Cubic::peng_robinson(parameters, None, None),
+            Self::Pr78 => Cubic::peng_robinson(parameters, Some(PengRobinson1978.into()), None),
+            Self::Srk => Cubic::redlich_kwong(parameters, None, None),
+        }
+    }
+}
+
+impl FromStr for CubicModel {
+    type Err = FeosError;
+
+    fn from_str(s: &str) -> FeosResult<Self> {
+        match s.to_lowercase().as_str() {
+            "pr" => Ok(Self::Pr),
+            "pr78" => Ok(Self::Pr78),
+            "srk" => Ok(Self::Srk),
+            _ => Err(FeosError::IncompatibleParameters(format!(
+                "unknown equation of state '{s}', expected one of: {}.",
+                Self::NAMES.join(", ")
+            ))),
+        }
+    }
+}
+
+/// Read the records of the given components from json files and create
+/// the equation of state.
+///
+/// Components are identified by their names. Binary records are optional.
+pub fn load_eos<P: AsRef<Path>>(
+    model: CubicModel,
+    components: &[&str],
+    file_pure: P,
+    file_binary: Option<P>,
+) -> FeosResult<Arc<Cubic>> {
+    let parameters = CubicParameters::from_json(
+        components.to_vec(),
+        file_pure,
+        file_binary,
+        IdentifierOption::Name,
+    )?;
+    Ok(Arc::new(model.build(Arc::new(parameters))?))
+}
+
+/// Properties that can be evaluated for every phase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Property {
+    /// Molar density in mol/m³.
+    #[serde(rename = "density")]
+    Density,
+    /// Molar volume in m³/mol.
+    #[serde(rename = "v")]
+    MolarVolume,
+    /// Compressibility factor.
+    #[serde(rename = "z")]
+    Compressibility,
+    /// Residual molar enthalpy in J/mol.
+    #[serde(rename = "h")]
+    ResidualMolarEnthalpy,
+    /// Residual molar entropy in J/mol/K.
+    #[serde(rename = "s")]
+    ResidualMolarEntropy,
+    /// Residual molar Gibbs energy in J/mol.
+    #[serde(rename = "g")]
+    ResidualMolarGibbsEnergy,
+    /// Residual molar isochoric heat capacity in J/mol/K.
+    #[serde(rename = "cv")]
+    ResidualMolarIsochoricHeatCapacity,
+    /// Residual molar isobaric heat capacity in J/mol/K.
+    #[serde(rename = "cp")]
+    ResidualMolarIsobaricHeatCapacity,
+    /// Logarithms of the fugacity coefficients.
+    #[serde(rename = "ln_phi")]
+    LnPhi,
+}
+
+impl Property {
+    /// All available properties.
+    pub const ALL: [Self; 9] = [
+        Self::Density,
+        Self::MolarVolume,
+        Self::Compressibility,
+        Self::ResidualMolarEnthalpy,
+        Self::ResidualMolarEntropy,
+        Self::ResidualMolarGibbsEnergy,
+        Self::ResidualMolarIsochoricHeatCapacity,
+        Self::ResidualMolarIsobaricHeatCapacity,
+        Self::LnPhi,
+    ];
+
+    /// Short name of the property.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Density => "density",
+            Self::MolarVolume => "v",
+            Self::Compressibility => "z",
+            Self::ResidualMolarEnthalpy => "h",
+            Self::ResidualMolarEntropy => "s",
+            Self::ResidualMolarGibbsEnergy => "g",
+            Self::ResidualMolarIsochoricHeatCapacity => "cv",
+            Self::ResidualMolarIsobaricHeatCapacity => "cp",
+            Self::LnPhi => "ln_phi",
+        }
+    }
+
+    /// SI unit of the property.
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Self::Density => "mol/m³",
+            Self::MolarVolume => "m³/mol",
+            Self::Compressibility | Self::LnPhi => "",
+            Self::ResidualMolarEnthalpy | Self::ResidualMolarGibbsEnergy => "J/mol",
+            Self::ResidualMolarEntropy
+            | Self::ResidualMolarIsochoricHeatCapacity
+            | Self::ResidualMolarIsobaricHeatCapacity => "J/mol/K",
+        }
+    }
+
+    /// Evaluate the property for a state.
+    pub fn evaluate<E: Residual>(&self, state: &State<E>) -> PropertyValue {
+        let molar_entropy = JOULE / MOL / KELVIN;
+        PropertyValue::Scalar(match self {
+            Self::Density => state.density.convert_to(MOL / METER.powi::<P3>()),
+            Self::MolarVolume => (1.0 / state.density).convert_to(METER.powi::<P3>() / MOL),
+            Self::Compressibility => state.compressibility(Contributions::Total),
+            Self::ResidualMolarEnthalpy => state.residual_molar_enthalpy().convert_to(JOULE / MOL),
+            Self::ResidualMolarEntropy => state.residual_molar_entropy().convert_to(molar_entropy),
+            Self::ResidualMolarGibbsEnergy => {
+                state.residual_molar_gibbs_energy().convert_to(JOULE / MOL)
+            }
+            Self::ResidualMolarIsochoricHeatCapacity => state
+                .residual_molar_isochoric_heat_capacity()
+                .convert_to(molar_entropy),
+            Self::ResidualMolarIsobaricHeatCapacity => state
+                .residual_molar_isobaric_heat_capacity()
+                .convert_to(molar_entropy),
+            Self::LnPhi => return PropertyValue::Vector(state.ln_phi().to_vec()),
+        })
+    }
+}
+
+impl FromStr for Property {
+    type Err = FeosError;
+
+    fn from_str(s: &str) -> FeosResult<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|p| p.name()).collect();
+                FeosError::IncompatibleParameters(format!(
+                    "unknown property '{s}', expected one of: {}.",
+                    names.join(", ")
+                ))
+            })
+    }
+}
+
+/// Value of a scalar or component specific property.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PropertyValue {
+    Scalar(f64),
+    Vector(Vec<f64>),
+}
+
+impl fmt::Display for PropertyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scalar(value) => write!(f, "{value:.8e}"),
+            Self::Vector(values) => {
+                let values: Vec<_> = values.iter().map(|v| format!("{v:.8e}")).collect();
+                write!(f, "[{}]", values.join(", "))
+            }
+        }
+    }
+}
+
+/// Specification of a property calculation at given temperature and pressure.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Calculation {
+    /// Temperature in K.
+    pub temperature: f64,
+    /// Pressure in Pa.
+    pub pressure: f64,
+    /// Mole fractions of the (feed) composition. Can be omitted for pure components.
+    #[serde(default)]
+    pub molefracs: Option<Vec<f64>>,
+    /// Properties that are evaluated for every phase.
+    pub properties: Vec<Property>,
+    /// User defined quantities ([StateExpression]s) that are evaluated for
+    /// every phase and reported with the properties under the given names.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub expressions: IndexMap<String, StateExpression>,
+    /// Perform a Tp-flash instead of evaluating a single phase.
+    #[serde(default)]
+    pub flash: bool,
+}
+
+/// Composition and properties of a single phase.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PhaseResult {
+    /// Label of the phase in a flash calculation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phase: Option<String>,
+    /// Molar fraction of the phase in the system.
+    pub fraction: f64,
+    /// Mole fractions of the phase.
+    pub molefracs: Vec<f64>,
+    /// Evaluated properties with their short names as keys, followed by
+    /// the user defined expressions.
+    pub properties: IndexMap<String, PropertyValue>,
+}
+
+impl PhaseResult {
+    fn new<E: Residual>(
+        phase: Option<&str>,
+        fraction: f64,
+        state: &State<E>,
+        calculation: &Calculation,
+    ) -> FeosResult<Self> {
+        let mut properties: IndexMap<_, _> = calculation
+            .properties
+            .iter()
+            .map(|p| (p.name().to_string(), p.evaluate(state)))
+            .collect();
+        for (name, expression) in &calculation.expressions {
+            let value = PropertyValue::Scalar(expression.evaluate(state)?);
+            properties.insert(name.clone(), value);
+        }
+        Ok(Self {
+            phase: phase.map(String::from),
+            fraction,
+            molefracs: state.molefracs.to_vec(),
+            properties,
+        })
+    }
+}
+
+/// Results of a [Calculation].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CalculationResult {
+    /// Temperature in K.
+    pub temperature: f64,
+    /// Pressure in Pa.
+    pub pressure: f64,
+    /// Results of all phases present at equilibrium.
+    pub phases: Vec<PhaseResult>,
+}
+
+impl Calculation {
+    /// Evaluate the calculation for the given equation of state.
+    ///
+    /// If a flash is requested for a feed that does not split into two phases,
+    /// the properties of the stable single phase are returned.
+    pub fn run(&self, eos: &Arc<Cubic>) -> FeosResult<CalculationResult> {
+        let n = eos.components();
+        let molefracs = match &self.molefracs {
+            Some(x) if x.len() == n => Array1::from_vec(x.clone()),
+            Some(x) => return Err(FeosError::IncompatibleComponents(n, x.len())),
+            None if n == 1 => arr1(&[1.0]),
+            None => {
+                return Err(FeosError::IncompatibleParameters(String::from(
+                    "the composition of a mixture has to be specified.",
+                )));
+            }
+        };
+        let temperature = self.temperature * KELVIN;
+        let pressure = self.pressure * PASCAL;
+        let moles = molefracs * MOL;
+        let single_phase = || -> FeosResult<_> {
+            let state = State::new_npt(
+                eos,
+                temperature,
+                pressure,
+                &moles,
+                DensityInitialization::None,
+            )?;
+            Ok(vec![PhaseResult::new(None, 1.0, &state, self)?])
+        };
+
+        let phases = if self.flash {
+            match PhaseEquilibrium::tp_flash(
+                eos,
+                temperature,
+                pressure,
+                &moles,
+                None,
+                Default::default(),
+                None,
+            ) {
+                Ok(vle) => {
+                    let (liquid, vapor) = (vle.liquid(), vle.vapor());
+                    let beta =
+                        (vapor.total_moles / (vapor.total_moles + liquid.total_moles)).into_value();
+                    vec![
+                        PhaseResult::new(Some("liquid"), 1.0 - beta, liquid, self)?,
+                        PhaseResult::new(Some("vapor"), beta, vapor, self)?,
+                    ]
+                }
+                Err(FeosError::NoPhaseSplit) => single_phase()?,
+                Err(e) => return Err(e),
+            }
+        } else {
+            single_phase()?
+        };
+        Ok(CalculationResult {
+            temperature: self.temperature,
+            pressure: self.pressure,
+            phases,
+        })
+    }
+}
+
+impl fmt::Display for CalculationResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<10} {:.8e} K", "T", self.temperature)?;
+        write!(f, "{:<10} {:.8e} Pa", "p", self.pressure)?;
+        for (i, phase) in self.phases.iter().enumerate() {
+            let label = phase.phase.clone().unwrap_or(format!("phase {}", i + 1));
+            write!(f, "\n\n{label} (fraction {:.8})", phase.fraction)?;
+            let x = PropertyValue::Vector(phase.molefracs.clone());
+            write!(f, "\n  {:<10} {x}", "x")?;
+            for (name, value) in &phase.properties {
+                let unit = Property::from_str(name).map_or("", |p| p.unit());
+                write!(f, "\n  {}", format!("{name:<10} {value} {unit}").trim_end())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use std::fs;
+
+    const PURE_RECORDS: &str = r#"[
+        {"identifier": {"name": "propane"}, "molarweight": 44.0962, "model_record": {"tc": 369.96, "pc": 4250000.0, "acentric_factor": 0.153}},
+        {"identifier": {"name": "butane"}, "molarweight": 58.123, "model_record": {"tc": 425.2, "pc": 3800000.0, "acentric_factor": 0.199}}
+    ]"#;
+
+    fn eos(components: &[&str]) -> FeosResult<Arc<Cubic>> {
+        let file = std::env::temp_dir().join(format!("feos_cli_{}.json", components.join("_")));
+        fs::write(&file, PURE_RECORDS)?;
+        let eos = load_eos(CubicModel::Pr, components, &file, None);
+        fs::remove_file(&file)?;
+        eos
+    }
+
+    #[test]
+    fn pure_properties() -> FeosResult<()> {
+        let eos = eos(&["propane"])?;
+        let calculation = Calculation {
+            temperature: 300.0,
+            pressure: 1e5,
+            molefracs: None,
+            properties: vec![
+                Property::Density,
+                Property::Compressibility,
+                Property::LnPhi,
+            ],
+            expressions: IndexMap::new(),
+            flash: false,
+        };
+        let result = calculation.run(&eos)?;
+        assert_eq!(result.phases.len(), 1);
+        let state = State::new_npt(
+            &eos,
+            300.0 * KELVIN,
+            1e5 * PASCAL,
+            &(arr1(&[1.0]) * MOL),
+            DensityInitialization::None,
+        )?;
+        let properties = &result.phases[0].properties;
+        assert_eq!(
+            properties["density"],
+            PropertyValue::Scalar(state.density.convert_to(MOL / METER.powi::<P3>()))
+        );
+        assert!(matches!(properties["ln_phi"], PropertyValue::Vector(ref v) if v.len() == 1));
+        assert_eq!(
+            properties.keys().collect::<Vec<_>>(),
+            vec!["density", "z", "ln_phi"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn flash() -> FeosResult<()> {
+        let eos = eos(&["propane", "butane"])?;
+        let mut calculation = Calculation {
+            temperature: 330.0,
+            pressure: 1.1e6,
+            molefracs: Some(vec![0.5, 0.5]),
+            properties: vec![Property::Density],
+            expressions: IndexMap::from([("v".to_string(), "1 / rho".parse()?)]),
+            flash: true,
+        };
+        let result = calculation.run(&eos)?;
+        assert_eq!(result.phases.len(), 2);
+        let (liquid, vapor) = (&result.phases[0], &result.phases[1]);
+        assert_eq!(liquid.phase.as_deref(), Some("liquid"));
+        assert_relative_eq!(liquid.fraction + vapor.fraction, 1.0);
+        for phase in [liquid, vapor] {
+            let (PropertyValue::Scalar(rho), PropertyValue::Scalar(v)) =
+                (&phase.properties["density"], &phase.properties["v"])
+            else {
+                panic!("scalar properties expected");
+            };
+            assert_relative_eq!(rho * v, 1.0, max_relative = 1e-14);
+        }
+        for i in 0..2 {
+            assert_relative_eq!(
+                liquid.fraction * liquid.molefracs[i] + vapor.fraction * vapor.molefracs[i],
+                0.5,
+                max_relative = 1e-8
+            );
+        }
+
+        // single-phase feeds are reported as one phase
+        calculation.pressure = 1e5;
+        let result = calculation.run(&eos)?;
+        assert_eq!(result.phases.len(), 1);
+        assert_eq!(result.phases[0].phase, None);
+
+        // json round trip
+        let json = serde_json::to_string(&result)?;
+        let result: CalculationResult = serde_json::from_str(&json)?;
+        assert_eq!(result.phases[0].molefracs, vec![0.5, 0.5]);
+
+        calculation.molefracs = None;
+        assert!(calculation.run(&eos).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn names() {
+        for property in Property::ALL {
+            assert_eq!(property.name().parse::<Property>().ok(), Some(property));
+            assert_eq!(
+                serde_json::to_string(&property).unwrap(),
+                format!("\"{}\"", property.name())
+            );
+        }
+        assert!("enthalpy".parse::<Property>().is_err());
+        assert_eq!("SRK".parse::<CubicModel>().ok(), Some(CubicModel::Srk));
+    }
+
+    #[test]
+    fn pr78() -> FeosResult<()> {
+        // propane and n-eicosane
+        let parameters = Arc::new(CubicParameters::new_simple(
+            &[369.96, 768.0],
+            &[4250000.0, 1070000.0],
+            &[0.153, 0.907],
+            &[44.0962, 282.553],
+        )?);
+        let pr = CubicModel::Pr.build(parameters.clone())?;
+        let pr78 = "PR78".parse::<CubicModel>()?.build(parameters)?;
+        let t = 300.0 * KELVIN;
+        let p = 1e5 * PASCAL;
+        let density = |eos: &Cubic, i: usize| -> FeosResult<f64> {
+            let eos = Arc::new(eos.subset(&[i]));
+            let moles = arr1(&[1.0]) * MOL;
+            let state = State::new_npt(&eos, t, p, &moles, DensityInitialization::Liquid)?;
+            Ok(state.density.convert_to(MOL / METER.powi::<P3>()))
+        };
+        // both polynomials agree for acentric factors below 0.491
+        assert_relative_eq!(density(&pr, 0)?, density(&pr78, 0)?);
+        assert!((density(&pr, 1)? / density(&pr78, 1)? - 1.0).abs() > 1e-3);
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+use clap::{Arg, ArgAction, ArgMatches, Command, value_parser};
+use feos::cubic::Cubic;
+use feos_cli::{Calculation, CubicModel, Property, load_eos};
+use feos_core::{FeosError, FeosResult, StateExpression};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+/// Arguments that specify the equation of state.
+fn eos_args() -> [Arg; 4] {
+    [
+        Arg::new("eos")
+            .long("eos")
+            .help("Cubic equation of state")
+            .value_parser(CubicModel::NAMES)
+            .default_value("pr"),
+        Arg::new("components")
+            .long("components")
+            .help("Comma-separated names of the components")
+            .value_delimiter(',')
+            .required(true),
+        Arg::new("json")
+            .long("json")
+            .help("Json file containing the pure component records")
+            .value_parser(value_parser!(PathBuf))
+            .required(true),
+        Arg::new("binary")
+            .long("binary")
+            .help("Json file containing the binary records")
+            .value_parser(value_parser!(PathBuf)),
+    ]
+}
+
+fn command() -> Command {
+    let calc = Command::new("calc")
+        .about("Evaluate properties or perform a Tp-flash at given temperature and pressure")
+        .args(eos_args())
+        .arg(
+            Arg::new("t")
+                .long("t")
+                .help("Temperature in K")
+                .value_parser(value_parser!(f64))
+                .required(true),
+        )
+        .arg(
+            Arg::new("p")
+                .long("p")
+                .help("Pressure in Pa")
+                .value_parser(value_parser!(f64))
+                .required(true),
+        )
+        .arg(
+            Arg::new("x")
+                .long("x")
+                .help("Comma-separated mole fractions (not required for pure components)")
+                .value_delimiter(',')
+                .value_parser(value_parser!(f64)),
+        )
+        .arg(
+            Arg::new("props")
+                .long("props")
+                .help("Comma-separated properties (caloric properties are residual properties)")
+                .value_delimiter(',')
+                .value_parser(Property::ALL.map(|p| p.name()))
+                .default_value("density"),
+        )
+        .arg(
+            Arg::new("expr")
+                .long("expr")
+                .help(
+                    "User defined quantity as name=expression, e.g., 'beta=dp_dT/rho' (repeatable)",
+                )
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("flash")
+                .long("flash")
+                .help("Perform a Tp-flash and report the properties of all phases")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .help("Output format")
+                .value_parser(["text", "json"])
+                .default_value("text"),
+        );
+    let command = Command::new("feos-cubics")
+        .about("Property calculations with cubic equations of state. All quantities in SI units.")
+        .version(env!("CARGO_PKG_VERSION"))
+        .subcommand_required(true)
+        .subcommand(calc);
+    #[cfg(feature = "server")]
+    let command = command.subcommand(
+        Command::new("serve")
+            .about("Answer property and flash requests via HTTP/json")
+            .args(eos_args())
+            .arg(
+                Arg::new("address")
+                    .long("address")
+                    .help("Socket address of the server")
+                    .default_value("127.0.0.1:8080"),
+            ),
+    );
+    command
+}
+
+fn eos(matches: &ArgMatches) -> FeosResult<Arc<Cubic>> {
+    let model: CubicModel = matches.get_one::<String>("eos").unwrap().parse()?;
+    let components: Vec<&str> = matches
+        .get_many::<String>("components")
+        .unwrap()
+        .map(String::as_str)
+        .collect();
+    load_eos(
+        model,
+        &components,
+        matches.get_one::<PathBuf>("json").unwrap(),
+        matches.get_one::<PathBuf>("binary"),
+    )
+}
+
+fn calc(matches: &ArgMatches) -> FeosResult<String> {
+    let eos = eos(matches)?;
+    let calculation = Calculation {
+        temperature: *matches.get_one("t").unwrap(),
+        pressure: *matches.get_one("p").unwrap(),
+        molefracs: matches.get_many("x").map(|x| x.copied().collect()),
+        properties: matches
+            .get_many::<String>("props")
+            .unwrap()
+            .map(|p| p.parse())
+            .collect::<FeosResult<_>>()?,
+        expressions: matches
+            .get_many::<String>("expr")
+            .unwrap_or_default()
+            .map(|e| {
+                let (name, expression) = e.split_once('=').ok_or_else(|| {
+                    FeosError::IncompatibleParameters(format!(
+                        "expected name=expression, got '{e}'."
+                    ))
+                })?;
+                Ok((
+                    name.trim().to_string(),
+                    expression.parse::<StateExpression>()?,
+                ))
+            })
+            .collect::<FeosResult<_>>()?,
+        flash: matches.get_flag("flash"),
+    };
+    let result = calculation.run(&eos)?;
+    let output = match matches.get_one::<String>("format").unwrap().as_str() {
+        "json" => serde_json::to_string_pretty(&result)?,
+        _ => result.to_string(),
+    };
+    Ok(output)
+}
+
+#[cfg(feature = "server")]
+fn serve(matches: &ArgMatches) -> FeosResult<String> {
+    let address = matches.get_one::<String>("address").unwrap();
+    let server = feos_cli::PropertyServer::new(&eos(matches)?);
+    eprintln!("listening on {address}");
+    server.serve(address)?;
+    Ok(String::new())
+}
+
+fn main() -> ExitCode {
+    let output = match command().get_matches().subcommand() {
+        Some(("calc", matches)) => calc(matches),
+        #[cfg(feature = "server")]
+        Some(("serve", matches)) => serve(matches),
+        _ => unreachable!("a subcommand is required"),
+    };
+    match output {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use feos_cli::CalculationResult;
+    use std::fs;
+
+    #[test]
+    fn calc_json() -> FeosResult<()> {
+        let file = std::env::temp_dir().join("feos_cli_main.json");
+        fs::write(
+            &file,
+            r#"[{"identifier": {"name": "propane"}, "molarweight": 44.0962, "model_record": {"tc": 369.96, "pc": 4250000.0, "acentric_factor": 0.153}}]"#,
+        )?;
+        let args = [
+            "feos-cubics",
+            "calc",
+            "--eos",
+            "srk",
+            "--components",
+            "propane",
+            "--json",
+            file.to_str().unwrap(),
+            "--t",
+            "300",
+            "--p",
+            "1e5",
+            "--props",
+            "density,h,s",
+            "--expr",
+            "hrt=h_res/(R*T)",
+            "--format",
+            "json",
+        ];
+        let matches = command().try_get_matches_from(args).unwrap();
+        let output = calc(matches.subcommand_matches("calc").unwrap());
+        fs::remove_file(&file)?;
+        let result: CalculationResult = serde_json::from_str(&output?)?;
+        assert_eq!(result.phases.len(), 1);
+        assert_eq!(result.phases[0].properties.len(), 4);
+        assert!(result.phases[0].properties.contains_key("hrt"));
+
+        let invalid = ["feos-cubics", "calc", "--props", "enthalpy"];
+        assert!(command().try_get_matches_from(invalid).is_err());
+        Ok(())
+    }
+}
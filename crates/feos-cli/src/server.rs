@@ -0,0 +1,322 @@
+//! A lightweight HTTP/1.1 server that answers property and flash requests
+//! for an equation of state.
+//!
+//! Endpoints:
+//! - `GET /components`: identifiers of the components of the equation of state.
+//! - `GET /variables`: names, units and descriptions of the quantities that
+//!   can be used in the `expressions` of a [Calculation].
+//! - `POST /properties`: evaluate a [Calculation] given as json in the request body.
+//! - `POST /flash`: same as `/properties`, but always performs a Tp-flash.
+//!
+//! Responses are json documents. Failed requests return an object with an
+//! `error` field and a 4xx status code.
+use crate::Calculation;
+use feos::cubic::Cubic;
+use feos_core::StateExpression;
+use feos_core::parameter::Parameter;
+use serde_json::json;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+const MAX_BODY_LENGTH: usize = 1 << 20;
+/// Maximum length of the request line and the headers.
+const MAX_HEADER_LENGTH: usize = 1 << 13;
+/// Maximum number of connections that are handled concurrently.
+const MAX_CONNECTIONS: usize = 64;
+/// Timeout for reading the request and writing the response.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// HTTP server backed by a single equation of state.
+///
+/// Every connection is handled in its own thread that shares the
+/// equation of state. At most 64 connections are handled
+/// concurrently, further connections wait in the backlog of the listener.
+#[derive(Clone)]
+pub struct PropertyServer {
+    eos: Arc<Cubic>,
+}
+
+impl PropertyServer {
+    /// Create a server for the given equation of state.
+    pub fn new(eos: &Arc<Cubic>) -> Self {
+        Self { eos: eos.clone() }
+    }
+
+    /// Bind to `address` and answer requests until the process is terminated.
+    pub fn serve<A: ToSocketAddrs>(&self, address: A) -> io::Result<()> {
+        self.serve_listener(TcpListener::bind(address)?)
+    }
+
+    /// Answer requests on an existing listener until the process is terminated.
+    ///
+    /// Errors of individual connections are logged and do not stop the server.
+    pub fn serve_listener(&self, listener: TcpListener) -> io::Result<()> {
+        let connections = Arc::new(ConnectionLimit::default());
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    // e.g., too many open files; wait for connections to close
+                    eprintln!("error: failed to accept connection: {e}");
+                    thread::sleep(Duration::from_millis(100));
+                    continue;
+                }
+            };
+            let slot = connections.acquire();
+            let server = self.clone();
+            thread::spawn(move || {
+                let _slot = slot;
+                if let Err(e) = server.handle_connection(stream) {
+                    eprintln!("error: {e}");
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Read a single request from the stream and write the response.
+    pub fn handle_connection(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        let (status, body) = match read_request(&mut stream) {
+            Ok((method, path, body)) => self.respond(&method, &path, &body),
+            Err(e) => error(400, e),
+        };
+        let reason = match status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            _ => "Unprocessable Content",
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+        stream.flush()
+    }
+
+    /// Determine the status code and the json response for a request.
+    pub fn respond(&self, method: &str, path: &str, body: &str) -> (u16, String) {
+        let flash = match (method, path) {
+            ("GET", "/components") => {
+                let (pure_records, _) = self.eos.parameters.records();
+                let identifiers: Vec<_> = pure_records.iter().map(|r| &r.identifier).collect();
+                return (200, json!(identifiers).to_string());
+            }
+            ("GET", "/variables") => {
+                let variables: Vec<_> = StateExpression::variables()
+                    .into_iter()
+                    .map(|(name, unit, description)| {
+                        json!({"name": name, "unit": unit, "description": description})
+                    })
+                    .collect();
+                return (200, json!(variables).to_string());
+            }
+            ("POST", "/properties") => false,
+            ("POST", "/flash") => true,
+            (_, "/components" | "/variables" | "/properties" | "/flash") => {
+                return error(405, format!("method {method} is not allowed for {path}"));
+            }
+            _ => return error(404, format!("unknown endpoint {path}")),
+        };
+        let mut calculation: Calculation = match serde_json::from_str(body) {
+            Ok(calculation) => calculation,
+            Err(e) => return error(400, e),
+        };
+        calculation.flash |= flash;
+        match calculation.run(&self.eos) {
+            Ok(result) => (200, json!(result).to_string()),
+            Err(e) => error(422, e),
+        }
+    }
+}
+
+/// Counting semaphore for the connection threads.
+#[derive(Default)]
+struct ConnectionLimit {
+    active: Mutex<usize>,
+    released: Condvar,
+}
+
+impl ConnectionLimit {
+    /// Block until fewer than [MAX_CONNECTIONS] connections are active.
+    fn acquire(self: &Arc<Self>) -> ConnectionSlot {
+        let mut active = self.active.lock().unwrap_or_else(PoisonError::into_inner);
+        while *active >= MAX_CONNECTIONS {
+            active = self
+                .released
+                .wait(active)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        *active += 1;
+        ConnectionSlot(self.clone())
+    }
+}
+
+/// An active connection that is released when the thread finishes (or panics).
+struct ConnectionSlot(Arc<ConnectionLimit>);
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        let mut active = self.0.active.lock().unwrap_or_else(PoisonError::into_inner);
+        *active -= 1;
+        self.0.released.notify_one();
+    }
+}
+
+fn error<E: ToString>(status: u16, error: E) -> (u16, String) {
+    (status, json!({ "error": error.to_string() }).to_string())
+}
+
+fn read_request(stream: &mut TcpStream) -> Result<(String, String, String), String> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    let mut remaining = MAX_HEADER_LENGTH;
+    read_header_line(&mut reader, &mut line, &mut remaining)?;
+    let mut request_line = line.split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Err(String::from("invalid request line"));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut content_length = 0;
+    loop {
+        read_header_line(&mut reader, &mut line, &mut remaining)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value
+                .trim()
+                .parse()
+                .map_err(|_| String::from("invalid content length"))?;
+        }
+    }
+    if content_length > MAX_BODY_LENGTH {
+        return Err(String::from("request body too large"));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(|e| e.to_string())?;
+    let body = String::from_utf8(body).map_err(|e| e.to_string())?;
+    Ok((method, path, body))
+}
+
+/// Read a line of the request header without exceeding the remaining length.
+fn read_header_line<R: BufRead>(
+    reader: &mut R,
+    line: &mut String,
+    remaining: &mut usize,
+) -> Result<(), String> {
+    line.clear();
+    let length = reader
+        .take(*remaining as u64)
+        .read_line(line)
+        .map_err(|e| e.to_string())?;
+    *remaining -= length;
+    if *remaining == 0 && !line.ends_with('\n') {
+        return Err(String::from("request header too large"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CalculationResult, CubicModel};
+    use feos::cubic::CubicParameters;
+    use serde_json::Value;
+    use std::net::Shutdown;
+
+    fn server() -> PropertyServer {
+        let parameters = CubicParameters::new_simple(
+            &[369.96, 425.2],
+            &[4250000.0, 3800000.0],
+            &[0.153, 0.199],
+            &[44.0962, 58.123],
+        )
+        .unwrap();
+        let eos = CubicModel::Pr.build(Arc::new(parameters)).unwrap();
+        PropertyServer::new(&Arc::new(eos))
+    }
+
+    #[test]
+    fn routes() {
+        let server = server();
+        let body = r#"{"temperature": 330.0, "pressure": 1.1e6, "molefracs": [0.5, 0.5], "properties": ["density"]}"#;
+        let (status, response) = server.respond("POST", "/properties", body);
+        assert_eq!(status, 200);
+        let result: CalculationResult = serde_json::from_str(&response).unwrap();
+        assert_eq!(result.phases.len(), 1);
+
+        let (status, response) = server.respond("POST", "/flash", body);
+        assert_eq!(status, 200);
+        let result: CalculationResult = serde_json::from_str(&response).unwrap();
+        assert_eq!(result.phases.len(), 2);
+
+        assert_eq!(server.respond("GET", "/components", "").0, 200);
+        assert_eq!(server.respond("GET", "/variables", "").0, 200);
+        let body = r#"{"temperature": 330.0, "pressure": 1.1e6, "molefracs": [0.5, 0.5], "properties": [], "expressions": {"beta": "dp_dT / rho"}}"#;
+        let (status, response) = server.respond("POST", "/properties", body);
+        assert_eq!(status, 200);
+        let result: Value = serde_json::from_str(&response).unwrap();
+        assert!(result["phases"][0]["properties"]["beta"].is_f64());
+        let body = r#"{"temperature": 330.0, "pressure": 1.1e6, "molefracs": [0.5, 0.5], "properties": [], "expressions": {"beta": "dp_dT /"}}"#;
+        assert_eq!(server.respond("POST", "/properties", body).0, 400);
+        assert_eq!(server.respond("GET", "/flash", "").0, 405);
+        assert_eq!(server.respond("GET", "/unknown", "").0, 404);
+        assert_eq!(server.respond("POST", "/flash", "{").0, 400);
+        let body = r#"{"temperature": 330.0, "pressure": 1.1e6, "properties": ["density"]}"#;
+        assert_eq!(server.respond("POST", "/flash", body).0, 422);
+    }
+
+    #[test]
+    fn connection() -> io::Result<()> {
+        let server = server();
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        let client = thread::spawn(move || -> io::Result<String> {
+            let mut stream = TcpStream::connect(address)?;
+            let body = r#"{"temperature": 330.0, "pressure": 1.1e6, "molefracs": [0.5, 0.5], "properties": ["density", "ln_phi"]}"#;
+            write!(
+                stream,
+                "POST /flash HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            )?;
+            stream.shutdown(Shutdown::Write)?;
+            let mut response = String::new();
+            stream.read_to_string(&mut response)?;
+            Ok(response)
+        });
+        let (stream, _) = listener.accept()?;
+        server.handle_connection(stream)?;
+        let response = client.join().unwrap()?;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let result: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(result["phases"][1]["phase"], "vapor");
+        Ok(())
+    }
+
+    #[test]
+    fn header_too_large() {
+        let mut line = String::new();
+        let mut remaining = MAX_HEADER_LENGTH;
+        let mut request = "GET /components HTTP/1.1\r\nHost: localhost\r\n".as_bytes();
+        assert!(read_header_line(&mut request, &mut line, &mut remaining).is_ok());
+        assert!(read_header_line(&mut request, &mut line, &mut remaining).is_ok());
+        assert_eq!(line, "Host: localhost\r\n");
+
+        let header = format!("X-Long: {}\r\n", "x".repeat(MAX_HEADER_LENGTH));
+        let mut remaining = MAX_HEADER_LENGTH;
+        let result = read_header_line(&mut header.as_bytes(), &mut line, &mut remaining);
+        assert_eq!(result, Err(String::from("request header too large")));
+    }
+}
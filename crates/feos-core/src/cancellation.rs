@@ -0,0 +1,383 @@
+//! Cooperative cancellation of long-running calculations.
+//!
+//! Phase envelopes, batch flashes and parameter regressions check the
+//! [CancellationToken] that is installed for the current thread with
+//! [with_cancellation] before every point (or iteration) and return
+//! [FeosError::Cancelled] once the token is cancelled. The token can be
+//! cancelled from any other thread, e.g., from the event loop of a GUI or
+//! from a server that lost the connection to its client.
+//!
+//! [CancellableTask] runs a calculation on a separate thread with its own
+//! token and can be awaited as a [Future] without depending on a specific
+//! async runtime.
+//!
+//! ```
+//! # use feos_core::{CancellableTask, FeosError, check_cancelled};
+//! let task = CancellableTask::spawn(|| loop {
+//!     check_cancelled()?;
+//! });
+//! task.cancel();
+//! let result: Result<(), _> = task.join();
+//! assert!(matches!(result, Err(FeosError::Cancelled)));
+//! ```
+use crate::errors::{FeosError, FeosResult};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+
+/// Shared flag that signals the cancellation of a calculation.
+///
+/// Clones of a token share the same flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request the cancellation of all calculations that check the token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the cancellation was requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Return [FeosError::Cancelled] if the cancellation was requested.
+    pub fn check(&self) -> FeosResult<()> {
+        if self.is_cancelled() {
+            Err(FeosError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+thread_local! {
+    static TOKEN: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+/// Restores the previously installed token, also during unwinding.
+struct TokenGuard(Option<CancellationToken>);
+
+impl Drop for TokenGuard {
+    fn drop(&mut self) {
+        TOKEN.with(|t| *t.borrow_mut() = self.0.take());
+    }
+}
+
+/// Install `token` for all calculations called in `f` on the current thread.
+///
+/// Calls can be nested, the innermost token is checked. Batch flashes pass
+/// the token on to their worker threads.
+pub fn with_cancellation<R>(token: CancellationToken, f: impl FnOnce() -> R) -> R {
+    let previous = TOKEN.with(|t| t.borrow_mut().replace(token));
+    let _guard = TokenGuard(previous);
+    f()
+}
+
+/// The token installed for the current thread.
+pub fn current_cancellation() -> Option<CancellationToken> {
+    TOKEN.with(|t| t.borrow().clone())
+}
+
+/// Return [FeosError::Cancelled] if the token installed for the current
+/// thread is cancelled.
+///
+/// Without an installed token, the check always succeeds.
+pub fn check_cancelled() -> FeosResult<()> {
+    TOKEN.with(|t| t.borrow().as_ref().map_or(Ok(()), CancellationToken::check))
+}
+
+/// Result of a task and the waker of the task that awaits it.
+struct Shared<T> {
+    result: Option<FeosResult<T>>,
+    waker: Option<Waker>,
+}
+
+/// Stores the result of a task and wakes the task that awaits it. If the
+/// calculation panics, an error is stored during unwinding.
+struct ResultGuard<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+    result: Option<FeosResult<T>>,
+}
+
+impl<T> Drop for ResultGuard<T> {
+    fn drop(&mut self) {
+        let result = self
+            .result
+            .take()
+            .unwrap_or_else(|| Err(FeosError::Error("the calculation panicked.".into())));
+        let mut shared = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+        shared.result = Some(result);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// A calculation running on a separate thread that can be cancelled.
+///
+/// The task is awaited either by blocking with [CancellableTask::join] or
+/// asynchronously, since it implements [Future]. Dropping the task before
+/// the calculation has finished cancels its token, so that abandoned
+/// calculations (e.g., after a timeout) do not keep running.
+pub struct CancellableTask<T> {
+    token: CancellationToken,
+    shared: Arc<Mutex<Shared<T>>>,
+    handle: Option<JoinHandle<()>>,
+    returned: bool,
+}
+
+impl<T: Send + 'static> CancellableTask<T> {
+    /// Run `f` on a new thread with a new [CancellationToken] installed.
+    pub fn spawn<F>(f: F) -> Self
+    where
+        F: FnOnce() -> FeosResult<T> + Send + 'static,
+    {
+        Self::spawn_with_token(CancellationToken::new(), f)
+    }
+
+    /// Run `f` on a new thread with `token` installed.
+    pub fn spawn_with_token<F>(token: CancellationToken, f: F) -> Self
+    where
+        F: FnOnce() -> FeosResult<T> + Send + 'static,
+    {
+        let shared = Arc::new(Mutex::new(Shared {
+            result: None,
+            waker: None,
+        }));
+        let (t, s) = (token.clone(), shared.clone());
+        let handle = thread::spawn(move || {
+            let mut guard = ResultGuard {
+                shared: s,
+                result: None,
+            };
+            guard.result = Some(with_cancellation(t, f));
+        });
+        Self {
+            token,
+            shared,
+            handle: Some(handle),
+            returned: false,
+        }
+    }
+}
+
+impl<T> CancellableTask<T> {
+    /// Request the cancellation of the task.
+    pub fn cancel(&self) {
+        self.token.cancel()
+    }
+
+    /// The token of the task.
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Whether the calculation has finished.
+    pub fn is_finished(&self) -> bool {
+        self.shared.lock().unwrap().result.is_some()
+    }
+
+    /// Block until the calculation has finished and return its result.
+    ///
+    /// Returns an error if the result was already returned by polling the task.
+    pub fn join(mut self) -> FeosResult<T> {
+        if let Some(handle) = self.handle.take() {
+            handle
+                .join()
+                .map_err(|_| FeosError::Error("the calculation panicked.".into()))?;
+        }
+        self.returned = true;
+        self.shared
+            .lock()
+            .unwrap()
+            .result
+            .take()
+            .unwrap_or_else(|| {
+                Err(FeosError::Error(
+                    "the result of the calculation was already returned.".into(),
+                ))
+            })
+    }
+}
+
+impl<T> Future for CancellableTask<T> {
+    type Output = FeosResult<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.result.take() {
+            Some(result) => {
+                drop(shared);
+                self.returned = true;
+                Poll::Ready(result)
+            }
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Drop for CancellableTask<T> {
+    fn drop(&mut self) {
+        // the token can be shared with other calculations, so that it is only
+        // cancelled if the calculation of the task has not finished
+        let finished = self.returned
+            || self
+                .shared
+                .lock()
+                .map_or(true, |shared| shared.result.is_some());
+        if !finished {
+            self.token.cancel();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::propane_butane;
+    use crate::{Iteration, PhaseDiagram, SolverOptions, with_observer};
+    use ndarray::arr1;
+    use quantity::{KELVIN, MOL};
+    use std::rc::Rc;
+    use std::sync::mpsc;
+    use std::task::Wake;
+
+    #[test]
+    fn nested_tokens() {
+        let outer = CancellationToken::new();
+        let inner = CancellationToken::new();
+        outer.cancel();
+        assert!(check_cancelled().is_ok());
+        with_cancellation(outer, || {
+            assert!(matches!(check_cancelled(), Err(FeosError::Cancelled)));
+            with_cancellation(inner.clone(), || assert!(check_cancelled().is_ok()));
+            assert!(check_cancelled().is_err());
+        });
+        assert!(current_cancellation().is_none());
+        assert!(!inner.is_cancelled());
+    }
+
+    #[test]
+    fn cancel_envelope() -> FeosResult<()> {
+        let eos = Arc::new(propane_butane()?);
+        let moles = arr1(&[0.5, 0.5]) * MOL;
+        let options = (SolverOptions::default(), SolverOptions::default());
+
+        // cancel the trace after the third point
+        let token = CancellationToken::new();
+        let t = token.clone();
+        let points = Rc::new(RefCell::new(0));
+        let p = points.clone();
+        let observer = Rc::new(move |it: &Iteration| {
+            if it.solver == "bubble point line" {
+                *p.borrow_mut() += 1;
+                if it.iteration == 2 {
+                    t.cancel();
+                }
+            }
+        });
+        let envelope = with_cancellation(token, || {
+            with_observer(observer, || {
+                PhaseDiagram::bubble_point_line(&eos, &moles, 250.0 * KELVIN, 20, None, options)
+            })
+        });
+        assert!(matches!(envelope, Err(FeosError::Cancelled)));
+        assert_eq!(*points.borrow(), 3);
+        Ok(())
+    }
+
+    struct ThreadWaker(mpsc::Sender<()>);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            let _ = self.0.send(());
+        }
+    }
+
+    #[test]
+    fn await_task() {
+        let (tx, rx) = mpsc::channel();
+        let waker = Waker::from(Arc::new(ThreadWaker(tx)));
+        let mut cx = Context::from_waker(&waker);
+
+        let (start_tx, start_rx) = mpsc::channel();
+        let mut task = CancellableTask::spawn(move || {
+            start_rx.recv().unwrap();
+            Ok(42)
+        });
+        assert!(Pin::new(&mut task).poll(&mut cx).is_pending());
+        start_tx.send(()).unwrap();
+        rx.recv().unwrap();
+        assert!(matches!(
+            Pin::new(&mut task).poll(&mut cx),
+            Poll::Ready(Ok(42))
+        ));
+    }
+
+    #[test]
+    fn drop_task() {
+        let (start_tx, start_rx) = mpsc::channel::<()>();
+        let task = CancellableTask::spawn(move || {
+            let _ = start_rx.recv();
+            check_cancelled()
+        });
+        let token = task.token().clone();
+        drop(task);
+        assert!(token.is_cancelled());
+        drop(start_tx);
+
+        // finished tasks do not cancel a shared token
+        let token = CancellationToken::new();
+        let task = CancellableTask::spawn_with_token(token.clone(), || Ok(1));
+        assert!(matches!(task.join(), Ok(1)));
+        assert!(!token.is_cancelled());
+    }
+
+    #[test]
+    fn join_after_poll() {
+        let waker = Waker::noop();
+        let mut cx = Context::from_waker(waker);
+        let mut task = CancellableTask::spawn(|| Ok(42));
+        while !task.is_finished() {
+            thread::yield_now();
+        }
+        assert!(matches!(
+            Pin::new(&mut task).poll(&mut cx),
+            Poll::Ready(Ok(42))
+        ));
+        assert!(matches!(task.join(), Err(FeosError::Error(_))));
+    }
+
+    #[test]
+    fn await_panicking_task() {
+        let (tx, rx) = mpsc::channel();
+        let waker = Waker::from(Arc::new(ThreadWaker(tx)));
+        let mut cx = Context::from_waker(&waker);
+
+        let (start_tx, start_rx) = mpsc::channel();
+        let mut task = CancellableTask::<()>::spawn(move || {
+            start_rx.recv().unwrap();
+            panic!("calculation failed")
+        });
+        assert!(Pin::new(&mut task).poll(&mut cx).is_pending());
+        start_tx.send(()).unwrap();
+        rx.recv().unwrap();
+        assert!(matches!(
+            Pin::new(&mut task).poll(&mut cx),
+            Poll::Ready(Err(FeosError::Error(_)))
+        ));
+    }
+}
@@ -0,0 +1,260 @@
+use crate::equation_of_state::{IdealGas, Residual};
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::observer::{report_iteration, report_result};
+use crate::reaction_equilibrium::{ChemicalReactions, ReactionEquilibrium};
+use crate::state::{Contributions, DensityInitialization, State};
+use crate::{ReferenceSystem, SolverOptions, Verbosity};
+use ndarray::Array1;
+use quantity::{Energy, JOULE, KELVIN, MOL, Moles, RGAS, Temperature};
+
+const MAX_ITER: usize = 50;
+const TOL: f64 = 1e-10;
+const REFERENCE_TEMPERATURE: f64 = 298.15;
+
+impl ChemicalReactions {
+    /// Enthalpy of the state relative to the elements at 298.15 K and 1 bar.
+    ///
+    /// The enthalpies of formation of the components are combined with the
+    /// enthalpy of the state relative to the ideal gas at 298.15 K, which is
+    /// calculated from the equation of state, so that the heat capacities of
+    /// the ideal gas model are used instead of the ones in the formation records.
+    pub fn absolute_enthalpy<E: Residual + IdealGas>(
+        &self,
+        state: &State<E>,
+    ) -> FeosResult<Energy> {
+        let n = state.moles.convert_to(MOL);
+        if n.len() != self.formation().len() {
+            return Err(FeosError::IncompatibleComponents(
+                self.formation().len(),
+                n.len(),
+            ));
+        }
+        let h_f: f64 = n
+            .iter()
+            .zip(self.formation())
+            .map(|(n, f)| n * f.enthalpy)
+            .sum();
+        let reference = State::new_nvt(
+            &state.eos,
+            REFERENCE_TEMPERATURE * KELVIN,
+            state.volume,
+            &state.moles,
+        )?;
+        Ok(h_f * 1e3 * JOULE + state.enthalpy(Contributions::Total)
+            - reference.enthalpy(Contributions::IdealGas))
+    }
+}
+
+/// Adiabatic flame temperature and flue gas of a fuel/oxidizer mixture.
+pub struct AdiabaticFlame<E> {
+    /// The adiabatic flame temperature.
+    pub temperature: Temperature,
+    /// The flue gas at the adiabatic flame temperature.
+    pub flue_gas: State<E>,
+    /// The extents of the reactions.
+    pub extents: Moles<Array1<f64>>,
+}
+
+impl<E: Residual + IdealGas> AdiabaticFlame<E> {
+    /// Adiabatic flame temperature for the complete conversion of the feed.
+    ///
+    /// The reactions are carried out one after the other until their limiting
+    /// reactant is consumed. Therefore, only the combustion reactions, and no
+    /// dissociation reactions, should be provided. The flue gas is at the
+    /// pressure of the feed.
+    pub fn complete_combustion(reactions: &ChemicalReactions, feed: &State<E>) -> FeosResult<Self> {
+        let nu = reactions.stoichiometry();
+        let mut n = feed.moles.to_reduced();
+        let mut extents = Array1::zeros(nu.nrows());
+        for (r, nu_r) in nu.outer_iter().enumerate() {
+            let xi = nu_r
+                .iter()
+                .zip(&n)
+                .filter(|&(&v, _)| v < 0.0)
+                .map(|(&v, &n)| -n / v)
+                .fold(f64::INFINITY, f64::min);
+            if !xi.is_finite() {
+                return Err(FeosError::IncompatibleParameters(format!(
+                    "reaction {r} does not have any reactants."
+                )));
+            }
+            n.scaled_add(xi, &nu_r);
+            // remove round-off errors of the limiting reactant
+            n.mapv_inplace(|n| n.max(0.0));
+            extents[r] = xi;
+        }
+        let moles = Moles::from_reduced(n);
+        let pressure = feed.pressure(Contributions::Total);
+
+        // the difference of the enthalpies of formation is released as heat
+        let h = reactions.absolute_enthalpy(feed)?;
+        let products = State::new_npt(
+            &feed.eos,
+            REFERENCE_TEMPERATURE * KELVIN,
+            pressure,
+            &moles,
+            DensityInitialization::Vapor,
+        )?;
+        let heat = h - reactions.absolute_enthalpy(&products)?;
+        let flue_gas = State::new_nph(
+            &feed.eos,
+            pressure,
+            products.molar_enthalpy(Contributions::Total) + heat / products.total_moles,
+            &moles,
+            DensityInitialization::Vapor,
+            Some(2.0 * feed.temperature),
+        )?;
+        Ok(Self {
+            temperature: flue_gas.temperature,
+            flue_gas,
+            extents: Moles::from_reduced(extents),
+        })
+    }
+
+    /// Adiabatic flame temperature for the chemical equilibrium of the flue gas.
+    ///
+    /// The temperature is iterated until the enthalpy of the equilibrium
+    /// mixture equals the enthalpy of the feed. If no initial temperature is
+    /// provided, the iteration starts at the temperature of the complete
+    /// combustion with the same reactions. The flue gas is assumed to be a
+    /// single phase at the pressure of the feed.
+    pub fn equilibrium(
+        reactions: &ChemicalReactions,
+        feed: &State<E>,
+        initial_temperature: Option<Temperature>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER, TOL);
+        let eos = &feed.eos;
+        let pressure = feed.pressure(Contributions::Total);
+        let h = reactions.absolute_enthalpy(feed)?;
+        let mut t = match initial_temperature {
+            Some(t) => t,
+            None => Self::complete_combustion(reactions, feed)?.temperature,
+        };
+
+        log_iter!(verbosity, " iter |    residual    |  temperature  ");
+        log_iter!(verbosity, "{:-<37}", "");
+        let mut old: Option<(Temperature, Energy)> = None;
+        let mut res = f64::INFINITY;
+        for i in 1..=max_iter {
+            let equilibrium =
+                ReactionEquilibrium::tp(eos, reactions, t, pressure, &feed.moles, false, options)?;
+            let flue_gas = &equilibrium.phases[0];
+            let f = reactions.absolute_enthalpy(flue_gas)? - h;
+            res = (f / (RGAS * t * flue_gas.total_moles)).into_value().abs();
+            log_iter!(verbosity, " {:4} | {:14.8e} | {:.8}", i, res, t);
+            report_iteration("adiabatic flame", i, res, &[t.convert_to(KELVIN)]);
+            if res < tol {
+                report_result("adiabatic flame", i, true);
+                log_result!(
+                    verbosity,
+                    "AdiabaticFlame: calculation converged in {} step(s)\n",
+                    i
+                );
+                return Ok(Self {
+                    temperature: t,
+                    flue_gas: flue_gas.clone(),
+                    extents: equilibrium.extents,
+                });
+            }
+
+            // secant step, the first step uses the heat capacity at constant composition
+            let t_new = match old {
+                Some((t_old, f_old)) => t - f * (t - t_old) / (f - f_old),
+                None => {
+                    t - f
+                        / (flue_gas.molar_isobaric_heat_capacity(Contributions::Total)
+                            * flue_gas.total_moles)
+                }
+            };
+            old = Some((t, f));
+            t = t_new;
+        }
+        report_result("adiabatic flame", max_iter, false);
+        Err(FeosError::NotConverged(
+            SolverFailure::new("adiabatic flame")
+                .iterations(max_iter)
+                .residual(res)
+                .temperature(t)
+                .pressure(pressure),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use crate::equation_of_state::EquationOfState;
+    use crate::reaction_equilibrium::FormationRecord;
+    use crate::test_utils::ConstantHeatCapacity;
+    use approx::assert_relative_eq;
+    use ndarray::{arr1, arr2};
+    use quantity::BAR;
+    use std::sync::Arc;
+
+    #[test]
+    fn methane_combustion() -> FeosResult<()> {
+        // CH4, O2, N2, CO2, H2O, CO, H2
+        let parameters = PengRobinsonParameters::new_simple(
+            &[190.56, 154.58, 126.2, 304.1, 647.1, 132.9, 33.19],
+            &[4.599e6, 5.043e6, 3.39e6, 7.38e6, 22.064e6, 3.499e6, 1.313e6],
+            &[0.011, 0.022, 0.039, 0.225, 0.344, 0.066, -0.216],
+            &[16.04, 32.0, 28.014, 44.01, 18.015, 28.01, 2.016],
+        )?;
+        let formation = vec![
+            FormationRecord::new(-50.5, -74.6, 35.7),
+            FormationRecord::new(0.0, 0.0, 29.4),
+            FormationRecord::new(0.0, 0.0, 29.1),
+            FormationRecord::new(-394.4, -393.5, 37.1),
+            FormationRecord::new(-228.6, -241.8, 33.6),
+            FormationRecord::new(-137.2, -110.5, 29.1),
+            FormationRecord::new(0.0, 0.0, 28.8),
+        ];
+        let c_p: Vec<_> = formation.iter().map(|f| f.heat_capacity).collect();
+        let eos = Arc::new(EquationOfState::new(
+            Arc::new(ConstantHeatCapacity::from_heat_capacities(&c_p)),
+            Arc::new(PengRobinson::new(Arc::new(parameters))),
+        ));
+        let feed = State::new_npt(
+            &eos,
+            298.15 * KELVIN,
+            BAR,
+            &Moles::from_reduced(arr1(&[1.0, 2.0, 7.52, 0.0, 0.0, 0.0, 0.0])),
+            DensityInitialization::Vapor,
+        )?;
+
+        // complete combustion heats the products with the lower heating value,
+        // up to the residual enthalpy of the feed
+        let combustion = ChemicalReactions::new(
+            arr2(&[[-1.0, -2.0, 0.0, 1.0, 2.0, 0.0, 0.0]]),
+            formation.clone(),
+        )?;
+        let complete = AdiabaticFlame::complete_combustion(&combustion, &feed)?;
+        let heat = 393.5e3 + 2.0 * 241.8e3 - 74.6e3;
+        let t = 298.15 + heat / (37.1 + 2.0 * 33.6 + 7.52 * 29.1);
+        assert_relative_eq!(complete.temperature, t * KELVIN, max_relative = 1e-3);
+        assert_relative_eq!(complete.extents.to_reduced()[0], 1.0);
+
+        // dissociation of the flue gas lowers the flame temperature
+        let reactions = ChemicalReactions::new(
+            arr2(&[
+                [-1.0, -2.0, 0.0, 1.0, 2.0, 0.0, 0.0],
+                [0.0, 0.5, 0.0, -1.0, 0.0, 1.0, 0.0],
+                [0.0, 0.5, 0.0, 0.0, -1.0, 0.0, 1.0],
+            ]),
+            formation,
+        )?;
+        let flame = AdiabaticFlame::equilibrium(&reactions, &feed, None, Default::default())?;
+        assert!(flame.temperature < complete.temperature - 100.0 * KELVIN);
+        assert!(flame.flue_gas.molefracs[5] > 1e-3);
+        assert!(flame.flue_gas.molefracs[0] < 1e-10);
+        assert_relative_eq!(
+            reactions.absolute_enthalpy(&flame.flue_gas)?,
+            reactions.absolute_enthalpy(&feed)?,
+            max_relative = 1e-8
+        );
+        Ok(())
+    }
+}
@@ -5,19 +5,98 @@
 //! The implementation closely follows the form of the equations given in
 //! [this wikipedia article](https://en.wikipedia.org/wiki/Cubic_equations_of_state#Peng%E2%80%93Robinson_equation_of_state).
 use crate::FeosError;
-use crate::equation_of_state::{Components, Molarweight, Residual};
-use crate::errors::FeosResult;
+use crate::equation_of_state::{Components, Molarweight, ReducingParameters, Residual};
+use crate::errors::{FeosResult, SolverFailure};
 use crate::parameter::{Identifier, Parameter, PureRecord};
 use crate::state::StateHD;
 use ndarray::{Array1, Array2, ScalarOperand};
-use num_dual::DualNum;
+use num_dual::{Dual, Dual3, DualNum, DualNumFloat, first_derivative, third_derivative};
+use num_traits::Float;
 use quantity::{GRAM, MOL, MolarWeight};
 use serde::{Deserialize, Serialize};
-use std::f64::consts::SQRT_2;
 use std::fmt;
 use std::sync::Arc;
 
 const KB_A3: f64 = 13806490.0;
+const MAX_ITER_PURE: usize = 50;
+
+/// Calculate the mechanically stable density roots of a generic cubic equation of state
+///
+/// $$p=\frac{k_BT}{v-b}-\frac{a}{(v+\delta_1b)(v+\delta_2b)}$$
+///
+/// at given temperature and pressure (all quantities in reduced units, with $a$
+/// divided by $k_B$). The cubic polynomial in the compressibility factor is solved
+/// analytically and every root is refined with a few Newton steps. Roots with
+/// $v\leq b$ are discarded. If three real roots exist, the intermediate root, which
+/// is always mechanically unstable, is discarded as well. The remaining densities
+/// are returned in ascending order.
+///
+/// The calculation is generic over the floating point type and can,
+/// e.g., be used with `f32`.
+pub fn cubic_density_roots<F: Float>(
+    temperature: F,
+    pressure: F,
+    a: F,
+    b: F,
+    delta1: F,
+    delta2: F,
+) -> Vec<F> {
+    let c = |x: f64| F::from(x).unwrap();
+    let a_red = a * pressure / temperature.powi(2);
+    let b_red = b * pressure / temperature;
+    let (s, p) = (delta1 + delta2, delta1 * delta2);
+
+    // Z^3 + c2 Z^2 + c1 Z + c0 = 0
+    let c2 = (s - F::one()) * b_red - F::one();
+    let c1 = a_red + p * b_red.powi(2) - s * b_red * (b_red + F::one());
+    let c0 = -(a_red * b_red + p * b_red.powi(2) * (b_red + F::one()));
+
+    // depressed cubic t^3 + p3 t + q3 = 0 with Z = t - c2 / 3
+    let shift = c2 / c(3.0);
+    let p3 = c1 - c2.powi(2) / c(3.0);
+    let q3 = c(2.0) * c2.powi(3) / c(27.0) - c2 * c1 / c(3.0) + c0;
+    let discriminant = (q3 * c(0.5)).powi(2) + (p3 / c(3.0)).powi(3);
+
+    let mut roots = if discriminant > F::zero() || p3 >= F::zero() {
+        let sqrt_d = discriminant.max(F::zero()).sqrt();
+        vec![(-c(0.5) * q3 + sqrt_d).cbrt() + (-c(0.5) * q3 - sqrt_d).cbrt() - shift]
+    } else {
+        let r = c(2.0) * (-p3 / c(3.0)).sqrt();
+        let phi = (c(1.5) * q3 / p3 * (-c(3.0) / p3).sqrt())
+            .max(-F::one())
+            .min(F::one())
+            .acos()
+            / c(3.0);
+        (0..3)
+            .map(|k| r * (phi - c(2.0 * std::f64::consts::PI * k as f64 / 3.0)).cos() - shift)
+            .collect()
+    };
+
+    // polish roots
+    for z in roots.iter_mut() {
+        for _ in 0..3 {
+            let f = ((*z + c2) * *z + c1) * *z + c0;
+            let df = (c(3.0) * *z + c(2.0) * c2) * *z + c1;
+            if df != F::zero() {
+                *z = *z - f / df;
+            }
+        }
+    }
+
+    roots.retain(|&z| z.is_finite() && z > b_red);
+    roots.sort_by(|z1, z2| z1.partial_cmp(z2).unwrap());
+    let tol = c(1e4) * F::epsilon();
+    roots.dedup_by(|z1, z2| (*z1 - *z2).abs() < tol * z2.abs());
+    if roots.len() == 3 {
+        roots.remove(1);
+    }
+    // smallest compressibility factor corresponds to the largest density
+    roots
+        .iter()
+        .rev()
+        .map(|&z| pressure / (z * temperature))
+        .collect()
+}
 
 /// Peng-Robinson parameters for a single substance.
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -50,14 +129,18 @@ impl std::fmt::Display for PengRobinsonRecord {
 }
 
 /// Peng-Robinson parameters for one ore more substances.
-pub struct PengRobinsonParameters {
+///
+/// The parameters are stored in the floating point type `F`. Parameters are
+/// always created in `f64` and can be converted to other floating point types
+/// with [PengRobinsonParameters::cast].
+pub struct PengRobinsonParameters<F = f64> {
     /// Critical temperature in Kelvin
-    tc: Array1<f64>,
-    a: Array1<f64>,
-    b: Array1<f64>,
+    tc: Array1<F>,
+    a: Array1<F>,
+    b: Array1<F>,
     /// Binary interaction parameter
-    k_ij: Array2<f64>,
-    kappa: Array1<f64>,
+    k_ij: Array2<F>,
+    kappa: Array1<F>,
     /// Molar weight in units of g/mol
     molarweight: Array1<f64>,
     /// List of pure component records
@@ -102,6 +185,20 @@ impl PengRobinsonParameters {
             .collect();
         PengRobinsonParameters::from_records(records, None)
     }
+
+    /// Convert the parameters to the floating point type `F`.
+    pub fn cast<F: Float>(&self) -> PengRobinsonParameters<F> {
+        let cast = |&x: &f64| F::from(x).unwrap();
+        PengRobinsonParameters {
+            tc: self.tc.map(cast),
+            a: self.a.map(cast),
+            b: self.b.map(cast),
+            k_ij: self.k_ij.map(cast),
+            kappa: self.kappa.map(cast),
+            molarweight: self.molarweight.clone(),
+            pure_records: self.pure_records.clone(),
+        }
+    }
 }
 
 impl Parameter for PengRobinsonParameters {
@@ -149,18 +246,261 @@ impl Parameter for PengRobinsonParameters {
 }
 
 /// A simple version of the Peng-Robinson equation of state.
-pub struct PengRobinson {
+///
+/// The model is generic over the floating point type `F` of its parameters.
+/// The [Residual] implementation, and therefore [State](crate::State)s and
+/// all phase equilibrium algorithms, require `f64`. In other floating point
+/// types, e.g., `f32` or [DoubleDouble](crate::DoubleDouble), the densities
+/// at given temperature and pressure can be calculated with
+/// [PengRobinson::density_roots_at] and [PengRobinson::density_at], and the
+/// critical point and vapor pressure of pure components with
+/// [PengRobinson::critical_point] and [PengRobinson::vapor_pressure_at].
+pub struct PengRobinson<F = f64> {
     /// Parameters
-    parameters: Arc<PengRobinsonParameters>,
+    parameters: Arc<PengRobinsonParameters<F>>,
 }
 
-impl PengRobinson {
+impl<F> PengRobinson<F> {
     /// Create a new equation of state from a set of parameters.
-    pub fn new(parameters: Arc<PengRobinsonParameters>) -> Self {
+    pub fn new(parameters: Arc<PengRobinsonParameters<F>>) -> Self {
         Self { parameters }
     }
 }
 
+impl<F: DualNumFloat> PengRobinson<F> {
+    /// Mixture energy parameter (divided by the Boltzmann constant) and co-volume.
+    fn mixture_parameters<D: DualNum<F> + Copy>(
+        &self,
+        temperature: D,
+        molefracs: &Array1<D>,
+    ) -> (D, D) {
+        let p = &self.parameters;
+        let x = molefracs;
+        let ak: Vec<_> = (0..p.tc.len())
+            .map(|i| {
+                ((D::one() - (temperature / p.tc[i]).sqrt()) * p.kappa[i] + F::one()).powi(2)
+                    * p.a[i]
+            })
+            .collect();
+
+        // Mixing rules
+        let mut ak_mix = D::zero();
+        for i in 0..ak.len() {
+            for j in 0..ak.len() {
+                ak_mix += (ak[i] * ak[j]).sqrt() * (x[i] * x[j] * (F::one() - p.k_ij[(i, j)]));
+            }
+        }
+        let b = x.iter().zip(&p.b).map(|(&x, &b)| x * b).sum();
+        (ak_mix, b)
+    }
+
+    /// Residual Helmholtz energy divided by $k_BT$ at given temperature,
+    /// volume, total number of molecules and composition.
+    fn helmholtz_energy<D: DualNum<F> + Copy>(
+        &self,
+        temperature: D,
+        volume: D,
+        n: D,
+        molefracs: &Array1<D>,
+    ) -> D {
+        let (ak_mix, b) = self.mixture_parameters(temperature, molefracs);
+        let v = volume;
+        let sqrt_2 = F::SQRT_2();
+        n * ((v / (v - b * n)).ln()
+            - ak_mix / (b * sqrt_2 * F::from(2.0).unwrap() * temperature)
+                * ((v + b * n * (F::one() + sqrt_2)) / (v + b * n * (F::one() - sqrt_2))).ln())
+    }
+}
+
+impl<F: DualNumFloat + DualNum<F>> PengRobinson<F> {
+    /// Mechanically stable densities at given temperature, pressure and
+    /// composition in ascending order.
+    ///
+    /// Temperature is given in Kelvin, pressure and densities in reduced units
+    /// (divided by $k_B$ and per Å³).
+    pub fn density_roots_at(&self, temperature: F, pressure: F, molefracs: &Array1<F>) -> Vec<F> {
+        let (ak_mix, b) = self.mixture_parameters(temperature, molefracs);
+        let sqrt_2 = F::SQRT_2();
+        cubic_density_roots(
+            temperature,
+            pressure,
+            ak_mix,
+            b,
+            F::one() + sqrt_2,
+            F::one() - sqrt_2,
+        )
+    }
+
+    /// Density of the stable phase, i.e., the density root with the lowest
+    /// Gibbs energy, at given temperature, pressure and composition.
+    ///
+    /// Units are the same as in [PengRobinson::density_roots_at].
+    pub fn density_at(&self, temperature: F, pressure: F, molefracs: &Array1<F>) -> Option<F> {
+        let gibbs_energy = |density: F| {
+            let z = pressure / (density * temperature);
+            self.helmholtz_energy(temperature, density.recip(), F::one(), molefracs) + z
+                - F::one()
+                - z.ln()
+        };
+        self.density_roots_at(temperature, pressure, molefracs)
+            .into_iter()
+            .map(|density| (density, gibbs_energy(density)))
+            .min_by(|(_, g1), (_, g2)| g1.partial_cmp(g2).unwrap())
+            .map(|(density, _)| density)
+    }
+
+    /// Pressure of a pure component at given temperature and density.
+    fn pure_pressure<D: DualNum<F> + Copy>(
+        &self,
+        component: usize,
+        temperature: D,
+        density: D,
+    ) -> D {
+        let n = self.parameters.tc.len();
+        let molefracs =
+            Array1::from_shape_fn(n, |i| if i == component { D::one() } else { D::zero() });
+        let (a, b) = self.mixture_parameters(temperature, &molefracs);
+        let b_rho = b * density;
+        density * temperature / (D::one() - b_rho)
+            - a * density.powi(2) / (D::one() + b_rho * (-b_rho + F::from_f64(2.0).unwrap()))
+    }
+
+    /// Pressure and its first and third density derivative, differentiated
+    /// with respect to temperature, of a pure component.
+    fn pure_pressure_derivatives(
+        &self,
+        component: usize,
+        temperature: F,
+        density: F,
+    ) -> [Dual<F, F>; 4] {
+        let temperature = Dual3::from_re(Dual::new(temperature, F::one()));
+        let (p, p_rho, p_rho2, p_rho3) = third_derivative(
+            |density| self.pure_pressure(component, temperature, density),
+            Dual::from_re(density),
+        );
+        [p, p_rho, p_rho2, p_rho3]
+    }
+
+    /// Critical temperature, pressure and density of a pure component.
+    ///
+    /// The critical point is determined from $\left(\frac{\partial p}{\partial\rho}\right)_T
+    /// =\left(\frac{\partial^2 p}{\partial\rho^2}\right)_T=0$ using a Newton solver
+    /// in the floating point type `F`. Units are the same as in
+    /// [PengRobinson::density_roots_at].
+    pub fn critical_point(&self, component: usize) -> FeosResult<[F; 3]> {
+        let p = &self.parameters;
+        let tol = F::epsilon() * F::from_f64(1e2).unwrap();
+        let mut temperature = p.tc[component];
+        // critical compressibility factor of the Peng-Robinson equation of state
+        let mut density = F::from_f64(0.07780 / 0.30740).unwrap() / p.b[component];
+        let mut residual = F::infinity();
+        for _ in 0..MAX_ITER_PURE {
+            let [_, p_rho, p_rho2, p_rho3] =
+                self.pure_pressure_derivatives(component, temperature, density);
+            let det = p_rho.eps * p_rho3.re - p_rho2.re * p_rho2.eps;
+            let dt = (p_rho.re * p_rho3.re - p_rho2.re * p_rho2.re) / det;
+            let drho = (p_rho.eps * p_rho2.re - p_rho2.eps * p_rho.re) / det;
+            temperature -= dt;
+            density -= drho;
+            if !(temperature.is_finite() && density.is_finite()) {
+                return Err(FeosError::IterationFailed(SolverFailure::new(
+                    "critical point",
+                )));
+            }
+            residual = (dt / temperature).abs().max((drho / density).abs());
+            if residual <= tol {
+                let pressure = self.pure_pressure(component, temperature, density);
+                return Ok([temperature, pressure, density]);
+            }
+        }
+        Err(FeosError::NotConverged(
+            SolverFailure::new("critical point")
+                .iterations(MAX_ITER_PURE)
+                .residual(residual.to_f64().unwrap_or(f64::NAN)),
+        ))
+    }
+
+    /// Vapor pressure and the densities of the coexisting vapor and liquid
+    /// phases of a pure component at given temperature.
+    ///
+    /// The phase equilibrium is solved with a Newton solver in the floating
+    /// point type `F`, starting from the asymptotic solution close to the critical
+    /// point. Close to the critical point, the differences in pressure and
+    /// chemical potential between the phases vanish with the third power of the
+    /// difference in densities, so that `f64` calculations fail for temperatures
+    /// within about $10^{-8}T_c$ of the critical temperature. Extended precision
+    /// types, e.g., [DoubleDouble](crate::DoubleDouble) (feature
+    /// `extended_precision`), converge up to about $10^{-12}T_c$.
+    /// Units are the same as in [PengRobinson::density_roots_at].
+    pub fn vapor_pressure_at(&self, component: usize, temperature: F) -> FeosResult<[F; 3]> {
+        let [tc, _, rho_c] = self.critical_point(component)?;
+        if temperature >= tc {
+            return Err(FeosError::SuperCritical);
+        }
+        let tol = F::epsilon() * F::from_f64(1e2).unwrap();
+        let x = Array1::from_shape_fn(self.parameters.tc.len(), |i| {
+            if i == component { F::one() } else { F::zero() }
+        });
+        let pressure = |density| {
+            first_derivative(
+                |density| self.pure_pressure(component, Dual::from_re(temperature), density),
+                density,
+            )
+        };
+        let chemical_potential = |density: F, pressure: F| {
+            self.helmholtz_energy(temperature, density.recip(), F::one(), &x)
+                + pressure / (density * temperature)
+                + density.ln()
+        };
+
+        // initial densities from the expansion around the critical point
+        let [_, p_rho, _, p_rho3] = self.pure_pressure_derivatives(component, tc, rho_c);
+        let delta = (F::from_f64(6.0).unwrap() * p_rho.eps * (tc - temperature) / p_rho3.re)
+            .sqrt()
+            .min(rho_c * F::from_f64(0.99).unwrap());
+        let mut rho_v = rho_c - delta;
+        let mut rho_l = rho_c + delta;
+        let mut residual = F::infinity();
+        for _ in 0..MAX_ITER_PURE {
+            let (p_v, dp_v) = pressure(rho_v);
+            let (p_l, dp_l) = pressure(rho_l);
+            let f1 = p_l - p_v;
+            let f2 = chemical_potential(rho_l, p_l) - chemical_potential(rho_v, p_v);
+
+            // Newton step in the logarithms of the densities
+            let d_rho = rho_v - rho_l;
+            let step_l = (rho_v * temperature * f2 - f1) / (dp_l * d_rho);
+            let step_v = (rho_l * temperature * f2 - f1) / (dp_v * d_rho);
+            let max_step = F::from_f64(0.5).unwrap();
+            let scale = max_step / step_l.abs().max(step_v.abs()).max(max_step);
+            rho_l *= (-step_l * scale).exp();
+            rho_v *= (-step_v * scale).exp();
+            if !(rho_l.is_finite() && rho_v.is_finite()) {
+                return Err(FeosError::IterationFailed(SolverFailure::new(
+                    "vapor pressure",
+                )));
+            }
+            if (rho_l - rho_v).abs() <= F::epsilon().sqrt() * rho_l {
+                return Err(FeosError::TrivialSolution);
+            }
+            // the condition number of the equilibrium conditions grows with the
+            // inverse third power of the difference of the (logarithmic) densities
+            residual = step_l.abs().max(step_v.abs());
+            let width = (rho_l / rho_v).ln();
+            let attainable = tol * (F::one() + width.powi(-3));
+            if residual <= attainable.min(width * tol.sqrt().sqrt()) {
+                let (p, _) = pressure(rho_v);
+                return Ok([p, rho_v, rho_l]);
+            }
+        }
+        Err(FeosError::NotConverged(
+            SolverFailure::new("vapor pressure")
+                .iterations(MAX_ITER_PURE)
+                .residual(residual.to_f64().unwrap_or(f64::NAN)),
+        ))
+    }
+}
+
 impl fmt::Display for PengRobinson {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Peng Robinson")
@@ -183,28 +523,25 @@ impl Residual for PengRobinson {
         0.9 / b
     }
 
-    fn residual_helmholtz_energy<D: DualNum<f64> + Copy>(&self, state: &StateHD<D>) -> D {
-        let p = &self.parameters;
-        let x = &state.molefracs;
-        let ak = (&p.tc.mapv(|tc| (D::one() - (state.temperature / tc).sqrt())) * &p.kappa + 1.0)
-            .mapv(|x| x.powi(2))
-            * &p.a;
+    fn density_roots(
+        &self,
+        temperature: f64,
+        pressure: f64,
+        molefracs: &Array1<f64>,
+    ) -> Option<Vec<f64>> {
+        Some(self.density_roots_at(temperature, pressure, molefracs))
+    }
 
-        // Mixing rules
-        let mut ak_mix = D::zero();
-        for i in 0..ak.len() {
-            for j in 0..ak.len() {
-                ak_mix += (ak[i] * ak[j]).sqrt() * (x[i] * x[j] * (1.0 - p.k_ij[(i, j)]));
-            }
-        }
-        let b = (x * &p.b).sum();
+    fn reducing_parameters(&self, molefracs: &Array1<f64>) -> Option<ReducingParameters> {
+        let records = &self.parameters.pure_records;
+        let tc = records.iter().map(|r| r.model_record.tc).collect();
+        let pc = records.iter().map(|r| r.model_record.pc).collect();
+        Some(ReducingParameters::pseudo_critical(molefracs, &tc, &pc))
+    }
 
-        // Helmholtz energy
+    fn residual_helmholtz_energy<D: DualNum<f64> + Copy>(&self, state: &StateHD<D>) -> D {
         let n = state.moles.sum();
-        let v = state.volume;
-        n * ((v / (v - b * n)).ln()
-            - ak_mix / (b * SQRT_2 * 2.0 * state.temperature)
-                * ((v + b * n * (1.0 + SQRT_2)) / (v + b * n * (1.0 - SQRT_2))).ln())
+        self.helmholtz_energy(state.temperature, state.volume, n, &state.molefracs)
     }
 
     fn residual_helmholtz_energy_contributions<D: DualNum<f64> + Copy + ScalarOperand>(
@@ -227,10 +564,13 @@ impl Molarweight for PengRobinson {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state::{Contributions, State};
-    use crate::{FeosResult, SolverOptions, Verbosity};
+    use crate::ReferenceSystem;
+    use crate::parameter::IdentifierOption;
+    use crate::state::{Contributions, DensityInitialization, State};
+    use crate::{FeosResult, PhaseEquilibrium, PhaseStability, SolverOptions, Verbosity};
     use approx::*;
-    use quantity::{KELVIN, PASCAL};
+    use ndarray::arr1;
+    use quantity::{KELVIN, Moles, PASCAL};
     use std::sync::Arc;
 
     fn pure_record_vec() -> Vec<PureRecord<PengRobinsonRecord>> {
@@ -271,6 +611,29 @@ mod tests {
         serde_json::from_str(records).expect("Unable to parse json.")
     }
 
+    #[test]
+    fn subset() -> FeosResult<()> {
+        let parameters = PengRobinsonParameters::from_records(pure_record_vec(), None)?;
+        let butane = parameters.subset_by_identifier(&["butane"], IdentifierOption::Name)?;
+        assert_eq!(
+            butane.records().0[0].identifier.cas,
+            Some("106-97-8".into())
+        );
+        let reversed = parameters.try_subset(&[1, 0])?;
+        assert_eq!(reversed.tc, arr1(&[425.2, 369.96]));
+        assert!(parameters.try_subset(&[2]).is_err());
+        assert!(parameters.try_subset(&[0, 0]).is_err());
+        assert!(matches!(
+            parameters.subset_by_identifier(&["water"], IdentifierOption::Name),
+            Err(FeosError::ComponentsNotFound(_))
+        ));
+
+        let pr = PengRobinson::new(Arc::new(parameters));
+        assert_eq!(pr.try_subset(&[1])?.components(), 1);
+        assert!(pr.try_subset(&[]).is_err());
+        Ok(())
+    }
+
     #[test]
     fn peng_robinson() -> FeosResult<()> {
         let mixture = pure_record_vec();
@@ -290,4 +653,170 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn density_roots() -> FeosResult<()> {
+        let propane = pure_record_vec()[0].clone();
+        let parameters = PengRobinsonParameters::new_pure(propane)?;
+        let pr = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let t = 300.0 * KELVIN;
+        let p = 5e5 * PASCAL;
+        let moles = Moles::from_reduced(arr1(&[1.0]));
+        let vapor = State::new_npt(&pr, t, p, &moles, DensityInitialization::Vapor)?;
+        let liquid = State::new_npt(&pr, t, p, &moles, DensityInitialization::Liquid)?;
+        assert!(vapor.density < liquid.density);
+        for s in [&vapor, &liquid] {
+            assert_relative_eq!(s.pressure(Contributions::Total), p, max_relative = 1e-10);
+            assert!(s.dp_drho(Contributions::Total).to_reduced() > 0.0);
+        }
+
+        // at 5 bar, propane is a vapor at 300 K
+        let stable = State::new_npt(&pr, t, p, &moles, DensityInitialization::None)?;
+        assert_relative_eq!(stable.density, vapor.density);
+        let stable = State::new_npt(&pr, t, 5e6 * PASCAL, &moles, DensityInitialization::None)?;
+        assert!(stable.density > liquid.density);
+        Ok(())
+    }
+
+    #[test]
+    fn density_roots_f32() -> FeosResult<()> {
+        let propane = pure_record_vec()[0].clone();
+        let parameters = PengRobinsonParameters::new_pure(propane)?;
+        let pr32 = PengRobinson::new(Arc::new(parameters.cast::<f32>()));
+        let pr = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let moles = Moles::from_reduced(arr1(&[1.0]));
+        for (t, p, phase) in [
+            (300.0, 5e5, DensityInitialization::Vapor),
+            (300.0, 5e5, DensityInitialization::Liquid),
+            (300.0, 5e6, DensityInitialization::None),
+        ] {
+            let state = State::new_npt(&pr, t * KELVIN, p * PASCAL, &moles, phase)?;
+            let p_red = (p * PASCAL).to_reduced() as f32;
+            let x = arr1(&[1.0f32]);
+            let density = match phase {
+                DensityInitialization::Vapor => pr32.density_roots_at(t as f32, p_red, &x)[0],
+                DensityInitialization::Liquid => pr32.density_roots_at(t as f32, p_red, &x)[1],
+                _ => pr32.density_at(t as f32, p_red, &x).unwrap(),
+            };
+            assert_relative_eq!(
+                state.density.to_reduced(),
+                density as f64,
+                max_relative = 1e-4
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn metastable_states() -> FeosResult<()> {
+        let propane = pure_record_vec()[0].clone();
+        let parameters = PengRobinsonParameters::new_pure(propane)?;
+        let pr = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let t = 300.0 * KELVIN;
+        let moles = Moles::from_reduced(arr1(&[1.0]));
+        let options = SolverOptions::default();
+
+        let vapor =
+            State::new_npt_metastable(&pr, t, 5e5 * PASCAL, &moles, DensityInitialization::Vapor)?;
+        assert_eq!(vapor.phase_stability(options)?, PhaseStability::Stable);
+        let vapor =
+            State::new_npt_metastable(&pr, t, 12e5 * PASCAL, &moles, DensityInitialization::Vapor)?;
+        assert_eq!(vapor.phase_stability(options)?, PhaseStability::Metastable);
+        let liquid =
+            State::new_npt_metastable(&pr, t, 5e5 * PASCAL, &moles, DensityInitialization::Liquid)?;
+        assert_eq!(liquid.phase_stability(options)?, PhaseStability::Metastable);
+        assert!(
+            State::new_npt_metastable(&pr, t, 1e7 * PASCAL, &moles, DensityInitialization::Vapor)
+                .is_err()
+        );
+
+        let [sv, sl] = State::spinodal(&pr, t, None, options)?;
+        let rho = 0.5 * (sv.density + sl.density);
+        let unstable = State::new_nvt(&pr, t, moles.sum() / rho, &moles)?;
+        assert_eq!(unstable.phase_stability(options)?, PhaseStability::Unstable);
+        Ok(())
+    }
+
+    #[test]
+    fn critical_point_and_vapor_pressure() -> FeosResult<()> {
+        let propane = pure_record_vec()[0].clone();
+        let parameters = PengRobinsonParameters::new_pure(propane)?;
+        let pr = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let options = SolverOptions::default();
+
+        let [tc, pc, rhoc] = pr.critical_point(0)?;
+        let cp = State::critical_point(&pr, None, None, options)?;
+        assert_relative_eq!(tc * KELVIN, cp.temperature, max_relative = 1e-8);
+        assert_relative_eq!(
+            pc,
+            cp.pressure(Contributions::Total).to_reduced(),
+            max_relative = 1e-8
+        );
+        assert_relative_eq!(rhoc, cp.density.to_reduced(), max_relative = 1e-6);
+
+        let [p, rho_v, rho_l] = pr.vapor_pressure_at(0, 300.0)?;
+        let vle = PhaseEquilibrium::pure(&pr, 300.0 * KELVIN, None, options)?;
+        assert_relative_eq!(
+            p,
+            vle.vapor().pressure(Contributions::Total).to_reduced(),
+            max_relative = 1e-10
+        );
+        assert_relative_eq!(
+            rho_v,
+            vle.vapor().density.to_reduced(),
+            max_relative = 1e-10
+        );
+        assert_relative_eq!(
+            rho_l,
+            vle.liquid().density.to_reduced(),
+            max_relative = 1e-10
+        );
+        assert!(matches!(
+            pr.vapor_pressure_at(0, tc),
+            Err(FeosError::SuperCritical)
+        ));
+        Ok(())
+    }
+
+    #[cfg(feature = "extended_precision")]
+    #[test]
+    fn near_critical_vapor_pressure() -> FeosResult<()> {
+        use crate::DoubleDouble;
+
+        let propane = pure_record_vec()[0].clone();
+        let parameters = PengRobinsonParameters::new_pure(propane)?;
+        let pr = PengRobinson::new(Arc::new(parameters.cast::<f64>()));
+        let pr_dd = PengRobinson::new(Arc::new(parameters.cast::<DoubleDouble>()));
+        let [tc, _, rhoc] = pr.critical_point(0)?;
+        let [tc_dd, _, rhoc_dd] = pr_dd.critical_point(0)?;
+        assert_relative_eq!(tc_dd.hi(), tc, max_relative = 1e-15);
+
+        // close to the critical point, the density difference between the phases
+        // approaches 2 sqrt(6 p_rhoT (Tc - T) / p_rhorhorho)
+        let [_, p_rho, _, p_rho3] = pr_dd.pure_pressure_derivatives(0, tc_dd, rhoc_dd);
+        let amplitude = (DoubleDouble::from(24.0) * p_rho.eps * tc_dd / p_rho3.re).sqrt() / rhoc_dd;
+        let width = |[_, rho_v, rho_l]: [DoubleDouble; 3], eps: f64| {
+            ((rho_l - rho_v) / rhoc_dd / DoubleDouble::from(eps).sqrt()).hi()
+        };
+
+        // both precisions agree at 1e-6 Tc below the critical point
+        let eps = 1e-6;
+        let [_, rho_v, rho_l] = pr.vapor_pressure_at(0, tc * (1.0 - eps))?;
+        let t = tc_dd * (DoubleDouble::from(1.0) - DoubleDouble::from(eps));
+        let vle_dd = pr_dd.vapor_pressure_at(0, t)?;
+        assert_relative_eq!(
+            (rho_l - rho_v) / rhoc / eps.sqrt(),
+            width(vle_dd, eps),
+            max_relative = 1e-6
+        );
+
+        // at 1e-12 Tc below the critical point, only the double-double
+        // calculation converges
+        let eps = 1e-12;
+        assert!(pr.vapor_pressure_at(0, tc * (1.0 - eps)).is_err());
+        let t = tc_dd * (DoubleDouble::from(1.0) - DoubleDouble::from(eps));
+        let vle_dd = pr_dd.vapor_pressure_at(0, t)?;
+        assert_relative_eq!(width(vle_dd, eps), amplitude.hi(), max_relative = 1e-5);
+        Ok(())
+    }
 }
@@ -1,10 +1,12 @@
-use crate::equation_of_state::Residual;
-use crate::errors::{FeosError, FeosResult};
-use crate::state::State;
 use crate::ReferenceSystem;
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::observer::{report_iteration, report_result};
+use crate::state::{DensityInitialization, State};
 use ndarray::Array1;
-use quantity::{Density, Moles, Pressure, Temperature};
+use quantity::{Density, METER, MOL, Moles, PASCAL, Pressure, Temperature};
 use std::sync::Arc;
+use typenum::P3;
 
 pub fn density_iteration<E: Residual>(
     eos: &Arc<E>,
@@ -64,7 +66,14 @@ pub fn density_iteration<E: Residual>(
                 error = sp_p - pressure;
                 if rho > 0.85 * maxdensity {
                     if error.is_sign_negative() {
-                        return Err(FeosError::IterationFailed(String::from("density_iteration")));
+                        return Err(FeosError::IterationFailed(
+                            SolverFailure::new("density iteration")
+                                .iterations(k)
+                                .residual(error.to_reduced().abs())
+                                .temperature(temperature)
+                                .pressure(pressure)
+                                .molefracs(&(moles / n).into_value()),
+                        ));
                     } else {
                         rho *= 0.98
                     }
@@ -125,17 +134,80 @@ pub fn density_iteration<E: Residual>(
         }
         // Newton step
         rho += delta_rho;
+        report_iteration(
+            "density iteration",
+            k,
+            error.convert_to(PASCAL),
+            &[rho.convert_to(MOL / METER.powi::<P3>())],
+        );
         if error.to_reduced().abs() < f64::max(abstol, (rho * reltol).to_reduced()) {
             break 'iteration;
         }
     }
+    report_result("density iteration", iterations, iterations <= maxiter);
     if iterations == maxiter + 1 {
-        Err(FeosError::NotConverged("density_iteration".to_owned()))
+        Err(FeosError::NotConverged(
+            SolverFailure::new("density iteration")
+                .iterations(maxiter)
+                .temperature(temperature)
+                .pressure(pressure)
+                .molefracs(&(moles / n).into_value()),
+        ))
     } else {
         Ok(State::new_nvt(eos, temperature, n / rho, moles)?)
     }
 }
 
+/// Create a state from the analytic density roots of the equation of state.
+///
+/// Returns `None` if the equation of state does not provide an analytic
+/// solution, in which case the iterative solver has to be used.
+pub(crate) fn density_from_roots<E: Residual>(
+    eos: &Arc<E>,
+    temperature: Temperature,
+    pressure: Pressure,
+    moles: &Moles<Array1<f64>>,
+    density_initialization: DensityInitialization,
+) -> Option<FeosResult<State<E>>> {
+    let t = temperature.to_reduced();
+    let p = pressure.to_reduced();
+    if !(t.is_finite() && t > 0.0 && p.is_finite() && p > 0.0) {
+        return None;
+    }
+    let m = moles.to_reduced();
+    if m.len() != eos.components() {
+        return None;
+    }
+    let x = &m / m.sum();
+    let roots = eos.density_roots(t, p, &x)?;
+    let n = moles.sum();
+    let state = |rho: f64| State::new_nvt(eos, temperature, n / Density::from_reduced(rho), moles);
+
+    let rho = match density_initialization {
+        DensityInitialization::Vapor => *roots.first()?,
+        DensityInitialization::Liquid => *roots.last()?,
+        DensityInitialization::InitialDensity(rho0) => {
+            let rho0 = rho0.to_reduced();
+            roots
+                .into_iter()
+                .min_by(|r1, r2| (r1 - rho0).abs().total_cmp(&(r2 - rho0).abs()))?
+        }
+        DensityInitialization::None => {
+            // select the root with the lowest Gibbs energy
+            let states = roots.into_iter().map(state).collect::<FeosResult<Vec<_>>>();
+            return match states {
+                Ok(states) => states
+                    .into_iter()
+                    .map(|s| (s.residual_gibbs_energy(), s))
+                    .min_by(|(g1, _), (g2, _)| g1.to_reduced().total_cmp(&g2.to_reduced()))
+                    .map(|(_, s)| Ok(s)),
+                Err(e) => Some(Err(e)),
+            };
+        }
+    };
+    Some(state(rho))
+}
+
 fn pressure_spinodal<E: Residual>(
     eos: &Arc<E>,
     temperature: Temperature,
@@ -157,6 +229,7 @@ fn pressure_spinodal<E: Residual>(
         ));
     }
 
+    let mut residual = f64::INFINITY;
     for _ in 0..maxiter {
         let (p, dpdrho, d2pdrho2) = State::new_nvt(eos, temperature, n / rho, moles)?.d2pdrho2();
 
@@ -168,9 +241,16 @@ fn pressure_spinodal<E: Residual>(
         delta_rho = delta_rho.min(maxdensity - rho); // prevent stepping to rho > maxdensity
         rho += delta_rho;
 
-        if dpdrho.to_reduced().abs() < abstol {
+        residual = dpdrho.to_reduced().abs();
+        if residual < abstol {
             return Ok((p, rho));
         }
     }
-    Err(FeosError::NotConverged("pressure_spinodal".to_owned()))
+    Err(FeosError::NotConverged(
+        SolverFailure::new("pressure_spinodal")
+            .iterations(maxiter)
+            .residual(residual)
+            .temperature(temperature)
+            .molefracs(&(moles / n).into_value()),
+    ))
 }
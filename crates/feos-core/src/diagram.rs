@@ -0,0 +1,314 @@
+//! Thermodynamic diagrams for plotting.
+//!
+//! A [Diagram] combines the phase boundary (the saturation dome of a pure
+//! component or the phase envelope of a mixture) with families of isolines.
+//! All properties are stored in SI units, so that diagrams can be exported
+//! to JSON or CSV and plotted in p-h, T-s, p-T or any other coordinates.
+use crate::SolverOptions;
+use crate::equation_of_state::{IdealGas, Residual};
+use crate::errors::FeosResult;
+use crate::phase_equilibria::{Isoline, PhaseDiagram, SaturationCache};
+use crate::state::{Contributions, StateVec};
+use ndarray::Array1;
+use quantity::{
+    Density, JOULE, KELVIN, METER, MOL, MolarEntropy, Moles, PASCAL, Pressure, Temperature,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fmt::Write;
+use std::sync::Arc;
+use typenum::P3;
+
+/// The type of a line in a [Diagram].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineKind {
+    /// Bubble point line (saturated liquid for pure components).
+    BubblePoint,
+    /// Dew point line (saturated vapor for pure components).
+    DewPoint,
+    Isotherm,
+    Isobar,
+    Isochore,
+    Isentrope,
+}
+
+impl fmt::Display for LineKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            Self::BubblePoint => "bubble_point",
+            Self::DewPoint => "dew_point",
+            Self::Isotherm => "isotherm",
+            Self::Isobar => "isobar",
+            Self::Isochore => "isochore",
+            Self::Isentrope => "isentrope",
+        };
+        write!(f, "{kind}")
+    }
+}
+
+/// A single line of a [Diagram].
+///
+/// Temperatures are given in K, pressures in Pa, densities in mol/m³,
+/// molar enthalpies in J/mol and molar entropies in J/mol/K.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiagramLine {
+    pub kind: LineKind,
+    /// The constant value of an isoline in SI units (`None` for phase boundaries).
+    pub value: Option<f64>,
+    pub temperature: Vec<f64>,
+    pub pressure: Vec<f64>,
+    pub density: Vec<f64>,
+    pub molar_enthalpy: Vec<f64>,
+    pub molar_entropy: Vec<f64>,
+    /// The molar vapor fraction of points on or inside the phase boundary.
+    pub vapor_fraction: Vec<Option<f64>>,
+}
+
+impl DiagramLine {
+    fn from_states<E: Residual + IdealGas>(
+        kind: LineKind,
+        states: &StateVec<'_, E>,
+        vapor_fraction: f64,
+    ) -> Self {
+        let c = Contributions::Total;
+        Self {
+            kind,
+            value: None,
+            temperature: states.temperature().convert_to(KELVIN).to_vec(),
+            pressure: states.pressure().convert_to(PASCAL).to_vec(),
+            density: states
+                .density()
+                .convert_to(MOL / METER.powi::<P3>())
+                .to_vec(),
+            molar_enthalpy: states.molar_enthalpy(c).convert_to(JOULE / MOL).to_vec(),
+            molar_entropy: states
+                .molar_entropy(c)
+                .convert_to(JOULE / MOL / KELVIN)
+                .to_vec(),
+            vapor_fraction: vec![Some(vapor_fraction); states.len()],
+        }
+    }
+
+    fn from_isoline<E: Residual + IdealGas>(
+        kind: LineKind,
+        value: f64,
+        isoline: &Isoline<E>,
+    ) -> Self {
+        let c = Contributions::Total;
+        Self {
+            kind,
+            value: Some(value),
+            temperature: isoline.temperature().convert_to(KELVIN).to_vec(),
+            pressure: isoline.pressure().convert_to(PASCAL).to_vec(),
+            density: isoline
+                .density()
+                .convert_to(MOL / METER.powi::<P3>())
+                .to_vec(),
+            molar_enthalpy: isoline.molar_enthalpy(c).convert_to(JOULE / MOL).to_vec(),
+            molar_entropy: isoline
+                .molar_entropy(c)
+                .convert_to(JOULE / MOL / KELVIN)
+                .to_vec(),
+            vapor_fraction: isoline.points.iter().map(|p| p.vapor_fraction()).collect(),
+        }
+    }
+}
+
+/// A thermodynamic diagram consisting of the phase boundary and
+/// families of isolines of a fluid with fixed composition.
+///
+/// Isotherms and isobars are available for pure components and mixtures,
+/// isochores and isentropes only for pure components.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Diagram {
+    /// The composition of the fluid.
+    pub molefracs: Vec<f64>,
+    pub lines: Vec<DiagramLine>,
+}
+
+impl Diagram {
+    /// Create a diagram of a pure component containing the saturation
+    /// dome from `min_temperature` up to the critical point.
+    pub fn pure<E: Residual + IdealGas>(
+        eos: &Arc<E>,
+        min_temperature: Temperature,
+        npoints: usize,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let vle = PhaseDiagram::pure(eos, min_temperature, npoints, None, options)?;
+        Ok(Self {
+            molefracs: vec![1.0],
+            lines: vec![
+                DiagramLine::from_states(LineKind::BubblePoint, &vle.liquid(), 0.0),
+                DiagramLine::from_states(LineKind::DewPoint, &vle.vapor(), 1.0),
+            ],
+        })
+    }
+
+    /// Create a diagram of a mixture with given composition containing
+    /// the bubble and dew point lines from `min_temperature` up to the
+    /// critical point. The pure component vapor pressures used to
+    /// initialize both lines are only calculated once.
+    pub fn mixture<E: Residual + IdealGas>(
+        eos: &Arc<E>,
+        moles: &Moles<Array1<f64>>,
+        min_temperature: Temperature,
+        npoints: usize,
+        options: (SolverOptions, SolverOptions),
+    ) -> FeosResult<Self> {
+        let moles = eos.validate_moles(Some(moles))?;
+        let cache = SaturationCache::new(eos, 1e-3 * KELVIN, 2 * eos.components())?;
+        let bubble = PhaseDiagram::bubble_point_line_with_cache(
+            eos,
+            &moles,
+            min_temperature,
+            npoints,
+            None,
+            &cache,
+            options,
+        )?;
+        let dew = PhaseDiagram::dew_point_line_with_cache(
+            eos,
+            &moles,
+            min_temperature,
+            npoints,
+            None,
+            &cache,
+            options,
+        )?;
+        Ok(Self {
+            molefracs: (&moles / moles.sum()).into_value().to_vec(),
+            lines: vec![
+                DiagramLine::from_states(LineKind::BubblePoint, &bubble.liquid(), 0.0),
+                DiagramLine::from_states(LineKind::DewPoint, &dew.vapor(), 1.0),
+            ],
+        })
+    }
+
+    fn moles(&self) -> Option<Moles<Array1<f64>>> {
+        (self.molefracs.len() > 1).then(|| Array1::from_vec(self.molefracs.clone()) * MOL)
+    }
+
+    /// Add isotherms at the given temperatures, each evaluated at the given pressures.
+    pub fn with_isotherms<E: Residual + IdealGas>(
+        mut self,
+        eos: &Arc<E>,
+        temperatures: &Temperature<Array1<f64>>,
+        pressures: &Pressure<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let moles = self.moles();
+        for t in temperatures.into_iter() {
+            let isoline = match &moles {
+                Some(n) => Isoline::isotherm_mixture(eos, n, t, pressures, options)?,
+                None => Isoline::isotherm(eos, t, pressures, options)?,
+            };
+            let value = t.convert_to(KELVIN);
+            self.lines.push(DiagramLine::from_isoline(
+                LineKind::Isotherm,
+                value,
+                &isoline,
+            ));
+        }
+        Ok(self)
+    }
+
+    /// Add isobars at the given pressures, each evaluated at the given temperatures.
+    pub fn with_isobars<E: Residual + IdealGas>(
+        mut self,
+        eos: &Arc<E>,
+        pressures: &Pressure<Array1<f64>>,
+        temperatures: &Temperature<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let moles = self.moles();
+        for p in pressures.into_iter() {
+            let isoline = match &moles {
+                Some(n) => Isoline::isobar_mixture(eos, n, p, temperatures, options)?,
+                None => Isoline::isobar(eos, p, temperatures, options)?,
+            };
+            let value = p.convert_to(PASCAL);
+            self.lines
+                .push(DiagramLine::from_isoline(LineKind::Isobar, value, &isoline));
+        }
+        Ok(self)
+    }
+
+    /// Add isochores at the given densities, each evaluated at the given
+    /// temperatures (only for pure components).
+    pub fn with_isochores<E: Residual + IdealGas>(
+        mut self,
+        eos: &Arc<E>,
+        densities: &Density<Array1<f64>>,
+        temperatures: &Temperature<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        for rho in densities.into_iter() {
+            let isoline = Isoline::isochore(eos, rho, temperatures, options)?;
+            let value = rho.convert_to(MOL / METER.powi::<P3>());
+            self.lines.push(DiagramLine::from_isoline(
+                LineKind::Isochore,
+                value,
+                &isoline,
+            ));
+        }
+        Ok(self)
+    }
+
+    /// Add isentropes at the given molar entropies, each evaluated at the
+    /// given pressures (only for pure components).
+    pub fn with_isentropes<E: Residual + IdealGas>(
+        mut self,
+        eos: &Arc<E>,
+        molar_entropies: &MolarEntropy<Array1<f64>>,
+        pressures: &Pressure<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        for s in molar_entropies.into_iter() {
+            let isoline = Isoline::isentrope(eos, s, pressures, options)?;
+            let value = s.convert_to(JOULE / MOL / KELVIN);
+            self.lines.push(DiagramLine::from_isoline(
+                LineKind::Isentrope,
+                value,
+                &isoline,
+            ));
+        }
+        Ok(self)
+    }
+
+    /// Serialize the diagram to JSON.
+    pub fn to_json(&self) -> FeosResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Serialize the diagram to CSV with one row per point.
+    ///
+    /// The column `line` contains the index of the line the point belongs to.
+    /// Missing values (the constant value of phase boundaries and the vapor
+    /// fraction of single phase states) are left empty.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "line,kind,value,temperature,pressure,density,molar_enthalpy,molar_entropy,vapor_fraction\n",
+        );
+        let optional = |x: Option<f64>| x.map_or(String::new(), |x| x.to_string());
+        for (i, line) in self.lines.iter().enumerate() {
+            for j in 0..line.temperature.len() {
+                writeln!(
+                    csv,
+                    "{i},{},{},{},{},{},{},{},{}",
+                    line.kind,
+                    optional(line.value),
+                    line.temperature[j],
+                    line.pressure[j],
+                    line.density[j],
+                    line.molar_enthalpy[j],
+                    line.molar_entropy[j],
+                    optional(line.vapor_fraction[j])
+                )
+                .unwrap();
+            }
+        }
+        csv
+    }
+}
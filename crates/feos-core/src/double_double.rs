@@ -0,0 +1,1252 @@
+//! Double-double floating point numbers for extended-precision calculations.
+//!
+//! A [DoubleDouble] represents a number as the unevaluated sum of two `f64`
+//! values and provides roughly 32 significant digits. It implements
+//! [Float](num_traits::Float) and [DualNum], so that it can be used as
+//! the floating point type of generic models, e.g.,
+//! [PengRobinson](crate::cubic::PengRobinson), and of dual numbers.
+//!
+//! The arithmetic follows the algorithms of the QD library (Hida, Li and
+//! Bailey, 2001).
+use ndarray::ScalarOperand;
+use num_dual::{DualNum, DualStruct};
+use num_traits::{
+    Float, FloatConst, FromPrimitive, Inv, Num, NumCast, One, Signed, ToPrimitive, Zero,
+};
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::num::{FpCategory, ParseFloatError};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+use std::str::FromStr;
+
+/// Floating point number with approximately twice the precision of `f64`.
+///
+/// The value is the sum `hi + lo` of two non-overlapping `f64` values with
+/// `|lo| <= ulp(hi) / 2`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DoubleDouble {
+    hi: f64,
+    lo: f64,
+}
+
+/// Sum of two `f64` values and its rounding error.
+#[inline]
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+/// Sum of two `f64` values with `|a| >= |b|` and its rounding error.
+#[inline]
+fn quick_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    (s, b - (s - a))
+}
+
+/// Product of two `f64` values and its rounding error.
+#[inline]
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    (p, a.mul_add(b, -p))
+}
+
+impl DoubleDouble {
+    /// Create a number from the (not necessarily normalized) sum of two `f64` values.
+    pub fn new(hi: f64, lo: f64) -> Self {
+        let (hi, lo) = two_sum(hi, lo);
+        Self::renormalized(hi, lo)
+    }
+
+    /// The leading part of the number.
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    /// The trailing part of the number.
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    #[inline]
+    const fn from_hi(hi: f64) -> Self {
+        Self { hi, lo: 0.0 }
+    }
+
+    /// Build a number from an already normalized pair, discarding the
+    /// trailing part of non-finite values.
+    #[inline]
+    fn renormalized(hi: f64, lo: f64) -> Self {
+        if hi.is_finite() {
+            Self { hi, lo }
+        } else {
+            Self { hi, lo: 0.0 }
+        }
+    }
+
+    #[inline]
+    fn from_quick_two_sum(a: f64, b: f64) -> Self {
+        if !a.is_finite() {
+            return Self { hi: a, lo: 0.0 };
+        }
+        let (hi, lo) = quick_two_sum(a, b);
+        Self::renormalized(hi, lo)
+    }
+
+    /// Exact multiplication with a power of two.
+    #[inline]
+    fn mul_pow2(self, x: f64) -> Self {
+        Self {
+            hi: self.hi * x,
+            lo: self.lo * x,
+        }
+    }
+
+    #[inline]
+    fn mul_f64(self, b: f64) -> Self {
+        let (p1, p2) = two_prod(self.hi, b);
+        Self::from_quick_two_sum(p1, p2 + self.lo * b)
+    }
+
+    #[inline]
+    fn square(self) -> Self {
+        let (p1, p2) = two_prod(self.hi, self.hi);
+        Self::from_quick_two_sum(p1, p2 + 2.0 * self.hi * self.lo)
+    }
+
+    fn spherical_bessel_j0(self) -> Self {
+        if Float::abs(self) < Self::epsilon() {
+            Self::one() - self.square() / Self::from_hi(6.0)
+        } else {
+            Float::sin(self) / self
+        }
+    }
+
+    fn spherical_bessel_j1(self) -> Self {
+        if Float::abs(self) < Self::epsilon() {
+            self / Self::from_hi(3.0)
+        } else {
+            let (sin, cos) = Float::sin_cos(self);
+            let rec = Float::recip(self);
+            (sin * rec - cos) * rec
+        }
+    }
+
+    fn spherical_bessel_j2(self) -> Self {
+        if Float::abs(self) < Self::epsilon() {
+            self.square() / Self::from_hi(15.0)
+        } else {
+            let (sin, cos) = Float::sin_cos(self);
+            let s2 = self.square();
+            ((Self::from_hi(3.0) - s2) * sin - Self::from_hi(3.0) * self * cos) / (self * s2)
+        }
+    }
+
+    /// Sum of a Taylor series with terms `term_k = term_{k-1} * factor(k)`
+    /// starting at `term_0 = first`, truncated when the terms become negligible.
+    fn series(first: Self, factor: impl Fn(usize) -> Self) -> Self {
+        let mut sum = first;
+        let mut term = first;
+        for k in 1..100 {
+            term *= factor(k);
+            sum += term;
+            if term.hi.abs() <= f64::EPSILON.powi(2) * 1e-2 * sum.hi.abs() {
+                break;
+            }
+        }
+        sum
+    }
+
+    /// Sine and cosine for |x| <= pi/4.
+    fn sin_cos_reduced(self) -> (Self, Self) {
+        let x2 = -self.square();
+        let sin = Self::series(self, |k| x2 / Self::from_hi((2 * k * (2 * k + 1)) as f64));
+        let cos = Self::series(Self::one(), |k| {
+            x2 / Self::from_hi(((2 * k - 1) * 2 * k) as f64)
+        });
+        (sin, cos)
+    }
+
+    /// Hyperbolic sine for small arguments.
+    fn sinh_series(self) -> Self {
+        let x2 = self.square();
+        Self::series(self, |k| x2 / Self::from_hi((2 * k * (2 * k + 1)) as f64))
+    }
+}
+
+impl From<f64> for DoubleDouble {
+    fn from(x: f64) -> Self {
+        Self::from_hi(x)
+    }
+}
+
+impl From<f32> for DoubleDouble {
+    fn from(x: f32) -> Self {
+        Self::from_hi(x as f64)
+    }
+}
+
+impl PartialOrd for DoubleDouble {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.hi.partial_cmp(&other.hi)? {
+            Ordering::Equal => self.lo.partial_cmp(&other.lo),
+            ordering => Some(ordering),
+        }
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+}
+
+impl Add for DoubleDouble {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        let (s1, s2) = two_sum(self.hi, other.hi);
+        if !s1.is_finite() {
+            return Self { hi: s1, lo: 0.0 };
+        }
+        let (t1, t2) = two_sum(self.lo, other.lo);
+        let (s1, s2) = quick_two_sum(s1, s2 + t1);
+        Self::from_quick_two_sum(s1, s2 + t2)
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        let (p1, p2) = two_prod(self.hi, other.hi);
+        Self::from_quick_two_sum(p1, p2 + (self.hi * other.lo + self.lo * other.hi))
+    }
+}
+
+impl Div for DoubleDouble {
+    type Output = Self;
+    fn div(self, other: Self) -> Self {
+        let q1 = self.hi / other.hi;
+        if !q1.is_finite() {
+            return Self { hi: q1, lo: 0.0 };
+        }
+        let r = self - other.mul_f64(q1);
+        let q2 = r.hi / other.hi;
+        let r = r - other.mul_f64(q2);
+        let q3 = r.hi / other.hi;
+        let (q1, q2) = quick_two_sum(q1, q2);
+        Self { hi: q1, lo: q2 } + Self::from_hi(q3)
+    }
+}
+
+impl Rem for DoubleDouble {
+    type Output = Self;
+    fn rem(self, other: Self) -> Self {
+        self - (self / other).trunc() * other
+    }
+}
+
+macro_rules! impl_ops {
+    ($($op:ident, $method:ident, $op_assign:ident, $method_assign:ident);*) => {
+        $(
+            impl $op<&DoubleDouble> for DoubleDouble {
+                type Output = Self;
+                fn $method(self, other: &Self) -> Self {
+                    $op::$method(self, *other)
+                }
+            }
+
+            impl $op_assign for DoubleDouble {
+                fn $method_assign(&mut self, other: Self) {
+                    *self = $op::$method(*self, other);
+                }
+            }
+
+            impl $op_assign<&DoubleDouble> for DoubleDouble {
+                fn $method_assign(&mut self, other: &Self) {
+                    *self = $op::$method(*self, *other);
+                }
+            }
+        )*
+    };
+}
+
+impl_ops!(
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+    Rem, rem, RemAssign, rem_assign
+);
+
+macro_rules! impl_f64_ops {
+    ($($op:ident, $method:ident, $op_assign:ident, $method_assign:ident);*) => {
+        $(
+            impl $op<f64> for DoubleDouble {
+                type Output = Self;
+                fn $method(self, other: f64) -> Self {
+                    $op::$method(self, Self::from_hi(other))
+                }
+            }
+
+            impl $op_assign<f64> for DoubleDouble {
+                fn $method_assign(&mut self, other: f64) {
+                    *self = $op::$method(*self, other);
+                }
+            }
+        )*
+    };
+}
+
+impl_f64_ops!(
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+    Rem, rem, RemAssign, rem_assign
+);
+
+impl Sum for DoubleDouble {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a DoubleDouble> for DoubleDouble {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, &x| acc + x)
+    }
+}
+
+impl Product for DoubleDouble {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a DoubleDouble> for DoubleDouble {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |acc, &x| acc * x)
+    }
+}
+
+impl Inv for DoubleDouble {
+    type Output = Self;
+    fn inv(self) -> Self {
+        self.recip()
+    }
+}
+
+impl Zero for DoubleDouble {
+    fn zero() -> Self {
+        Self::from_hi(0.0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.hi == 0.0
+    }
+}
+
+impl One for DoubleDouble {
+    fn one() -> Self {
+        Self::from_hi(1.0)
+    }
+}
+
+impl Num for DoubleDouble {
+    type FromStrRadixErr = ParseFloatError;
+
+    /// Only decimal numbers (`radix = 10`) are supported.
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        if radix != 10 {
+            // use the error of an invalid float literal
+            return Err("".parse::<f64>().unwrap_err());
+        }
+        s.parse()
+    }
+}
+
+impl FromStr for DoubleDouble {
+    type Err = ParseFloatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // validates the input and handles special values
+        let approx: f64 = s.parse()?;
+        if !approx.is_finite() || approx == 0.0 {
+            return Ok(Self::from_hi(approx));
+        }
+        let s = s.trim_start_matches(['+', '-']);
+        let (mantissa, exponent) = match s.find(['e', 'E']) {
+            Some(i) => (&s[..i], s[i + 1..].parse::<i32>().unwrap()),
+            None => (s, 0),
+        };
+        let mut value = Self::zero();
+        let mut exponent = exponent;
+        let mut fraction = false;
+        for c in mantissa.chars() {
+            match c {
+                '.' => fraction = true,
+                c => {
+                    let digit = c.to_digit(10).unwrap() as f64;
+                    value = value * Self::from_hi(10.0) + Self::from_hi(digit);
+                    if fraction {
+                        exponent -= 1;
+                    }
+                }
+            }
+        }
+        let ten = Self::from_hi(10.0);
+        value = if exponent < 0 {
+            value / ten.powi(-exponent)
+        } else {
+            value * ten.powi(exponent)
+        };
+        Ok(if approx < 0.0 { -value } else { value })
+    }
+}
+
+impl fmt::Display for DoubleDouble {
+    /// Scientific notation with 32 significant digits, or with the given
+    /// number of digits after the decimal point.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.hi.is_finite() || self.hi == 0.0 {
+            return fmt::Display::fmt(&self.hi, f);
+        }
+        let precision = f.precision().unwrap_or(31);
+        let ten = Self::from_hi(10.0);
+        let mut x = self.abs();
+        let mut exponent = x.hi.log10().floor() as i32;
+        x = if exponent < 0 {
+            x * ten.powi(-exponent)
+        } else {
+            x / ten.powi(exponent)
+        };
+        if x.hi >= 10.0 {
+            x /= ten;
+            exponent += 1;
+        } else if x.hi < 1.0 {
+            x *= ten;
+            exponent -= 1;
+        }
+
+        let mut digits = Vec::with_capacity(precision + 1);
+        for _ in 0..=precision {
+            let digit = x.hi.floor().clamp(0.0, 9.0);
+            digits.push(digit as u8);
+            x = (x - Self::from_hi(digit)) * ten;
+        }
+        // round to the last digit
+        if x.hi >= 5.0 {
+            let mut i = digits.len();
+            loop {
+                if i == 0 {
+                    digits.insert(0, 1);
+                    digits.pop();
+                    exponent += 1;
+                    break;
+                }
+                i -= 1;
+                if digits[i] == 9 {
+                    digits[i] = 0;
+                } else {
+                    digits[i] += 1;
+                    break;
+                }
+            }
+        }
+        if f.precision().is_none() {
+            while digits.len() > 1 && digits.last() == Some(&0) {
+                digits.pop();
+            }
+        }
+
+        let sign = if self.hi < 0.0 { "-" } else { "" };
+        let mut mantissa = String::with_capacity(digits.len() + 1);
+        for (i, d) in digits.iter().enumerate() {
+            if i == 1 {
+                mantissa.push('.');
+            }
+            mantissa.push((b'0' + d) as char);
+        }
+        write!(f, "{sign}{mantissa}e{exponent}")
+    }
+}
+
+impl ToPrimitive for DoubleDouble {
+    fn to_i64(&self) -> Option<i64> {
+        let x = self.trunc();
+        let value = x.hi.to_i128()? + x.lo.to_i128()?;
+        value.to_i64()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        let x = self.trunc();
+        let value = x.hi.to_i128()? + x.lo.to_i128()?;
+        value.to_u64()
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        Some(self.hi)
+    }
+}
+
+impl NumCast for DoubleDouble {
+    fn from<T: ToPrimitive>(n: T) -> Option<Self> {
+        n.to_f64().map(Self::from_hi)
+    }
+}
+
+impl FromPrimitive for DoubleDouble {
+    fn from_i64(n: i64) -> Option<Self> {
+        let hi = n as f64;
+        let lo = (n as i128 - hi as i128) as f64;
+        Some(Self::new(hi, lo))
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        let hi = n as f64;
+        let lo = (n as i128 - hi as i128) as f64;
+        Some(Self::new(hi, lo))
+    }
+
+    fn from_f64(n: f64) -> Option<Self> {
+        Some(Self::from_hi(n))
+    }
+
+    fn from_f32(n: f32) -> Option<Self> {
+        Some(Self::from_hi(n as f64))
+    }
+}
+
+impl Signed for DoubleDouble {
+    fn abs(&self) -> Self {
+        Float::abs(*self)
+    }
+
+    fn abs_sub(&self, other: &Self) -> Self {
+        Float::abs_sub(*self, *other)
+    }
+
+    fn signum(&self) -> Self {
+        Float::signum(*self)
+    }
+
+    fn is_positive(&self) -> bool {
+        self.hi > 0.0
+    }
+
+    fn is_negative(&self) -> bool {
+        self.hi < 0.0
+    }
+}
+
+macro_rules! impl_float_const {
+    ($($name:ident => $lo:expr),*) => {
+        impl FloatConst for DoubleDouble {
+            $(
+                fn $name() -> Self {
+                    Self { hi: std::f64::consts::$name, lo: $lo }
+                }
+            )*
+        }
+    };
+}
+
+impl_float_const!(
+    E => 1.4456468917292502e-16,
+    FRAC_1_PI => -1.9678676675182486e-17,
+    FRAC_1_SQRT_2 => -4.833646656726457e-17,
+    FRAC_2_PI => -3.935735335036497e-17,
+    FRAC_2_SQRT_PI => 1.533545961316588e-17,
+    FRAC_PI_2 => 6.123233995736766e-17,
+    FRAC_PI_3 => -1.072081766451091e-16,
+    FRAC_PI_4 => 3.061616997868383e-17,
+    FRAC_PI_6 => -5.360408832255455e-17,
+    FRAC_PI_8 => 1.5308084989341915e-17,
+    LN_10 => -2.1707562233822494e-16,
+    LN_2 => 2.3190468138462996e-17,
+    LOG10_E => 1.098319650216765e-17,
+    LOG2_E => 2.0355273740931033e-17,
+    PI => 1.2246467991473532e-16,
+    SQRT_2 => -9.667293313452913e-17,
+    TAU => 2.4492935982947064e-16,
+    LOG10_2 => -2.8037281277851704e-18,
+    LOG2_10 => 1.661617516973592e-16
+);
+
+impl Float for DoubleDouble {
+    fn nan() -> Self {
+        Self {
+            hi: f64::NAN,
+            lo: f64::NAN,
+        }
+    }
+
+    fn infinity() -> Self {
+        Self::from_hi(f64::INFINITY)
+    }
+
+    fn neg_infinity() -> Self {
+        Self::from_hi(f64::NEG_INFINITY)
+    }
+
+    fn neg_zero() -> Self {
+        Self::from_hi(-0.0)
+    }
+
+    fn min_value() -> Self {
+        -Self::max_value()
+    }
+
+    fn min_positive_value() -> Self {
+        Self::from_hi(2.004168360008973e-292)
+    }
+
+    fn epsilon() -> Self {
+        Self::from_hi(4.930380657631324e-32)
+    }
+
+    fn max_value() -> Self {
+        Self {
+            hi: f64::MAX,
+            lo: 9.979201547673598e291,
+        }
+    }
+
+    fn is_nan(self) -> bool {
+        self.hi.is_nan() || self.lo.is_nan()
+    }
+
+    fn is_infinite(self) -> bool {
+        self.hi.is_infinite()
+    }
+
+    fn is_finite(self) -> bool {
+        self.hi.is_finite()
+    }
+
+    fn is_normal(self) -> bool {
+        self.hi.is_normal()
+    }
+
+    fn classify(self) -> FpCategory {
+        self.hi.classify()
+    }
+
+    fn floor(self) -> Self {
+        let hi = self.hi.floor();
+        if hi == self.hi {
+            Self::from_quick_two_sum(hi, self.lo.floor())
+        } else {
+            Self::from_hi(hi)
+        }
+    }
+
+    fn ceil(self) -> Self {
+        let hi = self.hi.ceil();
+        if hi == self.hi {
+            Self::from_quick_two_sum(hi, self.lo.ceil())
+        } else {
+            Self::from_hi(hi)
+        }
+    }
+
+    fn round(self) -> Self {
+        let half = Self::from_hi(0.5);
+        if self.hi < 0.0 {
+            -(half - self).floor()
+        } else {
+            (self + half).floor()
+        }
+    }
+
+    fn trunc(self) -> Self {
+        if self.hi < 0.0 {
+            self.ceil()
+        } else {
+            self.floor()
+        }
+    }
+
+    fn fract(self) -> Self {
+        self - self.trunc()
+    }
+
+    fn abs(self) -> Self {
+        if self.hi < 0.0 { -self } else { self }
+    }
+
+    fn signum(self) -> Self {
+        Self::from_hi(self.hi.signum())
+    }
+
+    fn is_sign_positive(self) -> bool {
+        self.hi.is_sign_positive()
+    }
+
+    fn is_sign_negative(self) -> bool {
+        self.hi.is_sign_negative()
+    }
+
+    fn mul_add(self, a: Self, b: Self) -> Self {
+        self * a + b
+    }
+
+    fn recip(self) -> Self {
+        Self::one() / self
+    }
+
+    fn powi(self, n: i32) -> Self {
+        let mut base = self;
+        let mut exponent = n.unsigned_abs();
+        let mut res = Self::one();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                res *= base;
+            }
+            base = base.square();
+            exponent >>= 1;
+        }
+        if n < 0 { res.recip() } else { res }
+    }
+
+    fn powf(self, n: Self) -> Self {
+        if n.fract().is_zero() && n.abs().hi <= i32::MAX as f64 {
+            return self.powi(n.hi as i32);
+        }
+        if self.is_zero() {
+            return if n.hi > 0.0 {
+                Self::zero()
+            } else {
+                Self::infinity()
+            };
+        }
+        (n * self.ln()).exp()
+    }
+
+    fn sqrt(self) -> Self {
+        if self.hi <= 0.0 || !self.hi.is_finite() {
+            return Self::from_hi(self.hi.sqrt());
+        }
+        let x = 1.0 / self.hi.sqrt();
+        let ax = self.hi * x;
+        let (p1, p2) = two_prod(ax, ax);
+        let correction = (self - Self { hi: p1, lo: p2 }).hi * (x * 0.5);
+        let (hi, lo) = two_sum(ax, correction);
+        Self { hi, lo }
+    }
+
+    fn exp(self) -> Self {
+        if self.hi > 709.8 {
+            return Self::infinity();
+        }
+        if self.hi < -745.2 {
+            return Self::zero();
+        }
+        if self.is_nan() {
+            return self;
+        }
+        // exp(x) = 2^m exp(r)^512 with |r| <= ln(2) / 1024
+        let m = (self.hi / Self::LN_2().hi + 0.5).floor();
+        let r = (self - Self::LN_2().mul_f64(m)).mul_pow2(1.0 / 512.0);
+        let mut s = Self::series(r, |k| r / Self::from_hi((k + 1) as f64));
+        for _ in 0..9 {
+            s = s.mul_pow2(2.0) + s.square();
+        }
+        let scale = 2.0f64.powi(m as i32 / 2);
+        (s + Self::one())
+            .mul_pow2(scale)
+            .mul_pow2(2.0f64.powi(m as i32 - m as i32 / 2))
+    }
+
+    fn exp2(self) -> Self {
+        (self * Self::LN_2()).exp()
+    }
+
+    fn ln(self) -> Self {
+        if self.hi <= 0.0 || !self.hi.is_finite() {
+            return Self::from_hi(self.hi.ln());
+        }
+        if self == Self::one() {
+            return Self::zero();
+        }
+        // one Newton step for exp(y) = x starting from the f64 logarithm
+        let y = Self::from_hi(self.hi.ln());
+        y + self * (-y).exp() - Self::one()
+    }
+
+    fn log(self, base: Self) -> Self {
+        self.ln() / base.ln()
+    }
+
+    fn log2(self) -> Self {
+        self.ln() * Self::LOG2_E()
+    }
+
+    fn log10(self) -> Self {
+        self.ln() * Self::LOG10_E()
+    }
+
+    fn max(self, other: Self) -> Self {
+        if self.is_nan() || other > self {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn min(self, other: Self) -> Self {
+        if self.is_nan() || other < self {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn abs_sub(self, other: Self) -> Self {
+        if self <= other {
+            Self::zero()
+        } else {
+            self - other
+        }
+    }
+
+    fn cbrt(self) -> Self {
+        if self.is_zero() || !self.hi.is_finite() {
+            return Self::from_hi(self.hi.cbrt());
+        }
+        // one Newton step for y^3 = x starting from the f64 cube root
+        let y = Self::from_hi(self.hi.cbrt());
+        y - (y.powi(3) - self) / (y.square() * Self::from_hi(3.0))
+    }
+
+    fn hypot(self, other: Self) -> Self {
+        (self.square() + other.square()).sqrt()
+    }
+
+    fn sin(self) -> Self {
+        self.sin_cos().0
+    }
+
+    fn cos(self) -> Self {
+        self.sin_cos().1
+    }
+
+    fn tan(self) -> Self {
+        let (sin, cos) = self.sin_cos();
+        sin / cos
+    }
+
+    fn asin(self) -> Self {
+        if self.abs() > Self::one() {
+            return Self::nan();
+        }
+        self.atan2((Self::one() - self.square()).sqrt())
+    }
+
+    fn acos(self) -> Self {
+        if self.abs() > Self::one() {
+            return Self::nan();
+        }
+        (Self::one() - self.square()).sqrt().atan2(self)
+    }
+
+    fn atan(self) -> Self {
+        self.atan2(Self::one())
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        let (y, x) = (self, other);
+        if x.is_zero() {
+            return match y.hi.partial_cmp(&0.0) {
+                Some(Ordering::Greater) => Self::FRAC_PI_2(),
+                Some(Ordering::Less) => -Self::FRAC_PI_2(),
+                _ => Self::from_hi(y.hi.atan2(x.hi)),
+            };
+        }
+        if y.is_zero() || !x.is_finite() || !y.is_finite() {
+            return Self::from_hi(y.hi.atan2(x.hi));
+        }
+        // one Newton step starting from the f64 result
+        let r = x.hypot(y);
+        let (x, y) = (x / r, y / r);
+        let z = Self::from_hi(y.hi.atan2(x.hi));
+        let (sin, cos) = z.sin_cos();
+        if x.hi.abs() > y.hi.abs() {
+            z + (y - sin) / cos
+        } else {
+            z - (x - cos) / sin
+        }
+    }
+
+    fn sin_cos(self) -> (Self, Self) {
+        if !self.hi.is_finite() {
+            return (Self::nan(), Self::nan());
+        }
+        // reduce the argument to |t| <= pi/4
+        let r = self - Self::TAU() * (self / Self::TAU()).round();
+        let j = (r.hi / Self::FRAC_PI_2().hi).round();
+        let t = r - Self::FRAC_PI_2().mul_f64(j);
+        let (sin, cos) = t.sin_cos_reduced();
+        match j as i32 {
+            0 => (sin, cos),
+            1 => (cos, -sin),
+            -1 => (-cos, sin),
+            _ => (-sin, -cos),
+        }
+    }
+
+    fn exp_m1(self) -> Self {
+        if self.hi.abs() < 0.5 {
+            Self::series(self, |k| self / Self::from_hi((k + 1) as f64))
+        } else {
+            self.exp() - Self::one()
+        }
+    }
+
+    fn ln_1p(self) -> Self {
+        if self.hi.abs() < 0.5 {
+            // ln(1 + x) = 2 atanh(x / (2 + x))
+            let u = self / (Self::from_hi(2.0) + self);
+            let u2 = u.square();
+            Self::series(u, |k| {
+                u2 * Self::from_hi((2 * k - 1) as f64) / Self::from_hi((2 * k + 1) as f64)
+            })
+            .mul_pow2(2.0)
+        } else {
+            (Self::one() + self).ln()
+        }
+    }
+
+    fn sinh(self) -> Self {
+        if self.hi.abs() < 0.05 {
+            return self.sinh_series();
+        }
+        let e = self.exp();
+        (e - e.recip()).mul_pow2(0.5)
+    }
+
+    fn cosh(self) -> Self {
+        let e = self.exp();
+        (e + e.recip()).mul_pow2(0.5)
+    }
+
+    fn tanh(self) -> Self {
+        if self.hi.abs() > 40.0 {
+            return self.signum();
+        }
+        if self.hi.abs() < 0.05 {
+            let sinh = self.sinh_series();
+            return sinh / (Self::one() + sinh.square()).sqrt();
+        }
+        let e = self.exp();
+        let e_inv = e.recip();
+        (e - e_inv) / (e + e_inv)
+    }
+
+    fn asinh(self) -> Self {
+        let x = self.abs();
+        let x2 = x.square();
+        let res = (x + x2 / (Self::one() + (x2 + Self::one()).sqrt())).ln_1p();
+        if self.hi < 0.0 { -res } else { res }
+    }
+
+    fn acosh(self) -> Self {
+        if self < Self::one() {
+            return Self::nan();
+        }
+        (self + (self.square() - Self::one()).sqrt()).ln()
+    }
+
+    fn atanh(self) -> Self {
+        if self.abs() > Self::one() {
+            return Self::nan();
+        }
+        (self.mul_pow2(2.0) / (Self::one() - self))
+            .ln_1p()
+            .mul_pow2(0.5)
+    }
+
+    fn integer_decode(self) -> (u64, i16, i8) {
+        self.hi.integer_decode()
+    }
+}
+
+impl ScalarOperand for DoubleDouble {}
+
+macro_rules! impl_dual_num {
+    ($float:ty, |$x:ident| $re:expr) => {
+        impl DualStruct<DoubleDouble, $float> for DoubleDouble {
+            type Real = $float;
+            type Lifted<D: DualNum<$float, Inner = DoubleDouble>> = D;
+
+            fn real(&self) -> $float {
+                let $x = *self;
+                $re
+            }
+
+            fn lift<D: DualNum<$float, Inner = DoubleDouble>>(&self) -> D {
+                D::from_inner(*self)
+            }
+        }
+
+        impl DualNum<$float> for DoubleDouble {
+            const NDERIV: usize = 0;
+
+            type Inner = DoubleDouble;
+
+            fn from_inner(inner: Self::Inner) -> Self {
+                inner
+            }
+
+            fn re(&self) -> $float {
+                let $x = *self;
+                $re
+            }
+
+            fn mul_add(&self, a: Self, b: Self) -> Self {
+                Float::mul_add(*self, a, b)
+            }
+
+            fn recip(&self) -> Self {
+                Float::recip(*self)
+            }
+
+            fn powi(&self, n: i32) -> Self {
+                Float::powi(*self, n)
+            }
+
+            fn powf(&self, n: $float) -> Self {
+                Float::powf(*self, n.into())
+            }
+
+            fn powd(&self, n: Self) -> Self {
+                Float::powf(*self, n)
+            }
+
+            fn sqrt(&self) -> Self {
+                Float::sqrt(*self)
+            }
+
+            fn cbrt(&self) -> Self {
+                Float::cbrt(*self)
+            }
+
+            fn exp(&self) -> Self {
+                Float::exp(*self)
+            }
+
+            fn exp2(&self) -> Self {
+                Float::exp2(*self)
+            }
+
+            fn exp_m1(&self) -> Self {
+                Float::exp_m1(*self)
+            }
+
+            fn ln(&self) -> Self {
+                Float::ln(*self)
+            }
+
+            fn log(&self, base: $float) -> Self {
+                Float::log(*self, base.into())
+            }
+
+            fn log2(&self) -> Self {
+                Float::log2(*self)
+            }
+
+            fn log10(&self) -> Self {
+                Float::log10(*self)
+            }
+
+            fn ln_1p(&self) -> Self {
+                Float::ln_1p(*self)
+            }
+
+            fn sin(&self) -> Self {
+                Float::sin(*self)
+            }
+
+            fn cos(&self) -> Self {
+                Float::cos(*self)
+            }
+
+            fn tan(&self) -> Self {
+                Float::tan(*self)
+            }
+
+            fn sin_cos(&self) -> (Self, Self) {
+                Float::sin_cos(*self)
+            }
+
+            fn asin(&self) -> Self {
+                Float::asin(*self)
+            }
+
+            fn acos(&self) -> Self {
+                Float::acos(*self)
+            }
+
+            fn atan(&self) -> Self {
+                Float::atan(*self)
+            }
+
+            fn atan2(&self, other: Self) -> Self {
+                Float::atan2(*self, other)
+            }
+
+            fn sinh(&self) -> Self {
+                Float::sinh(*self)
+            }
+
+            fn cosh(&self) -> Self {
+                Float::cosh(*self)
+            }
+
+            fn tanh(&self) -> Self {
+                Float::tanh(*self)
+            }
+
+            fn asinh(&self) -> Self {
+                Float::asinh(*self)
+            }
+
+            fn acosh(&self) -> Self {
+                Float::acosh(*self)
+            }
+
+            fn atanh(&self) -> Self {
+                Float::atanh(*self)
+            }
+
+            fn sph_j0(&self) -> Self {
+                self.spherical_bessel_j0()
+            }
+
+            fn sph_j1(&self) -> Self {
+                self.spherical_bessel_j1()
+            }
+
+            fn sph_j2(&self) -> Self {
+                self.spherical_bessel_j2()
+            }
+        }
+    };
+}
+
+impl_dual_num!(DoubleDouble, |x| x);
+impl_dual_num!(f64, |x| x.hi);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_dual::{Dual, Dual2, HyperDual, first_derivative, second_derivative};
+
+    type DD = DoubleDouble;
+
+    fn d(x: f64) -> DD {
+        x.into()
+    }
+
+    fn dd(s: &str) -> DD {
+        s.parse().unwrap()
+    }
+
+    fn assert_close(x: DD, y: DD, tol: f64) {
+        let error = ((x - y) / y).abs();
+        assert!(error.hi < tol, "{x} != {y} (relative error {:e})", error.hi);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let third = DD::one() / d(3.0);
+        assert_close(third * d(3.0), DD::one(), 1e-31);
+        assert!((third.lo).abs() > 0.0);
+        assert_close(
+            d(0.1) + d(0.2) - d(0.3),
+            dd("2.77555756156289135105907917022705078125e-17"),
+            1e-30,
+        );
+        assert_close(DD::SQRT_2().square(), d(2.0), 1e-31);
+        assert_close(d(2.0).sqrt(), DD::SQRT_2(), 1e-31);
+        assert_close(d(-27.0).cbrt(), d(-3.0), 1e-31);
+        assert_close(d(1.5).powi(-7), DD::one() / d(17.0859375), 1e-31);
+        assert_eq!(d(7.0) % d(3.0), DD::one());
+        assert_eq!(DD::new(2.5, 1e-20).floor(), d(2.0));
+        assert_eq!(DD::new(3.0, -1e-20).floor(), d(2.0));
+        assert_eq!(DD::new(-2.5, 0.0).round(), d(-3.0));
+        assert_eq!(DD::from_i64(i64::MAX).unwrap().to_i64(), Some(i64::MAX));
+    }
+
+    #[test]
+    fn transcendental_functions() {
+        assert_close(DD::one().exp(), DD::E(), 1e-31);
+        assert_close(DD::E().ln(), DD::one(), 1e-31);
+        assert_close(d(10.0).ln(), DD::LN_10(), 1e-31);
+        assert_close(d(-50.5).exp().ln(), d(-50.5), 1e-31);
+        assert_close(d(1e-20).exp_m1(), DD::new(1e-20, 5e-41), 1e-31);
+        assert_close(d(1e-20).ln_1p(), DD::new(1e-20, -5e-41), 1e-31);
+        assert_close(DD::FRAC_PI_6().sin(), d(0.5), 1e-31);
+        assert_close(DD::FRAC_PI_3().cos(), d(0.5), 1e-31);
+        assert_close((DD::PI() * d(100.25)).sin(), DD::FRAC_1_SQRT_2(), 1e-29);
+        assert_close(DD::one().atan() * d(4.0), DD::PI(), 1e-31);
+        assert_close(d(0.5).asin(), DD::FRAC_PI_6(), 1e-31);
+        assert_close(d(0.5).acos(), DD::FRAC_PI_3(), 1e-31);
+        assert_close(d(0.5).atanh().tanh(), d(0.5), 1e-31);
+        assert_close(d(0.01).asinh().sinh(), d(0.01), 1e-31);
+        assert_close(d(3.0).acosh().cosh(), d(3.0), 1e-31);
+        assert_close(d(2.0).powf(d(0.5)), DD::SQRT_2(), 1e-31);
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let pi = dd("3.1415926535897932384626433832795028841971");
+        assert_close(pi, DD::PI(), 1e-31);
+        assert_eq!(format!("{pi:.20}"), "3.14159265358979323846e0");
+        assert_eq!(format!("{:.5}", -pi * d(1e-10)), "-3.14159e-10");
+        assert_eq!(format!("{}", d(0.5)), "5e-1");
+        assert_close(dd(&format!("{}", DD::LN_2())), DD::LN_2(), 1e-31);
+        assert!("1.2.3".parse::<DD>().is_err());
+    }
+
+    #[test]
+    fn dual_numbers() {
+        // d/dx exp(x^2) and d^2/dx^2 ln(x) in double-double precision
+        let x = d(0.5);
+        let (f, df) = first_derivative(|x: Dual<DD, DD>| (x * x).exp(), x);
+        assert_close(f, d(0.25).exp(), 1e-31);
+        assert_close(df, d(0.25).exp(), 1e-31);
+        let (_, _, d2f) = second_derivative(|x: Dual2<DD, DD>| x.ln(), x);
+        assert_close(d2f, d(-4.0), 1e-31);
+    }
+
+    #[test]
+    fn dual_numbers_f64() {
+        // dual numbers with f64 as the underlying field, as used by the
+        // Helmholtz energy of models that implement `Residual`
+        let x = DualNum::<f64>::re(&d(0.5));
+        assert_eq!(x, 0.5);
+        let (f, df) = first_derivative(|x: Dual<DD, f64>| (x * 2.0).powf(1.5).ln() + 1.0, d(0.5));
+        assert_close(f, DD::one(), 1e-31);
+        assert_close(df, d(3.0), 1e-31);
+        let mut y = HyperDual::<DD, f64>::from_re(DD::one() / d(3.0));
+        y.eps1 = DD::one();
+        y.eps2 = DD::one();
+        let z = y.ln() * 3.0;
+        assert_close(z.eps1eps2, d(-27.0), 1e-31);
+    }
+}
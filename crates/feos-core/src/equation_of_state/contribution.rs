@@ -0,0 +1,183 @@
+use super::{Components, Molarweight, ReducingParameters, Residual};
+use crate::{FeosError, FeosResult, StateHD};
+use ndarray::{Array1, ScalarOperand};
+use num_dual::DualNum;
+use quantity::MolarWeight;
+use std::fmt;
+
+/// An individual contribution to the residual Helmholtz energy.
+///
+/// Contributions are added to an existing [Residual] model with
+/// [Residual::with_contribution], which allows to extend models
+/// with additional terms (e.g., association, polar or electrostatic
+/// interactions) without modifying the original model.
+pub trait ResidualContribution: Components + fmt::Display + Send + Sync {
+    /// Evaluate the reduced Helmholtz energy $\beta A$ of the contribution.
+    fn helmholtz_energy<D: DualNum<f64> + Copy + ScalarOperand>(&self, state: &StateHD<D>) -> D;
+}
+
+/// A residual Helmholtz energy model combined with an additional contribution.
+///
+/// The combined model can itself be extended by further contributions.
+#[derive(Clone)]
+pub struct WithContribution<R, C> {
+    pub residual: R,
+    pub contribution: C,
+}
+
+impl<R: Components, C: Components> WithContribution<R, C> {
+    /// Return a new [WithContribution] from a residual model and an
+    /// additional contribution.
+    pub fn new(residual: R, contribution: C) -> FeosResult<Self> {
+        if residual.components() != contribution.components() {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "the residual model contains {} components, but the contribution is initialized for {} components.",
+                residual.components(),
+                contribution.components()
+            )));
+        }
+        Ok(Self {
+            residual,
+            contribution,
+        })
+    }
+}
+
+impl<R: Components, C: Components> Components for WithContribution<R, C> {
+    fn components(&self) -> usize {
+        self.residual.components()
+    }
+
+    fn subset(&self, component_list: &[usize]) -> Self {
+        Self {
+            residual: self.residual.subset(component_list),
+            contribution: self.contribution.subset(component_list),
+        }
+    }
+}
+
+impl<R: Residual, C: ResidualContribution> Residual for WithContribution<R, C> {
+    fn compute_max_density(&self, moles: &Array1<f64>) -> f64 {
+        self.residual.compute_max_density(moles)
+    }
+
+    fn reducing_parameters(&self, molefracs: &Array1<f64>) -> Option<ReducingParameters> {
+        self.residual.reducing_parameters(molefracs)
+    }
+
+    // The analytic density roots of the residual model are not valid
+    // in the presence of the additional contribution, therefore the
+    // default implementation is used.
+
+    fn residual_helmholtz_energy_contributions<D: DualNum<f64> + Copy + ScalarOperand>(
+        &self,
+        state: &StateHD<D>,
+    ) -> Vec<(String, D)> {
+        let mut contributions = self.residual.residual_helmholtz_energy_contributions(state);
+        contributions.push((
+            self.contribution.to_string(),
+            self.contribution.helmholtz_energy(state),
+        ));
+        contributions
+    }
+}
+
+impl<R: Molarweight, C> Molarweight for WithContribution<R, C> {
+    fn molar_weight(&self) -> MolarWeight<Array1<f64>> {
+        self.residual.molar_weight()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::propane_butane;
+    use crate::{ReferenceSystem, State, StateBuilder};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{BAR, KELVIN};
+    use std::sync::Arc;
+
+    /// Additional second virial coefficient (in Angstrom^3) for every component.
+    struct Virial(Array1<f64>);
+
+    impl fmt::Display for Virial {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "Virial")
+        }
+    }
+
+    impl Components for Virial {
+        fn components(&self) -> usize {
+            self.0.len()
+        }
+
+        fn subset(&self, component_list: &[usize]) -> Self {
+            Self(component_list.iter().map(|&i| self.0[i]).collect())
+        }
+    }
+
+    impl ResidualContribution for Virial {
+        fn helmholtz_energy<D: DualNum<f64> + Copy + ScalarOperand>(
+            &self,
+            state: &StateHD<D>,
+        ) -> D {
+            let b = (&state.molefracs * &self.0).sum();
+            state.moles.sum() * state.partial_density.sum() * b
+        }
+    }
+
+    fn vapor<R: Residual>(eos: &Arc<R>) -> FeosResult<State<R>> {
+        StateBuilder::new(eos)
+            .temperature(300.0 * KELVIN)
+            .pressure(BAR)
+            .molefracs(&arr1(&[0.4, 0.6]))
+            .vapor()
+            .build()
+    }
+
+    #[test]
+    fn with_contribution() -> FeosResult<()> {
+        let pr = propane_butane()?;
+        let eos = Arc::new(propane_butane()?.with_contribution(Virial(arr1(&[10.0, 20.0])))?);
+        assert_eq!(eos.components(), 2);
+        assert!(eos.density_roots(300.0, 1e-5, &arr1(&[0.5, 0.5])).is_none());
+
+        let state = StateHD::new(300.0, 1e4, arr1(&[0.3, 0.7]));
+        let contributions = eos.residual_helmholtz_energy_contributions(&state);
+        assert_eq!(contributions.len(), 2);
+        assert_eq!(contributions[1].0, "Virial");
+        assert_relative_eq!(
+            eos.residual_helmholtz_energy(&state),
+            pr.residual_helmholtz_energy(&state) + 17.0 * 1e-4
+        );
+
+        // the additional contribution shifts the second virial coefficient
+        let b_pr = pr
+            .subset(&[1])
+            .second_virial_coefficient(300.0 * KELVIN, None)?;
+        let pure = Arc::new(eos.subset(&[1]));
+        let b = pure.second_virial_coefficient(300.0 * KELVIN, None)?;
+        assert_relative_eq!((b - b_pr).to_reduced(), 20.0, max_relative = 1e-10);
+
+        // contributions can be chained and used to compute states
+        let chained = Arc::new(
+            propane_butane()?
+                .with_contribution(Virial(arr1(&[10.0, 20.0])))?
+                .with_contribution(Virial(arr1(&[-10.0, -20.0])))?,
+        );
+        let pr = Arc::new(pr);
+        assert_relative_eq!(
+            vapor(&chained)?.density,
+            vapor(&pr)?.density,
+            max_relative = 1e-10
+        );
+
+        assert!(
+            propane_butane()?
+                .with_contribution(Virial(arr1(&[10.0])))
+                .is_err()
+        );
+        Ok(())
+    }
+}
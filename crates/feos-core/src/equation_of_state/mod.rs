@@ -1,15 +1,19 @@
-use crate::FeosResult;
+use crate::{FeosError, FeosResult};
 use ndarray::{Array1, ScalarOperand};
 use quantity::{
     Diffusivity, MolarWeight, Moles, Temperature, ThermalConductivity, Viscosity, Volume,
 };
 use std::sync::Arc;
 
+mod contribution;
 mod ideal_gas;
+mod reduced;
 mod residual;
 
+pub use contribution::{ResidualContribution, WithContribution};
 pub use ideal_gas::IdealGas;
-pub use residual::{EntropyScaling, Molarweight, NoResidual, Residual};
+pub use reduced::{ReducedResidualDerivatives, ReducingParameters};
+pub use residual::{EntropyScaling, Molarweight, NoResidual, Parachor, Residual};
 
 /// The number of components that the model is initialized for.
 pub trait Components {
@@ -19,6 +23,41 @@ pub trait Components {
     /// Return a model consisting of the components
     /// contained in component_list.
     fn subset(&self, component_list: &[usize]) -> Self;
+
+    /// Return a model consisting of the components contained in
+    /// component_list (in this order).
+    ///
+    /// In contrast to [Components::subset], an error is returned if the list
+    /// is empty, contains duplicates or indices that are out of bounds.
+    fn try_subset(&self, component_list: &[usize]) -> FeosResult<Self>
+    where
+        Self: Sized,
+    {
+        check_component_list(component_list, self.components())?;
+        Ok(self.subset(component_list))
+    }
+}
+
+/// Check that a list of component indices is a valid selection of `n` components.
+pub(crate) fn check_component_list(component_list: &[usize], n: usize) -> FeosResult<()> {
+    if component_list.is_empty() {
+        return Err(FeosError::IncompatibleParameters(
+            "the subset does not contain any components.".into(),
+        ));
+    }
+    for (k, &i) in component_list.iter().enumerate() {
+        if i >= n {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "component index {i} is out of bounds for a model with {n} components."
+            )));
+        }
+        if component_list[..k].contains(&i) {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "component index {i} is selected more than once."
+            )));
+        }
+    }
+    Ok(())
 }
 
 /// An equation of state consisting of an ideal gas model
@@ -85,6 +124,20 @@ impl<I: IdealGas, R: Residual> Residual for EquationOfState<I, R> {
         self.residual.compute_max_density(moles)
     }
 
+    fn density_roots(
+        &self,
+        temperature: f64,
+        pressure: f64,
+        molefracs: &Array1<f64>,
+    ) -> Option<Vec<f64>> {
+        self.residual
+            .density_roots(temperature, pressure, molefracs)
+    }
+
+    fn reducing_parameters(&self, molefracs: &Array1<f64>) -> Option<ReducingParameters> {
+        self.residual.reducing_parameters(molefracs)
+    }
+
     fn residual_helmholtz_energy_contributions<D: num_dual::DualNum<f64> + Copy + ScalarOperand>(
         &self,
         state: &crate::StateHD<D>,
@@ -137,3 +190,9 @@ impl<I: IdealGas, R: Residual + EntropyScaling> EntropyScaling for EquationOfSta
         self.residual.thermal_conductivity_correlation(s_res, x)
     }
 }
+
+impl<I, R: Parachor> Parachor for EquationOfState<I, R> {
+    fn parachor(&self) -> FeosResult<Array1<f64>> {
+        self.residual.parachor()
+    }
+}
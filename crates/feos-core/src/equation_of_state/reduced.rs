@@ -0,0 +1,217 @@
+use crate::{ReferenceSystem, StateHD};
+use ndarray::Array1;
+use quantity::{Density, KELVIN, PASCAL, RGAS, Temperature};
+
+/// Characteristic temperature $T_\mathrm{r}$ and density $\rho_\mathrm{r}$
+/// that define the reduced variables $\tau=\frac{T_\mathrm{r}}{T}$ and
+/// $\delta=\frac{\rho}{\rho_\mathrm{r}}$.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReducingParameters {
+    /// Reducing temperature in K
+    pub temperature: f64,
+    /// Reducing density in Å^-3
+    pub density: f64,
+}
+
+impl ReducingParameters {
+    /// Reducing parameters from a temperature and a density.
+    pub fn new(temperature: Temperature, density: Density) -> Self {
+        Self {
+            temperature: temperature.to_reduced(),
+            density: density.to_reduced(),
+        }
+    }
+
+    /// Pseudo-critical reducing parameters from Kay's rule:
+    /// $T_\mathrm{r}=\sum_ix_iT_{c,i}$ and
+    /// $\rho_\mathrm{r}=\frac{\sum_ix_ip_{c,i}}{RT_\mathrm{r}}$
+    ///
+    /// Critical temperatures are given in K and critical pressures in Pa.
+    pub fn pseudo_critical(
+        molefracs: &Array1<f64>,
+        critical_temperature: &Array1<f64>,
+        critical_pressure: &Array1<f64>,
+    ) -> Self {
+        let temperature = (molefracs * critical_temperature).sum() * KELVIN;
+        let pressure = (molefracs * critical_pressure).sum() * PASCAL;
+        Self::new(temperature, pressure / (RGAS * temperature))
+    }
+
+    /// Reducing temperature $T_\mathrm{r}$
+    pub fn temperature(&self) -> Temperature {
+        Temperature::from_reduced(self.temperature)
+    }
+
+    /// Reducing density $\rho_\mathrm{r}$
+    pub fn density(&self) -> Density {
+        Density::from_reduced(self.density)
+    }
+
+    /// Reduced variables $\tau$ and $\delta$ of a temperature and a density.
+    pub fn reduce(&self, temperature: Temperature, density: Density) -> (f64, f64) {
+        (
+            self.temperature / temperature.to_reduced(),
+            density.to_reduced() / self.density,
+        )
+    }
+
+    /// State in the reduced units of the equation of state for given $\tau$,
+    /// $\delta$ and mole fractions.
+    pub(super) fn state<D: num_dual::DualNum<f64> + Copy>(
+        &self,
+        tau: D,
+        delta: D,
+        molefracs: &Array1<f64>,
+    ) -> StateHD<D> {
+        let temperature = tau.recip() * self.temperature;
+        let volume = (delta * self.density).recip();
+        StateHD::new(temperature, volume, molefracs.mapv(D::from))
+    }
+}
+
+/// Reduced residual Helmholtz energy $\alpha^\mathrm{r}=\frac{A^\mathrm{res}}{nRT}$
+/// and its partial derivatives with respect to $\tau$ and $\delta$ at
+/// constant composition.
+///
+/// The derivatives correspond to the quantities that are tabulated for the
+/// verification of reference equations of state.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReducedResidualDerivatives {
+    /// Inverse reduced temperature $\tau$
+    pub tau: f64,
+    /// Reduced density $\delta$
+    pub delta: f64,
+    /// $\alpha^\mathrm{r}$
+    pub alpha: f64,
+    /// $\alpha^\mathrm{r}_\delta$
+    pub alpha_delta: f64,
+    /// $\alpha^\mathrm{r}_\tau$
+    pub alpha_tau: f64,
+    /// $\alpha^\mathrm{r}_{\delta\delta}$
+    pub alpha_delta_delta: f64,
+    /// $\alpha^\mathrm{r}_{\tau\tau}$
+    pub alpha_tau_tau: f64,
+    /// $\alpha^\mathrm{r}_{\delta\tau}$
+    pub alpha_delta_tau: f64,
+}
+
+impl ReducedResidualDerivatives {
+    /// Compressibility factor: $Z=1+\delta\alpha^\mathrm{r}_\delta$
+    pub fn compressibility_factor(&self) -> f64 {
+        1.0 + self.delta * self.alpha_delta
+    }
+
+    /// Reduced residual internal energy: $\frac{u^\mathrm{res}}{RT}=\tau\alpha^\mathrm{r}_\tau$
+    pub fn residual_internal_energy(&self) -> f64 {
+        self.tau * self.alpha_tau
+    }
+
+    /// Reduced residual enthalpy: $\frac{h^\mathrm{res}}{RT}=\tau\alpha^\mathrm{r}_\tau+\delta\alpha^\mathrm{r}_\delta$
+    pub fn residual_enthalpy(&self) -> f64 {
+        self.tau * self.alpha_tau + self.delta * self.alpha_delta
+    }
+
+    /// Reduced residual entropy: $\frac{s^\mathrm{res}}{R}=\tau\alpha^\mathrm{r}_\tau-\alpha^\mathrm{r}$
+    pub fn residual_entropy(&self) -> f64 {
+        self.tau * self.alpha_tau - self.alpha
+    }
+
+    /// Reduced residual isochoric heat capacity: $\frac{c_v^\mathrm{res}}{R}=-\tau^2\alpha^\mathrm{r}_{\tau\tau}$
+    pub fn residual_isochoric_heat_capacity(&self) -> f64 {
+        -self.tau.powi(2) * self.alpha_tau_tau
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::PengRobinson;
+    use crate::equation_of_state::{Components, Residual};
+    use crate::state::{Contributions, State};
+    use crate::test_utils::propane_butane_parameters;
+    use crate::{FeosResult, SolverOptions};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{KILO, METER, MOL, Moles};
+    use std::sync::Arc;
+    use typenum::P3;
+
+    #[test]
+    fn reduced_derivatives() -> FeosResult<()> {
+        let parameters = propane_butane_parameters()?;
+        let eos = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let x = arr1(&[0.3, 0.7]);
+        let reducing = eos.reducing_parameters(&x).unwrap();
+        assert_relative_eq!(reducing.temperature, 0.3 * 369.96 + 0.7 * 425.2);
+        assert_relative_eq!(
+            reducing.density().convert_to(MOL / METER.powi::<P3>()),
+            (0.3 * 4250000.0 + 0.7 * 3800000.0) / (8.31446261815324 * reducing.temperature),
+            max_relative = 1e-12
+        );
+
+        // comparison with dimensional properties
+        let moles = Moles::from_reduced(x.clone());
+        let t = 350.0 * KELVIN;
+        let density = 6.0 * KILO * MOL / METER.powi::<P3>();
+        let state = State::new_nvt(&eos, t, moles.sum() / density, &moles)?;
+        let (tau, delta) = reducing.reduce(t, density);
+        let d = eos.reduced_residual_derivatives(&reducing, tau, delta, &x);
+        assert_relative_eq!(
+            d.alpha,
+            eos.reduced_residual_helmholtz_energy(&reducing, tau, delta, &x)
+        );
+        assert_relative_eq!(
+            d.compressibility_factor(),
+            state.compressibility(Contributions::Total),
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            d.residual_entropy(),
+            (state.residual_molar_entropy() / RGAS).into_value(),
+            max_relative = 1e-10
+        );
+        assert_relative_eq!(
+            d.residual_enthalpy(),
+            (state.residual_molar_enthalpy() / (RGAS * t)).into_value(),
+            max_relative = 1e-10
+        );
+        assert_relative_eq!(
+            d.residual_isochoric_heat_capacity(),
+            (state.residual_molar_isochoric_heat_capacity() / RGAS).into_value(),
+            max_relative = 1e-10
+        );
+
+        // mixed and second derivatives from finite differences
+        let h = 1e-5;
+        let f = |tau, delta| eos.reduced_residual_derivatives(&reducing, tau, delta, &x);
+        let (dp, dm) = (f(tau, delta + h), f(tau, delta - h));
+        let (tp, tm) = (f(tau + h, delta), f(tau - h, delta));
+        assert_relative_eq!(
+            d.alpha_delta_delta,
+            (dp.alpha_delta - dm.alpha_delta) / (2.0 * h),
+            max_relative = 1e-7
+        );
+        assert_relative_eq!(
+            d.alpha_tau_tau,
+            (tp.alpha_tau - tm.alpha_tau) / (2.0 * h),
+            max_relative = 1e-7
+        );
+        assert_relative_eq!(
+            d.alpha_delta_tau,
+            (tp.alpha_delta - tm.alpha_delta) / (2.0 * h),
+            max_relative = 1e-7
+        );
+
+        // the critical point of a pure component is close to the pseudo-critical scales
+        let eos = Arc::new(eos.subset(&[0]));
+        let cp = State::critical_point(&eos, None, None, SolverOptions::default())?;
+        let reducing = eos.reducing_parameters(&arr1(&[1.0])).unwrap();
+        let (tau, delta) = reducing.reduce(cp.temperature, cp.density);
+        assert_relative_eq!(tau, 1.0, max_relative = 1e-4);
+        let z_c = eos
+            .reduced_residual_derivatives(&reducing, tau, delta, &arr1(&[1.0]))
+            .compressibility_factor();
+        assert_relative_eq!(delta * z_c, 1.0, max_relative = 1e-4);
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
-use super::Components;
+use super::{
+    Components, ReducedResidualDerivatives, ReducingParameters, ResidualContribution,
+    WithContribution,
+};
 use crate::{FeosError, FeosResult, ReferenceSystem, StateHD};
 use ndarray::ScalarOperand;
 use ndarray::prelude::*;
@@ -13,6 +16,39 @@ use typenum::Quot;
 /// Enables calculation of (mass) specific properties.
 pub trait Molarweight {
     fn molar_weight(&self) -> MolarWeight<Array1<f64>>;
+
+    /// Mole fractions from mass fractions: $x_i=\frac{w_i/MW_i}{\sum_jw_j/MW_j}$
+    fn molefracs_from_massfracs(&self, massfracs: &Array1<f64>) -> Array1<f64> {
+        let n = massfracs / &self.molar_weight().to_reduced();
+        &n / n.sum()
+    }
+
+    /// Mass fractions from mole fractions: $w_i=\frac{x_iMW_i}{\sum_jx_jMW_j}$
+    fn massfracs_from_molefracs(&self, molefracs: &Array1<f64>) -> Array1<f64> {
+        let m = molefracs * &self.molar_weight().to_reduced();
+        &m / m.sum()
+    }
+
+    /// Mole numbers from the masses of all components: $n_i=\frac{m_i}{MW_i}$
+    fn moles_from_mass(&self, mass: &Mass<Array1<f64>>) -> Moles<Array1<f64>> {
+        mass.clone() / self.molar_weight()
+    }
+
+    /// Molar flow rates from the mass flow rates of all components: $\dot n_i=\frac{\dot m_i}{MW_i}$
+    fn mole_flow_rate(
+        &self,
+        mass_flow_rate: &MassFlowRate<Array1<f64>>,
+    ) -> MoleFlowRate<Array1<f64>> {
+        mass_flow_rate.clone() / self.molar_weight()
+    }
+
+    /// Mass flow rates from the molar flow rates of all components: $\dot m_i=\dot n_iMW_i$
+    fn mass_flow_rate(
+        &self,
+        mole_flow_rate: &MoleFlowRate<Array1<f64>>,
+    ) -> MassFlowRate<Array1<f64>> {
+        mole_flow_rate.clone() * self.molar_weight()
+    }
 }
 
 /// A residual Helmholtz energy model.
@@ -25,6 +61,78 @@ pub trait Residual: Components + Send + Sync {
     /// equation of state anyways).
     fn compute_max_density(&self, moles: &Array1<f64>) -> f64;
 
+    /// Return the densities in Angstrom^-3 that solve the equation of state
+    /// for given temperature (in K) and pressure (in reduced units), if an
+    /// analytic solution is available.
+    ///
+    /// Models for which the pressure is a polynomial in the volume (e.g.
+    /// cubic equations of state) can overwrite this method. The returned
+    /// roots are used instead of the iterative density solver when states
+    /// are created from temperature and pressure. Only mechanically stable
+    /// roots in ascending order should be returned. The default
+    /// implementation returns `None`.
+    fn density_roots(
+        &self,
+        _temperature: f64,
+        _pressure: f64,
+        _molefracs: &Array1<f64>,
+    ) -> Option<Vec<f64>> {
+        None
+    }
+
+    /// Return the characteristic temperature and density of a mixture with
+    /// the given mole fractions, if the model defines them.
+    ///
+    /// The scales define the reduced variables of the
+    /// [reduced residual derivatives](Residual::reduced_residual_derivatives).
+    /// The default implementation returns `None`.
+    fn reducing_parameters(&self, _molefracs: &Array1<f64>) -> Option<ReducingParameters> {
+        None
+    }
+
+    /// Evaluate the reduced residual Helmholtz energy
+    /// $\alpha^\mathrm{r}(\tau,\delta)=\frac{A^\mathrm{res}}{nRT}$ with
+    /// $\tau=\frac{T_\mathrm{r}}{T}$ and $\delta=\frac{\rho}{\rho_\mathrm{r}}$.
+    fn reduced_residual_helmholtz_energy(
+        &self,
+        reducing: &ReducingParameters,
+        tau: f64,
+        delta: f64,
+        molefracs: &Array1<f64>,
+    ) -> f64 {
+        self.residual_helmholtz_energy(&reducing.state(tau, delta, molefracs))
+    }
+
+    /// Evaluate the reduced residual Helmholtz energy and its first and
+    /// second partial derivatives with respect to $\tau$ and $\delta$.
+    fn reduced_residual_derivatives(
+        &self,
+        reducing: &ReducingParameters,
+        tau: f64,
+        delta: f64,
+        molefracs: &Array1<f64>,
+    ) -> ReducedResidualDerivatives {
+        let (t, d) = (
+            HyperDual64::from(tau).derivative1(),
+            HyperDual64::from(delta).derivative2(),
+        );
+        let a = self.residual_helmholtz_energy(&reducing.state(t, d, molefracs));
+        let t = Dual2_64::from(tau).derivative();
+        let a_tt = self.residual_helmholtz_energy(&reducing.state(t, delta.into(), molefracs));
+        let d = Dual2_64::from(delta).derivative();
+        let a_dd = self.residual_helmholtz_energy(&reducing.state(tau.into(), d, molefracs));
+        ReducedResidualDerivatives {
+            tau,
+            delta,
+            alpha: a.re,
+            alpha_delta: a.eps2,
+            alpha_tau: a.eps1,
+            alpha_delta_delta: a_dd.v2,
+            alpha_tau_tau: a_tt.v2,
+            alpha_delta_tau: a.eps1eps2,
+        }
+    }
+
     /// Evaluate the reduced Helmholtz energy of each individual contribution
     /// and return them together with a string representation of the contribution.
     fn residual_helmholtz_energy_contributions<D: DualNum<f64> + Copy + ScalarOperand>(
@@ -42,6 +150,18 @@ pub trait Residual: Components + Send + Sync {
             .fold(D::zero(), |acc, (_, a)| acc + a)
     }
 
+    /// Combine the model with an additional contribution to the residual
+    /// Helmholtz energy.
+    fn with_contribution<C: ResidualContribution>(
+        self,
+        contribution: C,
+    ) -> FeosResult<WithContribution<Self, C>>
+    where
+        Self: Sized,
+    {
+        WithContribution::new(self, contribution)
+    }
+
     /// Check if the provided optional mole number is consistent with the
     /// equation of state.
     ///
@@ -90,6 +210,47 @@ pub trait Residual: Components + Send + Sync {
         ))
     }
 
+    /// Calculate the matrix of (cross) second virial coefficients $B_{ij}(T)$
+    ///
+    /// The second virial coefficient of the mixture is given by
+    /// $B=\sum_{ij}x_ix_jB_{ij}$. The diagonal elements are the second virial
+    /// coefficients of the pure components and the off-diagonal elements are
+    /// obtained from the equimolar binary mixtures as
+    /// $B_{ij}=2B\left(x_i=x_j=\frac{1}{2}\right)-\frac{1}{2}\left(B_{ii}+B_{jj}\right)$.
+    /// For models in which $B$ is not strictly quadratic in the composition,
+    /// the sum is only an approximation of the second virial coefficient of
+    /// the mixture.
+    fn cross_second_virial_coefficients(
+        &self,
+        temperature: Temperature,
+    ) -> FeosResult<Quot<Array2<f64>, Density>> {
+        let n = self.components();
+        let virial = |x: Array1<f64>| {
+            let mut rho = HyperDual64::zero();
+            rho.eps1 = 1.0;
+            rho.eps2 = 1.0;
+            let t = HyperDual64::from(temperature.to_reduced());
+            let s = StateHD::new_virial(t, rho, x);
+            self.residual_helmholtz_energy(&s).eps1eps2 * 0.5
+        };
+        let mut b = Array2::zeros((n, n));
+        for i in 0..n {
+            let mut x = Array1::zeros(n);
+            x[i] = 1.0;
+            b[(i, i)] = virial(x);
+        }
+        for i in 0..n {
+            for j in i + 1..n {
+                let mut x = Array1::zeros(n);
+                x[i] = 0.5;
+                x[j] = 0.5;
+                b[(i, j)] = 2.0 * virial(x) - 0.5 * (b[(i, i)] + b[(j, j)]);
+                b[(j, i)] = b[(i, j)];
+            }
+        }
+        Ok(Quantity::from_reduced(b))
+    }
+
     /// Calculate the third virial coefficient $C(T)$
     fn third_virial_coefficient(
         &self,
@@ -169,6 +330,12 @@ pub trait EntropyScaling {
     fn thermal_conductivity_correlation(&self, s_res: f64, x: &Array1<f64>) -> FeosResult<f64>;
 }
 
+/// Parachors for the estimation of surface tensions using the Macleod-Sugden equation.
+pub trait Parachor {
+    /// Return the parachor of every component in units of (mN/m)^(1/4) cm³/mol.
+    fn parachor(&self) -> FeosResult<Array1<f64>>;
+}
+
 /// Dummy implementation for [EquationOfState](super::EquationOfState)s that only contain an ideal gas contribution.
 pub struct NoResidual(pub usize);
 
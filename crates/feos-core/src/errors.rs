@@ -1,4 +1,8 @@
+use crate::parameter::ValidationReport;
+use ndarray::Array1;
 use num_dual::linalg::LinAlgError;
+use quantity::{KELVIN, PASCAL, Pressure, Temperature};
+use std::fmt;
 use std::io;
 use thiserror::Error;
 
@@ -10,10 +14,18 @@ pub enum FeosError {
     Error(String),
 
     // errors related to algorithms
-    #[error("`{0}` did not converge within the maximum number of iterations.")]
-    NotConverged(String),
-    #[error("`{0}` encountered illegal values during the iteration.")]
-    IterationFailed(String),
+    #[error(
+        "`{}` did not converge within the maximum number of iterations.{}",
+        .0.algorithm,
+        .0
+    )]
+    NotConverged(SolverFailure),
+    #[error(
+        "`{}` encountered illegal values during the iteration.{}",
+        .0.algorithm,
+        .0
+    )]
+    IterationFailed(SolverFailure),
     #[error("Iteration resulted in trivial solution.")]
     TrivialSolution,
     #[error(
@@ -28,6 +40,8 @@ pub enum FeosError {
     SuperCritical,
     #[error("No phase split according to stability analysis.")]
     NoPhaseSplit,
+    #[error("The calculation was cancelled.")]
+    Cancelled,
     #[error("Wrong input units. Expected {0}, got {1}")]
     WrongUnits(String, String),
 
@@ -40,8 +54,8 @@ pub enum FeosError {
     Serde(#[from] serde_json::Error),
 
     // errors related to parameter handling
-    #[error("The following component(s) were not found: {0}")]
-    ComponentsNotFound(String),
+    #[error("The following component(s) were not found: {}", .0.join(", "))]
+    ComponentsNotFound(Vec<String>),
     #[error(
         "The identifier '{0}' is not known. ['cas', 'name', 'iupacname', 'smiles', inchi', 'formula']"
     )]
@@ -50,6 +64,15 @@ pub enum FeosError {
     InsufficientInformation,
     #[error("Incompatible parameters: {0}")]
     IncompatibleParameters(String),
+    #[error("Invalid parameters:\n{0}")]
+    InvalidParameters(ValidationReport),
+    #[error(
+        "Parameter file uses schema version {0}, the latest supported version is {latest}.",
+        latest = crate::parameter::SCHEMA_VERSION
+    )]
+    UnsupportedSchemaVersion(u64),
+    #[error("Record {0} of the parameter file is invalid: {1}")]
+    InvalidRecord(usize, serde_json::Error),
 
     // other errors
     #[error(transparent)]
@@ -63,3 +86,127 @@ pub enum FeosError {
 
 /// Convenience type for `Result<T, FeosError>`.
 pub type FeosResult<T> = Result<T, FeosError>;
+
+/// Diagnostic information about a failed iterative solver.
+///
+/// Apart from the name of the algorithm, all fields are optional and
+/// only provided by solvers for which they are meaningful. The [fmt::Display]
+/// implementation lists the available information about the last iterate.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SolverFailure {
+    /// Name of the algorithm.
+    pub algorithm: String,
+    /// Number of iterations performed.
+    pub iterations: Option<usize>,
+    /// Residual of the last iteration.
+    pub residual: Option<f64>,
+    /// Temperature of the last iterate in K.
+    pub temperature: Option<f64>,
+    /// Pressure of the last iterate in Pa.
+    pub pressure: Option<f64>,
+    /// Mole fractions of the last iterate.
+    pub molefracs: Option<Vec<f64>>,
+}
+
+impl SolverFailure {
+    pub fn new<S: Into<String>>(algorithm: S) -> Self {
+        Self {
+            algorithm: algorithm.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = Some(iterations);
+        self
+    }
+
+    pub fn residual(mut self, residual: f64) -> Self {
+        self.residual = Some(residual);
+        self
+    }
+
+    pub fn temperature(mut self, temperature: Temperature) -> Self {
+        self.temperature = Some(temperature.convert_to(KELVIN));
+        self
+    }
+
+    pub fn pressure(mut self, pressure: Pressure) -> Self {
+        self.pressure = Some(pressure.convert_to(PASCAL));
+        self
+    }
+
+    pub fn molefracs(mut self, molefracs: &Array1<f64>) -> Self {
+        self.molefracs = Some(molefracs.to_vec());
+        self
+    }
+}
+
+impl From<&str> for SolverFailure {
+    fn from(algorithm: &str) -> Self {
+        Self::new(algorithm)
+    }
+}
+
+impl From<String> for SolverFailure {
+    fn from(algorithm: String) -> Self {
+        Self::new(algorithm)
+    }
+}
+
+impl fmt::Display for SolverFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut details = Vec::new();
+        if let Some(iterations) = self.iterations {
+            details.push(format!("iterations = {iterations}"));
+        }
+        if let Some(residual) = self.residual {
+            details.push(format!("residual = {residual:e}"));
+        }
+        if let Some(temperature) = self.temperature {
+            details.push(format!("T = {temperature} K"));
+        }
+        if let Some(pressure) = self.pressure {
+            details.push(format!("p = {pressure} Pa"));
+        }
+        if let Some(molefracs) = &self.molefracs {
+            details.push(format!("x = {molefracs:?}"));
+        }
+        if !details.is_empty() {
+            write!(f, " Last iterate: {}.", details.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::arr1;
+    use quantity::BAR;
+
+    #[test]
+    fn error_messages() {
+        let err = FeosError::ComponentsNotFound(vec!["methane".into(), "ethane".into()]);
+        assert_eq!(
+            err.to_string(),
+            "The following component(s) were not found: methane, ethane"
+        );
+        let err = FeosError::NotConverged("TP flash".into());
+        assert_eq!(
+            err.to_string(),
+            "`TP flash` did not converge within the maximum number of iterations."
+        );
+        let failure = SolverFailure::new("TP flash")
+            .iterations(400)
+            .residual(1e-3)
+            .temperature(300.0 * KELVIN)
+            .pressure(BAR)
+            .molefracs(&arr1(&[0.25, 0.75]));
+        assert_eq!(failure.pressure, Some(1e5));
+        assert_eq!(
+            FeosError::NotConverged(failure).to_string(),
+            "`TP flash` did not converge within the maximum number of iterations. Last iterate: iterations = 400, residual = 1e-3, T = 300 K, p = 100000 Pa, x = [0.25, 0.75]."
+        );
+    }
+}
@@ -0,0 +1,336 @@
+//! Psychrometry of humid gases at elevated pressures.
+//!
+//! In contrast to the ideal-gas psychrometric correlations, the humidity is
+//! based on the fugacity of water calculated from the equation of state, so
+//! that the enhancement of the water content at high pressures and the
+//! non-ideality of the gas are taken into account. Condensed water is
+//! treated as liquid (see [WaterContent]).
+use crate::equation_of_state::{IdealGas, Molarweight, Residual};
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::observer::{report_iteration, report_result};
+use crate::state::{Contributions, DensityInitialization, State};
+use crate::water_content::WaterContent;
+use crate::{ReferenceSystem, SolverOptions, Verbosity};
+use ndarray::Array1;
+use quantity::{KELVIN, Moles, Pressure, RGAS, Temperature};
+use std::sync::Arc;
+
+const MAX_ITER: usize = 50;
+const TOL: f64 = 1e-10;
+/// Temperature step of the first secant step of the adiabatic saturation
+/// temperature in K.
+const T_STEP: f64 = 5.0;
+
+/// A dry gas mixture with variable amounts of water.
+pub struct HumidGas<E> {
+    eos: Arc<E>,
+    aqueous_eos: Option<Arc<E>>,
+    dry_gas: Array1<f64>,
+    water: usize,
+}
+
+impl<E: Residual + Molarweight> HumidGas<E> {
+    /// Create a humid gas from the mole fractions of the dry gas (the mole
+    /// fraction of `water` is ignored).
+    ///
+    /// If `aqueous_eos` is given, condensed water is described by a separate
+    /// model as in [WaterContent::saturated].
+    pub fn new(
+        eos: &Arc<E>,
+        aqueous_eos: Option<&Arc<E>>,
+        dry_gas: &Array1<f64>,
+        water: usize,
+    ) -> FeosResult<Self> {
+        if dry_gas.len() != eos.components() || water >= dry_gas.len() {
+            return Err(FeosError::IncompatibleComponents(
+                eos.components(),
+                dry_gas.len(),
+            ));
+        }
+        let mut dry_gas = dry_gas.clone();
+        dry_gas[water] = 0.0;
+        let total = dry_gas.sum();
+        if total <= 0.0 {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "the gas has to contain components other than water.",
+            )));
+        }
+        Ok(Self {
+            eos: eos.clone(),
+            aqueous_eos: aqueous_eos.cloned(),
+            dry_gas: dry_gas / total,
+            water,
+        })
+    }
+
+    /// Mole fractions of the humid gas with the given mole fraction of water.
+    pub fn molefracs(&self, water_molefrac: f64) -> Array1<f64> {
+        let mut x = &self.dry_gas * (1.0 - water_molefrac);
+        x[self.water] = water_molefrac;
+        x
+    }
+
+    /// The humid gas with the given mole fraction of water.
+    pub fn state(
+        &self,
+        temperature: Temperature,
+        pressure: Pressure,
+        water_molefrac: f64,
+    ) -> FeosResult<State<E>> {
+        State::new_npt(
+            &self.eos,
+            temperature,
+            pressure,
+            &Moles::from_reduced(self.molefracs(water_molefrac)),
+            DensityInitialization::Vapor,
+        )
+    }
+
+    /// The gas saturated with water.
+    pub fn saturation(
+        &self,
+        temperature: Temperature,
+        pressure: Pressure,
+    ) -> FeosResult<WaterContent<E>> {
+        WaterContent::saturated(
+            &self.eos,
+            self.aqueous_eos.as_ref(),
+            temperature,
+            pressure,
+            &self.dry_gas,
+            self.water,
+        )
+    }
+
+    /// Humidity ratio, i.e., the mass of water per mass of dry gas.
+    pub fn humidity_ratio(&self, water_molefrac: f64) -> f64 {
+        let mw = self.eos.molar_weight();
+        let mw_dry = (0..self.dry_gas.len())
+            .map(|i| mw.get(i) * self.dry_gas[i])
+            .reduce(|a, b| a + b)
+            .unwrap();
+        (mw.get(self.water) * water_molefrac / (mw_dry * (1.0 - water_molefrac))).into_value()
+    }
+
+    /// Mole fraction of water for the given humidity ratio.
+    pub fn water_molefrac_from_humidity_ratio(&self, humidity_ratio: f64) -> f64 {
+        let r = self.humidity_ratio(0.5);
+        humidity_ratio / (humidity_ratio + r)
+    }
+
+    /// Relative humidity $\varphi=\frac{f_w}{f_w^\mathrm{sat}}$, i.e., the
+    /// ratio of the fugacity of water in the humid gas and in the gas
+    /// saturated with water at the same temperature and pressure.
+    ///
+    /// Values larger than 1 correspond to supersaturated gases.
+    pub fn relative_humidity(
+        &self,
+        temperature: Temperature,
+        pressure: Pressure,
+        water_molefrac: f64,
+    ) -> FeosResult<f64> {
+        let ln_f_sat = self.ln_water_fugacity(self.saturation(temperature, pressure)?.gas());
+        let gas = self.state(temperature, pressure, water_molefrac)?;
+        Ok((self.ln_water_fugacity(&gas) - ln_f_sat).exp())
+    }
+
+    /// Mole fraction of water in the gas with the given relative humidity.
+    ///
+    /// The mole fraction is determined by successive substitution of the
+    /// fugacity coefficient of water.
+    pub fn water_molefrac(
+        &self,
+        temperature: Temperature,
+        pressure: Pressure,
+        relative_humidity: f64,
+    ) -> FeosResult<f64> {
+        let saturated = self.saturation(temperature, pressure)?;
+        let ln_f = self.ln_water_fugacity(saturated.gas()) + relative_humidity.ln();
+        let mut y = relative_humidity * saturated.water_molefrac;
+        for _ in 0..MAX_ITER {
+            let gas = self.state(temperature, pressure, y)?;
+            let y_new = (ln_f - gas.ln_phi()[self.water]).exp();
+            if (y_new - y).abs() < TOL * y {
+                return Ok(y_new);
+            }
+            y = y_new;
+        }
+        Err(FeosError::NotConverged(
+            SolverFailure::new("water_molefrac")
+                .iterations(MAX_ITER)
+                .temperature(temperature)
+                .pressure(pressure),
+        ))
+    }
+
+    /// Water dew point of the humid gas at given pressure (see
+    /// [WaterContent::dew_point]).
+    pub fn dew_point(
+        &self,
+        pressure: Pressure,
+        water_molefrac: f64,
+        initial_temperature: Option<Temperature>,
+        options: SolverOptions,
+    ) -> FeosResult<WaterContent<E>> {
+        WaterContent::dew_point(
+            &self.eos,
+            self.aqueous_eos.as_ref(),
+            pressure,
+            &self.molefracs(water_molefrac),
+            self.water,
+            initial_temperature,
+            options,
+        )
+    }
+
+    /// Logarithm of the reduced fugacity $\ln\left(y_w\varphi_w\right)$ of water.
+    fn ln_water_fugacity(&self, state: &State<E>) -> f64 {
+        state.molefracs[self.water].ln() + state.ln_phi()[self.water]
+    }
+}
+
+impl<E: Residual + Molarweight + IdealGas> HumidGas<E> {
+    /// Adiabatic saturation temperature of the humid gas at constant pressure.
+    ///
+    /// The adiabatic saturation temperature is the temperature that the gas
+    /// reaches if it is saturated with liquid water at the same temperature
+    /// without heat exchange, i.e.
+    /// $\frac{h(T,y)}{1-y}+\left(\frac{y^\mathrm{sat}}{1-y^\mathrm{sat}}-\frac{y}{1-y}\right)h_w^L(T^\mathrm{as})=\frac{h(T^\mathrm{as},y^\mathrm{sat})}{1-y^\mathrm{sat}}$
+    /// per mole of dry gas. For air-water systems at moderate pressures it
+    /// is a close approximation of the (psychrometric) wet-bulb temperature.
+    pub fn adiabatic_saturation_temperature(
+        &self,
+        temperature: Temperature,
+        pressure: Pressure,
+        water_molefrac: f64,
+        options: SolverOptions,
+    ) -> FeosResult<WaterContent<E>> {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER, TOL);
+        let y = water_molefrac;
+        let h = self
+            .state(temperature, pressure, y)?
+            .molar_enthalpy(Contributions::Total);
+        let water_eos = self.aqueous_eos.as_ref().unwrap_or(&self.eos);
+        let mut water = Array1::zeros(self.dry_gas.len());
+        water[self.water] = 1.0;
+        let water = Moles::from_reduced(water);
+
+        // residual of the energy balance per mole of dry gas
+        let residual = |t: f64| -> FeosResult<_> {
+            let t = t * KELVIN;
+            let saturated = self.saturation(t, pressure)?;
+            let y_sat = saturated.water_molefrac;
+            let liquid = State::new_npt(
+                water_eos,
+                t,
+                pressure,
+                &water,
+                DensityInitialization::Liquid,
+            )?;
+            let h_liquid = liquid.molar_enthalpy(Contributions::Total);
+            let h_sat = saturated.gas().molar_enthalpy(Contributions::Total);
+            let dn = y_sat / (1.0 - y_sat) - y / (1.0 - y);
+            let f = (h / (1.0 - y) + h_liquid * dn - h_sat / (1.0 - y_sat)) / (RGAS * t);
+            Ok((f.into_value(), saturated))
+        };
+
+        let t0 = temperature.convert_to(KELVIN);
+        let (mut t_old, mut f_old) = (t0, residual(t0)?.0);
+        let mut t = t0 - T_STEP;
+        log_iter!(verbosity, " iter |    residual    |  temperature   ");
+        log_iter!(verbosity, "{:-<38}", "");
+        let mut res = f64::INFINITY;
+        for i in 1..=max_iter {
+            let (f, saturated) = residual(t)?;
+            res = f.abs();
+            log_iter!(verbosity, " {:4} | {:14.8e} | {:14.8e}", i, res, t);
+            report_iteration("adiabatic saturation", i, res, &[t]);
+            if res < tol {
+                report_result("adiabatic saturation", i, true);
+                log_result!(
+                    verbosity,
+                    "adiabatic_saturation: calculation converged in {} step(s)\n",
+                    i
+                );
+                return Ok(saturated);
+            }
+            let t_new = t - f * (t - t_old) / (f - f_old);
+            (t_old, f_old, t) = (t, f, t_new);
+        }
+        report_result("adiabatic saturation", max_iter, false);
+        Err(FeosError::NotConverged(
+            SolverFailure::new("adiabatic saturation")
+                .iterations(max_iter)
+                .residual(res)
+                .temperature(temperature)
+                .pressure(pressure),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use crate::equation_of_state::EquationOfState;
+    use crate::test_utils::ConstantHeatCapacity;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::BAR;
+
+    #[test]
+    fn humid_nitrogen() -> FeosResult<()> {
+        // nitrogen, water
+        let parameters = PengRobinsonParameters::new_simple(
+            &[126.2, 647.1],
+            &[3.3958e6, 22.064e6],
+            &[0.0372, 0.344],
+            &[28.014, 18.015],
+        )?;
+        let eos = Arc::new(EquationOfState::new(
+            Arc::new(ConstantHeatCapacity::new(2)),
+            Arc::new(PengRobinson::new(Arc::new(parameters))),
+        ));
+        let gas = HumidGas::new(&eos, None, &arr1(&[1.0, 0.0]), 1)?;
+        let (t, p) = (300.0 * KELVIN, 1.0 * BAR);
+
+        // the saturated gas has a relative humidity of 1
+        let y_sat = gas.saturation(t, p)?.water_molefrac;
+        assert_relative_eq!(
+            gas.relative_humidity(t, p, y_sat)?,
+            1.0,
+            max_relative = 1e-8
+        );
+        let y = gas.water_molefrac(t, p, 0.5)?;
+        assert_relative_eq!(gas.relative_humidity(t, p, y)?, 0.5, max_relative = 1e-8);
+        assert_relative_eq!(y, 0.5 * y_sat, max_relative = 1e-3);
+
+        // humidity ratio in kg water per kg dry gas
+        let w = gas.humidity_ratio(y);
+        assert_relative_eq!(w, 18.015 * y / (28.014 * (1.0 - y)), max_relative = 1e-12);
+        assert_relative_eq!(
+            gas.water_molefrac_from_humidity_ratio(w),
+            y,
+            max_relative = 1e-12
+        );
+
+        // the dew point of the gas is the temperature at which it is saturated
+        let dew = gas.dew_point(p, y, Some(t), SolverOptions::default())?;
+        assert!(dew.temperature < t);
+        assert_relative_eq!(
+            gas.relative_humidity(dew.temperature, p, y)?,
+            1.0,
+            max_relative = 1e-6
+        );
+
+        // the adiabatic saturation temperature lies between the dew point and
+        // the temperature and coincides with both for saturated gas
+        let wet_bulb = gas.adiabatic_saturation_temperature(t, p, y, SolverOptions::default())?;
+        assert!(wet_bulb.temperature > dew.temperature && wet_bulb.temperature < t);
+        assert!(wet_bulb.water_molefrac > y);
+        let saturated =
+            gas.adiabatic_saturation_temperature(t, p, y_sat, SolverOptions::default())?;
+        assert_relative_eq!(saturated.temperature, t, max_relative = 1e-8);
+        Ok(())
+    }
+}
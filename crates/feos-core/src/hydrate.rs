@@ -0,0 +1,563 @@
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::observer::{report_iteration, report_result};
+use crate::parameter::{IdentifierOption, PureRecord};
+use crate::phase_equilibria::PhaseEquilibrium;
+use crate::state::{DensityInitialization, State};
+use crate::{ReferenceSystem, SolverOptions, Verbosity};
+use ndarray::{Array1, arr1};
+use quantity::{BAR, JOULE, KELVIN, MOL, Moles, PASCAL, Pressure, RGAS, Temperature};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+
+const MAX_ITER: usize = 50;
+const TOL: f64 = 1e-10;
+const T0: f64 = 273.15;
+const ENTHALPY_OF_FUSION: f64 = 6011.0;
+const VOLUME_OF_FUSION: f64 = 1.6e-6;
+const MAX_LN_PRESSURE_STEP: f64 = 1.0;
+const MAX_TEMPERATURE_STEP: f64 = 10.0;
+
+/// Crystal structure of a gas hydrate.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HydrateStructure {
+    /// Structure I (2 small and 6 large cavities per 46 water molecules)
+    #[serde(rename = "si")]
+    SI,
+    /// Structure II (16 small and 8 large cavities per 136 water molecules)
+    #[serde(rename = "sii")]
+    SII,
+}
+
+impl HydrateStructure {
+    /// Number of small and large cavities per water molecule.
+    fn cavities(self) -> [f64; 2] {
+        match self {
+            Self::SI => [2.0 / 46.0, 6.0 / 46.0],
+            Self::SII => [16.0 / 136.0, 8.0 / 136.0],
+        }
+    }
+
+    /// Chemical potential difference of water between the empty hydrate
+    /// lattice and pure liquid water (`ice = false`) or ice (`ice = true`)
+    /// divided by $RT$ for the temperature in K and the pressure in Pa.
+    ///
+    /// Reference properties at 273.15 K according to Holder et al. (1980).
+    fn delta_mu_water(self, temperature: f64, pressure: f64, ice: bool) -> f64 {
+        let r = RGAS.convert_to(JOULE / (MOL * KELVIN));
+        let (mu0, mut h0, mut v0) = match self {
+            Self::SI => (1264.0, -4858.0, 4.6e-6),
+            Self::SII => (883.0, -5201.0, 5.0e-6),
+        };
+        let (cp0, b) = if ice {
+            h0 += ENTHALPY_OF_FUSION;
+            v0 -= VOLUME_OF_FUSION;
+            (0.0, 0.0)
+        } else {
+            (-38.12, 0.141)
+        };
+        // integral of the enthalpy difference h0 + cp0 (T - T0) + b/2 (T - T0)^2 over RT^2
+        let c0 = h0 - cp0 * T0 + 0.5 * b * T0 * T0;
+        let c1 = cp0 - b * T0;
+        let c2 = 0.5 * b;
+        let integral = |t: f64| -c0 / t + c1 * t.ln() + c2 * t;
+        mu0 / (r * T0) - (integral(temperature) - integral(T0)) / r
+            + v0 * pressure / (r * temperature)
+    }
+}
+
+/// Logarithm of the activity of ice relative to pure liquid water for the
+/// temperature in K, neglecting the heat capacity of fusion.
+pub(crate) fn ln_ice_activity(temperature: f64) -> f64 {
+    let r = RGAS.convert_to(JOULE / (MOL * KELVIN));
+    -ENTHALPY_OF_FUSION / r * (1.0 / temperature - 1.0 / T0)
+}
+
+/// Parameters of the Langmuir constant $C=\frac{A}{T}\exp\left(\frac{B}{T}\right)$.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct LangmuirConstants {
+    /// $A$ in K/bar
+    pub a: f64,
+    /// $B$ in K
+    pub b: f64,
+}
+
+impl LangmuirConstants {
+    /// Langmuir constant in 1/bar for the temperature in K.
+    fn value(&self, temperature: f64) -> f64 {
+        self.a / temperature * (self.b / temperature).exp()
+    }
+}
+
+/// Langmuir constants of a guest in the small and large cavities
+/// of a hydrate structure.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct LangmuirRecord {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub small: Option<LangmuirConstants>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub large: Option<LangmuirConstants>,
+}
+
+impl LangmuirRecord {
+    fn cavity(&self, cavity: usize) -> Option<&LangmuirConstants> {
+        [&self.small, &self.large][cavity].as_ref()
+    }
+}
+
+/// Hydrate parameters of a guest molecule.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct HydrateGuestRecord {
+    /// Langmuir constants in structure I hydrates
+    #[serde(default)]
+    pub si: LangmuirRecord,
+    /// Langmuir constants in structure II hydrates
+    #[serde(default)]
+    pub sii: LangmuirRecord,
+}
+
+impl HydrateGuestRecord {
+    fn structure(&self, structure: HydrateStructure) -> &LangmuirRecord {
+        match structure {
+            HydrateStructure::SI => &self.si,
+            HydrateStructure::SII => &self.sii,
+        }
+    }
+}
+
+/// The van der Waals-Platteeuw model for gas hydrates.
+///
+/// The hydrate is in equilibrium with an aqueous phase (or ice) and a
+/// fluid phase. The fugacities of the guests and the activity of water,
+/// which accounts for inhibitors like methanol or glycols, are calculated
+/// from the equation of state for the fluid phases.
+///
+/// See https://doi.org/10.1002/9780470143483.ch1
+pub struct HydrateModel {
+    pub(crate) water: usize,
+    pub(crate) guests: Vec<Option<HydrateGuestRecord>>,
+}
+
+/// Equilibrium between a hydrate and two fluid phases.
+pub struct HydrateEquilibrium<E> {
+    /// Crystal structure of the hydrate
+    pub structure: HydrateStructure,
+    /// Hydrate formation temperature
+    pub temperature: Temperature,
+    /// Hydrate formation pressure
+    pub pressure: Pressure,
+    /// Aqueous phase in equilibrium with the hydrate
+    pub aqueous: State<E>,
+    /// Fluid (gas or hydrocarbon liquid) phase in equilibrium with the hydrate
+    pub fluid: State<E>,
+    /// Fractional occupancy of the small cavities by every component
+    pub small_cavity_occupancy: Array1<f64>,
+    /// Fractional occupancy of the large cavities by every component
+    pub large_cavity_occupancy: Array1<f64>,
+}
+
+impl HydrateModel {
+    /// Create a hydrate model from the index of water and the parameters
+    /// of all components. Components that do not enter the hydrate
+    /// (water, inhibitors, heavy hydrocarbons) have no parameters.
+    pub fn new(water: usize, guests: Vec<Option<HydrateGuestRecord>>) -> FeosResult<Self> {
+        if water >= guests.len() {
+            return Err(FeosError::IncompatibleComponents(guests.len(), water + 1));
+        }
+        if guests[water].is_some() {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "water can not be a guest in the hydrate.",
+            )));
+        }
+        Ok(Self { water, guests })
+    }
+
+    /// Read the parameters of the guests from a json file. Substances
+    /// that are not found in the file are not considered as guests.
+    pub fn from_json<P: AsRef<Path>>(
+        substances: &[&str],
+        water: usize,
+        file: P,
+        identifier_option: IdentifierOption,
+    ) -> FeosResult<Self> {
+        let reader = BufReader::new(File::open(file)?);
+        let records: Vec<PureRecord<HydrateGuestRecord>> = serde_json::from_reader(reader)?;
+        let guests = substances
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let s = identifier_option.normalize(s);
+                records
+                    .iter()
+                    .find(|r| {
+                        i != water
+                            && r.identifier
+                                .as_str(identifier_option)
+                                .is_some_and(|id| identifier_option.normalize(id) == s)
+                    })
+                    .map(|r| r.model_record)
+            })
+            .collect();
+        Self::new(water, guests)
+    }
+
+    /// Structures for which parameters of at least one guest are available.
+    pub(crate) fn structures(&self) -> Vec<HydrateStructure> {
+        [HydrateStructure::SI, HydrateStructure::SII]
+            .into_iter()
+            .filter(|&s| {
+                self.guests.iter().flatten().any(|g| {
+                    let r = g.structure(s);
+                    r.small.is_some() || r.large.is_some()
+                })
+            })
+            .collect()
+    }
+
+    /// Chemical potential difference of water between the empty and the
+    /// filled hydrate lattice divided by $RT$ and the occupancies of the
+    /// small and large cavities.
+    fn occupancy(
+        &self,
+        structure: HydrateStructure,
+        temperature: f64,
+        ln_f: &Array1<f64>,
+    ) -> (f64, [Array1<f64>; 2]) {
+        let mut delta_mu = 0.0;
+        let occupancy = [0, 1].map(|m| {
+            let c_f = Array1::from_shape_fn(self.guests.len(), |i| {
+                self.guests[i]
+                    .as_ref()
+                    .and_then(|g| g.structure(structure).cavity(m))
+                    .map_or(0.0, |c| c.value(temperature) * ln_f[i].exp())
+            });
+            let sum = c_f.sum();
+            delta_mu += structure.cavities()[m] * sum.ln_1p();
+            c_f / (1.0 + sum)
+        });
+        (delta_mu, occupancy)
+    }
+
+    /// Fluid phases at given temperature and pressure.
+    ///
+    /// A three phase flash is attempted first, so that the distribution of
+    /// water and inhibitors between an aqueous phase, a hydrocarbon liquid
+    /// and a gas is captured. If no third phase is found, the result of a
+    /// two phase flash is returned.
+    pub(crate) fn fluid_phases<E: Residual>(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressure: Pressure,
+        feed: &Moles<Array1<f64>>,
+    ) -> FeosResult<Vec<State<E>>> {
+        if let Ok(vlle) = PhaseEquilibrium::three_phase_flash(
+            eos,
+            temperature,
+            pressure,
+            feed,
+            SolverOptions::default(),
+        ) {
+            return Ok(vec![
+                vlle.vapor().clone(),
+                vlle.liquid1().clone(),
+                vlle.liquid2().clone(),
+            ]);
+        }
+        let vle = PhaseEquilibrium::tp_flash(
+            eos,
+            temperature,
+            pressure,
+            feed,
+            None,
+            SolverOptions::default(),
+            None,
+        )?;
+        Ok(vec![vle.vapor().clone(), vle.liquid().clone()])
+    }
+
+    /// Index of the aqueous phase, i.e., the phase with the highest
+    /// water content.
+    pub(crate) fn aqueous_phase<E>(&self, phases: &[State<E>]) -> usize {
+        let w = self.water;
+        (0..phases.len())
+            .max_by(|&i, &j| phases[i].molefracs[w].total_cmp(&phases[j].molefracs[w]))
+            .unwrap()
+    }
+
+    /// Logarithm of the activity of water in a phase relative to pure
+    /// liquid water at the same temperature and pressure.
+    pub(crate) fn ln_water_activity<E: Residual>(
+        &self,
+        pure_water: &Arc<E>,
+        phase: &State<E>,
+        pressure: Pressure,
+    ) -> FeosResult<f64> {
+        let water = State::new_npt(
+            pure_water,
+            phase.temperature,
+            pressure,
+            &Moles::from_reduced(arr1(&[1.0])),
+            DensityInitialization::Liquid,
+        )?;
+        let w = self.water;
+        Ok(phase.ln_phi()[w] + phase.molefracs[w].ln() - water.ln_phi()[0])
+    }
+
+    /// Difference of the chemical potentials of water in the hydrate and
+    /// in the coexisting aqueous phase or ice divided by $RT$. The hydrate
+    /// is stable if the difference is negative.
+    pub(crate) fn driving_force<E: Residual>(
+        &self,
+        pure_water: &Arc<E>,
+        structure: HydrateStructure,
+        pressure: Pressure,
+        phases: &[State<E>],
+    ) -> FeosResult<(f64, HydrateEquilibrium<E>)> {
+        let w = self.water;
+        let aqueous = &phases[self.aqueous_phase(phases)];
+        let fluid = phases
+            .iter()
+            .min_by(|s1, s2| s1.molefracs[w].total_cmp(&s2.molefracs[w]))
+            .unwrap();
+        let temperature = aqueous.temperature;
+        let (t, p) = (temperature.convert_to(KELVIN), pressure.convert_to(PASCAL));
+
+        // fugacities of the guests in bar
+        let ln_f =
+            fluid.ln_phi() + fluid.molefracs.mapv(f64::ln) + (pressure / BAR).into_value().ln();
+        let (delta_mu_hydrate, [small, large]) = self.occupancy(structure, t, &ln_f);
+
+        // the most stable of liquid water and ice coexists with the hydrate
+        let ln_a = self.ln_water_activity(pure_water, aqueous, pressure)?;
+        let delta_mu_water = (structure.delta_mu_water(t, p, false) - ln_a)
+            .max(structure.delta_mu_water(t, p, true));
+        Ok((
+            delta_mu_water - delta_mu_hydrate,
+            HydrateEquilibrium {
+                structure,
+                temperature,
+                pressure,
+                aqueous: aqueous.clone(),
+                fluid: fluid.clone(),
+                small_cavity_occupancy: small,
+                large_cavity_occupancy: large,
+            },
+        ))
+    }
+
+    /// Solve for the hydrate equilibrium of every structure and return the
+    /// most stable hydrate, i.e., the hydrate that forms first.
+    fn solve<E: Residual>(
+        &self,
+        eos: &Arc<E>,
+        feed: &Moles<Array1<f64>>,
+        solver: &'static str,
+        mut solve_structure: impl FnMut(
+            &dyn Fn(f64) -> FeosResult<(f64, HydrateEquilibrium<E>)>,
+        ) -> FeosResult<HydrateEquilibrium<E>>,
+        state: impl Fn(f64) -> (Temperature, Pressure),
+        more_stable: impl Fn(&HydrateEquilibrium<E>, &HydrateEquilibrium<E>) -> bool,
+    ) -> FeosResult<HydrateEquilibrium<E>> {
+        let feed = eos.validate_moles(Some(feed))?;
+        if self.guests.len() != feed.len() {
+            return Err(FeosError::IncompatibleComponents(
+                feed.len(),
+                self.guests.len(),
+            ));
+        }
+        let pure_water = Arc::new(eos.subset(&[self.water]));
+        let mut result: Option<FeosResult<HydrateEquilibrium<E>>> = None;
+        for structure in self.structures() {
+            let f = |x: f64| {
+                let (t, p) = state(x);
+                let phases = Self::fluid_phases(eos, t, p, &feed)?;
+                self.driving_force(&pure_water, structure, p, &phases)
+            };
+            let hydrate = solve_structure(&f);
+            result = Some(match (result, hydrate) {
+                (Some(Ok(old)), Ok(new)) if more_stable(&old, &new) => Ok(old),
+                (Some(Ok(old)), Err(_)) => Ok(old),
+                (_, hydrate) => hydrate,
+            });
+        }
+        result.unwrap_or_else(|| {
+            Err(FeosError::IncompatibleParameters(format!(
+                "{solver}: no component can enter a hydrate."
+            )))
+        })
+    }
+
+    /// Hydrate formation pressure at given temperature for a feed that
+    /// contains water and the hydrate forming components.
+    ///
+    /// The formation pressure is calculated for all hydrate structures
+    /// and the lowest one is returned.
+    pub fn formation_pressure<E: Residual>(
+        &self,
+        eos: &Arc<E>,
+        temperature: Temperature,
+        feed: &Moles<Array1<f64>>,
+        initial_pressure: Option<Pressure>,
+        options: SolverOptions,
+    ) -> FeosResult<HydrateEquilibrium<E>> {
+        let ln_p0 = initial_pressure.map_or(1e6, |p| p.convert_to(PASCAL)).ln();
+        self.solve(
+            eos,
+            feed,
+            "hydrate formation pressure",
+            |f| {
+                secant(
+                    "hydrate formation pressure",
+                    f,
+                    ln_p0,
+                    0.1,
+                    MAX_LN_PRESSURE_STEP,
+                    options,
+                )
+            },
+            |ln_p| (temperature, ln_p.exp() * PASCAL),
+            |old, new| old.pressure <= new.pressure,
+        )
+    }
+
+    /// Hydrate formation temperature at given pressure for a feed that
+    /// contains water and the hydrate forming components.
+    ///
+    /// The formation temperature is calculated for all hydrate structures
+    /// and the highest one is returned.
+    pub fn formation_temperature<E: Residual>(
+        &self,
+        eos: &Arc<E>,
+        pressure: Pressure,
+        feed: &Moles<Array1<f64>>,
+        initial_temperature: Option<Temperature>,
+        options: SolverOptions,
+    ) -> FeosResult<HydrateEquilibrium<E>> {
+        let t0 = initial_temperature.map_or(280.0, |t| t.convert_to(KELVIN));
+        self.solve(
+            eos,
+            feed,
+            "hydrate formation temperature",
+            |f| {
+                secant(
+                    "hydrate formation temperature",
+                    f,
+                    t0,
+                    -1.0,
+                    MAX_TEMPERATURE_STEP,
+                    options,
+                )
+            },
+            |t| (t * KELVIN, pressure),
+            |old, new| old.temperature >= new.temperature,
+        )
+    }
+}
+
+/// Secant method for the root of the driving force.
+fn secant<E>(
+    solver: &'static str,
+    f: &dyn Fn(f64) -> FeosResult<(f64, HydrateEquilibrium<E>)>,
+    x0: f64,
+    initial_step: f64,
+    max_step: f64,
+    options: SolverOptions,
+) -> FeosResult<HydrateEquilibrium<E>> {
+    let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER, TOL);
+    log_iter!(verbosity, " iter |    residual    |   variable   ");
+    log_iter!(verbosity, "{:-<36}", "");
+    let (mut x_old, mut g_old) = (x0, f(x0)?.0);
+    let mut x = x0 + initial_step;
+    for i in 1..=max_iter {
+        let (g, hydrate) = f(x)?;
+        log_iter!(verbosity, " {:4} | {:14.8e} | {:12.8}", i, g.abs(), x);
+        report_iteration(solver, i, g.abs(), &[x]);
+        if g.abs() < tol {
+            report_result(solver, i, true);
+            log_result!(
+                verbosity,
+                "{}: calculation converged in {} step(s)\n",
+                solver,
+                i
+            );
+            return Ok(hydrate);
+        }
+        let dx = (-g * (x - x_old) / (g - g_old)).clamp(-max_step, max_step);
+        (x_old, g_old) = (x, g);
+        x += dx;
+    }
+    report_result(solver, max_iter, false);
+    Err(FeosError::NotConverged(
+        SolverFailure::new(solver)
+            .iterations(max_iter)
+            .residual(g_old.abs()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Components;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use quantity::MEGA;
+
+    fn eos() -> FeosResult<Arc<PengRobinson>> {
+        // methane, water, methanol
+        let parameters = PengRobinsonParameters::new_simple(
+            &[190.56, 647.1, 512.6],
+            &[4.599e6, 22.064e6, 8.097e6],
+            &[0.011, 0.344, 0.565],
+            &[16.043, 18.015, 32.042],
+        )?;
+        Ok(Arc::new(PengRobinson::new(Arc::new(parameters))))
+    }
+
+    #[test]
+    fn methane_hydrate() -> FeosResult<()> {
+        let inhibited_eos = eos()?;
+        let eos = Arc::new(inhibited_eos.subset(&[0, 1]));
+        let file = "../../parameters/hydrate/munck1988.json";
+        let hydrate =
+            HydrateModel::from_json(&["methane", "water"], 1, file, IdentifierOption::Name)?;
+        let feed = Moles::from_reduced(arr1(&[0.3, 0.7]));
+
+        // experimental formation pressure: 5.3 MPa
+        let equilibrium =
+            hydrate.formation_pressure(&eos, 280.0 * KELVIN, &feed, None, Default::default())?;
+        assert_eq!(equilibrium.structure, HydrateStructure::SI);
+        assert!(equilibrium.pressure > 4.5 * MEGA * PASCAL);
+        assert!(equilibrium.pressure < 6.0 * MEGA * PASCAL);
+        assert!(equilibrium.aqueous.molefracs[1] > 0.99);
+        assert!(equilibrium.large_cavity_occupancy[0] > equilibrium.small_cavity_occupancy[0]);
+        assert_eq!(equilibrium.large_cavity_occupancy[1], 0.0);
+
+        // the formation temperature at the formation pressure is consistent
+        let inverse = hydrate.formation_temperature(
+            &eos,
+            equilibrium.pressure,
+            &feed,
+            None,
+            Default::default(),
+        )?;
+        assert!((inverse.temperature.convert_to(KELVIN) - 280.0).abs() < 1e-6);
+
+        // methanol inhibits the hydrate formation
+        let substances = ["methane", "water", "methanol"];
+        let hydrate = HydrateModel::from_json(&substances, 1, file, IdentifierOption::Name)?;
+        let feed = Moles::from_reduced(arr1(&[0.3, 0.6, 0.1]));
+        let inhibited = hydrate.formation_pressure(
+            &inhibited_eos,
+            280.0 * KELVIN,
+            &feed,
+            None,
+            Default::default(),
+        )?;
+        assert!(inhibited.pressure > equilibrium.pressure);
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::hydrate::{HydrateModel, HydrateStructure, ln_ice_activity};
+use crate::observer::{report_iteration, report_result};
+use crate::state::State;
+use crate::{ReferenceSystem, SolverOptions, Verbosity};
+use ndarray::Array1;
+use quantity::{KELVIN, Moles, Pressure, Temperature};
+use std::sync::Arc;
+
+const MAX_ITER: usize = 50;
+const TOL: f64 = 1e-10;
+const MAX_BRACKET_ITER: usize = 12;
+const INITIAL_DOSING: f64 = 0.1;
+
+/// Solid phase that determines the required amount of inhibitor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InhibitionTarget {
+    /// Formation of a hydrate with the given structure
+    Hydrate(HydrateStructure),
+    /// Formation of ice
+    Ice,
+}
+
+/// Amount of a thermodynamic inhibitor (e.g., methanol or MEG) that
+/// prevents the formation of hydrates and ice.
+pub struct InhibitorDosing<E> {
+    /// Amount of inhibitor that is added to the feed
+    pub inhibitor: Moles,
+    /// Fluid phases of the inhibited feed
+    pub phases: Vec<State<E>>,
+    /// Index of the aqueous phase
+    pub aqueous: usize,
+    /// Fraction of the total amount of inhibitor in every phase
+    pub distribution: Array1<f64>,
+    /// Solid phase that limits the dosing, `None` if no inhibitor is required
+    pub target: Option<InhibitionTarget>,
+}
+
+impl<E> InhibitorDosing<E> {
+    /// The aqueous phase of the inhibited feed.
+    pub fn aqueous_phase(&self) -> &State<E> {
+        &self.phases[self.aqueous]
+    }
+}
+
+impl HydrateModel {
+    /// Minimum amount of inhibitor that has to be added to the feed so that
+    /// neither hydrates nor ice form at the given temperature and pressure.
+    ///
+    /// The fluid phases are calculated with a three phase flash (if a third
+    /// phase exists), so that the loss of inhibitor to the gas and the
+    /// hydrocarbon liquid is accounted for. The amount of inhibitor is
+    /// bracketed and then refined with the Illinois method until the
+    /// hydrate or ice of the limiting solid phase is just at equilibrium.
+    pub fn inhibitor_dosing<E: Residual>(
+        &self,
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressure: Pressure,
+        feed: &Moles<Array1<f64>>,
+        inhibitor: usize,
+        options: SolverOptions,
+    ) -> FeosResult<InhibitorDosing<E>> {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER, TOL);
+        let feed = eos.validate_moles(Some(feed))?.to_reduced();
+        if self.guests.len() != feed.len() || inhibitor >= feed.len() {
+            return Err(FeosError::IncompatibleComponents(
+                feed.len(),
+                self.guests.len(),
+            ));
+        }
+        if inhibitor == self.water {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "water can not be used as inhibitor.",
+            )));
+        }
+        let pure_water = Arc::new(eos.subset(&[self.water]));
+        let t = temperature.convert_to(KELVIN);
+        let structures = self.structures();
+
+        // smallest margin to the formation of a hydrate or ice
+        let margin = |dosing: f64| -> FeosResult<_> {
+            let mut n = feed.clone();
+            n[inhibitor] += dosing;
+            let phases = Self::fluid_phases(eos, temperature, pressure, &Moles::from_reduced(n))?;
+            let aqueous = self.aqueous_phase(&phases);
+            let ln_a = self.ln_water_activity(&pure_water, &phases[aqueous], pressure)?;
+            let mut margin = (ln_ice_activity(t) - ln_a, InhibitionTarget::Ice);
+            for &structure in &structures {
+                let (g, _) = self.driving_force(&pure_water, structure, pressure, &phases)?;
+                if g < margin.0 {
+                    margin = (g, InhibitionTarget::Hydrate(structure));
+                }
+            }
+            Ok((margin, phases, aqueous))
+        };
+        let result = |dosing: f64, target, phases: Vec<State<E>>, aqueous| {
+            let total: f64 = phases.iter().map(|s| s.moles.to_reduced()[inhibitor]).sum();
+            let distribution = phases
+                .iter()
+                .map(|s| s.moles.to_reduced()[inhibitor] / total)
+                .collect();
+            InhibitorDosing {
+                inhibitor: Moles::from_reduced(dosing),
+                phases,
+                aqueous,
+                distribution,
+                target,
+            }
+        };
+
+        // no inhibitor is required if the feed is already stable
+        let ((mut f_a, _), phases, aqueous) = margin(0.0)?;
+        if f_a >= 0.0 {
+            return Ok(result(0.0, None, phases, aqueous));
+        }
+
+        // bracket the required amount of inhibitor
+        let mut a = 0.0;
+        let mut b = INITIAL_DOSING * feed[self.water];
+        let mut f_b = margin(b)?.0.0;
+        let mut k = 0;
+        while f_b < 0.0 {
+            k += 1;
+            if k > MAX_BRACKET_ITER {
+                return Err(FeosError::NotConverged(
+                    SolverFailure::new("inhibitor dosing")
+                        .iterations(k)
+                        .residual(-f_b)
+                        .temperature(temperature)
+                        .pressure(pressure),
+                ));
+            }
+            (a, f_a) = (b, f_b);
+            b *= 2.0;
+            f_b = margin(b)?.0.0;
+        }
+
+        log_iter!(verbosity, " iter |    residual    |    inhibitor   ");
+        log_iter!(verbosity, "{:-<38}", "");
+        let mut side = 0;
+        let mut res = f64::INFINITY;
+        for i in 1..=max_iter {
+            let c = (a * f_b - b * f_a) / (f_b - f_a);
+            let ((f_c, target), phases, aqueous) = margin(c)?;
+            res = f_c.abs();
+            log_iter!(verbosity, " {:4} | {:14.8e} | {:14.8e}", i, res, c);
+            report_iteration("inhibitor dosing", i, res, &[c]);
+            if res < tol {
+                report_result("inhibitor dosing", i, true);
+                log_result!(
+                    verbosity,
+                    "inhibitor_dosing: calculation converged in {} step(s)\n",
+                    i
+                );
+                return Ok(result(c, Some(target), phases, aqueous));
+            }
+            if f_c < 0.0 {
+                (a, f_a) = (c, f_c);
+                if side == -1 {
+                    f_b *= 0.5;
+                }
+                side = -1;
+            } else {
+                (b, f_b) = (c, f_c);
+                if side == 1 {
+                    f_a *= 0.5;
+                }
+                side = 1;
+            }
+        }
+        report_result("inhibitor dosing", max_iter, false);
+        Err(FeosError::NotConverged(
+            SolverFailure::new("inhibitor dosing")
+                .iterations(max_iter)
+                .residual(res)
+                .temperature(temperature)
+                .pressure(pressure),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use crate::parameter::IdentifierOption;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{MEGA, PASCAL};
+
+    #[test]
+    fn methanol_dosing() -> FeosResult<()> {
+        // methane, water, methanol
+        let parameters = PengRobinsonParameters::new_simple(
+            &[190.56, 647.1, 512.6],
+            &[4.599e6, 22.064e6, 8.097e6],
+            &[0.011, 0.344, 0.565],
+            &[16.043, 18.015, 32.042],
+        )?;
+        let eos = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let hydrate = HydrateModel::from_json(
+            &["methane", "water", "methanol"],
+            1,
+            "../../parameters/hydrate/munck1988.json",
+            IdentifierOption::Name,
+        )?;
+        let feed = Moles::from_reduced(arr1(&[0.3, 0.7, 1e-3]));
+
+        // the hydrate is stable at 10 MPa and 280 K without additional inhibitor
+        let pressure = 10.0 * MEGA * PASCAL;
+        let dosing = hydrate.inhibitor_dosing(
+            &eos,
+            280.0 * KELVIN,
+            pressure,
+            &feed,
+            2,
+            Default::default(),
+        )?;
+        assert_eq!(
+            dosing.target,
+            Some(InhibitionTarget::Hydrate(HydrateStructure::SI))
+        );
+        assert!(dosing.inhibitor.to_reduced() > 0.0);
+        assert!(dosing.distribution[dosing.aqueous] > 0.9);
+        assert_relative_eq!(dosing.distribution.sum(), 1.0, max_relative = 1e-12);
+
+        // the inhibited feed forms hydrates at the specified pressure
+        let mut inhibited = feed.to_reduced();
+        inhibited[2] += dosing.inhibitor.to_reduced();
+        let equilibrium = hydrate.formation_pressure(
+            &eos,
+            280.0 * KELVIN,
+            &Moles::from_reduced(inhibited),
+            Some(pressure),
+            Default::default(),
+        )?;
+        assert_relative_eq!(equilibrium.pressure, pressure, max_relative = 1e-6);
+
+        // below the hydrate formation pressure, ice limits the dosing
+        let dosing = hydrate.inhibitor_dosing(
+            &eos,
+            260.0 * KELVIN,
+            MEGA * PASCAL,
+            &feed,
+            2,
+            Default::default(),
+        )?;
+        assert_eq!(dosing.target, Some(InhibitionTarget::Ice));
+        let ln_a = dosing.aqueous_phase().ln_phi()[1] + dosing.aqueous_phase().molefracs[1].ln();
+        assert!(ln_a < 0.0);
+
+        // no inhibitor is required outside of the hydrate region
+        let dosing = hydrate.inhibitor_dosing(
+            &eos,
+            290.0 * KELVIN,
+            pressure,
+            &feed,
+            2,
+            Default::default(),
+        )?;
+        assert_eq!(dosing.target, None);
+        assert_eq!(dosing.inhibitor.to_reduced(), 0.0);
+        Ok(())
+    }
+}
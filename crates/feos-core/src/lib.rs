@@ -25,24 +25,74 @@ macro_rules! log_result {
     }
 }
 
+mod cancellation;
+mod combustion;
 pub mod cubic;
 mod density_iteration;
+pub mod diagram;
+#[cfg(feature = "extended_precision")]
+mod double_double;
 mod equation_of_state;
 mod errors;
+mod humid_gas;
+mod hydrate;
+mod inhibitor;
+mod natural_gas;
+mod newton;
+mod observer;
 pub mod parameter;
 mod phase_equilibria;
+mod props_si;
+mod reaction_equilibrium;
 mod state;
+mod tabulation;
+#[cfg(test)]
+mod test_utils;
+#[cfg(feature = "uom")]
+mod uom_conversion;
+mod water_content;
+pub use cancellation::{
+    CancellableTask, CancellationToken, check_cancelled, current_cancellation, with_cancellation,
+};
+pub use combustion::AdiabaticFlame;
+#[cfg(feature = "extended_precision")]
+pub use double_double::DoubleDouble;
 pub use equation_of_state::{
-    Components, EntropyScaling, EquationOfState, IdealGas, Molarweight, NoResidual, Residual,
+    Components, EntropyScaling, EquationOfState, IdealGas, Molarweight, NoResidual, Parachor,
+    ReducedResidualDerivatives, ReducingParameters, Residual, ResidualContribution,
+    WithContribution,
 };
-pub use errors::{FeosError, FeosResult};
+pub use errors::{FeosError, FeosResult, SolverFailure};
+pub use humid_gas::HumidGas;
+pub use hydrate::{
+    HydrateEquilibrium, HydrateGuestRecord, HydrateModel, HydrateStructure, LangmuirConstants,
+    LangmuirRecord,
+};
+pub use inhibitor::{InhibitionTarget, InhibitorDosing};
+pub use natural_gas::{GasComposition, GasQuality, MeteringDeviations, MeteringProperties};
+pub use newton::NewtonSolver;
+pub use observer::{Iteration, SolverObserver, with_observer};
+#[cfg(feature = "rayon")]
+pub use phase_equilibria::FlashBatch;
 pub use phase_equilibria::{
-    PhaseDiagram, PhaseDiagramHetero, PhaseEquilibrium, TemperatureOrPressure,
+    ConstantCompositionExpansion, ConstantVolumeDepletion, CriticalLocus, DifferentialLiberation,
+    FlashCache, FlashDerivatives, FlashSpecification, FlashWorkspace, GradientTheoryProfile,
+    Isoline, IsolinePoint, MinimumMiscibilityPressure, PhaseBehaviorType, PhaseDiagram,
+    PhaseDiagramHetero, PhaseEquilibrium, PhaseStability, SaturationCache, SaturationCurve,
+    SaturationDerivatives, SwellingTest, TemperatureOrPressure, rachford_rice,
+    rachford_rice_compositions, rachford_rice_multiphase, rachford_rice_multiphase_compositions,
+    rachford_rice_negative_flash,
 };
+pub use props_si::props_si;
+pub use reaction_equilibrium::{ChemicalReactions, FormationRecord, ReactionEquilibrium};
 pub use state::{
-    Contributions, DensityInitialization, Derivative, State, StateBuilder, StateHD, StateVec,
+    Contributions, DeadState, DensityInitialization, Derivative, PhaseLabel, State, StateBuilder,
+    StateExpression, StateHD, StateRecord, StateVec,
 };
-
+pub use tabulation::{PropertyTable, TabulatedProperty, TabulationOptions};
+#[cfg(feature = "uom")]
+pub use uom_conversion::{FromUom, IntoUom, UomQuantity};
+pub use water_content::WaterContent;
 
 /// Level of detail in the iteration output.
 #[derive(Copy, Clone, PartialOrd, PartialEq, Eq)]
@@ -61,6 +111,21 @@ impl Default for Verbosity {
     }
 }
 
+/// Iteration scheme of solvers that implement more than one algorithm.
+///
+/// Solvers that do not implement the requested algorithm use their
+/// default scheme.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SolverAlgorithm {
+    /// The solver specific default (usually a combination of the algorithms).
+    #[default]
+    Default,
+    /// Successive substitution without acceleration or Newton steps.
+    SuccessiveSubstitution,
+    /// Newton steps from the first iteration.
+    Newton,
+}
+
 /// Options for the various phase equilibria solvers.
 ///
 /// If the values are [None], solver specific default
@@ -71,6 +136,14 @@ pub struct SolverOptions {
     pub max_iter: Option<usize>,
     /// Tolerance.
     pub tol: Option<f64>,
+    /// Factor between 0 and 1 that scales the Newton steps of bubble and dew point,
+    /// critical point, spinodal and density gradient theory calculations.
+    pub damping: Option<f64>,
+    /// Iteration scheme of Tp flash and bubble and dew point (outer loop)
+    /// calculations indicated by the [SolverAlgorithm] enum.
+    pub algorithm: SolverAlgorithm,
+    /// Backtracking line search in solvers that use the [NewtonSolver].
+    pub line_search: Option<bool>,
     /// Iteration outpput indicated by the [Verbosity] enum.
     pub verbosity: Verbosity,
 }
@@ -81,6 +154,7 @@ impl From<(Option<usize>, Option<f64>, Option<Verbosity>)> for SolverOptions {
             max_iter: options.0,
             tol: options.1,
             verbosity: options.2.unwrap_or(Verbosity::None),
+            ..Default::default()
         }
     }
 }
@@ -105,6 +179,21 @@ impl SolverOptions {
         self
     }
 
+    pub fn damping(mut self, damping: f64) -> Self {
+        self.damping = Some(damping);
+        self
+    }
+
+    pub fn algorithm(mut self, algorithm: SolverAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    pub fn line_search(mut self, line_search: bool) -> Self {
+        self.line_search = Some(line_search);
+        self
+    }
+
     pub fn unwrap_or(self, max_iter: usize, tol: f64) -> (usize, f64, Verbosity) {
         (
             self.max_iter.unwrap_or(max_iter),
@@ -112,6 +201,17 @@ impl SolverOptions {
             self.verbosity,
         )
     }
+
+    /// The damping factor of Newton steps, `1.0` (no damping) by default.
+    pub fn damping_or_default(&self) -> FeosResult<f64> {
+        match self.damping {
+            None => Ok(1.0),
+            Some(d) if d > 0.0 && d <= 1.0 => Ok(d),
+            Some(d) => Err(FeosError::Error(format!(
+                "The damping factor has to be in (0, 1], got {d}."
+            ))),
+        }
+    }
 }
 
 /// Reference values used for reduced properties in feos
@@ -167,16 +267,8 @@ pub trait ReferenceSystem<
 }
 
 /// Conversion to and from reduced units
-impl<
-        Inner,
-        T: Integer,
-        L: Integer,
-        M: Integer,
-        I: Integer,
-        THETA: Integer,
-        N: Integer,
-        J: Integer,
-    > ReferenceSystem<Inner, T, L, M, I, THETA, N, J>
+impl<Inner, T: Integer, L: Integer, M: Integer, I: Integer, THETA: Integer, N: Integer, J: Integer>
+    ReferenceSystem<Inner, T, L, M, I, THETA, N, J>
     for Quantity<Inner, SIUnit<T, L, M, I, THETA, N, J>>
 {
     fn from_reduced(value: Inner) -> Self
@@ -203,12 +295,12 @@ impl<
 
 #[cfg(test)]
 mod tests {
-    use crate::cubic::*;
-    use crate::equation_of_state::{Components, EquationOfState, IdealGas};
-    use crate::parameter::*;
     use crate::Contributions;
     use crate::FeosResult;
     use crate::StateBuilder;
+    use crate::cubic::*;
+    use crate::equation_of_state::{Components, EquationOfState, IdealGas};
+    use crate::parameter::*;
     use approx::*;
     use ndarray::Array1;
     use num_dual::DualNum;
@@ -0,0 +1,596 @@
+//! Properties of natural gases for metering and gas quality calculations.
+//!
+//! - [MeteringProperties]: compressibility factor, density and molar mass
+//!   at metering conditions.
+//! - [GasQuality]: calorific values, Wobbe index and relative density in the
+//!   style of ISO 6976.
+//! - [GasComposition]: import of gas analyses in the style of GPA 2145 and
+//!   ISO 6976 composition tables.
+use crate::ReferenceSystem;
+use crate::equation_of_state::{Molarweight, Residual};
+use crate::errors::{FeosError, FeosResult};
+use crate::parameter::{IdentifierOption, PureRecord, ValidationReport};
+use crate::state::{Contributions, DensityInitialization, State};
+use ndarray::Array1;
+use quantity::{
+    Density, Energy, GRAM, JOULE, KELVIN, KILO, MOL, MassDensity, MolarEnergy, MolarWeight, Moles,
+    PASCAL, Pressure, Temperature, Volume,
+};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use typenum::Quot;
+
+/// Uncertainty of the compressibility factor in the pipeline quality range
+/// according to ISO 12213-2 in percent.
+const ISO_12213_UNCERTAINTY: f64 = 0.1;
+/// Molar mass of dry air in g/mol (ISO 6976:2016).
+const MOLAR_WEIGHT_AIR: f64 = 28.96546;
+/// Compressibility factor of dry air at 15 °C and 101.325 kPa (ISO 6976:2016).
+const COMPRESSIBILITY_AIR: f64 = 0.999595;
+/// Molar enthalpy of vaporization of water at 15 °C in kJ/mol (ISO 6976:2016).
+const ENTHALPY_OF_VAPORIZATION_WATER: f64 = 44.433;
+/// Relative deviation of the total of a gas analysis from 100 % above which
+/// a warning is issued during normalization.
+const NORMALIZATION_TOLERANCE: f64 = 0.01;
+
+/// Compressibility factor, density and molar mass of a natural gas at given
+/// metering conditions.
+#[derive(Clone, Copy, Debug)]
+pub struct MeteringProperties {
+    pub temperature: Temperature,
+    pub pressure: Pressure,
+    /// Compressibility factor $Z=\frac{p}{\rho RT}$.
+    pub compressibility: f64,
+    /// Molar density.
+    pub density: Density,
+    /// Mass density.
+    pub mass_density: MassDensity,
+    /// Molar mass of the gas.
+    pub molar_weight: MolarWeight,
+}
+
+impl MeteringProperties {
+    /// Calculate the properties of a natural gas with given composition
+    /// at the given temperature and pressure.
+    ///
+    /// The gas is always evaluated on the vapor branch of the equation of state.
+    pub fn new<E: Residual + Molarweight>(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressure: Pressure,
+        molefracs: &Array1<f64>,
+    ) -> FeosResult<Self> {
+        if molefracs.iter().any(|&x| x < 0.0) || molefracs.sum() <= 0.0 {
+            return Err(FeosError::Error(
+                "The composition of the natural gas has to be positive.".into(),
+            ));
+        }
+        let moles = Moles::from_reduced(molefracs / molefracs.sum());
+        let state = State::new_npt(
+            eos,
+            temperature,
+            pressure,
+            &moles,
+            DensityInitialization::Vapor,
+        )?;
+        Ok(Self {
+            temperature,
+            pressure,
+            compressibility: state.compressibility(Contributions::Total),
+            density: state.density,
+            mass_density: state.mass_density(),
+            molar_weight: state.total_molar_weight(),
+        })
+    }
+
+    /// Calculate the properties of a natural gas with given composition
+    /// at the standard reference conditions of ISO 13443
+    /// (288.15 K and 101.325 kPa).
+    pub fn standard_conditions<E: Residual + Molarweight>(
+        eos: &Arc<E>,
+        molefracs: &Array1<f64>,
+    ) -> FeosResult<Self> {
+        Self::new(eos, 288.15 * KELVIN, 101325.0 * PASCAL, molefracs)
+    }
+
+    /// Compare the compressibility factor and, optionally, the molar density
+    /// with reference values, e.g., from the AGA8-DC92 equation
+    /// of ISO 12213-2.
+    pub fn compare(&self, compressibility: f64, density: Option<Density>) -> MeteringDeviations {
+        MeteringDeviations {
+            compressibility: (self.compressibility / compressibility - 1.0) * 100.0,
+            density: density.map(|d| ((self.density / d).into_value() - 1.0) * 100.0),
+        }
+    }
+}
+
+/// Relative deviations of calculated metering properties from reference values in percent.
+#[derive(Clone, Copy, Debug)]
+pub struct MeteringDeviations {
+    /// Relative deviation of the compressibility factor.
+    pub compressibility: f64,
+    /// Relative deviation of the molar density, if a reference value was given.
+    pub density: Option<f64>,
+}
+
+impl MeteringDeviations {
+    /// Check if all deviations are within the given tolerance in percent.
+    pub fn is_within(&self, tolerance: f64) -> bool {
+        self.compressibility.abs() <= tolerance && self.density.is_none_or(|d| d.abs() <= tolerance)
+    }
+
+    /// Check if all deviations are within the uncertainty of 0.1% that
+    /// ISO 12213-2 states for pipeline quality gas.
+    pub fn is_within_iso_12213(&self) -> bool {
+        self.is_within(ISO_12213_UNCERTAINTY)
+    }
+}
+
+/// Calorific values, Wobbe index and relative density of a natural gas.
+///
+/// The calculation follows ISO 6976 for a combustion reference temperature and
+/// metering conditions of 15 °C and 101.325 kPa. The superior molar calorific
+/// value is the mole fraction weighted sum of the enthalpies of combustion stored
+/// in the pure records. For the inferior calorific value, the enthalpy of
+/// vaporization of the water formed from the hydrogen atoms in the chemical
+/// formulas is subtracted. Volumetric values and the relative density are
+/// evaluated for the real gas with the compressibility factor from the equation
+/// of state.
+#[derive(Clone, Copy, Debug)]
+pub struct GasQuality {
+    /// Superior (gross) molar calorific value.
+    pub superior_calorific_value: MolarEnergy,
+    /// Inferior (net) molar calorific value.
+    pub inferior_calorific_value: MolarEnergy,
+    /// Superior calorific value per volume of the real gas.
+    pub superior_volumetric_calorific_value: Quot<Energy, Volume>,
+    /// Inferior calorific value per volume of the real gas.
+    pub inferior_volumetric_calorific_value: Quot<Energy, Volume>,
+    /// Relative density of the real gas with respect to dry air.
+    pub relative_density: f64,
+    /// Superior Wobbe index $W_S=\frac{H_{S,V}}{\sqrt{d}}$.
+    pub wobbe_index: Quot<Energy, Volume>,
+    /// Properties of the gas at the metering conditions.
+    pub metering: MeteringProperties,
+}
+
+impl GasQuality {
+    /// Calculate the gas quality of a natural gas with given composition.
+    ///
+    /// Every pure record needs an enthalpy of combustion and components with a
+    /// nonzero enthalpy of combustion need a chemical formula (or SMILES).
+    pub fn new<E: Residual + Molarweight, M>(
+        eos: &Arc<E>,
+        pure_records: &[PureRecord<M>],
+        molefracs: &Array1<f64>,
+    ) -> FeosResult<Self> {
+        if pure_records.len() != eos.components() {
+            return Err(FeosError::IncompatibleComponents(
+                eos.components(),
+                pure_records.len(),
+            ));
+        }
+        let metering = MeteringProperties::standard_conditions(eos, molefracs)?;
+        let x = molefracs / molefracs.sum();
+
+        let mut h_s = 0.0;
+        let mut h_i = 0.0;
+        for (record, x) in pure_records.iter().zip(&x) {
+            let h = record.enthalpy_of_combustion.ok_or_else(|| {
+                FeosError::Error(format!(
+                    "No enthalpy of combustion available for {}.",
+                    record.identifier
+                ))
+            })?;
+            let water = if h == 0.0 {
+                0.0
+            } else {
+                let formula = record.identifier.chemical_formula().ok_or_else(|| {
+                    FeosError::Error(format!(
+                        "No chemical formula available for {}.",
+                        record.identifier
+                    ))
+                })??;
+                0.5 * formula.count("H") as f64
+            };
+            h_s += x * h;
+            h_i += x * (h - water * ENTHALPY_OF_VAPORIZATION_WATER);
+        }
+        let unit = KILO * JOULE / MOL;
+        let superior_calorific_value = h_s * unit;
+        let inferior_calorific_value = h_i * unit;
+
+        let relative_density = (metering.molar_weight / (MOLAR_WEIGHT_AIR * GRAM / MOL))
+            .into_value()
+            * COMPRESSIBILITY_AIR
+            / metering.compressibility;
+        let superior_volumetric_calorific_value = superior_calorific_value * metering.density;
+        Ok(Self {
+            superior_calorific_value,
+            inferior_calorific_value,
+            superior_volumetric_calorific_value,
+            inferior_volumetric_calorific_value: inferior_calorific_value * metering.density,
+            relative_density,
+            wobbe_index: superior_volumetric_calorific_value / relative_density.sqrt(),
+            metering,
+        })
+    }
+}
+
+/// Composition of a natural gas imported from a gas analysis.
+///
+/// Gas analyses are exchanged as composition tables in the style of GPA 2145
+/// or ISO 6976 with one component per row, e.g.,
+///
+/// ```text
+/// # component, mol%
+/// methane, 85.12
+/// ethane, 7.50
+/// CO2, 1.23
+/// nitrogen, balance
+/// ```
+///
+/// Columns are separated by commas, semicolons or tabs. Without any of these,
+/// the last whitespace separated token is the amount and the preceding tokens
+/// form the component. Amounts can be given either as mole fractions or in
+/// mol% (a trailing `%` is ignored); the basis is mol% if any amount exceeds 1.
+/// Lines starting with `#`, rows without amount (headers) and rows named
+/// `total` or `sum` are skipped.
+///
+/// Components are resolved against the identifiers of the pure records by
+/// name or IUPAC name (case-insensitive), CAS number, SMILES or chemical
+/// formula. Exactly one component can be marked as balance component, either
+/// in the table (`bal` or `balance`) or via the `balance` argument. It takes
+/// the remainder to 100 %. The resulting composition is normalized.
+/// Unmatched species are dropped, and reported together with repeated species
+/// and totals that deviate from 100 % by more than 1 %.
+#[derive(Clone, Debug)]
+pub struct GasComposition {
+    /// Normalized mole fractions in the order of the pure records.
+    pub molefracs: Array1<f64>,
+    /// Warnings for unmatched or repeated species and for the normalization.
+    pub report: ValidationReport,
+}
+
+impl GasComposition {
+    /// Read a composition table from a file.
+    pub fn from_file<P: AsRef<Path>, M>(
+        file: P,
+        pure_records: &[PureRecord<M>],
+        balance: Option<&str>,
+    ) -> FeosResult<Self> {
+        Self::from_table(&fs::read_to_string(file)?, pure_records, balance)
+    }
+
+    /// Parse a composition table given as string.
+    pub fn from_table<M>(
+        table: &str,
+        pure_records: &[PureRecord<M>],
+        balance: Option<&str>,
+    ) -> FeosResult<Self> {
+        let mut report = ValidationReport::new();
+        let mut amounts = Array1::zeros(pure_records.len());
+        let mut found = vec![false; pure_records.len()];
+        let mut balance = balance.map(|b| (b.to_string(), 0));
+        let mut unmatched = Vec::new();
+        let mut percent = false;
+        for (line_number, line) in table.lines().enumerate().map(|(i, l)| (i + 1, l)) {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((component, amount)) = split_row(line) else {
+                continue;
+            };
+            if ["total", "sum"].contains(&component.to_lowercase().as_str()) {
+                continue;
+            }
+            let Some(amount) = amount else {
+                if let Some((b, _)) = &balance {
+                    return Err(FeosError::Error(format!(
+                        "Line {line_number}: {component} and {b} are both balance components."
+                    )));
+                }
+                balance = Some((component.to_string(), line_number));
+                continue;
+            };
+            if amount < 0.0 {
+                return Err(FeosError::Error(format!(
+                    "Line {line_number}: negative amount of {component}."
+                )));
+            }
+            percent |= amount > 1.0;
+            match find_component(component, pure_records) {
+                Some(i) => {
+                    if found[i] {
+                        report.warning(
+                            vec![i],
+                            format!("{component} is given more than once, the amounts are added"),
+                        );
+                    }
+                    found[i] = true;
+                    amounts[i] += amount;
+                }
+                None => unmatched.push((component.to_string(), amount)),
+            }
+        }
+
+        let unmatched_total: f64 = unmatched.iter().map(|(_, x)| x).sum();
+        let total = amounts.sum() + unmatched_total;
+        percent |= total > 1.0;
+        let basis = if percent { 100.0 } else { 1.0 };
+        let unit = if percent { " mol%" } else { "" };
+        let total: f64 = match balance {
+            Some((component, line_number)) => {
+                let i = find_component(&component, pure_records)
+                    .ok_or_else(|| FeosError::ComponentsNotFound(vec![component.clone()]))?;
+                if found[i] {
+                    return Err(FeosError::Error(format!(
+                        "Line {line_number}: the balance component {component} has an explicit amount."
+                    )));
+                }
+                if total > basis {
+                    return Err(FeosError::Error(format!(
+                        "The amounts sum to {total}{unit}, leaving no remainder for the balance component {component}."
+                    )));
+                }
+                amounts[i] = basis - total;
+                basis
+            }
+            None => total,
+        };
+        for (component, amount) in unmatched {
+            report.warning(
+                vec![],
+                format!(
+                    "{component} ({amount}{unit}) was not found in the parameters and is ignored"
+                ),
+            );
+        }
+        if (total / basis - 1.0).abs() > NORMALIZATION_TOLERANCE {
+            report.warning(
+                vec![],
+                format!("the amounts sum to {total}{unit} and are normalized"),
+            );
+        }
+        let matched_total = amounts.sum();
+        if matched_total <= 0.0 {
+            return Err(FeosError::Error(
+                "None of the components of the gas analysis is contained in the parameters.".into(),
+            ));
+        }
+        Ok(Self {
+            molefracs: amounts / matched_total,
+            report,
+        })
+    }
+}
+
+/// Split a row of a composition table into the component and the amount,
+/// which is `None` for the balance component.
+fn split_row(line: &str) -> Option<(&str, Option<f64>)> {
+    let amount = |s: &str| match s.trim().trim_end_matches('%').trim() {
+        s if s.eq_ignore_ascii_case("bal") || s.eq_ignore_ascii_case("balance") => Some(None),
+        s => s.parse().ok().map(Some),
+    };
+    let (component, amount) = match line.find([',', ';', '\t']) {
+        Some(_) => {
+            let fields: Vec<_> = line.split([',', ';', '\t']).map(str::trim).collect();
+            (fields[0], fields[1..].iter().rev().find_map(|f| amount(f))?)
+        }
+        None => {
+            let (component, last) = line.rsplit_once(char::is_whitespace)?;
+            (component.trim(), amount(last)?)
+        }
+    };
+    (!component.is_empty()).then_some((component, amount))
+}
+
+/// Index of the pure record that matches any identifier of `component`.
+fn find_component<M>(component: &str, pure_records: &[PureRecord<M>]) -> Option<usize> {
+    let formula = IdentifierOption::Formula.normalize(component);
+    pure_records.iter().position(|r| {
+        let id = &r.identifier;
+        [&id.name, &id.iupac_name]
+            .into_iter()
+            .flatten()
+            .any(|n| n.eq_ignore_ascii_case(component))
+            || [&id.cas, &id.smiles]
+                .into_iter()
+                .flatten()
+                .any(|n| n == component)
+            || id.formula.as_deref() == Some(&formula)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use crate::parameter::Identifier;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{MEGA, METER, RGAS};
+    use typenum::P3;
+
+    fn eos() -> FeosResult<Arc<PengRobinson>> {
+        // methane, ethane, nitrogen, carbon dioxide
+        let parameters = PengRobinsonParameters::new_simple(
+            &[190.564, 305.32, 126.192, 304.1282],
+            &[4599200.0, 4872200.0, 3395800.0, 7377300.0],
+            &[0.01142, 0.0995, 0.0372, 0.22394],
+            &[16.0428, 30.069, 28.0135, 44.0098],
+        )?;
+        Ok(Arc::new(PengRobinson::new(Arc::new(parameters))))
+    }
+
+    #[test]
+    fn standard_conditions() -> FeosResult<()> {
+        let eos = eos()?;
+        let x = arr1(&[0.9, 0.05, 0.03, 0.02]);
+        let props = MeteringProperties::standard_conditions(&eos, &x)?;
+        assert_relative_eq!(
+            props.compressibility,
+            (props.pressure / (props.density * RGAS * props.temperature)).into_value(),
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(props.mass_density, props.density * props.molar_weight);
+        assert!(props.compressibility < 1.0 && props.compressibility > 0.99);
+
+        // unnormalized compositions are accepted
+        let scaled = MeteringProperties::standard_conditions(&eos, &(2.0 * &x))?;
+        assert_relative_eq!(scaled.compressibility, props.compressibility);
+        assert!(
+            MeteringProperties::standard_conditions(&eos, &arr1(&[1.0, -0.1, 0.0, 0.1])).is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn compare_reference() -> FeosResult<()> {
+        let eos = eos()?;
+        let x = arr1(&[0.9, 0.05, 0.03, 0.02]);
+        let t = 290.0 * KELVIN;
+        let p = 6.0 * MEGA * PASCAL;
+        let props = MeteringProperties::new(&eos, t, p, &x)?;
+        let state = State::new_npt(
+            &eos,
+            t,
+            p,
+            &Moles::from_reduced(x.clone()),
+            DensityInitialization::Vapor,
+        )?;
+        assert_relative_eq!(
+            props.compressibility,
+            state.compressibility(Contributions::Total)
+        );
+        assert!(props.compressibility < 0.95);
+
+        let deviations = props.compare(props.compressibility * 1.0005, Some(props.density));
+        assert_relative_eq!(deviations.compressibility, -0.04997501, max_relative = 1e-6);
+        assert_relative_eq!(deviations.density.unwrap(), 0.0, epsilon = 1e-12);
+        assert!(deviations.is_within_iso_12213());
+        assert!(!deviations.is_within(0.01));
+        let deviations = props.compare(props.compressibility, Some(props.density * 1.002));
+        assert!(!deviations.is_within_iso_12213());
+        Ok(())
+    }
+
+    fn pure_records() -> Vec<PureRecord<()>> {
+        // enthalpies of combustion at 15 °C from ISO 6976:2016
+        [
+            ("methane", "CH4", 16.0428, 891.51),
+            ("ethane", "C2H6", 30.069, 1562.14),
+            ("nitrogen", "N2", 28.0135, 0.0),
+            ("carbon dioxide", "CO2", 44.0098, 0.0),
+        ]
+        .into_iter()
+        .map(|(name, formula, mw, h)| {
+            let id = Identifier::new(None, Some(name), None, None, None, Some(formula));
+            PureRecord::new(id, mw, ()).with_enthalpy_of_combustion(h)
+        })
+        .collect()
+    }
+
+    #[test]
+    fn gas_quality() -> FeosResult<()> {
+        let eos = eos()?;
+        let records = pure_records();
+        let x = arr1(&[0.9, 0.05, 0.03, 0.02]);
+        let quality = GasQuality::new(&eos, &records, &x)?;
+        let mj = MEGA * JOULE;
+        assert_relative_eq!(
+            quality
+                .superior_calorific_value
+                .convert_to(KILO * JOULE / MOL),
+            0.9 * 891.51 + 0.05 * 1562.14,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            (quality.superior_calorific_value - quality.inferior_calorific_value)
+                .convert_to(KILO * JOULE / MOL),
+            (0.9 * 2.0 + 0.05 * 3.0) * 44.433,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            quality.wobbe_index,
+            quality.superior_volumetric_calorific_value / quality.relative_density.sqrt()
+        );
+        assert!(quality.relative_density > 0.6 && quality.relative_density < 0.65);
+        assert!(quality.wobbe_index.convert_to(mj / METER.powi::<P3>()) > 45.0);
+
+        // pure methane
+        let x = arr1(&[1.0, 0.0, 0.0, 0.0]);
+        let quality = GasQuality::new(&eos, &records, &x)?;
+        let z = quality.metering.compressibility;
+        let t = 288.15 * KELVIN;
+        let p = 101325.0 * PASCAL;
+        assert_relative_eq!(
+            quality.superior_volumetric_calorific_value,
+            891.51 * KILO * JOULE / MOL * p / (z * RGAS * t),
+            max_relative = 1e-10
+        );
+        assert_relative_eq!(
+            quality.relative_density,
+            16.0428 / 28.96546 * 0.999595 / z,
+            max_relative = 1e-10
+        );
+
+        let mut records = records;
+        records[2].enthalpy_of_combustion = None;
+        assert!(GasQuality::new(&eos, &records, &x).is_err());
+        assert!(GasQuality::new(&eos, &records[..3], &x).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn gas_composition() -> FeosResult<()> {
+        let records = pure_records();
+        let table = "# component, mol%
+component, amount
+Methane, 85.0 %
+C2H6, 5.0
+CO2, 2.0
+nitrogen, balance
+hydrogen sulfide, 0.5
+total, 100.0
+";
+        let composition = GasComposition::from_table(table, &records, None)?;
+        assert_relative_eq!(
+            composition.molefracs,
+            arr1(&[0.85, 0.05, 0.075, 0.02]) / 0.995,
+            max_relative = 1e-12
+        );
+        assert_eq!(composition.report.warnings().count(), 1);
+
+        // whitespace separated mole fractions that do not sum to 1
+        let table = "methane 0.8\ncarbon dioxide 0.1\nethane 0.05\nmethane 0.01";
+        let composition = GasComposition::from_table(table, &records, None)?;
+        assert_relative_eq!(
+            composition.molefracs,
+            arr1(&[0.81, 0.05, 0.0, 0.1]) / 0.96,
+            max_relative = 1e-12
+        );
+        assert_eq!(composition.report.warnings().count(), 2);
+
+        // balance component given as argument
+        let table = "methane; 0.9\nethane; 0.05";
+        let composition = GasComposition::from_table(table, &records, Some("N2"))?;
+        assert_relative_eq!(
+            composition.molefracs,
+            arr1(&[0.9, 0.05, 0.05, 0.0]),
+            max_relative = 1e-12
+        );
+        assert!(composition.report.is_valid() && composition.report.issues.is_empty());
+
+        assert!(GasComposition::from_table(table, &records, Some("argon")).is_err());
+        assert!(GasComposition::from_table("methane, 1.1\nethane, bal", &records, None).is_ok());
+        assert!(
+            GasComposition::from_table("methane, 0.9\nethane, bal", &records, Some("N2")).is_err()
+        );
+        assert!(GasComposition::from_table("methane, -0.1\nethane, 1.0", &records, None).is_err());
+        assert!(GasComposition::from_table("argon, 1.0", &records, None).is_err());
+        Ok(())
+    }
+}
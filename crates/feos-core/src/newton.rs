@@ -0,0 +1,339 @@
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::observer::{is_observed, report_iteration, report_result};
+use crate::{SolverOptions, Verbosity};
+use nalgebra::allocator::Allocator;
+use nalgebra::{DefaultAllocator, Dim, DimMin, OMatrix, OVector, U1};
+use num_dual::{DualVec64, try_jacobian};
+
+const MIN_STEP_LENGTH: f64 = 1.0 / 64.0;
+const ARMIJO: f64 = 1e-4;
+
+type Jacobian<D> = (OVector<f64, D>, OMatrix<f64, D, D>);
+type StepLimit<'a, D> = Box<dyn Fn(&OVector<f64, D>, &mut OVector<f64, D>) + 'a>;
+type Projection<'a, D> = Box<dyn Fn(&mut OVector<f64, D>) + 'a>;
+type Report<'a, D> = Box<dyn Fn(&OVector<f64, D>) -> Vec<f64> + 'a>;
+type Failure<'a, D> = Box<dyn Fn(&OVector<f64, D>, SolverFailure) -> SolverFailure + 'a>;
+
+/// Damped Newton solver for systems of nonlinear equations.
+///
+/// The Jacobian is calculated from the residuals using generalized (dual)
+/// numbers, so that only the residuals have to be implemented for a new
+/// problem. The Newton steps can be limited (to stay within a trust region),
+/// scaled by the damping factor of the [SolverOptions], and projected onto
+/// the feasible domain of the variables. Optionally, a backtracking line
+/// search on the norm of the residuals ensures that every step improves
+/// the solution.
+///
+/// The solver is used for the critical point, spinodal, pure component
+/// phase equilibrium and bubble and dew point calculations and can be used
+/// to implement new specifications of states and phase equilibria. If the
+/// number of variables is only known at runtime, the Jacobian can be
+/// supplied directly with [NewtonSolver::solve_with_jacobian].
+pub struct NewtonSolver<'a, D: Dim>
+where
+    DefaultAllocator: Allocator<D>,
+{
+    name: &'static str,
+    max_iter: usize,
+    tol: f64,
+    damping: f64,
+    line_search: bool,
+    verbosity: Verbosity,
+    options: SolverOptions,
+    step_limit: Option<StepLimit<'a, D>>,
+    projection: Option<Projection<'a, D>>,
+    report: Option<Report<'a, D>>,
+    failure: Option<Failure<'a, D>>,
+}
+
+impl<'a, D: DimMin<D, Output = D>> NewtonSolver<'a, D>
+where
+    DefaultAllocator: Allocator<D> + Allocator<D, D> + Allocator<U1, D>,
+{
+    /// Create a new solver. The maximum number of iterations and the
+    /// tolerance are used, if they are not specified in the options.
+    pub fn new(
+        name: &'static str,
+        max_iter: usize,
+        tol: f64,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let (max_iter, tol, verbosity) = options.unwrap_or(max_iter, tol);
+        Ok(Self {
+            name,
+            max_iter,
+            tol,
+            damping: options.damping_or_default()?,
+            line_search: options.line_search.unwrap_or(false),
+            verbosity,
+            options,
+            step_limit: None,
+            projection: None,
+            report: None,
+            failure: None,
+        })
+    }
+
+    /// Enable or disable the line search, if it is not specified in the options.
+    pub fn line_search(mut self, line_search: bool) -> Self {
+        self.line_search = self.options.line_search.unwrap_or(line_search);
+        self
+    }
+
+    /// Limit the Newton step for given variables, e.g., by scaling the step.
+    pub fn step_limit(
+        mut self,
+        step_limit: impl Fn(&OVector<f64, D>, &mut OVector<f64, D>) + 'a,
+    ) -> Self {
+        self.step_limit = Some(Box::new(step_limit));
+        self
+    }
+
+    /// Project the variables after every step onto their feasible domain.
+    pub fn projection(mut self, projection: impl Fn(&mut OVector<f64, D>) + 'a) -> Self {
+        self.projection = Some(Box::new(projection));
+        self
+    }
+
+    /// Values (e.g., the variables in SI units) that are printed and
+    /// reported to the observer in every iteration.
+    pub fn report(mut self, report: impl Fn(&OVector<f64, D>) -> Vec<f64> + 'a) -> Self {
+        self.report = Some(Box::new(report));
+        self
+    }
+
+    /// Add information about the last iterate (e.g., its temperature and
+    /// pressure) to the [SolverFailure] if the iteration fails.
+    pub fn failure(
+        mut self,
+        failure: impl Fn(&OVector<f64, D>, SolverFailure) -> SolverFailure + 'a,
+    ) -> Self {
+        self.failure = Some(Box::new(failure));
+        self
+    }
+
+    /// Solve the system of equations `residual(x) = 0` starting from `x0`.
+    ///
+    /// The iteration is converged if the norm of the residuals is smaller
+    /// than the tolerance. The final Newton step is applied to the result.
+    pub fn solve<F>(&self, residual: F, x0: OVector<f64, D>) -> FeosResult<OVector<f64, D>>
+    where
+        F: Fn(OVector<DualVec64<D>, D>) -> FeosResult<OVector<DualVec64<D>, D>>,
+    {
+        self.solve_with_jacobian(|x| try_jacobian(&residual, x.clone()), x0)
+    }
+
+    /// Solve the system of equations starting from `x0`, where `eval`
+    /// returns the residuals and the Jacobian for given variables.
+    pub fn solve_with_jacobian<F>(
+        &self,
+        eval: F,
+        x0: OVector<f64, D>,
+    ) -> FeosResult<OVector<f64, D>>
+    where
+        F: Fn(&OVector<f64, D>) -> FeosResult<Jacobian<D>>,
+    {
+        let name = self.name;
+        let mut x = x0;
+        let (mut res, mut jac) = eval(&x)?;
+        let mut norm = res.norm();
+        log_iter!(
+            self.verbosity,
+            " iter |    residual    | step length | variables"
+        );
+        log_iter!(self.verbosity, "{:-<60}", "");
+        for i in 1..=self.max_iter {
+            // calculate the Newton step
+            let Some(delta) = self.direction(&x, res, jac) else {
+                report_result(name, i, false);
+                return Err(FeosError::IterationFailed(self.failure_at(&x, i, norm)));
+            };
+
+            // check for convergence
+            if norm < self.tol {
+                x = self.step(&x, &delta, 1.0);
+                self.log(i, norm, 1.0, &x);
+                log_result!(
+                    self.verbosity,
+                    "{}: calculation converged in {} step(s)\n",
+                    name,
+                    i
+                );
+                report_result(name, i, true);
+                return Ok(x);
+            }
+
+            // backtracking line search on the norm of the residuals
+            let mut alpha = 1.0;
+            loop {
+                let x_new = self.step(&x, &delta, alpha);
+                let trial = eval(&x_new);
+                let accept = !self.line_search
+                    || alpha < MIN_STEP_LENGTH
+                    || trial
+                        .as_ref()
+                        .is_ok_and(|(r, _)| r.norm() <= (1.0 - ARMIJO * alpha) * norm);
+                if accept {
+                    (res, jac) = trial?;
+                    x = x_new;
+                    break;
+                }
+                alpha *= 0.5;
+            }
+            self.log(i, norm, alpha, &x);
+            norm = res.norm();
+        }
+        report_result(name, self.max_iter, false);
+        Err(FeosError::NotConverged(self.failure_at(
+            &x,
+            self.max_iter,
+            norm,
+        )))
+    }
+
+    /// Perform a single Newton step from `x`, where `eval` returns the
+    /// residuals and the Jacobian for given variables. Returns the new
+    /// variables and the norm of the residuals at `x`.
+    ///
+    /// The step is limited, damped and projected like the steps of
+    /// [NewtonSolver::solve_with_jacobian], but neither a line search nor
+    /// a convergence check is performed. This allows combining Newton
+    /// steps with other iterations, e.g., successive substitution.
+    pub fn step_with_jacobian<F>(
+        &self,
+        eval: F,
+        x: &OVector<f64, D>,
+    ) -> FeosResult<(OVector<f64, D>, f64)>
+    where
+        F: Fn(&OVector<f64, D>) -> FeosResult<Jacobian<D>>,
+    {
+        let (res, jac) = eval(x)?;
+        let norm = res.norm();
+        let delta = self
+            .direction(x, res, jac)
+            .ok_or_else(|| FeosError::IterationFailed(self.failure_at(x, 1, norm)))?;
+        Ok((self.step(x, &delta, 1.0), norm))
+    }
+
+    /// Limited and damped Newton step, or `None` if the Jacobian is singular.
+    fn direction(
+        &self,
+        x: &OVector<f64, D>,
+        res: OVector<f64, D>,
+        jac: OMatrix<f64, D, D>,
+    ) -> Option<OVector<f64, D>> {
+        let mut delta = jac.lu().solve(&res)?;
+        if let Some(step_limit) = &self.step_limit {
+            step_limit(x, &mut delta);
+        }
+        Some(delta * self.damping)
+    }
+
+    fn step(&self, x: &OVector<f64, D>, delta: &OVector<f64, D>, alpha: f64) -> OVector<f64, D> {
+        let mut x = x - delta * alpha;
+        if let Some(projection) = &self.projection {
+            projection(&mut x);
+        }
+        x
+    }
+
+    fn failure_at(&self, x: &OVector<f64, D>, iterations: usize, residual: f64) -> SolverFailure {
+        let failure = SolverFailure::new(self.name)
+            .iterations(iterations)
+            .residual(residual);
+        match &self.failure {
+            Some(f) => f(x, failure),
+            None => failure,
+        }
+    }
+
+    fn log(&self, iteration: usize, residual: f64, alpha: f64, x: &OVector<f64, D>) {
+        if self.verbosity < Verbosity::Iter && !is_observed() {
+            return;
+        }
+        let values = match &self.report {
+            Some(report) => report(x),
+            None => x.iter().copied().collect(),
+        };
+        log_iter!(
+            self.verbosity,
+            " {:4} | {:14.8e} | {:11.4e} | {}",
+            iteration,
+            residual,
+            alpha,
+            values
+                .iter()
+                .map(|v| format!("{v:14.8e}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        report_iteration(self.name, iteration, residual, &values);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use nalgebra::{DVector, SMatrix, SVector};
+    use num_dual::{DualNum, DualSVec64};
+
+    #[test]
+    fn line_search() -> FeosResult<()> {
+        // the undamped Newton iteration of arctan(x) = 0 diverges for |x0| > 1.39
+        let residual = |x: SVector<DualSVec64<1>, 1>| Ok(x.map(|x| x.atan()));
+        let x0 = SVector::from([2.0]);
+        let solver = NewtonSolver::new("arctan", 50, 1e-12, SolverOptions::default())?;
+        assert!(solver.solve(residual, x0).is_err());
+        let x = solver.line_search(true).solve(residual, x0)?;
+        assert!(x[0].abs() < 1e-12);
+        Ok(())
+    }
+
+    #[test]
+    fn dynamic_size() -> FeosResult<()> {
+        // x_i^2 = i + 1 with x_i > 0
+        let residual = |x: DVector<DualVec64<nalgebra::Dyn>>| {
+            Ok(DVector::from_iterator(
+                x.len(),
+                x.iter().enumerate().map(|(i, x)| x * x - (i + 1) as f64),
+            ))
+        };
+        let x = NewtonSolver::new("squares", 50, 1e-12, SolverOptions::default())?
+            .step_limit(|x: &DVector<f64>, delta: &mut DVector<f64>| {
+                // at most halve the variables in every step
+                let scale = x
+                    .iter()
+                    .zip(delta.iter())
+                    .map(|(&x, &d)| if d > 0.5 * x { 0.5 * x / d } else { 1.0 })
+                    .fold(1.0, f64::min);
+                *delta *= scale;
+            })
+            .projection(|x| x.apply(|x: &mut f64| *x = x.max(1e-10)))
+            .solve(residual, DVector::from_element(3, 10.0))?;
+        assert_relative_eq!(
+            x,
+            DVector::from_vec(vec![1.0, 2f64.sqrt(), 3f64.sqrt()]),
+            max_relative = 1e-12
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn single_step() -> FeosResult<()> {
+        // Newton step for x^2 = 2 from x = 1
+        let eval = |x: &SVector<f64, 1>| {
+            Ok((
+                SVector::from([x[0] * x[0] - 2.0]),
+                SMatrix::from([[2.0 * x[0]]]),
+            ))
+        };
+        let solver = NewtonSolver::new("square root", 50, 1e-12, SolverOptions::default())?;
+        let (x, residual) = solver.step_with_jacobian(eval, &SVector::from([1.0]))?;
+        assert_eq!(x[0], 1.5);
+        assert_eq!(residual, 1.0);
+        let x = solver.solve_with_jacobian(eval, x)?;
+        assert_relative_eq!(x[0], 2f64.sqrt(), max_relative = 1e-14);
+        Ok(())
+    }
+}
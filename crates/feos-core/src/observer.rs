@@ -0,0 +1,179 @@
+//! Observation of the progress of iterative solvers.
+//!
+//! All iterative solvers of the crate (density iteration, flash, bubble
+//! and dew points, pure component phase equilibria, critical points and
+//! phase envelopes) report their iterations to the [SolverObserver] that
+//! is installed for the current thread with [with_observer]. Without an
+//! installed observer, reporting has no effect. Phase envelopes report
+//! every point as one iteration with a NaN residual if the point could
+//! not be calculated.
+//!
+//! Solvers are identified by lowercase names, e.g., `"tp flash"` or
+//! `"bubble/dew point"`, which are also used in the errors of the solvers.
+//!
+//! ```
+//! # use feos_core::{with_observer, Iteration, SolverObserver};
+//! # use std::cell::RefCell;
+//! # use std::rc::Rc;
+//! let residuals = Rc::new(RefCell::new(Vec::new()));
+//! let r = residuals.clone();
+//! let observer = Rc::new(move |it: &Iteration| r.borrow_mut().push(it.residual));
+//! with_observer(observer, || {
+//!     // call solvers here
+//! });
+//! ```
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A single iteration of a solver.
+#[derive(Clone, Copy, Debug)]
+pub struct Iteration<'a> {
+    /// Name of the solver.
+    pub solver: &'static str,
+    /// Iteration count starting at 0.
+    pub iteration: usize,
+    /// Residual (or error measure) of the iteration.
+    pub residual: f64,
+    /// Current values of the iteration variables in SI units.
+    pub variables: &'a [f64],
+}
+
+/// Receives progress reports from the iterative solvers.
+pub trait SolverObserver {
+    /// Called after every iteration of a solver.
+    fn iteration(&self, iteration: &Iteration);
+
+    /// Called when a solver terminates, with the number of iterations
+    /// and whether the iteration converged.
+    fn result(&self, solver: &'static str, iterations: usize, converged: bool) {
+        let _ = (solver, iterations, converged);
+    }
+}
+
+impl<F: Fn(&Iteration)> SolverObserver for F {
+    fn iteration(&self, iteration: &Iteration) {
+        self(iteration)
+    }
+}
+
+thread_local! {
+    static OBSERVER: RefCell<Option<Rc<dyn SolverObserver>>> = const { RefCell::new(None) };
+}
+
+/// Restores the previously installed observer, also during unwinding.
+struct ObserverGuard(Option<Rc<dyn SolverObserver>>);
+
+impl Drop for ObserverGuard {
+    fn drop(&mut self) {
+        OBSERVER.with(|o| *o.borrow_mut() = self.0.take());
+    }
+}
+
+/// Install `observer` for all solvers called in `f` on the current thread.
+///
+/// Calls can be nested, the innermost observer receives the reports.
+/// Solvers running on other threads (e.g., with the `rayon` feature)
+/// are not observed.
+pub fn with_observer<R>(observer: Rc<dyn SolverObserver>, f: impl FnOnce() -> R) -> R {
+    let previous = OBSERVER.with(|o| o.borrow_mut().replace(observer));
+    let _guard = ObserverGuard(previous);
+    f()
+}
+
+fn current() -> Option<Rc<dyn SolverObserver>> {
+    OBSERVER.with(|o| o.borrow().clone())
+}
+
+/// Whether an observer is installed for the current thread.
+///
+/// Solvers use this to skip the evaluation of reported variables that
+/// are not needed for the iteration itself.
+pub(crate) fn is_observed() -> bool {
+    OBSERVER.with(|o| o.borrow().is_some())
+}
+
+/// Report an iteration to the installed observer.
+pub(crate) fn report_iteration(
+    solver: &'static str,
+    iteration: usize,
+    residual: f64,
+    variables: &[f64],
+) {
+    if let Some(observer) = current() {
+        observer.iteration(&Iteration {
+            solver,
+            iteration,
+            residual,
+            variables,
+        });
+    }
+}
+
+/// Report the termination of a solver to the installed observer.
+pub(crate) fn report_result(solver: &'static str, iterations: usize, converged: bool) {
+    if let Some(observer) = current() {
+        observer.result(solver, iterations, converged);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use crate::{FeosResult, PhaseEquilibrium, SolverOptions};
+    use quantity::KELVIN;
+    use std::sync::Arc;
+
+    #[test]
+    fn nested_observers() {
+        let outer = Rc::new(RefCell::new(Vec::new()));
+        let inner = Rc::new(RefCell::new(Vec::new()));
+        let (o, i) = (outer.clone(), inner.clone());
+        with_observer(
+            Rc::new(move |it: &Iteration| o.borrow_mut().push(it.iteration)),
+            || {
+                assert!(is_observed());
+                report_iteration("outer", 0, 1.0, &[]);
+                with_observer(
+                    Rc::new(move |it: &Iteration| i.borrow_mut().push(it.iteration)),
+                    || {
+                        report_iteration("inner", 1, 1.0, &[]);
+                    },
+                );
+                report_iteration("outer", 2, 1.0, &[]);
+            },
+        );
+        assert!(!is_observed());
+        report_iteration("none", 3, 1.0, &[]);
+        assert_eq!(*outer.borrow(), vec![0, 2]);
+        assert_eq!(*inner.borrow(), vec![1]);
+    }
+
+    struct Recorder(RefCell<Vec<(&'static str, usize, bool)>>);
+
+    impl SolverObserver for Recorder {
+        fn iteration(&self, _: &Iteration) {}
+
+        fn result(&self, solver: &'static str, iterations: usize, converged: bool) {
+            self.0.borrow_mut().push((solver, iterations, converged));
+        }
+    }
+
+    #[test]
+    fn observe_vle() -> FeosResult<()> {
+        let parameters =
+            PengRobinsonParameters::new_simple(&[369.96], &[4250000.0], &[0.153], &[44.0962])?;
+        let eos = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let recorder = Rc::new(Recorder(RefCell::new(Vec::new())));
+        with_observer(recorder.clone(), || {
+            PhaseEquilibrium::pure(&eos, 300.0 * KELVIN, None, SolverOptions::default())
+        })?;
+        let results = recorder.0.borrow();
+        let (_, iterations, converged) = results
+            .iter()
+            .rfind(|(solver, _, _)| *solver == "vapor pressure")
+            .unwrap();
+        assert!(*converged && *iterations > 0);
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
-use super::identifier::Identifier;
+use super::identifier::{Identifier, IdentifierOption};
+use super::read_chemical_records;
 use super::segment::SegmentRecord;
 use crate::{FeosError, FeosResult};
 use num_traits::NumAssign;
@@ -6,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     borrow::Cow,
     collections::{HashMap, HashSet},
+    path::Path,
 };
 
 // Auxiliary structure used to deserialize chemical records without explicit bond information.
@@ -103,6 +105,87 @@ impl std::fmt::Display for ChemicalRecord {
     }
 }
 
+/// Segment counts of a substance without information about bonds.
+///
+/// In contrast to [ChemicalRecord], counts can be non-integer, e.g., for
+/// group numbers obtained from correlations or for pseudo components. In
+/// json files, the segments are given either as map from segment identifiers
+/// to counts or, like in chemical records, as list of segment identifiers.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(from = "GroupCountRecordJSON")]
+pub struct GroupCountRecord {
+    pub identifier: Identifier,
+    pub segments: HashMap<String, f64>,
+}
+
+// Auxiliary structure used to deserialize segment counts given as list.
+#[derive(Deserialize)]
+struct GroupCountRecordJSON {
+    identifier: Identifier,
+    segments: SegmentsJSON,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SegmentsJSON {
+    Counts(HashMap<String, f64>),
+    List(Vec<String>),
+}
+
+impl From<GroupCountRecordJSON> for GroupCountRecord {
+    fn from(record: GroupCountRecordJSON) -> Self {
+        let segments = match record.segments {
+            SegmentsJSON::Counts(segments) => segments,
+            SegmentsJSON::List(segments) => {
+                let mut counts = HashMap::with_capacity(segments.len());
+                for s in segments {
+                    *counts.entry(s).or_insert(0.0) += 1.0;
+                }
+                counts
+            }
+        };
+        Self::new(record.identifier, segments)
+    }
+}
+
+impl GroupCountRecord {
+    /// Create a new `GroupCountRecord`.
+    pub fn new(identifier: Identifier, segments: HashMap<String, f64>) -> Self {
+        Self {
+            identifier,
+            segments,
+        }
+    }
+
+    /// Read the records of the given substances from a json file.
+    ///
+    /// The records are returned in the order of `substances`.
+    pub fn from_json<P: AsRef<Path>>(
+        substances: &[&str],
+        file: P,
+        identifier_option: IdentifierOption,
+    ) -> FeosResult<Vec<Self>> {
+        read_chemical_records(substances, file, identifier_option)
+    }
+}
+
+impl From<ChemicalRecord> for GroupCountRecord {
+    fn from(chemical_record: ChemicalRecord) -> Self {
+        let segments = chemical_record.segment_count();
+        Self::new(chemical_record.identifier, segments)
+    }
+}
+
+impl std::fmt::Display for GroupCountRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut segments: Vec<_> = self.segments.iter().collect();
+        segments.sort_by(|a, b| a.0.cmp(b.0));
+        write!(f, "GroupCountRecord(")?;
+        write!(f, "\n\tidentifier={},", self.identifier)?;
+        write!(f, "\n\tsegments={:?}\n)", segments)
+    }
+}
+
 pub trait CountType: Copy {
     fn apply_count(self, x: f64) -> f64;
 }
@@ -148,8 +231,7 @@ pub trait SegmentCount {
         let available = segments.keys().cloned().collect();
         if !queried.is_subset(&available) {
             let missing: Vec<String> = queried.difference(&available).cloned().collect();
-            let msg = format!("{:?}", missing);
-            return Err(FeosError::ComponentsNotFound(msg));
+            return Err(FeosError::ComponentsNotFound(missing));
         };
         Ok(count
             .iter()
@@ -169,3 +251,44 @@ impl SegmentCount for ChemicalRecord {
         Cow::Owned(self.segment_count())
     }
 }
+
+impl SegmentCount for GroupCountRecord {
+    type Count = f64;
+
+    fn identifier(&self) -> Cow<'_, Identifier> {
+        Cow::Borrowed(&self.identifier)
+    }
+
+    fn segment_count(&self) -> Cow<'_, HashMap<String, f64>> {
+        Cow::Borrowed(&self.segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_count_record() -> FeosResult<()> {
+        let json = r#"[
+            {"identifier": {"name": "pseudo"}, "segments": {"CH3": 2.0, "CH2": 3.4}},
+            {"identifier": {"name": "propane"}, "segments": ["CH3", "CH2", "CH3"]}
+        ]"#;
+        let records: Vec<GroupCountRecord> = serde_json::from_str(json)?;
+        assert_eq!(records[0].segment_count()["CH2"], 3.4);
+        assert_eq!(records[1].segments["CH3"], 2.0);
+
+        let chemical_record = ChemicalRecord::new(
+            Identifier::default(),
+            vec!["CH3".into(), "CH2".into(), "CH3".into()],
+            None,
+        );
+        let record = GroupCountRecord::from(chemical_record);
+        assert_eq!(record.segments, records[1].segments);
+
+        let json = serde_json::to_string(&records[0])?;
+        let record: GroupCountRecord = serde_json::from_str(&json)?;
+        assert_eq!(record.segments, records[0].segments);
+        Ok(())
+    }
+}
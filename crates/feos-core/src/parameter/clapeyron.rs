@@ -0,0 +1,427 @@
+//! Import of parameters from the CSV database format of
+//! [Clapeyron.jl](https://github.com/ClapeyronThermo/Clapeyron.jl).
+//!
+//! Clapeyron database files consist of a header line, a title line that
+//! specifies the type of the table (e.g. `PR Like Parameters [csvtype = like]`),
+//! a line with column names and the data. Columns are mapped onto the fields
+//! of the model records by their (lower case) name, and can be renamed to
+//! match the field names of the respective model record.
+use super::{BinaryRecord, BinarySegmentRecord, Identifier, PureRecord, SegmentRecord};
+use crate::errors::{FeosError, FeosResult};
+use indexmap::IndexMap;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// Type of a Clapeyron database table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClapeyronCsvType {
+    /// Pure component (or segment) parameters.
+    Like,
+    /// Binary interaction parameters.
+    Unlike,
+    /// Association parameters between sites.
+    Assoc,
+}
+
+/// A table in the Clapeyron database format.
+#[derive(Clone, Debug)]
+pub struct ClapeyronTable {
+    /// Type of the table.
+    pub csv_type: ClapeyronCsvType,
+    /// Lower case column names.
+    pub columns: Vec<String>,
+    /// Data rows.
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Association parameters between two sites read from a Clapeyron table.
+#[derive(Clone, Debug)]
+pub struct ClapeyronAssociationRecord {
+    pub species1: String,
+    pub site1: String,
+    pub species2: String,
+    pub site2: String,
+    /// All further parameters of the row.
+    pub parameters: IndexMap<String, f64>,
+}
+
+/// Split a line of a CSV file, taking into account quoted cells.
+fn split_line(line: &str) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            c => cell.push(c),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    while cells.last().is_some_and(|c| c.is_empty()) {
+        cells.pop();
+    }
+    cells
+}
+
+impl ClapeyronTable {
+    /// Read a table from a Clapeyron CSV file.
+    pub fn from_file<P: AsRef<Path>>(file: P) -> FeosResult<Self> {
+        Self::from_reader(BufReader::new(File::open(file)?))
+    }
+
+    /// Read a table from a reader that provides Clapeyron CSV data.
+    pub fn from_reader<R: Read>(mut reader: R) -> FeosResult<Self> {
+        let mut csv = String::new();
+        reader.read_to_string(&mut csv)?;
+        Self::from_csv_str(&csv)
+    }
+
+    /// Read a table from a string in the Clapeyron CSV format.
+    pub fn from_csv_str(csv: &str) -> FeosResult<Self> {
+        let mut lines = csv
+            .lines()
+            .filter(|l| !l.trim().trim_matches(',').is_empty());
+        let error = |msg: &str| FeosError::Error(format!("Invalid Clapeyron database file: {msg}"));
+        lines.next().ok_or_else(|| error("empty file."))?;
+        let title = lines.next().ok_or_else(|| error("missing title line."))?;
+        let csv_type = title
+            .split_once("csvtype")
+            .and_then(|(_, t)| t.trim_start().strip_prefix('='))
+            .map(|t| {
+                t.trim_start()
+                    .split(|c: char| !c.is_alphanumeric())
+                    .next()
+                    .unwrap_or_default()
+                    .to_lowercase()
+            })
+            .ok_or_else(|| error("missing csvtype in title line."))?;
+        let csv_type = match csv_type.as_str() {
+            "like" => ClapeyronCsvType::Like,
+            "unlike" => ClapeyronCsvType::Unlike,
+            "assoc" => ClapeyronCsvType::Assoc,
+            t => return Err(error(&format!("unsupported csvtype '{t}'."))),
+        };
+        let columns: Vec<_> = split_line(lines.next().ok_or_else(|| error("missing header."))?)
+            .into_iter()
+            .map(|c| c.to_lowercase())
+            .collect();
+        let rows = lines.map(split_line).collect();
+        Ok(Self {
+            csv_type,
+            columns,
+            rows,
+        })
+    }
+
+    fn check_type(&self, csv_type: ClapeyronCsvType) -> FeosResult<()> {
+        if self.csv_type != csv_type {
+            return Err(FeosError::Error(format!(
+                "Expected a Clapeyron table of type {csv_type:?}, got {:?}.",
+                self.csv_type
+            )));
+        }
+        Ok(())
+    }
+
+    fn column(&self, name: &str) -> FeosResult<usize> {
+        self.columns
+            .iter()
+            .position(|c| c == name)
+            .ok_or_else(|| FeosError::Error(format!("Missing column '{name}'.")))
+    }
+
+    /// Iterate over the rows as maps from (renamed) column names to values,
+    /// skipping empty cells and the columns in `skip`.
+    fn rows<'a>(
+        &'a self,
+        rename: &'a [(&str, &str)],
+        skip: &'a [usize],
+    ) -> impl Iterator<Item = Map<String, Value>> + 'a {
+        self.rows.iter().map(move |row| {
+            let mut map = Map::new();
+            for (i, (column, cell)) in self.columns.iter().zip(row).enumerate() {
+                if skip.contains(&i) || cell.is_empty() {
+                    continue;
+                }
+                let name = rename
+                    .iter()
+                    .find(|(from, _)| from.eq_ignore_ascii_case(column))
+                    .map_or(column.as_str(), |(_, to)| to);
+                let value = cell
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+                    .map_or_else(|| Value::String(cell.clone()), Value::Number);
+                map.insert(name.to_string(), value);
+            }
+            map
+        })
+    }
+
+    /// Read the association parameters of a table of type `assoc`.
+    pub fn association_records(&self) -> FeosResult<Vec<ClapeyronAssociationRecord>> {
+        self.check_type(ClapeyronCsvType::Assoc)?;
+        let ids = [
+            self.column("species1")?,
+            self.column("site1")?,
+            self.column("species2")?,
+            self.column("site2")?,
+        ];
+        self.rows
+            .iter()
+            .zip(self.rows(&[], &ids))
+            .map(|(row, parameters)| {
+                let [species1, site1, species2, site2] =
+                    ids.map(|i| row.get(i).cloned().unwrap_or_default());
+                let parameters = parameters
+                    .into_iter()
+                    .map(|(k, v)| {
+                        v.as_f64().map(|v| (k.clone(), v)).ok_or_else(|| {
+                            FeosError::Error(format!("Non-numeric association parameter '{k}'."))
+                        })
+                    })
+                    .collect::<FeosResult<_>>()?;
+                Ok(ClapeyronAssociationRecord {
+                    species1,
+                    site1,
+                    species2,
+                    site2,
+                    parameters,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Merge the rows of several `like` tables by the species name.
+fn merge_like_tables(
+    tables: &[ClapeyronTable],
+    rename: &[(&str, &str)],
+) -> FeosResult<IndexMap<String, Map<String, Value>>> {
+    let mut records: IndexMap<String, Map<String, Value>> = IndexMap::new();
+    for table in tables {
+        table.check_type(ClapeyronCsvType::Like)?;
+        let species = table.column("species")?;
+        for (row, values) in table.rows.iter().zip(table.rows(rename, &[species])) {
+            records
+                .entry(row.get(species).cloned().unwrap_or_default())
+                .or_default()
+                .extend(values);
+        }
+    }
+    Ok(records)
+}
+
+impl<M: DeserializeOwned> PureRecord<M> {
+    /// Create pure substance records from one or more Clapeyron tables of type `like`.
+    ///
+    /// Tables are merged by the `species` column, which is used as the name
+    /// of the substance. The columns `cas` and `mw` (or `molarmass`) are used
+    /// for the CAS number and the molar weight in g/mol. All other columns are
+    /// passed to the model record, after renaming them according to `rename`,
+    /// e.g., `&[("w", "acentric_factor")]`.
+    pub fn from_clapeyron(
+        tables: &[ClapeyronTable],
+        rename: &[(&str, &str)],
+    ) -> FeosResult<Vec<Self>> {
+        merge_like_tables(tables, rename)?
+            .into_iter()
+            .map(|(name, mut values)| {
+                let cas = values.shift_remove("cas").map(|c| match c {
+                    Value::String(c) => c,
+                    c => c.to_string(),
+                });
+                let molarweight = ["mw", "molarmass"]
+                    .iter()
+                    .find_map(|k| values.shift_remove(*k))
+                    .and_then(|m| m.as_f64())
+                    .unwrap_or_default();
+                let identifier =
+                    Identifier::new(cas.as_deref(), Some(&name), None, None, None, None);
+                let model_record = serde_json::from_value(Value::Object(values))?;
+                Ok(PureRecord::new(identifier, molarweight, model_record))
+            })
+            .collect()
+    }
+}
+
+impl<M: DeserializeOwned> SegmentRecord<M> {
+    /// Create segment records from one or more Clapeyron tables of type `like`.
+    ///
+    /// The `species` column is used as the segment identifier and the column
+    /// `mw` (or `molarmass`) as the molar weight in g/mol. All other columns
+    /// are passed to the model record, after renaming them according to `rename`.
+    pub fn from_clapeyron(
+        tables: &[ClapeyronTable],
+        rename: &[(&str, &str)],
+    ) -> FeosResult<Vec<Self>> {
+        merge_like_tables(tables, rename)?
+            .into_iter()
+            .map(|(identifier, mut values)| {
+                values.shift_remove("cas");
+                let molarweight = ["mw", "molarmass"]
+                    .iter()
+                    .find_map(|k| values.shift_remove(*k))
+                    .and_then(|m| m.as_f64())
+                    .unwrap_or_default();
+                let model_record = serde_json::from_value(Value::Object(values))?;
+                Ok(SegmentRecord::new(identifier, molarweight, model_record))
+            })
+            .collect()
+    }
+}
+
+impl<B: DeserializeOwned> BinaryRecord<B> {
+    /// Create binary records from a Clapeyron table of type `unlike`.
+    ///
+    /// The columns `species1` and `species2` are used as names of the
+    /// substances. All other columns are passed to the model record, after
+    /// renaming them according to `rename`, e.g., `&[("k", "k_ij")]`.
+    pub fn from_clapeyron(
+        table: &ClapeyronTable,
+        rename: &[(&str, &str)],
+    ) -> FeosResult<Vec<Self>> {
+        table.check_type(ClapeyronCsvType::Unlike)?;
+        let ids = [table.column("species1")?, table.column("species2")?];
+        table
+            .rows
+            .iter()
+            .zip(table.rows(rename, &ids))
+            .map(|(row, values)| {
+                let [id1, id2] = ids.map(|i| {
+                    Identifier::new(None, row.get(i).map(String::as_str), None, None, None, None)
+                });
+                let model_record = serde_json::from_value(Value::Object(values))?;
+                Ok(BinaryRecord::new(id1, id2, model_record))
+            })
+            .collect()
+    }
+}
+
+impl BinarySegmentRecord {
+    /// Create segment-segment interaction records from a Clapeyron table of type `unlike`.
+    ///
+    /// The columns `species1` and `species2` are used as segment identifiers
+    /// and `column` (e.g., `k`) as the interaction parameter. Rows without a
+    /// value in `column` are skipped.
+    pub fn from_clapeyron(table: &ClapeyronTable, column: &str) -> FeosResult<Vec<Self>> {
+        table.check_type(ClapeyronCsvType::Unlike)?;
+        let ids = [table.column("species1")?, table.column("species2")?];
+        let k = table.column(&column.to_lowercase())?;
+        table
+            .rows
+            .iter()
+            .filter(|row| row.get(k).is_some_and(|c| !c.is_empty()))
+            .map(|row| {
+                let [id1, id2] = ids.map(|i| row.get(i).cloned().unwrap_or_default());
+                let model_record = row[k].parse().map_err(|_| {
+                    FeosError::Error(format!(
+                        "Non-numeric value '{}' in column '{column}'.",
+                        row[k]
+                    ))
+                })?;
+                Ok(Self::new(id1, id2, model_record))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinsonParameters, PengRobinsonRecord};
+    use crate::parameter::{IdentifierOption, Parameter};
+    use serde::Deserialize;
+
+    const CRITICAL: &str = "Clapeyron Database File,,,,,
+PR Like Parameters [csvtype = like,grouptype = PR]
+species,CAS,Tc,Pc,Vc,w
+methane,74-82-8,190.564,4599200,9.86e-5,0.0115478
+\"n,butane\",106-97-8,425.12,3796000,0.000255,0.200164
+";
+    const MOLARMASS: &str = "Clapeyron Database File,
+Molar Mases Properties [csvtype = like,grouptype = molarmass]
+species,Mw
+methane,16.04
+\"n,butane\",58.12
+";
+    const UNLIKE: &str = "Clapeyron Database File,,
+PR Unlike Parameters [csvtype = unlike,grouptype = PR]
+species1,species2,k
+methane,\"n,butane\",0.0133
+";
+    const ASSOC: &str = "Clapeyron Database File,,,,,
+CPA Assoc Parameters [csvtype = assoc,grouptype = CPA]
+species1,site1,species2,site2,epsilon_assoc,bondvol
+water,e,water,H,16655,0.0692
+";
+
+    #[derive(Deserialize)]
+    struct KijRecord {
+        k_ij: f64,
+    }
+
+    #[test]
+    fn pure_and_binary_records() -> FeosResult<()> {
+        let tables = [
+            ClapeyronTable::from_csv_str(CRITICAL)?,
+            ClapeyronTable::from_csv_str(MOLARMASS)?,
+        ];
+        let records =
+            PureRecord::<PengRobinsonRecord>::from_clapeyron(&tables, &[("w", "acentric_factor")])?;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].identifier.name.as_deref(), Some("n,butane"));
+        assert_eq!(records[1].identifier.cas.as_deref(), Some("106-97-8"));
+        assert_eq!(records[0].molarweight, 16.04);
+
+        let unlike = ClapeyronTable::from_csv_str(UNLIKE)?;
+        let binary = BinaryRecord::<KijRecord>::from_clapeyron(&unlike, &[("k", "k_ij")])?;
+        assert_eq!(binary[0].model_record.k_ij, 0.0133);
+        assert_eq!(binary[0].id2.name.as_deref(), Some("n,butane"));
+        assert!(BinaryRecord::<KijRecord>::from_clapeyron(&unlike, &[]).is_err());
+
+        let k_ij = BinaryRecord::<KijRecord>::from_clapeyron(&unlike, &[("k", "k_ij")])?
+            .into_iter()
+            .map(|r| BinaryRecord::new(r.id1, r.id2, r.model_record.k_ij))
+            .collect::<Vec<_>>();
+        let k_ij = PengRobinsonParameters::binary_matrix_from_records(
+            &records,
+            &k_ij,
+            IdentifierOption::Name,
+        );
+        PengRobinsonParameters::from_records(records, k_ij)?;
+
+        assert!(BinaryRecord::<KijRecord>::from_clapeyron(&tables[0], &[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn binary_segment_records() -> FeosResult<()> {
+        let unlike = "Clapeyron Database File,,,
+GC Unlike Parameters [csvtype = unlike,grouptype = GC]
+species1,species2,k,l
+CH3,OH,0.05,
+CH2,OH,,0.01
+";
+        let table = ClapeyronTable::from_csv_str(unlike)?;
+        let records = BinarySegmentRecord::from_clapeyron(&table, "K")?;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].id2, "OH");
+        assert_eq!(records[0].model_record, 0.05);
+        assert_eq!(BinarySegmentRecord::from_clapeyron(&table, "l")?.len(), 1);
+        assert!(BinarySegmentRecord::from_clapeyron(&table, "m").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn association_records() -> FeosResult<()> {
+        let records = ClapeyronTable::from_csv_str(ASSOC)?.association_records()?;
+        assert_eq!(records[0].site2, "H");
+        assert_eq!(records[0].parameters["bondvol"], 0.0692);
+        Ok(())
+    }
+}
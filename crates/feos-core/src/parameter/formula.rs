@@ -0,0 +1,139 @@
+use super::periodic_table::{molar_weight, parse_formula, parse_smiles};
+use crate::errors::{FeosError, FeosResult};
+use ndarray::Array2;
+use std::fmt;
+use std::str::FromStr;
+
+/// Element composition of a molecule.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Formula {
+    elements: Vec<(&'static str, usize)>,
+}
+
+impl Formula {
+    /// Parse a chemical formula, e.g., "C2H5OH" or "CH3(CH2)4CH3".
+    pub fn new(formula: &str) -> FeosResult<Self> {
+        Ok(Self {
+            elements: parse_formula(formula)?,
+        })
+    }
+
+    /// Determine the element composition from a SMILES string including
+    /// implicit hydrogens.
+    pub fn from_smiles(smiles: &str) -> FeosResult<Self> {
+        Ok(Self {
+            elements: parse_smiles(smiles)?,
+        })
+    }
+
+    /// Number of atoms of the given element.
+    pub fn count(&self, element: &str) -> usize {
+        self.elements
+            .iter()
+            .find(|(e, _)| *e == element)
+            .map_or(0, |&(_, n)| n)
+    }
+
+    /// Iterate over all elements with their number of atoms in Hill order
+    /// (C and H first, then alphabetically).
+    pub fn elements(&self) -> impl Iterator<Item = (&'static str, usize)> + '_ {
+        let mut elements = self.elements.clone();
+        let has_carbon = self.count("C") > 0;
+        elements.sort_by_key(|&(e, _)| match e {
+            "C" if has_carbon => (0, e),
+            "H" if has_carbon => (1, e),
+            _ => (2, e),
+        });
+        elements.into_iter()
+    }
+
+    /// Total number of atoms.
+    pub fn atoms(&self) -> usize {
+        self.elements.iter().map(|(_, n)| n).sum()
+    }
+
+    /// Molar weight in g/mol.
+    pub fn molar_weight(&self) -> f64 {
+        molar_weight(&self.elements)
+    }
+
+    /// Number of atoms of every element (rows) in every formula (columns)
+    /// together with the list of elements, e.g., for element balances.
+    pub fn element_matrix(formulas: &[Self]) -> (Vec<&'static str>, Array2<usize>) {
+        let mut elements: Vec<&'static str> = Vec::new();
+        for formula in formulas {
+            for (e, _) in formula.elements() {
+                if !elements.contains(&e) {
+                    elements.push(e);
+                }
+            }
+        }
+        let matrix = Array2::from_shape_fn((elements.len(), formulas.len()), |(i, j)| {
+            formulas[j].count(elements[i])
+        });
+        (elements, matrix)
+    }
+}
+
+impl FromStr for Formula {
+    type Err = FeosError;
+
+    fn from_str(s: &str) -> FeosResult<Self> {
+        Self::new(s)
+    }
+}
+
+impl fmt::Display for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (e, n) in self.elements() {
+            write!(f, "{e}")?;
+            if n > 1 {
+                write!(f, "{n}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parameter::Identifier;
+    use ndarray::arr2;
+
+    #[test]
+    fn formula() -> FeosResult<()> {
+        let ethanol: Formula = "C2H5OH".parse()?;
+        assert_eq!(ethanol.count("C"), 2);
+        assert_eq!(ethanol.count("H"), 6);
+        assert_eq!(ethanol.count("N"), 0);
+        assert_eq!(ethanol.atoms(), 9);
+        assert_eq!(ethanol.to_string(), "C2H6O");
+        assert_eq!(ethanol, Formula::from_smiles("CCO")?);
+        assert_eq!(Formula::new("CH3(CH2)4CH3")?.to_string(), "C6H14");
+        assert_eq!(Formula::new("OH2")?.to_string(), "H2O");
+
+        let id = Identifier::new(None, None, None, Some("O=C=O"), None, None);
+        assert_eq!(id.chemical_formula().unwrap()?.to_string(), "CO2");
+        assert!(Identifier::default().chemical_formula().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn element_matrix() -> FeosResult<()> {
+        // CH4 + 2 O2 -> CO2 + 2 H2O
+        let formulas = ["CH4", "O2", "CO2", "H2O"]
+            .map(Formula::new)
+            .into_iter()
+            .collect::<FeosResult<Vec<_>>>()?;
+        let (elements, matrix) = Formula::element_matrix(&formulas);
+        assert_eq!(elements, ["C", "H", "O"]);
+        assert_eq!(matrix, arr2(&[[1, 0, 1, 0], [4, 0, 0, 2], [0, 2, 2, 1]]));
+        let nu = [-1, -2, 1, 2];
+        for row in matrix.outer_iter() {
+            let balance: i64 = row.iter().zip(nu).map(|(&a, n)| a as i64 * n).sum();
+            assert_eq!(balance, 0);
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
-use serde::{Deserialize, Serialize};
+use super::Formula;
+use crate::errors::FeosResult;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 
@@ -27,6 +30,28 @@ impl fmt::Display for IdentifierOption {
     }
 }
 
+impl IdentifierOption {
+    /// Bring an identifier of this kind into its canonical form.
+    ///
+    /// Chemical formulas are written in Hill order, e.g., "C2H5OH" becomes
+    /// "C2H6O". All other identifiers, and formulas that can not be parsed,
+    /// are returned unchanged.
+    pub fn normalize<'a>(&self, identifier: &'a str) -> Cow<'a, str> {
+        match self {
+            Self::Formula => Formula::new(identifier)
+                .map_or(Cow::Borrowed(identifier), |f| Cow::Owned(f.to_string())),
+            _ => Cow::Borrowed(identifier),
+        }
+    }
+}
+
+fn deserialize_formula<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    let formula = Option::<String>::deserialize(deserializer)?;
+    Ok(formula.map(|f| IdentifierOption::Formula.normalize(&f).into_owned()))
+}
+
 /// A collection of identifiers for a chemical structure or substance.
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Identifier {
@@ -50,8 +75,8 @@ pub struct Identifier {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub inchi: Option<String>,
-    /// Chemical formula
-    #[serde(default)]
+    /// Chemical formula (in Hill order, if it can be parsed)
+    #[serde(default, deserialize_with = "deserialize_formula")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub formula: Option<String>,
 }
@@ -59,6 +84,8 @@ pub struct Identifier {
 impl Identifier {
     /// Create a new identifier.
     ///
+    /// The chemical formula is converted to Hill order.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -85,7 +112,7 @@ impl Identifier {
             iupac_name: iupac_name.map(Into::into),
             smiles: smiles.map(Into::into),
             inchi: inchi.map(Into::into),
-            formula: formula.map(Into::into),
+            formula: formula.map(|f| IdentifierOption::Formula.normalize(f).into_owned()),
         }
     }
 
@@ -100,6 +127,27 @@ impl Identifier {
         }
     }
 
+    /// Element composition from the chemical formula or, if no valid
+    /// formula is available, from the SMILES.
+    ///
+    /// Returns `None` if neither a formula nor a SMILES is available.
+    pub fn chemical_formula(&self) -> Option<FeosResult<Formula>> {
+        let smiles = self.smiles.as_deref();
+        match self.formula.as_deref() {
+            Some(formula) => Some(Formula::new(formula).or_else(|e| match smiles {
+                Some(smiles) => Formula::from_smiles(smiles),
+                None => Err(e),
+            })),
+            None => smiles.map(Formula::from_smiles),
+        }
+    }
+
+    /// Molar weight in g/mol calculated from the chemical formula or,
+    /// if no valid formula is available, from the SMILES.
+    pub fn molar_weight(&self) -> Option<f64> {
+        Some(self.chemical_formula()?.ok()?.molar_weight())
+    }
+
     // returns the first available identifier in a somewhat arbitrary
     // prioritization. Used for readable outputs.
     pub fn as_readable_str(&self) -> Option<&str> {
@@ -160,4 +208,15 @@ mod test {
         let id = Identifier::new(None, Some("acetone"), None, Some("CC(=O)C"), None, None);
         assert_eq!(id.to_string(), "Identifier(name=acetone, smiles=CC(=O)C)");
     }
+
+    #[test]
+    fn normalize_formula() {
+        let id: Identifier = serde_json::from_str(r#"{"formula": "C2H5OH"}"#).unwrap();
+        assert_eq!(id.as_str(IdentifierOption::Formula), Some("C2H6O"));
+        let id = Identifier::new(None, None, None, None, None, Some("OH2"));
+        assert_eq!(id.formula.as_deref(), Some("H2O"));
+        assert_eq!(IdentifierOption::Formula.normalize("CH3(CH2)2CH3"), "C4H10");
+        assert_eq!(IdentifierOption::Formula.normalize("unknown"), "unknown");
+        assert_eq!(IdentifierOption::Name.normalize("OH2"), "OH2");
+    }
 }
@@ -1,8 +1,10 @@
 //! Structures and traits that can be used to build model parameters for equations of state.
 
+use crate::equation_of_state::check_component_list;
 use crate::errors::*;
-use indexmap::{IndexMap, IndexSet};
+use indexmap::IndexSet;
 use ndarray::Array2;
+use quantity::{KELVIN, PASCAL, Pressure, Temperature};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -12,14 +14,28 @@ use std::io::BufReader;
 use std::path::Path;
 
 mod chemical_record;
+mod clapeyron;
+mod formula;
 mod identifier;
 mod model_record;
+mod periodic_table;
+mod report;
+mod schema;
 mod segment;
+mod validation;
 
-pub use chemical_record::{ChemicalRecord, CountType, SegmentCount};
+pub use chemical_record::{ChemicalRecord, CountType, GroupCountRecord, SegmentCount};
+pub use clapeyron::{ClapeyronAssociationRecord, ClapeyronCsvType, ClapeyronTable};
+pub use formula::Formula;
 pub use identifier::{Identifier, IdentifierOption};
-pub use model_record::{BinaryRecord, FromSegments, FromSegmentsBinary, PureRecord};
+pub use model_record::{
+    BinaryRecord, DuplicatePolicy, FromSegments, FromSegmentsBinary, Metadata, PureRecord,
+};
+pub use report::{ParameterTable, TableFormat};
+use schema::read_records;
+pub use schema::{ParameterFile, ParsingMode, SCHEMA_VERSION};
 pub use segment::{BinarySegmentRecord, SegmentRecord};
+pub use validation::{Severity, ValidationIssue, ValidationReport};
 
 /// Constructor methods for parameters.
 ///
@@ -76,6 +92,66 @@ where
     #[expect(clippy::type_complexity)]
     fn records(&self) -> (&[PureRecord<Self::Pure>], Option<&Array2<Self::Binary>>);
 
+    /// Check the parameters for invalid or implausible values.
+    ///
+    /// The default implementation only checks for identifiers that are
+    /// used by more than one record.
+    fn validate(&self) -> ValidationReport {
+        ValidationReport::from_records(self.records().0)
+    }
+
+    /// Tabulate all pure component parameters and the binary interaction
+    /// parameters in the given format.
+    fn report(&self, format: TableFormat) -> FeosResult<String> {
+        let (pure_records, binary_records) = self.records();
+        let mut tables = vec![ParameterTable::from_pure_records(pure_records)?];
+        if let Some(binary_records) = binary_records {
+            tables.extend(ParameterTable::from_binary_matrix(
+                pure_records,
+                binary_records,
+            )?);
+        }
+        Ok(tables
+            .iter()
+            .map(|t| t.format(format))
+            .collect::<Vec<_>>()
+            .join("\n\n"))
+    }
+
+    /// Check whether the given temperature and pressure lie inside the validity
+    /// ranges declared in the metadata of the pure records.
+    ///
+    /// Returns a warning for every component whose range is violated.
+    fn validity_range_warnings(&self, temperature: Temperature, pressure: Pressure) -> Vec<String> {
+        let (t, p) = (temperature.convert_to(KELVIN), pressure.convert_to(PASCAL));
+        self.records()
+            .0
+            .iter()
+            .filter_map(|r| {
+                let msg = r.metadata.as_ref()?.check_range(t, p)?;
+                let id = r
+                    .identifier
+                    .as_readable_str()
+                    .unwrap_or("unknown component");
+                Some(format!("{id}: {msg}"))
+            })
+            .collect()
+    }
+
+    /// Creates parameters from records and validates them.
+    ///
+    /// Returns `FeosError::InvalidParameters` with the full [ValidationReport]
+    /// if errors were found or, if `strict` is `true`, also for warnings.
+    fn from_records_validated(
+        pure_records: Vec<PureRecord<Self::Pure>>,
+        binary_records: Option<Array2<Self::Binary>>,
+        strict: bool,
+    ) -> FeosResult<Self> {
+        let parameters = Self::from_records(pure_records, binary_records)?;
+        parameters.validate().into_result(strict)?;
+        Ok(parameters)
+    }
+
     /// Helper function to build matrix from list of records in correct order.
     ///
     /// If the identifiers in `binary_records` are not a subset of those in
@@ -138,6 +214,76 @@ where
         Self::from_multiple_json(&[(substances, file_pure)], file_binary, identifier_option)
     }
 
+    /// Creates parameters from substance information stored in json files and
+    /// reports unknown fields and missing molar weights in the records.
+    ///
+    /// In [ParsingMode::Strict], `FeosError::InvalidParameters` is returned if
+    /// any problem is found, otherwise the problems are returned as warnings.
+    fn from_json_with_mode<P>(
+        substances: Vec<&str>,
+        file_pure: P,
+        file_binary: Option<P>,
+        identifier_option: IdentifierOption,
+        mode: ParsingMode,
+    ) -> FeosResult<(Self, ValidationReport)>
+    where
+        P: AsRef<Path>,
+    {
+        let (records, mut report) =
+            PureRecord::from_json_with_mode(&substances, file_pure, identifier_option, mode)?;
+        let binary_records = if let Some(path) = file_binary {
+            let (binary_records, binary_report) = BinaryRecord::from_json_with_mode(path, mode)?;
+            report.issues.extend(binary_report.issues);
+            binary_records
+        } else {
+            Vec::new()
+        };
+        let record_matrix =
+            Self::binary_matrix_from_records(&records, &binary_records, identifier_option);
+        Ok((Self::from_records(records, record_matrix)?, report))
+    }
+
+    /// Creates parameters from substance information stored in json files and
+    /// resolves substances or pairs of substances that are defined by more than
+    /// one record according to `policy`.
+    ///
+    /// With [DuplicatePolicy::ErrorOnDuplicate], `FeosError::InvalidParameters`
+    /// is returned if any duplicates are found.
+    fn from_json_with_policy<P>(
+        substances: Vec<&str>,
+        file_pure: P,
+        file_binary: Option<P>,
+        identifier_option: IdentifierOption,
+        policy: DuplicatePolicy,
+    ) -> FeosResult<(Self, ValidationReport)>
+    where
+        P: AsRef<Path>,
+    {
+        let mut report = ValidationReport::new();
+        let file = serde_json::from_reader(BufReader::new(File::open(file_pure)?))?;
+        let records = PureRecord::select_with_policy(
+            &substances,
+            read_records(file)?,
+            identifier_option,
+            policy,
+            &mut report,
+        )?;
+        let binary_records = match file_binary {
+            Some(path) => BinaryRecord::select_with_policy(
+                BinaryRecord::from_json(path)?,
+                &substances,
+                identifier_option,
+                policy,
+                &mut report,
+            ),
+            None => Vec::new(),
+        };
+        report.clone().into_result(false)?;
+        let record_matrix =
+            Self::binary_matrix_from_records(&records, &binary_records, identifier_option);
+        Ok((Self::from_records(records, record_matrix)?, report))
+    }
+
     /// Creates parameters from substance information stored in multiple json files.
     fn from_multiple_json<P>(
         input: &[(Vec<&str>, P)],
@@ -178,9 +324,7 @@ where
         }
 
         let binary_records = if let Some(path) = file_binary {
-            let file = File::open(path)?;
-            let reader = BufReader::new(file);
-            serde_json::from_reader(reader)?
+            BinaryRecord::from_json(path)?
         } else {
             Vec::new()
         };
@@ -270,38 +414,8 @@ where
         Self::Pure: FromSegments<usize>,
         Self::Binary: FromSegmentsBinary<usize>,
     {
-        let queried: IndexSet<_> = substances.iter().copied().collect();
-
-        let file = File::open(file_pure)?;
-        let reader = BufReader::new(file);
-        let chemical_records: Vec<ChemicalRecord> = serde_json::from_reader(reader)?;
-        let mut record_map: HashMap<_, _> = chemical_records
-            .into_iter()
-            .filter_map(|record| {
-                record
-                    .identifier
-                    .as_str(identifier_option)
-                    .map(|i| i.to_owned())
-                    .map(|i| (i, record))
-            })
-            .collect();
-
-        // Compare queried components and available components
-        let available: IndexSet<_> = record_map
-            .keys()
-            .map(|identifier| identifier as &str)
-            .collect();
-        if !queried.is_subset(&available) {
-            let missing: Vec<_> = queried.difference(&available).cloned().collect();
-            let msg = format!("{:?}", missing);
-            return Err(FeosError::ComponentsNotFound(msg));
-        };
-
-        // collect all pure records that were queried
-        let chemical_records: Vec<_> = queried
-            .into_iter()
-            .filter_map(|identifier| record_map.remove(identifier))
-            .collect();
+        let chemical_records: Vec<ChemicalRecord> =
+            read_chemical_records(substances, file_pure, identifier_option)?;
 
         // Read segment records
         let segment_records: Vec<SegmentRecord<Self::Pure>> =
@@ -309,17 +423,109 @@ where
 
         // Read binary records
         let binary_records = file_binary
-            .map(|file_binary| {
-                let reader = BufReader::new(File::open(file_binary)?);
-                let binary_records: FeosResult<Vec<BinarySegmentRecord>> =
-                    Ok(serde_json::from_reader(reader)?);
-                binary_records
-            })
+            .map(|file_binary| BinarySegmentRecord::from_json(file_binary))
             .transpose()?;
 
         Self::from_segments(chemical_records, segment_records, binary_records)
     }
 
+    /// Creates parameters from (possibly non-integer) segment counts and
+    /// segment information stored in json files.
+    ///
+    /// The substances are read as [GroupCountRecord]s, i.e., the segments are
+    /// either given as map from segment identifiers to counts or as list.
+    fn from_json_group_counts<P>(
+        substances: &[&str],
+        file_pure: P,
+        file_segments: P,
+        file_binary: Option<P>,
+        identifier_option: IdentifierOption,
+    ) -> FeosResult<Self>
+    where
+        P: AsRef<Path>,
+        Self::Pure: FromSegments<f64>,
+        Self::Binary: FromSegmentsBinary<f64>,
+    {
+        let group_count_records: Vec<GroupCountRecord> =
+            read_chemical_records(substances, file_pure, identifier_option)?;
+        let segment_records = SegmentRecord::from_json(file_segments)?;
+        let binary_records = file_binary
+            .map(|file_binary| BinarySegmentRecord::from_json(file_binary))
+            .transpose()?;
+        Self::from_segments(group_count_records, segment_records, binary_records)
+    }
+
+    /// Return a new parameter set with an additional component, reusing the
+    /// records of this parameter set.
+    ///
+    /// `binary_records` contains the binary interaction parameters of the new
+    /// component with each of the existing components. If it is empty, the
+    /// default binary parameters are used.
+    fn with_component(
+        &self,
+        pure_record: PureRecord<Self::Pure>,
+        binary_records: &[Self::Binary],
+    ) -> FeosResult<Self> {
+        let (pure_records, binary_matrix) = self.records();
+        let n = pure_records.len();
+        if !binary_records.is_empty() && binary_records.len() != n {
+            return Err(FeosError::IncompatibleComponents(n, binary_records.len()));
+        }
+        let binary_matrix = if binary_matrix.is_none() && binary_records.is_empty() {
+            None
+        } else {
+            Some(Array2::from_shape_fn([n + 1, n + 1], |(i, j)| {
+                if i < n && j < n {
+                    binary_matrix.map(|b| b[[i, j]].clone()).unwrap_or_default()
+                } else if i == j {
+                    Self::Binary::default()
+                } else {
+                    binary_records.get(i.min(j)).cloned().unwrap_or_default()
+                }
+            }))
+        };
+        let mut pure_records = pure_records.to_vec();
+        pure_records.push(pure_record);
+        Self::from_records(pure_records, binary_matrix)
+    }
+
+    /// Return the indices of the given components in the parameter set.
+    fn component_indices(
+        &self,
+        components: &[&str],
+        identifier_option: IdentifierOption,
+    ) -> FeosResult<Vec<usize>> {
+        let pure_records = self.records().0;
+        let mut missing = Vec::new();
+        let indices = components
+            .iter()
+            .filter_map(|&c| {
+                let id = identifier_option.normalize(c);
+                let index = pure_records
+                    .iter()
+                    .position(|r| r.identifier.as_str(identifier_option) == Some(&id));
+                if index.is_none() {
+                    missing.push(c.to_string());
+                }
+                index
+            })
+            .collect();
+        if !missing.is_empty() {
+            return Err(FeosError::ComponentsNotFound(missing));
+        }
+        Ok(indices)
+    }
+
+    /// Return a parameter set containing the given components (in this order),
+    /// reusing the records of this parameter set.
+    fn subset_by_identifier(
+        &self,
+        components: &[&str],
+        identifier_option: IdentifierOption,
+    ) -> FeosResult<Self> {
+        self.try_subset(&self.component_indices(components, identifier_option)?)
+    }
+
     /// Return a parameter set containing the subset of components specified in `component_list`.
     ///
     /// # Panics
@@ -327,21 +533,86 @@ where
     /// Panics if index in `component_list` is out of bounds or if
     /// [Parameter::from_records] fails.
     fn subset(&self, component_list: &[usize]) -> Self {
-        let (pure_records, binary_records) = self.records();
-        let pure_records = component_list
-            .iter()
-            .map(|&i| pure_records[i].clone())
-            .collect();
-        let n = component_list.len();
-        let binary_records = binary_records.map(|br| {
-            Array2::from_shape_fn([n, n], |(i, j)| {
-                br[(component_list[i], component_list[j])].clone()
-            })
-        });
-
+        let (pure_records, binary_records) = subset_records(self.records(), component_list);
         Self::from_records(pure_records, binary_records)
             .expect("failed to create subset from parameters.")
     }
+
+    /// Return a parameter set containing the subset of components specified in
+    /// `component_list` (in this order).
+    ///
+    /// In contrast to [Parameter::subset], an error is returned if the list is
+    /// empty, contains duplicates or indices that are out of bounds.
+    fn try_subset(&self, component_list: &[usize]) -> FeosResult<Self> {
+        check_component_list(component_list, self.records().0.len())?;
+        let (pure_records, binary_records) = subset_records(self.records(), component_list);
+        Self::from_records(pure_records, binary_records)
+    }
+}
+
+/// Select the pure records and the binary records of the given components.
+fn subset_records<P: Clone, B: Clone>(
+    (pure_records, binary_records): (&[PureRecord<P>], Option<&Array2<B>>),
+    component_list: &[usize],
+) -> (Vec<PureRecord<P>>, Option<Array2<B>>) {
+    let pure_records = component_list
+        .iter()
+        .map(|&i| pure_records[i].clone())
+        .collect();
+    let n = component_list.len();
+    let binary_records = binary_records.map(|br| {
+        Array2::from_shape_fn([n, n], |(i, j)| {
+            br[(component_list[i], component_list[j])].clone()
+        })
+    });
+    (pure_records, binary_records)
+}
+
+/// Read the chemical records of the queried substances from a json file
+/// in the order of `substances`.
+fn read_chemical_records<C, P>(
+    substances: &[&str],
+    file: P,
+    identifier_option: IdentifierOption,
+) -> FeosResult<Vec<C>>
+where
+    C: SegmentCount + DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let queried: IndexSet<_> = substances
+        .iter()
+        .map(|s| identifier_option.normalize(s).into_owned())
+        .collect();
+
+    let file = File::open(file)?;
+    let reader = BufReader::new(file);
+    let chemical_records: Vec<C> = serde_json::from_reader(reader)?;
+    let mut record_map: HashMap<_, _> = chemical_records
+        .into_iter()
+        .filter_map(|record| {
+            record
+                .identifier()
+                .as_str(identifier_option)
+                .map(|i| i.to_owned())
+                .map(|i| (i, record))
+        })
+        .collect();
+
+    // Compare queried components and available components
+    let missing: Vec<_> = queried
+        .iter()
+        .filter(|&id| !record_map.contains_key(id))
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        return Err(FeosError::ComponentsNotFound(missing));
+    };
+
+    // collect all pure records that were queried
+    Ok(queried
+        .iter()
+        .filter_map(|identifier| record_map.remove(identifier))
+        .collect())
 }
 
 /// Dummy struct used for models that do not use binary interaction parameters.
@@ -394,39 +665,8 @@ pub trait ParameterHetero: Sized {
         P: AsRef<Path>,
         ChemicalRecord: Into<Self::Chemical>,
     {
-        let queried: IndexSet<_> = substances
-            .iter()
-            .map(|identifier| identifier as &str)
-            .collect();
-
-        let reader = BufReader::new(File::open(file_pure)?);
-        let chemical_records: Vec<ChemicalRecord> = serde_json::from_reader(reader)?;
-        let mut record_map: IndexMap<_, _> = chemical_records
-            .into_iter()
-            .filter_map(|record| {
-                record
-                    .identifier
-                    .as_str(identifier_option)
-                    .map(|i| i.to_owned())
-                    .map(|i| (i, record))
-            })
-            .collect();
-
-        // Compare queried components and available components
-        let available: IndexSet<_> = record_map
-            .keys()
-            .map(|identifier| identifier as &str)
-            .collect();
-        if !queried.is_subset(&available) {
-            let missing: Vec<_> = queried.difference(&available).cloned().collect();
-            return Err(FeosError::ComponentsNotFound(format!("{:?}", missing)));
-        };
-
-        // Collect all pure records that were queried
-        let chemical_records: Vec<_> = queried
-            .into_iter()
-            .filter_map(|identifier| record_map.shift_remove(identifier))
-            .collect();
+        let chemical_records: Vec<ChemicalRecord> =
+            read_chemical_records(substances, file_pure, identifier_option)?;
 
         // Read segment records
         let segment_records: Vec<SegmentRecord<Self::Pure>> =
@@ -434,12 +674,7 @@ pub trait ParameterHetero: Sized {
 
         // Read binary records
         let binary_records = file_binary
-            .map(|file_binary| {
-                let reader = BufReader::new(File::open(file_binary)?);
-                let binary_records: FeosResult<Vec<BinarySegmentRecord>> =
-                    Ok(serde_json::from_reader(reader)?);
-                binary_records
-            })
+            .map(|file_binary| BinarySegmentRecord::from_json(file_binary))
             .transpose()?;
 
         Self::from_segments(chemical_records, segment_records, binary_records)
@@ -1,16 +1,135 @@
-use super::IdentifierOption;
 use super::chemical_record::CountType;
 use super::identifier::Identifier;
+use super::schema::{ParsingMode, read_records, read_records_checked};
 use super::segment::SegmentRecord;
+use super::{IdentifierOption, ValidationReport};
 use crate::FeosResult;
 use crate::errors::FeosError;
+use indexmap::IndexMap;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, Read};
 use std::path::Path;
 
+/// Provenance of a parameter record.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    /// DOI of the publication the parameters are taken from
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    /// Date of the parameter fit (ISO 8601)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fit_date: Option<String>,
+    /// Temperature range of the experimental data in Kelvin
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature_range: Option<[f64; 2]>,
+    /// Pressure range of the experimental data in Pascal
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure_range: Option<[f64; 2]>,
+    /// Free text comment
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Whether the parameters are estimated from correlations instead of fitted to data
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub estimated: bool,
+}
+
+impl Metadata {
+    /// Describe why the given temperature (in K) and pressure (in Pa) lie outside
+    /// of the declared validity range, if they do.
+    pub fn check_range(&self, temperature: f64, pressure: f64) -> Option<String> {
+        let mut violations = Vec::new();
+        if let Some([t_min, t_max]) = self.temperature_range
+            && (temperature < t_min || temperature > t_max)
+        {
+            violations.push(format!(
+                "T = {temperature} K is outside of [{t_min}, {t_max}] K"
+            ));
+        }
+        if let Some([p_min, p_max]) = self.pressure_range
+            && (pressure < p_min || pressure > p_max)
+        {
+            violations.push(format!(
+                "p = {pressure} Pa is outside of [{p_min}, {p_max}] Pa"
+            ));
+        }
+        (!violations.is_empty()).then(|| violations.join(", "))
+    }
+}
+
+impl std::fmt::Display for Metadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut tokens = Vec::new();
+        if let Some(doi) = &self.doi {
+            tokens.push(format!("doi={doi}"));
+        }
+        if let Some(fit_date) = &self.fit_date {
+            tokens.push(format!("fit_date={fit_date}"));
+        }
+        if let Some(t) = &self.temperature_range {
+            tokens.push(format!("temperature_range={t:?}"));
+        }
+        if let Some(p) = &self.pressure_range {
+            tokens.push(format!("pressure_range={p:?}"));
+        }
+        if let Some(comment) = &self.comment {
+            tokens.push(format!("comment={comment}"));
+        }
+        if self.estimated {
+            tokens.push("estimated".into());
+        }
+        write!(f, "Metadata({})", tokens.join(", "))
+    }
+}
+
+/// Resolution of substances (or pairs of substances) that are defined by
+/// more than one record in a parameter file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Return an error.
+    ErrorOnDuplicate,
+    /// Use the first record.
+    #[default]
+    FirstWins,
+    /// Use the last record.
+    LastWins,
+    /// Use the first record and report a warning.
+    WarnAndFirstWins,
+}
+
+impl DuplicatePolicy {
+    /// Pick one of the (non-empty list of) records and report duplicates.
+    fn select<R>(
+        self,
+        records: &mut Vec<R>,
+        report: &mut ValidationReport,
+        components: Vec<usize>,
+        message: String,
+    ) -> R {
+        if records.len() > 1 {
+            match self {
+                Self::ErrorOnDuplicate => report.error(components, message),
+                Self::WarnAndFirstWins => {
+                    report.warning(components, format!("{message}, the first one is used"))
+                }
+                Self::FirstWins | Self::LastWins => (),
+            }
+        }
+        match self {
+            Self::LastWins => records.pop().unwrap(),
+            _ => records.swap_remove(0),
+        }
+    }
+}
+
 /// A collection of parameters of a pure substance.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PureRecord<M> {
@@ -18,6 +137,14 @@ pub struct PureRecord<M> {
     #[serde(default)]
     pub molarweight: f64,
     pub model_record: M,
+    /// Provenance of the parameters
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
+    /// Superior molar enthalpy of combustion at 15 °C in kJ/mol
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enthalpy_of_combustion: Option<f64>,
 }
 
 impl<M> PureRecord<M> {
@@ -27,9 +154,40 @@ impl<M> PureRecord<M> {
             identifier,
             molarweight,
             model_record,
+            metadata: None,
+            enthalpy_of_combustion: None,
         }
     }
 
+    /// Attach provenance information to the record.
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Convert the model record and keep all other information.
+    pub fn map_model_record<N, F: FnOnce(M) -> N>(self, f: F) -> PureRecord<N> {
+        PureRecord {
+            identifier: self.identifier,
+            molarweight: self.molarweight,
+            model_record: f(self.model_record),
+            metadata: self.metadata,
+            enthalpy_of_combustion: self.enthalpy_of_combustion,
+        }
+    }
+
+    /// Attach the superior molar enthalpy of combustion at 15 °C in kJ/mol,
+    /// e.g., from ISO 6976, to the record.
+    pub fn with_enthalpy_of_combustion(mut self, enthalpy_of_combustion: f64) -> Self {
+        self.enthalpy_of_combustion = Some(enthalpy_of_combustion);
+        self
+    }
+
+    /// Return the provenance information of the record, if available.
+    pub fn metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
     /// Update the `PureRecord` from segment counts.
     ///
     /// The [FromSegments] trait needs to be implemented for both the model record
@@ -61,8 +219,143 @@ impl<M> PureRecord<M> {
         P: AsRef<Path>,
         M: Clone + DeserializeOwned,
     {
+        Self::from_reader(
+            substances,
+            BufReader::new(File::open(file)?),
+            identifier_option,
+        )
+    }
+
+    /// Create pure substance parameters from a json file and report unknown
+    /// fields and missing molar weights of the selected records.
+    ///
+    /// In [ParsingMode::Strict], `FeosError::InvalidParameters` is returned if
+    /// any problem is found.
+    pub fn from_json_with_mode<P>(
+        substances: &[&str],
+        file: P,
+        identifier_option: IdentifierOption,
+        mode: ParsingMode,
+    ) -> FeosResult<(Vec<Self>, ValidationReport)>
+    where
+        P: AsRef<Path>,
+        M: Clone + DeserializeOwned + Serialize,
+    {
+        let file = serde_json::from_reader(BufReader::new(File::open(file)?))?;
+        let (file_records, issues): (Vec<_>, Vec<_>) =
+            read_records_checked::<Self>(file)?.into_iter().unzip();
+        let mut report = ValidationReport::new();
+        for (i, &substance) in substances.iter().enumerate() {
+            let id = identifier_option.normalize(substance);
+            let selected = file_records
+                .iter()
+                .position(|r| r.identifier.as_str(identifier_option) == Some(&id));
+            for issue in selected.into_iter().flat_map(|k| &issues[k]) {
+                mode.report(&mut report, vec![i], format!("{substance}: {issue}"));
+            }
+        }
+        let records = Self::select(substances, file_records, identifier_option)?;
+        report.clone().into_result(false)?;
+        Ok((records, report))
+    }
+
+    /// Create pure substance parameters from a json string.
+    pub fn from_json_str(
+        substances: &[&str],
+        json: &str,
+        identifier_option: IdentifierOption,
+    ) -> FeosResult<Vec<Self>>
+    where
+        M: Clone + DeserializeOwned,
+    {
+        Self::select(
+            substances,
+            read_records(serde_json::from_str(json)?)?,
+            identifier_option,
+        )
+    }
+
+    /// Create pure substance parameters from a reader that provides json data.
+    pub fn from_reader<R: Read>(
+        substances: &[&str],
+        reader: R,
+        identifier_option: IdentifierOption,
+    ) -> FeosResult<Vec<Self>>
+    where
+        M: Clone + DeserializeOwned,
+    {
+        // use stream in the future
+        Self::select(
+            substances,
+            read_records(serde_json::from_reader(reader)?)?,
+            identifier_option,
+        )
+    }
+
+    /// Create pure substance parameters from a json file and resolve
+    /// substances that are defined by more than one record according to `policy`.
+    ///
+    /// With [DuplicatePolicy::ErrorOnDuplicate], `FeosError::InvalidParameters`
+    /// is returned if a substance is defined more than once.
+    pub fn from_json_with_policy<P>(
+        substances: &[&str],
+        file: P,
+        identifier_option: IdentifierOption,
+        policy: DuplicatePolicy,
+    ) -> FeosResult<(Vec<Self>, ValidationReport)>
+    where
+        P: AsRef<Path>,
+        M: Clone + DeserializeOwned,
+    {
+        let file_records =
+            read_records(serde_json::from_reader(BufReader::new(File::open(file)?))?)?;
+        let mut report = ValidationReport::new();
+        let records = Self::select_with_policy(
+            substances,
+            file_records,
+            identifier_option,
+            policy,
+            &mut report,
+        )?;
+        report.clone().into_result(false)?;
+        Ok((records, report))
+    }
+
+    /// Select the records of the given substances from a list of records.
+    ///
+    /// If a substance is defined more than once, the first record is used.
+    fn select(
+        substances: &[&str],
+        file_records: Vec<Self>,
+        identifier_option: IdentifierOption,
+    ) -> FeosResult<Vec<Self>>
+    where
+        M: Clone,
+    {
+        Self::select_with_policy(
+            substances,
+            file_records,
+            identifier_option,
+            DuplicatePolicy::FirstWins,
+            &mut ValidationReport::new(),
+        )
+    }
+
+    /// Select the records of the given substances from a list of records
+    /// and add substances that are defined more than once to `report`.
+    pub(crate) fn select_with_policy(
+        substances: &[&str],
+        file_records: Vec<Self>,
+        identifier_option: IdentifierOption,
+        policy: DuplicatePolicy,
+        report: &mut ValidationReport,
+    ) -> FeosResult<Vec<Self>> {
         // create list of substances
-        let mut queried: HashSet<String> = substances.iter().map(|s| s.to_string()).collect();
+        let substances: Vec<_> = substances
+            .iter()
+            .map(|s| identifier_option.normalize(s))
+            .collect();
+        let queried: HashSet<&str> = substances.iter().map(|s| s.as_ref()).collect();
         // raise error on duplicate detection
         if queried.len() != substances.len() {
             return Err(FeosError::IncompatibleParameters(
@@ -70,32 +363,35 @@ impl<M> PureRecord<M> {
             ));
         }
 
-        let f = File::open(file)?;
-        let reader = BufReader::new(f);
-        // use stream in the future
-        let file_records: Vec<Self> = serde_json::from_reader(reader)?;
-        let mut records: HashMap<String, Self> = HashMap::with_capacity(substances.len());
-
-        // build map, draining list of queried substances in the process
+        // collect all records of every queried substance
+        let mut records: HashMap<String, Vec<Self>> = HashMap::with_capacity(substances.len());
         for record in file_records {
-            if let Some(id) = record.identifier.as_str(identifier_option) {
-                queried.take(id).map(|id| records.insert(id, record));
-            }
-            // all parameters parsed
-            if queried.is_empty() {
-                break;
+            if let Some(id) = record.identifier.as_str(identifier_option)
+                && queried.contains(id)
+            {
+                records.entry(id.to_owned()).or_default().push(record);
             }
         }
 
         // report missing parameters
-        if !queried.is_empty() {
-            return Err(FeosError::ComponentsNotFound(format!("{:?}", queried)));
+        let missing: Vec<_> = substances
+            .iter()
+            .filter(|&s| !records.contains_key(s.as_ref()))
+            .map(|s| s.to_string())
+            .collect();
+        if !missing.is_empty() {
+            return Err(FeosError::ComponentsNotFound(missing));
         };
 
         // collect into vec in correct order
         Ok(substances
             .iter()
-            .map(|&s| records.get(s).unwrap().clone())
+            .enumerate()
+            .map(|(i, s)| {
+                let mut records = records.remove(s.as_ref()).unwrap();
+                let message = format!("{s} is defined by {} records", records.len());
+                policy.select(&mut records, report, vec![i], message)
+            })
             .collect())
     }
 }
@@ -109,6 +405,12 @@ where
         write!(f, "\n\tidentifier={},", self.identifier)?;
         write!(f, "\n\tmolarweight={},", self.molarweight)?;
         write!(f, "\n\tmodel_record={},", self.model_record)?;
+        if let Some(metadata) = &self.metadata {
+            write!(f, "\n\tmetadata={},", metadata)?;
+        }
+        if let Some(h) = self.enthalpy_of_combustion {
+            write!(f, "\n\tenthalpy_of_combustion={},", h)?;
+        }
         write!(f, "\n)")
     }
 }
@@ -129,6 +431,19 @@ pub trait FromSegmentsBinary<T>: Clone {
     fn from_segments_binary(segments: &[(f64, T, T)]) -> FeosResult<Self>;
 }
 
+/// The binary interaction parameter of two components is the average of the
+/// segment-segment interaction parameters weighted by the products of the
+/// segment counts.
+impl<T: CountType> FromSegmentsBinary<T> for f64 {
+    fn from_segments_binary(segments: &[(f64, T, T)]) -> FeosResult<Self> {
+        let (k_ij, n) = segments.iter().fold((0.0, 0.0), |(k_ij, n), (br, n1, n2)| {
+            let nab = n1.apply_count(1.0) * n2.apply_count(1.0);
+            (k_ij + br * nab, n + nab)
+        });
+        Ok(k_ij / n)
+    }
+}
+
 /// A collection of parameters that model interactions between two substances.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BinaryRecord<B> {
@@ -138,6 +453,10 @@ pub struct BinaryRecord<B> {
     pub id2: Identifier,
     /// Binary interaction parameter(s)
     pub model_record: B,
+    /// Provenance of the parameters
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
 }
 
 impl<B> BinaryRecord<B> {
@@ -147,15 +466,110 @@ impl<B> BinaryRecord<B> {
             id1,
             id2,
             model_record,
+            metadata: None,
         }
     }
 
+    /// Attach provenance information to the record.
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Return the provenance information of the record, if available.
+    pub fn metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
     /// Read a list of `BinaryRecord`s from a JSON file.
     pub fn from_json<P: AsRef<Path>>(file: P) -> FeosResult<Vec<Self>>
     where
         B: DeserializeOwned,
     {
-        Ok(serde_json::from_reader(BufReader::new(File::open(file)?))?)
+        read_records(serde_json::from_reader(BufReader::new(File::open(file)?))?)
+    }
+
+    /// Read a list of `BinaryRecord`s from a JSON file and report unknown fields.
+    ///
+    /// In [ParsingMode::Strict], `FeosError::InvalidParameters` is returned if
+    /// any unknown field is found.
+    pub fn from_json_with_mode<P: AsRef<Path>>(
+        file: P,
+        mode: ParsingMode,
+    ) -> FeosResult<(Vec<Self>, ValidationReport)>
+    where
+        B: DeserializeOwned + Serialize,
+    {
+        let file = serde_json::from_reader(BufReader::new(File::open(file)?))?;
+        let mut report = ValidationReport::new();
+        let mut records = Vec::new();
+        for (i, (record, issues)) in read_records_checked::<Self>(file)?.into_iter().enumerate() {
+            for issue in issues {
+                mode.report(&mut report, vec![], format!("binary record {i}: {issue}"));
+            }
+            records.push(record);
+        }
+        report.clone().into_result(false)?;
+        Ok((records, report))
+    }
+
+    /// Select the binary records between the given substances and resolve
+    /// pairs of substances that are defined by more than one record according
+    /// to `policy`.
+    ///
+    /// Problems are added to `report` using the indices of the substances in
+    /// `substances`.
+    pub fn select_with_policy(
+        binary_records: Vec<Self>,
+        substances: &[&str],
+        identifier_option: IdentifierOption,
+        policy: DuplicatePolicy,
+        report: &mut ValidationReport,
+    ) -> Vec<Self> {
+        let substances: Vec<_> = substances
+            .iter()
+            .map(|s| identifier_option.normalize(s))
+            .collect();
+        let index = |id: &Identifier| {
+            let id = id.as_str(identifier_option)?;
+            substances.iter().position(|s| s == id)
+        };
+        let mut pairs: IndexMap<[usize; 2], Vec<Self>> = IndexMap::new();
+        for record in binary_records {
+            if let (Some(i), Some(j)) = (index(&record.id1), index(&record.id2))
+                && i != j
+            {
+                pairs.entry([i.min(j), i.max(j)]).or_default().push(record);
+            }
+        }
+        pairs
+            .into_iter()
+            .map(|([i, j], mut records)| {
+                let message = format!(
+                    "{}-{} is defined by {} binary records",
+                    substances[i],
+                    substances[j],
+                    records.len()
+                );
+                policy.select(&mut records, report, vec![i, j], message)
+            })
+            .collect()
+    }
+
+    /// Read a list of `BinaryRecord`s from a JSON string.
+    pub fn from_json_str(json: &str) -> FeosResult<Vec<Self>>
+    where
+        B: DeserializeOwned,
+    {
+        read_records(serde_json::from_str(json)?)
+    }
+
+    /// Read a list of `BinaryRecord`s from a reader that provides JSON data.
+    pub fn from_reader<R: Read>(reader: R) -> FeosResult<Vec<Self>>
+    where
+        B: DeserializeOwned,
+    {
+        read_records(serde_json::from_reader(reader)?)
     }
 }
 
@@ -165,6 +579,9 @@ impl<B: std::fmt::Display> std::fmt::Display for BinaryRecord<B> {
         write!(f, "\n\tid1={},", self.id1)?;
         write!(f, "\n\tid2={},", self.id2)?;
         write!(f, "\n\tmodel_record={},", self.model_record)?;
+        if let Some(metadata) = &self.metadata {
+            write!(f, "\n\tmetadata={},", metadata)?;
+        }
         write!(f, "\n)")
     }
 }
@@ -172,6 +589,7 @@ impl<B: std::fmt::Display> std::fmt::Display for BinaryRecord<B> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use approx::assert_relative_eq;
 
     #[derive(Serialize, Deserialize, Debug, Default, Clone)]
     struct TestModelRecordSegments {
@@ -224,4 +642,133 @@ mod test {
         assert_eq!(records[0].identifier.cas, Some("1".into()));
         assert_eq!(records[1].identifier.cas, Some("2".into()))
     }
+
+    #[test]
+    fn from_json_str() {
+        let r = r#"
+        [
+            {
+                "identifier": {
+                    "cas": "1"
+                },
+                "molarweight": 1.0,
+                "model_record": {
+                    "a": 1.0
+                }
+            },
+            {
+                "identifier": {
+                    "cas": "2"
+                },
+                "molarweight": 2.0,
+                "model_record": {
+                    "a": 2.0
+                }
+            }
+        ]"#;
+        let records = PureRecord::<TestModelRecordSegments>::from_json_str(
+            &["2", "1"],
+            r,
+            IdentifierOption::Cas,
+        )
+        .unwrap();
+        assert_eq!(records[0].identifier.cas, Some("2".into()));
+        assert_eq!(records[1].model_record.a, 1.0);
+        let records = PureRecord::<TestModelRecordSegments>::from_reader(
+            &["1"],
+            r.as_bytes(),
+            IdentifierOption::Cas,
+        )
+        .unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(
+            PureRecord::<TestModelRecordSegments>::from_json_str(&["3"], r, IdentifierOption::Cas)
+                .is_err()
+        );
+
+        let r = r#"[{"id1": {"cas": "1"}, "id2": {"cas": "2"}, "model_record": 0.5}]"#;
+        let records = BinaryRecord::<f64>::from_json_str(r).unwrap();
+        assert_eq!(records[0].model_record, 0.5);
+    }
+
+    #[test]
+    fn select_by_formula() -> FeosResult<()> {
+        let r = r#"[
+            {"identifier": {"name": "water", "formula": "OH2"}, "model_record": {"a": 1.0}},
+            {"identifier": {"name": "ethanol", "formula": "C2H6O"}, "model_record": {"a": 2.0}}
+        ]"#;
+        let records = PureRecord::<TestModelRecordSegments>::from_json_str(
+            &["C2H5OH", "H2O"],
+            r,
+            IdentifierOption::Formula,
+        )?;
+        assert_eq!(records[0].identifier.name.as_deref(), Some("ethanol"));
+        assert_eq!(records[1].identifier.formula.as_deref(), Some("H2O"));
+        Ok(())
+    }
+
+    #[test]
+    fn from_segments_binary() -> FeosResult<()> {
+        let k_ij = f64::from_segments_binary(&[(0.1, 2, 1), (0.4, 1, 1)])?;
+        assert_relative_eq!(k_ij, 0.2);
+        let k_ij = f64::from_segments_binary(&[(0.1, 0.5, 1.0), (0.4, 1.5, 1.0)])?;
+        assert_relative_eq!(k_ij, 0.325);
+        Ok(())
+    }
+
+    #[test]
+    fn metadata() {
+        let r = r#"
+        {
+            "identifier": {
+                "cas": "1"
+            },
+            "molarweight": 1.0,
+            "model_record": {
+                "a": 1.0
+            },
+            "metadata": {
+                "doi": "10.1000/xyz",
+                "temperature_range": [200.0, 400.0]
+            }
+        }
+        "#;
+        let record: PureRecord<TestModelRecordSegments> = serde_json::from_str(r).unwrap();
+        let metadata = record.metadata().unwrap();
+        assert_eq!(metadata.doi, Some("10.1000/xyz".into()));
+        assert!(metadata.check_range(300.0, 1e5).is_none());
+        assert!(metadata.check_range(500.0, 1e5).is_some());
+
+        let json = serde_json::to_string(&record).unwrap();
+        let record: PureRecord<TestModelRecordSegments> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            record.metadata().unwrap().temperature_range,
+            Some([200.0, 400.0])
+        );
+        let record = PureRecord::new(Identifier::default(), 1.0, ());
+        assert!(record.metadata().is_none());
+        assert!(!serde_json::to_string(&record).unwrap().contains("metadata"));
+    }
+
+    #[test]
+    fn enthalpy_of_combustion() {
+        let r = r#"
+        {
+            "identifier": {
+                "cas": "74-82-8"
+            },
+            "molarweight": 16.0428,
+            "model_record": {
+                "a": 1.0
+            },
+            "enthalpy_of_combustion": 891.51
+        }
+        "#;
+        let record: PureRecord<TestModelRecordSegments> = serde_json::from_str(r).unwrap();
+        assert_eq!(record.enthalpy_of_combustion, Some(891.51));
+        let record = PureRecord::new(Identifier::default(), 1.0, ());
+        assert!(record.enthalpy_of_combustion.is_none());
+        let json = serde_json::to_string(&record.with_enthalpy_of_combustion(0.0)).unwrap();
+        assert!(json.contains("enthalpy_of_combustion"));
+    }
 }
@@ -0,0 +1,436 @@
+//! Standard atomic weights and the element composition of chemical formulas and SMILES.
+use crate::errors::{FeosError, FeosResult};
+
+/// Standard atomic weights in g/mol (IUPAC, abridged). For elements without
+/// stable isotopes, the mass number of the longest-lived isotope is used.
+const ATOMIC_WEIGHTS: [(&str, f64); 92] = [
+    ("H", 1.008),
+    ("He", 4.0026),
+    ("Li", 6.94),
+    ("Be", 9.0122),
+    ("B", 10.81),
+    ("C", 12.011),
+    ("N", 14.007),
+    ("O", 15.999),
+    ("F", 18.998),
+    ("Ne", 20.180),
+    ("Na", 22.990),
+    ("Mg", 24.305),
+    ("Al", 26.982),
+    ("Si", 28.085),
+    ("P", 30.974),
+    ("S", 32.06),
+    ("Cl", 35.45),
+    ("Ar", 39.95),
+    ("K", 39.098),
+    ("Ca", 40.078),
+    ("Sc", 44.956),
+    ("Ti", 47.867),
+    ("V", 50.942),
+    ("Cr", 51.996),
+    ("Mn", 54.938),
+    ("Fe", 55.845),
+    ("Co", 58.933),
+    ("Ni", 58.693),
+    ("Cu", 63.546),
+    ("Zn", 65.38),
+    ("Ga", 69.723),
+    ("Ge", 72.630),
+    ("As", 74.922),
+    ("Se", 78.971),
+    ("Br", 79.904),
+    ("Kr", 83.798),
+    ("Rb", 85.468),
+    ("Sr", 87.62),
+    ("Y", 88.906),
+    ("Zr", 91.224),
+    ("Nb", 92.906),
+    ("Mo", 95.95),
+    ("Tc", 98.0),
+    ("Ru", 101.07),
+    ("Rh", 102.91),
+    ("Pd", 106.42),
+    ("Ag", 107.87),
+    ("Cd", 112.41),
+    ("In", 114.82),
+    ("Sn", 118.71),
+    ("Sb", 121.76),
+    ("Te", 127.60),
+    ("I", 126.90),
+    ("Xe", 131.29),
+    ("Cs", 132.91),
+    ("Ba", 137.33),
+    ("La", 138.91),
+    ("Ce", 140.12),
+    ("Pr", 140.91),
+    ("Nd", 144.24),
+    ("Pm", 145.0),
+    ("Sm", 150.36),
+    ("Eu", 151.96),
+    ("Gd", 157.25),
+    ("Tb", 158.93),
+    ("Dy", 162.50),
+    ("Ho", 164.93),
+    ("Er", 167.26),
+    ("Tm", 168.93),
+    ("Yb", 173.05),
+    ("Lu", 174.97),
+    ("Hf", 178.49),
+    ("Ta", 180.95),
+    ("W", 183.84),
+    ("Re", 186.21),
+    ("Os", 190.23),
+    ("Ir", 192.22),
+    ("Pt", 195.08),
+    ("Au", 196.97),
+    ("Hg", 200.59),
+    ("Tl", 204.38),
+    ("Pb", 207.2),
+    ("Bi", 208.98),
+    ("Po", 209.0),
+    ("At", 210.0),
+    ("Rn", 222.0),
+    ("Fr", 223.0),
+    ("Ra", 226.0),
+    ("Ac", 227.0),
+    ("Th", 232.04),
+    ("Pa", 231.04),
+    ("U", 238.03),
+];
+
+/// Return the standard atomic weight of an element in g/mol.
+pub(crate) fn atomic_weight(symbol: &str) -> Option<f64> {
+    ATOMIC_WEIGHTS
+        .iter()
+        .find(|(s, _)| *s == symbol)
+        .map(|&(_, w)| w)
+}
+
+/// Return the symbol of a known element as static string.
+fn element(symbol: &str) -> Option<&'static str> {
+    ATOMIC_WEIGHTS
+        .iter()
+        .find(|(s, _)| *s == symbol)
+        .map(|&(s, _)| s)
+}
+
+/// Molar weight in g/mol of a list of elements with their counts.
+pub(crate) fn molar_weight(elements: &[(&'static str, usize)]) -> f64 {
+    elements
+        .iter()
+        .map(|&(e, n)| atomic_weight(e).unwrap() * n as f64)
+        .sum()
+}
+
+fn add(elements: &mut Vec<(&'static str, usize)>, symbol: &'static str, count: usize) {
+    match elements.iter_mut().find(|(e, _)| *e == symbol) {
+        Some((_, n)) => *n += count,
+        None => elements.push((symbol, count)),
+    }
+}
+
+fn invalid(kind: &str, input: &str) -> FeosError {
+    FeosError::Error(format!("'{input}' is not a valid {kind}."))
+}
+
+/// Parse a number at the start of `chars`, if present.
+fn parse_count(chars: &[char], pos: &mut usize) -> Option<usize> {
+    let start = *pos;
+    while *pos < chars.len() && chars[*pos].is_ascii_digit() {
+        *pos += 1;
+    }
+    (*pos > start).then(|| {
+        chars[start..*pos]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .unwrap()
+    })
+}
+
+/// Parse the element symbol at the start of `chars`, preferring two-letter symbols.
+fn parse_symbol(chars: &[char], pos: &mut usize) -> Option<&'static str> {
+    if *pos < chars.len() && chars[*pos].is_ascii_uppercase() {
+        if *pos + 1 < chars.len() && chars[*pos + 1].is_ascii_lowercase() {
+            let symbol: String = chars[*pos..*pos + 2].iter().collect();
+            if let Some(e) = element(&symbol) {
+                *pos += 2;
+                return Some(e);
+            }
+        }
+        let e = element(&chars[*pos].to_string())?;
+        *pos += 1;
+        return Some(e);
+    }
+    None
+}
+
+/// Count the elements of a chemical formula, e.g., "C2H5OH" or "CH3(CH2)4CH3".
+///
+/// The elements are returned in the order of their first occurence.
+pub(crate) fn parse_formula(formula: &str) -> FeosResult<Vec<(&'static str, usize)>> {
+    let chars: Vec<char> = formula.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut pos = 0;
+    let mut stack = vec![Vec::new()];
+    while pos < chars.len() {
+        match chars[pos] {
+            '(' | '[' => {
+                pos += 1;
+                stack.push(Vec::new());
+            }
+            ')' | ']' => {
+                pos += 1;
+                let group = stack.pop().unwrap();
+                let count = parse_count(&chars, &mut pos).unwrap_or(1);
+                let outer = stack
+                    .last_mut()
+                    .ok_or_else(|| invalid("formula", formula))?;
+                for (e, n) in group {
+                    add(outer, e, n * count);
+                }
+            }
+            _ => {
+                let e =
+                    parse_symbol(&chars, &mut pos).ok_or_else(|| invalid("formula", formula))?;
+                let count = parse_count(&chars, &mut pos).unwrap_or(1);
+                add(stack.last_mut().unwrap(), e, count);
+            }
+        }
+    }
+    if stack.len() != 1 || stack[0].is_empty() {
+        return Err(invalid("formula", formula));
+    }
+    Ok(stack.pop().unwrap())
+}
+
+/// Default valences of the elements of the organic subset of SMILES.
+fn default_valences(symbol: &str) -> &'static [usize] {
+    match symbol {
+        "B" => &[3],
+        "C" => &[4],
+        "N" => &[3, 5],
+        "O" => &[2],
+        "P" => &[3, 5],
+        "S" => &[2, 4, 6],
+        "F" | "Cl" | "Br" | "I" => &[1],
+        _ => &[],
+    }
+}
+
+type SmilesAtom = (&'static str, Option<usize>, bool, usize);
+
+fn connect(atoms: &mut [SmilesAtom], a: usize, b: usize, order: usize) {
+    atoms[a].3 += order;
+    atoms[b].3 += order;
+}
+
+/// Count the elements of a SMILES string including implicit hydrogens.
+///
+/// Stereochemistry, isotopes and charges are parsed but ignored in the count.
+pub(crate) fn parse_smiles(smiles: &str) -> FeosResult<Vec<(&'static str, usize)>> {
+    let err = || invalid("SMILES", smiles);
+    let chars: Vec<char> = smiles.chars().collect();
+    // atoms: (symbol, explicit hydrogens (for bracket atoms), aromatic, bond order sum)
+    let mut atoms: Vec<SmilesAtom> = Vec::new();
+    let mut branches = Vec::new();
+    let mut rings: Vec<(usize, usize, Option<usize>)> = Vec::new();
+    let mut previous: Option<usize> = None;
+    let mut bond: Option<usize> = None;
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        let c = chars[pos];
+        let atom = match c {
+            '(' => {
+                branches.push(previous.ok_or_else(err)?);
+                pos += 1;
+                continue;
+            }
+            ')' => {
+                previous = Some(branches.pop().ok_or_else(err)?);
+                pos += 1;
+                continue;
+            }
+            '-' | '/' | '\\' | ':' => {
+                bond = Some(1);
+                pos += 1;
+                continue;
+            }
+            '=' => {
+                bond = Some(2);
+                pos += 1;
+                continue;
+            }
+            '#' => {
+                bond = Some(3);
+                pos += 1;
+                continue;
+            }
+            '$' => {
+                bond = Some(4);
+                pos += 1;
+                continue;
+            }
+            '.' => {
+                previous = None;
+                pos += 1;
+                continue;
+            }
+            '0'..='9' | '%' => {
+                let label = if c == '%' {
+                    let label = chars.get(pos + 1..pos + 3).ok_or_else(err)?;
+                    pos += 3;
+                    label
+                        .iter()
+                        .collect::<String>()
+                        .parse()
+                        .map_err(|_| err())?
+                } else {
+                    pos += 1;
+                    c.to_digit(10).unwrap() as usize
+                };
+                let atom = previous.ok_or_else(err)?;
+                if let Some(k) = rings.iter().position(|r| r.0 == label) {
+                    let (_, other, order) = rings.remove(k);
+                    connect(&mut atoms, atom, other, bond.or(order).unwrap_or(1));
+                } else {
+                    rings.push((label, atom, bond));
+                }
+                bond = None;
+                continue;
+            }
+            '[' => {
+                let end = pos
+                    + chars[pos..]
+                        .iter()
+                        .position(|&c| c == ']')
+                        .ok_or_else(err)?;
+                let inner = &chars[pos + 1..end];
+                pos = end + 1;
+                let mut i = 0;
+                parse_count(inner, &mut i);
+                let aromatic = inner.get(i).is_some_and(|c| c.is_ascii_lowercase());
+                let symbol = if aromatic {
+                    let mut upper = vec![inner[i].to_ascii_uppercase()];
+                    upper.extend(inner.get(i + 1).filter(|c| c.is_ascii_lowercase()));
+                    let mut j = 0;
+                    let e = parse_symbol(&upper, &mut j).ok_or_else(err)?;
+                    i += j;
+                    e
+                } else {
+                    parse_symbol(inner, &mut i).ok_or_else(err)?
+                };
+                while i < inner.len() && inner[i] == '@' {
+                    i += 1;
+                }
+                let hydrogens = if inner.get(i) == Some(&'H') {
+                    i += 1;
+                    parse_count(inner, &mut i).unwrap_or(1)
+                } else {
+                    0
+                };
+                (symbol, Some(hydrogens), aromatic)
+            }
+            'b' | 'c' | 'n' | 'o' | 'p' | 's' => {
+                let symbol = element(&c.to_ascii_uppercase().to_string()).ok_or_else(err)?;
+                pos += 1;
+                (symbol, None, true)
+            }
+            _ => {
+                let symbol = parse_symbol(&chars, &mut pos).ok_or_else(err)?;
+                if default_valences(symbol).is_empty() {
+                    return Err(err());
+                }
+                (symbol, None, false)
+            }
+        };
+        let (symbol, hydrogens, aromatic) = atom;
+        atoms.push((symbol, hydrogens, aromatic, 0));
+        let current = atoms.len() - 1;
+        if let Some(p) = previous {
+            connect(&mut atoms, p, current, bond.unwrap_or(1));
+        }
+        previous = Some(current);
+        bond = None;
+    }
+    if atoms.is_empty() || !branches.is_empty() || !rings.is_empty() {
+        return Err(err());
+    }
+
+    let mut elements = Vec::new();
+    for (symbol, hydrogens, aromatic, bonds) in atoms {
+        add(&mut elements, symbol, 1);
+        let hydrogens = hydrogens.unwrap_or_else(|| {
+            let valences = default_valences(symbol);
+            if aromatic {
+                // aromatic atoms contribute one additional bond to the delocalized system
+                valences[0].saturating_sub(bonds + 1)
+            } else {
+                valences
+                    .iter()
+                    .find(|&&v| v >= bonds)
+                    .map_or(0, |v| v - bonds)
+            }
+        });
+        if hydrogens > 0 {
+            add(&mut elements, "H", hydrogens);
+        }
+    }
+    Ok(elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn weight(elements: FeosResult<Vec<(&'static str, usize)>>) -> f64 {
+        molar_weight(&elements.unwrap())
+    }
+
+    #[test]
+    fn formula() {
+        assert_eq!(
+            parse_formula("C2H5OH").unwrap(),
+            vec![("C", 2), ("H", 6), ("O", 1)]
+        );
+        assert_eq!(
+            parse_formula("CH3(CH2)4CH3").unwrap(),
+            vec![("C", 6), ("H", 14)]
+        );
+        assert_relative_eq!(weight(parse_formula("CO2")), 44.009, max_relative = 1e-12);
+        assert_relative_eq!(weight(parse_formula("NaCl")), 58.44, max_relative = 1e-12);
+        assert!(parse_formula("na+").is_err());
+        assert!(parse_formula("C2(H").is_err());
+        assert!(parse_formula("").is_err());
+    }
+
+    #[test]
+    fn smiles() {
+        for (smiles, formula) in [
+            ("CCC", "C3H8"),
+            ("CCO", "C2H6O"),
+            ("O=C=O", "CO2"),
+            ("c1ccccc1", "C6H6"),
+            ("c1ccncc1", "C5H5N"),
+            ("c1cc[nH]c1", "C4H5N"),
+            ("c1ccsc1", "C4H4S"),
+            ("Cc1ccccc1", "C7H8"),
+            ("CC(C)(C)O", "C4H10O"),
+            ("C1CCCCC1", "C6H12"),
+            ("[Na+].[Cl-]", "NaCl"),
+            ("OS(=O)(=O)O", "H2SO4"),
+            ("C#N", "CHN"),
+            ("ClC(Cl)Cl", "CHCl3"),
+        ] {
+            assert_relative_eq!(
+                weight(parse_smiles(smiles)),
+                weight(parse_formula(formula)),
+                max_relative = 1e-12
+            );
+        }
+        assert!(parse_smiles("C1CC").is_err());
+        assert!(parse_smiles("C(C").is_err());
+        assert!(parse_smiles("Xx").is_err());
+    }
+}
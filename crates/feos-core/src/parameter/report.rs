@@ -0,0 +1,285 @@
+//! Tabular reports of parameter sets.
+use super::PureRecord;
+use crate::errors::FeosResult;
+use ndarray::Array2;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// Output format of a [ParameterTable].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableFormat {
+    /// Plain text with aligned columns.
+    Text,
+    /// Markdown table.
+    Markdown,
+    /// Comma-separated values.
+    Csv,
+}
+
+/// A table of parameters with a title, column headers and rows of formatted values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParameterTable {
+    pub title: String,
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl ParameterTable {
+    /// Tabulate the molar weights and all fields of the model records.
+    ///
+    /// Nested fields of the model records are separated by a dot
+    /// (e.g., `association.kappa_ab`). Fields that are missing in a
+    /// record are left empty.
+    pub fn from_pure_records<M: Serialize>(records: &[PureRecord<M>]) -> FeosResult<Self> {
+        let mut header = vec!["component".to_string(), "molarweight".to_string()];
+        let mut fields = Vec::with_capacity(records.len());
+        for record in records {
+            let mut f = Vec::new();
+            flatten("", serde_json::to_value(&record.model_record)?, &mut f);
+            for (key, _) in &f {
+                if !header.contains(key) {
+                    header.push(key.clone());
+                }
+            }
+            fields.push(f);
+        }
+        let rows = records
+            .iter()
+            .zip(fields)
+            .enumerate()
+            .map(|(i, (record, f))| {
+                let mut row = vec![String::new(); header.len()];
+                row[0] = component_name(record, i);
+                row[1] = record.molarweight.to_string();
+                for (key, value) in f {
+                    let k = header.iter().position(|h| *h == key).unwrap();
+                    row[k] = value;
+                }
+                row
+            })
+            .collect();
+        Ok(Self {
+            title: "pure component parameters".into(),
+            header,
+            rows,
+        })
+    }
+
+    /// Tabulate a matrix of binary parameters, with one table per field
+    /// of the binary records.
+    pub fn from_binary_matrix<M, B: Serialize>(
+        records: &[PureRecord<M>],
+        binary: &Array2<B>,
+    ) -> FeosResult<Vec<Self>> {
+        let names: Vec<_> = records
+            .iter()
+            .enumerate()
+            .map(|(i, r)| component_name(r, i))
+            .collect();
+        let mut header = vec!["component".to_string()];
+        header.extend(names.iter().cloned());
+
+        let mut tables: Vec<Self> = Vec::new();
+        for ((i, j), b) in binary.indexed_iter() {
+            let mut f = Vec::new();
+            flatten("", serde_json::to_value(b)?, &mut f);
+            for (key, value) in f {
+                let title = if key.is_empty() {
+                    "binary parameters".to_string()
+                } else {
+                    format!("binary parameters: {key}")
+                };
+                let k = match tables.iter().position(|t| t.title == title) {
+                    Some(k) => k,
+                    None => {
+                        let rows = names
+                            .iter()
+                            .map(|n| {
+                                let mut row = vec![String::new(); names.len() + 1];
+                                row[0] = n.clone();
+                                row
+                            })
+                            .collect();
+                        tables.push(Self {
+                            title,
+                            header: header.clone(),
+                            rows,
+                        });
+                        tables.len() - 1
+                    }
+                };
+                tables[k].rows[i][j + 1] = value;
+            }
+        }
+        Ok(tables)
+    }
+
+    /// Format the table.
+    pub fn format(&self, format: TableFormat) -> String {
+        match format {
+            TableFormat::Text => self.format_text(),
+            TableFormat::Markdown => self.format_markdown(),
+            TableFormat::Csv => self.format_csv(),
+        }
+    }
+
+    fn format_text(&self) -> String {
+        let widths: Vec<_> = (0..self.header.len())
+            .map(|k| {
+                self.rows
+                    .iter()
+                    .map(|r| r[k].chars().count())
+                    .chain([self.header[k].chars().count()])
+                    .max()
+                    .unwrap()
+            })
+            .collect();
+        let line = |row: &[String]| {
+            row.iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(k, (v, &w))| {
+                    if k == 0 {
+                        format!("{v:<w$}")
+                    } else {
+                        format!("{v:>w$}")
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+        };
+        let mut lines = vec![self.title.clone(), line(&self.header)];
+        lines.push(
+            widths
+                .iter()
+                .map(|&w| "-".repeat(w))
+                .collect::<Vec<_>>()
+                .join("  "),
+        );
+        lines.extend(self.rows.iter().map(|r| line(r)));
+        lines.join("\n")
+    }
+
+    fn format_markdown(&self) -> String {
+        let line = |row: &[String]| {
+            let cells: Vec<_> = row.iter().map(|v| v.replace('|', "\\|")).collect();
+            format!("| {} |", cells.join(" | "))
+        };
+        let mut lines = vec![format!("**{}**", self.title), String::new()];
+        lines.push(line(&self.header));
+        lines.push(format!("|{}", "---|".repeat(self.header.len())));
+        lines.extend(self.rows.iter().map(|r| line(r)));
+        lines.join("\n")
+    }
+
+    fn format_csv(&self) -> String {
+        let line = |row: &[String]| {
+            row.iter()
+                .map(|v| {
+                    if v.contains([',', '"', '\n']) {
+                        format!("\"{}\"", v.replace('"', "\"\""))
+                    } else {
+                        v.clone()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let mut lines = vec![line(&self.header)];
+        lines.extend(self.rows.iter().map(|r| line(r)));
+        lines.join("\n")
+    }
+}
+
+impl fmt::Display for ParameterTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format(TableFormat::Text))
+    }
+}
+
+fn component_name<M>(record: &PureRecord<M>, index: usize) -> String {
+    record
+        .identifier
+        .as_readable_str()
+        .map_or_else(|| format!("component {index}"), String::from)
+}
+
+/// Collect the leaves of a JSON value as (path, formatted value) pairs.
+fn flatten(prefix: &str, value: Value, fields: &mut Vec<(String, String)>) {
+    match value {
+        Value::Null => (),
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&key, value, fields);
+            }
+        }
+        Value::String(s) => fields.push((prefix.into(), s)),
+        value => fields.push((prefix.into(), value.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinsonParameters, PengRobinsonRecord};
+    use crate::parameter::{Identifier, Parameter};
+    use ndarray::arr2;
+
+    fn parameters() -> FeosResult<PengRobinsonParameters> {
+        let id = |name| Identifier::new(None, Some(name), None, None, None, None);
+        let records = vec![
+            PureRecord::new(
+                id("propane"),
+                44.0962,
+                PengRobinsonRecord::new(369.96, 4250000.0, 0.153),
+            ),
+            PureRecord::new(
+                id("butane"),
+                58.123,
+                PengRobinsonRecord::new(425.2, 3800000.0, 0.199),
+            ),
+        ];
+        PengRobinsonParameters::from_records(records, Some(arr2(&[[0.0, 0.1], [0.1, 0.0]])))
+    }
+
+    #[test]
+    fn tables() -> FeosResult<()> {
+        let parameters = parameters()?;
+        let (pure, binary) = parameters.records();
+        let table = ParameterTable::from_pure_records(pure)?;
+        assert_eq!(
+            table.header,
+            ["component", "molarweight", "tc", "pc", "acentric_factor"]
+        );
+        assert_eq!(table.rows[1][0], "butane");
+        assert_eq!(table.rows[1][2], "425.2");
+
+        let binary = ParameterTable::from_binary_matrix(pure, binary.unwrap())?;
+        assert_eq!(binary.len(), 1);
+        assert_eq!(binary[0].rows[0][2], "0.1");
+
+        let csv = table.format(TableFormat::Csv);
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv.starts_with("component,molarweight,tc,pc,acentric_factor\n"));
+        let markdown = table.format(TableFormat::Markdown);
+        assert!(markdown.contains("|---|---|---|---|---|"));
+        let text = table.format(TableFormat::Text);
+        let lengths: Vec<_> = text.lines().skip(1).map(str::len).collect();
+        assert!(lengths.iter().all(|&l| l == lengths[0]));
+        Ok(())
+    }
+
+    #[test]
+    fn report() -> FeosResult<()> {
+        let report = parameters()?.report(TableFormat::Markdown)?;
+        assert!(report.contains("**pure component parameters**"));
+        assert!(report.contains("**binary parameters**"));
+        Ok(())
+    }
+}
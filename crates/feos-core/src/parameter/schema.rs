@@ -0,0 +1,337 @@
+//! Versioning and migration of parameter files.
+//!
+//! Parameter files are either a plain list of records (the unversioned
+//! layout, treated as version 0) or an object of the form
+//!
+//! ```json
+//! {
+//!     "schema_version": 1,
+//!     "records": [...]
+//! }
+//! ```
+//!
+//! On load, files with an older version are upgraded record by record to the
+//! current layout before they are deserialized.
+use super::{Identifier, ValidationReport};
+use crate::errors::{FeosError, FeosResult};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Current version of the layout of parameter files.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Fields that are not part of the model record in records of version 0.
+const RECORD_FIELDS: [&str; 7] = [
+    "identifier",
+    "id1",
+    "id2",
+    "molarweight",
+    "model_record",
+    "metadata",
+    "enthalpy_of_combustion",
+];
+
+/// Fields of records of version 0 that were renamed in version 1.
+const RENAMED_FIELDS: [(&str, &str); 1] = [("molar_weight", "molarweight")];
+
+/// Fields of records of version 0 that are no longer used.
+const REMOVED_FIELDS: [&str; 1] = ["ideal_gas_record"];
+
+/// Treatment of suspicious content of parameter files.
+///
+/// Fields that are not used by a record (e.g., because of a typo in the field
+/// name) and pure component records without a molar weight, which then
+/// defaults to 0, are reported as errors in strict mode and as warnings in
+/// permissive mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParsingMode {
+    /// Reject files with unknown fields or missing molar weights.
+    Strict,
+    /// Accept unknown fields and missing molar weights but report them.
+    #[default]
+    Permissive,
+}
+
+impl ParsingMode {
+    /// Add a problem to the report with the severity implied by the mode.
+    pub(crate) fn report(
+        self,
+        report: &mut ValidationReport,
+        components: Vec<usize>,
+        message: String,
+    ) {
+        match self {
+            Self::Strict => report.error(components, message),
+            Self::Permissive => report.warning(components, message),
+        }
+    }
+}
+
+/// A list of records together with the version of its layout.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ParameterFile<R> {
+    pub schema_version: u32,
+    pub records: Vec<R>,
+}
+
+impl<R> ParameterFile<R> {
+    /// Wrap records in a file of the current version.
+    pub fn new(records: Vec<R>) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            records,
+        }
+    }
+
+    /// Serialize the records as a versioned JSON string.
+    pub fn to_json_string(&self) -> FeosResult<String>
+    where
+        R: Serialize,
+    {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Upgrade the content of a parameter file to the current version and
+/// deserialize its records.
+///
+/// Errors in individual records are reported with the index of the record.
+pub(crate) fn read_records<R: DeserializeOwned>(file: Value) -> FeosResult<Vec<R>> {
+    upgrade_records(file)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, record)| {
+            serde_json::from_value(record).map_err(|e| FeosError::InvalidRecord(i, e))
+        })
+        .collect()
+}
+
+/// Same as [read_records], but additionally returns a description of every
+/// suspicious part of each record, i.e., unknown fields and missing molar weights.
+pub(crate) fn read_records_checked<R: DeserializeOwned + Serialize>(
+    file: Value,
+) -> FeosResult<Vec<(R, Vec<String>)>> {
+    upgrade_records(file)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, raw)| {
+            let record: R =
+                serde_json::from_value(raw.clone()).map_err(|e| FeosError::InvalidRecord(i, e))?;
+            let mut issues = Vec::new();
+            unknown_fields(&raw, &serde_json::to_value(&record)?, "", &mut issues);
+            if raw.get("identifier").is_some() && raw.get("molarweight").is_none() {
+                issues.push("no molar weight given, it defaults to 0".into());
+            }
+            Ok((record, issues))
+        })
+        .collect()
+}
+
+/// Bring all records of a parameter file to the current layout.
+fn upgrade_records(file: Value) -> FeosResult<Vec<Value>> {
+    let (version, mut records) = match file {
+        Value::Array(records) => (0, records),
+        Value::Object(mut file) => {
+            let version = file
+                .get("schema_version")
+                .and_then(Value::as_u64)
+                .ok_or_else(|| {
+                    FeosError::IncompatibleParameters(
+                        "parameter file has no valid 'schema_version'.".into(),
+                    )
+                })?;
+            let Some(Value::Array(records)) = file.remove("records") else {
+                return Err(FeosError::IncompatibleParameters(
+                    "parameter file has no list of 'records'.".into(),
+                ));
+            };
+            (version, records)
+        }
+        _ => {
+            return Err(FeosError::IncompatibleParameters(
+                "parameter file has to be a list of records.".into(),
+            ));
+        }
+    };
+    if version > SCHEMA_VERSION as u64 {
+        return Err(FeosError::UnsupportedSchemaVersion(version));
+    }
+    for record in records.iter_mut() {
+        if version == 0 {
+            migrate_v0(record);
+        }
+        fill_molar_weight(record);
+    }
+    Ok(records)
+}
+
+/// Collect the fields of `raw` that are missing in the serialized record `known`.
+///
+/// Fields that are not serialized because they have their default value are
+/// indistinguishable from unknown fields. Therefore, fields with null, zero, or
+/// empty values are not reported.
+fn unknown_fields(raw: &Value, known: &Value, path: &str, unknown: &mut Vec<String>) {
+    let (Value::Object(raw), Value::Object(known)) = (raw, known) else {
+        return;
+    };
+    for (key, value) in raw {
+        let field = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{path}.{key}")
+        };
+        match known.get(key) {
+            Some(known) => unknown_fields(value, known, &field, unknown),
+            None if !is_default(value) => unknown.push(format!("unknown field '{field}'")),
+            None => (),
+        }
+    }
+}
+
+fn is_default(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Bool(b) => !b,
+        Value::Number(n) => n.as_f64() == Some(0.0),
+        Value::String(s) => s.is_empty(),
+        Value::Array(a) => a.is_empty(),
+        Value::Object(o) => o.is_empty(),
+    }
+}
+
+/// Upgrade a record of version 0 to version 1:
+/// * renamed fields are replaced by their current names,
+/// * removed fields are dropped,
+/// * flattened model records are moved into the `model_record` field.
+fn migrate_v0(record: &mut Value) {
+    let Value::Object(record) = record else {
+        return;
+    };
+    for (old, new) in RENAMED_FIELDS {
+        if !record.contains_key(new)
+            && let Some(value) = record.remove(old)
+        {
+            record.insert(new.into(), value);
+        }
+    }
+    for field in REMOVED_FIELDS {
+        record.remove(field);
+    }
+    if !record.contains_key("model_record") {
+        let keys: Vec<String> = record
+            .keys()
+            .filter(|k| !RECORD_FIELDS.contains(&k.as_str()))
+            .cloned()
+            .collect();
+        let model_record: Map<_, _> = keys
+            .into_iter()
+            .filter_map(|k| record.remove(&k).map(|v| (k, v)))
+            .collect();
+        record.insert("model_record".into(), Value::Object(model_record));
+    }
+}
+
+/// Calculate missing molar weights of pure component records from the
+/// chemical formula or SMILES in their identifier.
+///
+/// Records without a valid formula or SMILES are left unchanged.
+fn fill_molar_weight(record: &mut Value) {
+    let Value::Object(record) = record else {
+        return;
+    };
+    if record.contains_key("molarweight") {
+        return;
+    }
+    let Some(identifier @ Value::Object(_)) = record.get("identifier") else {
+        return;
+    };
+    let molar_weight = Identifier::deserialize(identifier)
+        .ok()
+        .and_then(|i| i.molar_weight());
+    if let Some(molar_weight) = molar_weight {
+        record.insert("molarweight".into(), molar_weight.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parameter::{BinaryRecord, PureRecord};
+    use approx::assert_relative_eq;
+
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    struct TestModelRecord {
+        a: f64,
+    }
+
+    #[test]
+    fn migrate_legacy_records() -> FeosResult<()> {
+        let legacy = r#"[
+            {"identifier": {"cas": "1"}, "molar_weight": 1.0, "a": 1.0},
+            {"identifier": {"cas": "2"}, "molarweight": 2.0, "model_record": {"a": 2.0}, "ideal_gas_record": {}}
+        ]"#;
+        let records: Vec<PureRecord<TestModelRecord>> =
+            read_records(serde_json::from_str(legacy)?)?;
+        assert_eq!(records[0].molarweight, 1.0);
+        assert_eq!(records[0].model_record.a, 1.0);
+        assert_eq!(records[1].model_record.a, 2.0);
+
+        let legacy = r#"[{"id1": {"cas": "1"}, "id2": {"cas": "2"}, "a": 0.5}]"#;
+        let records: Vec<BinaryRecord<TestModelRecord>> =
+            read_records(serde_json::from_str(legacy)?)?;
+        assert_eq!(records[0].model_record.a, 0.5);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_molar_weight() -> FeosResult<()> {
+        let file = r#"[
+            {"identifier": {"formula": "C3H8"}, "model_record": {"a": 1.0}},
+            {"identifier": {"smiles": "CCO"}, "model_record": {"a": 1.0}},
+            {"identifier": {"formula": "C3H8"}, "molarweight": 44.0, "model_record": {"a": 1.0}},
+            {"identifier": {"name": "unknown"}, "model_record": {"a": 1.0}}
+        ]"#;
+        let records: Vec<PureRecord<TestModelRecord>> = read_records(serde_json::from_str(file)?)?;
+        assert_relative_eq!(records[0].molarweight, 44.097, max_relative = 1e-12);
+        assert_relative_eq!(records[1].molarweight, 46.069, max_relative = 1e-12);
+        assert_eq!(records[2].molarweight, 44.0);
+        assert_eq!(records[3].molarweight, 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn versioned_file() -> FeosResult<()> {
+        let records = vec![PureRecord::new(
+            Default::default(),
+            1.0,
+            TestModelRecord { a: 1.0 },
+        )];
+        let json = ParameterFile::new(records).to_json_string()?;
+        let records: Vec<PureRecord<TestModelRecord>> = read_records(serde_json::from_str(&json)?)?;
+        assert_eq!(records[0].model_record.a, 1.0);
+
+        // flattened records are not migrated in the current version
+        let file = r#"{"schema_version": 1, "records": [{"identifier": {}, "molarweight": 1.0, "a": 1.0}]}"#;
+        let error = read_records::<PureRecord<TestModelRecord>>(serde_json::from_str(file)?);
+        assert!(matches!(error, Err(FeosError::InvalidRecord(0, _))));
+
+        let file = r#"{"schema_version": 2, "records": []}"#;
+        let error = read_records::<PureRecord<TestModelRecord>>(serde_json::from_str(file)?);
+        assert!(matches!(error, Err(FeosError::UnsupportedSchemaVersion(2))));
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_fields() -> FeosResult<()> {
+        let file = r#"[
+            {"identifier": {"name": "a"}, "molarweight": 1.0, "model_record": {"a": 1.0, "b": 2.0}, "metadata": null},
+            {"identifier": {"name": "b"}, "model_record": {"a": 1.0, "b": 0.0}, "comment": ""}
+        ]"#;
+        let records: Vec<(PureRecord<TestModelRecord>, _)> =
+            read_records_checked(serde_json::from_str(file)?)?;
+        assert_eq!(records[0].1, ["unknown field 'model_record.b'"]);
+        assert_eq!(records[1].1, ["no molar weight given, it defaults to 0"]);
+        Ok(())
+    }
+}
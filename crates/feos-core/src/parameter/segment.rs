@@ -2,9 +2,10 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::hash::{Hash, Hasher};
-use std::io::BufReader;
+use std::io::{BufReader, Read};
 use std::path::Path;
 
+use super::schema::read_records;
 use crate::FeosResult;
 
 /// Parameters describing an individual segment of a molecule.
@@ -30,7 +31,23 @@ impl<M> SegmentRecord<M> {
     where
         M: DeserializeOwned,
     {
-        Ok(serde_json::from_reader(BufReader::new(File::open(file)?))?)
+        read_records(serde_json::from_reader(BufReader::new(File::open(file)?))?)
+    }
+
+    /// Read a list of `SegmentRecord`s from a JSON string.
+    pub fn from_json_str(json: &str) -> FeosResult<Vec<Self>>
+    where
+        M: DeserializeOwned,
+    {
+        read_records(serde_json::from_str(json)?)
+    }
+
+    /// Read a list of `SegmentRecord`s from a reader that provides JSON data.
+    pub fn from_reader<R: Read>(reader: R) -> FeosResult<Vec<Self>>
+    where
+        M: DeserializeOwned,
+    {
+        read_records(serde_json::from_reader(reader)?)
     }
 }
 
@@ -79,7 +96,17 @@ impl BinarySegmentRecord {
 
     /// Read a list of `BinaryRecord`s from a JSON file.
     pub fn from_json<P: AsRef<Path>>(file: P) -> FeosResult<Vec<Self>> {
-        Ok(serde_json::from_reader(BufReader::new(File::open(file)?))?)
+        read_records(serde_json::from_reader(BufReader::new(File::open(file)?))?)
+    }
+
+    /// Read a list of `BinaryRecord`s from a JSON string.
+    pub fn from_json_str(json: &str) -> FeosResult<Vec<Self>> {
+        read_records(serde_json::from_str(json)?)
+    }
+
+    /// Read a list of `BinaryRecord`s from a reader that provides JSON data.
+    pub fn from_reader<R: Read>(reader: R) -> FeosResult<Vec<Self>> {
+        read_records(serde_json::from_reader(reader)?)
     }
 }
 
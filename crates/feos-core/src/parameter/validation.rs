@@ -0,0 +1,173 @@
+use super::{IdentifierOption, PureRecord};
+use crate::errors::{FeosError, FeosResult};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Severity of a problem found during the validation of parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The parameters are usable but likely not intended (e.g., implausible values).
+    Warning,
+    /// The parameters lead to meaningless results.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A single problem found during the validation of parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationIssue {
+    /// Severity of the problem.
+    pub severity: Severity,
+    /// Indices of the components (or component pair) concerned, if any.
+    pub components: Vec<usize>,
+    /// Description of the problem.
+    pub message: String,
+}
+
+/// List of all problems found during the validation of parameters.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Create an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a report containing the checks that apply to any parameter set,
+    /// i.e., that no identifier is used for more than one record.
+    pub fn from_records<M>(pure_records: &[PureRecord<M>]) -> Self {
+        let mut report = Self::new();
+        for option in [
+            IdentifierOption::Cas,
+            IdentifierOption::Name,
+            IdentifierOption::IupacName,
+            IdentifierOption::Smiles,
+            IdentifierOption::Inchi,
+        ] {
+            let mut seen: HashMap<&str, usize> = HashMap::new();
+            for (i, record) in pure_records.iter().enumerate() {
+                let Some(id) = record.identifier.as_str(option) else {
+                    continue;
+                };
+                if let Some(&j) = seen.get(id) {
+                    report.error(
+                        vec![j, i],
+                        format!("{option} '{id}' is used by more than one record"),
+                    );
+                } else {
+                    seen.insert(id, i);
+                }
+            }
+        }
+        report
+    }
+
+    /// Add a problem with [Severity::Error].
+    pub fn error<S: Into<String>>(&mut self, components: Vec<usize>, message: S) {
+        self.push(Severity::Error, components, message);
+    }
+
+    /// Add a problem with [Severity::Warning].
+    pub fn warning<S: Into<String>>(&mut self, components: Vec<usize>, message: S) {
+        self.push(Severity::Warning, components, message);
+    }
+
+    fn push<S: Into<String>>(&mut self, severity: Severity, components: Vec<usize>, message: S) {
+        self.issues.push(ValidationIssue {
+            severity,
+            components,
+            message: message.into(),
+        });
+    }
+
+    /// Iterate over all problems with [Severity::Error].
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|i| i.severity == Severity::Error)
+    }
+
+    /// Iterate over all problems with [Severity::Warning].
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == Severity::Warning)
+    }
+
+    /// Return `true` if no errors were found.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Return `Err(FeosError::InvalidParameters)` if the report contains errors
+    /// or, in strict mode, any problem at all.
+    pub fn into_result(self, strict: bool) -> FeosResult<()> {
+        let fail = if strict {
+            !self.issues.is_empty()
+        } else {
+            !self.is_valid()
+        };
+        if fail {
+            Err(FeosError::InvalidParameters(self))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (k, issue) in self.issues.iter().enumerate() {
+            if k > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", issue.severity)?;
+            if !issue.components.is_empty() {
+                write!(f, " (components {:?})", issue.components)?;
+            }
+            write!(f, ": {}", issue.message)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parameter::Identifier;
+
+    #[test]
+    fn duplicate_identifiers() {
+        let id = |name| Identifier::new(None, Some(name), None, None, None, None);
+        let records = vec![
+            PureRecord::new(id("methane"), 16.043, ()),
+            PureRecord::new(id("ethane"), 30.07, ()),
+            PureRecord::new(id("methane"), 16.043, ()),
+            PureRecord::new(Identifier::default(), 44.1, ()),
+            PureRecord::new(Identifier::default(), 44.1, ()),
+        ];
+        let report = ValidationReport::from_records(&records);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].components, vec![0, 2]);
+        assert!(!report.is_valid());
+        assert!(matches!(
+            report.into_result(false),
+            Err(FeosError::InvalidParameters(_))
+        ));
+
+        let mut report = ValidationReport::from_records(&records[..2]);
+        report.warning(vec![1], "implausible");
+        assert!(report.is_valid());
+        assert!(report.clone().into_result(false).is_ok());
+        assert!(report.into_result(true).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use super::PhaseEquilibrium;
+use super::tp_flash::FlashWorkspace;
+use crate::SolverOptions;
+use crate::cancellation::{check_cancelled, current_cancellation};
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult};
+use crate::state::{DensityInitialization, State};
+use ndarray::{Array1, Array2, Axis};
+use quantity::{Moles, Pressure, Temperature};
+use rayon::{ThreadPool, prelude::*};
+use std::sync::Arc;
+
+/// Results of a batch of Tp-flash calculations.
+///
+/// Every point of the batch either contains the phase equilibrium or the
+/// error that occurred during the calculation, e.g., [FeosError::NoPhaseSplit]
+/// for single-phase feeds or [FeosError::NotConverged] with the state of the solver.
+#[derive(Debug)]
+pub struct FlashBatch<E> {
+    /// The results of all points in the order of the specifications.
+    pub results: Vec<FeosResult<PhaseEquilibrium<E, 2>>>,
+}
+
+impl<E> FlashBatch<E> {
+    /// The number of points with a converged phase equilibrium.
+    pub fn converged(&self) -> usize {
+        self.results.iter().filter(|r| r.is_ok()).count()
+    }
+
+    /// The indices and errors of all points that failed.
+    pub fn failures(&self) -> impl Iterator<Item = (usize, &FeosError)> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(i, r)| r.as_ref().err().map(|e| (i, e)))
+    }
+}
+
+/// # Flash calculations
+impl<E: Residual> PhaseEquilibrium<E, 2> {
+    /// Perform Tp-flash calculations for a batch of specifications in parallel.
+    ///
+    /// The i-th flash is performed at `temperature[i]` and `pressure[i]` for the
+    /// feed in the i-th row of `feed`. The calculations are distributed over the
+    /// threads of `thread_pool` and every worker reuses a single [FlashWorkspace]
+    /// for all of its points. A failed point does not abort the batch; instead,
+    /// the error is stored in the corresponding entry of the results.
+    ///
+    /// The [CancellationToken](crate::CancellationToken) installed for the
+    /// calling thread is checked before every point. If it is cancelled, the
+    /// remaining points are skipped and [FeosError::Cancelled] is returned.
+    pub fn flash_many(
+        eos: &Arc<E>,
+        temperature: &Temperature<Array1<f64>>,
+        pressure: &Pressure<Array1<f64>>,
+        feed: &Moles<Array2<f64>>,
+        thread_pool: &ThreadPool,
+        options: SolverOptions,
+    ) -> FeosResult<FlashBatch<E>> {
+        let n = eos.components();
+        let points = temperature.len();
+        if pressure.len() != points || feed.shape() != [points, n] {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "flash specifications need {points} pressures and {points} feeds of {n} components."
+            )));
+        }
+        let token = current_cancellation();
+        let results = thread_pool.install(|| {
+            (0..points)
+                .into_par_iter()
+                .map_init(
+                    || FlashWorkspace::new(n),
+                    |workspace, i| {
+                        if let Some(token) = &token {
+                            token.check()?;
+                        }
+                        let moles = feed.index_axis(Axis(0), i).to_owned();
+                        State::new_npt(
+                            eos,
+                            temperature.get(i),
+                            pressure.get(i),
+                            &moles,
+                            DensityInitialization::None,
+                        )?
+                        .tp_flash_with_workspace(None, options, None, workspace)
+                    },
+                )
+                .collect()
+        });
+        check_cancelled()?;
+        Ok(FlashBatch { results })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Contributions;
+    use crate::test_utils::propane_butane;
+    use ndarray::{arr1, arr2};
+    use quantity::{KELVIN, MOL};
+    use rayon::ThreadPoolBuilder;
+
+    #[test]
+    fn flash_many() -> FeosResult<()> {
+        let eos = Arc::new(propane_butane()?);
+        let t = 330.0 * KELVIN;
+        let z = arr1(&[0.5, 0.5]);
+        let options = (SolverOptions::default(), SolverOptions::default());
+        let p_bubble = PhaseEquilibrium::bubble_point(&eos, t, &z, None, None, options)?
+            .vapor()
+            .pressure(Contributions::Total);
+        let p_dew = PhaseEquilibrium::dew_point(&eos, t, &z, None, None, options)?
+            .vapor()
+            .pressure(Contributions::Total);
+
+        // the third point is a single-phase vapor
+        let temperature = arr1(&[1.0, 1.0, 1.0, 1.0]) * t;
+        let pressure = Pressure::from_vec(vec![
+            p_dew + (p_bubble - p_dew) * 0.25,
+            p_dew + (p_bubble - p_dew) * 0.5,
+            p_dew * 0.5,
+            p_dew + (p_bubble - p_dew) * 0.75,
+        ]);
+        let feed = arr2(&[[0.5, 0.5], [0.5, 0.5], [0.5, 0.5], [0.5, 0.5]]) * MOL;
+        let thread_pool = ThreadPoolBuilder::new().num_threads(2).build()?;
+        let batch = PhaseEquilibrium::flash_many(
+            &eos,
+            &temperature,
+            &pressure,
+            &feed,
+            &thread_pool,
+            SolverOptions::default(),
+        )?;
+        assert_eq!(batch.results.len(), 4);
+
+        // the batch reproduces single flash calculations and keeps failed points
+        for (i, result) in batch.results.iter().enumerate() {
+            let single = PhaseEquilibrium::tp_flash(
+                &eos,
+                temperature.get(i),
+                pressure.get(i),
+                &feed.index_axis(Axis(0), i).to_owned(),
+                None,
+                SolverOptions::default(),
+                None,
+            );
+            match (result, single) {
+                (Ok(vle), Ok(single)) => {
+                    assert_eq!(vle.liquid().molefracs, single.liquid().molefracs)
+                }
+                (Err(_), Err(_)) => (),
+                _ => panic!("batch and single flash disagree at point {i}"),
+            }
+        }
+        assert!(batch.failures().any(|(i, _)| i == 2));
+        assert_eq!(batch.converged(), 3);
+        assert_eq!(batch.failures().count(), 1);
+
+        let wrong_feed = arr2(&[[0.5, 0.5]]) * MOL;
+        assert!(
+            PhaseEquilibrium::flash_many(
+                &eos,
+                &temperature,
+                &pressure,
+                &wrong_feed,
+                &thread_pool,
+                SolverOptions::default()
+            )
+            .is_err()
+        );
+
+        // a cancelled batch is aborted
+        let token = crate::CancellationToken::new();
+        token.cancel();
+        let cancelled = crate::with_cancellation(token, || {
+            PhaseEquilibrium::flash_many(
+                &eos,
+                &temperature,
+                &pressure,
+                &feed,
+                &thread_pool,
+                SolverOptions::default(),
+            )
+        });
+        assert!(matches!(cancelled, Err(FeosError::Cancelled)));
+        Ok(())
+    }
+}
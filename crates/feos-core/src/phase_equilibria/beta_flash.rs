@@ -0,0 +1,177 @@
+use super::PhaseEquilibrium;
+use super::bubble_dew::TemperatureOrPressure;
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::observer::{is_observed, report_iteration, report_result};
+use crate::state::{Contributions, DensityInitialization::InitialDensity, State};
+use crate::{ReferenceSystem, SolverOptions, Verbosity};
+use ndarray::Array1;
+use num_dual::linalg::norm;
+use quantity::{KELVIN, Moles, PASCAL};
+use std::sync::Arc;
+
+const MAX_ITER_INNER: usize = 5;
+const TOL_INNER: f64 = 1e-9;
+const MAX_ITER_OUTER: usize = 400;
+const TOL_OUTER: f64 = 1e-10;
+
+/// # Flash calculations
+impl<E: Residual> PhaseEquilibrium<E, 2> {
+    /// Calculate a phase equilibrium for a given temperature or pressure,
+    /// composition of the feed and molar vapor fraction $\beta$ (β-flash).
+    ///
+    /// The temperature or pressure that is not specified is determined
+    /// such that the feed splits into a vapor phase with the vapor fraction
+    /// $\beta$ and a liquid phase. For $\beta=0$ and $\beta=1$ the calculation
+    /// is a bubble and dew point calculation, respectively. Otherwise, the
+    /// iteration is initialized from the bubble and dew points of the feed,
+    /// which, at given pressure, require an initial temperature `tp_init`.
+    ///
+    /// As for bubble and dew points, the amount of substance in each phase
+    /// is not scaled with the vapor fraction.
+    pub fn beta_flash<TP: TemperatureOrPressure>(
+        eos: &Arc<E>,
+        temperature_or_pressure: TP,
+        feed_molefracs: &Array1<f64>,
+        vapor_fraction: f64,
+        tp_init: Option<TP::Other>,
+        options: (SolverOptions, SolverOptions),
+    ) -> FeosResult<Self> {
+        let tp_spec = temperature_or_pressure;
+        let beta = vapor_fraction;
+        if !(0.0..=1.0).contains(&beta) {
+            return Err(FeosError::InvalidState(
+                "beta flash".into(),
+                "vapor fraction".into(),
+                beta,
+            ));
+        }
+        let z = feed_molefracs / feed_molefracs.sum();
+        if beta == 0.0 {
+            return Self::bubble_point(eos, tp_spec, &z, tp_init, None, options);
+        }
+        if beta == 1.0 {
+            return Self::dew_point(eos, tp_spec, &z, tp_init, None, options);
+        }
+
+        // interpolate the K-values between the bubble and the dew point
+        let bubble = Self::bubble_point(eos, tp_spec, &z, tp_init, None, options)?;
+        let dew = Self::dew_point(eos, tp_spec, &z, tp_init, None, options)?;
+        let ln_k = |vle: &Self| (&vle.vapor().molefracs / &vle.liquid().molefracs).mapv(f64::ln);
+        let k = ((1.0 - beta) * ln_k(&bubble) + beta * ln_k(&dew)).mapv(f64::exp);
+        let init = if beta < 0.5 { bubble } else { dew };
+        let mut var_tp = TP::from_state(init.liquid());
+        let (t, p) = tp_spec.temperature_pressure(var_tp);
+        let (x, y) = phase_compositions(&z, &k, beta);
+        let mut liquid = State::new_npt(
+            eos,
+            t,
+            p,
+            &Moles::from_reduced(x),
+            InitialDensity(init.liquid().density),
+        )?;
+        let mut vapor = State::new_npt(
+            eos,
+            t,
+            p,
+            &Moles::from_reduced(y),
+            InitialDensity(init.vapor().density),
+        )?;
+
+        let (options_inner, options_outer) = options;
+        let max_iter_outer = options_outer.max_iter.unwrap_or(MAX_ITER_OUTER);
+        let tol_outer = options_outer.tol.unwrap_or(TOL_OUTER);
+        let mut err_out = f64::INFINITY;
+        for ko in 0..max_iter_outer {
+            // Inner loop for finding T or p from the Rachford-Rice equation
+            for _ in 0..options_inner.max_iter.unwrap_or(MAX_ITER_INNER) {
+                let k = (liquid.ln_phi() - vapor.ln_phi()).mapv(f64::exp);
+                let d = (&k - 1.0) * beta + 1.0;
+                let f = (&z * (&k - 1.0) / &d).sum();
+                let df_dln_k = &z * &k / (&d * &d);
+                TP::adjust_t_p_ln_k(tp_spec, &mut var_tp, f, &df_dln_k, &mut liquid, &mut vapor)?;
+                if f.abs() < options_inner.tol.unwrap_or(TOL_INNER) {
+                    break;
+                }
+            }
+
+            // Iso-fugacity condition
+            let ln_k = liquid.ln_phi() - vapor.ln_phi();
+            err_out = norm(&(&ln_k + (&liquid.molefracs / &vapor.molefracs).mapv(f64::ln)));
+            log_iter!(
+                options_outer.verbosity,
+                "{:<14.8e} | {:12.8} | {:.8} | {:.8}",
+                err_out,
+                var_tp,
+                liquid.molefracs,
+                vapor.molefracs
+            );
+            if is_observed() {
+                let variables: Vec<_> = [
+                    liquid.temperature.convert_to(KELVIN),
+                    liquid.pressure(Contributions::Total).convert_to(PASCAL),
+                ]
+                .into_iter()
+                .chain(liquid.molefracs.iter().copied())
+                .collect();
+                report_iteration("beta flash", ko, err_out, &variables);
+            }
+
+            if PhaseEquilibrium::is_trivial_solution(&vapor, &liquid) {
+                log_iter!(options_outer.verbosity, "Trivial solution encountered!");
+                report_result("beta flash", ko + 1, false);
+                return Err(FeosError::TrivialSolution);
+            }
+
+            if err_out < tol_outer {
+                report_result("beta flash", ko + 1, true);
+                log_result!(
+                    options_outer.verbosity,
+                    "Beta flash: calculation converged in {} step(s)\n",
+                    ko + 1
+                );
+                return Ok(Self([vapor, liquid]));
+            }
+
+            // Successive substitution of the phase compositions
+            let (t, p) = tp_spec.temperature_pressure(var_tp);
+            let (x, y) = phase_compositions(&z, &ln_k.mapv(f64::exp), beta);
+            liquid = State::new_npt(
+                eos,
+                t,
+                p,
+                &Moles::from_reduced(x),
+                InitialDensity(liquid.density),
+            )?;
+            vapor = State::new_npt(
+                eos,
+                t,
+                p,
+                &Moles::from_reduced(y),
+                InitialDensity(vapor.density),
+            )?;
+        }
+
+        report_result("beta flash", max_iter_outer, false);
+        Err(FeosError::NotConverged(
+            SolverFailure::new("beta flash")
+                .iterations(max_iter_outer)
+                .residual(err_out)
+                .temperature(liquid.temperature)
+                .pressure(liquid.pressure(Contributions::Total))
+                .molefracs(&z),
+        ))
+    }
+}
+
+/// Normalized compositions of the liquid and the vapor phase from the
+/// Rachford-Rice equation for given K-values and vapor fraction.
+fn phase_compositions(
+    feed_molefracs: &Array1<f64>,
+    k: &Array1<f64>,
+    beta: f64,
+) -> (Array1<f64>, Array1<f64>) {
+    let x = feed_molefracs / ((k - 1.0) * beta + 1.0);
+    let y = k * &x;
+    (&x / x.sum(), &y / y.sum())
+}
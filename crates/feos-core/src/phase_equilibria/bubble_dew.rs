@@ -1,15 +1,18 @@
 use super::PhaseEquilibrium;
 use crate::equation_of_state::Residual;
-use crate::errors::{FeosError, FeosResult};
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::observer::{is_observed, report_iteration, report_result};
 use crate::state::{
     Contributions,
     DensityInitialization::{InitialDensity, Liquid, Vapor},
     State, StateBuilder, TPSpec,
 };
-use crate::{ReferenceSystem, SolverOptions, Verbosity};
+use crate::{NewtonSolver, ReferenceSystem, SolverAlgorithm, SolverOptions, Verbosity};
+use nalgebra::{DMatrix, DVector};
 use ndarray::*;
-use num_dual::linalg::{LU, norm};
-use quantity::{Density, Dimensionless, Moles, Pressure, Quantity, RGAS, SIUnit, Temperature};
+use quantity::{
+    Density, Dimensionless, KELVIN, Moles, PASCAL, Pressure, Quantity, RGAS, SIUnit, Temperature,
+};
 use std::fmt;
 use std::sync::Arc;
 use typenum::{N1, N2, P1, Z0};
@@ -58,13 +61,25 @@ pub trait TemperatureOrPressure: Copy + Into<TPSpec> {
         verbosity: Verbosity,
     ) -> FeosResult<f64>;
 
-    fn newton_step<E: Residual>(
+    /// Residuals of the phase equilibrium conditions and their Jacobian with
+    /// respect to the partial densities of the second phase, the density of
+    /// the first phase and, for a given pressure, the temperature.
+    fn newton_residual<E: Residual>(
+        tp_spec: Self,
+        state1: &State<E>,
+        state2: &State<E>,
+    ) -> (Array1<f64>, Array2<f64>);
+
+    /// Newton step for the unspecified variable from a residual `f` and its
+    /// derivatives with respect to $\ln K_i$ with $K_i=\varphi_i^{(1)}/\varphi_i^{(2)}$.
+    fn adjust_t_p_ln_k<E: Residual>(
         tp_spec: Self,
         var: &mut Self::Other,
+        f: f64,
+        df_dln_k: &Array1<f64>,
         state1: &mut State<E>,
         state2: &mut State<E>,
-        verbosity: Verbosity,
-    ) -> FeosResult<f64>;
+    ) -> FeosResult<()>;
 }
 
 impl TemperatureOrPressure for Temperature {
@@ -186,13 +201,26 @@ impl TemperatureOrPressure for Temperature {
         Ok(f.abs())
     }
 
-    fn newton_step<E: Residual>(
-        _: Temperature,
+    fn adjust_t_p_ln_k<E: Residual>(
+        temperature: Temperature,
         pressure: &mut Pressure,
+        f: f64,
+        df_dln_k: &Array1<f64>,
         state1: &mut State<E>,
         state2: &mut State<E>,
-        verbosity: Verbosity,
-    ) -> FeosResult<f64> {
+    ) -> FeosResult<()> {
+        // derivative w.r.t. ln(pressure)
+        let dln_k = ((state1.dln_phi_dp() - state2.dln_phi_dp()) * *pressure).into_value();
+        let lnpstep = (-f / (df_dln_k * &dln_k).sum()).clamp(-MAX_LNPSTEP, MAX_LNPSTEP);
+        *pressure *= lnpstep.exp();
+        adjust_states(temperature, *pressure, state1, state2, None)
+    }
+
+    fn newton_residual<E: Residual>(
+        _: Temperature,
+        state1: &State<E>,
+        state2: &State<E>,
+    ) -> (Array1<f64>, Array2<f64>) {
         let dmu_drho_1 = (state1.dmu_dni(Contributions::Total) * state1.volume)
             .to_reduced()
             .dot(&state1.molefracs);
@@ -212,7 +240,6 @@ impl TemperatureOrPressure for Temperature {
                 .into_value()
                 .mapv(f64::ln);
         let res = concatenate![Axis(0), mu_1_res - mu_2_res + dmu_ig, arr1(&[p_1 - p_2])];
-        let error = norm(&res);
 
         // calculate Jacobian
         let jacobian = concatenate![
@@ -224,35 +251,7 @@ impl TemperatureOrPressure for Temperature {
                 arr2(&[[dp_drho_1]])
             ]
         ];
-
-        // calculate Newton step
-        let dx = LU::new(jacobian)?.solve(&res);
-
-        // apply Newton step
-        let rho_l1 = state1.density - Density::from_reduced(dx[dx.len() - 1]);
-        let rho_l2 =
-            state2.partial_density.clone() - Density::from_reduced(dx.slice(s![0..-1]).to_owned());
-
-        // update states
-        *state1 = StateBuilder::new(&state1.eos)
-            .temperature(state1.temperature)
-            .density(rho_l1)
-            .molefracs(&state1.molefracs)
-            .build()?;
-        *state2 = StateBuilder::new(&state2.eos)
-            .temperature(state2.temperature)
-            .partial_density(&rho_l2)
-            .build()?;
-        *pressure = state1.pressure(Contributions::Total);
-        log_iter!(
-            verbosity,
-            "{:<14.8e} | {:14} | {:12.8} | {:.8} NEWTON",
-            error,
-            "",
-            pressure,
-            state2.molefracs
-        );
-        Ok(error)
+        (res, jacobian)
     }
 }
 
@@ -351,13 +350,27 @@ impl TemperatureOrPressure for Quantity<f64, SIUnit<N2, N1, P1, Z0, Z0, Z0, Z0>>
         Ok(f.abs())
     }
 
-    fn newton_step<E: Residual>(
+    fn adjust_t_p_ln_k<E: Residual>(
         pressure: Pressure,
         temperature: &mut Temperature,
+        f: f64,
+        df_dln_k: &Array1<f64>,
         state1: &mut State<E>,
         state2: &mut State<E>,
-        verbosity: Verbosity,
-    ) -> FeosResult<f64> {
+    ) -> FeosResult<()> {
+        // derivative w.r.t. temperature
+        let dln_k_dt = state1.dln_phi_dt() - state2.dln_phi_dt();
+        let df = (dln_k_dt * Dimensionless::new(df_dln_k.clone())).sum();
+        let max_tstep = Temperature::from_reduced(MAX_TSTEP);
+        *temperature += (-f / df).max(-max_tstep).min(max_tstep);
+        adjust_states(*temperature, pressure, state1, state2, None)
+    }
+
+    fn newton_residual<E: Residual>(
+        pressure: Pressure,
+        state1: &State<E>,
+        state2: &State<E>,
+    ) -> (Array1<f64>, Array2<f64>) {
         let dmu_drho_1 = (state1.dmu_dni(Contributions::Total) * state1.volume)
             .to_reduced()
             .dot(&state1.molefracs);
@@ -387,7 +400,6 @@ impl TemperatureOrPressure for Quantity<f64, SIUnit<N2, N1, P1, Z0, Z0, Z0, Z0>>
             arr1(&[p_1 - p]),
             arr1(&[p_2 - p])
         ];
-        let error = norm(&res);
 
         // calculate Jacobian
         let jacobian = concatenate![
@@ -409,36 +421,7 @@ impl TemperatureOrPressure for Quantity<f64, SIUnit<N2, N1, P1, Z0, Z0, Z0, Z0>>
                 arr2(&[[dp_dt_1], [dp_dt_2]])
             ]
         ];
-
-        // calculate Newton step
-        let dx = LU::new(jacobian)?.solve(&res);
-
-        // apply Newton step
-        let rho_l1 = state1.density - Density::from_reduced(dx[dx.len() - 2]);
-        let rho_l2 =
-            state2.partial_density.clone() - Density::from_reduced(dx.slice(s![0..-2]).to_owned());
-        let t = state1.temperature - Temperature::from_reduced(dx[dx.len() - 1]);
-
-        // update states
-        *state1 = StateBuilder::new(&state1.eos)
-            .temperature(t)
-            .density(rho_l1)
-            .molefracs(&state1.molefracs)
-            .build()?;
-        *state2 = StateBuilder::new(&state2.eos)
-            .temperature(t)
-            .partial_density(&rho_l2)
-            .build()?;
-        *temperature = t;
-        log_iter!(
-            verbosity,
-            "{:<14.8e} | {:14} | {:12.8} | {:.8} NEWTON",
-            error,
-            "",
-            temperature,
-            state2.molefracs
-        );
-        Ok(error)
+        (res, jacobian)
     }
 }
 
@@ -689,9 +672,15 @@ fn bubble_dew<E: Residual, TP: TemperatureOrPressure>(
     );
 
     // Outer loop for finding x2
-    for ko in 0..options_outer.max_iter.unwrap_or(MAX_ITER_OUTER) {
+    let max_iter_outer = options_outer.max_iter.unwrap_or(MAX_ITER_OUTER);
+    for ko in 0..max_iter_outer {
         // Iso-Fugacity equation
-        err_out = if err_out > NEWTON_TOL {
+        let newton = match options_outer.algorithm {
+            SolverAlgorithm::Default => err_out <= NEWTON_TOL,
+            SolverAlgorithm::SuccessiveSubstitution => false,
+            SolverAlgorithm::Newton => true,
+        };
+        err_out = if !newton {
             // Inner loop for finding T or p
             for _ in 0..options_inner.max_iter.unwrap_or(MAX_ITER_INNER) {
                 if TP::adjust_t_p(
@@ -707,17 +696,29 @@ fn bubble_dew<E: Residual, TP: TemperatureOrPressure>(
             }
             adjust_x2(&state1, &mut state2, options_outer.verbosity)
         } else {
-            TP::newton_step(
+            newton_step(
                 tp_spec,
                 &mut var_tp,
                 &mut state1,
                 &mut state2,
-                options_outer.verbosity,
+                options_outer,
             )
         }?;
 
+        if is_observed() {
+            let variables: Vec<_> = [
+                state1.temperature.convert_to(KELVIN),
+                state1.pressure(Contributions::Total).convert_to(PASCAL),
+            ]
+            .into_iter()
+            .chain(state2.molefracs.iter().copied())
+            .collect();
+            report_iteration("bubble/dew point", ko, err_out, &variables);
+        }
+
         if PhaseEquilibrium::is_trivial_solution(&state1, &state2) {
             log_iter!(options_outer.verbosity, "Trivial solution encountered!");
+            report_result("bubble/dew point", ko + 1, false);
             return Err(FeosError::TrivialSolution);
         }
 
@@ -727,7 +728,13 @@ fn bubble_dew<E: Residual, TP: TemperatureOrPressure>(
         }
     }
 
-    if err_out < options_outer.tol.unwrap_or(TOL_OUTER) {
+    let converged = err_out < options_outer.tol.unwrap_or(TOL_OUTER);
+    report_result(
+        "bubble/dew point",
+        if converged { k_out } else { max_iter_outer },
+        converged,
+    );
+    if converged {
         log_result!(
             options_outer.verbosity,
             "Bubble/dew point: calculation converged in {} step(s)\n",
@@ -740,10 +747,73 @@ fn bubble_dew<E: Residual, TP: TemperatureOrPressure>(
         }
     } else {
         // not converged, return error
-        Err(FeosError::NotConverged(String::from(
-            "bubble-dew-iteration",
-        )))
+        Err(FeosError::NotConverged(
+            SolverFailure::new("bubble/dew point")
+                .iterations(max_iter_outer)
+                .residual(err_out)
+                .temperature(state1.temperature)
+                .pressure(state1.pressure(Contributions::Total))
+                .molefracs(&state2.molefracs),
+        ))
+    }
+}
+
+/// Newton step for the partial densities of the second phase, the density
+/// of the first phase and, for a given pressure, the temperature.
+fn newton_step<E: Residual, TP: TemperatureOrPressure>(
+    tp_spec: TP,
+    var: &mut TP::Other,
+    state1: &mut State<E>,
+    state2: &mut State<E>,
+    options: SolverOptions,
+) -> FeosResult<f64> {
+    let n = state1.eos.components();
+    let temperature = matches!(tp_spec.into(), TPSpec::Pressure(_));
+    let mut x = state2.partial_density.to_reduced().to_vec();
+    x.push(state1.density.to_reduced());
+    if temperature {
+        x.push(state1.temperature.to_reduced());
     }
+
+    let (x, error) = NewtonSolver::new("bubble/dew point", MAX_ITER_OUTER, TOL_OUTER, options)?
+        .step_with_jacobian(
+            |_| {
+                let (res, jacobian) = TP::newton_residual(tp_spec, state1, state2);
+                Ok((
+                    DVector::from_vec(res.to_vec()),
+                    DMatrix::from_fn(res.len(), res.len(), |i, j| jacobian[(i, j)]),
+                ))
+            },
+            &DVector::from_vec(x),
+        )?;
+
+    // update states
+    let t = if temperature {
+        Temperature::from_reduced(x[n + 1])
+    } else {
+        state1.temperature
+    };
+    *state1 = StateBuilder::new(&state1.eos)
+        .temperature(t)
+        .density(Density::from_reduced(x[n]))
+        .molefracs(&state1.molefracs)
+        .build()?;
+    *state2 = StateBuilder::new(&state2.eos)
+        .temperature(t)
+        .partial_density(&Density::from_reduced(Array1::from_iter(
+            x.rows(0, n).iter().copied(),
+        )))
+        .build()?;
+    *var = TP::from_state(state1);
+    log_iter!(
+        options.verbosity,
+        "{:<14.8e} | {:14} | {:12.8} | {:.8} NEWTON",
+        error,
+        "",
+        var,
+        state2.molefracs
+    );
+    Ok(error)
 }
 
 fn adjust_states<E: Residual>(
@@ -0,0 +1,235 @@
+use super::PhaseEquilibrium;
+use super::bubble_dew::TemperatureOrPressure;
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::observer::{is_observed, report_iteration, report_result};
+use crate::state::{Contributions, State};
+use crate::{SolverOptions, Verbosity};
+use ndarray::Array1;
+use quantity::{KELVIN, Moles, PASCAL, Pressure, Temperature};
+use std::fmt;
+use std::sync::Arc;
+
+const MAX_ITER: usize = 50;
+const TOL: f64 = 1e-10;
+const INITIAL_STEP: f64 = 0.02;
+const MAX_STEP: f64 = 0.1;
+const MAX_STEP_REDUCTIONS: usize = 8;
+
+/// # Cricondentherm and cricondenbar
+impl<E: Residual> PhaseEquilibrium<E, 2> {
+    /// Calculate the cricondentherm, i.e., the point with the maximum
+    /// temperature on the phase envelope of a mixture with given composition.
+    ///
+    /// Along the phase envelope, the temperature is stationary if
+    /// $\sum_iw_i\left(\frac{\partial\ln\varphi_i(\mathbf{z})}{\partial p}-\frac{\partial\ln\varphi_i(\mathbf{w})}{\partial p}\right)=0$,
+    /// with the composition of the mixture $\mathbf{z}$ and the composition of the
+    /// incipient phase $\mathbf{w}$. The condition is solved directly with a secant
+    /// iteration in the pressure. The iteration is started from `initial_state` or
+    /// from the dew point at the critical temperature of the mixture. Whether the
+    /// cricondentherm is searched on the bubble or on the dew point line is
+    /// determined by the initial state.
+    pub fn cricondentherm(
+        eos: &Arc<E>,
+        moles: &Moles<Array1<f64>>,
+        initial_state: Option<&Self>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let moles = eos.validate_moles(Some(moles))?;
+        let molefracs = (&moles / moles.sum()).into_value();
+        let initial_state = match initial_state {
+            Some(vle) => vle.clone(),
+            None => {
+                let cp = State::critical_point(eos, Some(&moles), None, SolverOptions::default())?;
+                Self::dew_point(
+                    eos,
+                    cp.temperature,
+                    &molefracs,
+                    None,
+                    None,
+                    Default::default(),
+                )?
+            }
+        };
+        Self::phase_envelope_extremum(
+            "cricondentherm",
+            &molefracs,
+            initial_state,
+            |s| s.pressure(Contributions::Total),
+            |p: Pressure, x| p * x.exp(),
+            |feed, incipient| {
+                let p = feed.pressure(Contributions::Total);
+                ((feed.dln_phi_dp() - incipient.dln_phi_dp()) * p)
+                    .into_value()
+                    .dot(&incipient.molefracs)
+            },
+            options,
+        )
+    }
+
+    /// Calculate the cricondenbar, i.e., the point with the maximum
+    /// pressure on the phase envelope of a mixture with given composition.
+    ///
+    /// Along the phase envelope, the pressure is stationary if
+    /// $\sum_iw_i\left(\frac{\partial\ln\varphi_i(\mathbf{z})}{\partial T}-\frac{\partial\ln\varphi_i(\mathbf{w})}{\partial T}\right)=0$,
+    /// with the composition of the mixture $\mathbf{z}$ and the composition of the
+    /// incipient phase $\mathbf{w}$. The condition is solved directly with a secant
+    /// iteration in the temperature. The iteration is started from `initial_state` or
+    /// from the bubble point at 90% of the critical temperature of the mixture. For
+    /// mixtures with a cricondenbar on the dew point line (e.g., gas condensates), a
+    /// dew point has to be provided as initial state.
+    pub fn cricondenbar(
+        eos: &Arc<E>,
+        moles: &Moles<Array1<f64>>,
+        initial_state: Option<&Self>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let moles = eos.validate_moles(Some(moles))?;
+        let molefracs = (&moles / moles.sum()).into_value();
+        let initial_state = match initial_state {
+            Some(vle) => vle.clone(),
+            None => {
+                let cp = State::critical_point(eos, Some(&moles), None, SolverOptions::default())?;
+                Self::bubble_point(
+                    eos,
+                    0.9 * cp.temperature,
+                    &molefracs,
+                    None,
+                    None,
+                    Default::default(),
+                )?
+            }
+        };
+        Self::phase_envelope_extremum(
+            "cricondenbar",
+            &molefracs,
+            initial_state,
+            |s| s.temperature,
+            |t: Temperature, x| t * x.exp(),
+            |feed, incipient| {
+                ((feed.dln_phi_dt() - incipient.dln_phi_dt()) * feed.temperature)
+                    .into_value()
+                    .dot(&incipient.molefracs)
+            },
+            options,
+        )
+    }
+
+    /// Secant iteration in the logarithm of the specified temperature or pressure
+    /// for a stationary point along the phase envelope.
+    fn phase_envelope_extremum<TP: TemperatureOrPressure + fmt::Display>(
+        solver: &'static str,
+        molefracs: &Array1<f64>,
+        initial_state: Self,
+        variable: impl Fn(&State<E>) -> TP,
+        scale: impl Fn(TP, f64) -> TP,
+        condition: impl Fn(&State<E>, &State<E>) -> f64,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER, TOL);
+
+        // the phase with the specified composition determines the side of the envelope
+        let distance = |s: &State<E>| (&s.molefracs - molefracs).mapv(f64::abs).sum();
+        let bubble = distance(initial_state.liquid()) < distance(initial_state.vapor());
+        let split = |vle: &Self| {
+            if bubble {
+                (vle.liquid().clone(), vle.vapor().clone())
+            } else {
+                (vle.vapor().clone(), vle.liquid().clone())
+            }
+        };
+
+        let (mut feed, mut incipient) = split(&initial_state);
+        let var_0 = variable(&feed);
+        let mut x_old = 0.0;
+        let mut g_old = condition(&feed, &incipient);
+        let mut dx = INITIAL_STEP;
+
+        log_iter!(
+            verbosity,
+            " iter |    residual    | {:^16} ",
+            TP::IDENTIFIER
+        );
+        log_iter!(verbosity, "{:-<44}", "");
+        log_iter!(
+            verbosity,
+            " {:4} | {:14.8e} | {:12.8}",
+            0,
+            g_old.abs(),
+            var_0
+        );
+        for i in 1..=max_iter {
+            // calculate the next point on the phase envelope
+            let mut vle = None;
+            for _ in 0..MAX_STEP_REDUCTIONS {
+                vle = PhaseEquilibrium::bubble_dew_point(
+                    &feed.eos,
+                    scale(var_0, x_old + dx),
+                    Some(TP::from_state(&feed)),
+                    molefracs,
+                    Some(&incipient.molefracs),
+                    bubble,
+                    Default::default(),
+                )
+                .ok();
+                if vle.is_some() {
+                    break;
+                }
+                dx *= 0.5;
+            }
+            let Some(vle) = vle else {
+                report_result(solver, i, false);
+                return Err(FeosError::IterationFailed(
+                    SolverFailure::new(solver)
+                        .iterations(i)
+                        .temperature(feed.temperature)
+                        .pressure(feed.pressure(Contributions::Total)),
+                ));
+            };
+            (feed, incipient) = split(&vle);
+            let x = x_old + dx;
+            let g = condition(&feed, &incipient);
+            log_iter!(
+                verbosity,
+                " {:4} | {:14.8e} | {:12.8}",
+                i,
+                g.abs(),
+                variable(&feed)
+            );
+            if is_observed() {
+                report_iteration(
+                    solver,
+                    i,
+                    g.abs(),
+                    &[
+                        feed.temperature.convert_to(KELVIN),
+                        feed.pressure(Contributions::Total).convert_to(PASCAL),
+                    ],
+                );
+            }
+            if dx.abs() < tol {
+                report_result(solver, i, true);
+                log_result!(
+                    verbosity,
+                    "{}: calculation converged in {} step(s)\n",
+                    solver,
+                    i
+                );
+                return Ok(vle);
+            }
+
+            // secant step
+            dx = (-g * (x - x_old) / (g - g_old)).clamp(-MAX_STEP, MAX_STEP);
+            x_old = x;
+            g_old = g;
+        }
+        report_result(solver, max_iter, false);
+        Err(FeosError::NotConverged(
+            SolverFailure::new(solver)
+                .iterations(max_iter)
+                .residual(g_old.abs())
+                .temperature(feed.temperature)
+                .pressure(feed.pressure(Contributions::Total)),
+        ))
+    }
+}
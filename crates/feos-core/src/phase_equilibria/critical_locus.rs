@@ -0,0 +1,210 @@
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult};
+use crate::state::{State, StateVec};
+use crate::{Contributions, ReferenceSystem, SolverOptions};
+use ndarray::Array1;
+use quantity::{Moles, Pressure};
+use std::sync::Arc;
+
+const DEFAULT_POINTS: usize = 51;
+const MAX_STEP_REDUCTIONS: usize = 6;
+const MAX_RELATIVE_CHANGE: f64 = 0.1;
+
+/// Classification of the phase behavior of a binary mixture according
+/// to van Konynenburg and Scott.
+///
+/// The classification is based on the vapor-liquid critical line only.
+/// Types that differ only in their liquid-liquid critical lines (like
+/// types I and II) can not be distinguished.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhaseBehaviorType {
+    /// The critical line continuously connects the critical points
+    /// of the pure components (types I and II).
+    Continuous,
+    /// The critical line is interrupted and one branch rises to high
+    /// pressures (type III).
+    TypeIII,
+    /// The critical line is interrupted and both branches end in
+    /// critical endpoints (types IV and V).
+    TypeIVOrV,
+}
+
+/// Reason for the termination of a branch of the critical line.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BranchEnd {
+    PureComponent,
+    MaxPressure,
+    CriticalEndpoint,
+}
+
+/// The critical locus of a binary mixture.
+pub struct CriticalLocus<E> {
+    /// The branches of the critical line, each starting at the
+    /// critical point of a pure component.
+    pub branches: Vec<Vec<State<E>>>,
+    pub phase_behavior: PhaseBehaviorType,
+}
+
+impl<E> Clone for CriticalLocus<E> {
+    fn clone(&self) -> Self {
+        Self {
+            branches: self.branches.clone(),
+            phase_behavior: self.phase_behavior,
+        }
+    }
+}
+
+impl<E: Residual> CriticalLocus<E> {
+    /// Trace the critical line of a binary mixture starting at the
+    /// critical points of the pure components.
+    ///
+    /// The critical line is traced by a continuation in the composition
+    /// with an adaptive step size. A branch terminates, if it reaches the
+    /// critical point of the other component, if the pressure exceeds
+    /// `max_pressure`, or if the critical points become unstable with
+    /// respect to the formation of an additional phase (critical endpoint).
+    pub fn binary(
+        eos: &Arc<E>,
+        npoints: Option<usize>,
+        max_pressure: Option<Pressure>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        if eos.components() != 2 {
+            return Err(FeosError::IncompatibleComponents(eos.components(), 2));
+        }
+        let npoints = npoints.unwrap_or(DEFAULT_POINTS);
+        let pure = State::critical_point_pure(eos, None, options)?;
+        let pure = [
+            Self::pure_critical_point(eos, &pure[0], 0)?,
+            Self::pure_critical_point(eos, &pure[1], 1)?,
+        ];
+
+        let (branch, end) = Self::trace_branch(eos, &pure, 0, npoints, max_pressure, options)?;
+        if end == BranchEnd::PureComponent {
+            return Ok(Self {
+                branches: vec![branch],
+                phase_behavior: PhaseBehaviorType::Continuous,
+            });
+        }
+        let (branch2, end2) = Self::trace_branch(eos, &pure, 1, npoints, max_pressure, options)?;
+        let phase_behavior = match (end, end2) {
+            (_, BranchEnd::PureComponent) => {
+                return Ok(Self {
+                    branches: vec![branch2],
+                    phase_behavior: PhaseBehaviorType::Continuous,
+                });
+            }
+            (BranchEnd::MaxPressure, _) | (_, BranchEnd::MaxPressure) => PhaseBehaviorType::TypeIII,
+            _ => PhaseBehaviorType::TypeIVOrV,
+        };
+        Ok(Self {
+            branches: vec![branch, branch2],
+            phase_behavior,
+        })
+    }
+
+    /// Return the critical point of a pure component as state of the mixture.
+    fn pure_critical_point(
+        eos: &Arc<E>,
+        state: &State<E>,
+        component: usize,
+    ) -> FeosResult<State<E>> {
+        let mut moles = Array1::zeros(2);
+        moles[component] = 1.0;
+        let moles = Moles::from_reduced(moles);
+        State::new_nvt(eos, state.temperature, moles.sum() / state.density, &moles)
+    }
+
+    /// Trace a single branch of the critical line starting at the critical
+    /// point of component `start`.
+    fn trace_branch(
+        eos: &Arc<E>,
+        pure: &[State<E>; 2],
+        start: usize,
+        npoints: usize,
+        max_pressure: Option<Pressure>,
+        options: SolverOptions,
+    ) -> FeosResult<(Vec<State<E>>, BranchEnd)> {
+        let max_step = 1.0 / (npoints - 1) as f64;
+        let min_step = max_step * 0.5f64.powi(MAX_STEP_REDUCTIONS as i32);
+        let mut branch = vec![pure[start].clone()];
+
+        // mole fraction of the component that is added along the branch
+        let mut x = 0.0;
+        let mut step = max_step;
+        loop {
+            let x_new = x + step;
+            let len = branch.len();
+            let (last, before_last) = (&branch[len - 1], len.checked_sub(2).map(|i| &branch[i]));
+
+            // the critical point of the other component is reached
+            if x_new >= 1.0 - 0.5 * min_step {
+                let end = &pure[1 - start];
+                if is_continuous(last, end) {
+                    branch.push(end.clone());
+                    return Ok((branch, BranchEnd::PureComponent));
+                }
+                step = 0.5 * (1.0 - x);
+                if step < min_step {
+                    return Ok((branch, BranchEnd::CriticalEndpoint));
+                }
+                continue;
+            }
+
+            // linear extrapolation of temperature and density from the last points
+            let (t0, rho0) = match before_last {
+                Some(s) => {
+                    let f = step / (x - molefrac(s, start));
+                    (
+                        last.temperature + (last.temperature - s.temperature) * f,
+                        last.density + (last.density - s.density) * f,
+                    )
+                }
+                None => (last.temperature, last.density),
+            };
+
+            let mut moles = Array1::from_elem(2, x_new);
+            moles[start] = 1.0 - x_new;
+            let moles = Moles::from_reduced(moles);
+            let state = State::critical_point_hkm(eos, &moles, t0, Some(rho0), options)
+                .ok()
+                .filter(|s| is_continuous(last, s));
+            if let Some(s) = state {
+                let p = s.pressure(Contributions::Total);
+                if max_pressure.is_some_and(|p_max| p > p_max) {
+                    return Ok((branch, BranchEnd::MaxPressure));
+                }
+                // the stability analysis converges slowly towards the trivial solution
+                // at critical points, therefore only a found phase split is considered
+                let unstable = matches!(s.is_stable(SolverOptions::default()), Ok(false));
+                if p.to_reduced() > 0.0 && !unstable {
+                    branch.push(s);
+                    x = x_new;
+                    step = f64::min(2.0 * step, max_step);
+                    continue;
+                }
+            }
+            step *= 0.5;
+            if step < min_step {
+                return Ok((branch, BranchEnd::CriticalEndpoint));
+            }
+        }
+    }
+
+    /// Return the states of a branch of the critical line.
+    pub fn branch(&self, i: usize) -> StateVec<'_, E> {
+        self.branches[i].iter().collect()
+    }
+}
+
+/// Mole fraction of the component that is added along a branch starting at `start`.
+fn molefrac<E>(state: &State<E>, start: usize) -> f64 {
+    state.molefracs[1 - start]
+}
+
+/// Check whether two neighboring critical points belong to the same branch.
+fn is_continuous<E>(s1: &State<E>, s2: &State<E>) -> bool {
+    let rel = |x1: f64, x2: f64| ((x2 - x1) / x1).abs() < MAX_RELATIVE_CHANGE;
+    rel(s1.temperature.to_reduced(), s2.temperature.to_reduced())
+        && rel(s1.density.to_reduced(), s2.density.to_reduced())
+}
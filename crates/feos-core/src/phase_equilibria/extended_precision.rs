@@ -0,0 +1,242 @@
+//! Bubble and dew points in double-double precision.
+//!
+//! Close to the critical point the two phases of a bubble or dew point
+//! become similar and the Jacobian of the phase equilibrium conditions
+//! becomes singular. The successive substitution and Newton iterations in
+//! `f64` then stall, because the residuals are dominated by cancellation.
+//! The Newton iteration in this module evaluates the Helmholtz energy in
+//! [DoubleDouble] precision and converges closer to the critical point.
+use super::PhaseEquilibrium;
+use crate::DoubleDouble;
+use crate::ReferenceSystem;
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::state::{State, StateHD, TPSpec};
+use ndarray::{Array1, Array2};
+use num_dual::{Dual, DualNum};
+use num_traits::{Float, One, Zero};
+use quantity::{Moles, Pressure, Temperature, Volume};
+use std::sync::Arc;
+
+const MAX_ITER: usize = 50;
+const TOL: f64 = 1e-26;
+const MAX_STEP: f64 = 0.1;
+
+type DD = DoubleDouble;
+
+impl<E: Residual> PhaseEquilibrium<E, 2> {
+    /// Calculate a bubble point (`bubble = true`) or dew point of the
+    /// phase with the given composition in double-double precision,
+    /// starting from the phase equilibrium `initial`, e.g., the previous
+    /// point of a phase envelope.
+    pub(crate) fn bubble_dew_point_extended_precision(
+        eos: &Arc<E>,
+        temperature_or_pressure: TPSpec,
+        molefracs: &Array1<f64>,
+        bubble: bool,
+        initial: &Self,
+    ) -> FeosResult<Self> {
+        let (fixed, incipient) = if bubble {
+            (initial.liquid(), initial.vapor())
+        } else {
+            (initial.vapor(), initial.liquid())
+        };
+        let n = eos.components();
+
+        // variables: ln(rho) of the phase with given composition, ln(rho_i)
+        // of the incipient phase and, for a given pressure, ln(T)
+        let mut x = vec![DD::from(fixed.density.to_reduced().ln())];
+        x.extend(
+            incipient
+                .partial_density
+                .to_reduced()
+                .iter()
+                .map(|&rho| DD::from(rho.ln())),
+        );
+        let spec = match temperature_or_pressure {
+            TPSpec::Temperature(t) => Spec::Temperature(DD::from(t.to_reduced())),
+            TPSpec::Pressure(p) => {
+                x.push(DD::from(fixed.temperature.to_reduced().ln()));
+                Spec::Pressure(p.to_reduced())
+            }
+        };
+
+        let mut residual = f64::INFINITY;
+        for iteration in 1..=MAX_ITER {
+            // Jacobian from forward derivatives with respect to each variable
+            let mut r = vec![DD::zero(); x.len()];
+            let mut jacobian = Array2::from_elem((x.len(), x.len()), DD::zero());
+            for j in 0..x.len() {
+                let xj: Vec<_> = x
+                    .iter()
+                    .enumerate()
+                    .map(|(k, &x)| Dual::new(x, if k == j { DD::one() } else { DD::zero() }))
+                    .collect();
+                let rj = objective(eos, &xj, molefracs, &spec);
+                for (i, rj) in rj.into_iter().enumerate() {
+                    r[i] = rj.re;
+                    jacobian[(i, j)] = rj.eps;
+                }
+            }
+            residual = r.iter().map(|r| r.hi().abs()).fold(0.0, f64::max);
+
+            let mut delta = solve(jacobian, r).ok_or_else(|| {
+                FeosError::IterationFailed(failure(iteration, residual, &x, n, &spec))
+            })?;
+            let max_delta = delta.iter().map(|d| d.hi().abs()).fold(0.0, f64::max);
+            if max_delta > MAX_STEP {
+                delta.iter_mut().for_each(|d| *d *= MAX_STEP / max_delta);
+            }
+            x.iter_mut().zip(&delta).for_each(|(x, d)| *x -= *d);
+            if !x.iter().all(|x| x.is_finite()) {
+                break;
+            }
+            if residual < TOL || max_delta < TOL {
+                return phase_equilibrium(eos, &x, molefracs, &spec, bubble);
+            }
+        }
+        Err(FeosError::NotConverged(failure(
+            MAX_ITER, residual, &x, n, &spec,
+        )))
+    }
+}
+
+enum Spec {
+    Temperature(DD),
+    Pressure(f64),
+}
+
+/// Equality of the chemical potentials and pressures of both phases and,
+/// for a given pressure, the pressure specification.
+fn objective<E: Residual>(
+    eos: &Arc<E>,
+    x: &[Dual<DD, f64>],
+    molefracs: &Array1<f64>,
+    spec: &Spec,
+) -> Vec<Dual<DD, f64>> {
+    let n = eos.components();
+    let t = match spec {
+        Spec::Temperature(t) => Dual::from_re(*t),
+        Spec::Pressure(_) => x[n + 1].exp(),
+    };
+    let rho = x[0].exp();
+    let fixed: Vec<_> = molefracs.iter().map(|&x| rho * x).collect();
+    let incipient: Vec<_> = x[1..=n].iter().map(|x| x.exp()).collect();
+    let (mu_fixed, p_fixed) = chemical_potential_and_pressure(eos, t, &fixed);
+    let (mu_incipient, p_incipient) = chemical_potential_and_pressure(eos, t, &incipient);
+
+    let mut r: Vec<_> = (0..n)
+        .map(|i| mu_incipient[i] + x[i + 1] - mu_fixed[i] - fixed[i].ln())
+        .collect();
+    r.push((p_incipient - p_fixed) / rho);
+    if let Spec::Pressure(p) = spec {
+        r.push((p_fixed * t - *p) / (rho * t));
+    }
+    r
+}
+
+/// Residual chemical potentials and pressure (both divided by kT) for the
+/// given partial densities.
+fn chemical_potential_and_pressure<E: Residual, D: DualNum<f64> + Copy>(
+    eos: &Arc<E>,
+    temperature: D,
+    partial_density: &[D],
+) -> (Vec<D>, D) {
+    let n = partial_density.len();
+    let a = |k: usize| {
+        let seed = |i: usize, x: D| Dual::new(x, if i == k { D::one() } else { D::zero() });
+        let v = seed(n, D::one());
+        let m = partial_density
+            .iter()
+            .enumerate()
+            .map(|(i, &rho)| seed(i, rho))
+            .collect();
+        let state = StateHD::new(Dual::from_re(temperature), v, m);
+        eos.residual_helmholtz_energy(&state).eps
+    };
+    let mu = (0..n).map(a).collect();
+    let p = partial_density.iter().fold(-a(n), |p, &rho| p + rho);
+    (mu, p)
+}
+
+/// Solve the linear system by Gaussian elimination with partial pivoting.
+fn solve(mut a: Array2<DD>, mut b: Vec<DD>) -> Option<Vec<DD>> {
+    let n = b.len();
+    for k in 0..n {
+        let pivot =
+            (k..n).max_by(|&i, &j| a[(i, k)].abs().partial_cmp(&a[(j, k)].abs()).unwrap())?;
+        if a[(pivot, k)].is_zero() {
+            return None;
+        }
+        for j in 0..n {
+            a.swap((k, j), (pivot, j));
+        }
+        b.swap(k, pivot);
+        for i in k + 1..n {
+            let f = a[(i, k)] / a[(k, k)];
+            for j in k..n {
+                a[(i, j)] = a[(i, j)] - f * a[(k, j)];
+            }
+            b[i] = b[i] - f * b[k];
+        }
+    }
+    for k in (0..n).rev() {
+        let s = (k + 1..n).fold(b[k], |s, j| s - a[(k, j)] * b[j]);
+        b[k] = s / a[(k, k)];
+    }
+    Some(b)
+}
+
+fn temperature(x: &[DD], n: usize, spec: &Spec) -> f64 {
+    match spec {
+        Spec::Temperature(t) => t.hi(),
+        Spec::Pressure(_) => x[n + 1].exp().hi(),
+    }
+}
+
+fn failure(iterations: usize, residual: f64, x: &[DD], n: usize, spec: &Spec) -> SolverFailure {
+    let failure = SolverFailure::new("extended precision bubble/dew point")
+        .iterations(iterations)
+        .residual(residual)
+        .temperature(Temperature::from_reduced(temperature(x, n, spec)));
+    match spec {
+        Spec::Pressure(p) => failure.pressure(Pressure::from_reduced(*p)),
+        Spec::Temperature(_) => failure,
+    }
+}
+
+fn phase_equilibrium<E: Residual>(
+    eos: &Arc<E>,
+    x: &[DD],
+    molefracs: &Array1<f64>,
+    spec: &Spec,
+    bubble: bool,
+) -> FeosResult<PhaseEquilibrium<E, 2>> {
+    let n = eos.components();
+    let t = Temperature::from_reduced(temperature(x, n, spec));
+    let rho = x[0].exp().hi();
+    let fixed = State::new_nvt(
+        eos,
+        t,
+        Volume::from_reduced(1.0),
+        &Moles::from_reduced(molefracs * rho),
+    )?;
+    let incipient = State::new_nvt(
+        eos,
+        t,
+        Volume::from_reduced(1.0),
+        &Moles::from_reduced(x[1..=n].iter().map(|&x| x.exp().hi()).collect()),
+    )?;
+    let trivial = (&incipient.molefracs - &fixed.molefracs)
+        .iter()
+        .all(|dx| dx.abs() < 1e-12)
+        && ((incipient.density / fixed.density).into_value() - 1.0).abs() < 1e-12;
+    if trivial {
+        return Err(FeosError::TrivialSolution);
+    }
+    Ok(if bubble {
+        PhaseEquilibrium::from_states(incipient, fixed)
+    } else {
+        PhaseEquilibrium::from_states(fixed, incipient)
+    })
+}
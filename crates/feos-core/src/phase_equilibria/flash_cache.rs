@@ -0,0 +1,360 @@
+use super::{FlashSpecification, IsolinePoint, PhaseEquilibrium};
+use crate::equation_of_state::{IdealGas, Residual};
+use crate::errors::{FeosError, FeosResult};
+use crate::state::State;
+use crate::{ReferenceSystem, SolverOptions};
+use ndarray::Array1;
+use quantity::{Moles, Temperature, Volume};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+
+/// Identity of the model, discriminant of the specification, quantized
+/// specification and quantized composition.
+type Key = (usize, u8, [i64; 2], Vec<i64>);
+
+/// Memoization of flash calculations with arbitrary
+/// [FlashSpecification]s.
+///
+/// Entries are keyed by the model, the specification and the composition
+/// of the feed. The specified state variables are quantized on a logarithmic
+/// scale with a relative spacing of `resolution` and the mole fractions and
+/// vapor fractions are quantized to multiples of `resolution`. Queries with
+/// exactly the same specification and feed are answered from the cache,
+/// queries that fall into the same quantization cell are warm started from
+/// the cached result. If the warm start fails, the flash is repeated without
+/// initialization. If the number of entries exceeds the capacity, the least
+/// recently used entry is removed.
+///
+/// Models are identified by their [Arc] and a single cache can be shared
+/// between threads and models, e.g., by all unit operations of a flowsheet.
+/// The cache only stores the temperatures, volumes and moles of the phases
+/// and a weak reference to the model, so that it does not keep the models
+/// alive. Entries of models that have been dropped are never reused.
+pub struct FlashCache<E> {
+    resolution: f64,
+    capacity: usize,
+    options: SolverOptions,
+    entries: Mutex<Entries<E>>,
+}
+
+struct Entry<E> {
+    // the weak reference also prevents the reuse of the address of the model
+    model: Weak<E>,
+    specification: FlashSpecification,
+    feed: Array1<f64>,
+    result: Record,
+    last_used: u64,
+}
+
+/// Temperature, volume and moles (in reduced units) of a phase.
+struct PhaseRecord {
+    temperature: f64,
+    volume: f64,
+    moles: Array1<f64>,
+}
+
+impl PhaseRecord {
+    fn new<E>(state: &State<E>) -> Self {
+        Self {
+            temperature: state.temperature.to_reduced(),
+            volume: state.volume.to_reduced(),
+            moles: state.moles.to_reduced(),
+        }
+    }
+
+    fn state<E: Residual>(&self, eos: &Arc<E>) -> State<E> {
+        State::new_nvt_unchecked(
+            eos,
+            Temperature::from_reduced(self.temperature),
+            Volume::from_reduced(self.volume),
+            &Moles::from_reduced(self.moles.clone()),
+        )
+    }
+}
+
+/// Cached result of a flash calculation.
+enum Record {
+    SinglePhase(PhaseRecord),
+    TwoPhase([PhaseRecord; 2], f64),
+}
+
+impl Record {
+    fn new<E>(point: &IsolinePoint<E>) -> Self {
+        match point {
+            IsolinePoint::SinglePhase(state) => Self::SinglePhase(PhaseRecord::new(state)),
+            IsolinePoint::TwoPhase(vle, beta) => Self::TwoPhase(
+                [
+                    PhaseRecord::new(vle.vapor()),
+                    PhaseRecord::new(vle.liquid()),
+                ],
+                *beta,
+            ),
+        }
+    }
+
+    fn point<E: Residual>(&self, eos: &Arc<E>) -> IsolinePoint<E> {
+        match self {
+            Self::SinglePhase(phase) => IsolinePoint::SinglePhase(phase.state(eos)),
+            Self::TwoPhase([vapor, liquid], beta) => IsolinePoint::TwoPhase(
+                PhaseEquilibrium([vapor.state(eos), liquid.state(eos)]),
+                *beta,
+            ),
+        }
+    }
+}
+
+struct Entries<E> {
+    map: HashMap<Key, Entry<E>>,
+    tick: u64,
+    hit: u64,
+    warm: u64,
+    miss: u64,
+}
+
+impl<E: Residual + IdealGas> FlashCache<E> {
+    /// Create an empty cache.
+    pub fn new(resolution: f64, capacity: usize) -> FeosResult<Self> {
+        if !(resolution > 0.0 && capacity > 0) {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "the resolution and capacity of the cache have to be positive.",
+            )));
+        }
+        Ok(Self {
+            resolution,
+            capacity,
+            options: SolverOptions::default(),
+            entries: Mutex::new(Entries {
+                map: HashMap::with_capacity(capacity),
+                tick: 0,
+                hit: 0,
+                warm: 0,
+                miss: 0,
+            }),
+        })
+    }
+
+    /// Use the given options for the flash calculations.
+    pub fn with_options(mut self, options: SolverOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Flash calculation analogous to [PhaseEquilibrium::flash] that is
+    /// answered from or warm started by the cache.
+    pub fn flash(
+        &self,
+        eos: &Arc<E>,
+        specification: FlashSpecification,
+        feed: &Moles<Array1<f64>>,
+    ) -> FeosResult<IsolinePoint<E>> {
+        let key = self.key(eos, specification, feed);
+        let feed_reduced = feed.to_reduced();
+
+        let initial_point = {
+            let mut entries = self.entries.lock().unwrap();
+            entries.tick += 1;
+            let tick = entries.tick;
+            match entries.map.get_mut(&key) {
+                Some(entry) if entry.model.strong_count() == 0 => {
+                    entries.map.remove(&key);
+                    None
+                }
+                Some(entry) => {
+                    entry.last_used = tick;
+                    let result = entry.result.point(eos);
+                    if entry.specification == specification && entry.feed == feed_reduced {
+                        entries.hit += 1;
+                        return Ok(result);
+                    }
+                    entries.warm += 1;
+                    Some(result)
+                }
+                None => None,
+            }
+        };
+        if initial_point.is_none() {
+            self.entries.lock().unwrap().miss += 1;
+        }
+
+        // the lock is released during the calculation
+        let flash = |initial_point| {
+            PhaseEquilibrium::flash_initialized(
+                eos,
+                specification,
+                feed,
+                None,
+                initial_point,
+                self.options,
+            )
+        };
+        let result = match initial_point {
+            Some(point) => flash(Some(&point)).or_else(|_| flash(None)),
+            None => flash(None),
+        }?;
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.map.len() >= self.capacity
+            && !entries.map.contains_key(&key)
+            && let Some(oldest) = entries
+                .map
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(k, _)| k.clone())
+        {
+            entries.map.remove(&oldest);
+        }
+        let last_used = entries.tick;
+        entries.map.insert(
+            key,
+            Entry {
+                model: Arc::downgrade(eos),
+                specification,
+                feed: feed_reduced,
+                result: Record::new(&result),
+                last_used,
+            },
+        );
+        Ok(result)
+    }
+
+    fn key(
+        &self,
+        eos: &Arc<E>,
+        specification: FlashSpecification,
+        feed: &Moles<Array1<f64>>,
+    ) -> Key {
+        // the sign is encoded in the last bit
+        let log = |x: f64| {
+            if x == 0.0 {
+                i64::MIN
+            } else {
+                2 * (x.abs().ln() / self.resolution).round() as i64 + (x < 0.0) as i64
+            }
+        };
+        let lin = |x: f64| (x / self.resolution).round() as i64;
+        let (discriminant, values) = match specification {
+            FlashSpecification::TP(t, p) => (0, [log(t.to_reduced()), log(p.to_reduced())]),
+            FlashSpecification::TBeta(t, beta) => (1, [log(t.to_reduced()), lin(beta)]),
+            FlashSpecification::PBeta(p, beta) => (2, [log(p.to_reduced()), lin(beta)]),
+            FlashSpecification::PH(p, h) => (3, [log(p.to_reduced()), log(h.to_reduced())]),
+            FlashSpecification::PS(p, s) => (4, [log(p.to_reduced()), log(s.to_reduced())]),
+            FlashSpecification::TV(t, v) => (5, [log(t.to_reduced()), log(v.to_reduced())]),
+            FlashSpecification::UV(u, v) => (6, [log(u.to_reduced()), log(v.to_reduced())]),
+        };
+        let z = feed.to_reduced();
+        let z = &z / z.sum();
+        (
+            Arc::as_ptr(eos) as *const () as usize,
+            discriminant,
+            values,
+            z.iter().map(|&z| lin(z)).collect(),
+        )
+    }
+
+    /// Number of cached flash results.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().map.len()
+    }
+
+    /// Returns `true` if the cache contains no flash results.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of queries that were answered from the cache, number of
+    /// queries that were warm started from a cached result and number
+    /// of queries that required a calculation without initialization.
+    pub fn statistics(&self) -> (u64, u64, u64) {
+        let entries = self.entries.lock().unwrap();
+        (entries.hit, entries.warm, entries.miss)
+    }
+
+    /// Remove all entries from the cache.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().map.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::PengRobinson;
+    use crate::equation_of_state::EquationOfState;
+    use crate::state::Contributions;
+    use crate::test_utils::{ConstantHeatCapacity, propane_butane};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{BAR, KELVIN, MOL};
+
+    #[test]
+    fn flash_cache() -> FeosResult<()> {
+        let eos = Arc::new(EquationOfState::new(
+            Arc::new(ConstantHeatCapacity::new(2)),
+            Arc::new(propane_butane()?),
+        ));
+        let cache = FlashCache::new(1e-3, 2)?;
+        let feed = arr1(&[0.4, 0.6]) * MOL;
+        let p = 5.0 * BAR;
+        let tp = FlashSpecification::TP(300.0 * KELVIN, p);
+
+        let point = cache.flash(&eos, tp, &feed)?;
+        assert_eq!(cache.statistics(), (0, 0, 1));
+        let cached = cache.flash(&eos, tp, &feed)?;
+        assert_eq!(cached.vapor_fraction(), point.vapor_fraction());
+        assert_eq!(cache.statistics(), (1, 0, 1));
+
+        // nearby specifications are warm started and agree with a cold flash
+        let nearby = FlashSpecification::TP(300.01 * KELVIN, p);
+        let warm = cache.flash(&eos, nearby, &feed)?;
+        let cold = PhaseEquilibrium::flash(&eos, nearby, &feed, None, SolverOptions::default())?;
+        assert_relative_eq!(
+            warm.vapor_fraction().unwrap(),
+            cold.vapor_fraction().unwrap(),
+            max_relative = 1e-8
+        );
+        assert_eq!(cache.statistics(), (1, 1, 1));
+
+        let h = point.molar_enthalpy(Contributions::Total);
+        let ph = cache.flash(&eos, FlashSpecification::PH(p, h), &feed)?;
+        assert_relative_eq!(ph.temperature(), 300.0 * KELVIN, max_relative = 1e-7);
+        let ph = cache.flash(&eos, FlashSpecification::PH(p, h * 1.0001), &feed)?;
+        assert_relative_eq!(
+            ph.molar_enthalpy(Contributions::Total),
+            h * 1.0001,
+            max_relative = 1e-8
+        );
+        assert_eq!(cache.statistics(), (1, 2, 2));
+        assert_eq!(cache.len(), 2);
+
+        // the least recently used entry (Tp-flash) is removed
+        cache.flash(&eos, FlashSpecification::TP(310.0 * KELVIN, p), &feed)?;
+        assert_eq!(cache.len(), 2);
+        cache.flash(&eos, tp, &feed)?;
+        assert_eq!(cache.statistics(), (1, 2, 4));
+
+        // entries of other models are not reused
+        let other = Arc::new(EquationOfState::new(
+            eos.ideal_gas.clone(),
+            eos.residual.clone(),
+        ));
+        cache.flash(&other, tp, &feed)?;
+        assert_eq!(cache.statistics(), (1, 2, 5));
+
+        // the cache does not keep models alive
+        let model = Arc::downgrade(&other);
+        drop(other);
+        assert!(model.upgrade().is_none());
+        let other = Arc::new(EquationOfState::new(
+            eos.ideal_gas.clone(),
+            eos.residual.clone(),
+        ));
+        cache.flash(&other, tp, &feed)?;
+        assert_eq!(cache.statistics(), (1, 2, 6));
+        cache.clear();
+        assert!(cache.is_empty());
+        assert!(
+            FlashCache::<EquationOfState<ConstantHeatCapacity, PengRobinson>>::new(0.0, 2).is_err()
+        );
+        Ok(())
+    }
+}
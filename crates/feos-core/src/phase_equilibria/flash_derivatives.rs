@@ -0,0 +1,242 @@
+use super::PhaseEquilibrium;
+use crate::ReferenceSystem;
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult};
+use crate::state::State;
+use ndarray::{Array1, Array2};
+use num_dual::linalg::LU;
+use quantity::{
+    _Dimensionless, _Moles, _Pressure, _Temperature, Moles, Pressure, Quantity, Temperature,
+};
+use typenum::Negate;
+
+/// Derivatives of the results of a Tp-flash with respect to a single
+/// specification or model parameter.
+#[derive(Clone)]
+pub struct FlashDerivatives<U> {
+    /// Derivative of the molar vapor fraction $\beta$.
+    pub vapor_fraction: Quantity<f64, U>,
+    /// Derivatives of the mole fractions $x_i$ of the liquid phase.
+    pub liquid_molefracs: Quantity<Array1<f64>, U>,
+    /// Derivatives of the mole fractions $y_i$ of the vapor phase.
+    pub vapor_molefracs: Quantity<Array1<f64>, U>,
+}
+
+/// # Flash derivatives
+impl<E: Residual> PhaseEquilibrium<E, 2> {
+    /// Derivatives of the flash results with respect to temperature at
+    /// constant pressure and feed.
+    pub fn flash_derivatives_temperature(
+        &self,
+    ) -> FeosResult<FlashDerivatives<Negate<_Temperature>>> {
+        let t = Temperature::from_reduced(1.0);
+        let (liquid, vapor) = (self.liquid(), self.vapor());
+        let dln_phi = ((liquid.dln_phi_dt() - vapor.dln_phi_dt()) * t).into_value();
+        let n = dln_phi.len();
+        self.implicit_derivatives(&dln_phi, &Array1::zeros(n), 1.0 / t)
+    }
+
+    /// Derivatives of the flash results with respect to pressure at
+    /// constant temperature and feed.
+    pub fn flash_derivatives_pressure(&self) -> FeosResult<FlashDerivatives<Negate<_Pressure>>> {
+        let p = Pressure::from_reduced(1.0);
+        let (liquid, vapor) = (self.liquid(), self.vapor());
+        let dln_phi = ((liquid.dln_phi_dp() - vapor.dln_phi_dp()) * p).into_value();
+        let n = dln_phi.len();
+        self.implicit_derivatives(&dln_phi, &Array1::zeros(n), 1.0 / p)
+    }
+
+    /// Derivatives of the flash results with respect to the amount of
+    /// substance of a component in the feed at constant temperature and pressure.
+    pub fn flash_derivatives_feed(
+        &self,
+        component: usize,
+    ) -> FeosResult<FlashDerivatives<Negate<_Moles>>> {
+        let n = self.liquid().molefracs.len();
+        if component >= n {
+            return Err(FeosError::IncompatibleComponents(n, component + 1));
+        }
+        let mut dfeed = Array1::zeros(n);
+        dfeed[component] = 1.0;
+        let moles = Moles::from_reduced(1.0);
+        self.implicit_derivatives(&Array1::zeros(n), &dfeed, 1.0 / moles)
+    }
+
+    /// Derivatives of the flash results with respect to an arbitrary variable
+    /// $\theta$, e.g., a model parameter, at constant temperature, pressure and feed.
+    ///
+    /// The variable enters through the derivatives of the logarithmic fugacity
+    /// coefficients of both phases $\left(\frac{\partial\ln\varphi_i}{\partial\theta}\right)_{T,p,N_i}$.
+    /// The results are given per unit of the variable.
+    pub fn flash_derivatives(
+        &self,
+        dln_phi_liquid: &Array1<f64>,
+        dln_phi_vapor: &Array1<f64>,
+    ) -> FeosResult<FlashDerivatives<_Dimensionless>> {
+        let n = self.liquid().molefracs.len();
+        self.implicit_derivatives(
+            &(dln_phi_liquid - dln_phi_vapor),
+            &Array1::zeros(n),
+            Quantity::new(1.0),
+        )
+    }
+
+    /// Apply the implicit function theorem to the equilibrium conditions
+    /// $\ln f_i^L\left(T,p,\mathbf{N}^L\right)=\ln f_i^V\left(T,p,\mathbf{N}^F-\mathbf{N}^L\right)$
+    /// at the converged solution, which results in
+    /// $\left(\mathbf{J}^L+\mathbf{J}^V\right)\frac{\mathrm{d}\mathbf{N}^L}{\mathrm{d}\theta}=\mathbf{J}^V\frac{\mathrm{d}\mathbf{N}^F}{\mathrm{d}\theta}-\frac{\partial\ln\boldsymbol{\varphi}^L}{\partial\theta}+\frac{\partial\ln\boldsymbol{\varphi}^V}{\partial\theta}$
+    /// with $J_{ij}=\left(\frac{\partial\ln f_i}{\partial N_j}\right)_{T,p}$.
+    fn implicit_derivatives<U>(
+        &self,
+        dln_phi: &Array1<f64>,
+        dfeed: &Array1<f64>,
+        unit: Quantity<f64, U>,
+    ) -> FeosResult<FlashDerivatives<U>>
+    where
+        Quantity<f64, U>: Copy,
+    {
+        let (liquid, vapor) = (self.liquid(), self.vapor());
+        let (jl, jv) = (ln_fugacity_jacobian(liquid), ln_fugacity_jacobian(vapor));
+        let rhs = jv.dot(dfeed) - dln_phi;
+        let dn_liquid = LU::new(&jl + &jv)?.solve(&rhs);
+        let dn_vapor = dfeed - &dn_liquid;
+
+        let (n_liquid, n_vapor) = (
+            liquid.total_moles.to_reduced(),
+            vapor.total_moles.to_reduced(),
+        );
+        let dmolefracs = |dn: &Array1<f64>, x: &Array1<f64>, n: f64| (dn - x * dn.sum()) / n;
+        let dx = dmolefracs(&dn_liquid, &liquid.molefracs, n_liquid);
+        let dy = dmolefracs(&dn_vapor, &vapor.molefracs, n_vapor);
+        let n_feed = n_liquid + n_vapor;
+        let dbeta = (dn_vapor.sum() - n_vapor / n_feed * dfeed.sum()) / n_feed;
+        Ok(FlashDerivatives {
+            vapor_fraction: unit * dbeta,
+            liquid_molefracs: dx * unit,
+            vapor_molefracs: dy * unit,
+        })
+    }
+}
+
+/// Derivatives of the logarithmic fugacities w.r.t. the moles in reduced units:
+/// $J_{ij}=\frac{\delta_{ij}}{N_i}-\frac{1}{N}+\left(\frac{\partial\ln\varphi_i}{\partial N_j}\right)_{T,p,N_k}$
+pub(super) fn ln_fugacity_jacobian<E: Residual>(state: &State<E>) -> Array2<f64> {
+    let moles = state.moles.to_reduced();
+    let total_moles = moles.sum();
+    let dln_phi_dnj = (state.dln_phi_dnj() * Moles::from_reduced(1.0)).into_value();
+    Array2::from_shape_fn(dln_phi_dnj.raw_dim(), |(i, j)| {
+        let ideal = if i == j { 1.0 / moles[i] } else { 0.0 };
+        dln_phi_dnj[[i, j]] + ideal - 1.0 / total_moles
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::PengRobinson;
+    use crate::test_utils::propane_butane;
+    use crate::{Contributions, SolverOptions};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{BAR, KELVIN, MOL};
+    use std::sync::Arc;
+
+    fn flash(
+        eos: &Arc<PengRobinson>,
+        t: Temperature,
+        p: Pressure,
+        feed: &Moles<Array1<f64>>,
+    ) -> FeosResult<PhaseEquilibrium<PengRobinson, 2>> {
+        PhaseEquilibrium::tp_flash(eos, t, p, feed, None, SolverOptions::default(), None)
+    }
+
+    fn vapor_fraction(vle: &PhaseEquilibrium<PengRobinson, 2>) -> f64 {
+        let (l, v) = (vle.liquid().total_moles, vle.vapor().total_moles);
+        (v / (l + v)).into_value()
+    }
+
+    #[test]
+    fn flash_derivatives() -> FeosResult<()> {
+        let eos = Arc::new(propane_butane()?);
+        let t = 330.0 * KELVIN;
+        let z = arr1(&[0.5, 0.5]);
+        let options = (SolverOptions::default(), SolverOptions::default());
+        let bubble = PhaseEquilibrium::bubble_point(&eos, t, &z, None, None, options)?;
+        let dew = PhaseEquilibrium::dew_point(&eos, t, &z, None, None, options)?;
+        let p = 0.5
+            * (bubble.vapor().pressure(Contributions::Total)
+                + dew.vapor().pressure(Contributions::Total));
+        let feed = z * MOL;
+        let vle = flash(&eos, t, p, &feed)?;
+
+        let check = |derivatives: (f64, &Array1<f64>, &Array1<f64>),
+                     plus: &PhaseEquilibrium<PengRobinson, 2>,
+                     minus: &PhaseEquilibrium<PengRobinson, 2>,
+                     h: f64| {
+            let (dbeta, dx, dy) = derivatives;
+            let fd = |f: &dyn Fn(&PhaseEquilibrium<PengRobinson, 2>) -> Array1<f64>| {
+                (f(plus) - f(minus)) / (2.0 * h)
+            };
+            assert_relative_eq!(
+                dbeta,
+                fd(&|vle| arr1(&[vapor_fraction(vle)]))[0],
+                max_relative = 1e-6,
+                epsilon = 1e-8
+            );
+            assert_relative_eq!(
+                *dx,
+                fd(&|vle| vle.liquid().molefracs.clone()),
+                max_relative = 1e-6,
+                epsilon = 1e-8
+            );
+            assert_relative_eq!(
+                *dy,
+                fd(&|vle| vle.vapor().molefracs.clone()),
+                max_relative = 1e-6,
+                epsilon = 1e-8
+            );
+        };
+
+        let h = 1e-4 * KELVIN;
+        let d = vle.flash_derivatives_temperature()?;
+        check(
+            (
+                (d.vapor_fraction * KELVIN).into_value(),
+                &(d.liquid_molefracs * KELVIN).into_value(),
+                &(d.vapor_molefracs * KELVIN).into_value(),
+            ),
+            &flash(&eos, t + h, p, &feed)?,
+            &flash(&eos, t - h, p, &feed)?,
+            1e-4,
+        );
+
+        let h = 1e-3 * BAR;
+        let d = vle.flash_derivatives_pressure()?;
+        check(
+            (
+                (d.vapor_fraction * BAR).into_value(),
+                &(d.liquid_molefracs * BAR).into_value(),
+                &(d.vapor_molefracs * BAR).into_value(),
+            ),
+            &flash(&eos, t, p + h, &feed)?,
+            &flash(&eos, t, p - h, &feed)?,
+            1e-3,
+        );
+
+        let h = 1e-5;
+        let d = vle.flash_derivatives_feed(0)?;
+        let dfeed = arr1(&[h, 0.0]) * MOL;
+        check(
+            (
+                (d.vapor_fraction * MOL).into_value(),
+                &(d.liquid_molefracs * MOL).into_value(),
+                &(d.vapor_molefracs * MOL).into_value(),
+            ),
+            &flash(&eos, t, p, &(&feed + &dfeed))?,
+            &flash(&eos, t, p, &(&feed - &dfeed))?,
+            h,
+        );
+        assert!(vle.flash_derivatives_feed(2).is_err());
+        Ok(())
+    }
+}
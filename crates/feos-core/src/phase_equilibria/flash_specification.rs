@@ -0,0 +1,469 @@
+use super::{FlashWorkspace, IsolinePoint, PhaseEquilibrium, TemperatureOrPressure};
+use crate::equation_of_state::{IdealGas, Residual};
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::observer::{report_iteration, report_result};
+use crate::state::{Contributions, DensityInitialization, State};
+use crate::{ReferenceSystem, SolverOptions, Verbosity};
+use ndarray::Array1;
+use quantity::{
+    Dimensionless, MolarEnergy, MolarEntropy, MolarVolume, Moles, Pressure, RGAS, Temperature,
+};
+use std::sync::Arc;
+
+const MAX_ITER_FLASH: usize = 100;
+const TOL_FLASH: f64 = 1e-10;
+/// Default initial temperature in K for specifications without temperature.
+const T_INITIAL: f64 = 298.15;
+/// Initial step of the bracketing in $\ln T$ or $\ln p$.
+const LN_STEP: f64 = 0.1;
+
+/// The two state variables that specify a flash calculation of a feed
+/// with given composition.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FlashSpecification {
+    /// Temperature and pressure.
+    TP(Temperature, Pressure),
+    /// Temperature and molar vapor fraction.
+    TBeta(Temperature, f64),
+    /// Pressure and molar vapor fraction.
+    PBeta(Pressure, f64),
+    /// Pressure and molar enthalpy.
+    PH(Pressure, MolarEnergy),
+    /// Pressure and molar entropy.
+    PS(Pressure, MolarEntropy),
+    /// Temperature and molar volume.
+    TV(Temperature, MolarVolume),
+    /// Molar internal energy and molar volume.
+    UV(MolarEnergy, MolarVolume),
+}
+
+/// # Flash calculations
+impl<E: Residual + IdealGas> PhaseEquilibrium<E, 2> {
+    /// Perform a flash calculation of the feed for an arbitrary
+    /// [FlashSpecification].
+    ///
+    /// The result is either a single stable phase or two coexisting phases
+    /// together with the molar vapor fraction. As for bubble and dew points,
+    /// the amount of substance in the coexisting phases is not necessarily
+    /// scaled with the vapor fraction.
+    ///
+    /// Tp- and β-specifications are solved with [State::tp_flash] and
+    /// [PhaseEquilibrium::beta_flash] using the `options`. For the remaining
+    /// specifications, the temperature and/or pressure are iterated around
+    /// Tp-flash calculations with default options, and the `options` apply
+    /// to the outer iteration. For pure components, the two-phase region
+    /// is resolved with the lever rule at the saturation point instead.
+    /// The `initial_temperature` is used for specifications without
+    /// temperature (298.15 K if not given).
+    pub fn flash(
+        eos: &Arc<E>,
+        specification: FlashSpecification,
+        feed: &Moles<Array1<f64>>,
+        initial_temperature: Option<Temperature>,
+        options: SolverOptions,
+    ) -> FeosResult<IsolinePoint<E>> {
+        Self::flash_initialized(eos, specification, feed, initial_temperature, None, options)
+    }
+
+    /// Flash calculation warm started from the result of a previous flash
+    /// calculation at similar conditions.
+    pub(super) fn flash_initialized(
+        eos: &Arc<E>,
+        specification: FlashSpecification,
+        feed: &Moles<Array1<f64>>,
+        initial_temperature: Option<Temperature>,
+        initial_point: Option<&IsolinePoint<E>>,
+        options: SolverOptions,
+    ) -> FeosResult<IsolinePoint<E>> {
+        let t0 = initial_point
+            .map(|point| point.temperature())
+            .or(initial_temperature)
+            .unwrap_or(Temperature::from_reduced(T_INITIAL));
+        let p0 = initial_point.map(|point| point.pressure());
+        let vle0 = match initial_point {
+            Some(IsolinePoint::TwoPhase(vle, _)) => Some(vle),
+            _ => None,
+        };
+        match specification {
+            FlashSpecification::TP(t, p) => {
+                let mut workspace = FlashWorkspace::new(eos.components());
+                Self::flash_tp(eos, t, p, feed, vle0, options, &mut workspace)
+            }
+            FlashSpecification::TBeta(t, beta) => Self::flash_beta(eos, t, p0, beta, feed, options),
+            FlashSpecification::PBeta(p, beta) => {
+                Self::flash_beta(eos, p, Some(t0), beta, feed, options)
+            }
+            FlashSpecification::PH(p, h) => Self::flash_p(
+                eos,
+                p,
+                feed,
+                t0,
+                |point| {
+                    (point.molar_enthalpy(Contributions::Total) - h) / (RGAS * point.temperature())
+                },
+                |density, t| State::new_nph(eos, p, h, feed, density, Some(t)),
+                options,
+            ),
+            FlashSpecification::PS(p, s) => Self::flash_p(
+                eos,
+                p,
+                feed,
+                t0,
+                |point| (point.molar_entropy(Contributions::Total) - s) / RGAS,
+                |density, t| State::new_nps(eos, p, s, feed, density, Some(t)),
+                options,
+            ),
+            FlashSpecification::TV(t, v) => Self::flash_tv(eos, t, v, feed, p0, options),
+            FlashSpecification::UV(u, v) => solve_monotonic(
+                "uv flash",
+                t0.to_reduced().ln(),
+                |ln_t| {
+                    let t = Temperature::from_reduced(ln_t.exp());
+                    let point = Self::flash_tv(eos, t, v, feed, None, SolverOptions::default())?;
+                    let u_point = point.molar_internal_energy(Contributions::Total);
+                    Ok(((u_point - u) / (RGAS * t), point))
+                },
+                options,
+            ),
+        }
+    }
+
+    /// Flash at given pressure and enthalpy or entropy. The `residual` has
+    /// to increase with temperature and has to be linear in the property
+    /// for the lever rule.
+    fn flash_p(
+        eos: &Arc<E>,
+        pressure: Pressure,
+        feed: &Moles<Array1<f64>>,
+        initial_temperature: Temperature,
+        residual: impl Fn(&IsolinePoint<E>) -> Dimensionless,
+        single_phase: impl Fn(DensityInitialization, Temperature) -> FeosResult<State<E>>,
+        options: SolverOptions,
+    ) -> FeosResult<IsolinePoint<E>> {
+        if eos.components() > 1 {
+            let mut workspace = FlashWorkspace::new(eos.components());
+            return solve_monotonic(
+                "flash",
+                initial_temperature.to_reduced().ln(),
+                |ln_t| {
+                    let t = Temperature::from_reduced(ln_t.exp());
+                    let options = SolverOptions::default();
+                    let point =
+                        Self::flash_tp(eos, t, pressure, feed, None, options, &mut workspace)?;
+                    Ok((residual(&point), point))
+                },
+                options,
+            );
+        }
+
+        // above the critical pressure, there is no phase boundary
+        let Ok(vle) = PhaseEquilibrium::pure(eos, pressure, None, options) else {
+            let state = single_phase(DensityInitialization::None, initial_temperature)?;
+            return Ok(IsolinePoint::SinglePhase(state));
+        };
+        let f = |state: &State<E>| residual(&IsolinePoint::SinglePhase(state.clone())).into_value();
+        let (f_vapor, f_liquid) = (f(vle.vapor()), f(vle.liquid()));
+        let t = vle.vapor().temperature;
+        if f_liquid > 0.0 {
+            let state = single_phase(DensityInitialization::Liquid, t)?;
+            Ok(IsolinePoint::SinglePhase(state))
+        } else if f_vapor < 0.0 {
+            let state = single_phase(DensityInitialization::Vapor, t)?;
+            Ok(IsolinePoint::SinglePhase(state))
+        } else {
+            let beta = f_liquid / (f_liquid - f_vapor);
+            Ok(IsolinePoint::TwoPhase(vle, beta))
+        }
+    }
+}
+
+impl<E: Residual> PhaseEquilibrium<E, 2> {
+    fn flash_tp(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressure: Pressure,
+        feed: &Moles<Array1<f64>>,
+        initial_state: Option<&PhaseEquilibrium<E, 2>>,
+        options: SolverOptions,
+        workspace: &mut FlashWorkspace,
+    ) -> FeosResult<IsolinePoint<E>> {
+        let state = State::new_npt(
+            eos,
+            temperature,
+            pressure,
+            feed,
+            DensityInitialization::None,
+        )?;
+        if eos.components() == 1 {
+            return Ok(IsolinePoint::SinglePhase(state));
+        }
+        match state.tp_flash_with_workspace(initial_state, options, None, workspace) {
+            Ok(vle) => {
+                let beta = vle.phase_fractions()[0];
+                Ok(IsolinePoint::TwoPhase(vle, beta))
+            }
+            Err(FeosError::NoPhaseSplit | FeosError::TrivialSolution) => {
+                Ok(IsolinePoint::SinglePhase(state))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn flash_beta<TP: TemperatureOrPressure>(
+        eos: &Arc<E>,
+        temperature_or_pressure: TP,
+        tp_init: Option<TP::Other>,
+        vapor_fraction: f64,
+        feed: &Moles<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<IsolinePoint<E>> {
+        let vle = if eos.components() == 1 {
+            if !(0.0..=1.0).contains(&vapor_fraction) {
+                return Err(FeosError::InvalidState(
+                    "beta flash".into(),
+                    "vapor fraction".into(),
+                    vapor_fraction,
+                ));
+            }
+            PhaseEquilibrium::pure(eos, temperature_or_pressure, None, options)?
+        } else {
+            let z = feed.to_reduced();
+            PhaseEquilibrium::beta_flash(
+                eos,
+                temperature_or_pressure,
+                &(&z / z.sum()),
+                vapor_fraction,
+                tp_init,
+                (options, options),
+            )?
+        };
+        Ok(IsolinePoint::TwoPhase(vle, vapor_fraction))
+    }
+
+    fn flash_tv(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        molar_volume: MolarVolume,
+        feed: &Moles<Array1<f64>>,
+        initial_pressure: Option<Pressure>,
+        options: SolverOptions,
+    ) -> FeosResult<IsolinePoint<E>> {
+        let volume = molar_volume * feed.sum();
+        if eos.components() == 1 {
+            if let Ok(vle) = PhaseEquilibrium::pure(eos, temperature, None, options) {
+                let (v_vapor, v_liquid) = (vle.vapor().molar_volume(), vle.liquid().molar_volume());
+                if molar_volume > v_liquid && molar_volume < v_vapor {
+                    let beta = ((molar_volume - v_liquid) / (v_vapor - v_liquid)).into_value();
+                    return Ok(IsolinePoint::TwoPhase(vle, beta));
+                }
+            }
+            let state = State::new_nvt(eos, temperature, volume, feed)?;
+            return Ok(IsolinePoint::SinglePhase(state));
+        }
+
+        let state = State::new_nvt(eos, temperature, volume, feed)?;
+        if state.pressure(Contributions::Total) > Pressure::from_reduced(0.0)
+            && state.is_stable(SolverOptions::default())?
+        {
+            return Ok(IsolinePoint::SinglePhase(state));
+        }
+        let p0 = initial_pressure.unwrap_or(RGAS * temperature / molar_volume);
+        let mut workspace = FlashWorkspace::new(eos.components());
+        solve_monotonic(
+            "tv flash",
+            p0.to_reduced().ln(),
+            |ln_p| {
+                let p = Pressure::from_reduced(ln_p.exp());
+                let options = SolverOptions::default();
+                let point =
+                    Self::flash_tp(eos, temperature, p, feed, None, options, &mut workspace)?;
+                let f = (molar_volume * point.density()).into_value().ln();
+                Ok((Dimensionless::new(f), point))
+            },
+            options,
+        )
+    }
+}
+
+/// Find the root of a monotonically increasing function by bracketing
+/// and the Illinois variant of the regula falsi.
+fn solve_monotonic<T>(
+    name: &'static str,
+    x0: f64,
+    mut f: impl FnMut(f64) -> FeosResult<(Dimensionless, T)>,
+    options: SolverOptions,
+) -> FeosResult<T> {
+    let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_FLASH, TOL_FLASH);
+    let mut f = |x: f64| f(x).map(|(fx, result)| (fx.into_value(), result));
+    log_iter!(verbosity, " iter |    residual    |        x       ");
+    log_iter!(verbosity, "{:-<38}", "");
+
+    let (mut a, (mut fa, result)) = (x0, f(x0)?);
+    if fa.abs() < tol {
+        report_result(name, 0, true);
+        return Ok(result);
+    }
+    let direction = -fa.signum();
+    let mut step = LN_STEP;
+    let (mut b, mut fb) = (a, fa);
+    let mut side = 0;
+    let mut res = fa.abs();
+    for i in 1..=max_iter {
+        let x = if fa.signum() == fb.signum() {
+            // expand the bracket
+            let x = b + direction * step;
+            step *= 2.0;
+            x
+        } else {
+            (a * fb - b * fa) / (fb - fa)
+        };
+        let (fx, result) = f(x)?;
+        res = fx.abs();
+        log_iter!(verbosity, " {:4} | {:14.8e} | {:14.8e}", i, fx.abs(), x);
+        report_iteration(name, i, fx.abs(), &[x]);
+        if fx.abs() < tol {
+            report_result(name, i, true);
+            log_result!(
+                verbosity,
+                "{}: calculation converged in {} step(s)\n",
+                name,
+                i
+            );
+            return Ok(result);
+        }
+        if fa.signum() == fb.signum() {
+            (a, fa) = (b, fb);
+            (b, fb) = (x, fx);
+        } else if fx.signum() == fb.signum() {
+            (b, fb) = (x, fx);
+            if side == -1 {
+                fa *= 0.5;
+            }
+            side = -1;
+        } else {
+            (a, fa) = (x, fx);
+            if side == 1 {
+                fb *= 0.5;
+            }
+            side = 1;
+        }
+    }
+    report_result(name, max_iter, false);
+    Err(FeosError::NotConverged(
+        SolverFailure::new(name).iterations(max_iter).residual(res),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use crate::equation_of_state::EquationOfState;
+    use crate::test_utils::{ConstantHeatCapacity, propane_butane};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{BAR, KELVIN, MOL};
+
+    #[test]
+    fn flash_specifications() -> FeosResult<()> {
+        let eos = Arc::new(EquationOfState::new(
+            Arc::new(ConstantHeatCapacity::new(2)),
+            Arc::new(propane_butane()?),
+        ));
+        let feed = arr1(&[0.4, 0.6]) * MOL;
+        let options = SolverOptions::default();
+        let (t, p) = (300.0 * KELVIN, 5.0 * BAR);
+        let flash = |spec| PhaseEquilibrium::flash(&eos, spec, &feed, None, options);
+
+        let tp = flash(FlashSpecification::TP(t, p))?;
+        let beta = tp.vapor_fraction().unwrap();
+        assert!(beta > 0.0 && beta < 1.0);
+        let single = flash(FlashSpecification::TP(400.0 * KELVIN, p))?;
+        assert!(matches!(single, IsolinePoint::SinglePhase(_)));
+
+        // all specifications reproduce the Tp-flash
+        let h = tp.molar_enthalpy(Contributions::Total);
+        let s = tp.molar_entropy(Contributions::Total);
+        let u = tp.molar_internal_energy(Contributions::Total);
+        let v = 1.0 / tp.density();
+        for spec in [
+            FlashSpecification::TBeta(t, beta),
+            FlashSpecification::PBeta(p, beta),
+            FlashSpecification::PH(p, h),
+            FlashSpecification::PS(p, s),
+            FlashSpecification::TV(t, v),
+            FlashSpecification::UV(u, v),
+        ] {
+            let point = PhaseEquilibrium::flash(&eos, spec, &feed, Some(t), options)?;
+            assert_relative_eq!(point.temperature(), t, max_relative = 1e-7);
+            assert_relative_eq!(point.pressure(), p, max_relative = 1e-7);
+            assert_relative_eq!(point.vapor_fraction().unwrap(), beta, max_relative = 1e-6);
+        }
+        let point = flash(FlashSpecification::PH(p, h))?;
+        assert_relative_eq!(
+            point.molar_enthalpy(Contributions::Total),
+            h,
+            max_relative = 1e-8
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn flash_specifications_pure() -> FeosResult<()> {
+        let parameters =
+            PengRobinsonParameters::new_simple(&[369.96], &[4250000.0], &[0.153], &[44.0962])?;
+        let eos = Arc::new(EquationOfState::new(
+            Arc::new(ConstantHeatCapacity::new(1)),
+            Arc::new(PengRobinson::new(Arc::new(parameters))),
+        ));
+        let feed = arr1(&[1.0]) * MOL;
+        let options = SolverOptions::default();
+        let p = 5.0 * BAR;
+        let vle = PhaseEquilibrium::pure(&eos, p, None, options)?;
+        let [h_vapor, h_liquid] = vle.phase_properties(|s| s.molar_enthalpy(Contributions::Total));
+
+        // the lever rule in the two-phase region
+        let h = h_liquid * 0.7 + h_vapor * 0.3;
+        let point =
+            PhaseEquilibrium::flash(&eos, FlashSpecification::PH(p, h), &feed, None, options)?;
+        assert_relative_eq!(point.vapor_fraction().unwrap(), 0.3, max_relative = 1e-10);
+        assert_relative_eq!(point.temperature(), vle.vapor().temperature);
+        let v = 1.0 / point.density();
+        let tv = PhaseEquilibrium::flash(
+            &eos,
+            FlashSpecification::TV(point.temperature(), v),
+            &feed,
+            None,
+            options,
+        )?;
+        assert_relative_eq!(tv.vapor_fraction().unwrap(), 0.3, max_relative = 1e-8);
+
+        // subcooled liquid and superheated vapor
+        let h = h_liquid - 1000.0 * quantity::JOULE / MOL;
+        let point =
+            PhaseEquilibrium::flash(&eos, FlashSpecification::PH(p, h), &feed, None, options)?;
+        assert!(point.temperature() < vle.liquid().temperature);
+        assert_relative_eq!(
+            point.molar_enthalpy(Contributions::Total),
+            h,
+            max_relative = 1e-8
+        );
+        let s =
+            vle.vapor().molar_entropy(Contributions::Total) + 1.0 * quantity::JOULE / MOL / KELVIN;
+        let point =
+            PhaseEquilibrium::flash(&eos, FlashSpecification::PS(p, s), &feed, None, options)?;
+        assert!(point.temperature() > vle.vapor().temperature);
+        assert!(point.vapor_fraction().is_none());
+        assert!(
+            PhaseEquilibrium::flash(
+                &eos,
+                FlashSpecification::TBeta(300.0 * KELVIN, 1.5),
+                &feed,
+                None,
+                options
+            )
+            .is_err()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+use super::PhaseEquilibrium;
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::state::{Contributions, State};
+use crate::{SolverOptions, Verbosity};
+use ndarray::{Array1, Array2, Axis};
+use num_dual::linalg::{LU, norm};
+use quantity::{
+    Density, JOULE, Length, METER, MOL, PASCAL, Pressure, RGAS, SurfaceTension, Temperature,
+};
+use std::sync::Arc;
+use typenum::{P2, P3};
+
+const MAX_ITER_DGT: usize = 50;
+const TOL_DGT: f64 = 1e-10;
+
+/// Density profiles and surface tension of a planar interface
+/// obtained from density gradient theory.
+#[derive(Clone, Debug)]
+pub struct GradientTheoryProfile {
+    /// Position normal to the interface
+    pub z: Length<Array1<f64>>,
+    /// Partial densities (components x grid points)
+    pub density: Density<Array2<f64>>,
+    /// Grand potential density relative to the bulk phases
+    pub grand_potential_density: Pressure<Array1<f64>>,
+    /// Surface tension
+    pub surface_tension: SurfaceTension,
+}
+
+/// # Density gradient theory
+impl<E: Residual> PhaseEquilibrium<E, 2> {
+    /// Calculate the density profiles and the surface tension of the
+    /// planar interface between the two phases using density gradient theory.
+    ///
+    /// The influence parameters $c_i$ are given per component in units of
+    /// J m⁵/mol² and combined with the geometric mean $c_{ij}=\sqrt{c_ic_j}$.
+    /// With this combining rule, the Euler-Lagrange equations reduce to
+    /// $\sqrt{c_j}\left(\mu_i-\mu_i^0\right)=\sqrt{c_i}\left(\mu_j-\mu_j^0\right)$,
+    /// which are solved on `n_points` equidistant densities of the component
+    /// with the largest (weighted) density difference between the phases.
+    ///
+    /// The position $z=0$ corresponds to the point where the density of this
+    /// component is the mean of both bulk densities.
+    pub fn gradient_theory(
+        &self,
+        influence_parameters: &Array1<f64>,
+        n_points: usize,
+        options: SolverOptions,
+    ) -> FeosResult<GradientTheoryProfile> {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_DGT, TOL_DGT);
+        let damping = options.damping_or_default()?;
+        let vapor = self.vapor();
+        let liquid = self.liquid();
+        let eos = &vapor.eos;
+        let n = eos.components();
+        if influence_parameters.len() != n {
+            return Err(FeosError::IncompatibleComponents(
+                influence_parameters.len(),
+                n,
+            ));
+        }
+        if n_points < 3 {
+            return Err(FeosError::Error(String::from(
+                "density gradient theory requires at least 3 grid points.",
+            )));
+        }
+        let temperature = vapor.temperature;
+        let rt = (RGAS * temperature).convert_to(JOULE / MOL);
+        let density_unit = MOL / METER.powi::<P3>();
+        let sqrt_c = influence_parameters.mapv(f64::sqrt);
+        let rho_v = vapor.partial_density.convert_to(density_unit);
+        let rho_l = liquid.partial_density.convert_to(density_unit);
+        let mu_0 = chemical_potential(vapor, rt);
+        let p_0 = vapor.pressure(Contributions::Total).convert_to(PASCAL);
+
+        // the path component has the largest weighted density difference
+        let weighted = (&rho_l - &rho_v).mapv(f64::abs) * &sqrt_c;
+        let k = (0..n).fold(0, |k, i| if weighted[i] > weighted[k] { i } else { k });
+
+        let mut density = Array2::zeros((n, n_points));
+        let mut omega = Array1::zeros(n_points);
+        density.column_mut(0).assign(&rho_v);
+        density.column_mut(n_points - 1).assign(&rho_l);
+        for m in 1..n_points - 1 {
+            // linear extrapolation of the previous solutions as initial guess
+            let mut rho = if m == 1 {
+                &rho_v + (&rho_l - &rho_v) / (n_points - 1) as f64
+            } else {
+                2.0 * &density.column(m - 1) - density.column(m - 2)
+            };
+            rho[k] = rho_v[k] + (rho_l[k] - rho_v[k]) * m as f64 / (n_points - 1) as f64;
+            rho.mapv_inplace(|r| r.max(f64::EPSILON));
+            let mut converged = n == 1;
+            let mut error = f64::INFINITY;
+            for i in 0..max_iter {
+                if converged {
+                    break;
+                }
+                let state = state_from_density(eos, temperature, &rho)?;
+                let dmu = chemical_potential(&state, rt) - &mu_0;
+                let dmu_drho = (state.dmu_dni(Contributions::Total) * state.volume)
+                    .convert_to(JOULE * METER.powi::<P3>() / MOL.powi::<P2>());
+                let others: Vec<_> = (0..n).filter(|&j| j != k).collect();
+                let res = Array1::from_shape_fn(n - 1, |a| {
+                    let j = others[a];
+                    sqrt_c[k] * dmu[j] - sqrt_c[j] * dmu[k]
+                });
+                let jacobian = Array2::from_shape_fn((n - 1, n - 1), |(a, b)| {
+                    let (j, l) = (others[a], others[b]);
+                    sqrt_c[k] * dmu_drho[[j, l]] - sqrt_c[j] * dmu_drho[[k, l]]
+                });
+                let dx = LU::new(jacobian)?.solve(&res) * damping;
+                for (a, &j) in others.iter().enumerate() {
+                    // limit the step to keep the densities positive
+                    rho[j] = (rho[j] - dx[a]).max(0.2 * rho[j]);
+                }
+                error = norm(&(&dx / &others.iter().map(|&j| rho[j]).collect::<Array1<_>>()));
+                log_iter!(verbosity, " {:4} | {:4} | {:14.8e}", m, i, error);
+                converged = error < tol;
+            }
+            if !converged {
+                return Err(FeosError::NotConverged(
+                    SolverFailure::new("density gradient theory")
+                        .iterations(max_iter)
+                        .residual(error)
+                        .temperature(temperature)
+                        .molefracs(&(&rho / rho.sum())),
+                ));
+            }
+            density.column_mut(m).assign(&rho);
+        }
+        for (m, rho) in density.axis_iter(Axis(1)).enumerate() {
+            if m == 0 || m == n_points - 1 {
+                continue;
+            }
+            let state = state_from_density(eos, temperature, &rho.to_owned())?;
+            let f = (state.residual_helmholtz_energy() / state.volume).convert_to(PASCAL)
+                + rt * rho.iter().map(|&r| r * (r.ln() - 1.0)).sum::<f64>();
+            let mu_rho = (rho.to_owned() * &mu_0).sum();
+            omega[m] = (f - mu_rho + p_0).max(0.0);
+        }
+
+        // integration along the path of the weighted density
+        let w = sqrt_c.dot(&density);
+        let mut surface_tension = 0.0;
+        let mut z = Array1::zeros(n_points);
+        for m in 0..n_points - 1 {
+            let dw = (w[m + 1] - w[m]).abs();
+            surface_tension += 0.5 * ((2.0 * omega[m]).sqrt() + (2.0 * omega[m + 1]).sqrt()) * dw;
+            z[m + 1] = z[m] + dw / (omega[m] + omega[m + 1]).sqrt();
+        }
+        let mid = 0.5 * (n_points - 1) as f64;
+        let (lo, hi) = (mid.floor() as usize, mid.ceil() as usize);
+        z -= 0.5 * (z[lo] + z[hi]);
+        log_result!(
+            verbosity,
+            "Density gradient theory: surface tension {:.5e} N/m",
+            surface_tension
+        );
+
+        Ok(GradientTheoryProfile {
+            z: z * METER,
+            density: density * density_unit,
+            grand_potential_density: omega * PASCAL,
+            surface_tension: surface_tension * JOULE / METER.powi::<P2>(),
+        })
+    }
+}
+
+fn state_from_density<E: Residual>(
+    eos: &Arc<E>,
+    temperature: Temperature,
+    density: &Array1<f64>,
+) -> FeosResult<State<E>> {
+    let volume = METER.powi::<P3>();
+    State::new_nvt(eos, temperature, volume, &(density * MOL))
+}
+
+/// Chemical potentials in J/mol without the (constant) de Broglie contribution.
+fn chemical_potential<E: Residual>(state: &State<E>, rt: f64) -> Array1<f64> {
+    let rho = state.partial_density.convert_to(MOL / METER.powi::<P3>());
+    state.residual_chemical_potential().convert_to(JOULE / MOL) + rt * rho.mapv(f64::ln)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use crate::test_utils::propane_butane;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{BAR, KELVIN, MILLI, NEWTON};
+
+    #[test]
+    fn pure_component() -> FeosResult<()> {
+        let parameters =
+            PengRobinsonParameters::new_simple(&[369.96], &[4250000.0], &[0.153], &[44.0962])?;
+        let eos = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let vle = PhaseEquilibrium::pure(&eos, 300.0 * KELVIN, None, SolverOptions::default())?;
+        let c = arr1(&[1.5e-19]);
+        let profile = vle.gradient_theory(&c, 401, SolverOptions::default())?;
+        let sigma = profile.surface_tension.convert_to(MILLI * NEWTON / METER);
+        assert!(sigma > 1.0 && sigma < 20.0);
+        assert_relative_eq!(profile.density.get((0, 0)), vle.vapor().density);
+        assert_relative_eq!(profile.density.get((0, 400)), vle.liquid().density);
+
+        // the surface tension scales with the square root of the influence parameter
+        let profile4 = vle.gradient_theory(&(4.0 * &c), 401, SolverOptions::default())?;
+        assert_relative_eq!(
+            profile4.surface_tension,
+            2.0 * profile.surface_tension,
+            max_relative = 1e-12
+        );
+        let coarse = vle.gradient_theory(&c, 101, SolverOptions::default())?;
+        assert_relative_eq!(
+            coarse.surface_tension,
+            profile.surface_tension,
+            max_relative = 1e-2
+        );
+        assert!(
+            vle.gradient_theory(&arr1(&[1.0, 1.0]), 101, SolverOptions::default())
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn binary_mixture() -> FeosResult<()> {
+        let eos = Arc::new(propane_butane()?);
+        let x = arr1(&[0.5, 0.5]);
+        let vle = PhaseEquilibrium::bubble_point(
+            &eos,
+            300.0 * KELVIN,
+            &x,
+            None,
+            None,
+            (SolverOptions::default(), SolverOptions::default()),
+        )?;
+        assert!(vle.vapor().pressure(Contributions::Total) > BAR);
+        let c = arr1(&[1.5e-19, 2.5e-19]);
+        let profile = vle.gradient_theory(&c, 201, SolverOptions::default())?;
+
+        // the profile satisfies the Euler-Lagrange equations
+        let rt = (RGAS * vle.vapor().temperature).convert_to(JOULE / MOL);
+        let mu_0 = chemical_potential(vle.liquid(), rt);
+        let density = profile.density.convert_to(MOL / METER.powi::<P3>());
+        for rho in density.axis_iter(Axis(1)).skip(1).take(199) {
+            let state = state_from_density(&eos, vle.vapor().temperature, &rho.to_owned())?;
+            let dmu = chemical_potential(&state, rt) - &mu_0;
+            assert_relative_eq!(
+                dmu[0] / c[0].sqrt(),
+                dmu[1] / c[1].sqrt(),
+                max_relative = 1e-6
+            );
+        }
+        assert!(profile.z.get(0) < profile.z.get(200));
+        let sigma = profile.surface_tension.convert_to(MILLI * NEWTON / METER);
+        assert!(sigma > 1.0 && sigma < 20.0);
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+use super::{Isoline, IsolinePoint, PhaseEquilibrium};
+use crate::equation_of_state::Residual;
+use crate::errors::FeosResult;
+use crate::state::{DensityInitialization, State};
+use crate::{ReferenceSystem, SolverOptions};
+use ndarray::Array1;
+use quantity::{Moles, Pressure, Temperature};
+use std::sync::Arc;
+
+impl<E: Residual> Isoline<E> {
+    /// Calculate the heating (or condensation) curve of a mixture with
+    /// fixed composition at constant pressure, e.g., for the duty curves of
+    /// heaters, evaporators and condensers.
+    ///
+    /// The curve consists of `npoints` states of the subcooled liquid between
+    /// the lower temperature and the bubble point, `vapor_fractions` points in
+    /// the two-phase region at evenly spaced molar vapor fractions between
+    /// the bubble point ($\beta=0$) and the dew point ($\beta=1$), and
+    /// `npoints` states of the superheated vapor between the dew point and
+    /// the upper temperature. The single phase segments are omitted if the
+    /// corresponding temperature lies inside the two-phase region. Points in
+    /// the two-phase region are calculated with β-flashes, so the resolution
+    /// of the curve is independent of the temperature glide. The bubble
+    /// point calculation is initialized with the mean of the two temperatures.
+    ///
+    /// Heat duties follow from the [molar enthalpy](Isoline::molar_enthalpy)
+    /// of the points. For pure components, the two-phase points are located
+    /// at the boiling temperature.
+    pub fn heating_curve(
+        eos: &Arc<E>,
+        moles: &Moles<Array1<f64>>,
+        pressure: Pressure,
+        temperatures: (Temperature, Temperature),
+        npoints: usize,
+        vapor_fractions: usize,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let moles = eos.validate_moles(Some(moles))?;
+        let molefracs = (&moles / moles.sum()).into_value();
+        let (t_min, t_max) = temperatures;
+        let (bubble, dew) = if eos.components() == 1 {
+            let vle = PhaseEquilibrium::pure(eos, pressure, None, options)?;
+            (vle.clone(), vle)
+        } else {
+            let options = (options, options);
+            let t = Some(0.5 * (t_min + t_max));
+            let bubble =
+                PhaseEquilibrium::bubble_point(eos, pressure, &molefracs, t, None, options)?;
+            let t = Some(bubble.liquid().temperature);
+            let dew = PhaseEquilibrium::dew_point(eos, pressure, &molefracs, t, None, options)?;
+            (bubble, dew)
+        };
+        let (t_bubble, t_dew) = (bubble.liquid().temperature, dew.vapor().temperature);
+        let npoints = npoints.max(2);
+        let mut points = Vec::with_capacity(2 * npoints + vapor_fractions);
+
+        // subcooled liquid
+        let mut density = DensityInitialization::Liquid;
+        if t_min < t_bubble {
+            let grid = Array1::linspace(t_min.to_reduced(), t_bubble.to_reduced(), npoints);
+            for &t in grid.iter().take(npoints - 1) {
+                let t = Temperature::from_reduced(t);
+                let state = State::new_npt(eos, t, pressure, &moles, density)?;
+                density = DensityInitialization::InitialDensity(state.density);
+                points.push(IsolinePoint::SinglePhase(state));
+            }
+        }
+
+        // two-phase region
+        let mut temperature = t_bubble;
+        for beta in Array1::linspace(0.0, 1.0, vapor_fractions.max(2)) {
+            let vle = if beta == 0.0 {
+                bubble.clone()
+            } else if beta == 1.0 {
+                dew.clone()
+            } else if eos.components() == 1 {
+                bubble.clone()
+            } else {
+                PhaseEquilibrium::beta_flash(
+                    eos,
+                    pressure,
+                    &molefracs,
+                    beta,
+                    Some(temperature),
+                    (options, options),
+                )?
+            };
+            temperature = vle.vapor().temperature;
+            points.push(IsolinePoint::TwoPhase(vle, beta));
+        }
+
+        // superheated vapor
+        let mut density = DensityInitialization::Vapor;
+        if t_max > t_dew {
+            let grid = Array1::linspace(t_dew.to_reduced(), t_max.to_reduced(), npoints);
+            for &t in grid.iter().skip(1) {
+                let t = Temperature::from_reduced(t);
+                let state = State::new_npt(eos, t, pressure, &moles, density)?;
+                density = DensityInitialization::InitialDensity(state.density);
+                points.push(IsolinePoint::SinglePhase(state));
+            }
+        }
+        Ok(Self { points })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equation_of_state::{Components, EquationOfState};
+    use crate::state::Contributions;
+    use crate::test_utils::{ConstantHeatCapacity, propane_butane};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{BAR, KELVIN};
+
+    #[test]
+    fn heating_curve() -> FeosResult<()> {
+        let eos = Arc::new(EquationOfState::new(
+            Arc::new(ConstantHeatCapacity::new(2)),
+            Arc::new(propane_butane()?),
+        ));
+        let moles = Moles::from_reduced(arr1(&[0.4, 0.6]));
+        let pressure = 5.0 * BAR;
+        let curve = Isoline::heating_curve(
+            &eos,
+            &moles,
+            pressure,
+            (250.0 * KELVIN, 350.0 * KELVIN),
+            5,
+            11,
+            SolverOptions::default(),
+        )?;
+        assert_eq!(curve.points.len(), 4 + 11 + 4);
+
+        // the two-phase region is bounded by the bubble and dew point
+        let beta = curve.vapor_fraction();
+        assert!(beta.iter().take(4).all(|b| b.is_nan()));
+        assert_eq!(beta[4], 0.0);
+        assert_relative_eq!(beta[9], 0.5, max_relative = 1e-12);
+        assert_eq!(beta[14], 1.0);
+        let bubble = PhaseEquilibrium::bubble_point(
+            &eos,
+            pressure,
+            &arr1(&[0.4, 0.6]),
+            Some(300.0 * KELVIN),
+            None,
+            Default::default(),
+        )?;
+        assert_relative_eq!(
+            curve.points[4].temperature(),
+            bubble.liquid().temperature,
+            max_relative = 1e-8
+        );
+
+        // temperature and enthalpy increase monotonically along the curve
+        let t = curve.temperature().to_reduced();
+        let h = curve.molar_enthalpy(Contributions::Total).to_reduced();
+        assert!(t.windows(2).into_iter().all(|w| w[1] > w[0]));
+        assert!(h.windows(2).into_iter().all(|w| w[1] > w[0]));
+        assert!(
+            curve
+                .pressure()
+                .to_reduced()
+                .iter()
+                .all(|&p| { (p / pressure.to_reduced() - 1.0).abs() < 1e-8 })
+        );
+
+        // the two-phase points of a pure component are at the boiling temperature
+        let eos = Arc::new(eos.subset(&[0]));
+        let moles = Moles::from_reduced(arr1(&[1.0]));
+        let curve = Isoline::heating_curve(
+            &eos,
+            &moles,
+            pressure,
+            (250.0 * KELVIN, 350.0 * KELVIN),
+            3,
+            5,
+            SolverOptions::default(),
+        )?;
+        let t = curve.temperature();
+        assert_relative_eq!(t.get(2), t.get(6), max_relative = 1e-12);
+        let h = curve.molar_enthalpy(Contributions::Total).to_reduced();
+        assert!(h.windows(2).into_iter().all(|w| w[1] > w[0]));
+        Ok(())
+    }
+}
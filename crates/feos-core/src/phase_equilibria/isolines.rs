@@ -0,0 +1,577 @@
+use super::{FlashWorkspace, PhaseEquilibrium};
+use crate::equation_of_state::{IdealGas, Residual};
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::state::{Contributions, DensityInitialization, State};
+use crate::{ReferenceSystem, SolverOptions};
+use ndarray::Array1;
+use quantity::{Density, MolarEnergy, MolarEntropy, Moles, Pressure, Temperature};
+use std::ops::{Add, Mul};
+use std::sync::Arc;
+
+const MAX_ITER_CROSSING: usize = 100;
+const TOL_CROSSING: f64 = 1e-10;
+
+/// A point on an isoline or the result of a flash calculation
+/// (see [PhaseEquilibrium::flash]).
+#[derive(Debug)]
+#[expect(clippy::large_enum_variant)]
+pub enum IsolinePoint<E> {
+    /// A single phase state.
+    SinglePhase(State<E>),
+    /// A vapor-liquid equilibrium together with the molar vapor fraction.
+    TwoPhase(PhaseEquilibrium<E, 2>, f64),
+}
+
+impl<E> Clone for IsolinePoint<E> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::SinglePhase(state) => Self::SinglePhase(state.clone()),
+            Self::TwoPhase(vle, vapor_fraction) => Self::TwoPhase(vle.clone(), *vapor_fraction),
+        }
+    }
+}
+
+impl<E: Residual> IsolinePoint<E> {
+    /// Molar average of a property over the coexisting phases.
+    fn average<Q, F>(&self, property: F) -> Q
+    where
+        F: Fn(&State<E>) -> Q,
+        Q: Mul<f64, Output = Q> + Add<Output = Q>,
+    {
+        match self {
+            Self::SinglePhase(state) => property(state),
+            Self::TwoPhase(vle, beta) => {
+                property(vle.liquid()) * (1.0 - beta) + property(vle.vapor()) * *beta
+            }
+        }
+    }
+
+    /// Temperature of the point.
+    pub fn temperature(&self) -> Temperature {
+        self.average(|s| s.temperature)
+    }
+
+    /// Pressure of the point.
+    pub fn pressure(&self) -> Pressure {
+        self.average(|s| s.pressure(Contributions::Total))
+    }
+
+    /// Overall density of the point.
+    pub fn density(&self) -> Density {
+        Density::from_reduced(1.0 / self.average(|s| 1.0 / s.density.to_reduced()))
+    }
+
+    /// Molar vapor fraction for points in the two-phase region
+    /// (including the saturation points) and `None` otherwise.
+    pub fn vapor_fraction(&self) -> Option<f64> {
+        match self {
+            Self::SinglePhase(_) => None,
+            Self::TwoPhase(_, beta) => Some(*beta),
+        }
+    }
+}
+
+impl<E: Residual + IdealGas> IsolinePoint<E> {
+    /// Overall molar enthalpy of the point.
+    pub fn molar_enthalpy(&self, contributions: Contributions) -> MolarEnergy {
+        self.average(|s| s.molar_enthalpy(contributions))
+    }
+
+    /// Overall molar entropy of the point.
+    pub fn molar_entropy(&self, contributions: Contributions) -> MolarEntropy {
+        self.average(|s| s.molar_entropy(contributions))
+    }
+
+    /// Overall molar internal energy of the point.
+    pub fn molar_internal_energy(&self, contributions: Contributions) -> MolarEnergy {
+        self.average(|s| s.molar_internal_energy(contributions))
+    }
+}
+
+/// Isotherms, isobars, isochores and isentropes of a pure component
+/// and isotherms and isobars of mixtures.
+///
+/// The isolines are traced along the given temperatures or pressures.
+/// Crossings of the phase boundary are handled explicitly by inserting
+/// the saturation points, so that the two-phase region is resolved
+/// exactly, e.g., in T-s or p-h diagrams. Points inside the two-phase
+/// region are represented by the coexisting phases and the molar vapor
+/// fraction.
+pub struct Isoline<E> {
+    pub points: Vec<IsolinePoint<E>>,
+}
+
+impl<E> Clone for Isoline<E> {
+    fn clone(&self) -> Self {
+        Self {
+            points: self.points.clone(),
+        }
+    }
+}
+
+impl<E: Residual> Isoline<E> {
+    /// Calculate an isotherm for the given pressures.
+    ///
+    /// Below the critical temperature, the saturated vapor and liquid
+    /// are inserted at the vapor pressure.
+    pub fn isotherm(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressures: &Pressure<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let critical_point = critical_point(eos, options)?;
+        let moles = eos.validate_moles(None)?;
+        let saturation = if temperature < critical_point.temperature {
+            let vle = PhaseEquilibrium::pure(eos, temperature, None, options)?;
+            let pressure = vle.vapor().pressure(Contributions::Total);
+            Some((pressure.to_reduced(), vle))
+        } else {
+            None
+        };
+        Self::trace_saturation(&pressures.to_reduced(), saturation, true, |p, density| {
+            State::new_npt(eos, temperature, Pressure::from_reduced(p), &moles, density)
+        })
+    }
+
+    /// Calculate an isobar for the given temperatures.
+    ///
+    /// Below the critical pressure, the saturated liquid and vapor
+    /// are inserted at the boiling temperature.
+    pub fn isobar(
+        eos: &Arc<E>,
+        pressure: Pressure,
+        temperatures: &Temperature<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let critical_point = critical_point(eos, options)?;
+        let moles = eos.validate_moles(None)?;
+        let saturation = if pressure < critical_point.pressure(Contributions::Total) {
+            let vle = PhaseEquilibrium::pure(eos, pressure, None, options)?;
+            Some((vle.vapor().temperature.to_reduced(), vle))
+        } else {
+            None
+        };
+        Self::trace_saturation(
+            &temperatures.to_reduced(),
+            saturation,
+            false,
+            |t, density| {
+                State::new_npt(eos, Temperature::from_reduced(t), pressure, &moles, density)
+            },
+        )
+    }
+
+    /// Calculate an isochore for the given temperatures.
+    ///
+    /// The saturation point at which the isochore enters or leaves the
+    /// two-phase region is inserted.
+    pub fn isochore(
+        eos: &Arc<E>,
+        density: Density,
+        temperatures: &Temperature<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let critical_point = critical_point(eos, options)?;
+        Self::trace_lever(
+            &temperatures.to_reduced(),
+            critical_point.temperature.to_reduced(),
+            &critical_point,
+            1.0 / density.to_reduced(),
+            |s| 1.0 / s.density.to_reduced(),
+            |t, vle| PhaseEquilibrium::pure(eos, Temperature::from_reduced(t), vle, options),
+            |t, _, _| State::new_pure(eos, Temperature::from_reduced(t), density),
+        )
+    }
+
+    /// Calculate an isotherm of a mixture with given composition for the
+    /// given pressures.
+    ///
+    /// Points inside the phase envelope are obtained from tp-flash
+    /// calculations and the bubble and dew points are inserted where the
+    /// isotherm crosses the phase envelope.
+    pub fn isotherm_mixture(
+        eos: &Arc<E>,
+        moles: &Moles<Array1<f64>>,
+        temperature: Temperature,
+        pressures: &Pressure<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let moles = eos.validate_moles(Some(moles))?;
+        let molefracs = (&moles / moles.sum()).into_value();
+        let mut workspace = FlashWorkspace::new(eos.components());
+        Self::trace_flash(
+            &pressures.to_reduced(),
+            |p, vle| {
+                let p = Pressure::from_reduced(p);
+                State::new_npt(eos, temperature, p, &moles, DensityInitialization::None)?
+                    .tp_flash_with_workspace(vle, options, None, &mut workspace)
+            },
+            |p, density| {
+                State::new_npt(eos, temperature, Pressure::from_reduced(p), &moles, density)
+            },
+            |p, bubble| {
+                let p = Some(Pressure::from_reduced(p));
+                let x = &molefracs;
+                if bubble {
+                    PhaseEquilibrium::bubble_point(eos, temperature, x, p, None, (options, options))
+                } else {
+                    PhaseEquilibrium::dew_point(eos, temperature, x, p, None, (options, options))
+                }
+            },
+            |s| s.pressure(Contributions::Total).to_reduced(),
+        )
+    }
+
+    /// Calculate an isobar of a mixture with given composition for the
+    /// given temperatures.
+    ///
+    /// Points inside the phase envelope are obtained from tp-flash
+    /// calculations and the bubble and dew points are inserted where the
+    /// isobar crosses the phase envelope.
+    pub fn isobar_mixture(
+        eos: &Arc<E>,
+        moles: &Moles<Array1<f64>>,
+        pressure: Pressure,
+        temperatures: &Temperature<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let moles = eos.validate_moles(Some(moles))?;
+        let molefracs = (&moles / moles.sum()).into_value();
+        let mut workspace = FlashWorkspace::new(eos.components());
+        Self::trace_flash(
+            &temperatures.to_reduced(),
+            |t, vle| {
+                let t = Temperature::from_reduced(t);
+                State::new_npt(eos, t, pressure, &moles, DensityInitialization::None)?
+                    .tp_flash_with_workspace(vle, options, None, &mut workspace)
+            },
+            |t, density| {
+                State::new_npt(eos, Temperature::from_reduced(t), pressure, &moles, density)
+            },
+            |t, bubble| {
+                let t = Some(Temperature::from_reduced(t));
+                let x = &molefracs;
+                if bubble {
+                    PhaseEquilibrium::bubble_point(eos, pressure, x, t, None, (options, options))
+                } else {
+                    PhaseEquilibrium::dew_point(eos, pressure, x, t, None, (options, options))
+                }
+            },
+            |s| s.temperature.to_reduced(),
+        )
+    }
+
+    /// Trace an isotherm or isobar, i.e., an isoline along which the
+    /// phase boundary is crossed at a single value `x_sat` of the
+    /// independent variable.
+    fn trace_saturation<F>(
+        grid: &Array1<f64>,
+        saturation: Option<(f64, PhaseEquilibrium<E, 2>)>,
+        liquid_above: bool,
+        mut state: F,
+    ) -> FeosResult<Self>
+    where
+        F: FnMut(f64, DensityInitialization) -> FeosResult<State<E>>,
+    {
+        let mut points = Vec::with_capacity(grid.len() + 2);
+        let mut previous: Option<f64> = None;
+        for &x in grid {
+            let density = match &saturation {
+                Some((x_sat, vle)) => {
+                    let liquid = (x > *x_sat) == liquid_above;
+                    if let Some(x0) = previous
+                        && (x0 - x_sat) * (x - x_sat) < 0.0
+                    {
+                        let (first, second) = if liquid { (1.0, 0.0) } else { (0.0, 1.0) };
+                        points.push(IsolinePoint::TwoPhase(vle.clone(), first));
+                        points.push(IsolinePoint::TwoPhase(vle.clone(), second));
+                    }
+                    if liquid {
+                        DensityInitialization::Liquid
+                    } else {
+                        DensityInitialization::Vapor
+                    }
+                }
+                None => match points.last() {
+                    Some(IsolinePoint::SinglePhase(s)) => {
+                        DensityInitialization::InitialDensity(s.density)
+                    }
+                    _ => DensityInitialization::None,
+                },
+            };
+            points.push(IsolinePoint::SinglePhase(state(x, density)?));
+            previous = Some(x);
+        }
+        Ok(Self { points })
+    }
+
+    /// Trace an isotherm or isobar of a mixture using tp-flash calculations.
+    ///
+    /// If the phase envelope is crossed between two points, the bubble point
+    /// (dew point) is inserted if the adjacent two-phase point is closer
+    /// to the liquid (vapor).
+    fn trace_flash<F, S, B, X>(
+        grid: &Array1<f64>,
+        mut flash: F,
+        mut state: S,
+        mut boundary: B,
+        x: X,
+    ) -> FeosResult<Self>
+    where
+        F: FnMut(f64, Option<&PhaseEquilibrium<E, 2>>) -> FeosResult<PhaseEquilibrium<E, 2>>,
+        S: FnMut(f64, DensityInitialization) -> FeosResult<State<E>>,
+        B: FnMut(f64, bool) -> FeosResult<PhaseEquilibrium<E, 2>>,
+        X: Fn(&State<E>) -> f64,
+    {
+        let mut points = Vec::with_capacity(grid.len() + 2);
+        let mut previous: Option<(f64, Option<f64>)> = None;
+        let mut last_vle = None;
+        for &xi in grid {
+            let point = match flash(xi, last_vle.as_ref()) {
+                Ok(vle) => {
+                    let (nv, nl) = (vle.vapor().moles.sum(), vle.liquid().moles.sum());
+                    let beta = (nv / (nv + nl)).into_value();
+                    last_vle = Some(vle.clone());
+                    IsolinePoint::TwoPhase(vle, beta)
+                }
+                Err(_) => {
+                    let density = match points.last() {
+                        Some(IsolinePoint::SinglePhase(s)) => {
+                            DensityInitialization::InitialDensity(s.density)
+                        }
+                        _ => DensityInitialization::None,
+                    };
+                    IsolinePoint::SinglePhase(state(xi, density)?)
+                }
+            };
+
+            // insert the bubble or dew point if the phase envelope is crossed
+            let beta = point.vapor_fraction();
+            if let Some((x0, beta0)) = previous
+                && beta0.is_some() != beta.is_some()
+                && let Some((x_init, b)) = beta0.map(|b| (x0, b)).or(beta.map(|b| (xi, b)))
+                && let Ok(vle) = boundary(x_init, b < 0.5)
+            {
+                let x_sat = x(vle.vapor());
+                if (x0 - x_sat) * (xi - x_sat) < 0.0 {
+                    points.push(IsolinePoint::TwoPhase(vle, if b < 0.5 { 0.0 } else { 1.0 }));
+                }
+            }
+            points.push(point);
+            previous = Some((xi, beta));
+        }
+        Ok(Self { points })
+    }
+
+    /// Trace an isoline with constant `target` value of a property that
+    /// is distributed among the coexisting phases by the lever rule
+    /// (e.g., the molar volume or the molar entropy).
+    fn trace_lever<L, V, S>(
+        grid: &Array1<f64>,
+        x_critical: f64,
+        critical_point: &State<E>,
+        target: f64,
+        lever: L,
+        mut vle: V,
+        mut state: S,
+    ) -> FeosResult<Self>
+    where
+        L: Fn(&State<E>) -> f64,
+        V: FnMut(f64, Option<&PhaseEquilibrium<E, 2>>) -> FeosResult<PhaseEquilibrium<E, 2>>,
+        S: FnMut(f64, DensityInitialization, Temperature) -> FeosResult<State<E>>,
+    {
+        let liquid_side = target < lever(critical_point);
+        let saturation = if liquid_side { 0.0 } else { 1.0 };
+        let fraction = |vle: &PhaseEquilibrium<E, 2>| {
+            let (l, v) = (lever(vle.liquid()), lever(vle.vapor()));
+            (target - l) / (v - l)
+        };
+
+        let mut points = Vec::with_capacity(grid.len() + 2);
+        let mut previous: Option<(f64, bool)> = None;
+        let mut last_vle = None;
+        for &x in grid {
+            let vle_x = if x < x_critical {
+                vle(x, last_vle.as_ref()).ok()
+            } else {
+                None
+            };
+            let beta = vle_x.as_ref().map(fraction);
+            let two_phase = beta.is_some_and(|b| b > 0.0 && b < 1.0);
+
+            // insert the saturation point if the phase boundary is crossed
+            if let Some((x0, two_phase0)) = previous
+                && two_phase0 != two_phase
+            {
+                let (x_two_phase, x_single) = if two_phase { (x, x0) } else { (x0, x) };
+                let x_single = if x_single > x_critical {
+                    x_critical
+                } else {
+                    x_single
+                };
+                let mut boundary = |x: f64| {
+                    if x >= x_critical {
+                        return Ok(target - lever(critical_point));
+                    }
+                    let vle = vle(x, last_vle.as_ref())?;
+                    let state = if liquid_side {
+                        vle.liquid()
+                    } else {
+                        vle.vapor()
+                    };
+                    Ok(target - lever(state))
+                };
+                if let Some(x_sat) = illinois(x_two_phase, x_single, &mut boundary)? {
+                    let vle_sat = if x_sat < x_critical {
+                        vle(x_sat, last_vle.as_ref())?
+                    } else {
+                        PhaseEquilibrium::from_states(
+                            critical_point.clone(),
+                            critical_point.clone(),
+                        )
+                    };
+                    points.push(IsolinePoint::TwoPhase(vle_sat, saturation));
+                }
+            }
+
+            if two_phase && let (Some(vle_x), Some(beta)) = (vle_x.as_ref(), beta) {
+                points.push(IsolinePoint::TwoPhase(vle_x.clone(), beta));
+            } else {
+                let density = match (beta, points.last()) {
+                    (Some(b), _) if b <= 0.0 => DensityInitialization::Liquid,
+                    (Some(_), _) => DensityInitialization::Vapor,
+                    (None, Some(IsolinePoint::SinglePhase(s))) => {
+                        DensityInitialization::InitialDensity(s.density)
+                    }
+                    (None, _) if liquid_side => DensityInitialization::Liquid,
+                    (None, _) => DensityInitialization::Vapor,
+                };
+                let temperature = points
+                    .last()
+                    .map_or(critical_point.temperature, |p: &IsolinePoint<E>| {
+                        p.temperature()
+                    });
+                points.push(IsolinePoint::SinglePhase(state(x, density, temperature)?));
+            }
+            if vle_x.is_some() {
+                last_vle = vle_x;
+            }
+            previous = Some((x, two_phase));
+        }
+        Ok(Self { points })
+    }
+}
+
+impl<E: Residual + IdealGas> Isoline<E> {
+    /// Calculate an isentrope for the given pressures.
+    ///
+    /// The saturation point at which the isentrope enters or leaves the
+    /// two-phase region is inserted.
+    pub fn isentrope(
+        eos: &Arc<E>,
+        molar_entropy: MolarEntropy,
+        pressures: &Pressure<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let critical_point = critical_point(eos, options)?;
+        let moles = eos.validate_moles(None)?;
+        Self::trace_lever(
+            &pressures.to_reduced(),
+            critical_point.pressure(Contributions::Total).to_reduced(),
+            &critical_point,
+            molar_entropy.to_reduced(),
+            |s| s.molar_entropy(Contributions::Total).to_reduced(),
+            |p, vle| PhaseEquilibrium::pure(eos, Pressure::from_reduced(p), vle, options),
+            |p, density, temperature| {
+                State::new_nps(
+                    eos,
+                    Pressure::from_reduced(p),
+                    molar_entropy,
+                    &moles,
+                    density,
+                    Some(temperature),
+                )
+            },
+        )
+    }
+
+    /// Return the molar enthalpy of all points.
+    pub fn molar_enthalpy(&self, contributions: Contributions) -> MolarEnergy<Array1<f64>> {
+        MolarEnergy::from_shape_fn(self.points.len(), |i| {
+            self.points[i].molar_enthalpy(contributions)
+        })
+    }
+
+    /// Return the molar entropy of all points.
+    pub fn molar_entropy(&self, contributions: Contributions) -> MolarEntropy<Array1<f64>> {
+        MolarEntropy::from_shape_fn(self.points.len(), |i| {
+            self.points[i].molar_entropy(contributions)
+        })
+    }
+}
+
+impl<E: Residual> Isoline<E> {
+    /// Return the temperature of all points.
+    pub fn temperature(&self) -> Temperature<Array1<f64>> {
+        Temperature::from_shape_fn(self.points.len(), |i| self.points[i].temperature())
+    }
+
+    /// Return the pressure of all points.
+    pub fn pressure(&self) -> Pressure<Array1<f64>> {
+        Pressure::from_shape_fn(self.points.len(), |i| self.points[i].pressure())
+    }
+
+    /// Return the overall density of all points.
+    pub fn density(&self) -> Density<Array1<f64>> {
+        Density::from_shape_fn(self.points.len(), |i| self.points[i].density())
+    }
+
+    /// Return the molar vapor fraction of all points (NaN for single phase states).
+    pub fn vapor_fraction(&self) -> Array1<f64> {
+        Array1::from_shape_fn(self.points.len(), |i| {
+            self.points[i].vapor_fraction().unwrap_or(f64::NAN)
+        })
+    }
+}
+
+/// Calculate the critical point of a pure component.
+fn critical_point<E: Residual>(eos: &Arc<E>, options: SolverOptions) -> FeosResult<State<E>> {
+    if eos.components() != 1 {
+        return Err(FeosError::IncompatibleComponents(eos.components(), 1));
+    }
+    State::critical_point(eos, None, None, options)
+}
+
+/// Find the root of `f` in the interval between `a` and `b` with the
+/// Illinois variant of the regula falsi. Returns `None` if the function
+/// values at the boundaries do not differ in sign.
+fn illinois<F>(mut a: f64, mut b: f64, f: &mut F) -> FeosResult<Option<f64>>
+where
+    F: FnMut(f64) -> FeosResult<f64>,
+{
+    let (mut fa, mut fb) = (f(a)?, f(b)?);
+    if fa * fb > 0.0 {
+        return Ok(None);
+    }
+    for _ in 0..MAX_ITER_CROSSING {
+        if fb == 0.0 || (b - a).abs() < TOL_CROSSING * b.abs() {
+            return Ok(Some(b));
+        }
+        let c = (a * fb - b * fa) / (fb - fa);
+        let fc = f(c)?;
+        if fc * fb < 0.0 {
+            a = b;
+            fa = fb;
+        } else {
+            fa *= 0.5;
+        }
+        b = c;
+        fb = fc;
+    }
+    Err(FeosError::NotConverged(
+        SolverFailure::new("isoline phase boundary")
+            .iterations(MAX_ITER_CROSSING)
+            .residual(fb.abs()),
+    ))
+}
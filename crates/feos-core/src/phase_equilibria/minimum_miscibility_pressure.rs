@@ -0,0 +1,136 @@
+use super::FlashWorkspace;
+use crate::equation_of_state::Residual;
+use crate::errors::FeosResult;
+use crate::state::{DensityInitialization, State};
+use crate::{ReferenceSystem, SolverOptions};
+use ndarray::{Array1, Zip};
+use quantity::{Moles, Pressure, Temperature};
+use std::sync::Arc;
+
+const DEFAULT_CONTACTS: usize = 20;
+const MISCIBLE_TIE_LINE_LENGTH: f64 = 1e-6;
+
+/// Minimum miscibility pressure (MMP) of an oil and an injection gas
+/// from the multiple-mixing-cell method.
+///
+/// For every pressure, the gas is repeatedly contacted with the oil in
+/// a growing sequence of mixing cells in which the equilibrium vapor
+/// moves ahead of the equilibrium liquid. The shortest tie line found in
+/// all cells approximates the key tie line that controls the development
+/// of miscibility. The MMP is obtained by extrapolating the length of
+/// the shortest tie line to zero.
+///
+/// See https://doi.org/10.2118/116823-PA
+pub struct MinimumMiscibilityPressure {
+    /// The pressures at which the mixing cells are evaluated.
+    pub pressure: Pressure<Array1<f64>>,
+    /// The length of the shortest tie line at each pressure.
+    pub tie_line_length: Array1<f64>,
+    /// The extrapolated minimum miscibility pressure.
+    pub mmp: Option<Pressure>,
+}
+
+impl MinimumMiscibilityPressure {
+    /// Calculate the MMP at given temperature with the multiple-mixing-cell method.
+    ///
+    /// The tie line lengths are calculated at all `pressures` with `contacts`
+    /// contacts (20 by default) between gas and oil, which are mixed in equal
+    /// amounts of substance. The MMP is linearly extrapolated from the two
+    /// highest pressures at which the fluids are not yet miscible. If the
+    /// tie line length does not decrease between these pressures or the fluids
+    /// are miscible at all pressures, no MMP is returned.
+    pub fn multiple_mixing_cell<E: Residual>(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        oil: &Moles<Array1<f64>>,
+        gas: &Moles<Array1<f64>>,
+        pressures: &Pressure<Array1<f64>>,
+        contacts: Option<usize>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let oil = eos.validate_moles(Some(oil))?;
+        let gas = eos.validate_moles(Some(gas))?;
+        let oil = (&oil / oil.sum()).into_value();
+        let gas = (&gas / gas.sum()).into_value();
+        let contacts = contacts.unwrap_or(DEFAULT_CONTACTS);
+
+        let tie_line_length: Array1<f64> = pressures
+            .into_iter()
+            .map(|p| shortest_tie_line(eos, temperature, p, &oil, &gas, contacts, options))
+            .collect::<FeosResult<_>>()?;
+
+        // extrapolate the two highest immiscible pressures to a vanishing tie line
+        let immiscible: Vec<_> = (0..tie_line_length.len())
+            .filter(|&i| tie_line_length[i] > MISCIBLE_TIE_LINE_LENGTH)
+            .collect();
+        let mmp = match immiscible[..] {
+            [.., i, j] if tie_line_length[j] < tie_line_length[i] => {
+                let (p1, p2) = (pressures.get(i), pressures.get(j));
+                let (l1, l2) = (tie_line_length[i], tie_line_length[j]);
+                Some(p2 + (p2 - p1) * (l2 / (l1 - l2)))
+            }
+            _ => None,
+        };
+        Ok(Self {
+            pressure: pressures.clone(),
+            tie_line_length,
+            mmp,
+        })
+    }
+}
+
+/// Length of the shortest tie line in all mixing cells at given pressure.
+fn shortest_tie_line<E: Residual>(
+    eos: &Arc<E>,
+    temperature: Temperature,
+    pressure: Pressure,
+    oil: &Array1<f64>,
+    gas: &Array1<f64>,
+    contacts: usize,
+    options: SolverOptions,
+) -> FeosResult<f64> {
+    let mut shortest = f64::INFINITY;
+    let mut cells = vec![0.5 * (oil + gas)];
+    let mut workspace = FlashWorkspace::new(eos.components());
+    for _ in 0..contacts {
+        // equilibrate all cells
+        let mut liquids = Vec::with_capacity(cells.len() + 1);
+        let mut vapors = Vec::with_capacity(cells.len() + 1);
+        vapors.push(gas.clone());
+        for z in &cells {
+            let feed = State::new_npt(
+                eos,
+                temperature,
+                pressure,
+                &Moles::from_reduced(z.clone()),
+                DensityInitialization::None,
+            )?;
+            // cells without a phase split remain single phase
+            match feed.tp_flash_with_workspace(None, options, None, &mut workspace) {
+                Ok(vle) => {
+                    let (x, y) = (&vle.liquid().molefracs, &vle.vapor().molefracs);
+                    let length = Zip::from(x)
+                        .and(y)
+                        .fold(0.0, |acc, x, y| acc + (x - y).powi(2))
+                        .sqrt();
+                    shortest = shortest.min(length);
+                    liquids.push(x.clone());
+                    vapors.push(y.clone());
+                }
+                Err(_) => {
+                    liquids.push(z.clone());
+                    vapors.push(z.clone());
+                }
+            }
+        }
+        liquids.push(oil.clone());
+
+        // the vapor of each cell moves on to the liquid of the next cell
+        cells = liquids
+            .iter()
+            .zip(vapors.iter())
+            .map(|(x, y)| 0.5 * (x + y))
+            .collect();
+    }
+    Ok(if shortest.is_finite() { shortest } else { 0.0 })
+}
@@ -1,23 +1,67 @@
 use crate::equation_of_state::Residual;
 use crate::errors::{FeosError, FeosResult};
-use crate::state::{DensityInitialization, State};
+use crate::state::{DensityInitialization, State, StateRecord};
 use crate::{Contributions, ReferenceSystem};
 use ndarray::Array1;
-use quantity::{Dimensionless, Energy, Moles, Pressure, Temperature, RGAS};
+use quantity::{Dimensionless, Energy, Moles, Pressure, RGAS, Temperature};
+use serde::{Serialize, Serializer};
 use std::fmt;
 use std::fmt::Write;
 use std::sync::Arc;
 
+#[cfg(feature = "rayon")]
+mod batch_flash;
+mod beta_flash;
 mod bubble_dew;
+mod cricondens;
+mod critical_locus;
+#[cfg(feature = "extended_precision")]
+mod extended_precision;
+mod flash_cache;
+mod flash_derivatives;
+mod flash_specification;
+mod gradient_theory;
+mod heating_curve;
+mod isolines;
+mod minimum_miscibility_pressure;
+mod parachor;
 mod phase_diagram_binary;
 mod phase_diagram_pure;
 mod phase_envelope;
+mod post_processing;
+mod pvt_experiments;
+mod rachford_rice;
+mod saturation_cache;
+mod saturation_curve;
+mod saturation_derivatives;
 mod stability_analysis;
+mod three_phase_flash;
 mod tp_flash;
 mod vle_pure;
+#[cfg(feature = "rayon")]
+pub use batch_flash::FlashBatch;
 pub use bubble_dew::TemperatureOrPressure;
+pub use critical_locus::{CriticalLocus, PhaseBehaviorType};
+pub use flash_cache::FlashCache;
+pub use flash_derivatives::FlashDerivatives;
+pub use flash_specification::FlashSpecification;
+pub use gradient_theory::GradientTheoryProfile;
+pub use isolines::{Isoline, IsolinePoint};
+pub use minimum_miscibility_pressure::MinimumMiscibilityPressure;
 pub use phase_diagram_binary::PhaseDiagramHetero;
 pub use phase_diagram_pure::PhaseDiagram;
+pub use pvt_experiments::{
+    ConstantCompositionExpansion, ConstantVolumeDepletion, DifferentialLiberation, SwellingTest,
+};
+pub use rachford_rice::{
+    rachford_rice, rachford_rice_compositions, rachford_rice_multiphase,
+    rachford_rice_multiphase_compositions, rachford_rice_negative_flash,
+};
+pub use saturation_cache::SaturationCache;
+pub use saturation_curve::SaturationCurve;
+pub use saturation_derivatives::SaturationDerivatives;
+pub use stability_analysis::PhaseStability;
+pub use tp_flash::FlashWorkspace;
 
 /// A thermodynamic equilibrium state.
 ///
@@ -29,7 +73,13 @@ pub use phase_diagram_pure::PhaseDiagram;
 /// + [Bubble and dew point calculations](#bubble-and-dew-point-calculations)
 /// + [Heteroazeotropes](#heteroazeotropes)
 /// + [Flash calculations](#flash-calculations)
+/// + [Flash derivatives](#flash-derivatives)
+/// + [Saturation derivatives](#saturation-derivatives)
+/// + [Post-processing](#post-processing)
+/// + [Cricondentherm and cricondenbar](#cricondentherm-and-cricondenbar)
 /// + [Pure component phase equilibria](#pure-component-phase-equilibria)
+/// + [Surface tension](#surface-tension)
+/// + [Density gradient theory](#density-gradient-theory)
 /// + [Utility functions](#utility-functions)
 #[derive(Debug)]
 pub struct PhaseEquilibrium<E, const N: usize>([State<E>; N]);
@@ -106,6 +156,32 @@ impl<E> PhaseEquilibrium<E, 3> {
     }
 }
 
+impl<E, const N: usize> Serialize for PhaseEquilibrium<E, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(StateRecord::from))
+    }
+}
+
+impl<E: Residual, const N: usize> PhaseEquilibrium<E, N> {
+    /// Restore a phase equilibrium from the [StateRecord]s of its phases.
+    ///
+    /// The records have to be in the same order as in the serialized
+    /// phase equilibrium, i.e., starting with the vapor phase.
+    pub fn from_records(eos: &Arc<E>, records: &[StateRecord]) -> FeosResult<Self> {
+        let states = records
+            .iter()
+            .map(|r| State::from_record(eos, r))
+            .collect::<FeosResult<Vec<_>>>()?;
+        let states: [State<E>; N] = states.try_into().map_err(|s: Vec<_>| {
+            FeosError::Error(format!(
+                "{} states were provided for a phase equilibrium with {N} phases.",
+                s.len()
+            ))
+        })?;
+        Ok(Self(states))
+    }
+}
+
 impl<E: Residual> PhaseEquilibrium<E, 2> {
     pub(super) fn from_states(state1: State<E>, state2: State<E>) -> Self {
         let (vapor, liquid) = if state1.density < state2.density {
@@ -170,23 +246,6 @@ impl<E: Residual, const N: usize> PhaseEquilibrium<E, N> {
         Ok(self)
     }
 
-    pub(super) fn update_moles(
-        &mut self,
-        pressure: Pressure,
-        moles: [&Moles<Array1<f64>>; N],
-    ) -> FeosResult<()> {
-        for (i, s) in self.0.iter_mut().enumerate() {
-            *s = State::new_npt(
-                &s.eos,
-                s.temperature,
-                pressure,
-                moles[i],
-                DensityInitialization::InitialDensity(s.density),
-            )?;
-        }
-        Ok(())
-    }
-
     // Total Gibbs energy excluding the constant contribution RT sum_i N_i ln(\Lambda_i^3)
     pub(super) fn total_gibbs_energy(&self) -> Energy {
         self.0.iter().fold(Energy::from_reduced(0.0), |acc, s| {
@@ -0,0 +1,22 @@
+use super::PhaseEquilibrium;
+use crate::equation_of_state::{Parachor, Residual};
+use crate::errors::FeosResult;
+use quantity::{CENTI, METER, MILLI, MOL, NEWTON, SurfaceTension};
+use typenum::P3;
+
+/// # Surface tension
+impl<E: Residual + Parachor> PhaseEquilibrium<E, 2> {
+    /// Surface tension from the Macleod-Sugden equation:
+    /// $\sigma^\frac{1}{4}=\sum_i P_i\left(x_i\rho^L-y_i\rho^V\right)$
+    ///
+    /// The parachors $P_i$ are provided by the model, the phase
+    /// densities and compositions are taken from the phase equilibrium.
+    pub fn parachor_surface_tension(&self) -> FeosResult<SurfaceTension> {
+        let parachor = self.vapor().eos.parachor()?;
+        let density_unit = MOL / (CENTI * METER).powi::<P3>();
+        let rho_v = self.vapor().partial_density.convert_to(density_unit);
+        let rho_l = self.liquid().partial_density.convert_to(density_unit);
+        let sigma = (&parachor * (rho_l - rho_v)).sum().abs().powi(4);
+        Ok(sigma * MILLI * NEWTON / METER)
+    }
+}
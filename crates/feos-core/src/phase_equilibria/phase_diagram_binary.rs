@@ -1,12 +1,12 @@
 use super::bubble_dew::TemperatureOrPressure;
-use super::{PhaseDiagram, PhaseEquilibrium};
+use super::{FlashWorkspace, PhaseDiagram, PhaseEquilibrium};
 use crate::equation_of_state::Residual;
 use crate::errors::{FeosError, FeosResult};
 use crate::state::{Contributions, DensityInitialization, State, StateBuilder, TPSpec};
 use crate::{ReferenceSystem, SolverOptions};
-use ndarray::{arr1, arr2, concatenate, s, Array1, Array2, Axis};
-use num_dual::linalg::{norm, LU};
-use quantity::{Density, Moles, Pressure, Temperature, RGAS};
+use ndarray::{Array1, Array2, Axis, arr1, arr2, concatenate, s};
+use num_dual::linalg::{LU, norm};
+use quantity::{Density, Moles, Pressure, RGAS, Temperature};
 use std::sync::Arc;
 
 const DEFAULT_POINTS: usize = 51;
@@ -155,18 +155,19 @@ impl<E: Residual> PhaseDiagram<E, 2> {
 
         let (t_vec, p_vec) = temperature_or_pressure.linspace(min_tp, max_tp, npoints);
         let mut vle = None;
+        let mut workspace = FlashWorkspace::new(eos.components());
         for i in 0..npoints {
             let (t, p) = (t_vec.get(i), p_vec.get(i));
-            vle = PhaseEquilibrium::tp_flash(
-                eos,
-                t,
-                p,
-                feed,
-                vle.as_ref(),
-                SolverOptions::default(),
-                None,
-            )
-            .ok();
+            vle = State::new_npt(eos, t, p, feed, DensityInitialization::None)
+                .and_then(|feed| {
+                    feed.tp_flash_with_workspace(
+                        vle.as_ref(),
+                        SolverOptions::default(),
+                        None,
+                        &mut workspace,
+                    )
+                })
+                .ok();
             if let Some(vle) = vle.as_ref() {
                 states.push(vle.clone());
             }
@@ -471,9 +472,9 @@ impl<E: Residual> PhaseEquilibrium<E, 3> {
                     || rho_l2.get(i).is_sign_negative()
                     || rho_v.get(i).is_sign_negative()
                 {
-                    return Err(FeosError::IterationFailed(String::from(
-                        "PhaseEquilibrium::heteroazeotrope_t",
-                    )));
+                    return Err(FeosError::IterationFailed(
+                        "PhaseEquilibrium::heteroazeotrope_t".into(),
+                    ));
                 }
             }
 
@@ -491,9 +492,9 @@ impl<E: Residual> PhaseEquilibrium<E, 3> {
                 .partial_density(&rho_v)
                 .build()?;
         }
-        Err(FeosError::NotConverged(String::from(
-            "PhaseEquilibrium::heteroazeotrope_t",
-        )))
+        Err(FeosError::NotConverged(
+            "PhaseEquilibrium::heteroazeotrope_t".into(),
+        ))
     }
 
     /// Calculate a heteroazeotrope (three phase equilbrium) for a binary
@@ -618,9 +619,9 @@ impl<E: Residual> PhaseEquilibrium<E, 3> {
                     || rho_v.get(i).is_sign_negative()
                     || t.is_sign_negative()
                 {
-                    return Err(FeosError::IterationFailed(String::from(
-                        "PhaseEquilibrium::heteroazeotrope_p",
-                    )));
+                    return Err(FeosError::IterationFailed(
+                        "PhaseEquilibrium::heteroazeotrope_p".into(),
+                    ));
                 }
             }
 
@@ -638,8 +639,8 @@ impl<E: Residual> PhaseEquilibrium<E, 3> {
                 .partial_density(&rho_v)
                 .build()?;
         }
-        Err(FeosError::NotConverged(String::from(
-            "PhaseEquilibrium::heteroazeotrope_p",
-        )))
+        Err(FeosError::NotConverged(
+            "PhaseEquilibrium::heteroazeotrope_p".into(),
+        ))
     }
 }
@@ -1,15 +1,17 @@
 use super::PhaseEquilibrium;
-use crate::equation_of_state::Residual;
-use crate::errors::FeosResult;
-use crate::state::{State, StateVec};
 #[cfg(feature = "rayon")]
 use crate::ReferenceSystem;
 use crate::SolverOptions;
+use crate::cancellation::check_cancelled;
+use crate::equation_of_state::Residual;
+use crate::errors::FeosResult;
+use crate::observer::{is_observed, report_iteration};
+use crate::state::{Contributions, State, StateVec};
 #[cfg(feature = "rayon")]
 use ndarray::{Array1, ArrayView1, Axis};
-use quantity::Temperature;
+use quantity::{KELVIN, PASCAL, Temperature};
 #[cfg(feature = "rayon")]
-use rayon::{prelude::*, ThreadPool};
+use rayon::{ThreadPool, prelude::*};
 use std::sync::Arc;
 
 /// Pure component and binary mixture phase diagrams.
@@ -50,8 +52,9 @@ impl<E: Residual> PhaseDiagram<E, 2> {
         let temperatures = Temperature::linspace(min_temperature, max_temperature, npoints - 1);
 
         let mut vle = None;
-        for ti in &temperatures {
+        for (i, ti) in temperatures.into_iter().enumerate() {
             vle = PhaseEquilibrium::pure(eos, ti, vle.as_ref(), options).ok();
+            report_point("pure phase diagram", i, vle.as_ref())?;
             if let Some(vle) = vle.as_ref() {
                 states.push(vle.clone());
             }
@@ -72,6 +75,34 @@ impl<E: Residual> PhaseDiagram<E, 2> {
     }
 }
 
+/// Report a point of a phase envelope to the installed observer and abort
+/// the envelope if the calculation was cancelled.
+///
+/// Points that could not be calculated are reported with a NaN residual.
+pub(super) fn report_point<E: Residual>(
+    solver: &'static str,
+    point: usize,
+    vle: Option<&PhaseEquilibrium<E, 2>>,
+) -> FeosResult<()> {
+    if is_observed() {
+        match vle {
+            Some(vle) => report_iteration(
+                solver,
+                point,
+                0.0,
+                &[
+                    vle.vapor().temperature.convert_to(KELVIN),
+                    vle.vapor()
+                        .pressure(Contributions::Total)
+                        .convert_to(PASCAL),
+                ],
+            ),
+            None => report_iteration(solver, point, f64::NAN, &[]),
+        }
+    }
+    check_cancelled()
+}
+
 #[cfg(feature = "rayon")]
 impl<E: Residual> PhaseDiagram<E, 2> {
     fn solve_temperatures(
@@ -1,10 +1,13 @@
-use super::{PhaseDiagram, PhaseEquilibrium};
+use super::phase_diagram_pure::report_point;
+use super::{PhaseDiagram, PhaseEquilibrium, SaturationCache};
+use crate::SolverOptions;
+#[cfg(feature = "extended_precision")]
+use crate::TemperatureOrPressure;
 use crate::equation_of_state::Residual;
 use crate::errors::FeosResult;
-use quantity::{Moles, Pressure, Temperature};
 use crate::state::{Contributions, State};
-use crate::SolverOptions;
 use ndarray::Array1;
+use quantity::{Moles, Pressure, Temperature};
 use std::sync::Arc;
 
 impl<E: Residual> PhaseDiagram<E, 2> {
@@ -16,6 +19,51 @@ impl<E: Residual> PhaseDiagram<E, 2> {
         npoints: usize,
         critical_temperature: Option<Temperature>,
         options: (SolverOptions, SolverOptions),
+    ) -> FeosResult<Self> {
+        Self::bubble_point_line_impl(
+            eos,
+            moles,
+            min_temperature,
+            npoints,
+            critical_temperature,
+            None,
+            options,
+        )
+    }
+
+    /// Calculate the bubble point line of a mixture with given composition.
+    ///
+    /// Points without a converged predecessor (the first point and points
+    /// following a failed point) are initialized with Raoult's law using
+    /// the vapor pressures from `cache`.
+    pub fn bubble_point_line_with_cache(
+        eos: &Arc<E>,
+        moles: &Moles<Array1<f64>>,
+        min_temperature: Temperature,
+        npoints: usize,
+        critical_temperature: Option<Temperature>,
+        cache: &SaturationCache<E>,
+        options: (SolverOptions, SolverOptions),
+    ) -> FeosResult<Self> {
+        Self::bubble_point_line_impl(
+            eos,
+            moles,
+            min_temperature,
+            npoints,
+            critical_temperature,
+            Some(cache),
+            options,
+        )
+    }
+
+    fn bubble_point_line_impl(
+        eos: &Arc<E>,
+        moles: &Moles<Array1<f64>>,
+        min_temperature: Temperature,
+        npoints: usize,
+        critical_temperature: Option<Temperature>,
+        cache: Option<&SaturationCache<E>>,
+        options: (SolverOptions, SolverOptions),
     ) -> FeosResult<Self> {
         let mut states = Vec::with_capacity(npoints);
 
@@ -32,21 +80,30 @@ impl<E: Residual> PhaseDiagram<E, 2> {
         let molefracs = (moles / moles.sum()).into_value();
 
         let mut vle: Option<PhaseEquilibrium<E, 2>> = None;
-        for ti in &temperatures {
+        for (i, ti) in temperatures.into_iter().enumerate() {
             // calculate new liquid point
-            let p_init = vle
-                .as_ref()
-                .map(|vle| vle.vapor().pressure(Contributions::Total));
-            let vapor_molefracs = vle.as_ref().map(|vle| &vle.vapor().molefracs);
-            vle = PhaseEquilibrium::bubble_point(
+            let (p_init, vapor_molefracs) = match (&vle, cache) {
+                (Some(vle), _) => (
+                    Some(vle.vapor().pressure(Contributions::Total)),
+                    Some(vle.vapor().molefracs.clone()),
+                ),
+                (None, Some(cache)) => cache.raoult_bubble_point(ti, &molefracs).unzip(),
+                (None, None) => (None, None),
+            };
+            let next = PhaseEquilibrium::bubble_point(
                 eos,
                 ti,
                 &molefracs,
                 p_init,
-                vapor_molefracs,
+                vapor_molefracs.as_ref(),
                 options,
             )
             .ok();
+            #[cfg(feature = "extended_precision")]
+            let next =
+                next.or_else(|| retry_in_extended_precision(eos, ti, &molefracs, true, &vle));
+            vle = next;
+            report_point("bubble point line", i, vle.as_ref())?;
 
             if let Some(vle) = vle.as_ref() {
                 states.push(vle.clone());
@@ -65,6 +122,50 @@ impl<E: Residual> PhaseDiagram<E, 2> {
         npoints: usize,
         critical_temperature: Option<Temperature>,
         options: (SolverOptions, SolverOptions),
+    ) -> FeosResult<Self> {
+        Self::dew_point_line_impl(
+            eos,
+            moles,
+            min_temperature,
+            npoints,
+            critical_temperature,
+            None,
+            options,
+        )
+    }
+
+    /// Calculate the dew point line of a mixture with given composition.
+    ///
+    /// Points of the isothermal part without a converged predecessor are
+    /// initialized with Raoult's law using the vapor pressures from `cache`.
+    pub fn dew_point_line_with_cache(
+        eos: &Arc<E>,
+        moles: &Moles<Array1<f64>>,
+        min_temperature: Temperature,
+        npoints: usize,
+        critical_temperature: Option<Temperature>,
+        cache: &SaturationCache<E>,
+        options: (SolverOptions, SolverOptions),
+    ) -> FeosResult<Self> {
+        Self::dew_point_line_impl(
+            eos,
+            moles,
+            min_temperature,
+            npoints,
+            critical_temperature,
+            Some(cache),
+            options,
+        )
+    }
+
+    fn dew_point_line_impl(
+        eos: &Arc<E>,
+        moles: &Moles<Array1<f64>>,
+        min_temperature: Temperature,
+        npoints: usize,
+        critical_temperature: Option<Temperature>,
+        cache: Option<&SaturationCache<E>>,
+        options: (SolverOptions, SolverOptions),
     ) -> FeosResult<Self> {
         let mut states = Vec::with_capacity(npoints);
 
@@ -82,14 +183,29 @@ impl<E: Residual> PhaseDiagram<E, 2> {
         let molefracs = (moles / moles.sum()).into_value();
 
         let mut vle: Option<PhaseEquilibrium<E, 2>> = None;
-        for ti in &temperatures {
-            let p_init = vle
-                .as_ref()
-                .map(|vle| vle.vapor().pressure(Contributions::Total));
-            let liquid_molefracs = vle.as_ref().map(|vle| &vle.liquid().molefracs);
-            vle =
-                PhaseEquilibrium::dew_point(eos, ti, &molefracs, p_init, liquid_molefracs, options)
-                    .ok();
+        for (i, ti) in temperatures.into_iter().enumerate() {
+            let (p_init, liquid_molefracs) = match (&vle, cache) {
+                (Some(vle), _) => (
+                    Some(vle.vapor().pressure(Contributions::Total)),
+                    Some(vle.liquid().molefracs.clone()),
+                ),
+                (None, Some(cache)) => cache.raoult_dew_point(ti, &molefracs).unzip(),
+                (None, None) => (None, None),
+            };
+            let next = PhaseEquilibrium::dew_point(
+                eos,
+                ti,
+                &molefracs,
+                p_init,
+                liquid_molefracs.as_ref(),
+                options,
+            )
+            .ok();
+            #[cfg(feature = "extended_precision")]
+            let next =
+                next.or_else(|| retry_in_extended_precision(eos, ti, &molefracs, false, &vle));
+            vle = next;
+            report_point("dew point line", i, vle.as_ref())?;
             if let Some(vle) = vle.as_ref() {
                 states.push(vle.clone());
             }
@@ -106,12 +222,17 @@ impl<E: Residual> PhaseDiagram<E, 2> {
             min_pressure + (p_c - min_pressure) * ((n_p - 2) as f64 / (n_p - 1) as f64);
         let pressures = Pressure::linspace(min_pressure, max_pressure, n_p);
 
-        for pi in &pressures {
+        for (i, pi) in pressures.into_iter().enumerate() {
             let t_init = vle.as_ref().map(|vle| vle.vapor().temperature);
             let liquid_molefracs = vle.as_ref().map(|vle| &vle.liquid().molefracs);
-            vle =
+            let next =
                 PhaseEquilibrium::dew_point(eos, pi, &molefracs, t_init, liquid_molefracs, options)
                     .ok();
+            #[cfg(feature = "extended_precision")]
+            let next =
+                next.or_else(|| retry_in_extended_precision(eos, pi, &molefracs, false, &vle));
+            vle = next;
+            report_point("dew point line", n_t - 1 + i, vle.as_ref())?;
             if let Some(vle) = vle.as_ref() {
                 states.push(vle.clone());
             }
@@ -144,10 +265,13 @@ impl<E: Residual> PhaseDiagram<E, 2> {
             + (sc.temperature - min_temperature) * ((npoints - 2) as f64 / (npoints - 1) as f64);
         let temperatures = Temperature::linspace(min_temperature, max_temperature, npoints - 1);
 
-        for ti in &temperatures {
-            let spinodal = State::spinodal(eos, ti, Some(moles), options).ok();
+        for (i, ti) in temperatures.into_iter().enumerate() {
+            let spinodal = State::spinodal(eos, ti, Some(moles), options)
+                .ok()
+                .map(PhaseEquilibrium);
+            report_point("spinodal line", i, spinodal.as_ref())?;
             if let Some(spinodal) = spinodal {
-                states.push(PhaseEquilibrium(spinodal));
+                states.push(spinodal);
             }
         }
         states.push(PhaseEquilibrium::from_states(sc.clone(), sc));
@@ -155,3 +279,91 @@ impl<E: Residual> PhaseDiagram<E, 2> {
         Ok(PhaseDiagram::new(states))
     }
 }
+
+/// Retry a bubble or dew point that failed in `f64` in extended precision,
+/// starting from the previous point of the phase envelope.
+#[cfg(feature = "extended_precision")]
+fn retry_in_extended_precision<E: Residual, TP: TemperatureOrPressure>(
+    eos: &Arc<E>,
+    temperature_or_pressure: TP,
+    molefracs: &Array1<f64>,
+    bubble: bool,
+    previous: &Option<PhaseEquilibrium<E, 2>>,
+) -> Option<PhaseEquilibrium<E, 2>> {
+    PhaseEquilibrium::bubble_dew_point_extended_precision(
+        eos,
+        temperature_or_pressure.into(),
+        molefracs,
+        bubble,
+        previous.as_ref()?,
+    )
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::propane_butane;
+    use ndarray::arr1;
+    use quantity::{KELVIN, MOL};
+
+    #[test]
+    #[cfg(feature = "extended_precision")]
+    fn near_critical_phase_envelope() -> FeosResult<()> {
+        let eos = Arc::new(propane_butane()?);
+        let moles = arr1(&[0.4, 0.6]) * MOL;
+        let options = (SolverOptions::default(), SolverOptions::default());
+        let envelope =
+            PhaseDiagram::bubble_point_line(&eos, &moles, 300.0 * KELVIN, 101, None, options)?;
+
+        // all points up to the critical point are found
+        assert_eq!(envelope.states.len(), 101);
+        let cp = envelope.states[100].vapor();
+        let last = &envelope.states[99];
+        assert!(cp.temperature - last.vapor().temperature < 1.1 * KELVIN);
+        assert!(last.liquid().density > last.vapor().density);
+        for vle in &envelope.states[..100] {
+            let p_v = vle.vapor().pressure(Contributions::Total);
+            let p_l = vle.liquid().pressure(Contributions::Total);
+            assert!(((p_v - p_l) / p_l).into_value().abs() < 1e-8);
+        }
+
+        // the dew point line is traced in temperature and pressure
+        let envelope =
+            PhaseDiagram::dew_point_line(&eos, &moles, 300.0 * KELVIN, 101, None, options)?;
+        assert_eq!(envelope.states.len(), 101);
+
+        for vle in &envelope.states {
+            let p_v = vle.vapor().pressure(Contributions::Total);
+            let p_l = vle.liquid().pressure(Contributions::Total);
+            assert!(((p_v - p_l) / p_l).into_value().abs() < 1e-8);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn phase_envelope_with_cache() -> FeosResult<()> {
+        let eos = Arc::new(propane_butane()?);
+        let moles = arr1(&[0.4, 0.6]) * MOL;
+        let options = (SolverOptions::default(), SolverOptions::default());
+        let cache = SaturationCache::new(&eos, 1e-3 * KELVIN, 100)?;
+
+        // the first points are initialized with Raoult's law and the dew
+        // point line reuses the vapor pressures of the bubble point line
+        let t_min = 250.0 * KELVIN;
+        let bubble = PhaseDiagram::bubble_point_line(&eos, &moles, t_min, 21, None, options)?;
+        let cached = PhaseDiagram::bubble_point_line_with_cache(
+            &eos, &moles, t_min, 21, None, &cache, options,
+        )?;
+        assert_eq!(cache.statistics(), (0, 2));
+        PhaseDiagram::dew_point_line_with_cache(&eos, &moles, t_min, 21, None, &cache, options)?;
+        assert_eq!(cache.statistics(), (2, 2));
+
+        for (b, c) in bubble.states.iter().zip(&cached.states) {
+            let p_b = b.vapor().pressure(Contributions::Total);
+            let p_c = c.vapor().pressure(Contributions::Total);
+            assert!(((p_b - p_c) / p_b).into_value().abs() < 1e-8);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use super::PhaseEquilibrium;
+use crate::equation_of_state::{Molarweight, Residual};
+use crate::errors::{FeosError, FeosResult};
+use crate::state::State;
+use ndarray::{Array1, Array2};
+
+/// # Post-processing
+impl<E: Residual, const N: usize> PhaseEquilibrium<E, N> {
+    /// The states of all phases.
+    pub fn phases(&self) -> &[State<E>; N] {
+        &self.0
+    }
+
+    /// Evaluate a property for the state of every phase.
+    ///
+    /// ```
+    /// # use feos_core::{Contributions, FeosResult, PhaseEquilibrium, SolverOptions};
+    /// # use feos_core::cubic::{PengRobinson, PengRobinsonParameters};
+    /// # use quantity::KELVIN;
+    /// # use std::sync::Arc;
+    /// # fn main() -> FeosResult<()> {
+    /// # let parameters = PengRobinsonParameters::new_simple(&[369.96], &[4250000.0], &[0.153], &[44.0962])?;
+    /// # let eos = Arc::new(PengRobinson::new(Arc::new(parameters)));
+    /// let vle = PhaseEquilibrium::pure(&eos, 300.0 * KELVIN, None, SolverOptions::default())?;
+    /// let [h_vapor, h_liquid] = vle.phase_properties(|s| s.residual_molar_enthalpy());
+    /// assert!(h_vapor > h_liquid);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn phase_properties<T>(&self, property: impl FnMut(&State<E>) -> T) -> [T; N] {
+        self.0.each_ref().map(property)
+    }
+
+    /// Molar phase fractions $\beta_k=\frac{N^{(k)}}{N}$.
+    pub fn phase_fractions(&self) -> Array1<f64> {
+        let n = self
+            .0
+            .iter()
+            .map(|s| s.total_moles)
+            .reduce(|a, b| a + b)
+            .unwrap();
+        self.0
+            .iter()
+            .map(|s| (s.total_moles / n).into_value())
+            .collect()
+    }
+
+    /// Distribution coefficients $D_i=\frac{x_i^{(k)}}{x_i^{(l)}}$ of all
+    /// components between the phases with indices `phase` ($k$) and `reference` ($l$).
+    pub fn distribution_coefficients(
+        &self,
+        phase: usize,
+        reference: usize,
+    ) -> FeosResult<Array1<f64>> {
+        if phase >= N || reference >= N {
+            return Err(FeosError::Error(format!(
+                "phase indices {phase} and {reference} are invalid for a phase equilibrium with {N} phases."
+            )));
+        }
+        Ok(&self.0[phase].molefracs / &self.0[reference].molefracs)
+    }
+}
+
+impl<E: Residual + Molarweight, const N: usize> PhaseEquilibrium<E, N> {
+    /// Mass fractions of the phases $\frac{m^{(k)}}{m}$.
+    pub fn phase_mass_fractions(&self) -> Array1<f64> {
+        let m = self.0.each_ref().map(|s| s.total_mass());
+        let total = m.iter().copied().reduce(|a, b| a + b).unwrap();
+        m.iter().map(|&m| (m / total).into_value()).collect()
+    }
+}
+
+impl<E: Residual> PhaseEquilibrium<E, 2> {
+    /// K-values $K_i=\frac{y_i}{x_i}$.
+    pub fn k_values(&self) -> Array1<f64> {
+        &self.vapor().molefracs / &self.liquid().molefracs
+    }
+
+    /// Relative volatilities $\alpha_{ij}=\frac{K_i}{K_j}$.
+    pub fn relative_volatilities(&self) -> Array2<f64> {
+        let k = self.k_values();
+        Array2::from_shape_fn((k.len(), k.len()), |(i, j)| k[i] / k[j])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::propane_butane;
+    use crate::{Contributions, SolverOptions};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{KELVIN, MOL};
+    use std::sync::Arc;
+
+    #[test]
+    fn post_processing() -> FeosResult<()> {
+        let eos = Arc::new(propane_butane()?);
+        let t = 330.0 * KELVIN;
+        let z = arr1(&[0.5, 0.5]);
+        let options = (SolverOptions::default(), SolverOptions::default());
+        let bubble = PhaseEquilibrium::bubble_point(&eos, t, &z, None, None, options)?;
+        let p = bubble.vapor().pressure(Contributions::Total) * 0.9;
+        let feed = z * MOL;
+        let vle =
+            PhaseEquilibrium::tp_flash(&eos, t, p, &feed, None, SolverOptions::default(), None)?;
+
+        let k = vle.k_values();
+        assert!(k[0] > 1.0 && k[1] < 1.0);
+        assert_eq!(k, vle.distribution_coefficients(0, 1)?);
+        let alpha = vle.relative_volatilities();
+        assert_relative_eq!(alpha[[0, 1]], k[0] / k[1]);
+        assert_relative_eq!(alpha[[0, 1]] * alpha[[1, 0]], 1.0);
+
+        // the phase fractions satisfy the material balance
+        let beta = vle.phase_fractions();
+        assert_relative_eq!(beta.sum(), 1.0);
+        let [y, x] = vle.phase_properties(|s| s.molefracs.clone());
+        assert_relative_eq!(
+            &y * beta[0] + &x * beta[1],
+            arr1(&[0.5, 0.5]),
+            epsilon = 1e-10
+        );
+
+        let w = vle.phase_mass_fractions();
+        assert_relative_eq!(w.sum(), 1.0);
+        let [m_vapor, m_liquid] = vle.phase_properties(|s| s.total_mass());
+        assert_relative_eq!(w[0], (m_vapor / (m_vapor + m_liquid)).into_value());
+        assert!(vle.distribution_coefficients(0, 2).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,720 @@
+use super::{FlashWorkspace, PhaseEquilibrium};
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult};
+use crate::state::{Contributions, DensityInitialization, PhaseLabel, State};
+use crate::{ReferenceSystem, SolverOptions};
+use ndarray::{Array1, Array2};
+use quantity::{Density, KELVIN, Moles, PASCAL, Pressure, RGAS, Temperature};
+use std::sync::Arc;
+
+/// Temperature of the standard conditions (15 °C).
+const STANDARD_TEMPERATURE: f64 = 288.15;
+/// Pressure of the standard conditions (1 atm).
+const STANDARD_PRESSURE: f64 = 101325.0;
+
+/// Upper bound of the pressure range in which saturation points are searched.
+const MAX_PRESSURE: f64 = 1e8;
+/// Relative width of the pressure interval after bracketing the saturation point.
+const BRACKET_TOL: f64 = 1e-4;
+
+/// Saturation point of a fluid at given temperature.
+///
+/// The highest pressure at which the fluid is unstable is bracketed with
+/// stability analyses at decreasing pressures and narrowed by bisection. This
+/// avoids the lower dew point of gas condensates. The saturation point is then
+/// converged as bubble point, if the incipient phase is less dense than the
+/// fluid, and as dew point otherwise. Returns the phase equilibrium and
+/// `true` for a bubble point.
+fn saturation_point<E: Residual>(
+    eos: &Arc<E>,
+    temperature: Temperature,
+    molefracs: &Array1<f64>,
+    options: SolverOptions,
+) -> FeosResult<(PhaseEquilibrium<E, 2>, bool)> {
+    let moles = Moles::from_reduced(molefracs.clone());
+    let incipient_phase = |p: f64| -> FeosResult<Option<(State<E>, State<E>)>> {
+        let feed = State::new_npt(
+            eos,
+            temperature,
+            Pressure::from_reduced(p),
+            &moles,
+            DensityInitialization::None,
+        )?;
+        let trial = feed.stability_analysis(options)?.into_iter().next();
+        Ok(trial.map(|trial| (feed, trial)))
+    };
+
+    // bracket the saturation pressure
+    let mut p_high = (MAX_PRESSURE * PASCAL).to_reduced();
+    if incipient_phase(p_high)?.is_some() {
+        return Err(FeosError::InvalidState(
+            "saturation point".into(),
+            "pressure (unstable)".into(),
+            MAX_PRESSURE,
+        ));
+    }
+    let mut p_low = 0.5 * p_high;
+    let mut phases = None;
+    for _ in 0..40 {
+        phases = incipient_phase(p_low)?;
+        if phases.is_some() {
+            break;
+        }
+        p_high = p_low;
+        p_low *= 0.5;
+    }
+    let Some(mut phases) = phases else {
+        return Err(FeosError::NoPhaseSplit);
+    };
+    while p_high / p_low > 1.0 + BRACKET_TOL {
+        let p = (p_low * p_high).sqrt();
+        match incipient_phase(p)? {
+            Some(p_phases) => {
+                p_low = p;
+                phases = p_phases;
+            }
+            None => p_high = p,
+        }
+    }
+
+    // converge the saturation point
+    let (feed, incipient) = phases;
+    let bubble_point = incipient.density < feed.density;
+    let p_init = Some(Pressure::from_reduced(p_low));
+    let w = Some(&incipient.molefracs);
+    let options = (options, options);
+    let vle = if bubble_point {
+        PhaseEquilibrium::bubble_point(eos, temperature, molefracs, p_init, w, options)?
+    } else {
+        PhaseEquilibrium::dew_point(eos, temperature, molefracs, p_init, w, options)?
+    };
+    Ok((vle, bubble_point))
+}
+
+/// Saturated phase of a phase equilibrium that has the composition of the fluid.
+fn saturated_phase<E>(vle: &PhaseEquilibrium<E, 2>, bubble_point: bool) -> &State<E> {
+    if bubble_point {
+        vle.liquid()
+    } else {
+        vle.vapor()
+    }
+}
+
+/// Phases of a fluid at given temperature and pressure. If no phase split
+/// is found, the single phase is classified as liquid or vapor with the
+/// phase identification parameter.
+#[expect(clippy::type_complexity)]
+fn flash<E: Residual>(
+    eos: &Arc<E>,
+    temperature: Temperature,
+    pressure: Pressure,
+    moles: &Moles<Array1<f64>>,
+    initial_state: &mut Option<PhaseEquilibrium<E, 2>>,
+    options: SolverOptions,
+    workspace: &mut FlashWorkspace,
+) -> FeosResult<(Option<State<E>>, Option<State<E>>)> {
+    let feed = State::new_npt(
+        eos,
+        temperature,
+        pressure,
+        moles,
+        DensityInitialization::None,
+    )?;
+    match feed.tp_flash_with_workspace(initial_state.as_ref(), options, None, workspace) {
+        Ok(vle) => {
+            let phases = (Some(vle.liquid().clone()), Some(vle.vapor().clone()));
+            *initial_state = Some(vle);
+            Ok(phases)
+        }
+        Err(FeosError::NoPhaseSplit | FeosError::TrivialSolution) => {
+            Ok(match feed.phase_label(None) {
+                PhaseLabel::Liquid => (Some(feed), None),
+                _ => (None, Some(feed)),
+            })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Check that the pressure steps of a depletion experiment decrease from
+/// the saturation pressure.
+fn check_depletion(
+    pressures: &Pressure<Array1<f64>>,
+    saturation_pressure: Pressure,
+) -> FeosResult<()> {
+    let p = pressures.to_reduced();
+    let p_sat = saturation_pressure.to_reduced();
+    if let Some(i) = (0..p.len()).find(|&i| p[i] >= if i == 0 { p_sat } else { p[i - 1] }) {
+        return Err(FeosError::InvalidState(
+            "depletion experiment".into(),
+            "pressure step (pressures have to decrease from the saturation pressure)".into(),
+            p[i],
+        ));
+    }
+    Ok(())
+}
+
+/// Constant composition expansion (CCE, constant mass expansion) of a fluid
+/// at reservoir temperature.
+///
+/// The fluid is expanded in a cell without removing any material. All
+/// volumes are reported relative to the volume $V_\mathrm{sat}$ at the
+/// saturation (bubble or dew) point.
+pub struct ConstantCompositionExpansion {
+    /// Saturation pressure
+    pub saturation_pressure: Pressure,
+    /// `true` if the saturation point is a bubble point (oil), `false` for
+    /// a dew point (gas condensate)
+    pub bubble_point: bool,
+    /// Pressure of the steps
+    pub pressure: Pressure<Array1<f64>>,
+    /// Relative volume $\frac{V}{V_\mathrm{sat}}$
+    pub relative_volume: Array1<f64>,
+    /// Relative liquid volume (liquid dropout) $\frac{V_\mathrm{L}}{V_\mathrm{sat}}$
+    pub liquid_volume: Array1<f64>,
+    /// Compressibility factor of the total fluid $Z=\frac{pV}{nRT}$
+    pub compressibility_factor: Array1<f64>,
+    /// Y-function $Y=\frac{p_\mathrm{sat}-p}{p\left(\frac{V}{V_\mathrm{sat}}-1\right)}$
+    /// below the saturation pressure (`NaN` above)
+    pub y_function: Array1<f64>,
+}
+
+impl ConstantCompositionExpansion {
+    /// Simulate a constant composition expansion of a fluid with the
+    /// composition `moles` at the given temperature and pressures.
+    pub fn new<E: Residual>(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        moles: &Moles<Array1<f64>>,
+        pressures: &Pressure<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let moles = eos.validate_moles(Some(moles))?;
+        let molefracs = (&moles / moles.sum()).into_value();
+        let moles = Moles::from_reduced(molefracs.clone());
+        let (vle, bubble_point) = saturation_point(eos, temperature, &molefracs, options)?;
+        let saturated = saturated_phase(&vle, bubble_point);
+        let p_sat = saturated.pressure(Contributions::Total);
+        let v_sat = saturated.molar_volume().to_reduced();
+        let rt = (RGAS * temperature).to_reduced();
+
+        let n = pressures.len();
+        let mut relative_volume = Array1::zeros(n);
+        let mut liquid_volume = Array1::zeros(n);
+        let mut compressibility_factor = Array1::zeros(n);
+        let mut y_function = Array1::from_elem(n, f64::NAN);
+        let mut initial_state = Some(vle.clone());
+        let mut workspace = FlashWorkspace::new(eos.components());
+        for (i, p) in pressures.into_iter().enumerate() {
+            let (liquid, vapor) = if p >= p_sat {
+                let density = if bubble_point {
+                    DensityInitialization::Liquid
+                } else {
+                    DensityInitialization::Vapor
+                };
+                let state = State::new_npt(eos, temperature, p, &moles, density)?;
+                if bubble_point {
+                    (Some(state), None)
+                } else {
+                    (None, Some(state))
+                }
+            } else {
+                flash(
+                    eos,
+                    temperature,
+                    p,
+                    &moles,
+                    &mut initial_state,
+                    options,
+                    &mut workspace,
+                )?
+            };
+            let v_liquid = liquid.map_or(0.0, |s| s.volume.to_reduced());
+            let v_vapor = vapor.map_or(0.0, |s| s.volume.to_reduced());
+            let v = v_liquid + v_vapor;
+            relative_volume[i] = v / v_sat;
+            liquid_volume[i] = v_liquid / v_sat;
+            compressibility_factor[i] = p.to_reduced() * v / rt;
+            if p < p_sat {
+                let (p, p_sat) = (p.to_reduced(), p_sat.to_reduced());
+                y_function[i] = (p_sat - p) / (p * (relative_volume[i] - 1.0));
+            }
+        }
+        Ok(Self {
+            saturation_pressure: p_sat,
+            bubble_point,
+            pressure: pressures.clone(),
+            relative_volume,
+            liquid_volume,
+            compressibility_factor,
+            y_function,
+        })
+    }
+}
+
+/// Constant volume depletion (CVD) of a gas condensate or a volatile oil at
+/// reservoir temperature.
+///
+/// Starting at the saturation point, the pressure is reduced in steps. After
+/// each step, vapor is removed from the cell at constant pressure until the
+/// volume of the cell equals the volume at the saturation point. The first
+/// entry of all results corresponds to the saturation point.
+pub struct ConstantVolumeDepletion {
+    /// Saturation pressure
+    pub saturation_pressure: Pressure,
+    /// Pressure of the steps
+    pub pressure: Pressure<Array1<f64>>,
+    /// Liquid dropout, i.e., liquid volume relative to the cell volume
+    pub liquid_volume: Array1<f64>,
+    /// Cumulative amount of produced gas relative to the initial amount of substance
+    pub cumulative_production: Array1<f64>,
+    /// Compressibility factor of the produced gas (of the incipient vapor at
+    /// the saturation point)
+    pub gas_compressibility_factor: Array1<f64>,
+    /// Two-phase compressibility factor $Z=\frac{pV_\mathrm{cell}}{nRT}$ of the
+    /// fluid that remains in the cell
+    pub two_phase_compressibility_factor: Array1<f64>,
+    /// Mole fractions of the produced gas (one row per step, the incipient
+    /// vapor at the saturation point)
+    pub gas_molefracs: Array2<f64>,
+}
+
+impl ConstantVolumeDepletion {
+    /// Simulate a constant volume depletion of a fluid with the composition
+    /// `moles` at the given temperature. The pressures have to decrease
+    /// from the saturation pressure.
+    pub fn new<E: Residual>(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        moles: &Moles<Array1<f64>>,
+        pressures: &Pressure<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let moles = eos.validate_moles(Some(moles))?;
+        let molefracs = (&moles / moles.sum()).into_value();
+        let (vle, bubble_point) = saturation_point(eos, temperature, &molefracs, options)?;
+        let saturated = saturated_phase(&vle, bubble_point);
+        let p_sat = saturated.pressure(Contributions::Total);
+        check_depletion(pressures, p_sat)?;
+        let v_cell = saturated.molar_volume().to_reduced();
+        let rt = (RGAS * temperature).to_reduced();
+
+        let n = pressures.len() + 1;
+        let mut pressure = Array1::zeros(n);
+        let mut liquid_volume = Array1::zeros(n);
+        let mut cumulative_production = Array1::zeros(n);
+        let mut gas_compressibility_factor = Array1::zeros(n);
+        let mut two_phase_compressibility_factor = Array1::zeros(n);
+        let mut gas_molefracs = Array2::zeros((n, molefracs.len()));
+        pressure[0] = p_sat.to_reduced();
+        liquid_volume[0] = if bubble_point { 1.0 } else { 0.0 };
+        gas_compressibility_factor[0] = vle.vapor().compressibility(Contributions::Total);
+        two_phase_compressibility_factor[0] = pressure[0] * v_cell / rt;
+        gas_molefracs.row_mut(0).assign(&vle.vapor().molefracs);
+
+        let mut cell = molefracs.clone();
+        let mut produced = 0.0;
+        let mut initial_state = Some(vle.clone());
+        let mut workspace = FlashWorkspace::new(eos.components());
+        for (i, p) in pressures.into_iter().enumerate().map(|(i, p)| (i + 1, p)) {
+            let cell_moles = Moles::from_reduced(cell.clone());
+            let (liquid, vapor) = flash(
+                eos,
+                temperature,
+                p,
+                &cell_moles,
+                &mut initial_state,
+                options,
+                &mut workspace,
+            )?;
+            let Some(vapor) = vapor else {
+                return Err(FeosError::InvalidState(
+                    "constant volume depletion".into(),
+                    "pressure (no vapor phase)".into(),
+                    p.to_reduced(),
+                ));
+            };
+            let v_liquid = liquid.as_ref().map_or(0.0, |s| s.volume.to_reduced());
+            let v_vapor = vapor.volume.to_reduced();
+
+            if v_liquid > v_cell {
+                return Err(FeosError::InvalidState(
+                    "constant volume depletion".into(),
+                    "pressure (liquid volume exceeds the cell volume)".into(),
+                    p.to_reduced(),
+                ));
+            }
+
+            // remove the excess vapor
+            let v_removed = (v_liquid + v_vapor - v_cell).max(0.0);
+            let n_removed = v_removed / vapor.molar_volume().to_reduced();
+            let y = &vapor.molefracs;
+            cell = &cell - &(y * n_removed);
+            produced += n_removed;
+
+            pressure[i] = p.to_reduced();
+            liquid_volume[i] = v_liquid / v_cell;
+            cumulative_production[i] = produced;
+            gas_compressibility_factor[i] = vapor.compressibility(Contributions::Total);
+            two_phase_compressibility_factor[i] = pressure[i] * v_cell / (cell.sum() * rt);
+            gas_molefracs.row_mut(i).assign(y);
+        }
+        Ok(Self {
+            saturation_pressure: p_sat,
+            pressure: Pressure::from_reduced(pressure),
+            liquid_volume,
+            cumulative_production,
+            gas_compressibility_factor,
+            two_phase_compressibility_factor,
+            gas_molefracs,
+        })
+    }
+}
+
+/// Differential liberation (DL) of an oil at reservoir temperature.
+///
+/// Starting at the bubble point, the pressure is reduced in steps. After
+/// each step, all liberated gas is removed from the cell. The oil that
+/// remains after the last step is cooled down to standard conditions
+/// (15 °C and 1 atm) to obtain the residual oil. Volumes of gas at
+/// standard conditions are calculated with the ideal gas law. The first
+/// entry of all results corresponds to the bubble point.
+pub struct DifferentialLiberation {
+    /// Bubble point pressure
+    pub bubble_point_pressure: Pressure,
+    /// Pressure of the steps
+    pub pressure: Pressure<Array1<f64>>,
+    /// Oil formation volume factor $B_\mathrm{o}$, i.e., the oil volume
+    /// relative to the volume of the residual oil
+    pub oil_formation_volume_factor: Array1<f64>,
+    /// Solution gas-oil ratio $R_\mathrm{s}$, i.e., the standard volume of the
+    /// gas that is still dissolved in the oil relative to the volume of
+    /// the residual oil
+    pub solution_gas_oil_ratio: Array1<f64>,
+    /// Gas formation volume factor $B_\mathrm{g}$, i.e., the gas volume relative
+    /// to its volume at standard conditions (`NaN` at the bubble point)
+    pub gas_formation_volume_factor: Array1<f64>,
+    /// Compressibility factor of the liberated gas (`NaN` at the bubble point)
+    pub gas_compressibility_factor: Array1<f64>,
+    /// Density of the oil
+    pub oil_density: Density<Array1<f64>>,
+    /// Mole fractions of the liberated gas (one row per step, `NaN` at the bubble point)
+    pub gas_molefracs: Array2<f64>,
+}
+
+impl DifferentialLiberation {
+    /// Simulate a differential liberation of an oil with the composition
+    /// `moles` at the given temperature. The pressures have to decrease
+    /// from the bubble point pressure.
+    pub fn new<E: Residual>(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        moles: &Moles<Array1<f64>>,
+        pressures: &Pressure<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let moles = eos.validate_moles(Some(moles))?;
+        let molefracs = (&moles / moles.sum()).into_value();
+        let (bubble, bubble_point) = saturation_point(eos, temperature, &molefracs, options)?;
+        if !bubble_point {
+            return Err(FeosError::UndeterminedState(
+                "differential liberation requires a fluid with a bubble point".into(),
+            ));
+        }
+        let p_bubble = bubble.liquid().pressure(Contributions::Total);
+        check_depletion(pressures, p_bubble)?;
+
+        let n = pressures.len() + 1;
+        let mut pressure = Array1::zeros(n);
+        let mut oil_volume = Array1::zeros(n);
+        let mut oil_density = Array1::zeros(n);
+        let mut gas_moles = Array1::zeros(n);
+        let mut gas_formation_volume_factor = Array1::from_elem(n, f64::NAN);
+        let mut gas_compressibility_factor = Array1::from_elem(n, f64::NAN);
+        let mut gas_molefracs = Array2::from_elem((n, molefracs.len()), f64::NAN);
+        pressure[0] = p_bubble.to_reduced();
+        oil_volume[0] = bubble.liquid().molar_volume().to_reduced();
+        oil_density[0] = bubble.liquid().density.to_reduced();
+
+        let (t, t_sc, p_sc) = (
+            temperature.to_reduced(),
+            STANDARD_TEMPERATURE,
+            STANDARD_PRESSURE,
+        );
+        let mut oil = molefracs.clone();
+        let mut initial_state = Some(bubble.clone());
+        let mut workspace = FlashWorkspace::new(eos.components());
+        for (i, p) in pressures.into_iter().enumerate().map(|(i, p)| (i + 1, p)) {
+            let oil_moles = Moles::from_reduced(oil.clone());
+            let (liquid, vapor) = flash(
+                eos,
+                temperature,
+                p,
+                &oil_moles,
+                &mut initial_state,
+                options,
+                &mut workspace,
+            )?;
+            let Some(liquid) = liquid else {
+                return Err(FeosError::InvalidState(
+                    "differential liberation".into(),
+                    "pressure (no liquid phase)".into(),
+                    p.to_reduced(),
+                ));
+            };
+            pressure[i] = p.to_reduced();
+            oil_volume[i] = liquid.volume.to_reduced();
+            oil_density[i] = liquid.density.to_reduced();
+            if let Some(vapor) = vapor {
+                let z = vapor.compressibility(Contributions::Total);
+                gas_moles[i] = vapor.total_moles.to_reduced();
+                gas_compressibility_factor[i] = z;
+                gas_formation_volume_factor[i] =
+                    z * t * (p_sc * PASCAL).to_reduced() / (pressure[i] * t_sc);
+                gas_molefracs.row_mut(i).assign(&vapor.molefracs);
+            }
+            oil = liquid.moles.to_reduced();
+        }
+
+        // residual oil at standard conditions
+        let oil_moles = Moles::from_reduced(oil);
+        let mut initial_state = None;
+        let mut workspace = FlashWorkspace::new(eos.components());
+        let (liquid, vapor) = flash(
+            eos,
+            t_sc * KELVIN,
+            p_sc * PASCAL,
+            &oil_moles,
+            &mut initial_state,
+            options,
+            &mut workspace,
+        )?;
+        let Some(residual_oil) = liquid else {
+            return Err(FeosError::InvalidState(
+                "differential liberation".into(),
+                "pressure (no residual oil)".into(),
+                p_sc,
+            ));
+        };
+        let v_residual = residual_oil.volume.to_reduced();
+        let gas_sc = vapor.map_or(0.0, |s| s.total_moles.to_reduced());
+
+        // gas that is liberated at lower pressures is still dissolved
+        let standard_volume = (RGAS * t_sc * KELVIN / (p_sc * PASCAL)).to_reduced();
+        let mut solution_gas_oil_ratio = Array1::zeros(n);
+        let mut dissolved = gas_sc;
+        for i in (0..n).rev() {
+            solution_gas_oil_ratio[i] = dissolved * standard_volume / v_residual;
+            dissolved += gas_moles[i];
+        }
+        Ok(Self {
+            bubble_point_pressure: p_bubble,
+            pressure: Pressure::from_reduced(pressure),
+            oil_formation_volume_factor: oil_volume / v_residual,
+            solution_gas_oil_ratio,
+            gas_formation_volume_factor,
+            gas_compressibility_factor,
+            oil_density: Density::from_reduced(oil_density),
+            gas_molefracs,
+        })
+    }
+}
+
+/// Swelling test of an oil with an injection gas at reservoir temperature.
+///
+/// Increasing amounts of the gas are added to the oil and the saturation
+/// pressure and the swelling factor of each mixture are determined. The
+/// swelling factor is the volume of the saturated mixture relative to the
+/// volume of the original oil at its saturation point.
+pub struct SwellingTest {
+    /// Mole fraction of the injection gas in the mixture
+    pub gas_fraction: Array1<f64>,
+    /// Saturation pressure of the mixture
+    pub saturation_pressure: Pressure<Array1<f64>>,
+    /// `true` if the saturation point is a bubble point, `false` for a dew point
+    pub bubble_point: Vec<bool>,
+    /// Swelling factor $\frac{V_\mathrm{sat}}{V_\mathrm{b,oil}}$
+    pub swelling_factor: Array1<f64>,
+}
+
+impl SwellingTest {
+    /// Simulate a swelling test for an oil and an injection gas with given
+    /// compositions for the mole fractions `gas_fractions` of the gas in the
+    /// mixture.
+    pub fn new<E: Residual>(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        oil: &Moles<Array1<f64>>,
+        gas: &Moles<Array1<f64>>,
+        gas_fractions: &Array1<f64>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let oil = eos.validate_moles(Some(oil))?;
+        let gas = eos.validate_moles(Some(gas))?;
+        let oil = (&oil / oil.sum()).into_value();
+        let gas = (&gas / gas.sum()).into_value();
+        if let Some(&f) = gas_fractions.iter().find(|f| !(0.0..1.0).contains(*f)) {
+            return Err(FeosError::InvalidState(
+                "swelling test".into(),
+                "gas fraction".into(),
+                f,
+            ));
+        }
+        let (vle, bubble_point) = saturation_point(eos, temperature, &oil, options)?;
+        let v_oil = saturated_phase(&vle, bubble_point)
+            .molar_volume()
+            .to_reduced();
+
+        let n = gas_fractions.len();
+        let mut saturation_pressure = Array1::zeros(n);
+        let mut bubble_point = Vec::with_capacity(n);
+        let mut swelling_factor = Array1::zeros(n);
+        for (i, &f) in gas_fractions.iter().enumerate() {
+            let z = (1.0 - f) * &oil + f * &gas;
+            let (vle, is_bubble_point) = saturation_point(eos, temperature, &z, options)?;
+            let saturated = saturated_phase(&vle, is_bubble_point);
+            saturation_pressure[i] = saturated.pressure(Contributions::Total).to_reduced();
+            bubble_point.push(is_bubble_point);
+            // volume of the mixture per amount of substance of the original oil
+            swelling_factor[i] = saturated.molar_volume().to_reduced() / (1.0 - f) / v_oil;
+        }
+        Ok(Self {
+            gas_fraction: gas_fractions.clone(),
+            saturation_pressure: Pressure::from_reduced(saturation_pressure),
+            bubble_point,
+            swelling_factor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::BAR;
+
+    fn eos() -> FeosResult<Arc<PengRobinson>> {
+        // methane, propane, n-decane
+        let parameters = PengRobinsonParameters::new_simple(
+            &[190.56, 369.96, 617.7],
+            &[4599000.0, 4250000.0, 2110000.0],
+            &[0.011, 0.153, 0.49],
+            &[16.043, 44.0962, 142.285],
+        )?;
+        Ok(Arc::new(PengRobinson::new(Arc::new(parameters))))
+    }
+
+    #[test]
+    fn constant_composition_expansion() -> FeosResult<()> {
+        let eos = eos()?;
+        let t = 350.0 * KELVIN;
+        let oil = Moles::from_reduced(arr1(&[0.3, 0.2, 0.5]));
+        let pressures = arr1(&[300.0, 200.0, 100.0, 50.0, 20.0]) * BAR;
+        let cce =
+            ConstantCompositionExpansion::new(&eos, t, &oil, &pressures, SolverOptions::default())?;
+        assert!(cce.bubble_point);
+        let p_sat = cce.saturation_pressure.convert_to(BAR);
+        assert!(p_sat > 50.0 && p_sat < 200.0);
+
+        // the oil is compressed above and expands strongly below the bubble point
+        let v = &cce.relative_volume;
+        assert!(v.windows(2).into_iter().all(|w| w[1] > w[0]));
+        assert!(v[0] < 1.0 && v[1] < 1.0 && v[3] > 1.0);
+        assert_relative_eq!(cce.liquid_volume[0], v[0]);
+        assert!(cce.liquid_volume[4] < 1.0);
+        assert!(cce.y_function[0].is_nan());
+        assert!(cce.y_function[3] > 0.0);
+
+        // a gas condensate has a dew point
+        let gas = Moles::from_reduced(arr1(&[0.9, 0.07, 0.03]));
+        let pressures = arr1(&[400.0, 150.0, 100.0]) * BAR;
+        let cce =
+            ConstantCompositionExpansion::new(&eos, t, &gas, &pressures, SolverOptions::default())?;
+        assert!(!cce.bubble_point);
+        assert_eq!(cce.liquid_volume[0], 0.0);
+        assert!(cce.liquid_volume[2] > 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn constant_volume_depletion() -> FeosResult<()> {
+        let eos = eos()?;
+        let gas = Moles::from_reduced(arr1(&[0.9, 0.07, 0.03]));
+        let t = 350.0 * KELVIN;
+        let cce = ConstantCompositionExpansion::new(
+            &eos,
+            t,
+            &gas,
+            &(arr1(&[1.0]) * BAR),
+            SolverOptions::default(),
+        )?;
+        assert!(!cce.bubble_point);
+        let p_dew = cce.saturation_pressure;
+        let pressures = Pressure::linspace(0.9 * p_dew, 0.3 * p_dew, 5);
+        let cvd =
+            ConstantVolumeDepletion::new(&eos, t, &gas, &pressures, SolverOptions::default())?;
+        assert_relative_eq!(cvd.saturation_pressure, p_dew, max_relative = 1e-8);
+        assert_eq!(cvd.pressure.len(), 6);
+        assert_eq!(cvd.liquid_volume[0], 0.0);
+        assert!(
+            cvd.liquid_volume
+                .iter()
+                .skip(1)
+                .all(|&v| v > 0.0 && v < 1.0)
+        );
+        let np = &cvd.cumulative_production;
+        assert!(np.windows(2).into_iter().all(|w| w[1] > w[0]));
+        assert!(np[5] < 1.0);
+        // the produced gas is leaner than the original gas
+        assert!(cvd.gas_molefracs[[5, 2]] < 0.03);
+        assert!(cvd.liquid_volume[3] > cvd.liquid_volume[1]);
+
+        // pressures have to decrease from the dew point
+        let pressures = Pressure::from_reduced(arr1(&[1.1 * p_dew.to_reduced()]));
+        assert!(
+            ConstantVolumeDepletion::new(&eos, t, &gas, &pressures, Default::default()).is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn differential_liberation() -> FeosResult<()> {
+        let eos = eos()?;
+        let oil = Moles::from_reduced(arr1(&[0.3, 0.2, 0.5]));
+        let t = 350.0 * KELVIN;
+        let pressures = arr1(&[80.0, 50.0, 20.0, 1.01325]) * BAR;
+        let dl = DifferentialLiberation::new(&eos, t, &oil, &pressures, SolverOptions::default())?;
+        assert_eq!(dl.pressure.len(), 5);
+        let (bo, rs) = (&dl.oil_formation_volume_factor, &dl.solution_gas_oil_ratio);
+        assert!(bo.windows(2).into_iter().all(|w| w[1] < w[0]));
+        assert!(rs.windows(2).into_iter().all(|w| w[1] < w[0]));
+        assert!(bo[4] > 1.0 && rs[4] >= 0.0);
+        assert!(dl.gas_formation_volume_factor[0].is_nan());
+        let bg = &dl.gas_formation_volume_factor;
+        assert!(bg[1] < bg[2] && bg[2] < bg[3]);
+        // the first gas is rich in methane
+        assert!(dl.gas_molefracs[[1, 0]] > 0.8);
+        Ok(())
+    }
+
+    #[test]
+    fn swelling_test() -> FeosResult<()> {
+        let eos = eos()?;
+        let oil = Moles::from_reduced(arr1(&[0.2, 0.2, 0.6]));
+        let gas = Moles::from_reduced(arr1(&[1.0, 0.0, 0.0]));
+        let t = 350.0 * KELVIN;
+        let fractions = arr1(&[0.0, 0.1, 0.2, 0.3]);
+        let swelling =
+            SwellingTest::new(&eos, t, &oil, &gas, &fractions, SolverOptions::default())?;
+        assert_relative_eq!(swelling.swelling_factor[0], 1.0, max_relative = 1e-10);
+        let sf = &swelling.swelling_factor;
+        let p_sat = swelling.saturation_pressure.to_reduced();
+        assert!(sf.windows(2).into_iter().all(|w| w[1] > w[0]));
+        assert!(p_sat.windows(2).into_iter().all(|w| w[1] > w[0]));
+        assert!(swelling.bubble_point.iter().all(|&b| b));
+        assert!(SwellingTest::new(&eos, t, &oil, &gas, &arr1(&[1.0]), Default::default()).is_err());
+        Ok(())
+    }
+}
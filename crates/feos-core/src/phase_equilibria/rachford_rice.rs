@@ -0,0 +1,351 @@
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use ndarray::{Array1, Array2, Zip};
+use num_dual::linalg::LU;
+
+const MAX_ITER_RR: usize = 50;
+const TOL_RR: f64 = 1e-12;
+
+/// Vapor phase fraction $\beta$ from the Rachford-Rice equation
+/// $\sum_i\frac{z_i(K_i-1)}{1-\beta+\beta K_i}=0$ for a two-phase solution with $0\leq\beta\leq 1$.
+///
+/// `beta_in` is used as initial value if it lies within the bounds of the solution.
+/// An error is returned if there is no two-phase solution for the given feed and K-values.
+pub fn rachford_rice(feed: &Array1<f64>, k: &Array1<f64>, beta_in: Option<f64>) -> FeosResult<f64> {
+    // check if solution exists
+    let feed_k: f64 = feed.iter().zip(k).map(|(f, k)| f * k).sum();
+    let feed_over_k: f64 = feed
+        .iter()
+        .zip(k)
+        .map(|(f, k)| f / k)
+        .filter(|x| !x.is_nan())
+        .sum();
+    if !(feed_k > 1.0 && feed_over_k > 1.0) {
+        return Err(FeosError::IterationFailed(
+            SolverFailure::new("rachford_rice").molefracs(feed),
+        ));
+    }
+    solve(feed, k, beta_in, (0.0, 1.0))
+}
+
+/// Vapor phase fraction $\beta$ from the Rachford-Rice equation including the negative flash.
+///
+/// The vapor phase fraction is not restricted to $0\leq\beta\leq 1$ but to the window
+/// $\frac{1}{1-K_\mathrm{max}}<\beta<\frac{1}{1-K_\mathrm{min}}$ in which all phase
+/// compositions are positive. A solution with $\beta<0$ or $\beta>1$ indicates
+/// that the feed is a single phase liquid or vapor, respectively.
+pub fn rachford_rice_negative_flash(
+    feed: &Array1<f64>,
+    k: &Array1<f64>,
+    beta_in: Option<f64>,
+) -> FeosResult<f64> {
+    let (k_min, k_max) = feed
+        .iter()
+        .zip(k)
+        .filter(|&(&f, _)| f > 0.0)
+        .fold((f64::INFINITY, 0.0f64), |(k_min, k_max), (_, &k)| {
+            (k_min.min(k), k_max.max(k))
+        });
+    if !(k_min < 1.0 && k_max > 1.0) {
+        return Err(FeosError::IterationFailed(
+            SolverFailure::new("rachford_rice_negative_flash").molefracs(feed),
+        ));
+    }
+    solve(feed, k, beta_in, (1.0 / (1.0 - k_max), 1.0 / (1.0 - k_min)))
+}
+
+/// Compositions of the vapor and the liquid phase $y_i=\frac{K_iz_i}{1-\beta+\beta K_i}$
+/// and $x_i=\frac{z_i}{1-\beta+\beta K_i}$ for a given vapor phase fraction.
+pub fn rachford_rice_compositions(
+    feed: &Array1<f64>,
+    k: &Array1<f64>,
+    beta: f64,
+) -> (Array1<f64>, Array1<f64>) {
+    let liquid = Zip::from(feed)
+        .and(k)
+        .map_collect(|&f, &k| f / (1.0 + beta * (k - 1.0)));
+    let vapor = &liquid * k;
+    (vapor, liquid)
+}
+
+/// Phase fractions $\beta_j$ of a multiphase split from the generalized Rachford-Rice equations
+/// $\sum_i\frac{z_iK_{ij}}{\sum_k\beta_kK_{ik}}=1$ for all phases with $\beta_j>0$.
+///
+/// The K-values of every phase (rows) and component (columns) are defined relative to an
+/// arbitrary reference phase $K_{ij}=\frac{x_{ij}}{x_{i,\mathrm{ref}}}$, e.g., as ratio of the
+/// fugacity coefficients $\frac{\varphi_{i,\mathrm{ref}}}{\varphi_{ij}}$. The phase fractions
+/// minimize the convex function $Q(\beta)=\sum_j\beta_j-\sum_iz_i\ln\sum_j\beta_jK_{ij}$ subject
+/// to $\beta_j\geq 0$ (Michelsen, 1994). Phases that are not present in the solution have a
+/// phase fraction of 0.
+///
+/// `beta_in` is used as initial value, otherwise all phases are initialized with equal fractions.
+pub fn rachford_rice_multiphase(
+    feed: &Array1<f64>,
+    k: &Array2<f64>,
+    beta_in: Option<&Array1<f64>>,
+) -> FeosResult<Array1<f64>> {
+    let (phases, components) = k.dim();
+    if feed.len() != components {
+        return Err(FeosError::IncompatibleComponents(components, feed.len()));
+    }
+    let mut beta = match beta_in {
+        Some(beta) if beta.len() == phases => beta.mapv(|b| b.max(0.0)),
+        Some(beta) => return Err(FeosError::IncompatibleComponents(phases, beta.len())),
+        None => Array1::from_elem(phases, 1.0 / phases as f64),
+    };
+    let objective = |beta: &Array1<f64>| {
+        let e = beta.dot(k);
+        let ln_e: f64 =
+            Zip::from(feed).and(&e).fold(
+                0.0,
+                |acc, &f, &e| if f > 0.0 { acc + f * e.ln() } else { acc },
+            );
+        beta.sum() - ln_e
+    };
+
+    let mut res = f64::INFINITY;
+    for _ in 0..MAX_ITER_RR {
+        // gradient and Hessian of the objective function
+        let e = beta.dot(k);
+        let w = Zip::from(feed)
+            .and(&e)
+            .map_collect(|&f, &e| if f > 0.0 { f / e } else { 0.0 });
+        let gradient = 1.0 - k.dot(&w);
+        let kw = k * &(&w / &e);
+        let hessian = kw.dot(&k.t());
+
+        // phases at the bound with positive gradient stay absent
+        let free: Vec<_> = (0..phases)
+            .filter(|&j| beta[j] > 0.0 || gradient[j] < 0.0)
+            .collect();
+        res = free.iter().map(|&j| gradient[j].abs()).fold(0.0, f64::max);
+        if res < TOL_RR {
+            return Ok(beta);
+        }
+
+        // Newton step for the free phases
+        let hessian_free = Array2::from_shape_fn((free.len(), free.len()), |(a, b)| {
+            hessian[[free[a], free[b]]]
+        });
+        let gradient_free = Array1::from_shape_fn(free.len(), |a| -gradient[free[a]]);
+        let delta = LU::new(hessian_free)?.solve(&gradient_free);
+
+        // limit the step to non-negative phase fractions; phases at the bound
+        // are clipped in the line search and must not block the step
+        let mut alpha: f64 = 1.0;
+        for (&j, &d) in free.iter().zip(&delta) {
+            if d < 0.0 && beta[j] > 0.0 {
+                alpha = alpha.min(-beta[j] / d);
+            }
+        }
+
+        // line search, close to the solution changes of the objective
+        // function within round-off are accepted
+        let q = objective(&beta);
+        loop {
+            let mut beta_new = beta.clone();
+            for (&j, &d) in free.iter().zip(&delta) {
+                beta_new[j] = (beta[j] + alpha * d).max(0.0);
+            }
+            if beta_new.dot(k).iter().all(|&e| e > 0.0)
+                && objective(&beta_new) <= q + 4.0 * f64::EPSILON * q.abs()
+            {
+                beta = beta_new;
+                break;
+            }
+            alpha *= 0.5;
+            if alpha < TOL_RR {
+                return Err(FeosError::IterationFailed(
+                    SolverFailure::new("rachford_rice_multiphase")
+                        .residual(res)
+                        .molefracs(feed),
+                ));
+            }
+        }
+    }
+    Err(FeosError::NotConverged(
+        SolverFailure::new("rachford_rice_multiphase")
+            .iterations(MAX_ITER_RR)
+            .residual(res)
+            .molefracs(feed),
+    ))
+}
+
+/// Compositions $x_{ij}=\frac{z_iK_{ij}}{\sum_k\beta_kK_{ik}}$ of all phases (rows) for given
+/// phase fractions of a multiphase split.
+pub fn rachford_rice_multiphase_compositions(
+    feed: &Array1<f64>,
+    k: &Array2<f64>,
+    beta: &Array1<f64>,
+) -> Array2<f64> {
+    let e = beta.dot(k);
+    k * &(feed / &e)
+}
+
+/// Safeguarded Newton iteration of the Rachford-Rice equation within the given bounds.
+fn solve(
+    feed: &Array1<f64>,
+    k: &Array1<f64>,
+    beta_in: Option<f64>,
+    (mut beta_min, mut beta_max): (f64, f64),
+) -> FeosResult<f64> {
+    // look for tighter bounds (all mole fractions are at most 1)
+    for (&k, &f) in k.iter().zip(feed.iter()) {
+        if k > 1.0 {
+            let b = (k * f - 1.0) / (k - 1.0);
+            if b > beta_min {
+                beta_min = b;
+            }
+        }
+        if k < 1.0 {
+            let b = (1.0 - f) / (1.0 - k);
+            if b < beta_max {
+                beta_max = b;
+            }
+        }
+    }
+
+    // initialize
+    let mut beta = 0.5 * (beta_min + beta_max);
+    if let Some(b) = beta_in
+        && b > beta_min
+        && b < beta_max
+    {
+        beta = b;
+    }
+
+    // iterate
+    let mut g = f64::INFINITY;
+    for _ in 0..MAX_ITER_RR {
+        let mut dg = 0.0;
+        g = 0.0;
+        for (&f, &k) in feed.iter().zip(k) {
+            if f > 0.0 {
+                let frac = (k - 1.0) / (1.0 + beta * (k - 1.0));
+                g += f * frac;
+                dg -= f * frac * frac;
+            }
+        }
+        if g > 0.0 {
+            beta_min = beta;
+        } else {
+            beta_max = beta;
+        }
+
+        // Newton step, bisection if the step leaves the bracket
+        let mut beta_new = beta - g / dg;
+        if !(beta_new > beta_min && beta_new < beta_max) {
+            beta_new = 0.5 * (beta_min + beta_max);
+        }
+        let dbeta = beta_new - beta;
+        beta = beta_new;
+        let tol = TOL_RR * beta.abs().max(1.0);
+        if dbeta.abs() < tol || beta_max - beta_min < tol {
+            return Ok(beta);
+        }
+    }
+    Err(FeosError::NotConverged(
+        SolverFailure::new("rachford_rice")
+            .iterations(MAX_ITER_RR)
+            .residual(g)
+            .molefracs(feed),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use ndarray::{Axis, arr1, arr2, stack};
+
+    fn residual(feed: &Array1<f64>, k: &Array1<f64>, beta: f64) -> f64 {
+        let (vapor, liquid) = rachford_rice_compositions(feed, k, beta);
+        (vapor - liquid).sum()
+    }
+
+    #[test]
+    fn two_phase() -> FeosResult<()> {
+        let feed = arr1(&[0.3, 0.3, 0.4]);
+        let k = arr1(&[3.0, 1.2, 0.1]);
+        let beta = rachford_rice(&feed, &k, None)?;
+        assert!(beta > 0.0 && beta < 1.0);
+        assert_relative_eq!(residual(&feed, &k, beta), 0.0, epsilon = 1e-12);
+        let (vapor, liquid) = rachford_rice_compositions(&feed, &k, beta);
+        assert_relative_eq!(vapor.sum(), 1.0, epsilon = 1e-12);
+        assert_relative_eq!(liquid.sum(), 1.0, epsilon = 1e-12);
+        assert_relative_eq!(
+            rachford_rice_negative_flash(&feed, &k, Some(0.9))?,
+            beta,
+            epsilon = 1e-12
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn negative_flash() -> FeosResult<()> {
+        // subcooled liquid
+        let feed = arr1(&[0.1, 0.9]);
+        let k = arr1(&[2.0, 0.5]);
+        assert!(rachford_rice(&feed, &k, None).is_err());
+        let beta = rachford_rice_negative_flash(&feed, &k, None)?;
+        assert!(beta < 0.0 && beta > -1.0);
+        assert_relative_eq!(residual(&feed, &k, beta), 0.0, epsilon = 1e-12);
+
+        // no phase split for K-values on one side of 1
+        assert!(rachford_rice_negative_flash(&feed, &arr1(&[2.0, 1.5]), None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn multiphase() -> FeosResult<()> {
+        // two phases are equivalent to the Rachford-Rice equation
+        let feed = arr1(&[0.3, 0.3, 0.4]);
+        let k = arr1(&[3.0, 1.2, 0.1]);
+        let beta = rachford_rice(&feed, &k, None)?;
+        let k2 = stack![Axis(0), Array1::ones(3), k];
+        let beta2 = rachford_rice_multiphase(&feed, &k2, None)?;
+        assert_relative_eq!(beta2[1], beta, epsilon = 1e-10);
+        assert_relative_eq!(beta2.sum(), 1.0, epsilon = 1e-10);
+
+        // an unstable phase is not present in the solution
+        let k3 = stack![Axis(0), k2.row(0), k2.row(1), 0.1 * &k2.row(0)];
+        let beta3 = rachford_rice_multiphase(&feed, &k3, None)?;
+        assert_eq!(beta3[2], 0.0);
+        assert_relative_eq!(beta3[1], beta, epsilon = 1e-10);
+
+        // three phases with known compositions
+        let x = arr2(&[[0.1, 0.3, 0.6], [0.7, 0.2, 0.1], [0.05, 0.9, 0.05]]);
+        let beta_ref = arr1(&[0.2, 0.5, 0.3]);
+        let feed = beta_ref.dot(&x);
+        let k = &x / &x.row(0);
+        let beta = rachford_rice_multiphase(&feed, &k, None)?;
+        assert_relative_eq!(beta, beta_ref, epsilon = 1e-10);
+        let compositions = rachford_rice_multiphase_compositions(&feed, &k, &beta);
+        assert_relative_eq!(compositions, x, epsilon = 1e-10);
+        Ok(())
+    }
+
+    #[test]
+    fn multiphase_absent_initial_phase() -> FeosResult<()> {
+        // the first phase is absent in the initial guess, and its Newton step
+        // is negative in the first iteration
+        let x = arr2(&[[0.66, 0.19, 0.15], [0.07, 0.19, 0.74], [0.34, 0.51, 0.15]]);
+        let beta_ref = arr1(&[0.54, 0.09, 0.37]);
+        let feed = beta_ref.dot(&x);
+        let k = &x / &x.row(0);
+        let beta_in = arr1(&[0.0, 1.0, 0.2]);
+        let beta = rachford_rice_multiphase(&feed, &k, Some(&beta_in))?;
+        assert_relative_eq!(beta, beta_ref, epsilon = 1e-10);
+        Ok(())
+    }
+
+    #[test]
+    fn near_degenerate() -> FeosResult<()> {
+        let eps = 2f64.powi(-30);
+        let feed = arr1(&[0.3, 0.7]);
+        let k = arr1(&[1.0 + eps, 1.0 - eps]);
+        let beta = rachford_rice_negative_flash(&feed, &k, None)?;
+        assert_relative_eq!(beta, -0.4 / eps, max_relative = 1e-8);
+        assert!(rachford_rice(&feed, &k, None).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,320 @@
+use super::PhaseEquilibrium;
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult};
+use crate::state::Contributions;
+use crate::{ReferenceSystem, SolverOptions};
+use ndarray::Array1;
+use quantity::{Density, Pressure, Temperature};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Memoization of pure component phase equilibria of the components
+/// of a mixture.
+///
+/// Temperatures are quantized to multiples of `resolution` and the phase
+/// equilibria are calculated at the quantized temperatures, so that
+/// repeated evaluations (e.g., during the tracing of phase envelopes) are
+/// answered from the cache. New entries are initialized with the closest
+/// cached phase equilibrium of the same component. If the number of entries
+/// exceeds the capacity, the least recently used entry is removed.
+///
+/// The cache can be shared between threads. It is used for the K-factor
+/// initialization of [PhaseDiagram::bubble_point_line_with_cache] and
+/// [PhaseDiagram::dew_point_line_with_cache] and for additional trial
+/// phases in [State::stability_analysis_with_cache]. Sharing one cache
+/// between these calculations, e.g., when tracing the phase envelopes of
+/// many compositions, avoids recalculating the same pure component phase
+/// equilibria.
+///
+/// [PhaseDiagram::bubble_point_line_with_cache]: super::PhaseDiagram::bubble_point_line_with_cache
+/// [PhaseDiagram::dew_point_line_with_cache]: super::PhaseDiagram::dew_point_line_with_cache
+/// [State::stability_analysis_with_cache]: crate::State::stability_analysis_with_cache
+pub struct SaturationCache<E> {
+    pure_eos: Vec<Arc<E>>,
+    resolution: f64,
+    capacity: usize,
+    options: SolverOptions,
+    entries: Mutex<Entries<E>>,
+}
+
+struct Entries<E> {
+    map: HashMap<(usize, i64), (u64, PhaseEquilibrium<E, 2>)>,
+    tick: u64,
+    hit: u64,
+    miss: u64,
+}
+
+impl<E: Residual> SaturationCache<E> {
+    /// Create an empty cache for all components of `eos`.
+    pub fn new(eos: &Arc<E>, resolution: Temperature, capacity: usize) -> FeosResult<Self> {
+        let resolution = resolution.to_reduced();
+        if !(resolution > 0.0 && capacity > 0) {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "the resolution and capacity of the cache have to be positive.",
+            )));
+        }
+        Ok(Self {
+            pure_eos: (0..eos.components())
+                .map(|i| Arc::new(eos.subset(&[i])))
+                .collect(),
+            resolution,
+            capacity,
+            options: SolverOptions::default(),
+            entries: Mutex::new(Entries {
+                map: HashMap::with_capacity(capacity),
+                tick: 0,
+                hit: 0,
+                miss: 0,
+            }),
+        })
+    }
+
+    /// Use the given options for the pure component phase equilibria.
+    pub fn with_options(mut self, options: SolverOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Pure component phase equilibrium of `component` at the
+    /// quantized temperature closest to `temperature`.
+    pub fn pure(
+        &self,
+        component: usize,
+        temperature: Temperature,
+    ) -> FeosResult<PhaseEquilibrium<E, 2>> {
+        let Some(eos) = self.pure_eos.get(component) else {
+            return Err(FeosError::IncompatibleComponents(
+                self.pure_eos.len(),
+                component + 1,
+            ));
+        };
+        let key = (
+            component,
+            (temperature.to_reduced() / self.resolution).round() as i64,
+        );
+
+        let initial_state = {
+            let mut entries = self.entries.lock().unwrap();
+            entries.tick += 1;
+            let tick = entries.tick;
+            if let Some((last_used, vle)) = entries.map.get_mut(&key) {
+                *last_used = tick;
+                let vle = vle.clone();
+                entries.hit += 1;
+                return Ok(vle);
+            }
+            entries.miss += 1;
+            entries
+                .map
+                .iter()
+                .filter(|((i, _), _)| *i == component)
+                .min_by_key(|((_, k), _)| k.abs_diff(key.1))
+                .map(|(_, (_, vle))| vle.clone())
+        };
+
+        // the lock is released during the calculation
+        let vle = PhaseEquilibrium::pure(
+            eos,
+            Temperature::from_reduced(key.1 as f64 * self.resolution),
+            initial_state.as_ref(),
+            self.options,
+        )?;
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.map.len() >= self.capacity
+            && !entries.map.contains_key(&key)
+            && let Some(&oldest) = entries
+                .map
+                .iter()
+                .min_by_key(|(_, (last_used, _))| *last_used)
+                .map(|(k, _)| k)
+        {
+            entries.map.remove(&oldest);
+        }
+        let tick = entries.tick;
+        entries.map.insert(key, (tick, vle.clone()));
+        Ok(vle)
+    }
+
+    /// Vapor pressure of `component` at the quantized temperature
+    /// closest to `temperature`.
+    pub fn vapor_pressure(
+        &self,
+        component: usize,
+        temperature: Temperature,
+    ) -> FeosResult<Pressure> {
+        Ok(self
+            .pure(component, temperature)?
+            .vapor()
+            .pressure(Contributions::Total))
+    }
+
+    /// Saturated liquid and vapor densities of `component` at the quantized
+    /// temperature closest to `temperature`.
+    pub fn saturated_densities(
+        &self,
+        component: usize,
+        temperature: Temperature,
+    ) -> FeosResult<(Density, Density)> {
+        let vle = self.pure(component, temperature)?;
+        Ok((vle.liquid().density, vle.vapor().density))
+    }
+
+    /// Vapor pressures of all components, analogous to
+    /// [PhaseEquilibrium::vapor_pressure].
+    pub fn vapor_pressures(&self, temperature: Temperature) -> Vec<Option<Pressure>> {
+        (0..self.pure_eos.len())
+            .map(|i| self.vapor_pressure(i, temperature).ok())
+            .collect()
+    }
+
+    /// Raoult's law K-factors $K_i=p_i^\mathrm{sat}/p$ of all components.
+    ///
+    /// Returns `None` if the vapor pressure of any component cannot be
+    /// calculated, e.g., because the temperature exceeds its critical
+    /// temperature.
+    pub fn k_factors(&self, temperature: Temperature, pressure: Pressure) -> Option<Array1<f64>> {
+        (0..self.pure_eos.len())
+            .map(|i| {
+                let p_sat = self.vapor_pressure(i, temperature).ok()?;
+                Some((p_sat / pressure).into_value())
+            })
+            .collect()
+    }
+
+    /// Bubble point pressure and vapor composition from Raoult's law.
+    pub(super) fn raoult_bubble_point(
+        &self,
+        temperature: Temperature,
+        liquid_molefracs: &Array1<f64>,
+    ) -> Option<(Pressure, Array1<f64>)> {
+        let p_i = self.k_factors(temperature, Pressure::from_reduced(1.0))? * liquid_molefracs;
+        let p = p_i.sum();
+        Some((Pressure::from_reduced(p), p_i / p))
+    }
+
+    /// Dew point pressure and liquid composition from Raoult's law.
+    pub(super) fn raoult_dew_point(
+        &self,
+        temperature: Temperature,
+        vapor_molefracs: &Array1<f64>,
+    ) -> Option<(Pressure, Array1<f64>)> {
+        let x_i = vapor_molefracs / self.k_factors(temperature, Pressure::from_reduced(1.0))?;
+        let p = 1.0 / x_i.sum();
+        Some((Pressure::from_reduced(p), x_i * p))
+    }
+
+    /// Number of cached phase equilibria.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().map.len()
+    }
+
+    /// Returns `true` if the cache contains no phase equilibria.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of queries that were answered from the cache and number
+    /// of queries that required a new calculation.
+    pub fn statistics(&self) -> (u64, u64) {
+        let entries = self.entries.lock().unwrap();
+        (entries.hit, entries.miss)
+    }
+
+    /// Remove all entries from the cache.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().map.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{DensityInitialization, State};
+    use crate::test_utils::propane_butane;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{KELVIN, MOL, PASCAL};
+
+    #[test]
+    fn saturation_cache() -> FeosResult<()> {
+        let eos = Arc::new(propane_butane()?);
+        let cache = SaturationCache::new(&eos, 1e-3 * KELVIN, 3)?;
+
+        let reference = PhaseEquilibrium::vapor_pressure(&eos, 300.0 * KELVIN);
+        let cached = cache.vapor_pressures(300.0 * KELVIN);
+        for (r, c) in reference.iter().zip(&cached) {
+            assert_relative_eq!(r.unwrap(), c.unwrap(), max_relative = 1e-10);
+        }
+        assert_eq!(cache.statistics(), (0, 2));
+
+        // temperatures within the resolution are answered from the cache
+        let p = cache.vapor_pressure(0, 300.0001 * KELVIN)?;
+        assert_eq!(p, cached[0].unwrap());
+        assert_eq!(cache.statistics(), (1, 2));
+        let (rho_l, rho_v) = cache.saturated_densities(1, 300.0 * KELVIN)?;
+        assert!(rho_l > rho_v);
+        assert_eq!(cache.statistics(), (2, 2));
+
+        // the least recently used entry (propane at 300 K) is removed
+        cache.vapor_pressure(0, 310.0 * KELVIN)?;
+        cache.vapor_pressure(0, 320.0 * KELVIN)?;
+        assert_eq!(cache.len(), 3);
+        cache.vapor_pressure(1, 300.0 * KELVIN)?;
+        assert_eq!(cache.statistics(), (3, 4));
+        cache.vapor_pressure(0, 300.0 * KELVIN)?;
+        assert_eq!(cache.statistics(), (3, 5));
+
+        assert!(cache.vapor_pressure(2, 300.0 * KELVIN).is_err());
+
+        // K-factors are only available below the critical temperatures
+        let p = 1e6 * PASCAL;
+        let k = cache.k_factors(300.0 * KELVIN, p).unwrap();
+        assert_relative_eq!(
+            k[0],
+            (cached[0].unwrap() / p).into_value(),
+            max_relative = 1e-10
+        );
+        assert!(cache.k_factors(400.0 * KELVIN, p).is_none());
+        cache.clear();
+        assert!(cache.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn stability_analysis_with_cache() -> FeosResult<()> {
+        let eos = Arc::new(propane_butane()?);
+        let cache = SaturationCache::new(&eos, 1e-3 * KELVIN, 10)?;
+
+        // a feed between its bubble and dew point is unstable
+        let moles = arr1(&[0.5, 0.5]) * MOL;
+        let feed = State::new_npt(
+            &eos,
+            300.0 * KELVIN,
+            6e5 * PASCAL,
+            &moles,
+            DensityInitialization::Liquid,
+        )?;
+        let options = SolverOptions::default();
+        let reference = feed.stability_analysis(options)?;
+        let trial_phases = feed.stability_analysis_with_cache(&cache, options)?;
+        assert!(!reference.is_empty());
+        assert!(trial_phases.len() >= reference.len());
+        assert_eq!(cache.statistics(), (0, 2));
+
+        // the vapor pressures are reused for a second feed
+        let feed = State::new_npt(
+            &eos,
+            300.0 * KELVIN,
+            2e5 * PASCAL,
+            &moles,
+            DensityInitialization::Vapor,
+        )?;
+        assert!(
+            feed.stability_analysis_with_cache(&cache, options)?
+                .is_empty()
+        );
+        assert_eq!(cache.statistics(), (2, 2));
+        Ok(())
+    }
+}
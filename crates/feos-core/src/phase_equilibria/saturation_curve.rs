@@ -0,0 +1,145 @@
+use super::PhaseEquilibrium;
+use crate::SolverOptions;
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult};
+use crate::state::{Contributions, State};
+use quantity::{Density, Pressure, RGAS, Temperature};
+use std::sync::Arc;
+
+/// Solver for the saturation curve of a pure component.
+///
+/// The critical point of the equation of state is calculated once and used
+/// to define ancillary equations for the vapor pressure
+/// $\ln\frac{p^\mathrm{sat}}{p_c}=A\left(1-\frac{T_c}{T}\right)$, where $A$
+/// follows from the slope of the critical isochore, and for the saturated
+/// densities (Guggenheim). The ancillary equations provide the initial
+/// values for the phase equilibrium calculations, which makes them faster
+/// and more robust close to the critical point than the generic
+/// initialization.
+pub struct SaturationCurve<E> {
+    eos: Arc<E>,
+    critical_point: State<E>,
+    slope: f64,
+}
+
+impl<E: Residual> SaturationCurve<E> {
+    /// Calculate the critical point and the ancillary equations of
+    /// a pure component.
+    pub fn new(eos: &Arc<E>, options: SolverOptions) -> FeosResult<Self> {
+        if eos.components() != 1 {
+            return Err(FeosError::IncompatibleComponents(eos.components(), 1));
+        }
+        let critical_point = State::critical_point(eos, None, None, options)?;
+        let tc = critical_point.temperature;
+        let pc = critical_point.pressure(Contributions::Total);
+        let slope = (critical_point.dp_dt(Contributions::Total) * tc / pc).into_value();
+        Ok(Self {
+            eos: eos.clone(),
+            critical_point,
+            slope,
+        })
+    }
+
+    /// The critical point of the component.
+    pub fn critical_point(&self) -> &State<E> {
+        &self.critical_point
+    }
+
+    /// Vapor pressure from the ancillary equation.
+    pub fn ancillary_pressure(&self, temperature: Temperature) -> Pressure {
+        let tc = self.critical_point.temperature;
+        let pc = self.critical_point.pressure(Contributions::Total);
+        pc * (self.slope * (1.0 - (tc / temperature).into_value())).exp()
+    }
+
+    /// Boiling temperature from the ancillary equation.
+    pub fn ancillary_temperature(&self, pressure: Pressure) -> Temperature {
+        let tc = self.critical_point.temperature;
+        let pc = self.critical_point.pressure(Contributions::Total);
+        tc / (1.0 - (pressure / pc).into_value().ln() / self.slope)
+    }
+
+    /// Saturated liquid and vapor densities from the ancillary equations.
+    ///
+    /// At low temperatures, the vapor density is that of an ideal gas at
+    /// the ancillary vapor pressure.
+    pub fn ancillary_densities(&self, temperature: Temperature) -> (Density, Density) {
+        let rho_c = self.critical_point.density;
+        let tau = (1.0 - (temperature / self.critical_point.temperature).into_value()).max(0.0);
+        let rho_l = rho_c * (1.0 + 0.75 * tau + 1.75 * tau.cbrt());
+        let rho_v = rho_c * (1.0 + 0.75 * tau - 1.75 * tau.cbrt());
+        let rho_ig = self.ancillary_pressure(temperature) / (RGAS * temperature);
+        (rho_l, if rho_v > rho_ig { rho_v } else { rho_ig })
+    }
+
+    /// Initial phase equilibrium from the ancillary equations.
+    fn ancillary(&self, temperature: Temperature) -> FeosResult<PhaseEquilibrium<E, 2>> {
+        let (rho_l, rho_v) = self.ancillary_densities(temperature);
+        Ok(PhaseEquilibrium::from_states(
+            State::new_pure(&self.eos, temperature, rho_v)?,
+            State::new_pure(&self.eos, temperature, rho_l)?,
+        ))
+    }
+
+    /// Vapor pressure and saturated densities at the given temperature.
+    pub fn psat(
+        &self,
+        temperature: Temperature,
+        options: SolverOptions,
+    ) -> FeosResult<PhaseEquilibrium<E, 2>> {
+        if temperature >= self.critical_point.temperature {
+            return Err(FeosError::SuperCritical);
+        }
+        let initial_state = self.ancillary(temperature)?;
+        PhaseEquilibrium::pure(&self.eos, temperature, Some(&initial_state), options)
+    }
+
+    /// Boiling temperature and saturated densities at the given pressure.
+    pub fn tsat(
+        &self,
+        pressure: Pressure,
+        options: SolverOptions,
+    ) -> FeosResult<PhaseEquilibrium<E, 2>> {
+        if pressure >= self.critical_point.pressure(Contributions::Total) {
+            return Err(FeosError::SuperCritical);
+        }
+        let initial_state = self.ancillary(self.ancillary_temperature(pressure))?;
+        PhaseEquilibrium::pure(&self.eos, pressure, Some(&initial_state), options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use approx::assert_relative_eq;
+    use quantity::{KELVIN, PASCAL};
+
+    #[test]
+    fn saturation_curve() -> FeosResult<()> {
+        let parameters =
+            PengRobinsonParameters::new_simple(&[369.96], &[4.25e6], &[0.153], &[44.0962])?;
+        let eos = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let curve = SaturationCurve::new(&eos, Default::default())?;
+        let tc = curve.critical_point().temperature;
+        assert_relative_eq!(tc, 369.96 * KELVIN, max_relative = 1e-4);
+
+        for t_r in [0.3, 0.5, 0.7, 0.9, 0.99, 0.999, 0.9999] {
+            let vle = curve.psat(t_r * tc, Default::default())?;
+            let p = vle.vapor().pressure(Contributions::Total);
+            assert_relative_eq!(
+                p,
+                vle.liquid().pressure(Contributions::Total),
+                max_relative = 1e-8
+            );
+            assert!(vle.liquid().density > vle.vapor().density);
+
+            // the boiling temperature at the vapor pressure is consistent
+            let vle = curve.tsat(p, Default::default())?;
+            assert_relative_eq!(vle.vapor().temperature, t_r * tc, max_relative = 1e-8);
+        }
+        assert!(curve.psat(1.001 * tc, Default::default()).is_err());
+        assert!(curve.tsat(5e6 * PASCAL, Default::default()).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use super::PhaseEquilibrium;
+use super::flash_derivatives::ln_fugacity_jacobian;
+use crate::ReferenceSystem;
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult};
+use crate::state::State;
+use ndarray::Array1;
+use quantity::{Pressure, Temperature};
+use std::ops::Div;
+
+/// Derivatives of a bubble or dew point along the phase boundary.
+///
+/// The composition of the reference phase (the liquid for bubble points and
+/// the vapor for dew points) is treated as independent mole fractions, i.e.,
+/// the change of the temperature for an arbitrary change of composition
+/// $\delta\mathbf{x}$ with $\sum_i\delta x_i=0$ is $\delta T=\sum_i\frac{\partial T}{\partial x_i}\delta x_i$.
+#[derive(Clone, Debug)]
+pub struct SaturationDerivatives {
+    /// Slope of the phase boundary $\frac{\mathrm{d}p}{\mathrm{d}T}$ at constant composition.
+    pub dp_dt: <Pressure as Div<Temperature>>::Output,
+    /// Derivatives of the temperature w.r.t. the mole fractions at constant pressure.
+    pub dt_dx: Temperature<Array1<f64>>,
+    /// Derivatives of the pressure w.r.t. the mole fractions at constant temperature.
+    pub dp_dx: Pressure<Array1<f64>>,
+}
+
+/// # Saturation derivatives
+impl<E: Residual> PhaseEquilibrium<E, 2> {
+    /// Derivatives of a bubble point at constant liquid composition.
+    ///
+    /// For a pure component, `dp_dt` is the slope of the vapor pressure curve
+    /// given by the Clausius-Clapeyron equation.
+    pub fn bubble_point_derivatives(&self) -> FeosResult<SaturationDerivatives> {
+        self.saturation_derivatives(self.liquid(), self.vapor(), 1.0)
+    }
+
+    /// Derivatives of a dew point at constant vapor composition.
+    pub fn dew_point_derivatives(&self) -> FeosResult<SaturationDerivatives> {
+        self.saturation_derivatives(self.vapor(), self.liquid(), -1.0)
+    }
+
+    /// Differentiate the equilibrium conditions $\ln f_i^L=\ln f_i^V$ and
+    /// eliminate the composition of the incipient phase using the Gibbs-Duhem
+    /// equation $\sum_iy_i\,\mathrm{d}\ln f_i^V=0$ at constant $T$ and $p$, which
+    /// results in
+    /// $\sum_iy_i\left(\Delta_T\ln\varphi_i\,\mathrm{d}T+\Delta_p\ln\varphi_i\,\mathrm{d}p+N^L\sum_jJ_{ij}^L\mathrm{d}x_j\right)=0$
+    /// for a bubble point (and analogously for a dew point) with the differences
+    /// $\Delta_\theta\ln\varphi_i=\frac{\partial\ln\varphi_i^L}{\partial\theta}-\frac{\partial\ln\varphi_i^V}{\partial\theta}$.
+    fn saturation_derivatives(
+        &self,
+        reference: &State<E>,
+        incipient: &State<E>,
+        sign: f64,
+    ) -> FeosResult<SaturationDerivatives> {
+        if Self::is_trivial_solution(reference, incipient) {
+            return Err(FeosError::TrivialSolution);
+        }
+        let (t, p) = (Temperature::from_reduced(1.0), Pressure::from_reduced(1.0));
+        let (liquid, vapor) = (self.liquid(), self.vapor());
+        let dln_phi_dt = ((liquid.dln_phi_dt() - vapor.dln_phi_dt()) * t).into_value();
+        let dln_phi_dp = ((liquid.dln_phi_dp() - vapor.dln_phi_dp()) * p).into_value();
+
+        let w = &incipient.molefracs;
+        let a = w.dot(&dln_phi_dt);
+        let b = w.dot(&dln_phi_dp);
+        let c = w.dot(&ln_fugacity_jacobian(reference)) * reference.total_moles.to_reduced() * sign;
+        Ok(SaturationDerivatives {
+            dp_dt: p * (-a / b) / t,
+            dt_dx: Temperature::from_reduced(-&c / a),
+            dp_dx: Pressure::from_reduced(-&c / b),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::propane_butane;
+    use crate::{Components, Contributions, SolverOptions};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{BAR, KELVIN};
+    use std::sync::Arc;
+
+    #[test]
+    fn saturation_derivatives() -> FeosResult<()> {
+        let eos = Arc::new(propane_butane()?);
+        let options = (SolverOptions::default(), SolverOptions::default());
+        let (t, p) = (330.0 * KELVIN, 10.0 * BAR);
+        let x = arr1(&[0.4, 0.6]);
+        let dx = arr1(&[1.0, -1.0]);
+        let (ht, hp, h) = (1e-4 * KELVIN, 1e-4 * BAR, 1e-6);
+
+        // bubble points
+        let bubble_p = |t, x: &Array1<f64>| -> FeosResult<Pressure> {
+            let vle = PhaseEquilibrium::bubble_point(&eos, t, x, None, None, options)?;
+            Ok(vle.vapor().pressure(Contributions::Total))
+        };
+        let bubble_t = |p, x: &Array1<f64>| -> FeosResult<Temperature> {
+            let vle = PhaseEquilibrium::bubble_point(&eos, p, x, Some(t), None, options)?;
+            Ok(vle.vapor().temperature)
+        };
+        let vle = PhaseEquilibrium::bubble_point(&eos, t, &x, None, None, options)?;
+        let d = vle.bubble_point_derivatives()?;
+        let dp_dt = (bubble_p(t + ht, &x)? - bubble_p(t - ht, &x)?) / (2.0 * ht);
+        assert_relative_eq!(d.dp_dt, dp_dt, max_relative = 1e-6);
+        let dp_dx = (bubble_p(t, &(&x + &dx * h))? - bubble_p(t, &(&x - &dx * h))?) / (2.0 * h);
+        assert_relative_eq!(
+            (d.dp_dx.get(0) - d.dp_dx.get(1)),
+            dp_dx,
+            max_relative = 1e-6
+        );
+
+        let vle = PhaseEquilibrium::bubble_point(&eos, p, &x, Some(t), None, options)?;
+        let d = vle.bubble_point_derivatives()?;
+        let dt_dx = (bubble_t(p, &(&x + &dx * h))? - bubble_t(p, &(&x - &dx * h))?) / (2.0 * h);
+        assert_relative_eq!(
+            (d.dt_dx.get(0) - d.dt_dx.get(1)),
+            dt_dx,
+            max_relative = 1e-6
+        );
+        let dp_dt = 1.0 / ((bubble_t(p + hp, &x)? - bubble_t(p - hp, &x)?) / (2.0 * hp));
+        assert_relative_eq!(d.dp_dt, dp_dt, max_relative = 1e-6);
+
+        // dew points
+        let dew_p = |t, y: &Array1<f64>| -> FeosResult<Pressure> {
+            let vle = PhaseEquilibrium::dew_point(&eos, t, y, None, None, options)?;
+            Ok(vle.vapor().pressure(Contributions::Total))
+        };
+        let vle = PhaseEquilibrium::dew_point(&eos, t, &x, None, None, options)?;
+        let d = vle.dew_point_derivatives()?;
+        let dp_dt = (dew_p(t + ht, &x)? - dew_p(t - ht, &x)?) / (2.0 * ht);
+        assert_relative_eq!(d.dp_dt, dp_dt, max_relative = 1e-6);
+        let dp_dz = (dew_p(t, &(&x + &dx * h))? - dew_p(t, &(&x - &dx * h))?) / (2.0 * h);
+        assert_relative_eq!(
+            (d.dp_dx.get(0) - d.dp_dx.get(1)),
+            dp_dz,
+            max_relative = 1e-6
+        );
+
+        // pure components reduce to the Clausius-Clapeyron equation
+        let pure = PhaseEquilibrium::pure(
+            &Arc::new(eos.subset(&[0])),
+            t,
+            None,
+            SolverOptions::default(),
+        )?;
+        let d = pure.bubble_point_derivatives()?;
+        let (l, v) = (pure.liquid(), pure.vapor());
+        let dh = v.residual_molar_enthalpy() - l.residual_molar_enthalpy();
+        let dv = v.molar_volume() - l.molar_volume();
+        assert_relative_eq!(d.dp_dt, dh / (t * dv), max_relative = 1e-8);
+        assert_relative_eq!(
+            d.dp_dt,
+            pure.dew_point_derivatives()?.dp_dt,
+            max_relative = 1e-10
+        );
+        Ok(())
+    }
+}
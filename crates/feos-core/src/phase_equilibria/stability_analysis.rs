@@ -1,11 +1,11 @@
-use super::PhaseEquilibrium;
+use super::{PhaseEquilibrium, SaturationCache};
 use crate::equation_of_state::Residual;
-use crate::errors::{FeosError, FeosResult};
+use crate::errors::{FeosError, FeosResult, SolverFailure};
 use crate::state::{Contributions, DensityInitialization, State};
 use crate::{ReferenceSystem, SolverOptions, Verbosity};
 use ndarray::*;
-use num_dual::linalg::smallest_ev;
 use num_dual::linalg::LU;
+use num_dual::linalg::smallest_ev;
 use quantity::Moles;
 use std::ops::MulAssign;
 
@@ -16,8 +16,35 @@ const ETA_STEP: f64 = 0.25;
 const MINIMIZE_KMAX: usize = 100;
 const ZERO_TPD: f64 = -1E-08;
 
+/// Classification of a state with respect to phase stability.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhaseStability {
+    /// No phase split lowers the Gibbs energy.
+    Stable,
+    /// Locally stable, but a phase split lowers the Gibbs energy
+    /// (e.g. superheated liquid or subcooled vapor).
+    Metastable,
+    /// Inside the spinodal, i.e., not even locally stable.
+    Unstable,
+}
+
 /// # Stability analysis
 impl<E: Residual> State<E> {
+    /// Determine whether the state is stable, metastable or unstable.
+    ///
+    /// States inside the spinodal are classified as unstable. Locally stable
+    /// states for which the stability analysis finds a phase with a lower
+    /// Gibbs energy are classified as metastable.
+    pub fn phase_stability(&self, options: SolverOptions) -> FeosResult<PhaseStability> {
+        if self.spinodal_eigenvalue()? < 0.0 {
+            Ok(PhaseStability::Unstable)
+        } else if self.is_stable(options)? {
+            Ok(PhaseStability::Stable)
+        } else {
+            Ok(PhaseStability::Metastable)
+        }
+    }
+
     /// Determine if the state is stable, i.e. if a phase split should
     /// occur or not.
     pub fn is_stable(&self, options: SolverOptions) -> FeosResult<bool> {
@@ -35,31 +62,141 @@ impl<E: Residual> State<E> {
             } else {
                 format!("Liquid phase {}", i_trial + 1)
             };
-            if let Ok(mut trial_state) = self.define_trial_state(i_trial) {
-                let (tpd, i) = self.minimize_tpd(&mut trial_state, options)?;
-                let msg = if let Some(tpd) = tpd {
-                    if tpd < ZERO_TPD {
-                        if result
-                            .iter()
-                            .any(|s| PhaseEquilibrium::is_trivial_solution(s, &trial_state))
-                        {
-                            "Found already identified minimum"
-                        } else {
-                            result.push(trial_state);
-                            "Found candidate"
-                        }
-                    } else {
-                        "Found minimum > 0"
-                    }
-                } else {
-                    "Found trivial solution"
-                };
-                log_result!(options.verbosity, "{}: {} in {} step(s)\n", phase, msg, i);
+            if let Ok(trial_state) = self.define_trial_state(i_trial) {
+                self.add_tpd_minimum(&mut result, trial_state, &phase, options)?;
             }
         }
         Ok(result)
     }
 
+    /// Perform a stability analysis with additional trial phases.
+    ///
+    /// In addition to the trial phases of [State::stability_analysis], a
+    /// vapor and a liquid trial phase are initialized with the Raoult's law
+    /// K-factors from `cache`, if the vapor pressures of all components are
+    /// available at the temperature of the state.
+    pub fn stability_analysis_with_cache(
+        &self,
+        cache: &SaturationCache<E>,
+        options: SolverOptions,
+    ) -> FeosResult<Vec<State<E>>> {
+        let mut result = self.stability_analysis(options)?;
+        let Some(k) = cache.k_factors(self.temperature, self.pressure(Contributions::Total)) else {
+            return Ok(result);
+        };
+        for (x_trial, density, phase) in [
+            (
+                &self.molefracs * &k,
+                DensityInitialization::Vapor,
+                "Raoult vapor phase",
+            ),
+            (
+                &self.molefracs / &k,
+                DensityInitialization::Liquid,
+                "Raoult liquid phase",
+            ),
+        ] {
+            let moles = Moles::from_reduced(&x_trial / x_trial.sum());
+            let pressure = self.pressure(Contributions::Total);
+            if let Ok(trial_state) =
+                State::new_npt(&self.eos, self.temperature, pressure, &moles, density)
+            {
+                self.add_tpd_minimum(&mut result, trial_state, phase, options)?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Determine if the state is stable using the exhaustive
+    /// [State::global_stability_analysis].
+    pub fn is_globally_stable(&self, divisions: usize, options: SolverOptions) -> FeosResult<bool> {
+        Ok(self
+            .global_stability_analysis(divisions, options)?
+            .is_empty())
+    }
+
+    /// Perform an exhaustive stability analysis for verification purposes.
+    ///
+    /// In addition to the trial phases of [State::stability_analysis], the
+    /// tangent plane distance is minimized starting from every composition
+    /// of a regular lattice on the composition simplex with `divisions`
+    /// intervals per component, each with a liquid-like and a vapor-like
+    /// density. Starting points for which the minimization fails are skipped.
+    /// An error is returned only if the minimization fails for all of them.
+    ///
+    /// A phase is found if its basin of attraction contains a lattice point,
+    /// so that refining the lattice makes the search arbitrarily robust at
+    /// the cost of $\binom{d+n-1}{n-1}$ minimizations per density for $n$
+    /// components and $d$ divisions.
+    pub fn global_stability_analysis(
+        &self,
+        divisions: usize,
+        options: SolverOptions,
+    ) -> FeosResult<Vec<State<E>>> {
+        let mut result = self.stability_analysis(options)?;
+        let temperature = self.temperature;
+        let pressure = self.pressure(Contributions::Total);
+        let lattice = composition_lattice(self.eos.components(), divisions.max(1));
+        let mut error = None;
+        let mut converged = false;
+        for (i, x_trial) in lattice.into_iter().enumerate() {
+            for (density, label) in [
+                (DensityInitialization::Liquid, "liquid"),
+                (DensityInitialization::Vapor, "vapor"),
+            ] {
+                let phase = format!("Lattice point {} ({label})", i + 1);
+                let moles = Moles::from_reduced(x_trial.clone());
+                let minimum = State::new_npt(&self.eos, temperature, pressure, &moles, density)
+                    .and_then(|trial_state| {
+                        self.add_tpd_minimum(&mut result, trial_state, &phase, options)
+                    });
+                match minimum {
+                    Ok(()) => converged = true,
+                    Err(FeosError::Cancelled) => return Err(FeosError::Cancelled),
+                    Err(e) => {
+                        log_result!(options.verbosity, "{}: {}\n", phase, e);
+                        error.get_or_insert(e);
+                    }
+                }
+            }
+        }
+        match error {
+            Some(e) if !converged => Err(e),
+            _ => Ok(result),
+        }
+    }
+
+    /// Minimize the tangent plane distance starting from `trial_state` and
+    /// add the minimum to `result` if it is negative and not yet identified.
+    fn add_tpd_minimum(
+        &self,
+        result: &mut Vec<State<E>>,
+        mut trial_state: State<E>,
+        phase: &str,
+        options: SolverOptions,
+    ) -> FeosResult<()> {
+        let (tpd, i) = self.minimize_tpd(&mut trial_state, options)?;
+        let msg = if let Some(tpd) = tpd {
+            if tpd < ZERO_TPD {
+                if result
+                    .iter()
+                    .any(|s| PhaseEquilibrium::is_trivial_solution(s, &trial_state))
+                {
+                    "Found already identified minimum"
+                } else {
+                    result.push(trial_state);
+                    "Found candidate"
+                }
+            } else {
+                "Found minimum > 0"
+            }
+        } else {
+            "Found trivial solution"
+        };
+        log_result!(options.verbosity, "{}: {} in {} step(s)\n", phase, msg, i);
+        Ok(())
+    }
+
     fn define_trial_state(&self, dominant_component: usize) -> FeosResult<State<E>> {
         let x_feed = &self.molefracs;
 
@@ -105,8 +242,9 @@ impl<E: Residual> State<E> {
         log_iter!(verbosity, " iter |    residual    |     tpd     | Newton");
         log_iter!(verbosity, "{:-<46}", "");
 
+        let mut error = f64::INFINITY;
         for i in 1..=max_iter {
-            let error = if !newton {
+            error = if !newton {
                 // case: direct substitution
                 let y = (&di - &trial.ln_phi()).mapv(f64::exp);
                 let tpd_old = tpd;
@@ -152,7 +290,14 @@ impl<E: Residual> State<E> {
                 return Ok((Some(tpd), i));
             }
         }
-        Err(FeosError::NotConverged(String::from("stability analysis")))
+        Err(FeosError::NotConverged(
+            SolverFailure::new("stability analysis")
+                .iterations(max_iter)
+                .residual(error)
+                .temperature(trial.temperature)
+                .pressure(trial.pressure(Contributions::Total))
+                .molefracs(&trial.molefracs),
+        ))
     }
 
     fn stability_newton_step(&mut self, di: &Array1<f64>, tpd: &mut f64) -> FeosResult<f64> {
@@ -231,3 +376,29 @@ impl<E: Residual> State<E> {
         Ok(gradient.mapv(f64::abs).sum())
     }
 }
+
+/// Compositions on a regular lattice in the interior of the composition
+/// simplex. The lattice points $k_i\geq 0$ with $\sum_ik_i=d$ are shifted by
+/// half an interval to avoid vanishing mole fractions.
+fn composition_lattice(n: usize, divisions: usize) -> Vec<Array1<f64>> {
+    fn recurse(k: &mut Vec<usize>, n: usize, left: usize, lattice: &mut Vec<Vec<usize>>) {
+        if k.len() == n - 1 {
+            k.push(left);
+            lattice.push(k.clone());
+            k.pop();
+            return;
+        }
+        for ki in 0..=left {
+            k.push(ki);
+            recurse(k, n, left - ki, lattice);
+            k.pop();
+        }
+    }
+    let mut lattice = Vec::new();
+    recurse(&mut Vec::with_capacity(n), n, divisions, &mut lattice);
+    let total = divisions as f64 + 0.5 * n as f64;
+    lattice
+        .into_iter()
+        .map(|k| k.into_iter().map(|k| (k as f64 + 0.5) / total).collect())
+        .collect()
+}
@@ -0,0 +1,191 @@
+use super::{PhaseEquilibrium, rachford_rice_multiphase, rachford_rice_multiphase_compositions};
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::observer::{report_iteration, report_result};
+use crate::state::{Contributions, DensityInitialization, State};
+use crate::{ReferenceSystem, SolverOptions, Verbosity};
+use ndarray::{Array1, Array2, Axis};
+use num_dual::linalg::norm;
+use quantity::{Moles, Pressure, Temperature};
+use std::sync::Arc;
+
+const MAX_ITER_3P: usize = 500;
+const TOL_3P: f64 = 1e-10;
+const MIN_COMPOSITION_DISTANCE: f64 = 1e-3;
+
+/// # Flash calculations
+impl<E: Residual> PhaseEquilibrium<E, 3> {
+    /// Perform a three phase Tp-flash calculation, e.g., for mixtures of
+    /// gas, hydrocarbon liquid and water.
+    ///
+    /// The calculation is initialized with a two phase flash and a stability
+    /// analysis of both phases. The compositions are updated by successive
+    /// substitution using the multiphase Rachford-Rice equations. If no third
+    /// phase is found or the third phase vanishes, an error is returned.
+    ///
+    /// The phases are sorted by increasing density.
+    pub fn three_phase_flash(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressure: Pressure,
+        feed: &Moles<Array1<f64>>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_3P, TOL_3P);
+        let feed_state = State::new_npt(
+            eos,
+            temperature,
+            pressure,
+            feed,
+            DensityInitialization::None,
+        )?;
+        let vle = feed_state.tp_flash(None, SolverOptions::default(), None)?;
+
+        // the third phase is initialized from an unstable direction of the two phases
+        let distinct = |s: &State<E>| {
+            [vle.vapor(), vle.liquid()].iter().all(|p| {
+                (&s.molefracs - &p.molefracs).mapv(f64::abs).sum() > MIN_COMPOSITION_DISTANCE
+            })
+        };
+        let mut trial = None;
+        for phase in [vle.liquid(), vle.vapor()] {
+            trial = phase
+                .stability_analysis(SolverOptions::default())?
+                .into_iter()
+                .find(distinct);
+            if trial.is_some() {
+                break;
+            }
+        }
+        let Some(trial) = trial else {
+            return Err(FeosError::NoPhaseSplit);
+        };
+
+        let z = &feed_state.molefracs;
+        let n = feed_state.total_moles.to_reduced();
+        let beta_v = vle.vapor_phase_fraction();
+        let mut beta = Array1::from_vec(vec![beta_v, 1.0 - beta_v, 0.0]);
+        let mut phases = [vle.vapor().clone(), vle.liquid().clone(), trial];
+        let mut k = Array2::zeros((3, z.len()));
+        let mut res = f64::INFINITY;
+
+        log_iter!(
+            verbosity,
+            " iter |    residual    |           phase fractions           "
+        );
+        log_iter!(verbosity, "{:-<60}", "");
+        for i in 1..=max_iter {
+            // K-values relative to the first phase
+            let ln_phi: Vec<_> = phases.iter().map(|s| s.ln_phi()).collect();
+            let ln_f0 = &ln_phi[0] + &phases[0].molefracs.mapv(f64::ln);
+            res = (1..3)
+                .map(|j| {
+                    let ln_f = &ln_phi[j] + &phases[j].molefracs.mapv(f64::ln);
+                    let r: Array1<f64> = (&ln_f - &ln_f0)
+                        .iter()
+                        .zip(z)
+                        .map(|(&r, &z)| if z > 0.0 { r } else { 0.0 })
+                        .collect();
+                    norm(&r)
+                })
+                .fold(0.0, f64::max);
+            for (j, mut k) in k.axis_iter_mut(Axis(0)).enumerate() {
+                k.assign(&(&ln_phi[0] - &ln_phi[j]).mapv(f64::exp));
+            }
+            beta = rachford_rice_multiphase(z, &k, Some(&beta))?;
+            log_iter!(verbosity, " {:4} | {:14.8e} | {:.8}", i, res, beta);
+            report_iteration("three phase flash", i, res, beta.as_slice().unwrap_or(&[]));
+
+            let x = rachford_rice_multiphase_compositions(z, &k, &beta);
+            for (j, s) in phases.iter_mut().enumerate() {
+                let x_j = x.row(j).to_owned();
+                let x_j = &x_j / x_j.sum();
+                *s = State::new_npt(
+                    eos,
+                    temperature,
+                    pressure,
+                    &Moles::from_reduced(x_j * n * beta[j].max(f64::EPSILON)),
+                    DensityInitialization::InitialDensity(s.density),
+                )?;
+            }
+
+            if res < tol {
+                report_result("three phase flash", i, true);
+                log_result!(
+                    verbosity,
+                    "Three phase flash: calculation converged in {} step(s)\n",
+                    i
+                );
+                if beta.iter().any(|&b| b <= 0.0)
+                    || (0..3).any(|j| {
+                        (j + 1..3)
+                            .any(|l| PhaseEquilibrium::is_trivial_solution(&phases[j], &phases[l]))
+                    })
+                {
+                    return Err(FeosError::NoPhaseSplit);
+                }
+                phases.sort_by(|s1, s2| s1.density.partial_cmp(&s2.density).unwrap());
+                return Ok(Self(phases));
+            }
+        }
+        report_result("three phase flash", max_iter, false);
+        Err(FeosError::NotConverged(
+            SolverFailure::new("three phase flash")
+                .iterations(max_iter)
+                .residual(res)
+                .temperature(temperature)
+                .pressure(pressure)
+                .molefracs(z),
+        ))
+    }
+
+    /// Pressure of the phase equilibrium.
+    pub fn pressure(&self) -> Pressure {
+        self.0[0].pressure(Contributions::Total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{BAR, KELVIN};
+
+    #[test]
+    fn gas_oil_water() -> FeosResult<()> {
+        // methane, n-decane, water
+        let parameters = PengRobinsonParameters::new_simple(
+            &[190.56, 617.7, 647.1],
+            &[4.599e6, 2.11e6, 22.064e6],
+            &[0.011, 0.49, 0.344],
+            &[16.043, 142.28, 18.015],
+        )?;
+        let eos = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let feed = Moles::from_reduced(arr1(&[0.4, 0.2, 0.4]));
+        let vlle = PhaseEquilibrium::three_phase_flash(
+            &eos,
+            300.0 * KELVIN,
+            50.0 * BAR,
+            &feed,
+            Default::default(),
+        )?;
+        assert!(vlle.vapor().molefracs[0] > 0.9);
+        assert!(vlle.liquid1().molefracs[1] > 0.5);
+        assert!(vlle.liquid2().molefracs[2] > 0.99);
+
+        // equal fugacities in all phases
+        let ln_f = |s: &State<_>| s.ln_phi() + s.molefracs.mapv(f64::ln);
+        assert_relative_eq!(ln_f(vlle.vapor()), ln_f(vlle.liquid1()), epsilon = 1e-8);
+        assert_relative_eq!(ln_f(vlle.vapor()), ln_f(vlle.liquid2()), epsilon = 1e-8);
+
+        // mass balance
+        let moles = vlle.vapor().moles.clone()
+            + vlle.liquid1().moles.clone()
+            + vlle.liquid2().moles.clone();
+        assert_relative_eq!(moles, feed, max_relative = 1e-8);
+        assert_relative_eq!(vlle.phase_fractions().sum(), 1.0, max_relative = 1e-12);
+        Ok(())
+    }
+}
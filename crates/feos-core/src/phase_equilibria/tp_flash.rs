@@ -1,8 +1,9 @@
-use super::PhaseEquilibrium;
+use super::{PhaseEquilibrium, rachford_rice};
 use crate::equation_of_state::Residual;
-use crate::errors::{FeosError, FeosResult};
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::observer::{report_iteration, report_result};
 use crate::state::{Contributions, DensityInitialization, State};
-use crate::{SolverOptions, Verbosity};
+use crate::{SolverAlgorithm, SolverOptions, Verbosity};
 use ndarray::*;
 use num_dual::linalg::norm;
 use quantity::{Dimensionless, Moles, Pressure, Temperature};
@@ -11,6 +12,56 @@ use std::sync::Arc;
 const MAX_ITER_TP: usize = 400;
 const TOL_TP: f64 = 1e-8;
 
+/// Preallocated buffers for the inner loops of Tp-flash calculations.
+///
+/// The K-values, fugacity coefficients, residuals and extrapolation history
+/// of the successive substitution are stored in the workspace, so that the
+/// iterations only allocate for the evaluation of the new phases. Repeated
+/// flash calculations of mixtures with the same number of components can
+/// reuse a single workspace with [State::tp_flash_with_workspace].
+#[derive(Clone, Debug)]
+pub struct FlashWorkspace {
+    k: Array1<f64>,
+    residual: Array1<f64>,
+    ln_phi_v: Array1<f64>,
+    ln_phi_l: Array1<f64>,
+    ln_k_history: Array2<f64>,
+}
+
+impl FlashWorkspace {
+    /// Create a workspace for mixtures with the given number of components.
+    pub fn new(components: usize) -> Self {
+        Self {
+            k: Array1::zeros(components),
+            residual: Array1::zeros(components),
+            ln_phi_v: Array1::zeros(components),
+            ln_phi_l: Array1::zeros(components),
+            ln_k_history: Array2::zeros((4, components)),
+        }
+    }
+
+    /// The number of components the workspace is allocated for.
+    pub fn components(&self) -> usize {
+        self.k.len()
+    }
+
+    fn check_components(&self, components: usize) -> FeosResult<()> {
+        if self.components() != components {
+            return Err(FeosError::IncompatibleComponents(
+                self.components(),
+                components,
+            ));
+        }
+        Ok(())
+    }
+
+    fn set_non_volatile(&mut self, non_volatile_components: &Option<Vec<usize>>) {
+        if let Some(nvc) = non_volatile_components.as_ref() {
+            nvc.iter().for_each(|&c| self.k[c] = 0.0);
+        }
+    }
+}
+
 /// # Flash calculations
 impl<E: Residual> PhaseEquilibrium<E, 2> {
     /// Perform a Tp-flash calculation. If no initial values are
@@ -46,12 +97,37 @@ impl<E: Residual> State<E> {
     ///
     /// The algorithm can be use to calculate phase equilibria of systems
     /// containing non-volatile components (e.g. ions).
+    ///
+    /// If the iteration starting from the provided initial values converges
+    /// to the trivial solution, the calculation is restarted from the results
+    /// of a stability analysis. If no phase split can be found, an error is
+    /// returned instead of a two-phase result with identical phases.
     pub fn tp_flash(
         &self,
         initial_state: Option<&PhaseEquilibrium<E, 2>>,
         options: SolverOptions,
         non_volatile_components: Option<Vec<usize>>,
     ) -> FeosResult<PhaseEquilibrium<E, 2>> {
+        let mut workspace = FlashWorkspace::new(self.eos.components());
+        self.tp_flash_with_workspace(
+            initial_state,
+            options,
+            non_volatile_components,
+            &mut workspace,
+        )
+    }
+
+    /// Perform a Tp-flash calculation like [State::tp_flash] reusing
+    /// the buffers of the provided [FlashWorkspace].
+    pub fn tp_flash_with_workspace(
+        &self,
+        initial_state: Option<&PhaseEquilibrium<E, 2>>,
+        options: SolverOptions,
+        non_volatile_components: Option<Vec<usize>>,
+        workspace: &mut FlashWorkspace,
+    ) -> FeosResult<PhaseEquilibrium<E, 2>> {
+        workspace.check_components(self.eos.components())?;
+
         // initialization
         if let Some(init) = initial_state {
             let vle = self.tp_flash_(
@@ -59,6 +135,7 @@ impl<E: Residual> State<E> {
                     .update_pressure(self.temperature, self.pressure(Contributions::Total))?,
                 options,
                 non_volatile_components.clone(),
+                workspace,
             );
             if vle.is_ok() {
                 return vle;
@@ -66,24 +143,30 @@ impl<E: Residual> State<E> {
         }
 
         let (init1, init2) = PhaseEquilibrium::vle_init_stability(self)?;
-        let vle = self.tp_flash_(init1, options, non_volatile_components.clone());
+        let vle = self.tp_flash_(init1, options, non_volatile_components.clone(), workspace);
         if vle.is_ok() {
             return vle;
         }
 
         if let Some(init2) = init2 {
-            self.tp_flash_(init2, options, non_volatile_components)
+            self.tp_flash_(init2, options, non_volatile_components, workspace)
         } else {
             vle
         }
     }
 
+    /// Perform a Tp-flash calculation starting from the given phases
+    /// without a stability analysis, using the buffers of the provided
+    /// [FlashWorkspace].
     pub fn tp_flash_(
         &self,
         mut new_vle_state: PhaseEquilibrium<E, 2>,
         options: SolverOptions,
         non_volatile_components: Option<Vec<usize>>,
+        workspace: &mut FlashWorkspace,
     ) -> FeosResult<PhaseEquilibrium<E, 2>> {
+        workspace.check_components(self.eos.components())?;
+
         // set options
         let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_TP, TOL_TP);
 
@@ -101,16 +184,47 @@ impl<E: Residual> State<E> {
         );
 
         let mut iter = 0;
+        if options.algorithm == SolverAlgorithm::SuccessiveSubstitution {
+            let converged = new_vle_state.successive_substitution(
+                self,
+                max_iter,
+                &mut iter,
+                false,
+                tol,
+                verbosity,
+                &non_volatile_components,
+                workspace,
+            )?;
+            report_result("tp flash", iter, converged);
+            if !converged {
+                return Err(FeosError::NotConverged(
+                    SolverFailure::new("tp flash")
+                        .iterations(iter)
+                        .residual(norm(&workspace.residual))
+                        .temperature(self.temperature)
+                        .pressure(self.pressure(Contributions::Total))
+                        .molefracs(&self.molefracs),
+                ));
+            }
+            log_result!(
+                verbosity,
+                "Tp flash: calculation converged in {} step(s)\n",
+                iter
+            );
+            return new_vle_state.check_trivial_solution();
+        }
+
         if non_volatile_components.is_none() {
             // 3 steps of successive substitution
             new_vle_state.successive_substitution(
                 self,
                 3,
                 &mut iter,
-                &mut None,
+                false,
                 tol,
                 verbosity,
                 &non_volatile_components,
+                workspace,
             )?;
 
             // check convergence
@@ -123,39 +237,43 @@ impl<E: Residual> State<E> {
 
             // fix if only tpd[1] is positive
             if tpd[0] < 0.0 && dg >= 0.0 {
-                let mut k = (self.ln_phi() - new_vle_state.vapor().ln_phi()).mapv(f64::exp);
+                Zip::from(&mut workspace.k)
+                    .and(&self.ln_phi())
+                    .and(&new_vle_state.vapor().ln_phi())
+                    .for_each(|k, &ln_phi_z, &ln_phi_v| *k = (ln_phi_z - ln_phi_v).exp());
                 // Set k = 0 for non-volatile components
-                if let Some(nvc) = non_volatile_components.as_ref() {
-                    nvc.iter().for_each(|&c| k[c] = 0.0);
-                }
-                new_vle_state.update_states(self, &k)?;
+                workspace.set_non_volatile(&non_volatile_components);
+                new_vle_state.update_states(self, &workspace.k)?;
                 new_vle_state.successive_substitution(
                     self,
                     1,
                     &mut iter,
-                    &mut None,
+                    false,
                     tol,
                     verbosity,
                     &non_volatile_components,
+                    workspace,
                 )?;
             }
 
             // fix if only tpd[0] is positive
             if tpd[1] < 0.0 && dg >= 0.0 {
-                let mut k = (new_vle_state.liquid().ln_phi() - self.ln_phi()).mapv(f64::exp);
+                Zip::from(&mut workspace.k)
+                    .and(&new_vle_state.liquid().ln_phi())
+                    .and(&self.ln_phi())
+                    .for_each(|k, &ln_phi_l, &ln_phi_z| *k = (ln_phi_l - ln_phi_z).exp());
                 // Set k = 0 for non-volatile components
-                if let Some(nvc) = non_volatile_components.as_ref() {
-                    nvc.iter().for_each(|&c| k[c] = 0.0);
-                }
-                new_vle_state.update_states(self, &k)?;
+                workspace.set_non_volatile(&non_volatile_components);
+                new_vle_state.update_states(self, &workspace.k)?;
                 new_vle_state.successive_substitution(
                     self,
                     1,
                     &mut iter,
-                    &mut None,
+                    false,
                     tol,
                     verbosity,
                     &non_volatile_components,
+                    workspace,
                 )?;
             }
         }
@@ -168,9 +286,11 @@ impl<E: Residual> State<E> {
             tol,
             verbosity,
             &non_volatile_components,
+            workspace,
         )?;
 
-        Ok(new_vle_state)
+        // reject identical phases
+        new_vle_state.check_trivial_solution()
     }
 
     fn tangent_plane_distance(&self, trial_state: &State<E>) -> f64 {
@@ -183,6 +303,7 @@ impl<E: Residual> State<E> {
 }
 
 impl<E: Residual> PhaseEquilibrium<E, 2> {
+    #[expect(clippy::too_many_arguments)]
     fn accelerated_successive_substitution(
         &mut self,
         feed_state: &State<E>,
@@ -191,24 +312,27 @@ impl<E: Residual> PhaseEquilibrium<E, 2> {
         tol: f64,
         verbosity: Verbosity,
         non_volatile_components: &Option<Vec<usize>>,
+        workspace: &mut FlashWorkspace,
     ) -> FeosResult<()> {
         for _ in 0..max_iter {
             // do 5 successive substitution steps and check for convergence
-            let mut k_vec = Array::zeros((4, self.vapor().eos.components()));
+            workspace.ln_k_history.fill(0.0);
             if self.successive_substitution(
                 feed_state,
                 5,
                 iter,
-                &mut Some(&mut k_vec),
+                true,
                 tol,
                 verbosity,
                 non_volatile_components,
+                workspace,
             )? {
                 log_result!(
                     verbosity,
                     "Tp flash: calculation converged in {} step(s)\n",
                     iter
                 );
+                report_result("tp flash", *iter, true);
                 return Ok(());
             }
 
@@ -216,36 +340,46 @@ impl<E: Residual> PhaseEquilibrium<E, 2> {
             let gibbs = self.total_gibbs_energy();
 
             // extrapolate K values
-            let delta_vec = &k_vec.slice(s![1.., ..]) - &k_vec.slice(s![..3, ..]);
-            let delta = Array::from_shape_fn((3, 3), |(i, j)| {
-                (&delta_vec.index_axis(Axis(0), i) * &delta_vec.index_axis(Axis(0), j)).sum()
-            });
-            let d = delta[(0, 1)] * delta[(0, 1)] - delta[(0, 0)] * delta[(1, 1)];
-            let a = (delta[(0, 2)] * delta[(0, 1)] - delta[(1, 2)] * delta[(0, 0)]) / d;
-            let b = (delta[(1, 2)] * delta[(0, 1)] - delta[(0, 2)] * delta[(1, 1)]) / d;
-
-            let mut k = (&k_vec.index_axis(Axis(0), 3)
-                + &((b * &delta_vec.index_axis(Axis(0), 1)
-                    + (a + b) * &delta_vec.index_axis(Axis(0), 2))
-                    / (1.0 - a - b)))
-                .mapv(f64::exp);
+            let ln_k = &workspace.ln_k_history;
+            let delta = |i: usize, j: usize| {
+                (0..ln_k.ncols())
+                    .map(|c| (ln_k[(i + 1, c)] - ln_k[(i, c)]) * (ln_k[(j + 1, c)] - ln_k[(j, c)]))
+                    .sum::<f64>()
+            };
+            let [d00, d01, d02, d11, d12] =
+                [(0, 0), (0, 1), (0, 2), (1, 1), (1, 2)].map(|(i, j)| delta(i, j));
+            let d = d01 * d01 - d00 * d11;
+            let a = (d02 * d01 - d12 * d00) / d;
+            let b = (d12 * d01 - d02 * d11) / d;
+
+            for c in 0..ln_k.ncols() {
+                let delta_1 = ln_k[(2, c)] - ln_k[(1, c)];
+                let delta_2 = ln_k[(3, c)] - ln_k[(2, c)];
+                workspace.k[c] =
+                    (ln_k[(3, c)] + (b * delta_1 + (a + b) * delta_2) / (1.0 - a - b)).exp();
+            }
 
             // Set k = 0 for non-volatile components
-            if let Some(nvc) = non_volatile_components.as_ref() {
-                nvc.iter().for_each(|&c| k[c] = 0.0);
-            }
-            if !k.iter().all(|i| i.is_finite()) {
+            workspace.set_non_volatile(non_volatile_components);
+            if !workspace.k.iter().all(|i| i.is_finite()) {
                 continue;
             }
 
             // calculate new states
-            let mut trial_vle_state = self.clone();
-            trial_vle_state.update_states(feed_state, &k)?;
+            let trial_vle_state = self.trial_states(feed_state, &workspace.k)?;
             if trial_vle_state.total_gibbs_energy() < gibbs {
                 *self = trial_vle_state;
             }
         }
-        Err(FeosError::NotConverged("TP flash".to_owned()))
+        report_result("tp flash", *iter, false);
+        Err(FeosError::NotConverged(
+            SolverFailure::new("tp flash")
+                .iterations(*iter)
+                .residual(norm(&workspace.residual))
+                .temperature(feed_state.temperature)
+                .pressure(feed_state.pressure(Contributions::Total))
+                .molefracs(&feed_state.molefracs),
+        ))
     }
 
     #[expect(clippy::too_many_arguments)]
@@ -254,37 +388,40 @@ impl<E: Residual> PhaseEquilibrium<E, 2> {
         feed_state: &State<E>,
         iterations: usize,
         iter: &mut usize,
-        k_vec: &mut Option<&mut Array2<f64>>,
+        store_ln_k: bool,
         abs_tol: f64,
         verbosity: Verbosity,
         non_volatile_components: &Option<Vec<usize>>,
+        workspace: &mut FlashWorkspace,
     ) -> FeosResult<bool> {
         for i in 0..iterations {
-            let ln_phi_v = self.vapor().ln_phi();
-            let ln_phi_l = self.liquid().ln_phi();
-            let mut k = (&ln_phi_l - &ln_phi_v).mapv(f64::exp);
+            self.vapor().ln_phi_into(&mut workspace.ln_phi_v);
+            self.liquid().ln_phi_into(&mut workspace.ln_phi_l);
+            Zip::from(&mut workspace.k)
+                .and(&workspace.ln_phi_l)
+                .and(&workspace.ln_phi_v)
+                .for_each(|k, &ln_phi_l, &ln_phi_v| *k = (ln_phi_l - ln_phi_v).exp());
 
             // Set k = 0 for non-volatile components
-            if let Some(nvc) = non_volatile_components.as_ref() {
-                nvc.iter().for_each(|&c| k[c] = 0.0);
-            }
+            workspace.set_non_volatile(non_volatile_components);
 
             // check for convergence
             *iter += 1;
-            let mut res_vec = ln_phi_l - ln_phi_v
-                + (&self.liquid().molefracs / &self.vapor().molefracs).map(|&i| {
-                    if i > 0.0 {
-                        i.ln()
-                    } else {
-                        0.0
-                    }
+            Zip::from(&mut workspace.residual)
+                .and(&workspace.ln_phi_l)
+                .and(&workspace.ln_phi_v)
+                .and(&self.liquid().molefracs)
+                .and(&self.vapor().molefracs)
+                .for_each(|res, &ln_phi_l, &ln_phi_v, &x, &y| {
+                    let ratio = x / y;
+                    *res = ln_phi_l - ln_phi_v + if ratio > 0.0 { ratio.ln() } else { 0.0 };
                 });
 
             // Set residuum to 0 for non-volatile components
             if let Some(nvc) = non_volatile_components.as_ref() {
-                nvc.iter().for_each(|&c| res_vec[c] = 0.0);
+                nvc.iter().for_each(|&c| workspace.residual[c] = 0.0);
             }
-            let res = norm(&res_vec);
+            let res = norm(&workspace.residual);
             log_iter!(
                 verbosity,
                 " {:4} | {:14.8e} | {:.8} | {:.8}",
@@ -293,35 +430,59 @@ impl<E: Residual> PhaseEquilibrium<E, 2> {
                 self.vapor().molefracs,
                 self.liquid().molefracs,
             );
+            report_iteration("tp flash", *iter, res, workspace.k.as_slice().unwrap());
             if res < abs_tol {
                 return Ok(true);
             }
 
-            self.update_states(feed_state, &k)?;
-            if let Some(k_vec) = k_vec {
-                if i >= iterations - 3 {
-                    k_vec
-                        .index_axis_mut(Axis(0), i + 3 - iterations)
-                        .assign(&k.map(|ki| if *ki > 0.0 { ki.ln() } else { 0.0 }));
-                }
+            self.update_states(feed_state, &workspace.k)?;
+            if store_ln_k && i >= iterations - 3 {
+                Zip::from(
+                    workspace
+                        .ln_k_history
+                        .index_axis_mut(Axis(0), i + 3 - iterations),
+                )
+                .and(&workspace.k)
+                .for_each(|ln_k, &k| *ln_k = if k > 0.0 { k.ln() } else { 0.0 });
             }
         }
         Ok(false)
     }
 
     fn update_states(&mut self, feed_state: &State<E>, k: &Array1<f64>) -> FeosResult<()> {
-        // calculate vapor phase fraction using Rachford-Rice algorithm
-        let mut beta = self.vapor_phase_fraction();
-        beta = rachford_rice(&feed_state.molefracs, k, Some(beta))?;
-
-        // update VLE
-        let v = feed_state.moles.clone() * Dimensionless::new(beta * k / (1.0 - beta + beta * k));
-        let l =
-            feed_state.moles.clone() * Dimensionless::new((1.0 - beta) / (1.0 - beta + beta * k));
-        self.update_moles(feed_state.pressure(Contributions::Total), [&v, &l])?;
+        *self = self.trial_states(feed_state, k)?;
         Ok(())
     }
 
+    fn trial_states(&self, feed_state: &State<E>, k: &Array1<f64>) -> FeosResult<Self> {
+        // calculate vapor phase fraction using Rachford-Rice algorithm
+        let beta = rachford_rice(&feed_state.molefracs, k, Some(self.vapor_phase_fraction()))?;
+
+        // calculate the phases from the moles of the feed
+        let moles = |f: &dyn Fn(f64) -> f64| {
+            feed_state.total_moles
+                * Dimensionless::new(
+                    Zip::from(&feed_state.molefracs)
+                        .and(k)
+                        .map_collect(|&x, &k| x * f(k) / (1.0 - beta + beta * k)),
+                )
+        };
+        let pressure = feed_state.pressure(Contributions::Total);
+        let state = |s: &State<E>, moles| {
+            State::new_npt(
+                &s.eos,
+                s.temperature,
+                pressure,
+                &moles,
+                DensityInitialization::InitialDensity(s.density),
+            )
+        };
+        Ok(Self([
+            state(self.vapor(), moles(&|k| beta * k))?,
+            state(self.liquid(), moles(&|_| 1.0 - beta))?,
+        ]))
+    }
+
     fn vle_init_stability(feed_state: &State<E>) -> FeosResult<(Self, Option<Self>)> {
         let mut stable_states = feed_state.stability_analysis(SolverOptions::default())?;
         let state1 = stable_states.pop();
@@ -338,70 +499,3 @@ impl<E: Residual> PhaseEquilibrium<E, 2> {
         }
     }
 }
-
-fn rachford_rice(feed: &Array1<f64>, k: &Array1<f64>, beta_in: Option<f64>) -> FeosResult<f64> {
-    const MAX_ITER: usize = 10;
-    const ABS_TOL: f64 = 1e-6;
-
-    // check if solution exists
-    let (mut beta_min, mut beta_max) =
-        if (feed * k).sum() > 1.0 && (feed / k).iter().filter(|x| !x.is_nan()).sum::<f64>() > 1.0 {
-            (0.0, 1.0)
-        } else {
-            return Err(FeosError::IterationFailed(String::from("rachford_rice")));
-        };
-
-    // look for tighter bounds
-    for (&k, &f) in k.iter().zip(feed.iter()) {
-        if k > 1.0 {
-            let b = (k * f - 1.0) / (k - 1.0);
-            if b > beta_min {
-                beta_min = b;
-            }
-        }
-        if k < 1.0 {
-            let b = (1.0 - f) / (1.0 - k);
-            if b < beta_max {
-                beta_max = b;
-            }
-        }
-    }
-
-    // initialize
-    let mut beta = 0.5 * (beta_min + beta_max);
-    if let Some(b) = beta_in {
-        if b > beta_min && b < beta_max {
-            beta = b;
-        }
-    }
-    let g = (feed * &(k - 1.0) / (1.0 - beta + beta * k)).sum();
-    if g > 0.0 {
-        beta_min = beta
-    } else {
-        beta_max = beta
-    }
-
-    // iterate
-    for _ in 0..MAX_ITER {
-        let frac = (k - 1.0) / (1.0 - beta + beta * k);
-        let g = (feed * &frac).sum();
-        let dg = -(feed * &frac * &frac).sum();
-        if g > 0.0 {
-            beta_min = beta;
-        } else {
-            beta_max = beta;
-        }
-
-        let dbeta = g / dg;
-        beta -= dbeta;
-
-        if beta < beta_min || beta > beta_max {
-            beta = 0.5 * (beta_min + beta_max);
-        }
-        if dbeta.abs() < ABS_TOL {
-            return Ok(beta);
-        }
-    }
-
-    Ok(beta)
-}
@@ -1,15 +1,21 @@
 use super::PhaseEquilibrium;
 use crate::equation_of_state::Residual;
 use crate::errors::{FeosError, FeosResult};
-use crate::state::{Contributions, DensityInitialization, State, TPSpec};
-use crate::{ReferenceSystem, SolverOptions, TemperatureOrPressure, Verbosity};
-use ndarray::{arr1, Array1};
-use quantity::{Moles, Pressure, Temperature, RGAS};
+use crate::state::{Contributions, DensityInitialization, State, StateHD, TPSpec};
+use crate::{NewtonSolver, ReferenceSystem, SolverOptions, TemperatureOrPressure};
+use nalgebra::allocator::Allocator;
+use nalgebra::{DefaultAllocator, Dim, OVector, SVector};
+use ndarray::{Array1, ScalarOperand, arr1};
+use num_dual::{Dual, DualNum, DualSVec64, first_derivative};
+use quantity::{Density, KELVIN, METER, MOL, Moles, Pressure, RGAS, Temperature};
 use std::sync::Arc;
+use typenum::P3;
 
 const SCALE_T_NEW: f64 = 0.7;
 const MAX_ITER_PURE: usize = 50;
 const TOL_PURE: f64 = 1e-12;
+const MAX_LNRHO_STEP: f64 = 1.0;
+const MAX_LNT_STEP: f64 = 0.05;
 
 /// # Pure component phase equilibria
 impl<E: Residual> PhaseEquilibrium<E, 2> {
@@ -34,19 +40,17 @@ impl<E: Residual> PhaseEquilibrium<E, 2> {
         initial_state: Option<&PhaseEquilibrium<E, 2>>,
         options: SolverOptions,
     ) -> FeosResult<Self> {
-        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_PURE, TOL_PURE);
-
         // First use given initial state if applicable
         let mut vle = initial_state.and_then(|init| {
             Self::init_pure_state(init, temperature)
-                .and_then(|vle| vle.iterate_pure_t(max_iter, tol, verbosity))
+                .and_then(|vle| vle.iterate_pure_t(options))
                 .ok()
         });
 
         // Next try to initialize with an ideal gas assumption
         vle = vle.or_else(|| {
             Self::init_pure_ideal_gas(eos, temperature)
-                .and_then(|vle| vle.iterate_pure_t(max_iter, tol, verbosity))
+                .and_then(|vle| vle.iterate_pure_t(options))
                 .ok()
         });
 
@@ -54,105 +58,32 @@ impl<E: Residual> PhaseEquilibrium<E, 2> {
         vle.map_or_else(
             || {
                 Self::init_pure_spinodal(eos, temperature)
-                    .and_then(|vle| vle.iterate_pure_t(max_iter, tol, verbosity))
+                    .and_then(|vle| vle.iterate_pure_t(options))
             },
             Ok,
         )
     }
 
-    fn iterate_pure_t(self, max_iter: usize, tol: f64, verbosity: Verbosity) -> FeosResult<Self> {
-        let mut p_old = self.vapor().pressure(Contributions::Total);
-        let [mut vapor, mut liquid] = self.0;
-
-        log_iter!(verbosity,
-            " iter |     residual      |     pressure     |    liquid density    |    vapor density     | Newton steps"
-        );
-        log_iter!(verbosity, "{:-<106}", "");
-        log_iter!(
-            verbosity,
-            " {:4} |                   | {:12.8} | {:12.8} | {:12.8} |",
-            0,
-            p_old,
-            liquid.density,
-            vapor.density
-        );
-
-        for i in 1..=max_iter {
-            // calculate the pressures and derivatives
-            let (p_l, p_rho_l) = liquid.p_dpdrho();
-            let (p_v, p_rho_v) = vapor.p_dpdrho();
-            // calculate the molar Helmholtz energies (already cached)
-            let a_l_res = liquid.residual_molar_helmholtz_energy();
-            let a_v_res = vapor.residual_molar_helmholtz_energy();
-
-            // Estimate the new pressure
-            let kt = RGAS * vapor.temperature;
-            let delta_v = 1.0 / vapor.density - 1.0 / liquid.density;
-            let delta_a =
-                a_v_res - a_l_res + kt * (vapor.density / liquid.density).into_value().ln();
-            let mut p_new = -delta_a / delta_v;
-
-            // If the pressure becomes negative, assume the gas phase is ideal. The
-            // resulting pressure is always positive.
-            if p_new.is_sign_negative() {
-                p_new = p_v
-                    * ((-delta_a - p_v * vapor.volume / vapor.total_moles) / kt)
-                        .into_value()
-                        .exp();
-            }
-
-            // Improve the estimate by exploiting the almost ideal behavior of the gas phase
-            let kt = RGAS * vapor.temperature;
-            let mut newton_iter = 0;
-            let newton_tol = p_old * delta_v * tol;
-            for _ in 0..20 {
-                let p_frac = (p_new / p_old).into_value();
-                let f = p_new * delta_v + delta_a + (p_frac.ln() + 1.0 - p_frac) * kt;
-                let df_dp = delta_v + (1.0 / p_new - 1.0 / p_old) * kt;
-                p_new -= f / df_dp;
-                newton_iter += 1;
-                if f.abs() < newton_tol {
-                    break;
-                }
-            }
-
-            // Emergency brake if the implementation of the EOS is not safe.
-            if p_new.is_nan() {
-                return Err(FeosError::IterationFailed("pure_t".to_owned()));
-            }
-
-            // Calculate Newton steps for the densities and update state.
-            let rho_l = liquid.density + (p_new - p_l) / p_rho_l;
-            let rho_v = vapor.density + (p_new - p_v) / p_rho_v;
-            liquid = State::new_pure(&liquid.eos, liquid.temperature, rho_l)?;
-            vapor = State::new_pure(&vapor.eos, vapor.temperature, rho_v)?;
-            if Self::is_trivial_solution(&vapor, &liquid) {
-                return Err(FeosError::TrivialSolution);
-            }
-
-            // Check for convergence
-            let res = (p_new - p_old).abs();
-            log_iter!(
-                verbosity,
-                " {:4} | {:14.8e} | {:12.8} | {:12.8} | {:12.8} | {}",
-                i,
-                res,
-                p_new,
-                liquid.density,
-                vapor.density,
-                newton_iter
-            );
-            if res < p_old * tol {
-                log_result!(
-                    verbosity,
-                    "PhaseEquilibrium::pure_t: calculation converged in {} step(s)\n",
-                    i
-                );
-                return Ok(Self([vapor, liquid]));
-            }
-            p_old = p_new;
-        }
-        Err(FeosError::NotConverged("pure_t".to_owned()))
+    fn iterate_pure_t(self, options: SolverOptions) -> FeosResult<Self> {
+        let [vapor, liquid] = &self.0;
+        let eos = &vapor.eos;
+        let t = vapor.temperature.to_reduced();
+        let x = NewtonSolver::new("vapor pressure", MAX_ITER_PURE, TOL_PURE, options)?
+            .step_limit(|_, delta: &mut SVector<f64, 2>| limit_step(delta, MAX_LNRHO_STEP))
+            .report(|x: &SVector<f64, 2>| vec![density_si(x[0]), density_si(x[1])])
+            .failure(|_, failure| failure.temperature(Temperature::from_reduced(t)))
+            .solve(
+                |x: SVector<DualSVec64<2>, 2>| {
+                    let t = DualSVec64::from(t);
+                    let ([r0, r1], _) = pure_objective(eos, t, x[0], x[1]);
+                    Ok(SVector::from([r0, r1]))
+                },
+                SVector::from([
+                    liquid.density.to_reduced().ln(),
+                    vapor.density.to_reduced().ln(),
+                ]),
+            )?;
+        Self::from_reduced(eos, t, x[0], x[1])
     }
 
     /// Calculate a phase equilibrium for a pure component
@@ -163,96 +94,56 @@ impl<E: Residual> PhaseEquilibrium<E, 2> {
         initial_state: Option<&Self>,
         options: SolverOptions,
     ) -> FeosResult<Self> {
-        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_PURE, TOL_PURE);
-
         // Initialize the phase equilibrium
-        let mut vle = match initial_state {
+        let vle = match initial_state {
             Some(init) => init
                 .clone()
                 .update_pressure(init.vapor().temperature, pressure)?,
             None => PhaseEquilibrium::init_pure_p(eos, pressure)?,
         };
 
-        log_iter!(
-            verbosity,
-            " iter |     residual     |   temperature   |    liquid density    |    vapor density     "
-        );
-        log_iter!(verbosity, "{:-<89}", "");
-        log_iter!(
-            verbosity,
-            " {:4} |                  | {:13.8} | {:12.8} | {:12.8}",
-            0,
-            vle.vapor().temperature,
-            vle.liquid().density,
-            vle.vapor().density
-        );
-        for i in 1..=max_iter {
-            // calculate the pressures and derivatives
-            let (p_l, p_rho_l) = vle.liquid().p_dpdrho();
-            let (p_v, p_rho_v) = vle.vapor().p_dpdrho();
-            let p_t_l = vle.liquid().dp_dt(Contributions::Total);
-            let p_t_v = vle.vapor().dp_dt(Contributions::Total);
-
-            // calculate the residual molar entropies (already cached)
-            let s_l_res = vle.liquid().residual_molar_entropy();
-            let s_v_res = vle.vapor().residual_molar_entropy();
-
-            // calculate the residual molar Helmholtz energies (already cached)
-            let a_l_res = vle.liquid().residual_molar_helmholtz_energy();
-            let a_v_res = vle.vapor().residual_molar_helmholtz_energy();
-
-            // calculate the molar volumes
-            let v_l = 1.0 / vle.liquid().density;
-            let v_v = 1.0 / vle.vapor().density;
-
-            // estimate the temperature steps
-            let kt = RGAS * vle.vapor().temperature;
-            let ln_rho = (v_l / v_v).into_value().ln();
-            let delta_t = (pressure * (v_v - v_l) + (a_v_res - a_l_res + kt * ln_rho))
-                / (s_v_res - s_l_res - RGAS * ln_rho);
-            let t_new = vle.vapor().temperature + delta_t;
-
-            // calculate Newton steps for the densities and update state.
-            let rho_l = vle.liquid().density + (pressure - p_l - p_t_l * delta_t) / p_rho_l;
-            let rho_v = vle.vapor().density + (pressure - p_v - p_t_v * delta_t) / p_rho_v;
-
-            if rho_l.is_sign_negative()
-                || rho_v.is_sign_negative()
-                || delta_t.abs() > Temperature::from_reduced(1.0)
-            {
-                // if densities are negative or the temperature step is large use density iteration instead
-                vle = vle
-                    .update_pressure(t_new, pressure)?
-                    .check_trivial_solution()?;
-            } else {
-                // update state
-                vle = Self([
-                    State::new_pure(eos, t_new, rho_v)?,
-                    State::new_pure(eos, t_new, rho_l)?,
-                ]);
-            }
+        let p = pressure.to_reduced();
+        let x = NewtonSolver::new("boiling temperature", MAX_ITER_PURE, TOL_PURE, options)?
+            .step_limit(|_, delta: &mut SVector<f64, 3>| {
+                limit_step(delta, MAX_LNRHO_STEP);
+                if delta[2].abs() > MAX_LNT_STEP {
+                    *delta *= MAX_LNT_STEP / delta[2].abs();
+                }
+            })
+            .report(|x: &SVector<f64, 3>| {
+                vec![
+                    Temperature::from_reduced(x[2].exp()).convert_to(KELVIN),
+                    density_si(x[0]),
+                    density_si(x[1]),
+                ]
+            })
+            .failure(|x, failure| {
+                failure
+                    .temperature(Temperature::from_reduced(x[2].exp()))
+                    .pressure(pressure)
+            })
+            .solve(
+                |x: SVector<DualSVec64<3>, 3>| {
+                    let t = x[2].exp();
+                    let ([r0, r1], p_v) = pure_objective(eos, t, x[0], x[1]);
+                    Ok(SVector::from([r0, r1, p_v * t / p - 1.0]))
+                },
+                SVector::from([
+                    vle.liquid().density.to_reduced().ln(),
+                    vle.vapor().density.to_reduced().ln(),
+                    vle.vapor().temperature.to_reduced().ln(),
+                ]),
+            )?;
+        Self::from_reduced(eos, x[2].exp(), x[0], x[1])
+    }
 
-            // check for convergence
-            let res = delta_t.abs();
-            log_iter!(
-                verbosity,
-                " {:4} | {:14.8e} | {:13.8} | {:12.8} | {:12.8}",
-                i,
-                res,
-                vle.vapor().temperature,
-                vle.liquid().density,
-                vle.vapor().density
-            );
-            if res < vle.vapor().temperature * tol {
-                log_result!(
-                    verbosity,
-                    "PhaseEquilibrium::pure_p: calculation converged in {} step(s)\n",
-                    i
-                );
-                return Ok(vle);
-            }
-        }
-        Err(FeosError::NotConverged("pure_p".to_owned()))
+    /// Phase equilibrium from the reduced temperature and the logarithms
+    /// of the reduced densities of both phases.
+    fn from_reduced(eos: &Arc<E>, t: f64, ln_rho_l: f64, ln_rho_v: f64) -> FeosResult<Self> {
+        let t = Temperature::from_reduced(t);
+        let liquid = State::new_pure(eos, t, Density::from_reduced(ln_rho_l.exp()))?;
+        let vapor = State::new_pure(eos, t, Density::from_reduced(ln_rho_v.exp()))?;
+        Self([vapor, liquid]).check_trivial_solution()
     }
 
     fn init_pure_state(initial_state: &Self, temperature: Temperature) -> FeosResult<Self> {
@@ -338,7 +229,7 @@ impl<E: Residual> PhaseEquilibrium<E, 2> {
                 }
             }
             Err(FeosError::IterationFailed(
-                "new_init_p: could not find proper initial state".to_owned(),
+                "new_init_p: could not find proper initial state".into(),
             ))
         } else {
             unreachable!()
@@ -346,6 +237,57 @@ impl<E: Residual> PhaseEquilibrium<E, 2> {
     }
 }
 
+/// Equality of the chemical potentials and pressures of a pure component
+/// for the logarithms of the reduced densities of both phases, together with
+/// the pressure of the second phase. Pressures are divided by $k_BT$ and the
+/// pressure difference by the density of the first phase.
+fn pure_objective<E: Residual, D: DualNum<f64> + Copy + ScalarOperand>(
+    eos: &Arc<E>,
+    temperature: D,
+    ln_rho_1: D,
+    ln_rho_2: D,
+) -> ([D; 2], D) {
+    // residual Helmholtz energy density and its density derivative
+    let mu_p = |rho: D| {
+        let (a, mu) = first_derivative(
+            |rho| {
+                let state = StateHD::new(
+                    Dual::from_re(temperature),
+                    Dual::from_re(D::one()),
+                    arr1(&[rho]),
+                );
+                eos.residual_helmholtz_energy(&state)
+            },
+            rho,
+        );
+        (mu, rho * mu - a)
+    };
+    let (rho_1, rho_2) = (ln_rho_1.exp(), ln_rho_2.exp());
+    let (mu_1, p_1) = mu_p(rho_1);
+    let (mu_2, p_2) = mu_p(rho_2);
+    let (p_1, p_2) = (rho_1 + p_1, rho_2 + p_2);
+    (
+        [mu_1 + ln_rho_1 - mu_2 - ln_rho_2, (p_1 - p_2) / rho_1],
+        p_2,
+    )
+}
+
+/// Scale the Newton step so that no logarithm of a density changes by
+/// more than `max_step`.
+fn limit_step<D: Dim>(delta: &mut OVector<f64, D>, max_step: f64)
+where
+    DefaultAllocator: Allocator<D>,
+{
+    let max_delta = delta[0].abs().max(delta[1].abs());
+    if max_delta > max_step {
+        *delta *= max_step / max_delta;
+    }
+}
+
+fn density_si(ln_rho: f64) -> f64 {
+    Density::from_reduced(ln_rho.exp()).convert_to(MOL / METER.powi::<P3>())
+}
+
 impl<E: Residual> PhaseEquilibrium<E, 2> {
     /// Calculate the pure component vapor pressures of all
     /// components in the system for the given temperature.
@@ -0,0 +1,486 @@
+//! High-level property interface with string keys, similar to `PropsSI` in CoolProp.
+use crate::SolverOptions;
+use crate::equation_of_state::{IdealGas, Molarweight, Residual};
+use crate::errors::{FeosError, FeosResult};
+use crate::phase_equilibria::{PhaseEquilibrium, TemperatureOrPressure};
+use crate::state::{Contributions, DensityInitialization, State};
+use ndarray::{Array1, arr1};
+use quantity::{
+    Dimensionless, JOULE, KELVIN, KILOGRAM, METER, MOL, Moles, PASCAL, Pressure, SECOND,
+    Temperature,
+};
+use std::str::FromStr;
+use std::sync::Arc;
+use typenum::P3;
+
+/// Properties that can be used as input or output of [props_si].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Key {
+    Temperature,
+    Pressure,
+    Quality,
+    Density,
+    Enthalpy,
+    Entropy,
+    InternalEnergy,
+    GibbsEnergy,
+    IsobaricHeatCapacity,
+    IsochoricHeatCapacity,
+    Compressibility,
+    SpeedOfSound,
+    MolarMass,
+}
+
+/// A property key together with the basis (molar or mass) of its value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Property {
+    key: Key,
+    mass: bool,
+}
+
+impl FromStr for Property {
+    type Err = FeosError;
+
+    fn from_str(s: &str) -> FeosResult<Self> {
+        use Key::*;
+        let (key, mass) = match s {
+            "T" => (Temperature, false),
+            "P" => (Pressure, false),
+            "Q" => (Quality, false),
+            "Dmolar" => (Density, false),
+            "D" | "Dmass" => (Density, true),
+            "Hmolar" => (Enthalpy, false),
+            "H" | "Hmass" => (Enthalpy, true),
+            "Smolar" => (Entropy, false),
+            "S" | "Smass" => (Entropy, true),
+            "Umolar" => (InternalEnergy, false),
+            "U" | "Umass" => (InternalEnergy, true),
+            "Gmolar" => (GibbsEnergy, false),
+            "G" | "Gmass" => (GibbsEnergy, true),
+            "Cpmolar" => (IsobaricHeatCapacity, false),
+            "C" | "Cpmass" => (IsobaricHeatCapacity, true),
+            "Cvmolar" => (IsochoricHeatCapacity, false),
+            "O" | "Cvmass" => (IsochoricHeatCapacity, true),
+            "Z" => (Compressibility, false),
+            "A" | "speed_of_sound" => (SpeedOfSound, false),
+            "M" | "molar_mass" => (MolarMass, false),
+            _ => {
+                return Err(FeosError::Error(format!("Unknown property key '{s}'.")));
+            }
+        };
+        Ok(Self { key, mass })
+    }
+}
+
+impl Property {
+    /// Convert a value from the basis of the property to a molar basis,
+    /// using the molar mass `m` in kg/mol.
+    fn to_molar(self, value: f64, m: f64) -> f64 {
+        match (self.mass, self.key) {
+            (false, _) => value,
+            (true, Key::Density) => value / m,
+            (true, _) => value * m,
+        }
+    }
+
+    /// Convert a value from a molar basis to the basis of the property,
+    /// using the molar mass `m` in kg/mol.
+    fn molar_to_basis(self, value: f64, m: f64) -> f64 {
+        match (self.mass, self.key) {
+            (false, _) => value,
+            (true, Key::Density) => value * m,
+            (true, _) => value / m,
+        }
+    }
+}
+
+/// The phase(s) present at the specified conditions.
+#[expect(clippy::large_enum_variant)]
+enum Phases<E> {
+    Single(State<E>),
+    TwoPhase(PhaseEquilibrium<E, 2>, f64),
+}
+
+/// Calculate a property of a fluid from two arbitrary input properties,
+/// similar to `PropsSI` in CoolProp.
+///
+/// All values are in SI units. Property keys follow the conventions of
+/// CoolProp, i.e., keys without a suffix (`"D"`, `"H"`, `"S"`, `"U"`,
+/// `"G"`, `"C"`, `"O"`) are mass specific:
+///
+/// | key | property | unit |
+/// |-|-|-|
+/// | `T` | temperature | K |
+/// | `P` | pressure | Pa |
+/// | `Q` | molar vapor fraction (-1 in the single phase region) | - |
+/// | `Dmolar`, `D`/`Dmass` | density | mol/m³, kg/m³ |
+/// | `Hmolar`, `H`/`Hmass` | enthalpy | J/mol, J/kg |
+/// | `Smolar`, `S`/`Smass` | entropy | J/mol/K, J/kg/K |
+/// | `Umolar`, `U`/`Umass` | internal energy | J/mol, J/kg |
+/// | `Gmolar`, `G`/`Gmass` | Gibbs energy | J/mol, J/kg |
+/// | `Cpmolar`, `C`/`Cpmass` | isobaric heat capacity | J/mol/K, J/kg/K |
+/// | `Cvmolar`, `O`/`Cvmass` | isochoric heat capacity | J/mol/K, J/kg/K |
+/// | `Z` | compressibility factor | - |
+/// | `A`/`speed_of_sound` | speed of sound | m/s |
+/// | `M`/`molar_mass` | molar mass | kg/mol |
+///
+/// Supported input pairs (in any order) are `TP`, `TQ`, `PQ`, `PH`,
+/// `PS`, `TH`, `TS` and `TD`. `molefracs` can be omitted for pure
+/// components. For mixtures, `TQ` and `PQ` inputs are restricted to
+/// bubble (`Q=0`) and dew (`Q=1`) points and `PH` and `PS` inputs to the
+/// single phase region.
+pub fn props_si<E: Residual + IdealGas + Molarweight>(
+    output: &str,
+    name1: &str,
+    value1: f64,
+    name2: &str,
+    value2: f64,
+    eos: &Arc<E>,
+    molefracs: Option<&Array1<f64>>,
+) -> FeosResult<f64> {
+    let output: Property = output.parse()?;
+    let molefracs = match molefracs {
+        Some(x) => x / x.sum(),
+        None if eos.components() == 1 => arr1(&[1.0]),
+        None => return Err(FeosError::InsufficientInformation),
+    };
+    let molar_mass = (eos.molar_weight() * Dimensionless::new(&molefracs))
+        .sum()
+        .convert_to(KILOGRAM / MOL);
+    let mut inputs = Vec::with_capacity(2);
+    for (name, value) in [(name1, value1), (name2, value2)] {
+        let p: Property = name.parse()?;
+        inputs.push((p.key, p.to_molar(value, molar_mass)));
+    }
+    let input = |key| inputs.iter().find(|(k, _)| *k == key).map(|&(_, v)| v);
+
+    let phases = match (
+        input(Key::Temperature).map(|t| t * KELVIN),
+        input(Key::Pressure).map(|p| p * PASCAL),
+    ) {
+        (Some(t), Some(p)) => tp(eos, t, p, &molefracs)?,
+        (t, p) => {
+            let moles = Moles::from_shape_fn(molefracs.len(), |i| molefracs[i] * MOL);
+            let h = input(Key::Enthalpy).map(|h| h * JOULE / MOL);
+            let s = input(Key::Entropy).map(|s| s * JOULE / MOL / KELVIN);
+            match (t, p, input(Key::Quality), h, s, input(Key::Density)) {
+                (Some(t), _, Some(q), _, _, _) => saturated(eos, t, &molefracs, q)?,
+                (_, Some(p), Some(q), _, _, _) => saturated(eos, p, &molefracs, q)?,
+                (_, Some(p), _, Some(h), _, _) => {
+                    ph_ps(eos, p, &molefracs, Key::Enthalpy, h.convert_to(JOULE / MOL))?
+                }
+                (_, Some(p), _, _, Some(s), _) => ph_ps(
+                    eos,
+                    p,
+                    &molefracs,
+                    Key::Entropy,
+                    s.convert_to(JOULE / MOL / KELVIN),
+                )?,
+                (Some(t), _, _, Some(h), _, _) => Phases::Single(State::new_nth(
+                    eos,
+                    t,
+                    h,
+                    &moles,
+                    DensityInitialization::None,
+                )?),
+                (Some(t), _, _, _, Some(s), _) => Phases::Single(State::new_nts(
+                    eos,
+                    t,
+                    s,
+                    &moles,
+                    DensityInitialization::None,
+                )?),
+                (Some(t), _, _, _, _, Some(d)) => Phases::Single(State::new_nvt(
+                    eos,
+                    t,
+                    MOL / (d * MOL / METER.powi::<P3>()),
+                    &moles,
+                )?),
+                _ => {
+                    return Err(FeosError::UndeterminedState(format!(
+                        "Unsupported input pair '{name1}' and '{name2}'."
+                    )));
+                }
+            }
+        }
+    };
+
+    let value = match &phases {
+        Phases::Single(state) => molar_property(state, output.key)?,
+        Phases::TwoPhase(vle, q) => two_phase_property(vle, *q, output.key)?,
+    };
+    let molar_mass = match &phases {
+        Phases::Single(state) => state.total_molar_weight(),
+        Phases::TwoPhase(vle, q) => {
+            vle.vapor().total_molar_weight() * *q + vle.liquid().total_molar_weight() * (1.0 - q)
+        }
+    };
+    Ok(output.molar_to_basis(value, molar_mass.convert_to(KILOGRAM / MOL)))
+}
+
+fn tp<E: Residual + IdealGas>(
+    eos: &Arc<E>,
+    temperature: Temperature,
+    pressure: Pressure,
+    molefracs: &Array1<f64>,
+) -> FeosResult<Phases<E>> {
+    let moles = Moles::from_shape_fn(molefracs.len(), |i| molefracs[i] * MOL);
+    let state = State::new_npt(
+        eos,
+        temperature,
+        pressure,
+        &moles,
+        DensityInitialization::None,
+    )?;
+    if eos.components() == 1 || state.is_stable(SolverOptions::default())? {
+        return Ok(Phases::Single(state));
+    }
+    let vle = state.tp_flash(None, SolverOptions::default(), None)?;
+    let (v, l) = (vle.vapor().total_moles, vle.liquid().total_moles);
+    let q = (v / (v + l)).into_value();
+    Ok(Phases::TwoPhase(vle, q))
+}
+
+fn saturated<E: Residual, TP: TemperatureOrPressure>(
+    eos: &Arc<E>,
+    temperature_or_pressure: TP,
+    molefracs: &Array1<f64>,
+    quality: f64,
+) -> FeosResult<Phases<E>> {
+    let options = SolverOptions::default();
+    let vle = if eos.components() == 1 {
+        if !(0.0..=1.0).contains(&quality) {
+            return Err(FeosError::Error(format!(
+                "The quality has to be between 0 and 1, got {quality}."
+            )));
+        }
+        PhaseEquilibrium::pure(eos, temperature_or_pressure, None, options)?
+    } else if quality == 0.0 {
+        PhaseEquilibrium::bubble_point(
+            eos,
+            temperature_or_pressure,
+            molefracs,
+            None,
+            None,
+            (options, options),
+        )?
+    } else if quality == 1.0 {
+        PhaseEquilibrium::dew_point(
+            eos,
+            temperature_or_pressure,
+            molefracs,
+            None,
+            None,
+            (options, options),
+        )?
+    } else {
+        return Err(FeosError::Error(String::from(
+            "Only bubble (Q=0) and dew (Q=1) points are supported for mixtures.",
+        )));
+    };
+    Ok(Phases::TwoPhase(vle, quality))
+}
+
+fn ph_ps<E: Residual + IdealGas + Molarweight>(
+    eos: &Arc<E>,
+    pressure: Pressure,
+    molefracs: &Array1<f64>,
+    key: Key,
+    value: f64,
+) -> FeosResult<Phases<E>> {
+    let moles = Moles::from_shape_fn(molefracs.len(), |i| molefracs[i] * MOL);
+    let new_state = |density_initialization, initial_temperature| {
+        if key == Key::Enthalpy {
+            State::new_nph(
+                eos,
+                pressure,
+                value * JOULE / MOL,
+                &moles,
+                density_initialization,
+                initial_temperature,
+            )
+        } else {
+            State::new_nps(
+                eos,
+                pressure,
+                value * JOULE / MOL / KELVIN,
+                &moles,
+                density_initialization,
+                initial_temperature,
+            )
+        }
+    };
+    if eos.components() > 1 {
+        return Ok(Phases::Single(new_state(
+            DensityInitialization::None,
+            None,
+        )?));
+    }
+
+    // check whether the state is in the two-phase region of a pure component
+    let Ok(vle) = PhaseEquilibrium::pure(eos, pressure, None, SolverOptions::default()) else {
+        return Ok(Phases::Single(new_state(
+            DensityInitialization::None,
+            None,
+        )?));
+    };
+    let liquid = molar_property(vle.liquid(), key)?;
+    let vapor = molar_property(vle.vapor(), key)?;
+    let t_sat = Some(vle.vapor().temperature);
+    Ok(if value < liquid {
+        Phases::Single(new_state(DensityInitialization::Liquid, t_sat)?)
+    } else if value > vapor {
+        Phases::Single(new_state(DensityInitialization::Vapor, t_sat)?)
+    } else {
+        let q = (value - liquid) / (vapor - liquid);
+        Phases::TwoPhase(vle, q)
+    })
+}
+
+/// Property of a single phase state on a molar basis.
+fn molar_property<E: Residual + IdealGas + Molarweight>(
+    state: &State<E>,
+    key: Key,
+) -> FeosResult<f64> {
+    let c = Contributions::Total;
+    Ok(match key {
+        Key::Temperature => state.temperature.convert_to(KELVIN),
+        Key::Pressure => state.pressure(c).convert_to(PASCAL),
+        Key::Quality => -1.0,
+        Key::Density => state.density.convert_to(MOL / METER.powi::<P3>()),
+        Key::Enthalpy => state.molar_enthalpy(c).convert_to(JOULE / MOL),
+        Key::Entropy => state.molar_entropy(c).convert_to(JOULE / MOL / KELVIN),
+        Key::InternalEnergy => state.molar_internal_energy(c).convert_to(JOULE / MOL),
+        Key::GibbsEnergy => state.molar_gibbs_energy(c).convert_to(JOULE / MOL),
+        Key::IsobaricHeatCapacity => state
+            .molar_isobaric_heat_capacity(c)
+            .convert_to(JOULE / MOL / KELVIN),
+        Key::IsochoricHeatCapacity => state
+            .molar_isochoric_heat_capacity(c)
+            .convert_to(JOULE / MOL / KELVIN),
+        Key::Compressibility => state.compressibility(c),
+        Key::SpeedOfSound => state.speed_of_sound().convert_to(METER / SECOND),
+        Key::MolarMass => state.total_molar_weight().convert_to(KILOGRAM / MOL),
+    })
+}
+
+/// Property of a two-phase system with molar vapor fraction `q` on a molar basis.
+fn two_phase_property<E: Residual + IdealGas + Molarweight>(
+    vle: &PhaseEquilibrium<E, 2>,
+    q: f64,
+    key: Key,
+) -> FeosResult<f64> {
+    let mix = |key| -> FeosResult<f64> {
+        Ok(q * molar_property(vle.vapor(), key)? + (1.0 - q) * molar_property(vle.liquid(), key)?)
+    };
+    Ok(match key {
+        Key::Temperature | Key::Pressure => molar_property(vle.vapor(), key)?,
+        Key::Quality => q,
+        Key::Density => {
+            let (rho_v, rho_l) = (
+                molar_property(vle.vapor(), key)?,
+                molar_property(vle.liquid(), key)?,
+            );
+            1.0 / (q / rho_v + (1.0 - q) / rho_l)
+        }
+        Key::Compressibility => {
+            let rho = two_phase_property(vle, q, Key::Density)?;
+            let p = molar_property(vle.vapor(), Key::Pressure)?;
+            let t = molar_property(vle.vapor(), Key::Temperature)?;
+            p / (rho * t * quantity::RGAS.convert_to(JOULE / MOL / KELVIN))
+        }
+        Key::Enthalpy | Key::Entropy | Key::InternalEnergy | Key::GibbsEnergy | Key::MolarMass => {
+            mix(key)?
+        }
+        Key::IsobaricHeatCapacity | Key::IsochoricHeatCapacity | Key::SpeedOfSound => {
+            return Err(FeosError::Error(format!(
+                "{key:?} is not defined in the two-phase region."
+            )));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::PengRobinson;
+    use crate::equation_of_state::{Components, EquationOfState};
+    use crate::test_utils::{ConstantHeatCapacity, propane_butane};
+    use approx::assert_relative_eq;
+
+    fn eos(
+        components: usize,
+    ) -> FeosResult<Arc<EquationOfState<ConstantHeatCapacity, PengRobinson>>> {
+        Ok(Arc::new(EquationOfState::new(
+            Arc::new(ConstantHeatCapacity::new(components)),
+            Arc::new(propane_butane()?.subset(&(0..components).collect::<Vec<_>>())),
+        )))
+    }
+
+    #[test]
+    fn pure_component() -> FeosResult<()> {
+        let eos = eos(1)?;
+        let p_sat = props_si("P", "T", 300.0, "Q", 0.5, &eos, None)?;
+        assert_relative_eq!(
+            p_sat,
+            props_si("P", "Q", 1.0, "T", 300.0, &eos, None)?,
+            max_relative = 1e-8
+        );
+
+        // PH-flash into the two-phase region
+        let h_l = props_si("H", "T", 300.0, "Q", 0.0, &eos, None)?;
+        let h_v = props_si("H", "T", 300.0, "Q", 1.0, &eos, None)?;
+        let h = 0.7 * h_v + 0.3 * h_l;
+        assert_relative_eq!(
+            props_si("Q", "P", p_sat, "H", h, &eos, None)?,
+            0.7,
+            max_relative = 1e-6
+        );
+        assert_relative_eq!(
+            props_si("T", "P", p_sat, "H", h, &eos, None)?,
+            300.0,
+            max_relative = 1e-6
+        );
+
+        // single phase PS-flash
+        let s = props_si("Smolar", "T", 350.0, "P", 1e5, &eos, None)?;
+        assert_relative_eq!(
+            props_si("T", "P", 1e5, "Smolar", s, &eos, None)?,
+            350.0,
+            max_relative = 1e-8
+        );
+        assert_eq!(props_si("Q", "T", 350.0, "P", 1e5, &eos, None)?, -1.0);
+        let d = props_si("D", "T", 350.0, "P", 1e5, &eos, None)?;
+        assert_relative_eq!(
+            props_si("P", "T", 350.0, "D", d, &eos, None)?,
+            1e5,
+            max_relative = 1e-8
+        );
+
+        assert!(props_si("X", "T", 300.0, "P", 1e5, &eos, None).is_err());
+        assert!(props_si("C", "T", 300.0, "Q", 0.5, &eos, None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn mixture() -> FeosResult<()> {
+        let eos = eos(2)?;
+        let x = arr1(&[0.5, 0.5]);
+        let p_bubble = props_si("P", "T", 300.0, "Q", 0.0, &eos, Some(&x))?;
+        let p_dew = props_si("P", "T", 300.0, "Q", 1.0, &eos, Some(&x))?;
+        assert!(p_dew < p_bubble);
+        let q = props_si(
+            "Q",
+            "T",
+            300.0,
+            "P",
+            0.5 * (p_bubble + p_dew),
+            &eos,
+            Some(&x),
+        )?;
+        assert!(q > 0.0 && q < 1.0);
+        assert!(props_si("P", "T", 300.0, "Q", 0.5, &eos, Some(&x)).is_err());
+        assert!(props_si("P", "T", 300.0, "Q", 0.0, &eos, None).is_err());
+        Ok(())
+    }
+}
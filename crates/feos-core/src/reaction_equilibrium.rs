@@ -0,0 +1,402 @@
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::observer::{report_iteration, report_result};
+use crate::state::{DensityInitialization, State};
+use crate::{ReferenceSystem, SolverOptions, Verbosity};
+use ndarray::{Array1, Array2, Axis, Zip};
+use num_dual::linalg::{LU, norm};
+use quantity::{BAR, JOULE, KELVIN, MOL, Moles, Pressure, RGAS, Temperature};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const MAX_ITER: usize = 100;
+const TOL: f64 = 1e-10;
+const REFERENCE_TEMPERATURE: f64 = 298.15;
+const INITIAL_EXTENT: f64 = 1e-6;
+const MAX_STEP_FRACTION: f64 = 0.99;
+
+/// Standard formation properties of a substance in the ideal gas state
+/// at 298.15 K and 1 bar.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct FormationRecord {
+    /// Standard Gibbs energy of formation in kJ/mol
+    pub gibbs_energy: f64,
+    /// Standard enthalpy of formation in kJ/mol
+    pub enthalpy: f64,
+    /// Ideal gas heat capacity in J/mol/K
+    #[serde(default)]
+    pub heat_capacity: f64,
+}
+
+impl FormationRecord {
+    /// Create a new `FormationRecord`.
+    pub fn new(gibbs_energy: f64, enthalpy: f64, heat_capacity: f64) -> Self {
+        Self {
+            gibbs_energy,
+            enthalpy,
+            heat_capacity,
+        }
+    }
+
+    /// Standard chemical potential divided by $RT$ at the given temperature
+    /// in K, assuming a constant heat capacity.
+    fn reduced_chemical_potential(&self, temperature: f64) -> f64 {
+        let r = RGAS.convert_to(JOULE / (MOL * KELVIN));
+        let (t, t0) = (temperature, REFERENCE_TEMPERATURE);
+        1e3 * self.gibbs_energy / (r * t0) + 1e3 * self.enthalpy / r * (1.0 / t - 1.0 / t0)
+            - self.heat_capacity / r * ((t / t0).ln() + t0 / t - 1.0)
+    }
+}
+
+/// A set of chemical reactions between the components of a mixture.
+///
+/// The stoichiometric coefficients are negative for reactants and
+/// positive for products. The equilibrium constants are calculated from the
+/// standard formation properties of the components. The temperature
+/// dependence follows from the van 't Hoff equation with constant heat
+/// capacities of reaction.
+#[derive(Clone, Debug)]
+pub struct ChemicalReactions {
+    stoichiometry: Array2<f64>,
+    formation: Vec<FormationRecord>,
+}
+
+impl ChemicalReactions {
+    /// Create a set of reactions from the stoichiometric coefficients
+    /// (one row per reaction and one column per component) and the
+    /// formation properties of all components.
+    pub fn new(stoichiometry: Array2<f64>, formation: Vec<FormationRecord>) -> FeosResult<Self> {
+        if stoichiometry.ncols() != formation.len() {
+            return Err(FeosError::IncompatibleComponents(
+                formation.len(),
+                stoichiometry.ncols(),
+            ));
+        }
+        if stoichiometry.nrows() == 0 {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "at least one reaction has to be specified.",
+            )));
+        }
+        Ok(Self {
+            stoichiometry,
+            formation,
+        })
+    }
+
+    /// The stoichiometric coefficients of all reactions.
+    pub fn stoichiometry(&self) -> &Array2<f64> {
+        &self.stoichiometry
+    }
+
+    /// The standard formation properties of all components.
+    pub fn formation(&self) -> &[FormationRecord] {
+        &self.formation
+    }
+
+    /// The number of reactions.
+    pub fn reactions(&self) -> usize {
+        self.stoichiometry.nrows()
+    }
+
+    /// Logarithms of the equilibrium constants of all reactions with respect
+    /// to a standard pressure of 1 bar.
+    pub fn ln_equilibrium_constants(&self, temperature: Temperature) -> Array1<f64> {
+        let t = temperature.convert_to(KELVIN);
+        let mu0: Array1<f64> = self
+            .formation
+            .iter()
+            .map(|f| f.reduced_chemical_potential(t))
+            .collect();
+        -self.stoichiometry.dot(&mu0)
+    }
+}
+
+/// Simultaneous chemical and phase equilibrium at given temperature
+/// and pressure.
+pub struct ReactionEquilibrium<E> {
+    /// The extents of the reactions.
+    pub extents: Moles<Array1<f64>>,
+    /// The coexisting phases. If two phases are present, the
+    /// liquid phase is the first entry.
+    pub phases: Vec<State<E>>,
+}
+
+impl<E: Residual> ReactionEquilibrium<E> {
+    /// Calculate the chemical equilibrium of the reactions for the given feed.
+    ///
+    /// The Gibbs energy is minimized with respect to the extents of the
+    /// reactions. The fugacities are calculated from the equation of state,
+    /// while the Newton steps use the Hessian of an ideal mixture. If
+    /// `phase_split` is `true`, every iterate is flashed, so that condensing
+    /// products are accounted for.
+    pub fn tp(
+        eos: &Arc<E>,
+        reactions: &ChemicalReactions,
+        temperature: Temperature,
+        pressure: Pressure,
+        feed: &Moles<Array1<f64>>,
+        phase_split: bool,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER, TOL);
+        let n0 = eos.validate_moles(Some(feed))?.to_reduced();
+        if reactions.formation.len() != n0.len() {
+            return Err(FeosError::IncompatibleComponents(
+                n0.len(),
+                reactions.formation.len(),
+            ));
+        }
+        let nu = &reactions.stoichiometry;
+        let ln_k = reactions.ln_equilibrium_constants(temperature);
+        let ln_p = (pressure / BAR).into_value().ln();
+        let delta_nu = nu.sum_axis(Axis(1));
+
+        // start from a composition that contains all reacting components
+        let mut xi = Array1::zeros(nu.nrows());
+        let mut n = n0.clone();
+        for (r, nu_r) in nu.outer_iter().enumerate() {
+            let missing = |sign: f64| {
+                Zip::from(&nu_r)
+                    .and(&n)
+                    .any(|&v, &n| v * sign > 0.0 && n <= 0.0)
+            };
+            let sign = if missing(1.0) {
+                1.0
+            } else if missing(-1.0) {
+                -1.0
+            } else {
+                continue;
+            };
+            // consume at most half of the available reactants
+            let available = Zip::from(&nu_r).and(&n).fold(f64::INFINITY, |acc, &v, &n| {
+                if v * sign < 0.0 {
+                    acc.min(-n / (v * sign))
+                } else {
+                    acc
+                }
+            });
+            xi[r] = sign * (INITIAL_EXTENT * n0.sum()).min(0.5 * available);
+            n.scaled_add(xi[r], &nu_r);
+        }
+        let reacting = |i: usize| nu.column(i).iter().any(|&v| v != 0.0);
+        if (0..n.len()).any(|i| reacting(i) && n[i] <= 0.0) {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "the feed does not contain the reactants of all reactions.",
+            )));
+        }
+        let mut res = f64::INFINITY;
+        if (0..n.len()).any(|i| reacting(i) && n[i] <= 0.0) {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "the feed does not contain the reactants of all reactions.",
+            )));
+        }
+
+        log_iter!(verbosity, " iter |    residual    |  phases ");
+        log_iter!(verbosity, "{:-<32}", "");
+        for i in 1..=max_iter {
+            let (phases, ln_f) = equilibrate(eos, temperature, pressure, &n, phase_split)?;
+
+            // gradient of the reduced Gibbs energy with respect to the extents
+            let g = Array1::from_shape_fn(nu.nrows(), |r| {
+                (0..n.len())
+                    .filter(|&i| nu[[r, i]] != 0.0)
+                    .map(|i| nu[[r, i]] * (ln_f[i] + ln_p))
+                    .sum::<f64>()
+                    - ln_k[r]
+            });
+            res = norm(&g);
+            log_iter!(verbosity, " {:4} | {:14.8e} | {:7}", i, res, phases.len());
+            report_iteration("reaction equilibrium", i, res, xi.as_slice().unwrap_or(&[]));
+            if res < tol {
+                report_result("reaction equilibrium", i, true);
+                log_result!(
+                    verbosity,
+                    "ReactionEquilibrium: calculation converged in {} step(s)\n",
+                    i
+                );
+                return Ok(Self {
+                    extents: Moles::from_reduced(xi),
+                    phases,
+                });
+            }
+
+            // Newton step with the Hessian of an ideal mixture
+            let n_tot = n.sum();
+            let hessian = Array2::from_shape_fn((nu.nrows(), nu.nrows()), |(r, s)| {
+                (0..n.len())
+                    .filter(|&i| reacting(i))
+                    .map(|i| nu[[r, i]] * nu[[s, i]] / n[i])
+                    .sum::<f64>()
+                    - delta_nu[r] * delta_nu[s] / n_tot
+            });
+            let dxi = -LU::new(hessian)?.solve(&g);
+
+            // limit the step so that all mole numbers stay positive
+            let dn = nu.t().dot(&dxi);
+            let mut alpha: f64 = 1.0;
+            Zip::from(&n).and(&dn).for_each(|&n, &dn| {
+                if dn < 0.0 {
+                    alpha = alpha.min(-MAX_STEP_FRACTION * n / dn);
+                }
+            });
+            // the mole numbers are updated directly to resolve traces accurately
+            xi.scaled_add(alpha, &dxi);
+            n.scaled_add(alpha, &dn);
+        }
+        report_result("reaction equilibrium", max_iter, false);
+        Err(FeosError::NotConverged(
+            SolverFailure::new("reaction equilibrium")
+                .iterations(max_iter)
+                .residual(res)
+                .temperature(temperature)
+                .pressure(pressure)
+                .molefracs(&(&n / n.sum())),
+        ))
+    }
+
+    /// The total amount of substance of all phases.
+    pub fn moles(&self) -> Moles<Array1<f64>> {
+        self.phases
+            .iter()
+            .map(|s| s.moles.clone())
+            .reduce(|a, b| a + b)
+            .unwrap()
+    }
+}
+
+/// Determine the phases for the given amounts of substance and
+/// the logarithms of the fugacities divided by the pressure.
+fn equilibrate<E: Residual>(
+    eos: &Arc<E>,
+    temperature: Temperature,
+    pressure: Pressure,
+    moles: &Array1<f64>,
+    phase_split: bool,
+) -> FeosResult<(Vec<State<E>>, Array1<f64>)> {
+    let state = State::new_npt(
+        eos,
+        temperature,
+        pressure,
+        &Moles::from_reduced(moles.clone()),
+        DensityInitialization::None,
+    )?;
+    let ln_f = |s: &State<E>| s.ln_phi() + s.molefracs.mapv(f64::ln);
+    if phase_split && let Ok(split) = state.tp_flash(None, SolverOptions::default(), None) {
+        let ln_f = ln_f(split.vapor());
+        let phases = vec![split.liquid().clone(), split.vapor().clone()];
+        return Ok((phases, ln_f));
+    }
+    Ok((vec![state.clone()], ln_f(&state)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use approx::assert_relative_eq;
+    use ndarray::{arr1, arr2};
+
+    fn ammonia() -> FeosResult<(Arc<PengRobinson>, ChemicalReactions)> {
+        // N2, H2, NH3
+        let parameters = PengRobinsonParameters::new_simple(
+            &[126.2, 33.19, 405.4],
+            &[3.39e6, 1.313e6, 11.33e6],
+            &[0.039, -0.216, 0.257],
+            &[28.014, 2.016, 17.031],
+        )?;
+        let reactions = ChemicalReactions::new(
+            arr2(&[[-1.0, -3.0, 2.0]]),
+            vec![
+                FormationRecord::new(0.0, 0.0, 29.1),
+                FormationRecord::new(0.0, 0.0, 28.8),
+                FormationRecord::new(-16.4, -45.9, 35.1),
+            ],
+        )?;
+        Ok((Arc::new(PengRobinson::new(Arc::new(parameters))), reactions))
+    }
+
+    #[test]
+    fn ammonia_synthesis() -> FeosResult<()> {
+        let (eos, reactions) = ammonia()?;
+        let ln_k = reactions.ln_equilibrium_constants(298.15 * KELVIN);
+        assert_relative_eq!(
+            ln_k[0],
+            2.0 * 16.4e3 / (8.31446261815324 * 298.15),
+            max_relative = 1e-10
+        );
+
+        let feed = Moles::from_reduced(arr1(&[1.0, 3.0, 0.0]));
+        let t = 700.0 * KELVIN;
+        let mut ammonia_fraction = Vec::new();
+        for p in [1.0 * BAR, 300.0 * BAR] {
+            let equilibrium =
+                ReactionEquilibrium::tp(&eos, &reactions, t, p, &feed, false, Default::default())?;
+            assert_eq!(equilibrium.phases.len(), 1);
+            let state = &equilibrium.phases[0];
+            let ln_f = state.ln_phi() + state.molefracs.mapv(f64::ln) + (p / BAR).into_value().ln();
+            assert_relative_eq!(
+                reactions.stoichiometry().dot(&ln_f)[0],
+                reactions.ln_equilibrium_constants(t)[0],
+                max_relative = 1e-8
+            );
+            let xi = equilibrium.extents.to_reduced()[0];
+            assert_relative_eq!(
+                equilibrium.moles().to_reduced(),
+                arr1(&[1.0 - xi, 3.0 - 3.0 * xi, 2.0 * xi]),
+                max_relative = 1e-10
+            );
+            ammonia_fraction.push(state.molefracs[2]);
+        }
+        // Le Chatelier: higher pressures favor ammonia
+        assert!(ammonia_fraction[1] > 10.0 * ammonia_fraction[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn methanol_synthesis() -> FeosResult<()> {
+        // CO, H2, CH3OH, CO2, H2O
+        let parameters = PengRobinsonParameters::new_simple(
+            &[132.9, 33.19, 512.6, 304.1, 647.1],
+            &[3.499e6, 1.313e6, 8.097e6, 7.38e6, 22.064e6],
+            &[0.066, -0.216, 0.565, 0.225, 0.344],
+            &[28.01, 2.016, 32.04, 44.01, 18.015],
+        )?;
+        let eos = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let reactions = ChemicalReactions::new(
+            arr2(&[[-1.0, -2.0, 1.0, 0.0, 0.0], [1.0, -1.0, 0.0, -1.0, 1.0]]),
+            vec![
+                FormationRecord::new(-137.2, -110.5, 29.1),
+                FormationRecord::new(0.0, 0.0, 28.8),
+                FormationRecord::new(-162.3, -201.0, 44.1),
+                FormationRecord::new(-394.4, -393.5, 37.1),
+                FormationRecord::new(-228.6, -241.8, 33.6),
+            ],
+        )?;
+        let feed = Moles::from_reduced(arr1(&[0.2, 2.0, 0.0, 0.1, 0.0]));
+        let (t, p) = (350.0 * KELVIN, 50.0 * BAR);
+        let equilibrium =
+            ReactionEquilibrium::tp(&eos, &reactions, t, p, &feed, true, Default::default())?;
+
+        // methanol and water condense
+        assert_eq!(equilibrium.phases.len(), 2);
+        let ln_k = reactions.ln_equilibrium_constants(t);
+        for state in &equilibrium.phases {
+            let ln_f = state.ln_phi() + state.molefracs.mapv(f64::ln) + (p / BAR).into_value().ln();
+            let g = reactions.stoichiometry().dot(&ln_f) - &ln_k;
+            assert!(norm(&g) < 1e-6);
+        }
+
+        // conservation of the elements
+        let elements = arr2(&[
+            [1.0, 0.0, 1.0, 1.0, 0.0],
+            [0.0, 2.0, 4.0, 0.0, 2.0],
+            [1.0, 0.0, 1.0, 2.0, 1.0],
+        ]);
+        assert_relative_eq!(
+            elements.dot(&equilibrium.moles().to_reduced()),
+            elements.dot(&feed.to_reduced()),
+            max_relative = 1e-10
+        );
+        Ok(())
+    }
+}
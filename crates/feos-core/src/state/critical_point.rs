@@ -1,21 +1,23 @@
 use super::{DensityInitialization, State, StateHD, TPSpec};
 use crate::equation_of_state::Residual;
-use crate::errors::{FeosError, FeosResult};
-use crate::{ReferenceSystem, SolverOptions, TemperatureOrPressure, Verbosity};
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::{NewtonSolver, ReferenceSystem, SolverOptions, TemperatureOrPressure};
 use nalgebra::SVector;
-use ndarray::{arr1, Array1, Array2};
+use ndarray::{Array1, Array2, ScalarOperand, arr1};
 use num_dual::linalg::smallest_ev;
-use num_dual::{
-    first_derivative, try_first_derivative, try_jacobian, Dual, Dual3, Dual64, DualNum, DualSVec64,
-    DualVec, HyperDual,
-};
+use num_dual::{Dual, Dual3, DualNum, DualSVec64, DualVec, HyperDual, first_derivative};
 use num_traits::{One, Zero};
-use quantity::{Density, Moles, Pressure, Temperature, Volume};
+use quantity::{Density, KELVIN, METER, MOL, Moles, Pressure, Temperature, Volume};
 use std::sync::Arc;
+use typenum::P3;
 
 const MAX_ITER_CRIT_POINT: usize = 50;
 const MAX_ITER_CRIT_POINT_BINARY: usize = 200;
 const TOL_CRIT_POINT: f64 = 1e-8;
+#[cfg(feature = "extended_precision")]
+const MAX_ITER_EXTENDED_PRECISION: usize = 10;
+#[cfg(feature = "extended_precision")]
+const TOL_EXTENDED_PRECISION: f64 = 1e-25;
 
 /// # Critical points
 impl<R: Residual> State<R> {
@@ -72,90 +74,66 @@ impl<R: Residual> State<R> {
             Temperature::from_reduced(500.0),
         ];
         if let Some(t) = initial_temperature {
-            return Self::critical_point_hkm(eos, &moles, t, options);
+            return Self::critical_point_hkm(eos, &moles, t, None, options);
         }
+        // report the last iterate of the last attempt that did not converge
+        let mut failure =
+            SolverFailure::new("critical point").molefracs(&(&moles / moles.sum()).into_value());
         for &t in trial_temperatures.iter() {
-            let s = Self::critical_point_hkm(eos, &moles, t, options);
-            if s.is_ok() {
-                return s;
+            match Self::critical_point_hkm(eos, &moles, t, None, options) {
+                Ok(s) => return Ok(s),
+                Err(FeosError::NotConverged(f)) => failure = f,
+                Err(_) => (),
             }
         }
-        Err(FeosError::NotConverged(String::from("Critical point")))
+        Err(FeosError::NotConverged(failure))
     }
 
-    fn critical_point_hkm(
+    pub(crate) fn critical_point_hkm(
         eos: &Arc<R>,
         moles: &Moles<Array1<f64>>,
         initial_temperature: Temperature,
+        initial_density: Option<Density>,
         options: SolverOptions,
     ) -> FeosResult<Self> {
-        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_CRIT_POINT, TOL_CRIT_POINT);
-
-        let mut t = initial_temperature.to_reduced();
+        let t = initial_temperature.to_reduced();
         let max_density = eos.max_density(Some(moles))?.to_reduced();
-        let mut rho = 0.3 * max_density;
+        let rho = initial_density.map_or(0.3 * max_density, |rho| rho.to_reduced());
         let n = moles.to_reduced();
 
-        log_iter!(
-            verbosity,
-            " iter |    residual    |   temperature   |       density        "
-        );
-        log_iter!(verbosity, "{:-<64}", "");
-        log_iter!(
-            verbosity,
-            " {:4} |                | {:13.8} | {:12.8}",
-            0,
-            Temperature::from_reduced(t),
-            Density::from_reduced(rho),
-        );
-
-        for i in 1..=max_iter {
-            // calculate residuals and derivatives w.r.t. temperature and density
-            let res = |x: SVector<DualSVec64<2>, 2>| critical_point_objective(eos, x[0], x[1], &n);
-            let (res, jac) = try_jacobian(res, SVector::from([t, rho]))?;
-
-            // calculate Newton step
-            let delta = jac.lu().solve(&res);
-            let mut delta = delta.ok_or(FeosError::IterationFailed("Critical point".into()))?;
-
-            // reduce step if necessary
-            if delta[0].abs() > 0.25 * t {
-                delta *= 0.25 * t / delta[0].abs()
+        let x = NewtonSolver::new(
+            "critical point",
+            MAX_ITER_CRIT_POINT,
+            TOL_CRIT_POINT,
+            options,
+        )?
+        .step_limit(|x: &SVector<f64, 2>, delta: &mut SVector<f64, 2>| {
+            if delta[0].abs() > 0.25 * x[0] {
+                *delta *= 0.25 * x[0] / delta[0].abs()
             }
             if delta[1].abs() > 0.03 * max_density {
-                delta *= 0.03 * max_density / delta[1].abs()
-            }
-
-            // apply step
-            t -= delta[0];
-            rho -= delta[1];
-            rho = f64::max(rho, 1e-4 * max_density);
-
-            log_iter!(
-                verbosity,
-                " {:4} | {:14.8e} | {:13.8} | {:12.8}",
-                i,
-                res.norm(),
-                Temperature::from_reduced(t),
-                Density::from_reduced(rho),
-            );
-
-            // check convergence
-            if res.norm() < tol {
-                log_result!(
-                    verbosity,
-                    "Critical point calculation converged in {} step(s)\n",
-                    i
-                );
-                return State::new_nvt(
-                    eos,
-                    Temperature::from_reduced(t),
-                    moles.sum() / Density::from_reduced(rho),
-                    moles,
-                );
+                *delta *= 0.03 * max_density / delta[1].abs()
             }
-        }
-        Err(FeosError::NotConverged(String::from("Critical point")))
+        })
+        .projection(|x| x[1] = f64::max(x[1], 1e-4 * max_density))
+        .report(|x| {
+            vec![
+                Temperature::from_reduced(x[0]).convert_to(KELVIN),
+                Density::from_reduced(x[1]).convert_to(MOL / METER.powi::<P3>()),
+            ]
+        })
+        .solve(
+            |x| critical_point_objective(eos, x[0], x[1], &n),
+            SVector::from([t, rho]),
+        )?;
+        #[cfg(feature = "extended_precision")]
+        let x = refine_critical_point(eos, x, &n)?;
+        State::new_nvt(
+            eos,
+            Temperature::from_reduced(x[0]),
+            moles.sum() / Density::from_reduced(x[1]),
+            moles,
+        )
     }
 
     /// Calculate the critical point of a binary system for given temperature.
@@ -165,75 +143,41 @@ impl<R: Residual> State<R> {
         initial_molefracs: Option<[f64; 2]>,
         options: SolverOptions,
     ) -> FeosResult<Self> {
-        let (max_iter, tol, verbosity) =
-            options.unwrap_or(MAX_ITER_CRIT_POINT_BINARY, TOL_CRIT_POINT);
-
         let t = temperature.to_reduced();
         let x = SVector::from(initial_molefracs.unwrap_or([0.5, 0.5]));
         let max_density = eos
             .max_density(Some(&Moles::from_reduced(arr1(&x.data.0[0]))))?
             .to_reduced();
-        let mut rho = x * 0.3 * max_density;
-
-        log_iter!(
-            verbosity,
-            " iter |    residual    |      density 1       |      density 2       "
-        );
-        log_iter!(verbosity, "{:-<69}", "");
-        log_iter!(
-            verbosity,
-            " {:4} |                | {:12.8} | {:12.8}",
-            0,
-            Density::from_reduced(rho[0]),
-            Density::from_reduced(rho[1]),
-        );
-
-        for i in 1..=max_iter {
-            // calculate residuals and derivatives w.r.t. partial densities
-            let res = |rho| critical_point_objective_t(eos, t, rho);
-            let (res, jac) = try_jacobian(res, rho)?;
-
-            // calculate Newton step
-            let delta = jac.lu().solve(&res);
-            let mut delta = delta.ok_or(FeosError::IterationFailed("Critical point".into()))?;
-
-            // reduce step if necessary
+
+        let rho = NewtonSolver::new(
+            "critical point",
+            MAX_ITER_CRIT_POINT_BINARY,
+            TOL_CRIT_POINT,
+            options,
+        )?
+        .step_limit(|_, delta: &mut SVector<f64, 2>| {
             for i in 0..2 {
                 if delta[i].abs() > 0.03 * max_density {
-                    delta *= 0.03 * max_density / delta[i].abs()
+                    *delta *= 0.03 * max_density / delta[i].abs()
                 }
             }
-
-            // apply step
-            rho -= delta;
-            rho[0] = f64::max(rho[0], 1e-4 * max_density);
-            rho[1] = f64::max(rho[1], 1e-4 * max_density);
-
-            log_iter!(
-                verbosity,
-                " {:4} | {:14.8e} | {:12.8} | {:12.8}",
-                i,
-                res.norm(),
-                Density::from_reduced(rho[0]),
-                Density::from_reduced(rho[1]),
-            );
-
-            // check convergence
-            if res.norm() < tol {
-                log_result!(
-                    verbosity,
-                    "Critical point calculation converged in {} step(s)\n",
-                    i
-                );
-                return State::new_nvt(
-                    eos,
-                    Temperature::from_reduced(t),
-                    Volume::from_reduced(1.0),
-                    &Moles::from_reduced(arr1(&rho.data.0[0])),
-                );
-            }
-        }
-        Err(FeosError::NotConverged(String::from("Critical point")))
+        })
+        .projection(|rho| rho.apply(|rho| *rho = f64::max(*rho, 1e-4 * max_density)))
+        .report(|rho| {
+            rho.iter()
+                .map(|&rho| Density::from_reduced(rho).convert_to(MOL / METER.powi::<P3>()))
+                .collect()
+        })
+        .solve(
+            |rho| critical_point_objective_t(eos, t, rho),
+            x * 0.3 * max_density,
+        )?;
+        State::new_nvt(
+            eos,
+            Temperature::from_reduced(t),
+            Volume::from_reduced(1.0),
+            &Moles::from_reduced(arr1(&rho.data.0[0])),
+        )
     }
 
     /// Calculate the critical point of a binary system for given pressure.
@@ -244,87 +188,54 @@ impl<R: Residual> State<R> {
         initial_molefracs: Option<[f64; 2]>,
         options: SolverOptions,
     ) -> FeosResult<Self> {
-        let (max_iter, tol, verbosity) =
-            options.unwrap_or(MAX_ITER_CRIT_POINT_BINARY, TOL_CRIT_POINT);
-
         let p = pressure.to_reduced();
-        let mut t = initial_temperature.map(|t| t.to_reduced()).unwrap_or(300.0);
+        let t = initial_temperature.map(|t| t.to_reduced()).unwrap_or(300.0);
         let x = SVector::from(initial_molefracs.unwrap_or([0.5, 0.5]));
         let max_density = eos
             .max_density(Some(&Moles::from_reduced(arr1(&x.data.0[0]))))?
             .to_reduced();
-        let mut rho = x * 0.3 * max_density;
-
-        log_iter!(
-            verbosity,
-            " iter |    residual    |   temperature   |      density 1       |      density 2       "
-        );
-        log_iter!(verbosity, "{:-<87}", "");
-        log_iter!(
-            verbosity,
-            " {:4} |                | {:13.8} | {:12.8} | {:12.8}",
-            0,
-            Temperature::from_reduced(t),
-            Density::from_reduced(rho[0]),
-            Density::from_reduced(rho[1]),
-        );
-
-        for i in 1..=max_iter {
-            // calculate residuals and derivatives w.r.t. temperature and partial densities
-            let res = |x: SVector<DualSVec64<3>, 3>| {
-                let r = SVector::from([x[1], x[2]]);
-                critical_point_objective_p(eos, p, x[0], r)
-            };
-            let (res, jac) = try_jacobian(res, SVector::from([t, rho[0], rho[1]]))?;
+        let rho = x * 0.3 * max_density;
 
-            // calculate Newton step
-            let delta = jac.lu().solve(&res);
-            let mut delta = delta.ok_or(FeosError::IterationFailed("Critical point".into()))?;
-
-            // reduce step if necessary
-            if delta[0].abs() > 0.25 * t {
-                delta *= 0.25 * t / delta[0].abs()
-            }
-            if delta[1].abs() > 0.03 * max_density {
-                delta *= 0.03 * max_density / delta[1].abs()
-            }
-            if delta[2].abs() > 0.03 * max_density {
-                delta *= 0.03 * max_density / delta[2].abs()
+        let x = NewtonSolver::new(
+            "critical point",
+            MAX_ITER_CRIT_POINT_BINARY,
+            TOL_CRIT_POINT,
+            options,
+        )?
+        .step_limit(|x: &SVector<f64, 3>, delta: &mut SVector<f64, 3>| {
+            if delta[0].abs() > 0.25 * x[0] {
+                *delta *= 0.25 * x[0] / delta[0].abs()
             }
-
-            // apply step
-            t -= delta[0];
-            rho[0] -= delta[1];
-            rho[1] -= delta[2];
-            rho[0] = f64::max(rho[0], 1e-4 * max_density);
-            rho[1] = f64::max(rho[1], 1e-4 * max_density);
-
-            log_iter!(
-                verbosity,
-                " {:4} | {:14.8e} | {:13.8} | {:12.8} | {:12.8}",
-                i,
-                res.norm(),
-                Temperature::from_reduced(t),
-                Density::from_reduced(rho[0]),
-                Density::from_reduced(rho[1]),
-            );
-
-            // check convergence
-            if res.norm() < tol {
-                log_result!(
-                    verbosity,
-                    "Critical point calculation converged in {} step(s)\n",
-                    i
-                );
-                return State::new_nvt(
-                    eos,
-                    Temperature::from_reduced(t),
-                    Volume::from_reduced(1.0),
-                    &Moles::from_reduced(arr1(&rho.data.0[0])),
-                );
+            for i in 1..3 {
+                if delta[i].abs() > 0.03 * max_density {
+                    *delta *= 0.03 * max_density / delta[i].abs()
+                }
             }
-        }
-        Err(FeosError::NotConverged(String::from("Critical point")))
+        })
+        .projection(|x| {
+            x[1] = f64::max(x[1], 1e-4 * max_density);
+            x[2] = f64::max(x[2], 1e-4 * max_density);
+        })
+        .report(|x| {
+            vec![
+                Temperature::from_reduced(x[0]).convert_to(KELVIN),
+                Density::from_reduced(x[1]).convert_to(MOL / METER.powi::<P3>()),
+                Density::from_reduced(x[2]).convert_to(MOL / METER.powi::<P3>()),
+            ]
+        })
+        .solve(
+            |x: SVector<DualSVec64<3>, 3>| {
+                let r = SVector::from([x[1], x[2]]);
+                critical_point_objective_p(eos, p, x[0], r)
+            },
+            SVector::from([t, rho[0], rho[1]]),
+        )?;
+        State::new_nvt(
+            eos,
+            Temperature::from_reduced(x[0]),
+            Volume::from_reduced(1.0),
+            &Moles::from_reduced(arr1(&[x[1], x[2]])),
+        )
     }
 
     pub fn spinodal(
@@ -353,6 +264,15 @@ impl<R: Residual> State<R> {
         Ok([spinodal_vapor, spinodal_liquid])
     }
 
+    /// Smallest eigenvalue of the (scaled) Hessian of the Helmholtz energy
+    /// with respect to the mole numbers. Negative values indicate a state
+    /// inside the spinodal.
+    pub(crate) fn spinodal_eigenvalue(&self) -> FeosResult<f64> {
+        let t = DualSVec64::from(self.temperature.to_reduced());
+        let rho = DualSVec64::from(self.density.to_reduced());
+        Ok(spinodal_objective(&self.eos, t, rho, &self.moles.to_reduced())?.re)
+    }
+
     fn calculate_spinodal(
         eos: &Arc<R>,
         temperature: Temperature,
@@ -360,11 +280,9 @@ impl<R: Residual> State<R> {
         density_initialization: DensityInitialization,
         options: SolverOptions,
     ) -> FeosResult<Self> {
-        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_CRIT_POINT, TOL_CRIT_POINT);
-
         let max_density = eos.max_density(Some(moles))?.to_reduced();
         let t = temperature.to_reduced();
-        let mut rho = match density_initialization {
+        let rho = match density_initialization {
             DensityInitialization::Vapor => 1e-5 * max_density,
             DensityInitialization::Liquid => max_density,
             DensityInitialization::InitialDensity(rho) => rho.to_reduced(),
@@ -372,72 +290,51 @@ impl<R: Residual> State<R> {
         };
         let n = moles.to_reduced();
 
-        log_iter!(verbosity, " iter |    residual    |       density        ");
-        log_iter!(verbosity, "{:-<46}", "");
-        log_iter!(
-            verbosity,
-            " {:4} |                | {:12.8}",
-            0,
-            Density::from_reduced(rho),
-        );
-
-        for i in 1..=max_iter {
-            // calculate residuals and derivative w.r.t. density
-            let (f, df) =
-                try_first_derivative(|rho| spinodal_objective(eos, t.into(), rho, &n), rho)?;
-
-            // calculate Newton step
-            let mut delta = f / df;
-
-            // reduce step if necessary
-            if delta.abs() > 0.03 * max_density {
-                delta *= 0.03 * max_density / delta.abs()
-            }
-
-            // apply step
-            rho -= delta;
-            rho = f64::max(rho, 1e-4 * max_density);
-
-            log_iter!(
-                verbosity,
-                " {:4} | {:14.8e} | {:12.8}",
-                i,
-                f.abs(),
-                Density::from_reduced(rho),
-            );
-
-            // check convergence
-            if f.abs() < tol {
-                log_result!(
-                    verbosity,
-                    "Spinodal calculation converged in {} step(s)\n",
-                    i
-                );
-                return State::new_nvt(
-                    eos,
-                    temperature,
-                    moles.sum() / Density::from_reduced(rho),
-                    moles,
-                );
-            }
-        }
-        Err(FeosError::SuperCritical)
+        let rho = NewtonSolver::new("spinodal", MAX_ITER_CRIT_POINT, TOL_CRIT_POINT, options)?
+            .step_limit(|_, delta: &mut SVector<f64, 1>| {
+                if delta[0].abs() > 0.03 * max_density {
+                    *delta *= 0.03 * max_density / delta[0].abs()
+                }
+            })
+            .projection(|rho| rho[0] = f64::max(rho[0], 1e-4 * max_density))
+            .report(|rho| vec![Density::from_reduced(rho[0]).convert_to(MOL / METER.powi::<P3>())])
+            .solve(
+                |rho| {
+                    Ok(SVector::from([spinodal_objective(
+                        eos,
+                        t.into(),
+                        rho[0],
+                        &n,
+                    )?]))
+                },
+                SVector::from([rho]),
+            )
+            .map_err(|e| match e {
+                FeosError::NotConverged(_) => FeosError::SuperCritical,
+                e => e,
+            })?;
+        State::new_nvt(
+            eos,
+            temperature,
+            moles.sum() / Density::from_reduced(rho[0]),
+            moles,
+        )
     }
 }
 
-fn critical_point_objective<R: Residual>(
+fn critical_point_objective<R: Residual, D: DualNum<f64> + Copy + ScalarOperand>(
     eos: &Arc<R>,
-    temperature: DualSVec64<2>,
-    density: DualSVec64<2>,
+    temperature: D,
+    density: D,
     moles: &Array1<f64>,
-) -> FeosResult<SVector<DualSVec64<2>, 2>> {
+) -> FeosResult<SVector<D, 2>> {
     // calculate second partial derivatives w.r.t. moles
     let t = HyperDual::from_re(temperature);
     let v = HyperDual::from_re(density.recip() * moles.sum());
     let qij = Array2::from_shape_fn((eos.components(), eos.components()), |(i, j)| {
         let mut m = moles.mapv(HyperDual::from);
-        m[i].eps1 = DualSVec64::one();
-        m[j].eps2 = DualSVec64::one();
+        m[i].eps1 = D::one();
+        m[j].eps2 = D::one();
         let state = StateHD::new(t, v, m);
         eos.residual_helmholtz_energy(&state).eps1eps2 * (moles[i] * moles[j]).sqrt()
             + kronecker(i, j)
@@ -449,10 +346,10 @@ fn critical_point_objective<R: Residual>(
     // evaluate third partial derivative w.r.t. s
     let moles_hd = Array1::from_shape_fn(eos.components(), |i| {
         Dual3::new(
-            DualSVec64::from(moles[i]),
+            D::from(moles[i]),
             evec[i] * moles[i].sqrt(),
-            DualSVec64::zero(),
-            DualSVec64::zero(),
+            D::zero(),
+            D::zero(),
         )
     });
     let state_s = StateHD::new(
@@ -465,6 +362,58 @@ fn critical_point_objective<R: Residual>(
     Ok(SVector::from([eval, (res + ig).v3]))
 }
 
+/// Refine a critical point in double-double precision.
+///
+/// The objective function of the critical point contains third derivatives
+/// of the Helmholtz energy that are affected by cancellation, which limits
+/// the accuracy of the Newton iteration in `f64`. Starting from the `f64`
+/// solution, a few Newton steps with the Helmholtz energy evaluated in
+/// [DoubleDouble](crate::DoubleDouble) precision determine the critical
+/// temperature and density to full `f64` precision.
+#[cfg(feature = "extended_precision")]
+fn refine_critical_point<R: Residual>(
+    eos: &Arc<R>,
+    x: SVector<f64, 2>,
+    moles: &Array1<f64>,
+) -> FeosResult<SVector<f64, 2>> {
+    use crate::DoubleDouble;
+    use crate::errors::SolverFailure;
+    use nalgebra::Const;
+    use num_dual::try_jacobian;
+    use num_traits::Float;
+
+    let mut x = x.map(DoubleDouble::from);
+    let mut residual = f64::INFINITY;
+    for _ in 0..MAX_ITER_EXTENDED_PRECISION {
+        let (f, jac) = try_jacobian(
+            |x: SVector<DualVec<DoubleDouble, f64, Const<2>>, 2>| {
+                critical_point_objective(eos, x[0], x[1], moles)
+            },
+            x,
+        )?;
+        residual = f.iter().map(|f| f.hi().abs()).fold(0.0, f64::max);
+        let det = jac[(0, 0)] * jac[(1, 1)] - jac[(0, 1)] * jac[(1, 0)];
+        let delta = SVector::from([
+            (jac[(1, 1)] * f[0] - jac[(0, 1)] * f[1]) / det,
+            (jac[(0, 0)] * f[1] - jac[(1, 0)] * f[0]) / det,
+        ]);
+        x -= delta;
+        if !x.iter().all(|x| x.is_finite()) {
+            break;
+        }
+        if (0..2).all(|i| (delta[i] / x[i]).hi().abs() < TOL_EXTENDED_PRECISION) {
+            return Ok(x.map(|x| x.hi()));
+        }
+    }
+    Err(FeosError::NotConverged(
+        SolverFailure::new("critical point")
+            .iterations(MAX_ITER_EXTENDED_PRECISION)
+            .residual(residual)
+            .temperature(Temperature::from_reduced(x[0].hi()))
+            .molefracs(&(moles / moles.sum())),
+    ))
+}
+
 fn critical_point_objective_t<R: Residual>(
     eos: &Arc<R>,
     temperature: f64,
@@ -548,17 +497,17 @@ fn critical_point_objective_p<R: Residual>(
 
 fn spinodal_objective<R: Residual>(
     eos: &Arc<R>,
-    temperature: Dual64,
-    density: Dual64,
+    temperature: DualSVec64<1>,
+    density: DualSVec64<1>,
     moles: &Array1<f64>,
-) -> FeosResult<Dual64> {
+) -> FeosResult<DualSVec64<1>> {
     // calculate second partial derivatives w.r.t. moles
     let t = HyperDual::from_re(temperature);
     let v = HyperDual::from_re(density.recip() * moles.sum());
     let qij = Array2::from_shape_fn((eos.components(), eos.components()), |(i, j)| {
         let mut m = moles.mapv(HyperDual::from);
-        m[i].eps1 = Dual64::one();
-        m[j].eps2 = Dual64::one();
+        m[i].eps1 = DualSVec64::one();
+        m[j].eps2 = DualSVec64::one();
         let state = StateHD::new(t, v, m);
         eos.residual_helmholtz_energy(&state).eps1eps2 * (moles[i] * moles[j]).sqrt()
             + kronecker(i, j)
@@ -571,9 +520,40 @@ fn spinodal_objective<R: Residual>(
 }
 
 fn kronecker(i: usize, j: usize) -> f64 {
-    if i == j {
-        1.0
-    } else {
-        0.0
+    if i == j { 1.0 } else { 0.0 }
+}
+
+#[cfg(all(test, feature = "extended_precision"))]
+mod tests {
+    use super::*;
+    use crate::DoubleDouble;
+    use crate::cubic::PengRobinson;
+    use crate::test_utils::propane_butane_parameters;
+    use ndarray::arr1;
+
+    #[test]
+    fn extended_precision_critical_point() -> FeosResult<()> {
+        let parameters = propane_butane_parameters()?;
+        let eos_dd = PengRobinson::new(Arc::new(parameters.cast::<DoubleDouble>()));
+        let eos = Arc::new(PengRobinson::new(Arc::new(parameters)));
+
+        // the pure component critical points are the correctly rounded
+        // solutions of the Peng-Robinson equation of state in double-double
+        // precision
+        let cp = State::critical_point_pure(&eos, None, Default::default())?;
+        for (i, cp) in cp.iter().enumerate() {
+            let [tc, _, rhoc] = eos_dd.critical_point(i)?;
+            assert_eq!(cp.temperature.to_reduced(), tc.hi());
+            assert_eq!(cp.density.to_reduced(), rhoc.hi());
+        }
+
+        // the critical conditions of the mixture are fulfilled in double-double precision
+        let moles = arr1(&[0.4, 0.6]) * MOL;
+        let cp = State::critical_point(&eos, Some(&moles), None, Default::default())?;
+        let t = DoubleDouble::from(cp.temperature.to_reduced());
+        let rho = DoubleDouble::from(cp.density.to_reduced());
+        let f = critical_point_objective(&eos, t, rho, &moles.to_reduced())?;
+        assert!(f.iter().all(|f| f.hi().abs() < 1e-12));
+        Ok(())
     }
 }
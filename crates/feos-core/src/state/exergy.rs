@@ -0,0 +1,201 @@
+use super::{Contributions, DensityInitialization, State};
+use crate::ReferenceSystem;
+use crate::equation_of_state::{IdealGas, Residual};
+use crate::errors::{FeosError, FeosResult};
+use ndarray::Array1;
+use quantity::{Energy, MolarEnergy, Moles, Pressure, RGAS, Temperature};
+use std::sync::Arc;
+
+/// Dead state (reference environment) of an exergy analysis.
+///
+/// The dead state is defined by the temperature $T_0$, the pressure $p_0$
+/// and the composition of the environment.
+pub struct DeadState<E> {
+    /// Temperature $T_0$ of the environment
+    pub temperature: Temperature,
+    /// Pressure $p_0$ of the environment
+    pub pressure: Pressure,
+    /// State of the environment at $T_0$ and $p_0$
+    pub environment: State<E>,
+}
+
+impl<E: Residual> DeadState<E> {
+    /// Create a dead state from the temperature, pressure and composition
+    /// of the environment.
+    pub fn new(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressure: Pressure,
+        molefracs: &Array1<f64>,
+    ) -> FeosResult<Self> {
+        let moles = Moles::from_reduced(molefracs / molefracs.sum());
+        let environment = State::new_npt(
+            eos,
+            temperature,
+            pressure,
+            &moles,
+            DensityInitialization::None,
+        )?;
+        Ok(Self {
+            temperature,
+            pressure,
+            environment,
+        })
+    }
+
+    /// State with the composition of `state` in thermal and mechanical
+    /// equilibrium with the environment (restricted dead state).
+    fn restricted(&self, state: &State<E>) -> FeosResult<State<E>> {
+        State::new_npt(
+            &state.eos,
+            self.temperature,
+            self.pressure,
+            &state.moles,
+            DensityInitialization::None,
+        )
+    }
+}
+
+/// # Exergy
+impl<E: Residual> State<E> {
+    /// Molar chemical exergy
+    /// $e^\mathrm{ch}=RT_0\sum_ix_i\ln\frac{f_i(T_0,p_0,x)}{f_i(T_0,p_0,x^0)}$
+    ///
+    /// The chemical exergy is the work that can be obtained by bringing the
+    /// state from the restricted dead state (the composition of the state
+    /// at $T_0$ and $p_0$) into equilibrium with the environment of
+    /// composition $x^0$. The fugacities account for the non-ideal
+    /// mixing of the state and the environment.
+    ///
+    /// Returns an error if a component of the state is not present in the
+    /// environment.
+    pub fn molar_chemical_exergy(&self, dead_state: &DeadState<E>) -> FeosResult<MolarEnergy> {
+        let restricted = dead_state.restricted(self)?;
+        let environment = &dead_state.environment;
+        let ln_f = |s: &State<E>| s.ln_phi() + s.molefracs.mapv(f64::ln);
+        let (ln_f, ln_f0) = (ln_f(&restricted), ln_f(environment));
+        let mut sum = 0.0;
+        for (i, &x) in self.molefracs.iter().enumerate() {
+            if x == 0.0 {
+                continue;
+            }
+            if environment.molefracs[i] <= 0.0 {
+                return Err(FeosError::InvalidState(
+                    "chemical exergy".into(),
+                    format!("environment mole fraction of component {i}"),
+                    environment.molefracs[i],
+                ));
+            }
+            sum += x * (ln_f[i] - ln_f0[i]);
+        }
+        Ok(RGAS * dead_state.temperature * sum)
+    }
+}
+
+impl<E: Residual + IdealGas> State<E> {
+    /// Molar physical (thermo-mechanical) exergy
+    /// $e^\mathrm{ph}=h-h_0-T_0\left(s-s_0\right)$
+    ///
+    /// The enthalpy $h_0$ and entropy $s_0$ are evaluated for the
+    /// composition of the state at the temperature and pressure of the
+    /// dead state.
+    pub fn molar_physical_exergy(&self, dead_state: &DeadState<E>) -> FeosResult<MolarEnergy> {
+        let restricted = dead_state.restricted(self)?;
+        let c = Contributions::Total;
+        Ok(self.molar_enthalpy(c)
+            - restricted.molar_enthalpy(c)
+            - dead_state.temperature * (self.molar_entropy(c) - restricted.molar_entropy(c)))
+    }
+
+    /// Molar exergy (flow availability), i.e., the sum of the
+    /// [physical](State::molar_physical_exergy) and the
+    /// [chemical](State::molar_chemical_exergy) exergy.
+    pub fn molar_exergy(&self, dead_state: &DeadState<E>) -> FeosResult<MolarEnergy> {
+        Ok(self.molar_physical_exergy(dead_state)? + self.molar_chemical_exergy(dead_state)?)
+    }
+
+    /// Exergy (flow availability) of the total amount of substance.
+    pub fn exergy(&self, dead_state: &DeadState<E>) -> FeosResult<Energy> {
+        Ok(self.molar_exergy(dead_state)? * self.total_moles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use crate::equation_of_state::EquationOfState;
+    use crate::test_utils::ConstantHeatCapacity;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{BAR, JOULE, KELVIN, MOL};
+
+    #[test]
+    fn exergy() -> FeosResult<()> {
+        // nitrogen, oxygen, carbon dioxide
+        let parameters = PengRobinsonParameters::new_simple(
+            &[126.2, 154.58, 304.1],
+            &[3.39e6, 5.043e6, 7.38e6],
+            &[0.039, 0.022, 0.225],
+            &[28.014, 32.0, 44.01],
+        )?;
+        let eos = Arc::new(EquationOfState::new(
+            Arc::new(ConstantHeatCapacity::new(3)),
+            Arc::new(PengRobinson::new(Arc::new(parameters))),
+        ));
+        let (t0, p0) = (298.15 * KELVIN, BAR);
+        let air = arr1(&[0.7898, 0.21, 0.0002]);
+        let dead_state = DeadState::new(&eos, t0, p0, &air)?;
+        let state = |t, p, x: &Array1<f64>| {
+            let moles = Moles::from_reduced(x.clone());
+            State::new_npt(&eos, t, p, &moles, DensityInitialization::None)
+        };
+
+        // the exergy of the environment vanishes
+        let environment = state(t0, p0, &air)?;
+        assert_relative_eq!(
+            environment
+                .molar_exergy(&dead_state)?
+                .convert_to(JOULE / MOL),
+            0.0,
+            epsilon = 1e-8
+        );
+
+        // the physical exergy is positive for hot or compressed states
+        let hot = state(500.0 * KELVIN, p0, &air)?;
+        let compressed = state(t0, 10.0 * BAR, &air)?;
+        assert!(hot.molar_physical_exergy(&dead_state)? > 0.0 * JOULE / MOL);
+        assert!(compressed.molar_physical_exergy(&dead_state)? > 0.0 * JOULE / MOL);
+        assert_relative_eq!(
+            hot.molar_chemical_exergy(&dead_state)?
+                .convert_to(JOULE / MOL),
+            0.0,
+            epsilon = 1e-8
+        );
+
+        // the chemical exergy of pure CO2 approaches the ideal gas mixing term
+        let co2 = state(t0, p0, &arr1(&[0.0, 0.0, 1.0]))?;
+        let e_ch = co2.molar_chemical_exergy(&dead_state)?;
+        let ideal = -RGAS * t0 * 0.0002f64.ln();
+        assert_relative_eq!(e_ch, ideal, max_relative = 1e-2);
+        assert_relative_eq!(
+            co2.exergy(&dead_state)?,
+            co2.molar_exergy(&dead_state)? * co2.total_moles
+        );
+
+        // the physical exergy is the minimum work of an isothermal compression
+        let p = 5.0 * BAR;
+        let compressed = state(t0, p, &air)?;
+        let isothermal = RGAS * t0 * (p / p0).into_value().ln();
+        assert_relative_eq!(
+            compressed.molar_physical_exergy(&dead_state)?,
+            isothermal,
+            max_relative = 1e-2
+        );
+
+        // components that are not part of the environment
+        let dead_state = DeadState::new(&eos, t0, p0, &arr1(&[0.79, 0.21, 0.0]))?;
+        assert!(co2.molar_chemical_exergy(&dead_state).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,625 @@
+use super::{Contributions, State};
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult};
+use quantity::{JOULE, KELVIN, METER, MOL, PASCAL, RGAS};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use typenum::P3;
+
+/// Scalar quantities of a state that can be used in a [StateExpression].
+///
+/// All values are in SI units. Caloric quantities are residual properties.
+const VARIABLES: [(&str, &str, &str); 17] = [
+    ("T", "K", "temperature"),
+    ("p", "Pa", "pressure"),
+    ("rho", "mol/m³", "molar density"),
+    ("v", "m³/mol", "molar volume"),
+    ("z", "", "compressibility factor"),
+    (
+        "dp_dT",
+        "Pa/K",
+        "derivative of the pressure w.r.t. temperature at constant volume",
+    ),
+    (
+        "dp_drho",
+        "J/mol",
+        "derivative of the pressure w.r.t. density at constant temperature",
+    ),
+    (
+        "dp_dv",
+        "Pa mol/m³",
+        "derivative of the pressure w.r.t. molar volume at constant temperature",
+    ),
+    ("kappa_T", "1/Pa", "isothermal compressibility"),
+    ("alpha_p", "1/K", "isobaric thermal expansion coefficient"),
+    ("a_res", "J/mol", "residual molar Helmholtz energy"),
+    ("u_res", "J/mol", "residual molar internal energy"),
+    ("h_res", "J/mol", "residual molar enthalpy"),
+    ("s_res", "J/mol/K", "residual molar entropy"),
+    ("g_res", "J/mol", "residual molar Gibbs energy"),
+    (
+        "cv_res",
+        "J/mol/K",
+        "residual molar isochoric heat capacity",
+    ),
+    ("cp_res", "J/mol/K", "residual molar isobaric heat capacity"),
+];
+
+/// Component specific quantities that are used as `name[i]`.
+const INDEXED_VARIABLES: [(&str, &str, &str); 2] = [
+    ("x", "", "mole fraction"),
+    ("ln_phi", "", "logarithm of the fugacity coefficient"),
+];
+
+/// Named constants: the ideal gas constant `R` in J/mol/K and `pi`.
+fn constant(name: &str) -> Option<f64> {
+    match name {
+        "R" => Some(RGAS.convert_to(JOULE / MOL / KELVIN)),
+        "pi" => Some(std::f64::consts::PI),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Function {
+    Sqrt,
+    Exp,
+    Ln,
+    Log10,
+    Abs,
+    Pow,
+    Min,
+    Max,
+}
+
+impl Function {
+    const ALL: [(&'static str, Self, usize); 8] = [
+        ("sqrt", Self::Sqrt, 1),
+        ("exp", Self::Exp, 1),
+        ("ln", Self::Ln, 1),
+        ("log10", Self::Log10, 1),
+        ("abs", Self::Abs, 1),
+        ("pow", Self::Pow, 2),
+        ("min", Self::Min, 2),
+        ("max", Self::Max, 2),
+    ];
+
+    fn apply(self, args: &[f64]) -> f64 {
+        match self {
+            Self::Sqrt => args[0].sqrt(),
+            Self::Exp => args[0].exp(),
+            Self::Ln => args[0].ln(),
+            Self::Log10 => args[0].log10(),
+            Self::Abs => args[0].abs(),
+            Self::Pow => args[0].powf(args[1]),
+            Self::Min => args[0].min(args[1]),
+            Self::Max => args[0].max(args[1]),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Number(f64),
+    Variable(&'static str),
+    Indexed(&'static str, usize),
+    Neg(Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Symbol(char),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut end = start;
+            let mut previous = c;
+            while let Some(&(i, c)) = chars.peek() {
+                let exponent_sign = (c == '+' || c == '-') && matches!(previous, 'e' | 'E');
+                if c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent_sign {
+                    end = i + c.len_utf8();
+                    previous = c;
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let number = &input[start..end];
+            let value = number
+                .parse()
+                .map_err(|_| format!("invalid number '{number}'"))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' {
+                    end = i + c.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Name(input[start..end].to_string()));
+        } else if "+-*/^(),[]".contains(c) {
+            tokens.push(Token::Symbol(c));
+            chars.next();
+        } else {
+            return Err(format!("unexpected character '{c}'"));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Maximum depth of the expression tree.
+const MAX_DEPTH: usize = 64;
+
+/// Recursive descent parser for the grammar
+///
+/// ```text
+/// sum     = product { ("+" | "-") product }
+/// product = unary { ("*" | "/") unary }
+/// unary   = "-" unary | power
+/// power   = atom [ "^" unary ]
+/// atom    = number | name | name "[" integer "]" | name "(" sum { "," sum } ")" | "(" sum ")"
+/// ```
+///
+/// The depth of the expression tree is limited, so that untrusted input
+/// cannot overflow the stack during parsing or evaluation.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), String> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err(format!("expected '{symbol}'"))
+        }
+    }
+
+    /// Increase the depth of the expression tree.
+    fn nest(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(format!(
+                "expression is nested deeper than {MAX_DEPTH} levels"
+            ));
+        }
+        Ok(())
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let depth = self.depth;
+        let mut lhs = self.product()?;
+        while let Some(&Token::Symbol(op @ ('+' | '-'))) = self.peek() {
+            self.position += 1;
+            self.nest()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.product()?));
+        }
+        self.depth = depth;
+        Ok(lhs)
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        let depth = self.depth;
+        let mut lhs = self.unary()?;
+        while let Some(&Token::Symbol(op @ ('*' | '/'))) = self.peek() {
+            self.position += 1;
+            self.nest()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+        self.depth = depth;
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat('-') {
+            self.nest()?;
+            let expr = Expr::Neg(Box::new(self.unary()?));
+            self.depth -= 1;
+            Ok(expr)
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> Result<Expr, String> {
+        let base = self.atom()?;
+        if self.eat('^') {
+            self.nest()?;
+            let expr = Expr::Binary('^', Box::new(base), Box::new(self.unary()?));
+            self.depth -= 1;
+            Ok(expr)
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expr::Number(value)),
+            Some(Token::Symbol('(')) => {
+                self.nest()?;
+                let expr = self.sum()?;
+                self.expect(')')?;
+                self.depth -= 1;
+                Ok(expr)
+            }
+            Some(Token::Name(name)) => {
+                if self.eat('(') {
+                    self.nest()?;
+                    let &(_, function, arity) =
+                        Function::ALL
+                            .iter()
+                            .find(|(n, _, _)| *n == name)
+                            .ok_or_else(|| format!("unknown function '{name}'"))?;
+                    let mut args = vec![self.sum()?];
+                    while self.eat(',') {
+                        args.push(self.sum()?);
+                    }
+                    self.expect(')')?;
+                    self.depth -= 1;
+                    if args.len() != arity {
+                        return Err(format!("'{name}' expects {arity} argument(s)"));
+                    }
+                    Ok(Expr::Call(function, args))
+                } else if self.eat('[') {
+                    let &(name, _, _) = INDEXED_VARIABLES
+                        .iter()
+                        .find(|(n, _, _)| *n == name)
+                        .ok_or_else(|| format!("unknown component specific quantity '{name}'"))?;
+                    let index = match self.next() {
+                        Some(Token::Number(i)) if i >= 0.0 && i.fract() == 0.0 => i as usize,
+                        _ => return Err(format!("expected a component index after '{name}['")),
+                    };
+                    self.expect(']')?;
+                    Ok(Expr::Indexed(name, index))
+                } else if let Some(value) = constant(&name) {
+                    Ok(Expr::Number(value))
+                } else {
+                    VARIABLES
+                        .iter()
+                        .find(|(n, _, _)| *n == name)
+                        .map(|&(name, _, _)| Expr::Variable(name))
+                        .ok_or_else(|| format!("unknown quantity '{name}'"))
+                }
+            }
+            Some(Token::Symbol(c)) => Err(format!("unexpected '{c}'")),
+            None => Err("unexpected end of the expression".into()),
+        }
+    }
+}
+
+/// User defined quantity that is calculated from the properties of a state.
+///
+/// Expressions are parsed from strings that combine numbers, the names of
+/// state quantities (in SI units), constants (`R`, `pi`) and functions
+/// (`sqrt`, `exp`, `ln`, `log10`, `abs`, `pow`, `min`, `max`) with the
+/// operators `+`, `-`, `*`, `/` and `^`. Component specific quantities are
+/// indexed with brackets, e.g., `x[0]`. See [StateExpression::variables]
+/// for the available quantities.
+///
+/// # Example
+/// ```
+/// # use feos_core::{StateExpression, FeosResult};
+/// // (dp/dT)_v / rho
+/// let expression: StateExpression = "dp_dT / rho".parse()?;
+/// // residual enthalpy relative to RT
+/// let expression = StateExpression::new("h_res / (R * T)")?;
+/// # Ok::<(), feos_core::FeosError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct StateExpression {
+    source: String,
+    expr: Expr,
+}
+
+impl StateExpression {
+    /// Parse an expression.
+    pub fn new(expression: &str) -> FeosResult<Self> {
+        let invalid = |message: String| {
+            FeosError::IncompatibleParameters(format!(
+                "invalid expression '{expression}': {message}."
+            ))
+        };
+        let tokens = tokenize(expression).map_err(invalid)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            depth: 0,
+        };
+        let expr = parser.sum().map_err(invalid)?;
+        if let Some(token) = parser.peek() {
+            return Err(invalid(format!("unexpected {token:?}")));
+        }
+        Ok(Self {
+            source: expression.to_string(),
+            expr,
+        })
+    }
+
+    /// Names, units and descriptions of all quantities that can be used in
+    /// expressions. Component specific quantities are listed as `name[i]`.
+    pub fn variables() -> Vec<(String, &'static str, &'static str)> {
+        VARIABLES
+            .iter()
+            .map(|&(name, unit, description)| (name.to_string(), unit, description))
+            .chain(
+                INDEXED_VARIABLES
+                    .iter()
+                    .map(|&(name, unit, description)| (format!("{name}[i]"), unit, description)),
+            )
+            .collect()
+    }
+
+    /// Evaluate the expression for a state.
+    pub fn evaluate<E: Residual>(&self, state: &State<E>) -> FeosResult<f64> {
+        evaluate(&self.expr, state)
+    }
+}
+
+fn variable<E: Residual>(name: &str, state: &State<E>) -> f64 {
+    let molar_energy = JOULE / MOL;
+    let molar_entropy = JOULE / MOL / KELVIN;
+    let density = MOL / METER.powi::<P3>();
+    let dp_dt = || {
+        state
+            .dp_dt(Contributions::Total)
+            .convert_to(PASCAL / KELVIN)
+    };
+    let dp_drho = || {
+        state
+            .dp_drho(Contributions::Total)
+            .convert_to(PASCAL / density)
+    };
+    let rho = || state.density.convert_to(density);
+    match name {
+        "T" => state.temperature.convert_to(KELVIN),
+        "p" => state.pressure(Contributions::Total).convert_to(PASCAL),
+        "rho" => rho(),
+        "v" => 1.0 / rho(),
+        "z" => state.compressibility(Contributions::Total),
+        "dp_dT" => dp_dt(),
+        "dp_drho" => dp_drho(),
+        "dp_dv" => -dp_drho() * rho().powi(2),
+        "kappa_T" => 1.0 / (rho() * dp_drho()),
+        "alpha_p" => dp_dt() / (rho() * dp_drho()),
+        "a_res" => state
+            .residual_molar_helmholtz_energy()
+            .convert_to(molar_energy),
+        "u_res" => state
+            .residual_molar_internal_energy()
+            .convert_to(molar_energy),
+        "h_res" => state.residual_molar_enthalpy().convert_to(molar_energy),
+        "s_res" => state.residual_molar_entropy().convert_to(molar_entropy),
+        "g_res" => state.residual_molar_gibbs_energy().convert_to(molar_energy),
+        "cv_res" => state
+            .residual_molar_isochoric_heat_capacity()
+            .convert_to(molar_entropy),
+        "cp_res" => state
+            .residual_molar_isobaric_heat_capacity()
+            .convert_to(molar_entropy),
+        _ => unreachable!("variables are checked during parsing"),
+    }
+}
+
+fn evaluate<E: Residual>(expr: &Expr, state: &State<E>) -> FeosResult<f64> {
+    Ok(match expr {
+        Expr::Number(value) => *value,
+        Expr::Variable(name) => variable(name, state),
+        Expr::Indexed(name, i) => {
+            let n = state.eos.components();
+            if *i >= n {
+                return Err(FeosError::IncompatibleParameters(format!(
+                    "component index {i} of '{name}' is out of bounds for {n} components."
+                )));
+            }
+            match *name {
+                "x" => state.molefracs[*i],
+                _ => state.ln_phi()[*i],
+            }
+        }
+        Expr::Neg(expr) => -evaluate(expr, state)?,
+        Expr::Binary(op, lhs, rhs) => {
+            let (lhs, rhs) = (evaluate(lhs, state)?, evaluate(rhs, state)?);
+            match op {
+                '+' => lhs + rhs,
+                '-' => lhs - rhs,
+                '*' => lhs * rhs,
+                '/' => lhs / rhs,
+                _ => lhs.powf(rhs),
+            }
+        }
+        Expr::Call(function, args) => {
+            let args = args
+                .iter()
+                .map(|arg| evaluate(arg, state))
+                .collect::<FeosResult<Vec<_>>>()?;
+            function.apply(&args)
+        }
+    })
+}
+
+impl FromStr for StateExpression {
+    type Err = FeosError;
+
+    fn from_str(s: &str) -> FeosResult<Self> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<String> for StateExpression {
+    type Error = FeosError;
+
+    fn try_from(value: String) -> FeosResult<Self> {
+        Self::new(&value)
+    }
+}
+
+impl From<StateExpression> for String {
+    fn from(value: StateExpression) -> Self {
+        value.source
+    }
+}
+
+impl fmt::Display for StateExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use crate::test_utils::propane_butane;
+    use crate::{DensityInitialization, SolverOptions};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::BAR;
+    use std::sync::Arc;
+
+    #[test]
+    fn parse() {
+        let e = |s: &str| StateExpression::new(s).map(|e| e.expr);
+        let n = |v| Box::new(Expr::Number(v));
+        assert_eq!(
+            e("1 + 2 * 3").ok(),
+            Some(Expr::Binary(
+                '+',
+                n(1.0),
+                Box::new(Expr::Binary('*', n(2.0), n(3.0)))
+            ))
+        );
+        assert_eq!(
+            e("-2^2").ok(),
+            Some(Expr::Neg(Box::new(Expr::Binary('^', n(2.0), n(2.0)))))
+        );
+        assert_eq!(e("1.5e-3").ok(), Some(Expr::Number(1.5e-3)));
+        assert_eq!(e("x[1]").ok(), Some(Expr::Indexed("x", 1)));
+        for invalid in [
+            "",
+            "1 +",
+            "(1",
+            "foo",
+            "sqrt(1, 2)",
+            "x[-1]",
+            "T[0]",
+            "1 2",
+            "2 $ 3",
+        ] {
+            assert!(StateExpression::new(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn nesting_depth() {
+        let nested = |open: &str, close: &str, n| format!("{}T{}", open.repeat(n), close.repeat(n));
+        let too_deep = |open, close| {
+            StateExpression::new(&nested(open, close, 100_000))
+                .is_err_and(|e| e.to_string().contains("nested deeper"))
+        };
+        assert!(StateExpression::new(&nested("(", ")", 32)).is_ok());
+        assert!(too_deep("(", ")"));
+        assert!(too_deep("-", ""));
+        assert!(too_deep("exp(", ")"));
+        assert!(too_deep("", "^2"));
+        assert!(too_deep("", "+1"));
+    }
+
+    #[test]
+    fn evaluate() -> FeosResult<()> {
+        let eos = Arc::new(propane_butane()?);
+        let moles = arr1(&[0.3, 0.7]) * MOL;
+        let state = State::new_npt(
+            &eos,
+            300.0 * KELVIN,
+            10.0 * BAR,
+            &moles,
+            DensityInitialization::Liquid,
+        )?;
+        let eval = |s: &str| StateExpression::new(s)?.evaluate(&state);
+
+        let rho = state.density.convert_to(MOL / METER.powi::<P3>());
+        let dp_dt = state
+            .dp_dt(Contributions::Total)
+            .convert_to(PASCAL / KELVIN);
+        assert_relative_eq!(eval("dp_dT / rho")?, dp_dt / rho);
+        assert_relative_eq!(eval("p * v / (R * T)")?, eval("z")?, max_relative = 1e-12);
+        assert_relative_eq!(
+            eval("h_res - T * s_res - R * T * ln(z)")?,
+            eval("g_res")?,
+            max_relative = 1e-10
+        );
+        assert_relative_eq!(eval("x[0] + x[1]")?, 1.0);
+        assert_relative_eq!(eval("exp(ln_phi[1])")?, state.ln_phi()[1].exp());
+        assert_relative_eq!(eval("2^3^2")?, 512.0);
+        assert_relative_eq!(eval("max(1, pow(2, 0.5)) - sqrt(2)")?, 0.0);
+        assert_relative_eq!(
+            eval("kappa_T * alpha_p")?,
+            state.isothermal_compressibility().convert_to(1.0 / PASCAL) * dp_dt
+                / (rho
+                    * state
+                        .dp_drho(Contributions::Total)
+                        .convert_to(PASCAL / (MOL / METER.powi::<P3>()))),
+            max_relative = 1e-12
+        );
+        assert!(eval("x[2]").is_err());
+
+        // equal residual Gibbs energies of coexisting phases
+        let pure = Arc::new(PengRobinson::new(Arc::new(
+            PengRobinsonParameters::new_simple(&[369.96], &[4250000.0], &[0.153], &[44.0962])?,
+        )));
+        let vle =
+            crate::PhaseEquilibrium::pure(&pure, 300.0 * KELVIN, None, SolverOptions::default())?;
+        let g = StateExpression::new("g_res / (R * T)")?;
+        assert_relative_eq!(
+            g.evaluate(vle.liquid())?,
+            g.evaluate(vle.vapor())?,
+            max_relative = 1e-8
+        );
+
+        // serialized as string
+        let expression = StateExpression::new("dp_dT / rho")?;
+        let json = serde_json::to_string(&expression)?;
+        assert_eq!(json, "\"dp_dT / rho\"");
+        assert_eq!(serde_json::from_str::<StateExpression>(&json)?, expression);
+        assert!(serde_json::from_str::<StateExpression>("\"T +\"").is_err());
+        assert_eq!(StateExpression::variables().len(), 19);
+        Ok(())
+    }
+}
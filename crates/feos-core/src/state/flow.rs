@@ -0,0 +1,208 @@
+use super::{Contributions, DensityInitialization, State};
+use crate::equation_of_state::{IdealGas, Molarweight, Residual};
+use crate::errors::FeosResult;
+use ndarray::Array1;
+use quantity::{
+    HeatCapacityRate, MassFlowRate, MoleFlowRate, Power, Pressure, SECOND, Temperature, Time,
+    Volume,
+};
+use std::ops::Div;
+use std::sync::Arc;
+
+type VolumeFlowRate = <Volume as Div<Time>>::Output;
+
+/// # Process streams
+///
+/// States of process streams are created from the flow rates of all
+/// components. The amount of substance of such a state is the amount that
+/// passes in one second, so that all total (extensive) properties of the
+/// state correspond to the flow rates of these properties multiplied by one
+/// second. The `*_flow_rate` accessors return the flow rates directly.
+impl<E: Residual> State<E> {
+    /// Return a new `State` for given temperature, pressure, and mole flow
+    /// rates of all components.
+    pub fn new_npt_flow(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressure: Pressure,
+        mole_flow_rate: &MoleFlowRate<Array1<f64>>,
+        density_initialization: DensityInitialization,
+    ) -> FeosResult<Self> {
+        let moles = mole_flow_rate.clone() * SECOND;
+        State::new_npt(eos, temperature, pressure, &moles, density_initialization)
+    }
+
+    /// Mole flow rate of each component: $\dot n_i$
+    pub fn mole_flow_rate(&self) -> MoleFlowRate<Array1<f64>> {
+        self.moles.clone() / SECOND
+    }
+
+    /// Total mole flow rate: $\dot n=\sum_i\dot n_i$
+    pub fn total_mole_flow_rate(&self) -> MoleFlowRate {
+        self.total_moles / SECOND
+    }
+
+    /// Volume flow rate: $\dot V=\dot nv$
+    pub fn volume_flow_rate(&self) -> VolumeFlowRate {
+        self.volume / SECOND
+    }
+}
+
+impl<E: Residual + Molarweight> State<E> {
+    /// Return a new `State` for given temperature, pressure, and mass flow
+    /// rates of all components.
+    pub fn new_npt_mass_flow(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressure: Pressure,
+        mass_flow_rate: &MassFlowRate<Array1<f64>>,
+        density_initialization: DensityInitialization,
+    ) -> FeosResult<Self> {
+        let mole_flow_rate = eos.mole_flow_rate(mass_flow_rate);
+        Self::new_npt_flow(
+            eos,
+            temperature,
+            pressure,
+            &mole_flow_rate,
+            density_initialization,
+        )
+    }
+
+    /// Mass flow rate of each component: $\dot m_i=\dot n_iMW_i$
+    pub fn mass_flow_rate(&self) -> MassFlowRate<Array1<f64>> {
+        self.mass() / SECOND
+    }
+
+    /// Total mass flow rate: $\dot m=\sum_i\dot m_i$
+    pub fn total_mass_flow_rate(&self) -> MassFlowRate {
+        self.total_mass() / SECOND
+    }
+}
+
+impl<E: Residual + IdealGas> State<E> {
+    /// Enthalpy flow rate: $\dot H=\dot nh$
+    pub fn enthalpy_flow_rate(&self, contributions: Contributions) -> Power {
+        self.enthalpy(contributions) / SECOND
+    }
+
+    /// Entropy flow rate: $\dot S=\dot ns$
+    pub fn entropy_flow_rate(&self, contributions: Contributions) -> HeatCapacityRate {
+        self.entropy(contributions) / SECOND
+    }
+
+    /// Heat capacity rate: $\dot C_p=\dot nc_p$
+    pub fn heat_capacity_rate(&self, contributions: Contributions) -> HeatCapacityRate {
+        self.isobaric_heat_capacity(contributions) / SECOND
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equation_of_state::EquationOfState;
+    use crate::test_utils::{ConstantHeatCapacity, propane_butane};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{BAR, KELVIN, KILOGRAM, MOL, RGAS};
+
+    #[test]
+    fn molar_and_total_properties() -> FeosResult<()> {
+        let eos = Arc::new(EquationOfState::new(
+            Arc::new(ConstantHeatCapacity::new(2)),
+            Arc::new(propane_butane()?),
+        ));
+        let (t, p) = (300.0 * KELVIN, 20.0 * BAR);
+        let moles = arr1(&[1.5, 2.5]) * MOL;
+        let state = State::new_npt(&eos, t, p, &moles, DensityInitialization::Liquid)?;
+        let n = state.total_moles;
+
+        // total properties are the molar properties scaled with the amount of substance
+        for c in [
+            Contributions::IdealGas,
+            Contributions::Residual,
+            Contributions::Total,
+        ] {
+            assert_relative_eq!(
+                state.isochoric_heat_capacity(c),
+                state.molar_isochoric_heat_capacity(c) * n,
+                max_relative = 1e-12
+            );
+            assert_relative_eq!(
+                state.isobaric_heat_capacity(c),
+                state.molar_isobaric_heat_capacity(c) * n,
+                max_relative = 1e-12
+            );
+        }
+        assert_relative_eq!(
+            state.residual_isochoric_heat_capacity(),
+            state.isochoric_heat_capacity(Contributions::Residual),
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            state.residual_isobaric_heat_capacity(),
+            state.residual_molar_isobaric_heat_capacity() * n,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            state.isobaric_heat_capacity(Contributions::IdealGas),
+            4.0 * RGAS * n,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(state.molar_volume() * n, state.volume, max_relative = 1e-12);
+        assert_relative_eq!(
+            state.excess_enthalpy()?,
+            state.excess_molar_enthalpy()? * n,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            state.excess_gibbs_energy()?,
+            state.excess_molar_gibbs_energy()? * n,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            state.excess_volume()?,
+            state.excess_molar_volume()? * n,
+            max_relative = 1e-12
+        );
+
+        // process streams
+        let mass_flow = arr1(&[1.0, 2.0]) * KILOGRAM / SECOND;
+        let stream =
+            State::new_npt_mass_flow(&eos, t, p, &mass_flow, DensityInitialization::Liquid)?;
+        assert_relative_eq!(stream.mass_flow_rate(), mass_flow, max_relative = 1e-12);
+        assert_relative_eq!(
+            stream.total_mass_flow_rate(),
+            3.0 * KILOGRAM / SECOND,
+            max_relative = 1e-12
+        );
+        let mole_flow = stream.mole_flow_rate();
+        let stream = State::new_npt_flow(&eos, t, p, &mole_flow, DensityInitialization::Liquid)?;
+        assert_relative_eq!(
+            stream.total_mole_flow_rate(),
+            mole_flow.sum(),
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            stream.volume_flow_rate(),
+            stream.molar_volume() * stream.total_mole_flow_rate(),
+            max_relative = 1e-12
+        );
+        let c = Contributions::Total;
+        assert_relative_eq!(
+            stream.enthalpy_flow_rate(c),
+            stream.molar_enthalpy(c) * stream.total_mole_flow_rate(),
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            stream.entropy_flow_rate(c),
+            stream.molar_entropy(c) * stream.total_mole_flow_rate(),
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            stream.heat_capacity_rate(c),
+            stream.molar_isobaric_heat_capacity(c) * stream.total_mole_flow_rate(),
+            max_relative = 1e-12
+        );
+        Ok(())
+    }
+}
@@ -7,9 +7,9 @@
 //!
 //! Internally, all properties are computed using such states as input.
 use crate::ReferenceSystem;
-use crate::density_iteration::density_iteration;
-use crate::equation_of_state::{IdealGas, Residual};
-use crate::errors::{FeosError, FeosResult};
+use crate::density_iteration::{density_from_roots, density_iteration};
+use crate::equation_of_state::{IdealGas, Molarweight, Residual};
+use crate::errors::{FeosError, FeosResult, SolverFailure};
 use cache::Cache;
 use ndarray::prelude::*;
 use num_dual::*;
@@ -21,10 +21,19 @@ use typenum::{N1, N2, P1, Z0};
 
 mod builder;
 mod cache;
+mod exergy;
+mod expression;
+mod flow;
+mod phase_identification;
 mod properties;
+mod record;
 mod residual_properties;
 mod statevec;
 pub use builder::StateBuilder;
+pub use exergy::DeadState;
+pub use expression::StateExpression;
+pub use phase_identification::PhaseLabel;
+pub use record::StateRecord;
 pub use statevec::StateVec;
 
 /// Possible contributions that can be computed.
@@ -411,6 +420,11 @@ impl<E: Residual> State<E> {
 
     /// Return a new `State` using a density iteration. [DensityInitialization] is used to
     /// influence the calculation with respect to the possible solutions.
+    ///
+    /// If the equation of state provides analytic density roots (see
+    /// [Residual::density_roots]), the vapor (liquid) initialization selects the
+    /// lowest (highest) density root, an initial density selects the closest root
+    /// and otherwise the root with the lowest Gibbs energy is returned.
     pub fn new_npt(
         eos: &Arc<E>,
         temperature: Temperature,
@@ -418,6 +432,13 @@ impl<E: Residual> State<E> {
         moles: &Moles<Array1<f64>>,
         density_initialization: DensityInitialization,
     ) -> FeosResult<Self> {
+        // use analytic solution if available
+        if let Some(state) =
+            density_from_roots(eos, temperature, pressure, moles, density_initialization)
+        {
+            return state;
+        }
+
         // calculate state from initial density or given phase
         match density_initialization {
             DensityInitialization::InitialDensity(rho0) => {
@@ -475,6 +496,46 @@ impl<E: Residual> State<E> {
         }
     }
 
+    /// Return a new `State` on the vapor or liquid branch, even if that branch is metastable.
+    ///
+    /// In contrast to [State::new_npt], the solution is not allowed to jump to the other branch.
+    /// If the requested branch does not exist at the given conditions, i.e., if the pressure lies
+    /// beyond the corresponding spinodal, an error is returned. The stability of the resulting
+    /// state can be determined using [State::phase_stability].
+    pub fn new_npt_metastable(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressure: Pressure,
+        moles: &Moles<Array1<f64>>,
+        density_initialization: DensityInitialization,
+    ) -> FeosResult<Self> {
+        let phase = match density_initialization {
+            DensityInitialization::Vapor => "vapor",
+            DensityInitialization::Liquid => "liquid",
+            _ => {
+                return Err(FeosError::UndeterminedState(String::from(
+                    "A metastable state requires a vapor or liquid density initialization.",
+                )));
+            }
+        };
+        let state = Self::new_npt(eos, temperature, pressure, moles, density_initialization)?;
+        let on_branch = match State::spinodal(eos, temperature, Some(moles), Default::default()) {
+            Ok([vapor, liquid]) => match density_initialization {
+                DensityInitialization::Vapor => state.density <= vapor.density,
+                _ => state.density >= liquid.density,
+            },
+            Err(FeosError::SuperCritical) => true,
+            Err(e) => return Err(e),
+        };
+        if on_branch {
+            Ok(state)
+        } else {
+            Err(FeosError::UndeterminedState(format!(
+                "No {phase} branch exists at the given conditions."
+            )))
+        }
+    }
+
     /// Return a new `State` for given pressure $p$, volume $V$, temperature $T$ and composition $x_i$.
     pub fn new_npvx(
         eos: &Arc<E>,
@@ -675,6 +736,78 @@ impl<E: Residual + IdealGas> State<E> {
     }
 }
 
+/// # State constructors from mass or volume based compositions
+impl<E: Residual> State<E> {
+    /// Return a new `State` for given temperature, pressure, and volume fractions.
+    ///
+    /// The volume fractions refer to the unmixed pure components at the given
+    /// temperature and pressure, i.e., $x_i\propto\phi_iv_i^{-1}$, where the pure
+    /// component molar volumes $v_i$ are calculated with the given [DensityInitialization].
+    pub fn new_npt_volumefracs(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressure: Pressure,
+        volumefracs: &Array1<f64>,
+        total_moles: Moles,
+        density_initialization: DensityInitialization,
+    ) -> FeosResult<Self> {
+        if volumefracs.len() != eos.components() {
+            return Err(FeosError::IncompatibleComponents(
+                eos.components(),
+                volumefracs.len(),
+            ));
+        }
+        let mut x = Array1::zeros(volumefracs.len());
+        for (i, &phi) in volumefracs.iter().enumerate() {
+            if phi > 0.0 {
+                let pure = State::new_npt(
+                    &Arc::new(eos.subset(&[i])),
+                    temperature,
+                    pressure,
+                    &Moles::from_reduced(arr1(&[1.0])),
+                    density_initialization,
+                )?;
+                x[i] = phi * pure.density.to_reduced();
+            }
+        }
+        let moles = Dimensionless::new(&x / x.sum()) * total_moles;
+        State::new_npt(eos, temperature, pressure, &moles, density_initialization)
+    }
+}
+
+impl<E: Residual + Molarweight> State<E> {
+    /// Return a new `State` for given temperature, pressure, and masses of all components.
+    pub fn new_npt_mass(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressure: Pressure,
+        mass: &Mass<Array1<f64>>,
+        density_initialization: DensityInitialization,
+    ) -> FeosResult<Self> {
+        if mass.len() != eos.components() {
+            return Err(FeosError::IncompatibleComponents(
+                eos.components(),
+                mass.len(),
+            ));
+        }
+        let moles = eos.moles_from_mass(mass);
+        State::new_npt(eos, temperature, pressure, &moles, density_initialization)
+    }
+
+    /// Return a new `State` for given temperature, pressure, mass fractions, and total mass.
+    pub fn new_npt_massfracs(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressure: Pressure,
+        massfracs: &Array1<f64>,
+        total_mass: Mass,
+        density_initialization: DensityInitialization,
+    ) -> FeosResult<Self> {
+        let mass = Dimensionless::new(massfracs.clone()) * total_mass;
+        State::new_npt_mass(eos, temperature, pressure, &mass, density_initialization)
+    }
+}
+
 impl<E: Residual> State<E> {
     /// Update the state with the given temperature
     pub fn update_temperature(&self, temperature: Temperature) -> FeosResult<Self> {
@@ -779,15 +912,22 @@ where
     let rtol = 1e-10;
     let maxiter = 50;
 
+    let mut failure = SolverFailure::new("newton").iterations(maxiter);
     for _ in 0..maxiter {
         let (fx, dfx, state) = f(x0)?;
+        let residual = fx.convert_to(Quantity::new(1.0)).abs();
         let x = x0 - fx / dfx;
         if is_close(x, x0, atol, rtol) {
             return Ok(state);
         }
+        failure = failure
+            .residual(residual)
+            .temperature(state.temperature)
+            .pressure(state.pressure(Contributions::Total))
+            .molefracs(&state.molefracs);
         x0 = x;
     }
-    Err(FeosError::NotConverged("newton".to_owned()))
+    Err(FeosError::NotConverged(failure))
 }
 
 /// Validate the given temperature, mole numbers and volume.
@@ -798,7 +938,11 @@ where
 ///
 /// There is no validation of the physical state, e.g.
 /// if resulting densities are below maximum packing fraction.
-fn validate(temperature: Temperature, volume: Volume, moles: &Moles<Array1<f64>>) -> FeosResult<()> {
+fn validate(
+    temperature: Temperature,
+    volume: Volume,
+    moles: &Moles<Array1<f64>>,
+) -> FeosResult<()> {
     let t = temperature.to_reduced();
     let v = volume.to_reduced();
     let m = moles.to_reduced();
@@ -903,4 +1047,48 @@ mod tests {
         let moles = &arr1(&[0.01, 0.02, 0.05]) * MOL;
         assert!(validate(temperature, volume, &moles).is_err());
     }
+
+    #[test]
+    fn mass_and_volume_compositions() -> FeosResult<()> {
+        use crate::test_utils::propane_butane;
+        use approx::assert_relative_eq;
+
+        let eos = Arc::new(propane_butane()?);
+        let (t, p) = (300.0 * KELVIN, 5e6 * PASCAL);
+
+        let w = arr1(&[0.4, 0.6]);
+        let state =
+            State::new_npt_massfracs(&eos, t, p, &w, KILOGRAM, DensityInitialization::None)?;
+        assert_relative_eq!(state.massfracs(), w, max_relative = 1e-12);
+        assert_relative_eq!(state.total_mass(), KILOGRAM, max_relative = 1e-12);
+        assert_relative_eq!(
+            eos.molefracs_from_massfracs(&w),
+            state.molefracs,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            eos.massfracs_from_molefracs(&state.molefracs),
+            w,
+            max_relative = 1e-12
+        );
+
+        let phi = arr1(&[0.3, 0.7]);
+        let state =
+            State::new_npt_volumefracs(&eos, t, p, &phi, MOL, DensityInitialization::Liquid)?;
+        assert_relative_eq!(state.volumefracs()?, phi, max_relative = 1e-8);
+        assert_relative_eq!(state.total_moles, MOL, max_relative = 1e-12);
+
+        let mass_flow = arr1(&[1.0, 2.0]) * KILOGRAM / SECOND;
+        let mole_flow = eos.mole_flow_rate(&mass_flow);
+        assert_relative_eq!(mole_flow.get(0), KILOGRAM / SECOND / (44.0962 * GRAM / MOL));
+        assert_relative_eq!(
+            eos.mass_flow_rate(&mole_flow),
+            mass_flow,
+            max_relative = 1e-12
+        );
+
+        let mass = arr1(&[1.0, 2.0, 3.0]) * KILOGRAM;
+        assert!(State::new_npt_mass(&eos, t, p, &mass, DensityInitialization::None).is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,154 @@
+use super::{Contributions, State, StateHD};
+use crate::ReferenceSystem;
+use crate::equation_of_state::Residual;
+use num_dual::{Dual2, Dual64};
+use num_traits::Zero;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Label of a single phase state.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PhaseLabel {
+    Liquid,
+    Vapor,
+    Supercritical,
+}
+
+impl fmt::Display for PhaseLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Liquid => write!(f, "liquid"),
+            Self::Vapor => write!(f, "vapor"),
+            Self::Supercritical => write!(f, "supercritical"),
+        }
+    }
+}
+
+/// # Phase identification
+impl<E: Residual> State<E> {
+    /// Phase identification parameter
+    /// $\Pi=V\left(\frac{\left(\frac{\partial^2p}{\partial T\partial V}\right)_{N_i}}{\left(\frac{\partial p}{\partial T}\right)_{V,N_i}}-\frac{\left(\frac{\partial^2p}{\partial V^2}\right)_{T,N_i}}{\left(\frac{\partial p}{\partial V}\right)_{T,N_i}}\right)$
+    ///
+    /// States with $\Pi>1$ are liquid-like and states with $\Pi<1$ are
+    /// vapor-like. The parameter is a continuous function of the state,
+    /// which avoids the arbitrary density cutoffs of other criteria.
+    ///
+    /// See https://doi.org/10.1016/j.fluid.2010.12.001
+    pub fn phase_identification_parameter(&self) -> f64 {
+        // third derivative of the residual Helmholtz energy w.r.t. volume (twice) and temperature
+        let t = Dual2::from_re(Dual64::from(self.reduced_temperature).derivative());
+        let v = Dual2::new(
+            Dual64::from(self.reduced_volume),
+            Dual64::from(1.0),
+            Dual64::zero(),
+        );
+        let n = self.reduced_moles.mapv(|n| Dual2::from_re(Dual64::from(n)));
+        let state = StateHD::new(t, v, n);
+        let a = self.eos.residual_helmholtz_energy(&state) * t;
+        let d2p_dtdv_res = -a.v2.eps;
+
+        let v = self.reduced_volume;
+        let d2p_dtdv = -self.total_moles.to_reduced() / (v * v) + d2p_dtdv_res;
+        let dp_dt = self.dp_dt(Contributions::Total).to_reduced();
+        let dp_dv = self.dp_dv(Contributions::Total).to_reduced();
+        let d2p_dv2 = self.d2p_dv2(Contributions::Total).to_reduced();
+        v * (d2p_dtdv / dp_dt - d2p_dv2 / dp_dv)
+    }
+
+    /// Classify the state as liquid-like or vapor-like using the
+    /// [phase identification parameter](State::phase_identification_parameter).
+    ///
+    /// If the critical point of the mixture is provided, states with a
+    /// temperature and pressure above the critical point are labeled as
+    /// supercritical.
+    pub fn phase_label(&self, critical_point: Option<&State<E>>) -> PhaseLabel {
+        if let Some(cp) = critical_point
+            && self.temperature > cp.temperature
+            && self.pressure(Contributions::Total) > cp.pressure(Contributions::Total)
+        {
+            return PhaseLabel::Supercritical;
+        }
+        if self.phase_identification_parameter() > 1.0 {
+            PhaseLabel::Liquid
+        } else {
+            PhaseLabel::Vapor
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use crate::phase_equilibria::PhaseEquilibrium;
+    use crate::state::DensityInitialization;
+    use crate::{FeosResult, SolverOptions};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{BAR, KELVIN, Moles};
+    use std::sync::Arc;
+
+    #[test]
+    fn phase_labels() -> FeosResult<()> {
+        let parameters =
+            PengRobinsonParameters::new_simple(&[369.96], &[4.25e6], &[0.153], &[44.0962])?;
+        let eos = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let cp = State::critical_point(&eos, None, None, SolverOptions::default())?;
+
+        // coexisting phases are labeled correctly up to the critical point
+        for t in [200.0, 300.0, 360.0, 369.0] {
+            let vle = PhaseEquilibrium::pure(&eos, t * KELVIN, None, Default::default())?;
+            assert_eq!(vle.liquid().phase_label(Some(&cp)), PhaseLabel::Liquid);
+            assert_eq!(vle.vapor().phase_label(Some(&cp)), PhaseLabel::Vapor);
+        }
+
+        // the ideal gas limit is vapor-like
+        let moles = Moles::from_reduced(arr1(&[1.0]));
+        let state = State::new_npt(
+            &eos,
+            400.0 * KELVIN,
+            1e-3 * BAR,
+            &moles,
+            DensityInitialization::Vapor,
+        )?;
+        assert!(state.phase_identification_parameter() < 1.0);
+
+        // comparison with finite differences
+        let state = State::new_npt(
+            &eos,
+            300.0 * KELVIN,
+            20.0 * BAR,
+            &moles,
+            DensityInitialization::Liquid,
+        )?;
+        let h = 1e-4 * KELVIN;
+        let dp_dv = |t| -> FeosResult<_> {
+            let s = State::new_nvt(&eos, t, state.volume, &moles)?;
+            Ok(s.dp_dv(Contributions::Total))
+        };
+        let d2p_dtdv = (dp_dv(state.temperature + h)? - dp_dv(state.temperature - h)?) / (2.0 * h);
+        let pi = (state.volume
+            * (d2p_dtdv / state.dp_dt(Contributions::Total)
+                - state.d2p_dv2(Contributions::Total) / state.dp_dv(Contributions::Total)))
+        .into_value();
+        assert_relative_eq!(
+            state.phase_identification_parameter(),
+            pi,
+            max_relative = 1e-6
+        );
+
+        // supercritical states
+        let state = State::new_npt(
+            &eos,
+            400.0 * KELVIN,
+            100.0 * BAR,
+            &moles,
+            DensityInitialization::None,
+        )?;
+        assert_eq!(state.phase_label(Some(&cp)), PhaseLabel::Supercritical);
+        assert_ne!(state.phase_label(None), PhaseLabel::Supercritical);
+        assert_eq!(PhaseLabel::Supercritical.to_string(), "supercritical");
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use super::{Contributions, Derivative::*, PartialDerivative, State};
-use crate::equation_of_state::{IdealGas, Molarweight, Residual};
 use crate::ReferenceSystem;
+use crate::equation_of_state::{IdealGas, Molarweight, Residual};
 use ndarray::Array1;
 use quantity::*;
 use std::ops::Div;
@@ -69,6 +69,11 @@ impl<E: Residual + IdealGas> State<E> {
         }))
     }
 
+    /// Isochoric heat capacity: $C_v=\left(\frac{\partial U}{\partial T}\right)_{V,N_i}$
+    pub fn isochoric_heat_capacity(&self, contributions: Contributions) -> Entropy {
+        self.temperature * self.ds_dt(contributions)
+    }
+
     /// Molar isochoric heat capacity: $c_v=\left(\frac{\partial u}{\partial T}\right)_{V,N_i}$
     pub fn molar_isochoric_heat_capacity(&self, contributions: Contributions) -> MolarEntropy {
         self.temperature * self.ds_dt(contributions) / self.total_moles
@@ -95,6 +100,11 @@ impl<E: Residual + IdealGas> State<E> {
         }
     }
 
+    /// Isobaric heat capacity: $C_p=\left(\frac{\partial H}{\partial T}\right)_{p,N_i}$
+    pub fn isobaric_heat_capacity(&self, contributions: Contributions) -> Entropy {
+        self.molar_isobaric_heat_capacity(contributions) * self.total_moles
+    }
+
     /// Entropy: $S=-\left(\frac{\partial A}{\partial T}\right)_{V,N_i}$
     pub fn entropy(&self, contributions: Contributions) -> Entropy {
         Entropy::from_reduced(
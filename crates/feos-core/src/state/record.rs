@@ -0,0 +1,90 @@
+use super::State;
+use crate::equation_of_state::Residual;
+use crate::errors::FeosResult;
+use ndarray::Array1;
+use quantity::{KELVIN, METER, MOL};
+use serde::{Deserialize, Serialize, Serializer};
+use std::sync::Arc;
+use typenum::P3;
+
+/// Serializable representation of a [State].
+///
+/// A state is fully defined by its temperature, volume and mole numbers,
+/// which are stored in SI units. The equation of state has to be
+/// provided to restore the state using [State::from_record].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateRecord {
+    /// temperature in Kelvin
+    pub temperature: f64,
+    /// volume in m³
+    pub volume: f64,
+    /// mole numbers in mol
+    pub moles: Vec<f64>,
+}
+
+impl<E> From<&State<E>> for StateRecord {
+    fn from(state: &State<E>) -> Self {
+        Self {
+            temperature: state.temperature.convert_to(KELVIN),
+            volume: state.volume.convert_to(METER.powi::<P3>()),
+            moles: state.moles.convert_to(MOL).to_vec(),
+        }
+    }
+}
+
+impl<E> Serialize for State<E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        StateRecord::from(self).serialize(serializer)
+    }
+}
+
+impl<E: Residual> State<E> {
+    /// Restore a state from a [StateRecord].
+    pub fn from_record(eos: &Arc<E>, record: &StateRecord) -> FeosResult<Self> {
+        Self::new_nvt(
+            eos,
+            record.temperature * KELVIN,
+            record.volume * METER.powi::<P3>(),
+            &(Array1::from_vec(record.moles.clone()) * MOL),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PhaseEquilibrium;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use crate::{Contributions, SolverOptions};
+    use approx::assert_relative_eq;
+    use quantity::BAR;
+
+    #[test]
+    fn serialize_phase_equilibrium() -> FeosResult<()> {
+        let parameters =
+            PengRobinsonParameters::new_simple(&[369.96], &[4250000.0], &[0.153], &[44.0962])?;
+        let eos = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let vle = PhaseEquilibrium::pure(&eos, 300.0 * KELVIN, None, SolverOptions::default())?;
+        let json = serde_json::to_string(&vle)?;
+        let records: Vec<StateRecord> = serde_json::from_str(&json)?;
+        let restored = PhaseEquilibrium::<_, 2>::from_records(&eos, &records)?;
+        for (s1, s2) in [
+            (vle.vapor(), restored.vapor()),
+            (vle.liquid(), restored.liquid()),
+        ] {
+            assert_relative_eq!(s1.density, s2.density, max_relative = 1e-14);
+            assert_relative_eq!(
+                s1.pressure(Contributions::Total),
+                s2.pressure(Contributions::Total),
+                max_relative = 1e-12
+            );
+        }
+        assert!(vle.vapor().pressure(Contributions::Total) > 1.0 * BAR);
+        assert!(PhaseEquilibrium::<_, 3>::from_records(&eos, &records).is_err());
+
+        let state: StateRecord = serde_json::from_str(&serde_json::to_string(vle.liquid())?)?;
+        let state = State::from_record(&eos, &state)?;
+        assert_relative_eq!(state.molefracs, vle.liquid().molefracs);
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use super::{Contributions, Derivative::*, PartialDerivative, State};
 use crate::ReferenceSystem;
 use crate::equation_of_state::{EntropyScaling, Molarweight, Residual};
-use crate::errors::FeosResult;
+use crate::errors::{FeosError, FeosResult};
 use crate::phase_equilibria::PhaseEquilibrium;
 use ndarray::{Array1, Array2, arr1};
 use quantity::*;
@@ -128,6 +128,11 @@ impl<E: Residual> State<E> {
         res
     }
 
+    /// Molar volume: $v=\frac{V}{N}$
+    pub fn molar_volume(&self) -> MolarVolume {
+        1.0 / self.density
+    }
+
     /// Compressibility factor: $Z=\frac{pV}{NRT}$
     pub fn compressibility(&self, contributions: Contributions) -> f64 {
         (self.pressure(contributions) / (self.density * self.temperature * RGAS)).into_value()
@@ -300,6 +305,16 @@ impl<E: Residual> State<E> {
             - self.compressibility(Contributions::Total).ln()
     }
 
+    /// Logarithm of the fugacity coefficient like [State::ln_phi] written into an existing array.
+    pub(crate) fn ln_phi_into(&self, ln_phi: &mut Array1<f64>) {
+        let rt = (RGAS * self.temperature).to_reduced();
+        let ln_z = self.compressibility(Contributions::Total).ln();
+        for (i, ln_phi) in ln_phi.iter_mut().enumerate() {
+            *ln_phi = self.get_or_compute_derivative_residual(PartialDerivative::First(DN(i))) / rt
+                - ln_z;
+        }
+    }
+
     /// Logarithm of the fugacity coefficient of all components treated as pure substance at mixture temperature and pressure.
     pub fn ln_phi_pure_liquid(&self) -> FeosResult<Array1<f64>> {
         let pressure = self.pressure(Contributions::Total);
@@ -326,6 +341,69 @@ impl<E: Residual> State<E> {
         }
     }
 
+    /// Logarithm of the activity coefficient at infinite dilution $\ln\gamma_i^\infty=\ln\varphi_i^{\infty}(T,p)-\ln\varphi_i^\mathrm{pure}(T,p)$
+    ///
+    /// The composition of the (possibly mixed) solvent is determined by the molefracs. All components for which the composition is 0 are treated as solutes. The fugacity coefficients of the solutes are evaluated in the liquid solvent, so that no state with a small but finite amount of solute has to be constructed.
+    pub fn ln_activity_coefficient_infinite_dilution(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressure: Pressure,
+        molefracs: &Array1<f64>,
+    ) -> FeosResult<Array1<f64>> {
+        let solutes: Vec<_> = molefracs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &x)| (x == 0.0).then_some(i))
+            .collect();
+        if solutes.len() == molefracs.len() {
+            return Err(FeosError::UndeterminedState(
+                "at least one solvent with a non-zero mole fraction is required".into(),
+            ));
+        }
+
+        // Calculate the liquid solvent including the solutes at infinite dilution
+        let liquid = State::new_npt(
+            eos,
+            temperature,
+            pressure,
+            &Moles::from_reduced(molefracs / molefracs.sum()),
+            crate::DensityInitialization::Liquid,
+        )?;
+        let ln_phi = liquid.ln_phi();
+
+        // Subtract the fugacity coefficients of the pure liquid solutes
+        solutes
+            .into_iter()
+            .map(|i| {
+                let eos = Arc::new(eos.subset(&[i]));
+                let pure = Self::new_npt(
+                    &eos,
+                    temperature,
+                    pressure,
+                    &Moles::from_reduced(arr1(&[1.0])),
+                    crate::DensityInitialization::Liquid,
+                )?;
+                Ok(ln_phi[i] - pure.ln_phi()[0])
+            })
+            .collect()
+    }
+
+    /// Logarithm of the activity coefficient at infinite dilution $\ln\gamma_i^\infty$ for a binary system
+    ///
+    /// The solute (i) is the first component and the solvent (s) the second component.
+    pub fn ln_activity_coefficient_infinite_dilution_binary(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        pressure: Pressure,
+    ) -> FeosResult<f64> {
+        Ok(Self::ln_activity_coefficient_infinite_dilution(
+            eos,
+            temperature,
+            pressure,
+            &arr1(&[0.0, 1.0]),
+        )?[0])
+    }
+
     /// Henry's law constant $H_{i,s}=\lim_{x_i\to 0}\frac{y_ip}{x_i}=p_s^\mathrm{sat}\frac{\varphi_i^{\infty,\mathrm{L}}}{\varphi_i^{\infty,\mathrm{V}}}$
     ///
     /// The composition of the (possibly mixed) solvent is determined by the molefracs. All components for which the composition is 0 are treated as solutes.
@@ -431,6 +509,11 @@ impl<E: Residual> State<E> {
         })
     }
 
+    /// Residual isochoric heat capacity: $C_v^\text{res}=\left(\frac{\partial U^\text{res}}{\partial T}\right)_{V,N_i}$
+    pub fn residual_isochoric_heat_capacity(&self) -> Entropy {
+        self.temperature * self.ds_res_dt()
+    }
+
     /// Residual molar isochoric heat capacity: $c_v^\text{res}=\left(\frac{\partial u^\text{res}}{\partial T}\right)_{V,N_i}$
     pub fn residual_molar_isochoric_heat_capacity(&self) -> MolarEntropy {
         self.temperature * self.ds_res_dt() / self.total_moles
@@ -449,6 +532,11 @@ impl<E: Residual> State<E> {
             - RGAS
     }
 
+    /// Residual isobaric heat capacity: $C_p^\text{res}=\left(\frac{\partial H^\text{res}}{\partial T}\right)_{p,N_i}$
+    pub fn residual_isobaric_heat_capacity(&self) -> Entropy {
+        self.residual_molar_isobaric_heat_capacity() * self.total_moles
+    }
+
     /// Residual enthalpy: $H^\text{res}(T,p,\mathbf{n})=A^\text{res}+TS^\text{res}+p^\text{res}V$
     pub fn residual_enthalpy(&self) -> Energy {
         self.temperature * self.residual_entropy()
@@ -484,6 +572,86 @@ impl<E: Residual> State<E> {
     pub fn residual_molar_gibbs_energy(&self) -> MolarEnergy {
         self.residual_gibbs_energy() / self.total_moles
     }
+
+    /// Mole fractions and states of all pure components with non-zero mole fractions at the temperature and pressure of the mixture.
+    ///
+    /// The density of the mixture is used as initial value so that the pure components are in the same phase as the mixture.
+    fn pure_component_states(&self) -> FeosResult<Vec<(f64, Self)>> {
+        let pressure = self.pressure(Contributions::Total);
+        self.molefracs
+            .iter()
+            .enumerate()
+            .filter(|&(_, &x)| x > 0.0)
+            .map(|(i, &x)| {
+                let eos = Arc::new(self.eos.subset(&[i]));
+                let state = Self::new_npt(
+                    &eos,
+                    self.temperature,
+                    pressure,
+                    &Moles::from_reduced(arr1(&[1.0])),
+                    crate::DensityInitialization::InitialDensity(self.density),
+                )?;
+                Ok((x, state))
+            })
+            .collect()
+    }
+
+    /// Volume fractions of the unmixed pure components at the temperature and
+    /// pressure of the state: $\phi_i=\frac{x_iv_i}{\sum_jx_jv_j}$
+    pub fn volumefracs(&self) -> FeosResult<Array1<f64>> {
+        let mut pure_states = self.pure_component_states()?.into_iter();
+        let mut phi = Array1::zeros(self.molefracs.len());
+        for (i, &x) in self.molefracs.iter().enumerate() {
+            if x > 0.0
+                && let Some((_, state)) = pure_states.next()
+            {
+                phi[i] = x / state.density.to_reduced();
+            }
+        }
+        Ok(&phi / phi.sum())
+    }
+
+    /// Molar excess Gibbs energy: $g^E(T,p,\mathbf{x})=g^\text{res}-\sum_ix_ig_i^\text{res,pure}=RT\sum_ix_i\ln\gamma_i$
+    pub fn excess_molar_gibbs_energy(&self) -> FeosResult<MolarEnergy> {
+        let mut g_e = self.residual_molar_gibbs_energy();
+        for (x, state) in self.pure_component_states()? {
+            g_e -= x * state.residual_molar_gibbs_energy();
+        }
+        Ok(g_e)
+    }
+
+    /// Molar excess enthalpy: $h^E(T,p,\mathbf{x})=h^\text{res}-\sum_ix_ih_i^\text{res,pure}$
+    pub fn excess_molar_enthalpy(&self) -> FeosResult<MolarEnergy> {
+        let mut h_e = self.residual_molar_enthalpy();
+        for (x, state) in self.pure_component_states()? {
+            h_e -= x * state.residual_molar_enthalpy();
+        }
+        Ok(h_e)
+    }
+
+    /// Molar excess volume: $v^E(T,p,\mathbf{x})=v-\sum_ix_iv_i^\text{pure}$
+    pub fn excess_molar_volume(&self) -> FeosResult<MolarVolume> {
+        let mut v_e = 1.0 / self.density;
+        for (x, state) in self.pure_component_states()? {
+            v_e -= x / state.density;
+        }
+        Ok(v_e)
+    }
+
+    /// Excess Gibbs energy: $G^E=ng^E$
+    pub fn excess_gibbs_energy(&self) -> FeosResult<Energy> {
+        Ok(self.excess_molar_gibbs_energy()? * self.total_moles)
+    }
+
+    /// Excess enthalpy: $H^E=nh^E$
+    pub fn excess_enthalpy(&self) -> FeosResult<Energy> {
+        Ok(self.excess_molar_enthalpy()? * self.total_moles)
+    }
+
+    /// Excess volume: $V^E=nv^E$
+    pub fn excess_volume(&self) -> FeosResult<Volume> {
+        Ok(self.excess_molar_volume()? * self.total_moles)
+    }
 }
 
 impl<E: Residual + Molarweight> State<E> {
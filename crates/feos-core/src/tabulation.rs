@@ -0,0 +1,412 @@
+//! Tabulated single-phase properties of a mixture with fixed composition.
+//!
+//! The [PropertyTable] stores property values together with their first
+//! and second derivatives on a uniform grid in temperature and density.
+//! Queries are answered with a second-order Taylor series expansion around
+//! the closest grid point (TTSE), which is orders of magnitude faster than
+//! the evaluation of the equation of state.
+use crate::equation_of_state::Residual;
+use crate::errors::{FeosError, FeosResult};
+use crate::state::{Contributions, State};
+use ndarray::{Array1, Array2, Array4};
+use quantity::{Density, JOULE, KELVIN, METER, MOL, PASCAL, RGAS, Temperature};
+use std::sync::Arc;
+use typenum::P3;
+
+/// Relative step size of the finite differences.
+const RELATIVE_STEP: f64 = 1e-4;
+
+/// Properties that are stored in a [PropertyTable].
+///
+/// All properties are returned in SI units.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TabulatedProperty {
+    /// Pressure in Pa.
+    Pressure,
+    /// Residual molar enthalpy in J/mol.
+    ResidualMolarEnthalpy,
+    /// Residual molar entropy in J/mol/K.
+    ResidualMolarEntropy,
+    /// Residual molar internal energy in J/mol.
+    ResidualMolarInternalEnergy,
+    /// Residual molar isochoric heat capacity in J/mol/K.
+    ResidualMolarIsochoricHeatCapacity,
+    /// Residual molar isobaric heat capacity in J/mol/K.
+    ResidualMolarIsobaricHeatCapacity,
+}
+
+impl TabulatedProperty {
+    /// All tabulated properties.
+    pub const ALL: [Self; 6] = [
+        Self::Pressure,
+        Self::ResidualMolarEnthalpy,
+        Self::ResidualMolarEntropy,
+        Self::ResidualMolarInternalEnergy,
+        Self::ResidualMolarIsochoricHeatCapacity,
+        Self::ResidualMolarIsobaricHeatCapacity,
+    ];
+
+    /// Evaluate the property for a state in SI units.
+    fn evaluate<E: Residual>(&self, state: &State<E>) -> f64 {
+        let (energy, entropy) = (JOULE / MOL, JOULE / MOL / KELVIN);
+        match self {
+            Self::Pressure => state.pressure(Contributions::Total).convert_to(PASCAL),
+            Self::ResidualMolarEnthalpy => state.residual_molar_enthalpy().convert_to(energy),
+            Self::ResidualMolarEntropy => state.residual_molar_entropy().convert_to(entropy),
+            Self::ResidualMolarInternalEnergy => {
+                state.residual_molar_internal_energy().convert_to(energy)
+            }
+            Self::ResidualMolarIsochoricHeatCapacity => state
+                .residual_molar_isochoric_heat_capacity()
+                .convert_to(entropy),
+            Self::ResidualMolarIsobaricHeatCapacity => state
+                .residual_molar_isobaric_heat_capacity()
+                .convert_to(entropy),
+        }
+    }
+
+    /// Characteristic magnitude of the property in SI units that is used
+    /// as lower bound in the relative error estimate.
+    fn scale(&self, temperature: f64, density: f64) -> f64 {
+        let r = RGAS.convert_to(JOULE / MOL / KELVIN);
+        match self {
+            Self::Pressure => density * r * temperature,
+            Self::ResidualMolarEnthalpy | Self::ResidualMolarInternalEnergy => r * temperature,
+            Self::ResidualMolarEntropy
+            | Self::ResidualMolarIsochoricHeatCapacity
+            | Self::ResidualMolarIsobaricHeatCapacity => r,
+        }
+    }
+}
+
+/// Options for the construction of a [PropertyTable].
+#[derive(Clone, Copy, Debug)]
+pub struct TabulationOptions {
+    /// Number of grid points in temperature.
+    pub temperature_points: usize,
+    /// Number of grid points in density.
+    pub density_points: usize,
+    /// Maximum relative error of the interpolated properties. Queries in
+    /// grid cells that exceed the tolerance are evaluated with the equation of state.
+    pub tolerance: f64,
+}
+
+impl Default for TabulationOptions {
+    fn default() -> Self {
+        Self {
+            temperature_points: 100,
+            density_points: 100,
+            tolerance: 1e-6,
+        }
+    }
+}
+
+/// Tabulated single-phase properties of a mixture with fixed composition
+/// in a temperature-density window.
+///
+/// The table is constructed once for a grid that is uniform in temperature
+/// and density. Every grid point stores the values and first and
+/// second derivatives of all [TabulatedProperty]s. Afterwards, the error of
+/// the Taylor series expansion is checked at the center of every grid cell
+/// and cells that do not satisfy the tolerance, as well as queries outside
+/// of the window, are evaluated with the full equation of state.
+///
+/// The properties correspond to the homogeneous fluid at the given
+/// temperature and density, i.e., no phase split is considered.
+pub struct PropertyTable<E> {
+    eos: Arc<E>,
+    molefracs: Array1<f64>,
+    temperature: (f64, f64),
+    density: (f64, f64),
+    points: (usize, usize),
+    /// Values and derivatives with axes (property, temperature, density, coefficient).
+    coefficients: Array4<f64>,
+    /// Cells in which the interpolation satisfies the tolerance.
+    valid: Array2<bool>,
+}
+
+impl<E: Residual> PropertyTable<E> {
+    /// Tabulate the properties of the mixture with the given mole fractions
+    /// between `temperature.0` and `temperature.1` and `density.0` and `density.1`.
+    pub fn new(
+        eos: &Arc<E>,
+        molefracs: &Array1<f64>,
+        temperature: (Temperature, Temperature),
+        density: (Density, Density),
+        options: TabulationOptions,
+    ) -> FeosResult<Self> {
+        let molar_density = MOL / METER.powi::<P3>();
+        let (t_min, t_max) = (
+            temperature.0.convert_to(KELVIN),
+            temperature.1.convert_to(KELVIN),
+        );
+        let (rho_min, rho_max) = (
+            density.0.convert_to(molar_density),
+            density.1.convert_to(molar_density),
+        );
+        let (nt, nrho) = (options.temperature_points, options.density_points);
+        if !(0.0 < t_min && t_min < t_max && 0.0 < rho_min && rho_min < rho_max) {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "the temperature and density ranges have to be positive and increasing.",
+            )));
+        }
+        if nt < 2 || nrho < 2 {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "at least two grid points are required in temperature and density.",
+            )));
+        }
+        if molefracs.len() != eos.components() {
+            return Err(FeosError::IncompatibleComponents(
+                eos.components(),
+                molefracs.len(),
+            ));
+        }
+
+        let mut table = Self {
+            eos: eos.clone(),
+            molefracs: molefracs / molefracs.sum(),
+            temperature: (t_min, (t_max - t_min) / (nt - 1) as f64),
+            density: (rho_min, (rho_max - rho_min) / (nrho - 1) as f64),
+            points: (nt, nrho),
+            coefficients: Array4::zeros((TabulatedProperty::ALL.len(), nt, nrho, 6)),
+            valid: Array2::from_elem((nt - 1, nrho - 1), true),
+        };
+        for i in 0..nt {
+            for j in 0..nrho {
+                let (t, rho) = table.node(i, j);
+                let coefficients = table.taylor_coefficients(t, rho)?;
+                for (k, c) in coefficients.iter().enumerate() {
+                    for (l, &c) in c.iter().enumerate() {
+                        table.coefficients[[k, i, j, l]] = c;
+                    }
+                }
+            }
+        }
+
+        // check the error at the center of every cell
+        for i in 0..nt - 1 {
+            for j in 0..nrho - 1 {
+                let (t0, rho0) = table.node(i, j);
+                let t = t0 + 0.5 * table.temperature.1;
+                let rho = rho0 + 0.5 * table.density.1;
+                let state = table.state(t, rho)?;
+                table.valid[[i, j]] = TabulatedProperty::ALL.iter().enumerate().all(|(k, p)| {
+                    let exact = p.evaluate(&state);
+                    let bound = options.tolerance * exact.abs().max(p.scale(t, rho));
+                    [(i, j), (i + 1, j), (i, j + 1), (i + 1, j + 1)]
+                        .into_iter()
+                        .all(|node| (table.expand(k, node, t, rho)[0] - exact).abs() <= bound)
+                });
+            }
+        }
+        Ok(table)
+    }
+
+    /// Fraction of grid cells in which the tabulated values are used.
+    pub fn coverage(&self) -> f64 {
+        self.valid.iter().filter(|&&v| v).count() as f64 / self.valid.len() as f64
+    }
+
+    /// Evaluate a property in SI units.
+    pub fn value(
+        &self,
+        property: TabulatedProperty,
+        temperature: Temperature,
+        density: Density,
+    ) -> FeosResult<f64> {
+        let (t, rho) = Self::si(temperature, density);
+        match self.closest_node(t, rho) {
+            Some(node) => Ok(self.expand(property as usize, node, t, rho)[0]),
+            None => Ok(property.evaluate(&self.state(t, rho)?)),
+        }
+    }
+
+    /// Evaluate a property and its partial derivatives with respect to
+    /// temperature (per K) and density (per mol/m³) in SI units.
+    pub fn derivatives(
+        &self,
+        property: TabulatedProperty,
+        temperature: Temperature,
+        density: Density,
+    ) -> FeosResult<[f64; 3]> {
+        let (t, rho) = Self::si(temperature, density);
+        match self.closest_node(t, rho) {
+            Some(node) => Ok(self.expand(property as usize, node, t, rho)),
+            None => {
+                let c = self.taylor_coefficients(t, rho)?[property as usize];
+                Ok([c[0], c[1], c[2]])
+            }
+        }
+    }
+
+    fn si(temperature: Temperature, density: Density) -> (f64, f64) {
+        (
+            temperature.convert_to(KELVIN),
+            density.convert_to(MOL / METER.powi::<P3>()),
+        )
+    }
+
+    fn node(&self, i: usize, j: usize) -> (f64, f64) {
+        (
+            self.temperature.0 + i as f64 * self.temperature.1,
+            self.density.0 + j as f64 * self.density.1,
+        )
+    }
+
+    /// The grid point closest to the query if it lies in a valid cell.
+    fn closest_node(&self, t: f64, rho: f64) -> Option<(usize, usize)> {
+        let x = (t - self.temperature.0) / self.temperature.1;
+        let y = (rho - self.density.0) / self.density.1;
+        let (nt, nrho) = self.points;
+        if !(0.0..=(nt - 1) as f64).contains(&x) || !(0.0..=(nrho - 1) as f64).contains(&y) {
+            return None;
+        }
+        let cell = (
+            (x.floor() as usize).min(nt - 2),
+            (y.floor() as usize).min(nrho - 2),
+        );
+        self.valid[cell].then(|| (x.round() as usize, y.round() as usize))
+    }
+
+    /// Second-order Taylor series expansion of property `k` around a grid point.
+    /// Returns the value and the derivatives w.r.t. temperature and density.
+    fn expand(&self, k: usize, (i, j): (usize, usize), t: f64, rho: f64) -> [f64; 3] {
+        let (t0, rho0) = self.node(i, j);
+        let (dt, dr) = (t - t0, rho - rho0);
+        let c = |l| self.coefficients[[k, i, j, l]];
+        let df_dt = c(1) + c(3) * dt + c(4) * dr;
+        let df_drho = c(2) + c(4) * dt + c(5) * dr;
+        let f = c(0)
+            + c(1) * dt
+            + c(2) * dr
+            + 0.5 * c(3) * dt * dt
+            + c(4) * dt * dr
+            + 0.5 * c(5) * dr * dr;
+        [f, df_dt, df_drho]
+    }
+
+    fn state(&self, t: f64, rho: f64) -> FeosResult<State<E>> {
+        let moles = &self.molefracs * MOL;
+        State::new_nvt(
+            &self.eos,
+            t * KELVIN,
+            MOL / (rho * MOL / METER.powi::<P3>()),
+            &moles,
+        )
+    }
+
+    /// Values, first and second derivatives of all properties w.r.t.
+    /// temperature and density using central differences.
+    fn taylor_coefficients(
+        &self,
+        t: f64,
+        rho: f64,
+    ) -> FeosResult<[[f64; 6]; TabulatedProperty::ALL.len()]> {
+        let (ht, hr) = (RELATIVE_STEP * t, RELATIVE_STEP * rho);
+        let mut f = [[0.0; 9]; TabulatedProperty::ALL.len()];
+        for (s, (a, b)) in [-1.0, 0.0, 1.0]
+            .into_iter()
+            .flat_map(|a| [-1.0, 0.0, 1.0].map(|b| (a, b)))
+            .enumerate()
+        {
+            let state = self.state(t + a * ht, rho + b * hr)?;
+            for (k, p) in TabulatedProperty::ALL.iter().enumerate() {
+                f[k][s] = p.evaluate(&state);
+            }
+        }
+        // stencil index 3 * (a + 1) + (b + 1)
+        Ok(f.map(|f| {
+            [
+                f[4],
+                (f[7] - f[1]) / (2.0 * ht),
+                (f[5] - f[3]) / (2.0 * hr),
+                (f[7] - 2.0 * f[4] + f[1]) / (ht * ht),
+                (f[8] - f[6] - f[2] + f[0]) / (4.0 * ht * hr),
+                (f[5] - 2.0 * f[4] + f[3]) / (hr * hr),
+            ]
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+
+    #[test]
+    fn property_table() -> FeosResult<()> {
+        let parameters = PengRobinsonParameters::new_simple(
+            &[190.56, 304.12],
+            &[4599000.0, 7377000.0],
+            &[0.011, 0.225],
+            &[16.043, 44.01],
+        )?;
+        let eos = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let molefracs = arr1(&[0.8, 0.2]);
+        let molar_density = MOL / METER.powi::<P3>();
+        let options = TabulationOptions {
+            temperature_points: 41,
+            density_points: 41,
+            tolerance: 1e-5,
+        };
+        let table = PropertyTable::new(
+            &eos,
+            &molefracs,
+            (300.0 * KELVIN, 400.0 * KELVIN),
+            (10.0 * molar_density, 5000.0 * molar_density),
+            options,
+        )?;
+        assert!(table.coverage() > 0.5);
+
+        // queries inside and outside of the table
+        let moles = &molefracs * MOL;
+        for (t_si, rho_si) in [
+            (312.3, 47.1),
+            (355.5, 1234.5),
+            (399.9, 4999.0),
+            (450.0, 100.0),
+        ] {
+            let (t, rho) = (t_si * KELVIN, rho_si * molar_density);
+            let state = State::new_nvt(&eos, t, MOL / rho, &moles)?;
+            for property in TabulatedProperty::ALL {
+                let exact = property.evaluate(&state);
+                let bound = 1e-5 * exact.abs().max(property.scale(t_si, rho_si));
+                assert!((table.value(property, t, rho)? - exact).abs() <= bound);
+            }
+            let [p, dp_dt, dp_drho] = table.derivatives(TabulatedProperty::Pressure, t, rho)?;
+            assert_relative_eq!(
+                p,
+                state.pressure(Contributions::Total).convert_to(PASCAL),
+                max_relative = 1e-5
+            );
+            assert_relative_eq!(
+                dp_dt,
+                state
+                    .dp_dt(Contributions::Total)
+                    .convert_to(PASCAL / KELVIN),
+                max_relative = 1e-3
+            );
+            assert_relative_eq!(
+                dp_drho,
+                state
+                    .dp_drho(Contributions::Total)
+                    .convert_to(PASCAL / molar_density),
+                max_relative = 1e-3
+            );
+        }
+
+        assert!(
+            PropertyTable::new(
+                &eos,
+                &molefracs,
+                (400.0 * KELVIN, 300.0 * KELVIN),
+                (10.0 * molar_density, 5000.0 * molar_density),
+                options,
+            )
+            .is_err()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+//! Models shared by the unit tests.
+use crate::FeosResult;
+use crate::cubic::{PengRobinson, PengRobinsonParameters};
+use crate::equation_of_state::{Components, IdealGas};
+use ndarray::Array1;
+use num_dual::DualNum;
+use quantity::{JOULE, KELVIN, MOL, RGAS};
+use std::sync::Arc;
+
+/// Peng-Robinson parameters of propane and n-butane.
+pub(crate) fn propane_butane_parameters() -> FeosResult<PengRobinsonParameters> {
+    PengRobinsonParameters::new_simple(
+        &[369.96, 425.2],
+        &[4250000.0, 3800000.0],
+        &[0.153, 0.199],
+        &[44.0962, 58.123],
+    )
+}
+
+/// Peng-Robinson equation of state of propane and n-butane.
+pub(crate) fn propane_butane() -> FeosResult<PengRobinson> {
+    Ok(PengRobinson::new(Arc::new(propane_butane_parameters()?)))
+}
+
+/// Ideal gas with constant isobaric heat capacities (divided by R).
+pub(crate) struct ConstantHeatCapacity(Vec<f64>);
+
+impl ConstantHeatCapacity {
+    /// Isochoric heat capacity of 3R for all components.
+    pub(crate) fn new(components: usize) -> Self {
+        Self(vec![4.0; components])
+    }
+
+    /// Isobaric heat capacities of the components in J/mol/K.
+    pub(crate) fn from_heat_capacities(c_p: &[f64]) -> Self {
+        let r = RGAS.convert_to(JOULE / (MOL * KELVIN));
+        Self(c_p.iter().map(|c_p| c_p / r).collect())
+    }
+}
+
+impl Components for ConstantHeatCapacity {
+    fn components(&self) -> usize {
+        self.0.len()
+    }
+
+    fn subset(&self, component_list: &[usize]) -> Self {
+        Self(component_list.iter().map(|&i| self.0[i]).collect())
+    }
+}
+
+impl IdealGas for ConstantHeatCapacity {
+    fn ln_lambda3<D: DualNum<f64> + Copy>(&self, temperature: D) -> Array1<D> {
+        let ln_t = temperature.ln();
+        self.0.iter().map(|c_p| ln_t * (1.0 - c_p)).collect()
+    }
+
+    fn ideal_gas_model(&self) -> String {
+        "ConstantHeatCapacity".into()
+    }
+}
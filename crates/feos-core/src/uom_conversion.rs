@@ -0,0 +1,153 @@
+//! Conversion between the quantities used in feos and [uom](https://docs.rs/uom) quantities.
+use ndarray::Array1;
+use quantity::{Quantity, SIUnit};
+use std::marker::PhantomData;
+use typenum::Integer;
+use uom::si::{ISQ, SI};
+
+/// A `uom` quantity in the SI system with `f64` values.
+pub type UomQuantity<T, L, M, I, THETA, N, J, K> =
+    uom::si::Quantity<ISQ<L, M, T, I, THETA, N, J, K>, SI<f64>, f64>;
+
+/// Conversion of feos quantities into `uom` quantities.
+///
+/// The kind of the `uom` quantity is inferred from the target type, e.g.,
+/// temperatures can be converted into both
+/// `uom::si::f64::ThermodynamicTemperature` and
+/// `uom::si::f64::TemperatureInterval`.
+pub trait IntoUom<Q> {
+    fn into_uom(self) -> Q;
+}
+
+/// Conversion of `uom` quantities into feos quantities.
+pub trait FromUom<Q> {
+    fn from_uom(value: Q) -> Self;
+}
+
+impl<T, L, M, I, THETA, N, J, K> IntoUom<UomQuantity<T, L, M, I, THETA, N, J, K>>
+    for Quantity<f64, SIUnit<T, L, M, I, THETA, N, J>>
+where
+    T: Integer,
+    L: Integer,
+    M: Integer,
+    I: Integer,
+    THETA: Integer,
+    N: Integer,
+    J: Integer,
+    K: ?Sized,
+{
+    fn into_uom(self) -> UomQuantity<T, L, M, I, THETA, N, J, K> {
+        uom::si::Quantity {
+            dimension: PhantomData,
+            units: PhantomData,
+            value: self.convert_into(Quantity::new(1.0)),
+        }
+    }
+}
+
+impl<T, L, M, I, THETA, N, J, K> IntoUom<Array1<UomQuantity<T, L, M, I, THETA, N, J, K>>>
+    for Quantity<Array1<f64>, SIUnit<T, L, M, I, THETA, N, J>>
+where
+    T: Integer,
+    L: Integer,
+    M: Integer,
+    I: Integer,
+    THETA: Integer,
+    N: Integer,
+    J: Integer,
+    K: ?Sized,
+{
+    fn into_uom(self) -> Array1<UomQuantity<T, L, M, I, THETA, N, J, K>> {
+        self.convert_into(Quantity::new(1.0))
+            .mapv(|value| Quantity::new(value).into_uom())
+    }
+}
+
+impl<T, L, M, I, THETA, N, J, K> FromUom<UomQuantity<T, L, M, I, THETA, N, J, K>>
+    for Quantity<f64, SIUnit<T, L, M, I, THETA, N, J>>
+where
+    T: Integer,
+    L: Integer,
+    M: Integer,
+    I: Integer,
+    THETA: Integer,
+    N: Integer,
+    J: Integer,
+    K: ?Sized,
+{
+    fn from_uom(value: UomQuantity<T, L, M, I, THETA, N, J, K>) -> Self {
+        Quantity::new(value.value)
+    }
+}
+
+impl<T, L, M, I, THETA, N, J, K> FromUom<&[UomQuantity<T, L, M, I, THETA, N, J, K>]>
+    for Quantity<Array1<f64>, SIUnit<T, L, M, I, THETA, N, J>>
+where
+    T: Integer,
+    L: Integer,
+    M: Integer,
+    I: Integer,
+    THETA: Integer,
+    N: Integer,
+    J: Integer,
+    K: ?Sized,
+{
+    fn from_uom(value: &[UomQuantity<T, L, M, I, THETA, N, J, K>]) -> Self {
+        Quantity::new(value.iter().map(|v| v.value).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use crate::{Contributions, DensityInitialization, FeosResult, State};
+    use approx::assert_relative_eq;
+    use quantity::{BAR, KELVIN, MOL, Moles, Pressure, Temperature};
+    use std::sync::Arc;
+    use uom::si::f64::{MolarConcentration, Pressure as UomPressure, ThermodynamicTemperature};
+    use uom::si::{molar_concentration, pressure, thermodynamic_temperature};
+
+    #[test]
+    fn round_trip() {
+        let t: ThermodynamicTemperature = (300.0 * KELVIN).into_uom();
+        assert_relative_eq!(t.get::<thermodynamic_temperature::kelvin>(), 300.0);
+        let p = UomPressure::new::<pressure::bar>(5.0);
+        assert_relative_eq!(Pressure::from_uom(p), 5.0 * BAR);
+        let moles: Array1<uom::si::f64::AmountOfSubstance> =
+            Moles::new(ndarray::arr1(&[1.0, 2.0])).into_uom();
+        assert_relative_eq!(
+            Moles::from_uom(moles.as_slice().unwrap()),
+            Moles::new(ndarray::arr1(&[1.0, 2.0]))
+        );
+    }
+
+    #[test]
+    fn state_from_uom() -> FeosResult<()> {
+        let parameters =
+            PengRobinsonParameters::new_simple(&[369.96], &[4250000.0], &[0.153], &[44.0962])?;
+        let eos = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let t = ThermodynamicTemperature::new::<thermodynamic_temperature::degree_celsius>(25.0);
+        let p = UomPressure::new::<pressure::bar>(1.0);
+        let state = State::new_npt(
+            &eos,
+            Temperature::from_uom(t),
+            Pressure::from_uom(p),
+            &(ndarray::arr1(&[1.0]) * MOL),
+            DensityInitialization::Vapor,
+        )?;
+        let density: MolarConcentration = state.density.into_uom();
+        assert_relative_eq!(
+            density.get::<molar_concentration::mole_per_cubic_meter>(),
+            state
+                .density
+                .convert_to(MOL / quantity::METER.powi::<typenum::P3>())
+        );
+        assert_relative_eq!(
+            state.pressure(Contributions::Total),
+            1.0 * BAR,
+            max_relative = 1e-10
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,303 @@
+//! Saturated water content and water dew point of natural gas.
+//!
+//! The calculations consider liquid water as the condensing phase, i.e., the
+//! formation of hydrates and ice is not taken into account. The resulting
+//! dew point below the hydrate formation temperature is therefore the
+//! (metastable) dew point with respect to liquid water, as it is commonly
+//! specified for the design of gas dehydration units.
+use crate::equation_of_state::{Molarweight, Residual};
+use crate::errors::{FeosError, FeosResult, SolverFailure};
+use crate::hydrate::HydrateModel;
+use crate::observer::{report_iteration, report_result};
+use crate::phase_equilibria::{rachford_rice, rachford_rice_compositions};
+use crate::state::{DensityInitialization, State};
+use crate::{Contributions, ReferenceSystem};
+use crate::{SolverOptions, Verbosity};
+use ndarray::Array1;
+use quantity::{KELVIN, MassDensity, Moles, PASCAL, Pressure, RGAS, Temperature};
+use std::sync::Arc;
+
+const MAX_ITER: usize = 50;
+const TOL: f64 = 1e-10;
+/// Standard temperature in K (ISO 13443).
+const T_STANDARD: f64 = 288.15;
+/// Standard pressure in Pa (ISO 13443).
+const P_STANDARD: f64 = 101325.0;
+/// Initial temperature of the dew point iteration in K.
+const T_INITIAL: f64 = 300.0;
+/// Temperature step of the first secant step of the dew point iteration in K.
+const T_STEP: f64 = 5.0;
+
+/// Water content of a natural gas in equilibrium with liquid water.
+pub struct WaterContent<E> {
+    /// Temperature of the equilibrium.
+    pub temperature: Temperature,
+    /// Pressure of the equilibrium.
+    pub pressure: Pressure,
+    /// Mole fraction of water in the gas.
+    pub water_molefrac: f64,
+    /// Mass of water per volume of dry gas at standard conditions
+    /// (15 °C and 101.325 kPa, ideal gas).
+    pub water_content: MassDensity,
+    /// Fluid phases at equilibrium, starting with the gas.
+    pub phases: Vec<State<E>>,
+    /// Index of the aqueous phase.
+    pub aqueous: usize,
+}
+
+impl<E> WaterContent<E> {
+    /// The gas phase.
+    pub fn gas(&self) -> &State<E> {
+        &self.phases[0]
+    }
+
+    /// The aqueous phase.
+    pub fn aqueous_phase(&self) -> &State<E> {
+        &self.phases[self.aqueous]
+    }
+}
+
+impl<E: Residual + Molarweight> WaterContent<E> {
+    /// Saturated water content of a gas at given temperature and pressure.
+    ///
+    /// The gas with the mole fractions `gas` (the mole fraction of `water`
+    /// is ignored) is flashed with an excess of water. If a hydrocarbon liquid
+    /// condenses, a three phase flash is used.
+    ///
+    /// If `aqueous_eos` is given, the aqueous phase is described by a separate
+    /// model, e.g., the aqueous model of Søreide and Whitson for cubic
+    /// equations of state, while `eos` describes the gas. In that case, the
+    /// gas/aqueous equilibrium is solved by successive substitution and a
+    /// hydrocarbon liquid is not considered.
+    pub fn saturated(
+        eos: &Arc<E>,
+        aqueous_eos: Option<&Arc<E>>,
+        temperature: Temperature,
+        pressure: Pressure,
+        gas: &Array1<f64>,
+        water: usize,
+    ) -> FeosResult<Self> {
+        if gas.len() != eos.components() || water >= gas.len() {
+            return Err(FeosError::IncompatibleComponents(
+                eos.components(),
+                gas.len(),
+            ));
+        }
+        let mut feed = gas.clone();
+        feed[water] = 0.0;
+        let total = feed.sum();
+        if total <= 0.0 {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "the gas has to contain components other than water.",
+            )));
+        }
+        feed /= total;
+        feed[water] = 1.0;
+        let mut phases = HydrateModel::fluid_phases(
+            eos,
+            temperature,
+            pressure,
+            &Moles::from_reduced(feed.clone()),
+        )?;
+        let aqueous = (0..phases.len())
+            .max_by(|&i, &j| phases[i].molefracs[water].total_cmp(&phases[j].molefracs[water]))
+            .unwrap();
+        if aqueous == 0 {
+            return Err(FeosError::Error(String::from(
+                "no aqueous phase coexists with the gas.",
+            )));
+        }
+        let aqueous = match aqueous_eos {
+            Some(aqueous_eos) => {
+                phases = Self::successive_substitution(
+                    eos,
+                    aqueous_eos,
+                    &feed,
+                    [&phases[0], &phases[aqueous]],
+                )?;
+                1
+            }
+            None => aqueous,
+        };
+        let y = phases[0].molefracs[water];
+        let molar_volume = RGAS * T_STANDARD * KELVIN / (P_STANDARD * PASCAL);
+        let water_content = y / (1.0 - y) * eos.molar_weight().get(water) / molar_volume;
+        Ok(Self {
+            temperature,
+            pressure,
+            water_molefrac: y,
+            water_content,
+            phases,
+            aqueous,
+        })
+    }
+
+    /// Gas/aqueous equilibrium with different models for both phases.
+    fn successive_substitution(
+        eos: &Arc<E>,
+        aqueous_eos: &Arc<E>,
+        feed: &Array1<f64>,
+        [gas, aqueous]: [&State<E>; 2],
+    ) -> FeosResult<Vec<State<E>>> {
+        let (temperature, pressure) = (gas.temperature, gas.pressure(Contributions::Total));
+        let z = feed / feed.sum();
+        let (mut y, mut x) = (gas.molefracs.clone(), aqueous.molefracs.clone());
+        let mut beta = None;
+        let mut res = f64::INFINITY;
+        for _ in 0..MAX_ITER {
+            let gas = State::new_npt(
+                eos,
+                temperature,
+                pressure,
+                &Moles::from_reduced(y.clone()),
+                DensityInitialization::Vapor,
+            )?;
+            let aqueous = State::new_npt(
+                aqueous_eos,
+                temperature,
+                pressure,
+                &Moles::from_reduced(x.clone()),
+                DensityInitialization::Liquid,
+            )?;
+            let k = (aqueous.ln_phi() - gas.ln_phi()).mapv(f64::exp);
+            let b = rachford_rice(&z, &k, beta)?;
+            let (y_new, x_new) = rachford_rice_compositions(&z, &k, b);
+            let (y_new, x_new) = (&y_new / y_new.sum(), &x_new / x_new.sum());
+            res = (&y_new - &y)
+                .iter()
+                .chain((&x_new - &x).iter())
+                .fold(0.0, |acc, d| d.abs().max(acc));
+            if res < TOL {
+                return Ok(vec![gas, aqueous]);
+            }
+            (y, x, beta) = (y_new, x_new, Some(b));
+        }
+        Err(FeosError::NotConverged(
+            SolverFailure::new("water content")
+                .iterations(MAX_ITER)
+                .residual(res)
+                .temperature(temperature)
+                .pressure(pressure)
+                .molefracs(&z),
+        ))
+    }
+
+    /// Water dew point of a wet gas at given pressure.
+    ///
+    /// The dew point is the temperature at which the saturated water content
+    /// equals the mole fraction of `water` in `gas`. The temperature is
+    /// iterated with the secant method in $1/T$, for which the logarithm of
+    /// the saturated water content is almost linear. The models are used as
+    /// in [WaterContent::saturated].
+    pub fn dew_point(
+        eos: &Arc<E>,
+        aqueous_eos: Option<&Arc<E>>,
+        pressure: Pressure,
+        gas: &Array1<f64>,
+        water: usize,
+        initial_temperature: Option<Temperature>,
+        options: SolverOptions,
+    ) -> FeosResult<Self> {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER, TOL);
+        if water >= gas.len() || !(gas[water] > 0.0 && gas[water] < gas.sum()) {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "the gas has to contain water and at least one other component.",
+            )));
+        }
+        let ln_y = (gas[water] / gas.sum()).ln();
+        let residual = |t: f64| -> FeosResult<_> {
+            let content = Self::saturated(eos, aqueous_eos, t * KELVIN, pressure, gas, water)?;
+            Ok((content.water_molefrac.ln() - ln_y, content))
+        };
+
+        let t0 = initial_temperature.map_or(T_INITIAL, |t| t.convert_to(KELVIN));
+        let (mut x0, mut f0) = (1.0 / t0, residual(t0)?.0);
+        let mut x1 = 1.0 / (t0 + f0.signum() * -T_STEP);
+        log_iter!(verbosity, " iter |    residual    |  temperature   ");
+        log_iter!(verbosity, "{:-<38}", "");
+        let mut res = f64::INFINITY;
+        for i in 1..=max_iter {
+            let (f1, content) = residual(1.0 / x1)?;
+            res = f1.abs();
+            log_iter!(verbosity, " {:4} | {:14.8e} | {:14.8e}", i, res, 1.0 / x1);
+            report_iteration("water dew point", i, res, &[1.0 / x1]);
+            if res < tol {
+                report_result("water dew point", i, true);
+                log_result!(
+                    verbosity,
+                    "water_dew_point: calculation converged in {} step(s)\n",
+                    i
+                );
+                return Ok(content);
+            }
+            let x2 = x1 - f1 * (x1 - x0) / (f1 - f0);
+            (x0, f0, x1) = (x1, f1, x2);
+        }
+        report_result("water dew point", max_iter, false);
+        Err(FeosError::NotConverged(
+            SolverFailure::new("water dew point")
+                .iterations(max_iter)
+                .residual(res)
+                .pressure(pressure)
+                .molefracs(gas),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{PengRobinson, PengRobinsonParameters};
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+    use quantity::{BAR, GRAM, METER, MILLI};
+    use typenum::P3;
+
+    #[test]
+    fn methane_water() -> FeosResult<()> {
+        let parameters = PengRobinsonParameters::new_simple(
+            &[190.56, 647.1],
+            &[4.599e6, 22.064e6],
+            &[0.011, 0.344],
+            &[16.043, 18.015],
+        )?;
+        let eos = Arc::new(PengRobinson::new(Arc::new(parameters)));
+        let gas = arr1(&[1.0, 0.0]);
+        let (t, p) = (300.0 * KELVIN, 50.0 * BAR);
+
+        // about 650 mg/Sm³ according to the chart of McKetta and Wehe, the
+        // Peng-Robinson equation of state overestimates the vapor pressure of water
+        let content = WaterContent::saturated(&eos, None, t, p, &gas, 1)?;
+        let mg_sm3 = content
+            .water_content
+            .convert_to(MILLI * GRAM / METER.powi::<P3>());
+        assert!(mg_sm3 > 500.0 && mg_sm3 < 1000.0);
+        assert!(content.aqueous_phase().molefracs[1] > 0.99);
+        assert_eq!(content.gas().molefracs[1], content.water_molefrac);
+
+        // the same model for both phases reproduces the flash
+        let two_models = WaterContent::saturated(&eos, Some(&eos), t, p, &gas, 1)?;
+        assert_relative_eq!(
+            two_models.water_molefrac,
+            content.water_molefrac,
+            max_relative = 1e-8
+        );
+        assert_eq!(two_models.aqueous, 1);
+
+        // the dew point of the saturated gas is the temperature of the equilibrium
+        let wet = content.gas().molefracs.clone();
+        let dew = WaterContent::dew_point(&eos, None, p, &wet, 1, None, SolverOptions::default())?;
+        assert_relative_eq!(dew.temperature, t, max_relative = 1e-8);
+
+        // drier gas has a lower dew point
+        let dry = arr1(&[1.0 - 1e-4, 1e-4]);
+        let dew = WaterContent::dew_point(&eos, None, p, &dry, 1, None, SolverOptions::default())?;
+        assert!(dew.temperature < 270.0 * KELVIN);
+        assert_relative_eq!(dew.water_molefrac, 1e-4, max_relative = 1e-8);
+        assert!(
+            WaterContent::dew_point(&eos, None, p, &gas, 1, None, SolverOptions::default())
+                .is_err()
+        );
+        Ok(())
+    }
+}
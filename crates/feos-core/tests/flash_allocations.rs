@@ -0,0 +1,121 @@
+use feos_core::cubic::{PengRobinson, PengRobinsonParameters};
+use feos_core::{
+    Contributions, DensityInitialization, FeosResult, FlashWorkspace, SolverAlgorithm,
+    SolverOptions, State, rachford_rice,
+};
+use ndarray::arr1;
+use quantity::{BAR, KELVIN, MOL};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::Arc;
+
+/// Allocator that counts the allocations of the current thread, so that
+/// tests running in parallel do not interfere.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count() {
+    let _ = ALLOCATIONS.try_with(|a| a.set(a.get() + 1));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count();
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count();
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let start = ALLOCATIONS.with(Cell::get);
+    let res = f();
+    (res, ALLOCATIONS.with(Cell::get) - start)
+}
+
+fn propane_butane() -> FeosResult<Arc<PengRobinson>> {
+    let parameters = PengRobinsonParameters::new_simple(
+        &[369.96, 425.2],
+        &[4250000.0, 3800000.0],
+        &[0.153, 0.199],
+        &[44.0962, 58.123],
+    )?;
+    Ok(Arc::new(PengRobinson::new(Arc::new(parameters))))
+}
+
+#[test]
+fn rachford_rice_does_not_allocate() -> FeosResult<()> {
+    let feed = arr1(&[0.3, 0.5, 0.2]);
+    let k = arr1(&[3.0, 0.8, 0.1]);
+    let (beta, count) = allocations(|| rachford_rice(&feed, &k, Some(0.5)));
+    assert!(beta? > 0.0);
+    assert_eq!(count, 0);
+    Ok(())
+}
+
+#[test]
+fn tp_flash_reuses_workspace() -> FeosResult<()> {
+    let eos = propane_butane()?;
+    let feed = arr1(&[0.5, 0.5]) * MOL;
+    let state = State::new_npt(
+        &eos,
+        300.0 * KELVIN,
+        6.0 * BAR,
+        &feed,
+        DensityInitialization::None,
+    )?;
+    let options = SolverOptions::new().tol(1e-10);
+
+    // apart from the workspace, both calculations perform the same steps
+    let mut workspace = FlashWorkspace::new(2);
+    state.tp_flash_with_workspace(None, options, None, &mut workspace)?;
+    let (vle, reused) =
+        allocations(|| state.tp_flash_with_workspace(None, options, None, &mut workspace));
+    let (reference, fresh) = allocations(|| state.tp_flash(None, options, None));
+    let (_, workspace) = allocations(|| FlashWorkspace::new(2));
+    assert_eq!(vle?.vapor().molefracs, reference?.vapor().molefracs);
+    assert_eq!(fresh - reused, workspace);
+    Ok(())
+}
+
+#[test]
+fn successive_substitution_allocations_per_iteration() -> FeosResult<()> {
+    let eos = propane_butane()?;
+    let feed = arr1(&[0.5, 0.5]) * MOL;
+    let (t, p) = (300.0 * KELVIN, 6.0 * BAR);
+    let state = State::new_npt(&eos, t, p, &feed, DensityInitialization::None)?;
+    let vle = state.tp_flash(None, SolverOptions::default(), None)?;
+    let mut workspace = FlashWorkspace::new(2);
+
+    // the iterations only allocate for the evaluation of the new phases
+    let (_, per_iteration) = allocations(|| -> FeosResult<_> {
+        for phase in [vle.vapor(), vle.liquid()] {
+            let density = DensityInitialization::InitialDensity(phase.density);
+            let pressure = phase.pressure(Contributions::Total);
+            State::new_npt(&eos, t, pressure, &phase.moles, density)?.ln_phi();
+        }
+        Ok(())
+    });
+    let options = SolverOptions::new()
+        .max_iter(10)
+        .tol(0.0)
+        .algorithm(SolverAlgorithm::SuccessiveSubstitution);
+    let init = vle.clone();
+    let (res, count) = allocations(|| state.tp_flash_(init, options, None, &mut workspace));
+    assert!(res.is_err());
+    assert!(count <= 10 * per_iteration);
+    Ok(())
+}
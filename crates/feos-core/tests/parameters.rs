@@ -1,3 +1,4 @@
+use approx::assert_relative_eq;
 use feos_core::FeosError;
 use feos_core::FeosResult;
 use feos_core::parameter::*;
@@ -14,6 +15,21 @@ struct MyBinaryModel {
     b: f64,
 }
 
+impl FromSegments<f64> for MyPureModel {
+    fn from_segments(segments: &[(Self, f64)]) -> FeosResult<Self> {
+        let a = segments.iter().map(|(s, n)| s.a * n).sum();
+        Ok(Self { a })
+    }
+}
+
+impl FromSegmentsBinary<f64> for MyBinaryModel {
+    fn from_segments_binary(segments: &[(f64, f64, f64)]) -> FeosResult<Self> {
+        Ok(Self {
+            b: f64::from_segments_binary(segments)?,
+        })
+    }
+}
+
 struct MyParameter {
     pure_records: Vec<PureRecord<MyPureModel>>,
     binary_records: Option<Array2<MyBinaryModel>>,
@@ -281,3 +297,94 @@ fn from_records_correct_binary_order() -> FeosResult<()> {
     assert_eq!(br[[1, 2]].b, 12.0);
     Ok(())
 }
+
+#[test]
+fn from_json_with_mode() -> FeosResult<()> {
+    let (p, report) = MyParameter::from_json_with_mode(
+        vec!["123-4-5", "678-9-1"],
+        "tests/test_parameters2.json",
+        Some("tests/test_parameters_binary.json"),
+        IdentifierOption::Cas,
+        ParsingMode::Strict,
+    )?;
+    assert!(report.issues.is_empty());
+    assert_eq!(p.binary_records.unwrap()[[0, 1]].b, 12.0);
+
+    // only the selected records are checked
+    let (p, report) = MyParameter::from_json_with_mode(
+        vec!["123-4-5", "678-9-1"],
+        "tests/test_parameters_typos.json",
+        None,
+        IdentifierOption::Cas,
+        ParsingMode::Permissive,
+    )?;
+    let messages: Vec<_> = report.warnings().map(|w| w.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "123-4-5: unknown field 'model_record.aa'",
+            "678-9-1: unknown field 'molar_weigth'",
+            "678-9-1: no molar weight given, it defaults to 0"
+        ]
+    );
+    assert_eq!(report.issues[1].components, vec![1]);
+    assert_eq!(p.pure_records[1].molarweight, 0.0);
+
+    let p = MyParameter::from_json_with_mode(
+        vec!["000-0-0"],
+        "tests/test_parameters_typos.json",
+        None,
+        IdentifierOption::Cas,
+        ParsingMode::Strict,
+    );
+    assert!(matches!(p, Err(FeosError::InvalidParameters(r)) if r.errors().count() == 2));
+    Ok(())
+}
+
+#[test]
+fn from_json_group_counts() -> FeosResult<()> {
+    let p = MyParameter::from_json_group_counts(
+        &["pseudo", "propane"],
+        "tests/test_group_counts.json",
+        "tests/test_segments.json",
+        Some("tests/test_segments_binary.json"),
+        IdentifierOption::Name,
+    )?;
+    assert_eq!(p.pure_records[0].model_record.a, 4.25);
+    assert_eq!(p.pure_records[1].model_record.a, 2.5);
+    assert_eq!(p.pure_records[0].molarweight, 2.0 * 15.035 + 4.5 * 14.027);
+    // (2 * 1 + 4.5 * 2) CH3-CH2 pairs of 6.5 * 3 segment pairs
+    assert_relative_eq!(p.binary_records.unwrap()[[0, 1]].b, 0.1 * 11.0 / 19.5);
+    Ok(())
+}
+
+#[test]
+fn from_json_with_policy() -> FeosResult<()> {
+    let load = |policy| {
+        MyParameter::from_json_with_policy(
+            vec!["123-4-5", "678-9-1"],
+            "tests/test_parameters2.json",
+            Some("tests/test_parameters_binary_duplicates.json"),
+            IdentifierOption::Cas,
+            policy,
+        )
+    };
+
+    let (p, report) = load(DuplicatePolicy::FirstWins)?;
+    assert!(report.issues.is_empty());
+    assert_eq!(p.pure_records[0].model_record.a, 0.1);
+    assert_eq!(p.binary_records.unwrap()[[1, 0]].b, 12.0);
+
+    let (p, _) = load(DuplicatePolicy::LastWins)?;
+    assert_eq!(p.pure_records[0].model_record.a, 0.3);
+    assert_eq!(p.binary_records.unwrap()[[0, 1]].b, 13.0);
+
+    let (p, report) = load(DuplicatePolicy::WarnAndFirstWins)?;
+    assert_eq!(p.pure_records[0].model_record.a, 0.1);
+    assert_eq!(report.warnings().count(), 2);
+    assert_eq!(report.issues[1].components, vec![0, 1]);
+
+    let p = load(DuplicatePolicy::ErrorOnDuplicate);
+    assert!(matches!(p, Err(FeosError::InvalidParameters(r)) if r.errors().count() == 2));
+    Ok(())
+}
@@ -244,14 +244,14 @@ impl<F: HelmholtzEnergyFunctional> PlanarInterface<F> {
         };
 
         if limit_upper >= 1.0 || limit_upper.is_sign_negative() {
-            return Err(FeosError::IterationFailed(String::from(
-                "Upper limit 'l' of interface thickness needs to satisfy 0 < l < 1.",
-            )));
+            return Err(FeosError::IterationFailed(
+                "Upper limit 'l' of interface thickness needs to satisfy 0 < l < 1.".into(),
+            ));
         }
         if limit_lower >= 1.0 || limit_lower.is_sign_negative() {
-            return Err(FeosError::IterationFailed(String::from(
-                "Lower limit 'l' of interface thickness needs to satisfy 0 < l < 1.",
-            )));
+            return Err(FeosError::IterationFailed(
+                "Lower limit 'l' of interface thickness needs to satisfy 0 < l < 1.".into(),
+            ));
         }
 
         // Get the densities in the liquid and in the vapor phase
@@ -246,7 +246,7 @@ where
                 iterations
             );
         } else {
-            return Err(FeosError::NotConverged(String::from("DFT")));
+            return Err(FeosError::NotConverged("DFT".into()));
         }
         Ok(())
     }
@@ -0,0 +1,73 @@
+//! Benchmarks for the cache of the attractive parameters of the cubic
+//! equation of state. Every calculation is performed with the default
+//! cache and with a disabled cache.
+use criterion::{Criterion, criterion_group, criterion_main};
+use feos::core::parameter::{Identifier, Parameter, PureRecord};
+use feos::core::{DensityInitialization, PhaseEquilibrium, SolverOptions, State};
+use feos::cubic::{Cubic, CubicParameters, CubicRecord};
+use ndarray::Array1;
+use quantity::*;
+use std::sync::Arc;
+
+/// Peng-Robinson equation of state for a fictitious mixture of
+/// `n` hydrocarbon-like components.
+fn cubic(n: usize, cache_capacity: usize) -> Arc<Cubic> {
+    let records = (0..n)
+        .map(|i| {
+            let x = i as f64 / n as f64;
+            let record = CubicRecord::new(190.0 + 600.0 * x, 4.6e6 - 3.0e6 * x, 0.01 + x);
+            let identifier = Identifier::new(None, Some(&format!("C{i}")), None, None, None, None);
+            PureRecord::new(identifier, 16.0 + 300.0 * x, record)
+        })
+        .collect();
+    let parameters = Arc::new(CubicParameters::from_records(records, None).unwrap());
+    let eos = Cubic::peng_robinson(parameters, None, None).unwrap();
+    Arc::new(eos.with_cache_capacity(cache_capacity))
+}
+
+fn bench_cubic_cache(c: &mut Criterion) {
+    let n = 5;
+    let moles = Array1::from_elem(n, 1.0 / n as f64) * MOL;
+    let x = Array1::from_elem(n, 1.0 / n as f64);
+    let mut group = c.benchmark_group("cubic_cache");
+    for (name, capacity) in [("cached", 1024), ("uncached", 0)] {
+        let eos = cubic(n, capacity);
+        let state = State::new_npt(
+            &eos,
+            300.0 * KELVIN,
+            100.0 * BAR,
+            &moles,
+            DensityInitialization::Liquid,
+        )
+        .unwrap();
+        group.bench_function(format!("ln_phi_{name}"), |b| b.iter(|| state.ln_phi()));
+        group.bench_function(format!("density_iteration_{name}"), |b| {
+            b.iter(|| {
+                State::new_npt(
+                    &eos,
+                    300.0 * KELVIN,
+                    100.0 * BAR,
+                    &moles,
+                    DensityInitialization::Liquid,
+                )
+            })
+        });
+        // the temperature changes in every iteration
+        group.bench_function(format!("bubble_point_temperature_{name}"), |b| {
+            b.iter(|| {
+                eos.clear_cache();
+                PhaseEquilibrium::bubble_point(
+                    &eos,
+                    10.0 * BAR,
+                    &x,
+                    Some(300.0 * KELVIN),
+                    None,
+                    (SolverOptions::default(), SolverOptions::default()),
+                )
+            })
+        });
+    }
+}
+
+criterion_group!(bench, bench_cubic_cache);
+criterion_main!(bench);
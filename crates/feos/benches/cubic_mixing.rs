@@ -0,0 +1,72 @@
+//! Benchmarks for the evaluation of the Helmholtz energy function of the
+//! cubic equation of state for a mixture with many components. The double
+//! sums of the mixing rule dominate the evaluation for all dual number types.
+use criterion::{Criterion, criterion_group, criterion_main};
+use feos::core::parameter::{Identifier, Parameter, PureRecord};
+use feos::core::{DensityInitialization, Derivative, Residual, State, StateHD};
+use feos::cubic::{Cubic, CubicParameters, CubicRecord};
+use ndarray::{Array1, ScalarOperand};
+use num_dual::DualNum;
+use quantity::*;
+use std::sync::Arc;
+
+/// Peng-Robinson equation of state for a fictitious mixture of
+/// `n` hydrocarbon-like components with an equimolar liquid state
+/// at 300 K and 100 bar.
+fn state_cubic(n: usize) -> State<Cubic> {
+    let records = (0..n)
+        .map(|i| {
+            let x = i as f64 / n as f64;
+            let record = CubicRecord::new(190.0 + 600.0 * x, 4.6e6 - 3.0e6 * x, 0.01 + x);
+            let identifier = Identifier::new(None, Some(&format!("C{i}")), None, None, None, None);
+            PureRecord::new(identifier, 16.0 + 300.0 * x, record)
+        })
+        .collect();
+    let parameters = Arc::new(CubicParameters::from_records(records, None).unwrap());
+    let eos = Arc::new(Cubic::peng_robinson(parameters, None, None).unwrap());
+    let moles = Array1::from_elem(n, 1.0 / n as f64) * MOL;
+    State::new_npt(
+        &eos,
+        300.0 * KELVIN,
+        100.0 * BAR,
+        &moles,
+        DensityInitialization::Liquid,
+    )
+    .unwrap()
+}
+
+/// Residual Helmholtz energy given an equation of state and a StateHD.
+fn a_res<D: DualNum<f64> + Copy + ScalarOperand, E: Residual>(inp: (&Arc<E>, &StateHD<D>)) -> D {
+    inp.0.residual_helmholtz_energy(inp.1)
+}
+
+fn bench_cubic_mixing(c: &mut Criterion) {
+    for n in [5, 30, 100] {
+        let state = state_cubic(n);
+        let mut group = c.benchmark_group(format!("cubic_mixing_{n}_components"));
+        group.bench_function("a_f64", |b| {
+            b.iter(|| a_res((&state.eos, &state.derive0())))
+        });
+        group.bench_function("a_dual", |b| {
+            b.iter(|| a_res((&state.eos, &state.derive1(Derivative::DN(0)))))
+        });
+        group.bench_function("a_dual2", |b| {
+            b.iter(|| a_res((&state.eos, &state.derive2(Derivative::DN(0)))))
+        });
+        group.bench_function("a_hyperdual", |b| {
+            b.iter(|| {
+                a_res((
+                    &state.eos,
+                    &state.derive2_mixed(Derivative::DN(0), Derivative::DN(1)),
+                ))
+            })
+        });
+        group.bench_function("a_dual3", |b| {
+            b.iter(|| a_res((&state.eos, &state.derive3(Derivative::DN(0)))))
+        });
+        group.bench_function("ln_phi", |b| b.iter(|| state.ln_phi()));
+    }
+}
+
+criterion_group!(bench, bench_cubic_mixing);
+criterion_main!(bench);
@@ -6,6 +6,9 @@ use num_dual::DualNum;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+/// Alpha function of Mathias and Copeman (1983).
+///
+/// For supercritical temperatures only the first coefficient is used.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct MathiasCopeman(pub Vec<[f64; 3]>);
 
@@ -13,16 +16,16 @@ impl AlphaFunction for MathiasCopeman {
     #[inline]
     fn alpha<D: DualNum<f64> + Copy>(
         &self,
-        _: &Array1<f64>,
+        _: &Array1<D>,
         reduced_temperature: &Array1<D>,
     ) -> Array1<D> {
         Zip::from(reduced_temperature)
             .and(&self.0)
             .map_collect(|tr, c| {
                 let trsq = -tr.sqrt() + 1.0;
-                let a1 = trsq + c[0] + 1.0;
+                let a1 = trsq * c[0] + 1.0;
                 let a2 = match tr {
-                    tr if tr.re() < 1.0 => trsq * (c[1] + c[2]),
+                    tr if tr.re() < 1.0 => trsq.powi(2) * (trsq * c[2] + c[1]),
                     _ => D::zero(),
                 };
                 (a1 + a2).powi(2)
@@ -1,24 +1,27 @@
 use super::parameters::CubicParameters;
 use enum_dispatch::enum_dispatch;
-use feos_core::FeosResult;
+use feos_core::{FeosError, FeosResult};
 pub use mathias_copeman::MathiasCopeman;
 use ndarray::{Array1, ScalarOperand};
 use num_dual::DualNum;
+use serde::{Deserialize, Serialize};
 pub use soave::{
     PengRobinson1976, PengRobinson1978, PengRobinson2019, RedlichKwong1972, RedlichKwong2019, Soave,
 };
+pub use soreide_whitson::SoreideWhitsonAlpha;
 use std::sync::Arc;
 pub use twu::{GeneralizedTwu, Twu};
 
 mod mathias_copeman;
 mod soave;
+mod soreide_whitson;
 mod twu;
 
 #[enum_dispatch]
 pub trait AlphaFunction {
     fn alpha<D: DualNum<f64> + Copy + ScalarOperand>(
         &self,
-        acentric_factor: &Array1<f64>,
+        acentric_factor: &Array1<D>,
         reduced_temperature: &Array1<D>,
     ) -> Array1<D>;
 
@@ -31,7 +34,8 @@ pub trait AlphaFunction {
 }
 
 #[enum_dispatch(AlphaFunction)]
-#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
 pub enum Alpha {
     Soave,
     PengRobinson1976,
@@ -42,4 +46,117 @@ pub enum Alpha {
     MathiasCopeman,
     GeneralizedTwu,
     Twu,
+    SoreideWhitsonAlpha,
+}
+
+/// Component specific parameters of an alpha function.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AlphaParameters {
+    /// Soave-type alpha function with component specific slope $m$.
+    Soave { m: f64 },
+    /// Twu (1991) alpha function.
+    Twu { l: f64, m: f64, n: f64 },
+    /// Mathias-Copeman (1983) alpha function.
+    MathiasCopeman { c1: f64, c2: f64, c3: f64 },
+}
+
+impl AlphaParameters {
+    /// Return the adjustable coefficients.
+    pub fn coefficients(&self) -> Vec<f64> {
+        match *self {
+            Self::Soave { m } => vec![m],
+            Self::Twu { l, m, n } => vec![l, m, n],
+            Self::MathiasCopeman { c1, c2, c3 } => vec![c1, c2, c3],
+        }
+    }
+
+    /// Return parameters of the same type with the given coefficients.
+    pub fn with_coefficients(&self, coefficients: &[f64]) -> Self {
+        let c = coefficients;
+        match self {
+            Self::Soave { .. } => Self::Soave { m: c[0] },
+            Self::Twu { .. } => Self::Twu {
+                l: c[0],
+                m: c[1],
+                n: c[2],
+            },
+            Self::MathiasCopeman { .. } => Self::MathiasCopeman {
+                c1: c[0],
+                c2: c[1],
+                c3: c[2],
+            },
+        }
+    }
+
+    /// Evaluate the alpha function for the given coefficients (in the order
+    /// of [AlphaParameters::coefficients]) and reduced temperature.
+    pub(crate) fn alpha<D: DualNum<f64> + Copy>(
+        &self,
+        coefficients: &[D],
+        reduced_temperature: D,
+    ) -> D {
+        let (c, tr) = (coefficients, reduced_temperature);
+        match self {
+            Self::Twu { .. } => {
+                let (l, m, n) = (c[0], c[1], c[2]);
+                tr.powd(n * (m - 1.0)) * ((-tr.powd(n * m) + 1.0) * l).exp()
+            }
+            Self::Soave { .. } | Self::MathiasCopeman { .. } => {
+                let trsq = -tr.sqrt() + 1.0;
+                let a1 = trsq * c[0] + 1.0;
+                let a2 = match c {
+                    [_, c2, c3] if tr.re() < 1.0 => trsq.powi(2) * (trsq * *c3 + *c2),
+                    _ => D::zero(),
+                };
+                (a1 + a2).powi(2)
+            }
+        }
+    }
+}
+
+impl Alpha {
+    /// Build an alpha function from component specific parameters.
+    ///
+    /// All components need to use the same type of alpha function. Soave-type
+    /// parameters are represented by a Mathias-Copeman function with vanishing
+    /// higher-order coefficients.
+    pub fn from_parameters(parameters: &[AlphaParameters]) -> FeosResult<Self> {
+        let error = || {
+            FeosError::IncompatibleParameters(String::from(
+                "all components need to use the same type of alpha function.",
+            ))
+        };
+        match parameters.first() {
+            Some(AlphaParameters::Twu { .. }) => {
+                let (mut l, mut m, mut n) = (Vec::new(), Vec::new(), Vec::new());
+                for p in parameters {
+                    let &AlphaParameters::Twu {
+                        l: li,
+                        m: mi,
+                        n: ni,
+                    } = p
+                    else {
+                        return Err(error());
+                    };
+                    l.push(li);
+                    m.push(mi);
+                    n.push(ni);
+                }
+                Ok(Twu::new(l, m, Some(n)).into())
+            }
+            Some(_) => parameters
+                .iter()
+                .map(|p| match *p {
+                    AlphaParameters::Soave { m } => Ok([m, 0.0, 0.0]),
+                    AlphaParameters::MathiasCopeman { c1, c2, c3 } => Ok([c1, c2, c3]),
+                    AlphaParameters::Twu { .. } => Err(error()),
+                })
+                .collect::<FeosResult<_>>()
+                .map(|c| MathiasCopeman(c).into()),
+            None => Err(FeosError::IncompatibleParameters(String::from(
+                "no alpha function parameters provided.",
+            ))),
+        }
+    }
 }
@@ -21,17 +21,17 @@ impl Soave {
 
 impl AlphaFunction for Soave {
     #[inline]
-    fn alpha<D: DualNum<f64>>(
+    fn alpha<D: DualNum<f64> + Copy>(
         &self,
-        acentric_factor: &Array1<f64>,
+        acentric_factor: &Array1<D>,
         reduced_temperature: &Array1<D>,
     ) -> Array1<D> {
         let m = self
             .mi
             .iter()
             .enumerate()
-            .fold(Array1::zeros(acentric_factor.len()), |m, (i, &mi)| {
-                &m + acentric_factor.mapv(|w| w.powi(i as i32)) * mi
+            .fold(Array1::<D>::zeros(acentric_factor.len()), |m, (i, &mi)| {
+                &m + &acentric_factor.mapv(|w| w.powi(i as i32) * mi)
             });
         ((-reduced_temperature.mapv(|t| t.sqrt()) + 1.0) * m + 1.0).mapv(|a| a.powi(2))
     }
@@ -50,12 +50,12 @@ pub struct RedlichKwong1972;
 
 impl AlphaFunction for RedlichKwong1972 {
     #[inline]
-    fn alpha<D: DualNum<f64>>(
+    fn alpha<D: DualNum<f64> + Copy>(
         &self,
-        acentric_factor: &Array1<f64>,
+        acentric_factor: &Array1<D>,
         reduced_temperature: &Array1<D>,
     ) -> Array1<D> {
-        let m = acentric_factor.mapv(|w| 0.48 + w * (1.574 - w * 0.176));
+        let m = acentric_factor.mapv(|w| w * (-w * 0.176 + 1.574) + 0.48);
         ((-reduced_temperature.mapv(|t| t.sqrt()) + 1.0) * m + 1.0).mapv(|a| a.powi(2))
     }
 
@@ -73,12 +73,12 @@ pub struct PengRobinson1976;
 
 impl AlphaFunction for PengRobinson1976 {
     #[inline]
-    fn alpha<D: DualNum<f64>>(
+    fn alpha<D: DualNum<f64> + Copy>(
         &self,
-        acentric_factor: &Array1<f64>,
+        acentric_factor: &Array1<D>,
         reduced_temperature: &Array1<D>,
     ) -> Array1<D> {
-        let m = acentric_factor.mapv(|w| 0.37464 + w * (1.54226 - w * 0.26992));
+        let m = acentric_factor.mapv(|w| w * (-w * 0.26992 + 1.54226) + 0.37464);
         ((-reduced_temperature.mapv(|t| t.sqrt()) + 1.0) * m + 1.0).mapv(|a| a.powi(2))
     }
 
@@ -98,17 +98,17 @@ impl AlphaFunction for PengRobinson1978 {
     #[inline]
     fn alpha<D: DualNum<f64> + Copy>(
         &self,
-        acentric_factor: &Array1<f64>,
+        acentric_factor: &Array1<D>,
         reduced_temperature: &Array1<D>,
     ) -> Array1<D> {
         Zip::from(acentric_factor)
             .and(reduced_temperature)
             .map_collect(|&w, &tr| {
-                let m = if w <= 0.491 {
-                    0.37464 + w * (1.54226 - w * 0.26992)
+                let m = if w.re() <= 0.491 {
+                    w * (-w * 0.26992 + 1.54226) + 0.37464
                 } else {
                     // use higher-order polynomial if w > w(n-decane)
-                    0.379642 + w * (1.48503 + w * (-0.164423 + w * 0.016666))
+                    w * (w * (w * 0.016666 - 0.164423) + 1.48503) + 0.379642
                 };
                 ((-tr.sqrt() + 1.0) * m + 1.0).powi(2)
             })
@@ -128,12 +128,12 @@ pub struct RedlichKwong2019;
 
 impl AlphaFunction for RedlichKwong2019 {
     #[inline]
-    fn alpha<D: DualNum<f64>>(
+    fn alpha<D: DualNum<f64> + Copy>(
         &self,
-        acentric_factor: &Array1<f64>,
+        acentric_factor: &Array1<D>,
         reduced_temperature: &Array1<D>,
     ) -> Array1<D> {
-        let m = acentric_factor.mapv(|w| 0.481 + w * (1.5963 + w * (-0.2963 + w * 0.1223)));
+        let m = acentric_factor.mapv(|w| w * (w * (w * 0.1223 - 0.2963) + 1.5963) + 0.481);
         ((-reduced_temperature.mapv(|t| t.sqrt()) + 1.0) * m + 1.0).mapv(|a| a.powi(2))
     }
 
@@ -151,12 +151,12 @@ pub struct PengRobinson2019;
 
 impl AlphaFunction for PengRobinson2019 {
     #[inline]
-    fn alpha<D: DualNum<f64>>(
+    fn alpha<D: DualNum<f64> + Copy>(
         &self,
-        acentric_factor: &Array1<f64>,
+        acentric_factor: &Array1<D>,
         reduced_temperature: &Array1<D>,
     ) -> Array1<D> {
-        let m = acentric_factor.mapv(|w| 0.3919 + w * (1.4996 + w * (-0.2721 + w * 0.1063)));
+        let m = acentric_factor.mapv(|w| w * (w * (w * 0.1063 - 0.2721) + 1.4996) + 0.3919);
         ((-reduced_temperature.mapv(|t| t.sqrt()) + 1.0) * m + 1.0).mapv(|a| a.powi(2))
     }
 
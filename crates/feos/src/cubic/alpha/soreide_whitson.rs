@@ -0,0 +1,69 @@
+use super::{Alpha, AlphaFunction};
+use crate::cubic::parameters::CubicParameters;
+use feos_core::{FeosError, FeosResult};
+use ndarray::{Array1, ScalarOperand};
+use num_dual::DualNum;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Alpha function of Søreide and Whitson (1992) for water in brine.
+///
+/// The alpha function of water depends on the salinity (in mol NaCl per kg water)
+/// $$\sqrt{\alpha_w}=1+0.4530\left(1-T_r\left(1-0.0103c_\mathrm{sw}^{1.1}\right)\right)+0.0034\left(T_r^{-3}-1\right)$$
+/// All other components use the base alpha function.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SoreideWhitsonAlpha {
+    water: Option<usize>,
+    salinity: f64,
+    base: Box<Alpha>,
+}
+
+impl SoreideWhitsonAlpha {
+    /// Alpha function for water (index `water`) at the given salinity in mol/kg.
+    pub fn new<A: Into<Alpha>>(water: usize, salinity: f64, base: A) -> Self {
+        Self {
+            water: Some(water),
+            salinity,
+            base: Box::new(base.into()),
+        }
+    }
+}
+
+impl AlphaFunction for SoreideWhitsonAlpha {
+    fn alpha<D: DualNum<f64> + Copy + ScalarOperand>(
+        &self,
+        acentric_factor: &Array1<D>,
+        reduced_temperature: &Array1<D>,
+    ) -> Array1<D> {
+        let mut alpha = self.base.alpha(acentric_factor, reduced_temperature);
+        if let Some(w) = self.water {
+            let tr = reduced_temperature[w];
+            let c = 1.0 - 0.0103 * self.salinity.powf(1.1);
+            let sqrt_alpha = (-tr * c + 1.0) * 0.4530 + (tr.powi(-3) - 1.0) * 0.0034 + 1.0;
+            alpha[w] = sqrt_alpha.powi(2);
+        }
+        alpha
+    }
+
+    fn validate(&self, parameters: &Arc<CubicParameters>) -> FeosResult<()> {
+        if let Some(w) = self.water
+            && w >= parameters.tc.len()
+        {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "Søreide-Whitson alpha function was initialized for water as component {w}, but the equation of state contains {} components.",
+                parameters.tc.len()
+            )));
+        }
+        self.base.validate(parameters)
+    }
+
+    fn subset(&self, component_list: &[usize]) -> Self {
+        Self {
+            water: self
+                .water
+                .and_then(|w| component_list.iter().position(|&i| i == w)),
+            salinity: self.salinity,
+            base: Box::new(self.base.subset(component_list)),
+        }
+    }
+}
@@ -45,7 +45,7 @@ impl AlphaFunction for GeneralizedTwu {
     #[inline]
     fn alpha<D: DualNum<f64> + Copy>(
         &self,
-        acentric_factor: &Array1<f64>,
+        acentric_factor: &Array1<D>,
         reduced_temperature: &Array1<D>,
     ) -> Array1<D> {
         Zip::from(acentric_factor)
@@ -96,7 +96,7 @@ impl AlphaFunction for Twu {
     #[inline]
     fn alpha<D: DualNum<f64> + Copy>(
         &self,
-        _: &Array1<f64>,
+        _: &Array1<D>,
         reduced_temperature: &Array1<D>,
     ) -> Array1<D> {
         izip!(reduced_temperature, &self.0)
@@ -0,0 +1,444 @@
+use super::Cubic;
+use super::alpha::Alpha;
+#[cfg(feature = "kij_database")]
+use super::combining_rules::CombiningRule;
+use super::electrolyte::DebyeHuckel;
+#[cfg(feature = "kij_database")]
+use super::kij_database::{KijDatabase, KijDatabaseRecord};
+use super::mixing_rules::MixingRule;
+use super::parameters::{CubicBinaryRecord, CubicParameters, CubicRecord};
+#[cfg(feature = "kij_database")]
+use feos_core::parameter::Identifier;
+use feos_core::parameter::{BinaryRecord, IdentifierOption, Parameter, PureRecord};
+use feos_core::{Components, EquationOfState, FeosError, FeosResult};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Universal constants of the cubic equation of state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum CubicModel {
+    #[default]
+    PengRobinson,
+    RedlichKwong,
+}
+
+/// Builder for cubic equations of state.
+///
+/// Collects the components, the files or records with pure and binary
+/// parameters, the model and the alpha function and mixing rule before
+/// assembling the equation of state.
+///
+/// With the `kij_database` feature, binary interaction parameters for pairs
+/// of components without user supplied binary records are taken from the
+/// [embedded database](super::KijDatabase). These values were fitted with
+/// the default alpha function and the quadratic mixing rule; combining them
+/// with a different alpha function or mixing rule results in an error.
+///
+/// ```no_run
+/// # use feos::cubic::{CubicBuilder, GeneralizedTwu};
+/// # use feos_core::FeosResult;
+/// # fn main() -> FeosResult<()> {
+/// let eos = CubicBuilder::new()
+///     .components(&["carbon dioxide", "methane"])
+///     .from_file("pure.json")
+///     .kij_file("binary.json")
+///     .alpha(GeneralizedTwu::peng_robinson())
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct CubicBuilder {
+    components: Vec<String>,
+    identifier_option: Option<IdentifierOption>,
+    pure_file: Option<PathBuf>,
+    pure_records: Vec<PureRecord<CubicRecord>>,
+    binary_file: Option<PathBuf>,
+    binary_records: Vec<BinaryRecord<CubicBinaryRecord>>,
+    model: CubicModel,
+    alpha: Option<Alpha>,
+    mixing: Option<MixingRule>,
+    electrostatics: Option<DebyeHuckel>,
+    #[cfg(feature = "kij_database")]
+    without_kij_database: bool,
+}
+
+impl CubicBuilder {
+    /// Create a builder for the Peng-Robinson equation of state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Select the components that are read from files, in this order.
+    pub fn components<S: AsRef<str>>(mut self, components: &[S]) -> Self {
+        self.components = components.iter().map(|c| c.as_ref().into()).collect();
+        self
+    }
+
+    /// Identifier used to select components from files and to match binary records
+    /// (default: [IdentifierOption::Name]).
+    pub fn identifier_option(mut self, identifier_option: IdentifierOption) -> Self {
+        self.identifier_option = Some(identifier_option);
+        self
+    }
+
+    /// Set the file from which the pure component records of the selected
+    /// components are read.
+    pub fn from_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.pure_file = Some(path.as_ref().into());
+        self
+    }
+
+    /// Add pure component records directly. They precede the
+    /// components that are read from the file.
+    pub fn pure_records(mut self, records: Vec<PureRecord<CubicRecord>>) -> Self {
+        self.pure_records.extend(records);
+        self
+    }
+
+    /// Set the file from which binary interaction parameters are read.
+    pub fn kij_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.binary_file = Some(path.as_ref().into());
+        self
+    }
+
+    /// Add binary records directly.
+    pub fn binary_records(mut self, records: Vec<BinaryRecord<CubicBinaryRecord>>) -> Self {
+        self.binary_records.extend(records);
+        self
+    }
+
+    /// Use the Peng-Robinson equation of state (default).
+    pub fn peng_robinson(mut self) -> Self {
+        self.model = CubicModel::PengRobinson;
+        self
+    }
+
+    /// Use the (Soave) Redlich-Kwong equation of state.
+    pub fn redlich_kwong(mut self) -> Self {
+        self.model = CubicModel::RedlichKwong;
+        self
+    }
+
+    /// Set the alpha function. Without an alpha function, the component specific
+    /// parameters from the pure records or the default of the model are used.
+    pub fn alpha<A: Into<Alpha>>(mut self, alpha: A) -> Self {
+        self.alpha = Some(alpha.into());
+        self
+    }
+
+    /// Set the mixing rule (default: quadratic mixing).
+    pub fn mixing<M: Into<MixingRule>>(mut self, mixing: M) -> Self {
+        self.mixing = Some(mixing.into());
+        self
+    }
+
+    /// Add the electrostatic contribution of Debye and Hückel for ions.
+    pub fn debye_huckel(mut self, debye_huckel: DebyeHuckel) -> Self {
+        self.electrostatics = Some(debye_huckel);
+        self
+    }
+
+    /// Do not use the embedded database for binary interaction parameters.
+    #[cfg(feature = "kij_database")]
+    pub fn without_kij_database(mut self) -> Self {
+        self.without_kij_database = true;
+        self
+    }
+
+    fn identifier(&self) -> IdentifierOption {
+        self.identifier_option.unwrap_or(IdentifierOption::Name)
+    }
+
+    /// Read the pure and binary records from all files.
+    #[expect(clippy::type_complexity)]
+    fn records(
+        &self,
+    ) -> FeosResult<(
+        Vec<PureRecord<CubicRecord>>,
+        Vec<BinaryRecord<CubicBinaryRecord>>,
+    )> {
+        let identifier_option = self.identifier();
+        let mut pure_records = self.pure_records.clone();
+        if !self.components.is_empty() {
+            let Some(file) = &self.pure_file else {
+                return Err(FeosError::IncompatibleParameters(
+                    "components are selected but no file with pure records is given.".into(),
+                ));
+            };
+            let components: Vec<_> = self.components.iter().map(String::as_str).collect();
+            pure_records.extend(PureRecord::from_json(&components, file, identifier_option)?);
+        }
+        if pure_records.is_empty() {
+            return Err(FeosError::IncompatibleParameters(
+                "no components are specified.".into(),
+            ));
+        }
+
+        let mut binary_records = self.binary_records.clone();
+        if let Some(file) = &self.binary_file {
+            binary_records.extend(BinaryRecord::from_json(file)?);
+        }
+        if !binary_records.is_empty()
+            && let Some(i) = pure_records
+                .iter()
+                .position(|r| r.identifier.as_str(identifier_option).is_none())
+        {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "component {i} has no {identifier_option} to assign binary parameters."
+            )));
+        }
+        Ok((pure_records, binary_records))
+    }
+
+    /// Read all files and assemble the parameters.
+    pub fn build_parameters(&self) -> FeosResult<CubicParameters> {
+        let (pure_records, binary_records) = self.records()?;
+        #[cfg_attr(not(feature = "kij_database"), expect(unused_mut))]
+        let mut binary_matrix = CubicParameters::binary_matrix_from_records(
+            &pure_records,
+            &binary_records,
+            self.identifier(),
+        );
+        #[cfg(feature = "kij_database")]
+        {
+            let database_kij = self.match_database(&pure_records, &binary_records);
+            if !database_kij.is_empty() && !self.database_compatible(&pure_records) {
+                let name = |i: usize| {
+                    pure_records[i]
+                        .identifier
+                        .as_str(self.identifier())
+                        .map_or_else(|| format!("component {i}"), String::from)
+                };
+                let pairs: Vec<_> = database_kij
+                    .iter()
+                    .map(|&(i, j, _, _)| format!("{}-{}", name(i), name(j)))
+                    .collect();
+                return Err(FeosError::IncompatibleParameters(format!(
+                    "the embedded binary interaction parameters for {} were fitted for the quadratic mixing rule with the default alpha function. Supply binary records for these pairs or use `without_kij_database`.",
+                    pairs.join(", ")
+                )));
+            }
+            if !database_kij.is_empty() {
+                let n = pure_records.len();
+                let matrix = binary_matrix.get_or_insert_with(|| ndarray::Array2::default((n, n)));
+                for (i, j, k_ij, _) in database_kij {
+                    matrix[[i, j]] = k_ij.into();
+                    matrix[[j, i]] = k_ij.into();
+                }
+            }
+        }
+        CubicParameters::from_records(pure_records, binary_matrix)
+    }
+
+    /// Binary interaction parameters that are taken from the embedded
+    /// database, as pairs of component indices together with the
+    /// corresponding records, which also contain the source of the parameters.
+    #[cfg(feature = "kij_database")]
+    pub fn database_kij(&self) -> FeosResult<Vec<(usize, usize, &'static KijDatabaseRecord)>> {
+        let (pure_records, binary_records) = self.records()?;
+        Ok(self
+            .match_database(&pure_records, &binary_records)
+            .into_iter()
+            .map(|(i, j, _, record)| (i, j, record))
+            .collect())
+    }
+
+    /// Whether the alpha function and the mixing rule are those for which
+    /// the parameters of the embedded database were fitted: the default
+    /// alpha function of the model without component specific alpha
+    /// parameters and the quadratic mixing rule with the default combining
+    /// rule for the energy parameter.
+    #[cfg(feature = "kij_database")]
+    fn database_compatible(&self, pure_records: &[PureRecord<CubicRecord>]) -> bool {
+        let alpha = match (&self.alpha, self.model) {
+            (None, _) => pure_records.iter().all(|r| r.model_record.alpha.is_none()),
+            (Some(Alpha::PengRobinson1976(_)), CubicModel::PengRobinson) => true,
+            (Some(Alpha::RedlichKwong1972(_)), CubicModel::RedlichKwong) => true,
+            (Some(_), _) => false,
+        };
+        let mixing = match &self.mixing {
+            None => true,
+            Some(MixingRule::Quadratic(quadratic)) => {
+                matches!(quadratic.combining_rules.a, CombiningRule::Geometric)
+            }
+            Some(_) => false,
+        };
+        alpha && mixing
+    }
+
+    #[cfg(feature = "kij_database")]
+    fn match_database(
+        &self,
+        pure_records: &[PureRecord<CubicRecord>],
+        binary_records: &[BinaryRecord<CubicBinaryRecord>],
+    ) -> Vec<(usize, usize, f64, &'static KijDatabaseRecord)> {
+        if self.without_kij_database {
+            return vec![];
+        }
+        let identifier_option = self.identifier();
+        let user_supplied = |id1: &Identifier, id2: &Identifier| {
+            let (Some(id1), Some(id2)) =
+                (id1.as_str(identifier_option), id2.as_str(identifier_option))
+            else {
+                return false;
+            };
+            binary_records.iter().any(|br| {
+                let ids = (
+                    br.id1.as_str(identifier_option),
+                    br.id2.as_str(identifier_option),
+                );
+                ids == (Some(id1), Some(id2)) || ids == (Some(id2), Some(id1))
+            })
+        };
+        let database = KijDatabase::embedded();
+        let mut database_kij = Vec::new();
+        for (i, r1) in pure_records.iter().enumerate() {
+            for (j, r2) in pure_records.iter().enumerate().skip(i + 1) {
+                let (id1, id2) = (&r1.identifier, &r2.identifier);
+                if user_supplied(id1, id2) {
+                    continue;
+                }
+                if let Some(record) = database.get(id1, id2) {
+                    let k_ij = match self.model {
+                        CubicModel::PengRobinson => record.peng_robinson,
+                        CubicModel::RedlichKwong => record.redlich_kwong,
+                    };
+                    if let Some(k_ij) = k_ij {
+                        database_kij.push((i, j, k_ij, record));
+                    }
+                }
+            }
+        }
+        database_kij
+    }
+
+    /// Build the residual equation of state.
+    pub fn build(&self) -> FeosResult<Cubic> {
+        let parameters = Arc::new(self.build_parameters()?);
+        let (alpha, mixing) = (self.alpha.clone(), self.mixing.clone());
+        let cubic = match self.model {
+            CubicModel::PengRobinson => Cubic::peng_robinson(parameters, alpha, mixing),
+            CubicModel::RedlichKwong => Cubic::redlich_kwong(parameters, alpha, mixing),
+        }?;
+        match self.electrostatics {
+            Some(debye_huckel) => cubic.with_debye_huckel(debye_huckel),
+            None => Ok(cubic),
+        }
+    }
+
+    /// Build the equation of state together with an ideal gas model whose
+    /// records for the same components are read from `path`.
+    pub fn build_with_ideal_gas<I, P>(&self, path: P) -> FeosResult<EquationOfState<I, Cubic>>
+    where
+        I: Parameter + Components,
+        P: AsRef<Path>,
+    {
+        let residual = self.build()?;
+        let identifier_option = self.identifier();
+        let (pure_records, _) = residual.parameters.records();
+        let components = pure_records
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                r.identifier.as_str(identifier_option).ok_or_else(|| {
+                    FeosError::IncompatibleParameters(format!(
+                        "component {i} has no {identifier_option} to select the ideal gas record."
+                    ))
+                })
+            })
+            .collect::<FeosResult<Vec<_>>>()?;
+        let ideal_gas = I::from_json(components, path, None, identifier_option)?;
+        Ok(EquationOfState::new(
+            Arc::new(ideal_gas),
+            Arc::new(residual),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ideal_gas::Dippr;
+    use feos_core::Molarweight;
+    use feos_core::parameter::Identifier;
+    use ndarray::arr1;
+    use quantity::{GRAM, MOL};
+
+    const PURE: &str = "../../examples/peng-robinson.json";
+
+    #[test]
+    fn build() -> FeosResult<()> {
+        let eos = CubicBuilder::new()
+            .components(&["butane", "propane"])
+            .from_file(PURE)
+            .build()?;
+        assert_eq!(eos.components(), 2);
+        assert_eq!(
+            eos.molar_weight().convert_to(GRAM / MOL),
+            arr1(&[58.123, 44.0962])
+        );
+
+        let id = |name| Identifier::new(None, Some(name), None, None, None, None);
+        let binary = BinaryRecord::new(
+            id("propane"),
+            id("butane"),
+            CubicBinaryRecord::new(Some(0.02), None),
+        );
+        let parameters = CubicBuilder::new()
+            .components(&["propane", "butane"])
+            .from_file(PURE)
+            .binary_records(vec![binary])
+            .redlich_kwong()
+            .build_parameters()?;
+        assert_eq!(parameters.k_ij[[0, 1]], 0.02);
+
+        let eos = CubicBuilder::new()
+            .components(&["propane"])
+            .from_file(PURE)
+            .build_with_ideal_gas::<Dippr, _>("../../parameters/ideal_gas/poling2000.json")?;
+        assert_eq!(eos.ideal_gas.components(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        assert!(CubicBuilder::new().build().is_err());
+        assert!(
+            CubicBuilder::new()
+                .components(&["propane"])
+                .build()
+                .is_err()
+        );
+        assert!(matches!(
+            CubicBuilder::new()
+                .components(&["propane", "water"])
+                .from_file(PURE)
+                .build(),
+            Err(FeosError::ComponentsNotFound(c)) if c == ["water"]
+        ));
+        let record = PureRecord::new(
+            Identifier::default(),
+            44.0962,
+            CubicRecord::new(369.96, 4250000.0, 0.153),
+        );
+        let binary = BinaryRecord::new(
+            Identifier::default(),
+            Identifier::default(),
+            CubicBinaryRecord::default(),
+        );
+        assert!(
+            CubicBuilder::new()
+                .pure_records(vec![record.clone()])
+                .build()
+                .is_ok()
+        );
+        assert!(
+            CubicBuilder::new()
+                .pure_records(vec![record])
+                .binary_records(vec![binary])
+                .build()
+                .is_err()
+        );
+    }
+}
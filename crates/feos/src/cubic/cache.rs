@@ -0,0 +1,408 @@
+//! Concurrent cache of the temperature dependent attractive parameters.
+use super::Cubic;
+use super::alpha::AlphaFunction;
+use ndarray::{Array1, ScalarOperand};
+use num_dual::{Dual3_64, DualNum};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Default maximum number of temperatures stored in the cache.
+const CAPACITY: usize = 1024;
+/// Number of independently locked shards of the cache (a power of two).
+const SHARDS: usize = 16;
+/// Index of a missing node in the linked list of a shard.
+const NIL: usize = usize::MAX;
+
+/// Attractive parameters and their first three temperature derivatives.
+type Derivatives = Arc<[Array1<f64>; 4]>;
+
+/// Cache of the attractive parameters $a_i(T)=a_{c,i}\alpha_i(T)$ of all
+/// components and their temperature derivatives.
+///
+/// Phase equilibrium and flash calculations evaluate the equation of state
+/// many times at the same temperature, whereas the attractive parameters
+/// only depend on the temperature (and not on the composition). The values
+/// are stored for the real part of the temperature, so that evaluations with
+/// (hyper) dual numbers, e.g., for derivatives with respect to the volume or
+/// the mole numbers, use the cache as well. Temperatures are compared bitwise.
+///
+/// The cache is shared by all threads that use the same equation of state.
+/// It is split into shards that are locked independently, so that threads
+/// working at different temperatures do not block each other. Every shard
+/// removes its least recently used entry in constant time if it is full.
+pub(super) struct AttractionCache {
+    shards: Vec<Mutex<Lru>>,
+}
+
+impl Default for AttractionCache {
+    fn default() -> Self {
+        Self::new(CAPACITY)
+    }
+}
+
+impl AttractionCache {
+    /// Cache with space for (at least) `capacity` temperatures. A capacity
+    /// of zero disables the cache.
+    pub(super) fn new(capacity: usize) -> Self {
+        let capacity = capacity.div_ceil(SHARDS);
+        Self {
+            shards: (0..SHARDS)
+                .map(|_| Mutex::new(Lru::new(capacity)))
+                .collect(),
+        }
+    }
+
+    fn get_or_insert_with<F: FnOnce() -> Derivatives>(
+        &self,
+        temperature: f64,
+        f: F,
+    ) -> Derivatives {
+        let key = temperature.to_bits();
+        // Fibonacci hashing of the bits of the temperature
+        let shard = (key.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> (64 - SHARDS.ilog2())) as usize;
+        // a poisoned lock only disables the cache
+        let Ok(mut lru) = self.shards[shard].lock() else {
+            return f();
+        };
+        if let Some(at) = lru.get(key) {
+            return at;
+        }
+        // the calculation is cheap compared to locking the shard again
+        let at = f();
+        lru.insert(key, at.clone());
+        at
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().map_or(0, |lru| lru.index.len()))
+            .sum()
+    }
+
+    pub(super) fn clear(&self) {
+        for shard in &self.shards {
+            if let Ok(mut lru) = shard.lock() {
+                lru.clear();
+            }
+        }
+    }
+}
+
+/// Entry of a shard, linked in the order of use.
+struct Node {
+    key: u64,
+    value: Derivatives,
+    previous: usize,
+    next: usize,
+}
+
+/// Least recently used cache with a fixed capacity. The entries form a
+/// doubly linked list (stored in a vector) from the most recently used
+/// (`head`) to the least recently used (`tail`) entry.
+struct Lru {
+    capacity: usize,
+    index: HashMap<u64, usize>,
+    nodes: Vec<Node>,
+    head: usize,
+    tail: usize,
+}
+
+impl Lru {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            index: HashMap::with_capacity(capacity),
+            nodes: Vec::with_capacity(capacity),
+            head: NIL,
+            tail: NIL,
+        }
+    }
+
+    fn get(&mut self, key: u64) -> Option<Derivatives> {
+        let i = *self.index.get(&key)?;
+        self.unlink(i);
+        self.push_front(i);
+        Some(self.nodes[i].value.clone())
+    }
+
+    fn insert(&mut self, key: u64, value: Derivatives) {
+        if self.capacity == 0 {
+            return;
+        }
+        if let Some(&i) = self.index.get(&key) {
+            self.nodes[i].value = value;
+            self.unlink(i);
+            self.push_front(i);
+            return;
+        }
+        let i = if self.nodes.len() < self.capacity {
+            self.nodes.push(Node {
+                key,
+                value,
+                previous: NIL,
+                next: NIL,
+            });
+            self.nodes.len() - 1
+        } else {
+            // reuse the node of the least recently used entry
+            let i = self.tail;
+            self.unlink(i);
+            self.index.remove(&self.nodes[i].key);
+            self.nodes[i].key = key;
+            self.nodes[i].value = value;
+            i
+        };
+        self.index.insert(key, i);
+        self.push_front(i);
+    }
+
+    fn unlink(&mut self, i: usize) {
+        let Node { previous, next, .. } = self.nodes[i];
+        match previous {
+            NIL => self.head = next,
+            p => self.nodes[p].next = next,
+        }
+        match next {
+            NIL => self.tail = previous,
+            n => self.nodes[n].previous = previous,
+        }
+    }
+
+    fn push_front(&mut self, i: usize) {
+        self.nodes[i].previous = NIL;
+        self.nodes[i].next = self.head;
+        match self.head {
+            NIL => self.tail = i,
+            h => self.nodes[h].previous = i,
+        }
+        self.head = i;
+    }
+
+    fn clear(&mut self) {
+        self.index.clear();
+        self.nodes.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+}
+
+/// Whether the real part of `D` is more precise than `f64`.
+fn is_extended_precision<D: DualNum<f64>>() -> bool {
+    D::one() + f64::EPSILON * 0.5 != D::one()
+}
+
+impl Cubic {
+    /// Attractive parameters $a_i(T)=a_{c,i}\alpha_i(T)$ of all components.
+    ///
+    /// For (hyper) dual numbers up to third order, the values are obtained
+    /// from the cached derivatives via a Taylor expansion around the real
+    /// part of the temperature, which is exact for these numbers. Numbers
+    /// that are more precise than `f64`, e.g., `DoubleDouble`, bypass the
+    /// cache.
+    pub(super) fn attractive_parameters<D: DualNum<f64> + Copy + ScalarOperand>(
+        &self,
+        temperature: D,
+    ) -> Array1<D> {
+        if D::NDERIV > 3 || is_extended_precision::<D>() {
+            return self.compute_attractive_parameters(temperature);
+        }
+        let t = temperature.re();
+        let at = self.attraction_cache.get_or_insert_with(t, || {
+            let at = self.compute_attractive_parameters(Dual3_64::from(t).derivative());
+            Arc::new([
+                at.mapv(|a| a.re),
+                at.mapv(|a| a.v1),
+                at.mapv(|a| a.v2),
+                at.mapv(|a| a.v3),
+            ])
+        });
+        let [a, da, d2a, d3a] = &*at;
+        if D::NDERIV == 0 {
+            return a.mapv(D::from);
+        }
+        let dt = temperature - t;
+        let dt2 = dt * dt * 0.5;
+        let dt3 = dt2 * dt * (1.0 / 3.0);
+        Array1::from_shape_fn(a.len(), |i| dt3 * d3a[i] + dt2 * d2a[i] + dt * da[i] + a[i])
+    }
+
+    fn compute_attractive_parameters<D: DualNum<f64> + Copy + ScalarOperand>(
+        &self,
+        temperature: D,
+    ) -> Array1<D> {
+        let p = &self.parameters;
+        let tr = p.tc.mapv(|tc| temperature / tc);
+        let acentric_factor = p.acentric_factor.mapv(D::from);
+        self.options.alpha.alpha(&acentric_factor, &tr) * &self.critical_parameters.ac
+    }
+
+    /// Number of temperatures for which the attractive parameters are cached.
+    pub fn cached_temperatures(&self) -> usize {
+        self.attraction_cache.len()
+    }
+
+    /// Remove all cached attractive parameters.
+    pub fn clear_cache(&self) {
+        self.attraction_cache.clear()
+    }
+
+    /// Replace the cache of the attractive parameters by an empty cache with
+    /// space for (at least) `capacity` temperatures. A capacity of zero
+    /// disables the cache.
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.attraction_cache = AttractionCache::new(capacity);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{CubicParameters, CubicRecord};
+    use crate::ideal_gas::Dippr;
+    use approx::assert_relative_eq;
+    use feos_core::parameter::{Identifier, Parameter, PureRecord};
+    use feos_core::{
+        Components, Contributions, EquationOfState, FeosResult, PhaseEquilibrium, StateHD,
+    };
+    use ndarray::arr1;
+    use num_dual::{Dual64, HyperDual64};
+    use quantity::KELVIN;
+    use std::sync::Arc;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn send_sync() {
+        assert_send_sync::<Cubic>();
+        assert_send_sync::<EquationOfState<Dippr, Cubic>>();
+    }
+
+    #[test]
+    fn shared_cache() -> FeosResult<()> {
+        let record = |name, tc, pc, w, mw| {
+            let id = Identifier::new(None, Some(name), None, None, None, None);
+            PureRecord::new(id, mw, CubicRecord::new(tc, pc, w))
+        };
+        let parameters = CubicParameters::from_records(
+            vec![
+                record("propane", 369.96, 4250000.0, 0.153, 44.0962),
+                record("butane", 425.2, 3800000.0, 0.199, 58.123),
+            ],
+            None,
+        )?;
+        let eos = Arc::new(Cubic::peng_robinson(Arc::new(parameters), None, None)?);
+
+        // cached and uncached values agree, also for dual numbers
+        let at = eos.attractive_parameters(300.0);
+        assert_eq!(eos.cached_temperatures(), 1);
+        assert_eq!(eos.attractive_parameters(300.0), at);
+        assert_eq!(eos.compute_attractive_parameters(300.0), at);
+        let t = Dual64::from(300.0).derivative();
+        let at_dual = eos.attractive_parameters(t);
+        assert_eq!(at_dual.mapv(|a| a.re), at);
+        assert_eq!(eos.cached_temperatures(), 1);
+        let reference = eos.compute_attractive_parameters(t);
+        for (a, a_ref) in at_dual.iter().zip(&reference) {
+            assert_relative_eq!(a.eps, a_ref.eps, max_relative = 1e-14);
+        }
+        let t = HyperDual64::from(300.0).derivative1().derivative2();
+        let at_dual = eos.attractive_parameters(t);
+        let reference = eos.compute_attractive_parameters(t);
+        for (a, a_ref) in at_dual.iter().zip(&reference) {
+            assert_relative_eq!(a.eps1eps2, a_ref.eps1eps2, max_relative = 1e-14);
+        }
+        assert_eq!(eos.cached_temperatures(), 1);
+        let state = StateHD::new(300.0, 1e4, arr1(&[0.5, 0.5]));
+        let a = eos.cubic_helmholtz_energy(&state);
+        eos.clear_cache();
+        assert_eq!(eos.cached_temperatures(), 0);
+        assert_eq!(eos.compute_attractive_parameters(300.0), at);
+        assert_relative_eq!(eos.cubic_helmholtz_energy(&state), a);
+
+        // one equation of state is shared by concurrent phase equilibria
+        let eos = Arc::new(eos.subset(&[0]));
+        let temperatures = [250.0, 275.0, 300.0, 325.0];
+        let vapor_pressure = |t: f64| {
+            PhaseEquilibrium::pure(&eos, t * KELVIN, None, Default::default())
+                .map(|vle| vle.vapor().pressure(Contributions::Total))
+        };
+        let serial = temperatures
+            .iter()
+            .map(|&t| vapor_pressure(t))
+            .collect::<FeosResult<Vec<_>>>()?;
+        eos.clear_cache();
+        let parallel = std::thread::scope(|s| {
+            let handles: Vec<_> = temperatures
+                .iter()
+                .map(|&t| s.spawn(move || vapor_pressure(t)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .collect::<FeosResult<Vec<_>>>()
+        })?;
+        assert_eq!(serial, parallel);
+        assert!(eos.cached_temperatures() >= temperatures.len());
+        Ok(())
+    }
+
+    #[test]
+    fn least_recently_used() -> FeosResult<()> {
+        let value = |x| Arc::new([arr1(&[x]), arr1(&[0.0]), arr1(&[0.0]), arr1(&[0.0])]);
+        let mut lru = Lru::new(2);
+        lru.insert(1, value(1.0));
+        lru.insert(2, value(2.0));
+        assert!(lru.get(1).is_some());
+        lru.insert(3, value(3.0));
+        assert!(lru.get(2).is_none());
+        assert_eq!(lru.get(1).unwrap()[0], arr1(&[1.0]));
+        lru.insert(4, value(4.0));
+        assert!(lru.get(3).is_none());
+        assert_eq!(lru.index.len(), 2);
+        assert!(Lru::new(0).get(1).is_none());
+
+        // the number of cached temperatures is bounded
+        let record = PureRecord::new(
+            Identifier::new(None, Some("propane"), None, None, None, None),
+            44.0962,
+            CubicRecord::new(369.96, 4250000.0, 0.153),
+        );
+        let parameters = Arc::new(CubicParameters::from_records(vec![record], None)?);
+        let eos = Cubic::peng_robinson(parameters.clone(), None, None)?;
+        for i in 0..2 * CAPACITY {
+            eos.attractive_parameters(200.0 + 0.1 * i as f64);
+        }
+        assert!(eos.cached_temperatures() <= CAPACITY);
+        assert!(eos.cached_temperatures() > CAPACITY / 2);
+        let eos = Cubic::peng_robinson(parameters, None, None)?.with_cache_capacity(0);
+        let at = eos.attractive_parameters(300.0);
+        assert_eq!(eos.cached_temperatures(), 0);
+        assert_eq!(at, eos.compute_attractive_parameters(300.0));
+        Ok(())
+    }
+
+    #[cfg(feature = "extended_precision")]
+    #[test]
+    fn extended_precision() -> FeosResult<()> {
+        use feos_core::DoubleDouble;
+
+        let record = PureRecord::new(
+            Identifier::new(None, Some("propane"), None, None, None, None),
+            44.0962,
+            CubicRecord::new(369.96, 4250000.0, 0.153),
+        );
+        let parameters = Arc::new(CubicParameters::from_records(vec![record], None)?);
+        let eos = Cubic::peng_robinson(parameters, None, None)?;
+
+        // the trailing part of the temperature is not lost in the cache
+        let t = DoubleDouble::new(300.0, 1e-14);
+        let at = eos.attractive_parameters(t);
+        assert_eq!(eos.cached_temperatures(), 0);
+        assert_eq!(at, eos.compute_attractive_parameters(t));
+        let at_f64 = DoubleDouble::from(eos.attractive_parameters(300.0)[0]);
+        assert!(at[0] != at_f64);
+        Ok(())
+    }
+}
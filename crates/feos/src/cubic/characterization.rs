@@ -0,0 +1,337 @@
+use super::parameters::CubicRecord;
+use feos_core::parameter::{Identifier, Metadata, PureRecord};
+use feos_core::{FeosError, FeosResult};
+use quantity::{JOULE, KELVIN, MOL, RGAS};
+
+/// Conversion factor from Kelvin to Rankine.
+const RANKINE: f64 = 1.8;
+/// Conversion factor from psia to Pascal.
+const PSIA: f64 = 6894.757293168361;
+/// Atmospheric pressure in psia.
+const P_ATM_PSIA: f64 = 14.695948775513449;
+/// Density of water at 60°F in kg/m³.
+const WATER_DENSITY_60F: f64 = 999.016;
+
+/// Correlation used to estimate the critical properties of a petroleum fraction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CharacterizationCorrelation {
+    /// [Kesler and Lee (1976)](https://www.osti.gov/biblio/7317920)
+    KeslerLee,
+    /// [Twu (1984)](https://doi.org/10.1016/0378-3812(84)80013-8) for
+    /// critical properties and molar weight combined with the acentric
+    /// factor correlation of Kesler and Lee.
+    Twu,
+}
+
+/// Cubic equation of state for which the Peneloux volume shift of a
+/// pseudo-component is estimated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PenelouxCorrelation {
+    /// Soave-Redlich-Kwong: $c=0.40768\,(0.29441-Z_{RA})\,RT_c/p_c$
+    SoaveRedlichKwong,
+    /// Peng-Robinson: $c=0.50033\,(0.25969-Z_{RA})\,RT_c/p_c$
+    PengRobinson,
+}
+
+/// Cubic equation of state parameters of a petroleum pseudo-component.
+#[derive(Clone, Debug)]
+pub struct PseudoComponent {
+    /// normal boiling point in Kelvin
+    pub boiling_temperature: f64,
+    /// specific gravity (60°F/60°F)
+    pub specific_gravity: f64,
+    /// critical temperature in Kelvin
+    pub tc: f64,
+    /// critical pressure in Pascal
+    pub pc: f64,
+    /// acentric factor
+    pub acentric_factor: f64,
+    /// molar weight in g/mol
+    pub molarweight: f64,
+}
+
+impl PseudoComponent {
+    /// Characterize a pseudo-component from its normal boiling point (in Kelvin)
+    /// and its specific gravity.
+    pub fn new(
+        boiling_temperature: f64,
+        specific_gravity: f64,
+        correlation: CharacterizationCorrelation,
+    ) -> FeosResult<Self> {
+        if !(boiling_temperature > 0.0 && specific_gravity > 0.0) {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "boiling temperature ({boiling_temperature} K) and specific gravity ({specific_gravity}) have to be positive."
+            )));
+        }
+        let tb = boiling_temperature * RANKINE;
+        let sg = specific_gravity;
+        let (tc, pc, molarweight) = match correlation {
+            CharacterizationCorrelation::KeslerLee => kesler_lee(tb, sg),
+            CharacterizationCorrelation::Twu => twu(tb, sg)?,
+        };
+        let acentric_factor = kesler_lee_acentric_factor(tb, sg, tc, pc);
+
+        let tc = tc / RANKINE;
+        let pc = pc * PSIA;
+
+        if ![tc, pc, acentric_factor, molarweight]
+            .iter()
+            .all(|x| x.is_finite() && *x > 0.0)
+        {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "the {correlation:?} correlation is not applicable for Tb = {boiling_temperature} K and SG = {specific_gravity}."
+            )));
+        }
+
+        Ok(Self {
+            boiling_temperature,
+            specific_gravity,
+            tc,
+            pc,
+            acentric_factor,
+            molarweight,
+        })
+    }
+
+    /// Estimate the parameters of a component for which only the normal
+    /// boiling point (in Kelvin) and the molar weight (in g/mol) are known.
+    ///
+    /// If the liquid density at 60°F (in kg/m³) is not available, the
+    /// specific gravity is estimated from the correlation of Riazi and Daubert
+    /// $M=1.6607\cdot 10^{-4}\,T_b^{2.1962}\,SG^{-1.0164}$.
+    /// The critical properties and the acentric factor are then obtained from
+    /// `correlation`, while the given molar weight is kept.
+    ///
+    /// See https://doi.org/10.1021/i260076a019
+    pub fn from_boiling_point(
+        boiling_temperature: f64,
+        molarweight: f64,
+        liquid_density: Option<f64>,
+        correlation: CharacterizationCorrelation,
+    ) -> FeosResult<Self> {
+        if !(boiling_temperature > 0.0 && molarweight > 0.0) {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "boiling temperature ({boiling_temperature} K) and molar weight ({molarweight} g/mol) have to be positive."
+            )));
+        }
+        let specific_gravity = match liquid_density {
+            Some(density) => density / WATER_DENSITY_60F,
+            None => (1.6607e-4 * boiling_temperature.powf(2.1962) / molarweight).powf(1.0 / 1.0164),
+        };
+        Ok(Self {
+            molarweight,
+            ..Self::new(boiling_temperature, specific_gravity, correlation)?
+        })
+    }
+
+    /// Peneloux volume shift in m³/mol for the given equation of state.
+    ///
+    /// The Rackett compressibility factor is estimated from the acentric
+    /// factor as $Z_{RA}=0.29056-0.08775\,\omega$.
+    pub fn volume_shift(&self, correlation: PenelouxCorrelation) -> f64 {
+        let z_ra = 0.29056 - 0.08775 * self.acentric_factor;
+        let c = match correlation {
+            PenelouxCorrelation::SoaveRedlichKwong => 0.40768 * (0.29441 - z_ra),
+            PenelouxCorrelation::PengRobinson => 0.50033 * (0.25969 - z_ra),
+        };
+        c * RGAS.convert_to(JOULE / (MOL * KELVIN)) * self.tc / self.pc
+    }
+
+    /// Return the pure component record of the pseudo-component including
+    /// the Peneloux volume shift for the given equation of state.
+    ///
+    /// The record is flagged as estimated in its metadata.
+    pub fn pure_record(
+        &self,
+        name: &str,
+        correlation: PenelouxCorrelation,
+    ) -> PureRecord<CubicRecord> {
+        let identifier = Identifier::new(None, Some(name), None, None, None, None);
+        let metadata = Metadata {
+            comment: Some(format!(
+                "Tb = {} K, SG = {:.4}",
+                self.boiling_temperature, self.specific_gravity
+            )),
+            estimated: true,
+            ..Default::default()
+        };
+        PureRecord::new(
+            identifier,
+            self.molarweight,
+            CubicRecord::new(self.tc, self.pc, self.acentric_factor)
+                .with_volume_shift(self.volume_shift(correlation)),
+        )
+        .with_metadata(metadata)
+    }
+}
+
+/// Critical temperature (°R), critical pressure (psia) and molar weight (g/mol)
+/// according to Kesler and Lee.
+fn kesler_lee(tb: f64, sg: f64) -> (f64, f64, f64) {
+    let tc = 341.7 + 811.0 * sg + (0.4244 + 0.1174 * sg) * tb + (0.4669 - 3.2623 * sg) * 1e5 / tb;
+    let ln_pc = 8.3634 - 0.0566 / sg - (0.24244 + 2.2898 / sg + 0.11857 / sg.powi(2)) * 1e-3 * tb
+        + (1.4685 + 3.648 / sg + 0.47227 / sg.powi(2)) * 1e-7 * tb.powi(2)
+        - (0.42019 + 1.6977 / sg.powi(2)) * 1e-10 * tb.powi(3);
+    let mw = -12272.6
+        + 9486.4 * sg
+        + (4.6523 - 3.3287 * sg) * tb
+        + (1.0 - 0.77084 * sg - 0.02058 * sg.powi(2)) * (1.3437 - 720.79 / tb) * 1e7 / tb
+        + (1.0 - 0.80882 * sg + 0.02226 * sg.powi(2)) * (1.8828 - 181.98 / tb) * 1e12 / tb.powi(3);
+    (tc, ln_pc.exp(), mw)
+}
+
+/// Acentric factor according to Kesler and Lee (Tb and Tc in °R, pc in psia).
+fn kesler_lee_acentric_factor(tb: f64, sg: f64, tc: f64, pc: f64) -> f64 {
+    let tbr = tb / tc;
+    if tbr < 0.8 {
+        (-(pc / P_ATM_PSIA).ln() - 5.92714 + 6.09648 / tbr + 1.28862 * tbr.ln()
+            - 0.169347 * tbr.powi(6))
+            / (15.2518 - 15.6875 / tbr - 13.4721 * tbr.ln() + 0.43577 * tbr.powi(6))
+    } else {
+        let kw = tb.cbrt() / sg;
+        -7.904 + 0.1352 * kw - 0.007465 * kw.powi(2) + 8.359 * tbr + (1.408 - 0.01063 * kw) / tbr
+    }
+}
+
+/// Critical temperature (°R), critical pressure (psia) and molar weight (g/mol)
+/// according to Twu, using the n-alkanes as reference.
+fn twu(tb: f64, sg: f64) -> FeosResult<(f64, f64, f64)> {
+    // n-alkane reference
+    let tc0 = tb
+        / (0.533272 + 0.191017e-3 * tb + 0.779681e-7 * tb.powi(2) - 0.284376e-10 * tb.powi(3)
+            + 0.959468e28 / tb.powi(13));
+    let alpha = 1.0 - tb / tc0;
+    let pc0 = (3.83354
+        + 1.19629 * alpha.sqrt()
+        + 34.8888 * alpha
+        + 36.1952 * alpha.powi(2)
+        + 104.193 * alpha.powi(4))
+    .powi(2);
+    let vc0 = (1.0
+        - (0.419869 - 0.505839 * alpha - 1.56436 * alpha.powi(3) - 9481.70 * alpha.powi(14)))
+    .powi(-8);
+    let sg0 = 0.843593 - 0.128624 * alpha - 3.36159 * alpha.powi(3) - 13749.5 * alpha.powi(12);
+    let mw0 = twu_reference_molarweight(tb)?;
+
+    // correction for specific gravity
+    let correction = |f: f64| ((1.0 + 2.0 * f) / (1.0 - 2.0 * f)).powi(2);
+    let sqrt_tb = tb.sqrt();
+
+    let dsg_t = (5.0 * (sg0 - sg)).exp() - 1.0;
+    let f_t = dsg_t * (-0.362456 / sqrt_tb + (0.0398285 - 0.948125 / sqrt_tb) * dsg_t);
+    let tc = tc0 * correction(f_t);
+
+    let dsg_v = (4.0 * (sg0.powi(2) - sg.powi(2))).exp() - 1.0;
+    let f_v = dsg_v * (0.466590 / sqrt_tb + (-0.182421 + 3.01721 / sqrt_tb) * dsg_v);
+    let vc = vc0 * correction(f_v);
+
+    let dsg_p = (0.5 * (sg0 - sg)).exp() - 1.0;
+    let f_p = dsg_p
+        * ((2.53262 - 46.1955 / sqrt_tb - 0.00127885 * tb)
+            + (-11.4277 + 252.140 / sqrt_tb + 0.00230535 * tb) * dsg_p);
+    let pc = pc0 * tc / tc0 * vc0 / vc * correction(f_p);
+
+    let dsg_m = (5.0 * (sg0 - sg)).exp() - 1.0;
+    let x = (0.0123420 - 0.328086 / sqrt_tb).abs();
+    let f_m = dsg_m * (x + (-0.0175691 + 0.193168 / sqrt_tb) * dsg_m);
+    let mw = (mw0.ln() * correction(f_m)).exp();
+
+    Ok((tc, pc, mw))
+}
+
+/// Molar weight of the n-alkane with the given boiling point (°R).
+fn twu_reference_molarweight(tb: f64) -> FeosResult<f64> {
+    let mut theta = (tb / (10.44 - 0.0052 * tb)).ln();
+    for _ in 0..50 {
+        let e = (5.71419 + 2.71579 * theta
+            - 0.286590 * theta.powi(2)
+            - 39.8544 / theta
+            - 0.122488 / theta.powi(2))
+        .exp();
+        let de = e
+            * (2.71579 - 2.0 * 0.286590 * theta
+                + 39.8544 / theta.powi(2)
+                + 2.0 * 0.122488 / theta.powi(3));
+        let f = e - 24.7522 * theta + 35.3155 * theta.powi(2) - tb;
+        let df = de - 24.7522 + 2.0 * 35.3155 * theta;
+        let delta = f / df;
+        theta -= delta;
+        if delta.abs() < 1e-12 {
+            return Ok(theta.exp());
+        }
+    }
+    Err(FeosError::NotConverged("Twu reference molar weight".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn n_octane() -> FeosResult<()> {
+        // experimental data: Tc = 568.7 K, pc = 24.9 bar, omega = 0.399, M = 114.2 g/mol
+        for correlation in [
+            CharacterizationCorrelation::KeslerLee,
+            CharacterizationCorrelation::Twu,
+        ] {
+            let octane = PseudoComponent::new(398.8, 0.7068, correlation)?;
+            assert_relative_eq!(octane.tc, 568.7, max_relative = 0.01);
+            assert_relative_eq!(octane.pc, 24.9e5, max_relative = 0.05);
+            assert_relative_eq!(octane.acentric_factor, 0.399, max_relative = 0.05);
+            // Kesler-Lee overestimates molar weights of light fractions
+            assert_relative_eq!(octane.molarweight, 114.2, max_relative = 0.1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn from_boiling_point() -> FeosResult<()> {
+        // experimental data: Tc = 568.7 K, pc = 24.9 bar, omega = 0.399, SG = 0.7068
+        let octane = PseudoComponent::from_boiling_point(
+            398.8,
+            114.23,
+            None,
+            CharacterizationCorrelation::Twu,
+        )?;
+        assert_eq!(octane.molarweight, 114.23);
+        assert_relative_eq!(octane.specific_gravity, 0.7068, max_relative = 0.1);
+        assert_relative_eq!(octane.tc, 568.7, max_relative = 0.05);
+        assert_relative_eq!(octane.pc, 24.9e5, max_relative = 0.25);
+        assert_relative_eq!(octane.acentric_factor, 0.399, max_relative = 0.25);
+
+        // with the liquid density, the results equal the characterization from Tb and SG
+        let octane = PseudoComponent::from_boiling_point(
+            398.8,
+            114.23,
+            Some(706.1),
+            CharacterizationCorrelation::Twu,
+        )?;
+        let reference =
+            PseudoComponent::new(398.8, 706.1 / 999.016, CharacterizationCorrelation::Twu)?;
+        assert_eq!(octane.tc, reference.tc);
+        assert_eq!(octane.acentric_factor, reference.acentric_factor);
+
+        let record = octane.pure_record("n-octane", PenelouxCorrelation::PengRobinson);
+        assert_eq!(record.molarweight, 114.23);
+        assert_eq!(
+            record.model_record.volume_shift,
+            Some(octane.volume_shift(PenelouxCorrelation::PengRobinson))
+        );
+        // SRK overestimates liquid volumes more than PR
+        let c_srk = octane.volume_shift(PenelouxCorrelation::SoaveRedlichKwong);
+        let c_pr = octane.volume_shift(PenelouxCorrelation::PengRobinson);
+        assert!(c_srk > c_pr && c_pr > 0.0);
+        assert!(record.metadata().unwrap().estimated);
+        assert!(
+            PseudoComponent::from_boiling_point(
+                398.8,
+                -1.0,
+                None,
+                CharacterizationCorrelation::Twu
+            )
+            .is_err()
+        );
+        Ok(())
+    }
+}
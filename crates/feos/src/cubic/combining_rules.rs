@@ -0,0 +1,230 @@
+use ndarray::{Array1, Array2};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+
+/// Combining rule for a cross parameter from the parameters of two components.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum CombiningRule {
+    /// Geometric mean: $\sqrt{x_ix_j}$
+    Geometric,
+    /// Arithmetic mean: $\frac{x_i+x_j}{2}$
+    Arithmetic,
+    /// Harmonic mean: $\frac{2x_ix_j}{x_i+x_j}$
+    Harmonic,
+    /// Lorentz rule for volumes: $\left(\frac{x_i^{1/3}+x_j^{1/3}}{2}\right)^3$
+    Lorentz,
+    /// User defined combining rule (cannot be serialized).
+    #[serde(skip)]
+    Custom(Arc<dyn Fn(f64, f64) -> f64 + Send + Sync>),
+}
+
+impl CombiningRule {
+    /// User defined combining rule.
+    pub fn custom<F: Fn(f64, f64) -> f64 + Send + Sync + 'static>(f: F) -> Self {
+        Self::Custom(Arc::new(f))
+    }
+
+    /// Combine the parameters of two components.
+    pub fn combine(&self, x_i: f64, x_j: f64) -> f64 {
+        match self {
+            Self::Geometric => (x_i * x_j).sqrt(),
+            Self::Arithmetic => 0.5 * (x_i + x_j),
+            Self::Harmonic => 2.0 * x_i * x_j / (x_i + x_j),
+            Self::Lorentz => (0.5 * (x_i.cbrt() + x_j.cbrt())).powi(3),
+            Self::Custom(f) => f(x_i, x_j),
+        }
+    }
+
+    /// Matrix of the combined parameters of all pairs of components.
+    pub fn matrix(&self, parameters: &Array1<f64>) -> Array2<f64> {
+        let n = parameters.len();
+        Array2::from_shape_fn((n, n), |(i, j)| self.combine(parameters[i], parameters[j]))
+    }
+}
+
+impl fmt::Debug for CombiningRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Geometric => write!(f, "Geometric"),
+            Self::Arithmetic => write!(f, "Arithmetic"),
+            Self::Harmonic => write!(f, "Harmonic"),
+            Self::Lorentz => write!(f, "Lorentz"),
+            Self::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// Combining rules for the cross parameters of the quadratic mixing rule.
+///
+/// The cross energy parameter is
+/// $a_{ij}(T)=a_{c,ij}\sqrt{\alpha_i(T)\alpha_j(T)}\left(1-k_{ij}\right)$,
+/// where $a_{c,ij}$ is obtained from the critical energy parameters of the
+/// components with the combining rule for $a$. The temperature dependence
+/// is always combined geometrically. The cross co-volume is
+/// $b_{ij}=b_{c,ij}\left(1-l_{ij}\right)$ with the combining rule for $b$.
+///
+/// The default corresponds to the classical van der Waals one-fluid mixing
+/// rule with the geometric mean for $a$ and the arithmetic mean for $b$.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CombiningRules {
+    /// Combining rule for the energy parameter
+    #[serde(default = "CombiningRules::default_a")]
+    pub a: CombiningRule,
+    /// Combining rule for the co-volume
+    #[serde(default = "CombiningRules::default_b")]
+    pub b: CombiningRule,
+}
+
+impl Default for CombiningRules {
+    fn default() -> Self {
+        Self::new(Self::default_a(), Self::default_b())
+    }
+}
+
+impl CombiningRules {
+    fn default_a() -> CombiningRule {
+        CombiningRule::Geometric
+    }
+
+    fn default_b() -> CombiningRule {
+        CombiningRule::Arithmetic
+    }
+
+    /// Combining rules for the energy parameter and the co-volume.
+    pub fn new(a: CombiningRule, b: CombiningRule) -> Self {
+        Self { a, b }
+    }
+
+    /// Lorentz-Berthelot type rules with the geometric mean for $a$ and the
+    /// Lorentz rule for $b$, i.e., the arithmetic mean of the molecular
+    /// diameters.
+    pub fn lorentz_berthelot() -> Self {
+        Self::new(CombiningRule::Geometric, CombiningRule::Lorentz)
+    }
+
+    /// Returns `true` for the rules of the van der Waals one-fluid mixing rule.
+    pub fn is_van_der_waals(&self) -> bool {
+        matches!(
+            (&self.a, &self.b),
+            (CombiningRule::Geometric, CombiningRule::Arithmetic)
+        )
+    }
+
+    /// Ratio of the combined critical energy parameters and their geometric mean.
+    pub(super) fn attraction_factors(&self, ac: &Array1<f64>) -> Array2<f64> {
+        let mut factors = self.a.matrix(ac);
+        for ((i, j), f) in factors.indexed_iter_mut() {
+            *f /= (ac[i] * ac[j]).sqrt();
+        }
+        factors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::mixing_rules::{MixingRuleFunction, MixtureParameters};
+    use crate::cubic::{
+        Cubic, CubicBinaryRecord, CubicParameter, CubicParameters, CubicRecord, Quadratic,
+    };
+    use approx::assert_relative_eq;
+    use feos_core::parameter::{Identifier, Parameter, PureRecord};
+    use feos_core::{DensityInitialization, FeosResult, State, StateHD};
+    use ndarray::arr1;
+    use quantity::{BAR, KELVIN, MOL};
+
+    #[test]
+    fn combining_rules() -> FeosResult<()> {
+        assert_eq!(CombiningRule::Geometric.combine(2.0, 8.0), 4.0);
+        assert_eq!(CombiningRule::Arithmetic.combine(2.0, 8.0), 5.0);
+        assert_eq!(CombiningRule::Harmonic.combine(2.0, 8.0), 3.2);
+        assert_relative_eq!(CombiningRule::Lorentz.combine(1.0, 8.0), 3.375);
+
+        // rules are stored in and read from json
+        let rules: CombiningRules = serde_json::from_str(r#"{"b": "lorentz"}"#)?;
+        assert!(matches!(rules.a, CombiningRule::Geometric));
+        assert!(matches!(rules.b, CombiningRule::Lorentz));
+        let json = serde_json::to_string(&CombiningRules::default())?;
+        assert_eq!(json, r#"{"a":"geometric","b":"arithmetic"}"#);
+        let rules: CombiningRules = serde_json::from_str(&json)?;
+        assert!(rules.is_van_der_waals());
+        let custom = CombiningRules::new(CombiningRule::custom(f64::max), CombiningRule::Lorentz);
+        assert!(serde_json::to_string(&custom).is_err());
+
+        // mixture parameters of a binary mixture
+        let record = |name, tc, pc, w| {
+            let id = Identifier::new(None, Some(name), None, None, None, None);
+            PureRecord::new(id, 1.0, CubicRecord::new(tc, pc, w))
+        };
+        let records = vec![
+            record("methane", 190.56, 4599000.0, 0.011),
+            record("decane", 617.7, 2110000.0, 0.49),
+        ];
+        let k_ij = ndarray::Array2::from_shape_fn((2, 2), |(i, j)| {
+            CubicBinaryRecord::new(Some(if i != j { 0.05 } else { 0.0 }), None)
+        });
+        let parameters = Arc::new(CubicParameters::from_records(records, Some(k_ij))?);
+        let mixture = |rules: CombiningRules| -> FeosResult<_> {
+            let mixing = Quadratic::new(rules);
+            let eos = Cubic::peng_robinson(parameters.clone(), None, Some(mixing.into()))?;
+            let state = StateHD::new(300.0, 1e3, arr1(&[0.4, 0.6]));
+            let MixtureParameters { a, b, .. } = eos.options.mixing.apply(&eos, &state);
+            Ok((a, b, eos))
+        };
+        let (a_vdw, b_vdw, eos) = mixture(CombiningRules::default())?;
+        let at = eos.attractive_parameters(300.0);
+        let bc = &eos.critical_parameters.bc;
+        let (x0, x1) = (0.4, 0.6);
+        assert_relative_eq!(
+            a_vdw,
+            x0 * x0 * at[0] + x1 * x1 * at[1] + 2.0 * x0 * x1 * (at[0] * at[1]).sqrt() * 0.95,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(b_vdw, x0 * bc[0] + x1 * bc[1], max_relative = 1e-12);
+
+        let (a_lb, b_lb, _) = mixture(CombiningRules::lorentz_berthelot())?;
+        assert_relative_eq!(a_lb, a_vdw, max_relative = 1e-12);
+        let b_01 = CombiningRule::Lorentz.combine(bc[0], bc[1]);
+        assert_relative_eq!(
+            b_lb,
+            x0 * x0 * bc[0] + x1 * x1 * bc[1] + 2.0 * x0 * x1 * b_01,
+            max_relative = 1e-12
+        );
+        assert!(b_lb < b_vdw);
+
+        // the arithmetic mean of the critical energy parameters
+        let rules = CombiningRules::new(CombiningRule::Arithmetic, CombiningRule::Arithmetic);
+        let (a_ar, b_ar, eos) = mixture(rules)?;
+        let ac = &eos.critical_parameters.ac;
+        let a_01 = 0.5 * (ac[0] + ac[1]) * (at[0] * at[1] / (ac[0] * ac[1])).sqrt() * 0.95;
+        assert_relative_eq!(
+            a_ar,
+            x0 * x0 * at[0] + x1 * x1 * at[1] + 2.0 * x0 * x1 * a_01,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(b_ar, b_vdw, max_relative = 1e-12);
+
+        // user defined rules that reproduce the named rules
+        let rules = CombiningRules::new(
+            CombiningRule::custom(|a, b| 0.5 * (a + b)),
+            CombiningRule::custom(|a, b| (0.5 * (a.cbrt() + b.cbrt())).powi(3)),
+        );
+        let (a_custom, b_custom, eos) = mixture(rules)?;
+        assert_relative_eq!(a_custom, a_ar, max_relative = 1e-12);
+        assert_relative_eq!(b_custom, b_lb, max_relative = 1e-12);
+
+        // parameter sensitivities require the van der Waals rules
+        let moles = arr1(&[0.4, 0.6]) * MOL;
+        let state = State::new_npt(
+            &Arc::new(eos),
+            300.0 * KELVIN,
+            BAR,
+            &moles,
+            DensityInitialization::None,
+        )?;
+        assert!(Cubic::density_sensitivities(&state, &[CubicParameter::Kij(0, 1)]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+use super::Cubic;
+use feos_core::{FeosResult, State};
+use ndarray::Array1;
+use quantity::*;
+
+/// Upper limit of the reduced density of the viscosity correlation of Lohrenz, Bray and Clark.
+const MAX_REDUCED_DENSITY_VISCOSITY: f64 = 3.0;
+/// Upper limit of the reduced density of the thermal conductivity correlation of Stiel and Thodos.
+const MAX_REDUCED_DENSITY_THERMAL_CONDUCTIVITY: f64 = 2.8;
+
+/// Method used to calculate a transport property.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransportQuality {
+    /// Entropy scaling with fitted coefficients for all components.
+    EntropyScaling,
+    /// Corresponding states correlation within its range of validity.
+    CorrespondingStates,
+    /// Corresponding states correlation at reduced densities above its range of validity.
+    Extrapolated,
+}
+
+/// A transport property together with the method used to calculate it.
+#[derive(Clone, Copy, Debug)]
+pub struct TransportProperty<T> {
+    pub value: T,
+    pub quality: TransportQuality,
+}
+
+/// Pseudo-critical properties of a mixture.
+struct PseudoCritical {
+    /// Temperature in K.
+    tc: f64,
+    /// Pressure in Pa.
+    pc: f64,
+    /// Compressibility factor.
+    zc: f64,
+    /// Molar weight in g/mol.
+    molarweight: f64,
+}
+
+impl PseudoCritical {
+    /// Critical volume in m³/mol.
+    fn volume(&self) -> f64 {
+        self.zc * RGAS.convert_to(JOULE / MOL / KELVIN) * self.tc / self.pc
+    }
+}
+
+impl Cubic {
+    /// Pseudo-critical properties from the van der Waals one-fluid mixing rules
+    /// for the critical attraction and covolume of the equation of state.
+    ///
+    /// Using $a_{c,i}\propto T_{c,i}^2/p_{c,i}$ and $b_i\propto T_{c,i}/p_{c,i}$
+    /// with the binary interaction parameters of the model, the pseudo-critical
+    /// temperature and pressure are $T_{c,m}=a_{c,m}/b_m$ and $p_{c,m}=T_{c,m}/b_m$.
+    fn pseudo_critical(&self, x: &Array1<f64>) -> PseudoCritical {
+        let p = &self.parameters;
+        let a = (&p.tc * &p.tc / &p.pc).mapv(f64::sqrt);
+        let b = &p.tc / &p.pc;
+        let n = x.len();
+        let (mut a_mix, mut b_mix) = (0.0, 0.0);
+        for i in 0..n {
+            for j in 0..n {
+                a_mix += x[i] * x[j] * a[i] * a[j] * p.a_ij_factor[[i, j]];
+                b_mix += x[i] * x[j] * 0.5 * (b[i] + b[j]) * p.b_ij_factor[[i, j]];
+            }
+        }
+        let tc = a_mix / b_mix;
+        PseudoCritical {
+            tc,
+            pc: tc / b_mix,
+            zc: 0.291 - 0.080 * x.dot(&p.acentric_factor),
+            molarweight: x.dot(&p.molarweight),
+        }
+    }
+
+    /// Viscosity from the corresponding states correlation of Lohrenz, Bray
+    /// and Clark for the residual viscosity.
+    ///
+    /// The correlation of Jossi, Stiel and Thodos for a reference fluid is
+    /// evaluated at the reduced density $\rho/\rho_{c,m}$ with the density
+    /// from the equation of state and the pseudo-critical volume of the mixture.
+    /// The dilute gas contribution is the reference viscosity used for entropy
+    /// scaling. Instead of the constant $10^{-4}$ of the original correlation,
+    /// the value of the polynomial at zero density is subtracted, so that the
+    /// residual viscosity vanishes in the dilute gas limit.
+    ///
+    /// See https://doi.org/10.2118/915-PA
+    pub fn corresponding_states_viscosity(
+        state: &State<Self>,
+    ) -> FeosResult<TransportProperty<Viscosity>> {
+        let critical = state.eos.pseudo_critical(&state.molefracs);
+        let rho_r = state.density.convert_to(MOL / METER.powi::<typenum::P3>()) * critical.volume();
+        let xi = critical.tc.powf(1.0 / 6.0)
+            / critical.molarweight.sqrt()
+            / (critical.pc / 101325.0).powf(2.0 / 3.0);
+        let poly = |rho_r: f64| {
+            0.1023
+                + rho_r * (0.023364 + rho_r * (0.058533 + rho_r * (-0.040758 + rho_r * 0.0093324)))
+        };
+        let eta_res = (poly(rho_r).powi(4) - poly(0.0).powi(4)) / xi * 1e-3 * PASCAL * SECOND;
+        let eta_0 = state.viscosity_reference()?;
+        Ok(TransportProperty {
+            value: eta_0 + eta_res,
+            quality: Self::corresponding_states_quality(rho_r, MAX_REDUCED_DENSITY_VISCOSITY),
+        })
+    }
+
+    /// Thermal conductivity from the corresponding states correlation of
+    /// Stiel and Thodos for the residual thermal conductivity.
+    ///
+    /// The correlation is evaluated at the reduced density $\rho/\rho_{c,m}$
+    /// with the density from the equation of state and the pseudo-critical
+    /// properties of the mixture. The dilute gas contribution is the reference
+    /// thermal conductivity used for entropy scaling.
+    ///
+    /// See https://doi.org/10.1002/aic.690100114
+    pub fn corresponding_states_thermal_conductivity(
+        state: &State<Self>,
+    ) -> FeosResult<TransportProperty<ThermalConductivity>> {
+        let critical = state.eos.pseudo_critical(&state.molefracs);
+        let rho_r = state.density.convert_to(MOL / METER.powi::<typenum::P3>()) * critical.volume();
+        let gamma = 210.0
+            * (critical.tc * critical.molarweight.powi(3) / (critical.pc * 1e-5).powi(4))
+                .powf(1.0 / 6.0);
+        let f = if rho_r < 0.5 {
+            1.22e-2 * ((0.535 * rho_r).exp() - 1.0)
+        } else if rho_r < 2.0 {
+            1.14e-2 * ((0.67 * rho_r).exp() - 1.069)
+        } else {
+            2.60e-3 * ((1.155 * rho_r).exp() + 2.016)
+        };
+        let lambda_res = f / (gamma * critical.zc.powi(5)) * WATT / METER / KELVIN;
+        let lambda_0 = state.thermal_conductivity_reference()?;
+        Ok(TransportProperty {
+            value: lambda_0 + lambda_res,
+            quality: Self::corresponding_states_quality(
+                rho_r,
+                MAX_REDUCED_DENSITY_THERMAL_CONDUCTIVITY,
+            ),
+        })
+    }
+
+    fn corresponding_states_quality(reduced_density: f64, max: f64) -> TransportQuality {
+        if reduced_density > max {
+            TransportQuality::Extrapolated
+        } else {
+            TransportQuality::CorrespondingStates
+        }
+    }
+
+    /// Viscosity via entropy scaling if coefficients are available for all
+    /// components and via [Cubic::corresponding_states_viscosity] otherwise.
+    pub fn transport_viscosity(state: &State<Self>) -> FeosResult<TransportProperty<Viscosity>> {
+        if state.eos.parameters.viscosity.is_some() {
+            Ok(TransportProperty {
+                value: state.viscosity()?,
+                quality: TransportQuality::EntropyScaling,
+            })
+        } else {
+            Self::corresponding_states_viscosity(state)
+        }
+    }
+
+    /// Thermal conductivity via entropy scaling if coefficients are available
+    /// for all components and via [Cubic::corresponding_states_thermal_conductivity] otherwise.
+    pub fn transport_thermal_conductivity(
+        state: &State<Self>,
+    ) -> FeosResult<TransportProperty<ThermalConductivity>> {
+        if state.eos.parameters.thermal_conductivity.is_some() {
+            Ok(TransportProperty {
+                value: state.thermal_conductivity()?,
+                quality: TransportQuality::EntropyScaling,
+            })
+        } else {
+            Self::corresponding_states_thermal_conductivity(state)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{CubicParameters, CubicRecord};
+    use approx::assert_relative_eq;
+    use feos_core::parameter::{Identifier, Parameter, PureRecord};
+    use feos_core::{Components, DensityInitialization, FeosResult};
+    use ndarray::arr1;
+    use std::sync::Arc;
+
+    fn propane_butane() -> FeosResult<Cubic> {
+        let records = [
+            (369.96, 4250000.0, 0.153, 44.0962),
+            (425.2, 3800000.0, 0.199, 58.123),
+        ]
+        .map(|(tc, pc, omega, mw)| {
+            PureRecord::new(Identifier::default(), mw, CubicRecord::new(tc, pc, omega))
+        });
+        let parameters = Arc::new(CubicParameters::from_records(records.to_vec(), None)?);
+        Cubic::peng_robinson(parameters, None, None)
+    }
+
+    #[test]
+    fn fallback() -> FeosResult<()> {
+        let eos = Arc::new(propane_butane()?);
+        let propane = Arc::new(eos.subset(&[0]));
+        let t = 300.0 * KELVIN;
+        let n = arr1(&[1.0]) * MOL;
+
+        // saturated liquid propane: 0.098 mPa s and 0.094 W/m/K
+        let liquid = State::new_npt(&propane, t, 10.0 * BAR, &n, DensityInitialization::Liquid)?;
+        let eta = Cubic::transport_viscosity(&liquid)?;
+        assert_eq!(eta.quality, TransportQuality::CorrespondingStates);
+        assert!(eta.value > 0.05e-3 * PASCAL * SECOND && eta.value < 0.2e-3 * PASCAL * SECOND);
+        let lambda = Cubic::transport_thermal_conductivity(&liquid)?;
+        assert_eq!(lambda.quality, TransportQuality::CorrespondingStates);
+        assert!(lambda.value > 0.05 * WATT / METER / KELVIN);
+        assert!(lambda.value < 0.15 * WATT / METER / KELVIN);
+
+        // the residual contributions vanish in the dilute gas limit
+        let dilute = State::new_npt(&propane, t, 1e-3 * BAR, &n, DensityInitialization::Vapor)?;
+        assert_relative_eq!(
+            Cubic::transport_viscosity(&dilute)?.value,
+            dilute.viscosity_reference()?,
+            max_relative = 1e-3
+        );
+        assert_relative_eq!(
+            Cubic::transport_thermal_conductivity(&dilute)?.value,
+            dilute.thermal_conductivity_reference()?,
+            max_relative = 1e-3
+        );
+
+        // compressed liquid beyond the range of the correlations
+        let compressed = State::new_npt(
+            &propane,
+            200.0 * KELVIN,
+            5000.0 * BAR,
+            &n,
+            DensityInitialization::Liquid,
+        )?;
+        let eta = Cubic::transport_viscosity(&compressed)?;
+        assert_eq!(eta.quality, TransportQuality::Extrapolated);
+        assert!(eta.value > Cubic::transport_viscosity(&liquid)?.value);
+        Ok(())
+    }
+
+    #[test]
+    fn pure_limit() -> FeosResult<()> {
+        let eos = Arc::new(propane_butane()?);
+        let t = 300.0 * KELVIN;
+        let p = 20.0 * BAR;
+        for i in 0..2 {
+            let pure = Arc::new(eos.subset(&[i]));
+            let mut x = arr1(&[0.0, 0.0]);
+            x[i] = 1.0;
+            let mixture = State::new_npt(&eos, t, p, &(x * MOL), DensityInitialization::Liquid)?;
+            let pure = State::new_npt(
+                &pure,
+                t,
+                p,
+                &(arr1(&[1.0]) * MOL),
+                DensityInitialization::Liquid,
+            )?;
+            assert_relative_eq!(
+                Cubic::corresponding_states_viscosity(&mixture)?.value,
+                Cubic::corresponding_states_viscosity(&pure)?.value,
+                max_relative = 1e-12
+            );
+            assert_relative_eq!(
+                Cubic::corresponding_states_thermal_conductivity(&mixture)?.value,
+                Cubic::corresponding_states_thermal_conductivity(&pure)?.value,
+                max_relative = 1e-12
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn entropy_scaling() -> FeosResult<()> {
+        let record = PureRecord::new(
+            Identifier::default(),
+            44.0962,
+            CubicRecord::new(369.96, 4250000.0, 0.153)
+                .with_viscosity([0.0, -0.8, 0.1, -0.01])
+                .with_thermal_conductivity([0.0, -0.5, 0.2, 0.01]),
+        );
+        let parameters = Arc::new(CubicParameters::new_pure(record)?);
+        let eos = Arc::new(Cubic::peng_robinson(parameters, None, None)?);
+        let n = arr1(&[1.0]) * MOL;
+        let state = State::new_npt(&eos, 300.0 * KELVIN, BAR, &n, DensityInitialization::Vapor)?;
+        let eta = Cubic::transport_viscosity(&state)?;
+        assert_eq!(eta.quality, TransportQuality::EntropyScaling);
+        assert_eq!(eta.value, state.viscosity()?);
+        let lambda = Cubic::transport_thermal_conductivity(&state)?;
+        assert_eq!(lambda.quality, TransportQuality::EntropyScaling);
+        assert_eq!(lambda.value, state.thermal_conductivity()?);
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use super::Cubic;
+use feos_core::parameter::ValidationReport;
+use feos_core::{Components, Contributions, FeosResult, SolverOptions, State};
+use quantity::{Density, KELVIN, PASCAL, Pressure, Temperature};
+use std::sync::Arc;
+
+/// Critical point of a pure component implied by a cubic equation of state.
+#[derive(Clone, Copy, Debug)]
+pub struct ModelCriticalPoint {
+    pub temperature: Temperature,
+    pub pressure: Pressure,
+    pub density: Density,
+}
+
+impl Cubic {
+    /// Calculate the critical points of all pure components implied by the
+    /// equation of state.
+    ///
+    /// For modified cubics (e.g., alpha functions with $\alpha(T_c)\neq 1$)
+    /// the critical point of the model deviates from the critical temperature
+    /// and pressure in the pure records. Relative deviations larger than
+    /// `tolerance` are reported as warnings.
+    pub fn critical_points(
+        eos: &Arc<Self>,
+        tolerance: f64,
+        options: SolverOptions,
+    ) -> FeosResult<(Vec<ModelCriticalPoint>, ValidationReport)> {
+        let mut report = ValidationReport::new();
+        let mut critical_points = Vec::with_capacity(eos.components());
+        for i in 0..eos.components() {
+            let tc = eos.parameters.tc[i];
+            let pc = eos.parameters.pc[i];
+            let cp = State::critical_point(
+                &Arc::new(eos.subset(&[i])),
+                None,
+                Some(tc * KELVIN),
+                options,
+            )?;
+            let cp = ModelCriticalPoint {
+                temperature: cp.temperature,
+                pressure: cp.pressure(Contributions::Total),
+                density: cp.density,
+            };
+            let t = cp.temperature.convert_to(KELVIN);
+            let p = cp.pressure.convert_to(PASCAL);
+            if ((t - tc) / tc).abs() > tolerance {
+                report.warning(
+                    vec![i],
+                    format!("critical temperature of the model {t} K deviates from {tc} K"),
+                );
+            }
+            if ((p - pc) / pc).abs() > tolerance {
+                report.warning(
+                    vec![i],
+                    format!("critical pressure of the model {p} Pa deviates from {pc} Pa"),
+                );
+            }
+            critical_points.push(cp);
+        }
+        Ok((critical_points, report))
+    }
+}
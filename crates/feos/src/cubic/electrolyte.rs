@@ -0,0 +1,199 @@
+use super::parameters::CubicParameters;
+use feos_core::StateHD;
+use num_dual::DualNum;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+const EPSILON_0: f64 = 8.8541878128e-12;
+const QE: f64 = 1.602176634e-19;
+const BOLTZMANN: f64 = 1.380649e-23;
+
+/// Relative permittivity of the solvent used in the electrostatic contribution.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Permittivity {
+    /// Constant relative permittivity.
+    Constant(f64),
+    /// Relative permittivity of water from the correlation of Malmberg and Maryott (1956).
+    Water,
+}
+
+impl Permittivity {
+    fn relative_permittivity<D: DualNum<f64> + Copy>(&self, temperature: D) -> D {
+        match *self {
+            Self::Constant(epsilon_r) => D::one() * epsilon_r,
+            Self::Water => {
+                let t = temperature - 273.15;
+                t * (t * (t * -1.410e-6 + 9.398e-4) - 0.40008) + 87.740
+            }
+        }
+    }
+}
+
+/// Electrostatic contribution of Debye and Hückel for the ions in a cubic
+/// equation of state.
+///
+/// $$\frac{A^\mathrm{DH}}{k_BT}=-\frac{\kappa\lambda_B}{3}\sum_iN_iz_i^2\chi_i\quad\text{with}\quad\chi_i=\frac{3}{(\kappa d_i)^3}\left(\frac{3}{2}+\ln(1+\kappa d_i)-2(1+\kappa d_i)+\frac{1}{2}(1+\kappa d_i)^2\right)$$
+///
+/// The charge numbers $z_i$ and diameters $d_i$ of the ions are taken from
+/// the pure records. $\lambda_B$ is the Bjerrum length and $\kappa$ the
+/// inverse Debye length, which are calculated from the relative
+/// permittivity of the solvent.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct DebyeHuckel {
+    permittivity: Permittivity,
+}
+
+impl DebyeHuckel {
+    /// Debye-Hückel contribution with the given relative permittivity of the solvent.
+    pub fn new(permittivity: Permittivity) -> Self {
+        Self { permittivity }
+    }
+
+    /// Bjerrum length in units of Angstrom.
+    fn bjerrum_length<D: DualNum<f64> + Copy>(&self, temperature: D) -> D {
+        let epsilon_r = self.permittivity.relative_permittivity(temperature);
+        (epsilon_r * temperature * 4.0 * PI * EPSILON_0 * BOLTZMANN).recip() * QE.powi(2) * 1e10
+    }
+
+    pub(super) fn helmholtz_energy<D: DualNum<f64> + Copy>(
+        &self,
+        parameters: &CubicParameters,
+        state: &StateHD<D>,
+    ) -> D {
+        let z = &parameters.charge;
+        let lambda_b = self.bjerrum_length(state.temperature);
+        let rho_z2 = state
+            .partial_density
+            .iter()
+            .zip(z)
+            .fold(D::zero(), |acc, (&rho, &z)| acc + rho * z.powi(2));
+        if rho_z2.re() == 0.0 {
+            return D::zero();
+        }
+        let kappa = (lambda_b * rho_z2 * 4.0 * PI).sqrt();
+
+        let mut res = D::zero();
+        for ((&n, &z), &d) in state.moles.iter().zip(z).zip(&parameters.ion_diameter) {
+            if z != 0.0 {
+                let kd = kappa * d;
+                // use the series expansion for small arguments to avoid cancellation
+                let chi = if kd.re() < 1e-3 {
+                    kd * (kd * (kd * -0.5 + 0.6) - 0.75) + 1.0
+                } else {
+                    (kd.ln_1p() - kd + kd.powi(2) * 0.5) * 3.0 / kd.powi(3)
+                };
+                res += n * z.powi(2) * chi;
+            }
+        }
+        -kappa * lambda_b * res / 3.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{Cubic, CubicRecord};
+    use approx::assert_relative_eq;
+    use feos_core::parameter::{Identifier, Parameter, PureRecord};
+    use feos_core::{FeosResult, Residual, StateBuilder};
+    use ndarray::arr1;
+    use quantity::{BAR, KELVIN};
+    use std::sync::Arc;
+
+    fn water_nacl(diameter: f64) -> FeosResult<CubicParameters> {
+        let record = |name, mw, record| {
+            PureRecord::new(
+                Identifier::new(None, Some(name), None, None, None, None),
+                mw,
+                record,
+            )
+        };
+        CubicParameters::from_records(
+            vec![
+                record(
+                    "water",
+                    18.015,
+                    CubicRecord::new(647.096, 22064000.0, 0.3443),
+                ),
+                record(
+                    "sodium",
+                    22.99,
+                    CubicRecord::new(1.0, 22064000.0, 0.0).with_ion(1.0, diameter),
+                ),
+                record(
+                    "chloride",
+                    35.45,
+                    CubicRecord::new(1.0, 22064000.0, 0.0).with_ion(-1.0, diameter),
+                ),
+            ],
+            None,
+        )
+    }
+
+    #[test]
+    fn limiting_law() -> FeosResult<()> {
+        // for point charges the Debye-Hückel limiting law is recovered
+        let parameters = water_nacl(1e-6)?;
+        let dh = DebyeHuckel::new(Permittivity::Constant(78.4));
+        let state = StateHD::new(298.15, 1e6, arr1(&[0.98, 0.01, 0.01]));
+        let lambda_b = dh.bjerrum_length(298.15);
+        assert_relative_eq!(lambda_b, 7.1487, max_relative = 1e-4);
+        let kappa: f64 = (4.0 * PI * lambda_b * 0.02 / 1e6).sqrt();
+        assert_relative_eq!(
+            dh.helmholtz_energy(&parameters, &state),
+            -kappa.powi(3) * 1e6 / (12.0 * PI),
+            max_relative = 1e-8
+        );
+
+        let permittivity = Permittivity::Water.relative_permittivity(298.15);
+        assert_relative_eq!(permittivity, 78.3, max_relative = 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn cubic() -> FeosResult<()> {
+        let parameters = Arc::new(water_nacl(3.0)?);
+        let dh = DebyeHuckel::new(Permittivity::Water);
+        let cubic = Cubic::peng_robinson(parameters.clone(), None, None)?;
+        let electrolyte =
+            Cubic::peng_robinson(parameters.clone(), None, None)?.with_debye_huckel(dh)?;
+        let state = StateHD::new(298.15, 1000.0, arr1(&[30.0, 0.5, 0.5]));
+        let contributions = electrolyte.residual_helmholtz_energy_contributions(&state);
+        assert_eq!(contributions.len(), 2);
+        assert_eq!(contributions[0].1, cubic.residual_helmholtz_energy(&state));
+        assert_eq!(contributions[1].1, dh.helmholtz_energy(&parameters, &state));
+        assert!(contributions[1].1 < 0.0);
+        assert!(
+            electrolyte
+                .density_roots(298.15, 1e-3, &arr1(&[0.98, 0.01, 0.01]))
+                .is_none()
+        );
+
+        // mean ionic activity coefficients are lowered by the electrostatic contribution
+        let molefracs = arr1(&[0.98, 0.01, 0.01]);
+        let state = |eos| {
+            StateBuilder::new(&Arc::new(eos))
+                .temperature(298.15 * KELVIN)
+                .pressure(BAR)
+                .molefracs(&molefracs)
+                .liquid()
+                .build()
+                .map(|s| s.ln_phi())
+        };
+        let ln_phi = state(electrolyte)?;
+        let ln_phi_cubic = state(cubic)?;
+        assert!(ln_phi[1] + ln_phi[2] < ln_phi_cubic[1] + ln_phi_cubic[2]);
+
+        // without ions the contribution vanishes
+        let state = StateHD::new(298.15, 1000.0, arr1(&[30.0, 0.0, 0.0]));
+        assert_eq!(dh.helmholtz_energy(&parameters, &state), 0.0);
+        let water = Arc::new(parameters.subset(&[0]));
+        assert!(
+            Cubic::peng_robinson(water, None, None)?
+                .with_debye_huckel(dh)
+                .is_err()
+        );
+        Ok(())
+    }
+}
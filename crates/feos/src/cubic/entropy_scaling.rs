@@ -0,0 +1,395 @@
+use super::Cubic;
+use feos_core::{Components, EntropyScaling, FeosError, FeosResult, Molarweight, State};
+use ndarray::{Array1, Array2};
+use quantity::*;
+use std::f64::consts::PI;
+use typenum::P2;
+
+/// Collision integral $\Omega^{(1,1)*}$ of Neufeld et al.
+fn omega11(t: f64) -> f64 {
+    1.06036 * t.powf(-0.15610)
+        + 0.19300 * (-0.47635 * t).exp()
+        + 1.03587 * (-1.52996 * t).exp()
+        + 1.76474 * (-3.89411 * t).exp()
+}
+
+/// Collision integral $\Omega^{(2,2)*}$ of Neufeld et al.
+fn omega22(t: f64) -> f64 {
+    1.16145 * t.powf(-0.14874) + 0.52487 * (-0.77320 * t).exp() + 2.16178 * (-2.43787 * t).exp()
+}
+
+impl Cubic {
+    /// Critical volume in cm³/mol estimated from the acentric factor.
+    fn critical_volume(&self, i: usize) -> f64 {
+        let p = &self.parameters;
+        let zc = 0.291 - 0.080 * p.acentric_factor[i];
+        zc * RGAS.convert_to(JOULE / MOL / KELVIN) * p.tc[i] / p.pc[i] * 1e6
+    }
+
+    /// Dilute gas viscosity of Chung et al. for every component.
+    fn chung_viscosity(&self, temperature: Temperature) -> Viscosity<Array1<f64>> {
+        let p = &self.parameters;
+        let t = temperature.convert_to(KELVIN);
+        let eta = Array1::from_shape_fn(self.components(), |i| {
+            let tr = 1.2593 * t / p.tc[i];
+            let fc = 1.0 - 0.2756 * p.acentric_factor[i];
+            40.785 * fc * (p.molarweight[i] * t).sqrt()
+                / self.critical_volume(i).powf(2.0 / 3.0)
+                / omega22(tr)
+        });
+        eta * (1e-7 * PASCAL * SECOND)
+    }
+
+    /// Binary Maxwell-Stefan diffusion coefficients $\mathcal{D}_{ij}$ from kinetic theory.
+    ///
+    /// The Chapman-Enskog expression is evaluated at the given molar density
+    /// with Lennard-Jones parameters estimated from critical data
+    /// following Chung et al. The diagonal contains the kinetic theory
+    /// self-diffusion coefficients.
+    pub fn maxwell_stefan_diffusion(
+        &self,
+        temperature: Temperature,
+        density: Density,
+    ) -> Diffusivity<Array2<f64>> {
+        let p = &self.parameters;
+        let n = self.components();
+        let sigma = Array1::from_shape_fn(n, |i| 0.809 * self.critical_volume(i).cbrt());
+        let epsilon_k = &p.tc / 1.2593;
+        Diffusivity::from_shape_fn((n, n), |(i, j)| {
+            let sigma_ij = 0.5 * (sigma[i] + sigma[j]) * ANGSTROM;
+            let tr = temperature.convert_to(KELVIN) / (epsilon_k[i] * epsilon_k[j]).sqrt();
+            let mw_ij = 1.0 / (1.0 / p.molarweight[i] + 1.0 / p.molarweight[j]) * GRAM / MOL;
+            3.0 / 16.0 * (2.0 * RGAS * temperature / mw_ij / PI).sqrt()
+                / (density * NAV)
+                / sigma_ij.powi::<P2>()
+                / omega11(tr)
+        })
+    }
+
+    /// Fick diffusion coefficient of a binary mixture.
+    ///
+    /// The Maxwell-Stefan diffusion coefficient from kinetic theory
+    /// is corrected with the thermodynamic factor obtained from the
+    /// derivatives of the fugacity coefficients.
+    pub fn fick_diffusion(state: &State<Self>) -> FeosResult<Diffusivity> {
+        let n = state.eos.components();
+        if n != 2 {
+            return Err(FeosError::IncompatibleComponents(n, 2));
+        }
+        let d = state
+            .eos
+            .maxwell_stefan_diffusion(state.temperature, state.density);
+        Ok(d.get((0, 1)) * state.thermodynamic_factor()[[0, 0]])
+    }
+
+    /// Denominators of the mixing rule of Wilke for a property
+    /// weighted with the pure component viscosities.
+    fn wilke_denominators(&self, x: &Array1<f64>, eta: &Viscosity<Array1<f64>>) -> Array1<f64> {
+        let mw = &self.parameters.molarweight;
+        Array1::from_shape_fn(self.components(), |i| {
+            (0..self.components())
+                .map(|j| {
+                    x[j] * (1.0
+                        + (eta.get(i) / eta.get(j)).into_value().sqrt()
+                            * (mw[j] / mw[i]).powf(1.0 / 4.0))
+                    .powi(2)
+                        / (8.0 * (1.0 + mw[i] / mw[j])).sqrt()
+                })
+                .sum()
+        })
+    }
+}
+
+impl EntropyScaling for Cubic {
+    fn viscosity_reference(
+        &self,
+        temperature: Temperature,
+        _: Volume,
+        moles: &Moles<Array1<f64>>,
+    ) -> FeosResult<Viscosity> {
+        let x = (moles / moles.sum()).into_value();
+        let eta = self.chung_viscosity(temperature);
+        let denom = self.wilke_denominators(&x, &eta);
+        Ok((eta * Dimensionless::new(x / denom)).sum())
+    }
+
+    fn viscosity_correlation(&self, s_res: f64, x: &Array1<f64>) -> FeosResult<f64> {
+        let coefficients = self.parameters.viscosity.as_ref().ok_or_else(|| {
+            FeosError::IncompatibleParameters(String::from("missing viscosity coefficients."))
+        })?;
+        let a: f64 = (&coefficients.row(0) * x).sum();
+        let b: f64 = (&coefficients.row(1) * x).sum();
+        let c: f64 = (&coefficients.row(2) * x).sum();
+        let d: f64 = (&coefficients.row(3) * x).sum();
+        Ok(a + b * s_res + c * s_res.powi(2) + d * s_res.powi(3))
+    }
+
+    fn diffusion_reference(
+        &self,
+        temperature: Temperature,
+        volume: Volume,
+        moles: &Moles<Array1<f64>>,
+    ) -> FeosResult<Diffusivity> {
+        if self.components() != 1 {
+            return Err(FeosError::IncompatibleComponents(self.components(), 1));
+        }
+        let density = moles.sum() / volume;
+        Ok(self
+            .maxwell_stefan_diffusion(temperature, density)
+            .get((0, 0)))
+    }
+
+    fn diffusion_correlation(&self, s_res: f64, x: &Array1<f64>) -> FeosResult<f64> {
+        if self.components() != 1 {
+            return Err(FeosError::IncompatibleComponents(self.components(), 1));
+        }
+        let coefficients = self.parameters.diffusion.as_ref().ok_or_else(|| {
+            FeosError::IncompatibleParameters(String::from("missing diffusion coefficients."))
+        })?;
+        let a: f64 = (&coefficients.row(0) * x).sum();
+        let b: f64 = (&coefficients.row(1) * x).sum();
+        let c: f64 = (&coefficients.row(2) * x).sum();
+        let d: f64 = (&coefficients.row(3) * x).sum();
+        let e: f64 = (&coefficients.row(4) * x).sum();
+        Ok(a + b * s_res
+            - c * (1.0 - s_res.exp()) * s_res.powi(2)
+            - d * s_res.powi(4)
+            - e * s_res.powi(8))
+    }
+
+    // Eucken relation for monatomic gases combined with the mixing rule of Mason and Saxena
+    fn thermal_conductivity_reference(
+        &self,
+        temperature: Temperature,
+        _: Volume,
+        moles: &Moles<Array1<f64>>,
+    ) -> FeosResult<ThermalConductivity> {
+        let x = (moles / moles.sum()).into_value();
+        let eta = self.chung_viscosity(temperature);
+        let denom = self.wilke_denominators(&x, &eta);
+        let lambda = &eta * RGAS / self.molar_weight() * 3.75;
+        Ok((lambda * Dimensionless::new(x / denom)).sum())
+    }
+
+    fn thermal_conductivity_correlation(&self, s_res: f64, x: &Array1<f64>) -> FeosResult<f64> {
+        let coefficients = self
+            .parameters
+            .thermal_conductivity
+            .as_ref()
+            .ok_or_else(|| {
+                FeosError::IncompatibleParameters(String::from(
+                    "missing thermal conductivity coefficients.",
+                ))
+            })?;
+        let a: f64 = (&coefficients.row(0) * x).sum();
+        let b: f64 = (&coefficients.row(1) * x).sum();
+        let c: f64 = (&coefficients.row(2) * x).sum();
+        let d: f64 = (&coefficients.row(3) * x).sum();
+        Ok(a + b * s_res + c * (1.0 - s_res.exp()) + d * s_res.powi(2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{CubicParameters, CubicRecord};
+    use approx::assert_relative_eq;
+    use feos_core::parameter::{Identifier, Parameter, PureRecord};
+    use feos_core::{DensityInitialization, State};
+    use ndarray::arr1;
+    use std::sync::Arc;
+
+    fn propane(viscosity: [f64; 4]) -> Cubic {
+        let record = PureRecord::new(
+            Identifier::new(None, Some("propane"), None, None, None, None),
+            44.0962,
+            CubicRecord::new(369.96, 4250000.0, 0.153)
+                .with_viscosity(viscosity)
+                .with_diffusion([0.0, 0.5, 0.1, 0.01, 0.0])
+                .with_thermal_conductivity([0.0, -0.5, 0.2, 0.01]),
+        );
+        let parameters = Arc::new(CubicParameters::new_pure(record).unwrap());
+        Cubic::peng_robinson(parameters, None, None).unwrap()
+    }
+
+    #[test]
+    fn viscosity() -> FeosResult<()> {
+        let eos = Arc::new(propane([0.0, -0.8, 0.1, -0.01]));
+        let n = arr1(&[1.0]) * MOL;
+        let vapor = State::new_npt(&eos, 300.0 * KELVIN, BAR, &n, DensityInitialization::Vapor)?;
+        assert_relative_eq!(
+            vapor.viscosity_reference()?,
+            8.2906e-6 * PASCAL * SECOND,
+            max_relative = 1e-4
+        );
+        assert_relative_eq!(
+            vapor.ln_viscosity_reduced()?,
+            (vapor.viscosity()? / vapor.viscosity_reference()?)
+                .into_value()
+                .ln(),
+            epsilon = 1e-14
+        );
+        let liquid = State::new_npt(
+            &eos,
+            300.0 * KELVIN,
+            20.0 * BAR,
+            &n,
+            DensityInitialization::Liquid,
+        )?;
+        assert!(liquid.viscosity()? > 10.0 * vapor.viscosity()?);
+        Ok(())
+    }
+
+    #[test]
+    fn thermal_conductivity() -> FeosResult<()> {
+        let eos = Arc::new(propane([0.0, -0.8, 0.1, -0.01]));
+        let n = arr1(&[1.0]) * MOL;
+        let vapor = State::new_npt(&eos, 300.0 * KELVIN, BAR, &n, DensityInitialization::Vapor)?;
+        assert_relative_eq!(
+            vapor.thermal_conductivity_reference()?,
+            vapor.viscosity_reference()? * RGAS / (44.0962 * GRAM / MOL) * 3.75,
+            max_relative = 1e-14
+        );
+        assert_relative_eq!(
+            vapor.ln_thermal_conductivity_reduced()?,
+            (vapor.thermal_conductivity()? / vapor.thermal_conductivity_reference()?)
+                .into_value()
+                .ln(),
+            epsilon = 1e-14
+        );
+        let liquid = State::new_npt(
+            &eos,
+            300.0 * KELVIN,
+            20.0 * BAR,
+            &n,
+            DensityInitialization::Liquid,
+        )?;
+        assert!(liquid.thermal_conductivity()? > vapor.thermal_conductivity()?);
+        Ok(())
+    }
+
+    #[test]
+    fn mixture_reference() -> FeosResult<()> {
+        let records = [
+            (369.96, 4250000.0, 0.153, 44.0962),
+            (425.2, 3800000.0, 0.199, 58.123),
+        ]
+        .map(|(tc, pc, omega, mw)| {
+            PureRecord::new(Identifier::default(), mw, CubicRecord::new(tc, pc, omega))
+        });
+        let parameters = Arc::new(CubicParameters::from_records(records.to_vec(), None)?);
+        let eos = Arc::new(Cubic::peng_robinson(parameters, None, None)?);
+        let t = 350.0 * KELVIN;
+        let v = 1.0 * METER.powi::<typenum::P3>();
+        for i in 0..2 {
+            let pure = Arc::new(eos.subset(&[i]));
+            let n = arr1(&[1.0]) * MOL;
+            let mut x = arr1(&[0.0, 0.0]);
+            x[i] = 1.0;
+            assert_relative_eq!(
+                eos.thermal_conductivity_reference(t, v, &(x * MOL))?,
+                pure.thermal_conductivity_reference(t, v, &n)?,
+                max_relative = 1e-14
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn diffusion() -> FeosResult<()> {
+        let eos = Arc::new(propane([0.0, -0.8, 0.1, -0.01]));
+        let n = arr1(&[1.0]) * MOL;
+        let vapor = State::new_npt(&eos, 300.0 * KELVIN, BAR, &n, DensityInitialization::Vapor)?;
+        assert_relative_eq!(
+            vapor.ln_diffusion_reduced()?,
+            (vapor.diffusion()? / vapor.diffusion_reference()?)
+                .into_value()
+                .ln(),
+            epsilon = 1e-14
+        );
+        // dilute gas limit: rho * D is independent of the density
+        let dilute = State::new_npt(
+            &eos,
+            300.0 * KELVIN,
+            0.01 * BAR,
+            &n,
+            DensityInitialization::Vapor,
+        )?;
+        assert_relative_eq!(
+            dilute.diffusion_reference()? * dilute.density,
+            vapor.diffusion_reference()? * vapor.density,
+            max_relative = 1e-12
+        );
+        let liquid = State::new_npt(
+            &eos,
+            300.0 * KELVIN,
+            20.0 * BAR,
+            &n,
+            DensityInitialization::Liquid,
+        )?;
+        assert!(liquid.diffusion()? < 0.01 * vapor.diffusion()?);
+        Ok(())
+    }
+
+    #[test]
+    fn binary_diffusion() -> FeosResult<()> {
+        let records = [
+            (369.96, 4250000.0, 0.153, 44.0962),
+            (425.2, 3800000.0, 0.199, 58.123),
+        ]
+        .map(|(tc, pc, omega, mw)| {
+            PureRecord::new(Identifier::default(), mw, CubicRecord::new(tc, pc, omega))
+        });
+        let parameters = Arc::new(CubicParameters::from_records(records.to_vec(), None)?);
+        let eos = Arc::new(Cubic::peng_robinson(parameters, None, None)?);
+        let t = 300.0 * KELVIN;
+        let d = eos.maxwell_stefan_diffusion(t, 40.0 * MOL / METER.powi::<typenum::P3>());
+        assert_relative_eq!(d.get((0, 1)), d.get((1, 0)));
+        assert!(d.get((0, 0)) > d.get((0, 1)) && d.get((0, 1)) > d.get((1, 1)));
+
+        // the thermodynamic factor approaches unity in the ideal gas limit
+        let n = arr1(&[0.4, 0.6]) * MOL;
+        let dilute = State::new_npt(&eos, t, 1e-3 * BAR, &n, DensityInitialization::Vapor)?;
+        assert_relative_eq!(
+            Cubic::fick_diffusion(&dilute)?,
+            eos.maxwell_stefan_diffusion(t, dilute.density).get((0, 1)),
+            max_relative = 1e-5
+        );
+        let liquid = State::new_npt(&eos, t, 20.0 * BAR, &n, DensityInitialization::Liquid)?;
+        assert_relative_eq!(
+            Cubic::fick_diffusion(&liquid)?,
+            eos.maxwell_stefan_diffusion(t, liquid.density).get((0, 1))
+                * liquid.thermodynamic_factor()[[0, 0]],
+            max_relative = 1e-14
+        );
+        assert!(
+            Cubic::fick_diffusion(&State::new_npt(
+                &Arc::new(eos.subset(&[0])),
+                t,
+                BAR,
+                &(arr1(&[1.0]) * MOL),
+                DensityInitialization::Vapor,
+            )?)
+            .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_coefficients() {
+        let record = PureRecord::new(
+            Identifier::default(),
+            44.0962,
+            CubicRecord::new(369.96, 4250000.0, 0.153),
+        );
+        let parameters = Arc::new(CubicParameters::new_pure(record).unwrap());
+        let eos = Arc::new(Cubic::peng_robinson(parameters, None, None).unwrap());
+        let n = arr1(&[1.0]) * MOL;
+        let state =
+            State::new_npt(&eos, 300.0 * KELVIN, BAR, &n, DensityInitialization::Vapor).unwrap();
+        assert!(state.viscosity().is_err());
+        assert!(state.thermal_conductivity().is_err());
+        assert!(state.diffusion().is_err());
+        assert!(state.viscosity_reference().is_ok());
+    }
+}
@@ -0,0 +1,151 @@
+use feos_core::parameter::Identifier;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+static DATABASE: LazyLock<KijDatabase> = LazyLock::new(|| KijDatabase {
+    records: serde_json::from_str(include_str!("kij_database.json"))
+        .expect("the embedded kij database is valid json"),
+});
+
+/// Binary interaction parameters of a pair of components in the
+/// [embedded database](KijDatabase).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KijDatabaseRecord {
+    /// Identifier of the first component
+    pub id1: Identifier,
+    /// Identifier of the second component
+    pub id2: Identifier,
+    /// Binary interaction parameter for the Peng-Robinson equation of state
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peng_robinson: Option<f64>,
+    /// Binary interaction parameter for the Soave-Redlich-Kwong equation of state
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redlich_kwong: Option<f64>,
+    /// Publication from which the parameters are taken
+    pub source: String,
+}
+
+impl KijDatabaseRecord {
+    fn matches(&self, id1: &Identifier, id2: &Identifier) -> bool {
+        let same = |a: &Identifier, b: &Identifier| match (&a.cas, &b.cas) {
+            (Some(a), Some(b)) => a == b,
+            _ => a.name.is_some() && a.name == b.name,
+        };
+        (same(&self.id1, id1) && same(&self.id2, id2))
+            || (same(&self.id1, id2) && same(&self.id2, id1))
+    }
+}
+
+/// Database of binary interaction parameters $k_{ij}$ for common pairs of
+/// light gases, hydrocarbons, carbon dioxide, hydrogen sulfide, nitrogen and
+/// water for the Peng-Robinson and the Soave-Redlich-Kwong equation of state.
+///
+/// The database is embedded in the binary and used by the
+/// [CubicBuilder](super::CubicBuilder) for all pairs of components without
+/// user supplied binary parameters. Components are matched by their CAS
+/// number or, if the CAS number is not available, by their name. The values
+/// are used with the quadratic mixing rule and the default alpha functions.
+pub struct KijDatabase {
+    records: Vec<KijDatabaseRecord>,
+}
+
+impl KijDatabase {
+    /// The database embedded in the crate.
+    pub fn embedded() -> &'static Self {
+        &DATABASE
+    }
+
+    /// All records of the database.
+    pub fn records(&self) -> &[KijDatabaseRecord] {
+        &self.records
+    }
+
+    /// The record for the given pair of components, if available.
+    pub fn get(&self, id1: &Identifier, id2: &Identifier) -> Option<&KijDatabaseRecord> {
+        self.records.iter().find(|r| r.matches(id1, id2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{
+        CombiningRule, CombiningRules, CubicBinaryRecord, CubicBuilder, GeneralizedTwu,
+        PengRobinson1976, Quadratic,
+    };
+    use feos_core::parameter::BinaryRecord;
+    use feos_core::{FeosError, FeosResult};
+
+    const PURE: &str = "../../examples/peng-robinson.json";
+
+    #[test]
+    fn embedded_records() {
+        let database = KijDatabase::embedded();
+        let records = database.records();
+        assert!(records.len() > 20);
+        for (i, r) in records.iter().enumerate() {
+            assert!(r.peng_robinson.is_some() && r.redlich_kwong.is_some());
+            assert!(r.id1.cas.is_some() && r.id2.cas.is_some());
+            assert!(!r.source.is_empty());
+            assert!(records[..i].iter().all(|s| !s.matches(&r.id1, &r.id2)));
+        }
+
+        // pairs are symmetric and matched by CAS number or name
+        let name = |name| Identifier::new(None, Some(name), None, None, None, None);
+        let cas = |cas| Identifier::new(Some(cas), Some("x"), None, None, None, None);
+        let r = database
+            .get(&name("methane"), &name("carbon dioxide"))
+            .unwrap();
+        assert_eq!(r.peng_robinson, Some(0.0919));
+        let r = database.get(&cas("124-38-9"), &cas("74-82-8")).unwrap();
+        assert_eq!(r.redlich_kwong, Some(0.0933));
+        assert!(database.get(&name("methane"), &name("methanol")).is_none());
+    }
+
+    #[test]
+    fn builder() -> FeosResult<()> {
+        let builder = CubicBuilder::new()
+            .components(&["propane", "butane"])
+            .from_file(PURE);
+        let parameters = builder.build_parameters()?;
+        assert_eq!(parameters.k_ij[[0, 1]], 0.0033);
+        assert_eq!(parameters.k_ij[[1, 0]], 0.0033);
+        let applied = builder.database_kij()?;
+        assert_eq!(applied.len(), 1);
+        assert_eq!((applied[0].0, applied[0].1), (0, 1));
+        assert!(applied[0].2.source.contains("Knapp"));
+
+        let parameters = builder.clone().redlich_kwong().build_parameters()?;
+        assert_eq!(parameters.k_ij[[0, 1]], 0.0);
+
+        // user supplied values take precedence
+        let id = |name| Identifier::new(None, Some(name), None, None, None, None);
+        let binary = BinaryRecord::new(id("butane"), id("propane"), CubicBinaryRecord::default());
+        let user = builder.clone().binary_records(vec![binary]);
+        assert_eq!(user.build_parameters()?.k_ij[[0, 1]], 0.0);
+        assert!(user.database_kij()?.is_empty());
+
+        let parameters = builder.clone().without_kij_database().build_parameters()?;
+        assert_eq!(parameters.k_ij[[0, 1]], 0.0);
+
+        // the database is only used with the default alpha function and
+        // quadratic mixing
+        let parameters = builder.clone().alpha(PengRobinson1976).build_parameters()?;
+        assert_eq!(parameters.k_ij[[0, 1]], 0.0033);
+        let twu = builder.clone().alpha(GeneralizedTwu::peng_robinson());
+        assert!(matches!(
+            twu.build_parameters(),
+            Err(FeosError::IncompatibleParameters(e)) if e.contains("propane-butane")
+        ));
+        let parameters = twu.without_kij_database().build_parameters()?;
+        assert_eq!(parameters.k_ij[[0, 1]], 0.0);
+        let arithmetic = CombiningRules::new(CombiningRule::Arithmetic, CombiningRule::Arithmetic);
+        let mixing = Quadratic {
+            combining_rules: arithmetic,
+        };
+        assert!(builder.mixing(mixing).build().is_err());
+        Ok(())
+    }
+}
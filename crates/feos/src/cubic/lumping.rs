@@ -0,0 +1,259 @@
+use super::parameters::{CubicBinaryRecord, CubicParameters, CubicRecord};
+use feos_core::parameter::{Identifier, Parameter, PureRecord};
+use feos_core::{FeosError, FeosResult, PhaseEquilibrium, Residual, rachford_rice_compositions};
+use ndarray::{Array1, Array2};
+
+/// Lumping of a detailed component slate into pseudo-components.
+///
+/// Every pseudo-component consists of a group of detailed components. The
+/// properties of the pseudo-components are averaged with the mole fractions
+/// of the detailed components in a reference composition (usually the feed).
+#[derive(Clone, Debug)]
+pub struct Lumping {
+    /// indices of the detailed components in each pseudo-component
+    pub groups: Vec<Vec<usize>>,
+    /// mole fractions of the detailed components used for averaging and delumping
+    pub molefracs: Array1<f64>,
+}
+
+impl Lumping {
+    /// Create a new lumping scheme.
+    ///
+    /// Every detailed component has to be part of exactly one group and every
+    /// group needs a positive mole fraction.
+    pub fn new(groups: Vec<Vec<usize>>, molefracs: &Array1<f64>) -> FeosResult<Self> {
+        let n = molefracs.len();
+        let mut count = vec![0; n];
+        for &i in groups.iter().flatten() {
+            if i >= n {
+                return Err(FeosError::IncompatibleParameters(format!(
+                    "component {i} is not part of the detailed composition with {n} components."
+                )));
+            }
+            count[i] += 1;
+        }
+        if let Some(i) = count.iter().position(|&c| c != 1) {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "component {i} has to be part of exactly one pseudo-component."
+            )));
+        }
+        let molefracs = molefracs / molefracs.sum();
+        if let Some(g) = groups
+            .iter()
+            .position(|g| g.iter().map(|&i| molefracs[i]).sum::<f64>() <= 0.0)
+        {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "pseudo-component {g} has to have a positive mole fraction."
+            )));
+        }
+        Ok(Self { groups, molefracs })
+    }
+
+    /// Mole fractions of the detailed components normalized within each group.
+    fn weights(&self, group: &[usize]) -> Vec<(usize, f64)> {
+        let total: f64 = group.iter().map(|&i| self.molefracs[i]).sum();
+        group
+            .iter()
+            .map(|&i| (i, self.molefracs[i] / total))
+            .collect()
+    }
+
+    /// Calculate the parameters of the pseudo-components.
+    ///
+    /// Critical temperatures, critical pressures, acentric factors, molar weights
+    /// and (if available for all components of a group) parachors and volume shifts
+    /// are averaged with the mole fractions. The binary interaction parameters
+    /// between two pseudo-components are $k_{IJ}=\sum_{i\in I}\sum_{j\in J}w_iw_jk_{ij}$,
+    /// with the mole fractions $w_i$ normalized within each group. The name of a
+    /// pseudo-component combines the names of the detailed components.
+    pub fn parameters(&self, parameters: &CubicParameters) -> FeosResult<CubicParameters> {
+        let (pure_records, _) = parameters.records();
+        if pure_records.len() != self.molefracs.len() {
+            return Err(FeosError::IncompatibleComponents(
+                self.molefracs.len(),
+                pure_records.len(),
+            ));
+        }
+        let weights: Vec<_> = self.groups.iter().map(|g| self.weights(g)).collect();
+
+        let lumped_records = weights
+            .iter()
+            .map(|w| {
+                let average = |f: &dyn Fn(&PureRecord<CubicRecord>) -> f64| {
+                    w.iter().map(|&(i, w)| w * f(&pure_records[i])).sum::<f64>()
+                };
+                let average_option = |f: &dyn Fn(&CubicRecord) -> Option<f64>| {
+                    w.iter()
+                        .map(|&(i, w)| f(&pure_records[i].model_record).map(|v| w * v))
+                        .sum::<Option<f64>>()
+                };
+                let mut record = CubicRecord::new(
+                    average(&|r| r.model_record.tc),
+                    average(&|r| r.model_record.pc),
+                    average(&|r| r.model_record.acentric_factor),
+                );
+                record.parachor = average_option(&|r| r.parachor);
+                record.volume_shift = average_option(&|r| r.volume_shift);
+                let name = w
+                    .iter()
+                    .map(|&(i, _)| {
+                        let id = &pure_records[i].identifier;
+                        id.name.clone().or(id.cas.clone()).unwrap_or(i.to_string())
+                    })
+                    .collect::<Vec<_>>()
+                    .join("+");
+                let identifier = Identifier::new(None, Some(&name), None, None, None, None);
+                PureRecord::new(identifier, average(&|r| r.molarweight), record)
+            })
+            .collect();
+
+        let n = self.groups.len();
+        let binary_records = Array2::from_shape_fn((n, n), |(g1, g2)| {
+            let mut record = CubicBinaryRecord::default();
+            if g1 != g2 {
+                for &(i, wi) in &weights[g1] {
+                    for &(j, wj) in &weights[g2] {
+                        record.k_ij += wi * wj * parameters.k_ij[[i, j]];
+                        record.l_ij += wi * wj * parameters.l_ij[[i, j]];
+                    }
+                }
+            }
+            record
+        });
+        CubicParameters::from_records(lumped_records, Some(binary_records))
+    }
+
+    /// Lump a detailed composition into mole fractions of the pseudo-components.
+    pub fn lump(&self, molefracs: &Array1<f64>) -> Array1<f64> {
+        self.groups
+            .iter()
+            .map(|g| g.iter().map(|&i| molefracs[i]).sum())
+            .collect()
+    }
+
+    /// Delump the phase compositions of a flash calculation with the lumped
+    /// components back to the detailed components.
+    ///
+    /// The K-value of every pseudo-component is assigned to all its detailed
+    /// components and the phase compositions follow from the Rachford-Rice equation
+    /// for the detailed composition of the lumping scheme as feed and the vapor
+    /// fraction of the phase equilibrium. Returns the detailed compositions of
+    /// the vapor and the liquid phase.
+    pub fn delump<E: Residual>(
+        &self,
+        vle: &PhaseEquilibrium<E, 2>,
+    ) -> FeosResult<(Array1<f64>, Array1<f64>)> {
+        let (vapor, liquid) = (vle.vapor(), vle.liquid());
+        if vapor.molefracs.len() != self.groups.len() {
+            return Err(FeosError::IncompatibleComponents(
+                self.groups.len(),
+                vapor.molefracs.len(),
+            ));
+        }
+        let beta = (vapor.total_moles / (vapor.total_moles + liquid.total_moles)).into_value();
+        let mut k = Array1::zeros(self.molefracs.len());
+        for (g, group) in self.groups.iter().enumerate() {
+            for &i in group {
+                k[i] = vapor.molefracs[g] / liquid.molefracs[g];
+            }
+        }
+        let (y, x) = rachford_rice_compositions(&self.molefracs, &k, beta);
+        Ok((&y / y.sum(), &x / x.sum()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::Cubic;
+    use approx::assert_relative_eq;
+    use feos_core::{DensityInitialization, SolverOptions, State};
+    use ndarray::arr1;
+    use quantity::{BAR, KELVIN, MOL};
+    use std::sync::Arc;
+
+    fn parameters() -> FeosResult<CubicParameters> {
+        let records = [
+            ("methane", 190.564, 4599200.0, 0.01142, 16.0428),
+            ("ethane", 305.32, 4872200.0, 0.0995, 30.069),
+            ("propane", 369.89, 4251200.0, 0.1521, 44.0956),
+            ("n-butane", 425.125, 3796000.0, 0.201, 58.1222),
+        ]
+        .into_iter()
+        .map(|(name, tc, pc, w, mw)| {
+            let id = Identifier::new(None, Some(name), None, None, None, None);
+            PureRecord::new(id, mw, CubicRecord::new(tc, pc, w))
+        })
+        .collect();
+        let mut binary = Array2::from_elem((4, 4), CubicBinaryRecord::default());
+        binary[[0, 3]].k_ij = 0.02;
+        binary[[3, 0]].k_ij = 0.02;
+        CubicParameters::from_records(records, Some(binary))
+    }
+
+    #[test]
+    fn lumped_parameters() -> FeosResult<()> {
+        let parameters = parameters()?;
+        let z = arr1(&[0.5, 0.2, 0.2, 0.1]);
+        let lumping = Lumping::new(vec![vec![0], vec![1], vec![2, 3]], &z)?;
+        let lumped = lumping.parameters(&parameters)?;
+        assert_eq!(
+            lumped.pure_records[2].identifier.name.as_deref(),
+            Some("propane+n-butane")
+        );
+        assert_relative_eq!(lumped.tc[2], (2.0 * 369.89 + 425.125) / 3.0);
+        assert_relative_eq!(lumped.k_ij[[0, 2]], 0.02 / 3.0);
+        assert_relative_eq!(lumped.k_ij[[2, 1]], 0.0);
+        assert_relative_eq!(lumping.lump(&z), arr1(&[0.5, 0.2, 0.3]));
+
+        // the trivial lumping reproduces the detailed parameters
+        let trivial = Lumping::new(vec![vec![0], vec![1], vec![2], vec![3]], &z)?;
+        let detailed = trivial.parameters(&parameters)?;
+        assert_eq!(detailed.tc, parameters.tc);
+        assert_eq!(detailed.k_ij, parameters.k_ij);
+
+        assert!(Lumping::new(vec![vec![0, 1], vec![1, 2, 3]], &z).is_err());
+        assert!(Lumping::new(vec![vec![0, 1], vec![2]], &z).is_err());
+        assert!(Lumping::new(vec![vec![0, 1], vec![2, 3, 4]], &z).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn delump_flash() -> FeosResult<()> {
+        let parameters = parameters()?;
+        let z = arr1(&[0.5, 0.2, 0.2, 0.1]);
+        let lumping = Lumping::new(vec![vec![0], vec![1], vec![2, 3]], &z)?;
+        let lumped = Arc::new(Cubic::peng_robinson(
+            Arc::new(lumping.parameters(&parameters)?),
+            None,
+            None,
+        )?);
+        let detailed = Arc::new(Cubic::peng_robinson(Arc::new(parameters), None, None)?);
+
+        let (t, p) = (250.0 * KELVIN, 30.0 * BAR);
+        let flash = |eos: &Arc<Cubic>, z: &Array1<f64>| {
+            State::new_npt(eos, t, p, &(z * MOL), DensityInitialization::None)?.tp_flash(
+                None,
+                SolverOptions::default(),
+                None,
+            )
+        };
+        let vle = flash(&lumped, &lumping.lump(&z))?;
+        let (y, x) = lumping.delump(&vle)?;
+        assert_relative_eq!(lumping.lump(&y), vle.vapor().molefracs, max_relative = 1e-8);
+        assert_relative_eq!(
+            lumping.lump(&x),
+            vle.liquid().molefracs,
+            max_relative = 1e-8
+        );
+
+        // material balance and comparison with the detailed flash
+        let beta = (vle.vapor().total_moles / (vle.vapor().total_moles + vle.liquid().total_moles))
+            .into_value();
+        assert_relative_eq!(beta * &y + (1.0 - beta) * &x, z, max_relative = 1e-8);
+        let vle_detailed = flash(&detailed, &z)?;
+        assert_relative_eq!(y, vle_detailed.vapor().molefracs, epsilon = 2e-2);
+        assert_relative_eq!(x, vle_detailed.liquid().molefracs, epsilon = 2e-2);
+        Ok(())
+    }
+}
@@ -1,8 +1,14 @@
-use super::{Cubic, alpha::AlphaFunction};
+use super::Cubic;
+use super::combining_rules::{CombiningRule, CombiningRules};
+use super::parameters::CubicParameters;
+use super::psrk::Psrk;
+use super::simd::bilinear_form;
 use enum_dispatch::enum_dispatch;
-use feos_core::StateHD;
-use ndarray::ScalarOperand;
+use feos_core::parameter::Identifier;
+use feos_core::{FeosError, FeosResult, StateHD};
+use ndarray::{Array1, ScalarOperand};
 use num_dual::DualNum;
+use serde::{Deserialize, Serialize};
 
 /// Parameters of cubics
 pub struct MixtureParameters<D> {
@@ -14,6 +20,17 @@ pub struct MixtureParameters<D> {
     pub c: D,
 }
 
+/// Linear mixing rule for the volume shift.
+pub(super) fn volume_shift<D: DualNum<f64> + Copy>(
+    parameters: &CubicParameters,
+    molefracs: &Array1<D>,
+) -> D {
+    molefracs
+        .iter()
+        .zip(&parameters.volume_shift)
+        .fold(D::zero(), |c, (&x, &ci)| c + x * ci)
+}
+
 #[enum_dispatch]
 pub trait MixingRuleFunction {
     fn apply<D: DualNum<f64> + Copy + ScalarOperand>(
@@ -21,11 +38,52 @@ pub trait MixingRuleFunction {
         cubic: &Cubic,
         state: &StateHD<D>,
     ) -> MixtureParameters<D>;
+
+    /// Check for validity of the mixing rule against parameters.
+    fn validate(&self, _: &CubicParameters) -> FeosResult<()> {
+        Ok(())
+    }
+
+    /// Generate the mixing rule for a subset of components.
+    fn subset(&self, component_list: &[usize]) -> Self;
+}
+
+/// Boxed mixing rules, used for large variants of [MixingRule].
+impl<T: MixingRuleFunction> MixingRuleFunction for Box<T> {
+    fn apply<D: DualNum<f64> + Copy + ScalarOperand>(
+        &self,
+        cubic: &Cubic,
+        state: &StateHD<D>,
+    ) -> MixtureParameters<D> {
+        (**self).apply(cubic, state)
+    }
+
+    fn validate(&self, parameters: &CubicParameters) -> FeosResult<()> {
+        (**self).validate(parameters)
+    }
+
+    fn subset(&self, component_list: &[usize]) -> Self {
+        Box::new((**self).subset(component_list))
+    }
 }
 
 /// Quadratic summation over a and b.
-#[derive(Debug, Clone)]
-pub struct Quadratic;
+///
+/// The cross parameters are obtained from the [CombiningRules], by default
+/// the geometric mean for a and the arithmetic mean for b.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Quadratic {
+    /// Combining rules for the cross parameters
+    #[serde(default)]
+    pub combining_rules: CombiningRules,
+}
+
+impl Quadratic {
+    /// Quadratic mixing rule with the given combining rules.
+    pub fn new(combining_rules: CombiningRules) -> Self {
+        Self { combining_rules }
+    }
+}
 
 impl MixingRuleFunction for Quadratic {
     fn apply<D: DualNum<f64> + Copy + ScalarOperand>(
@@ -35,28 +93,185 @@ impl MixingRuleFunction for Quadratic {
     ) -> MixtureParameters<D> {
         let p = &cubic.parameters;
         let pc = &cubic.critical_parameters;
-        let n = p.tc.len();
+        let x = &state.molefracs;
+        let at = cubic.attractive_parameters(state.temperature);
+        let x_sqrt_a = at.mapv(|a| a.sqrt()) * x;
+        let rules = &self.combining_rules;
+        let a = match rules.a {
+            CombiningRule::Geometric => bilinear_form(&p.a_ij_factor, &x_sqrt_a, &x_sqrt_a),
+            _ => {
+                let a_ij_factor = rules.attraction_factors(&pc.ac) * &p.a_ij_factor;
+                bilinear_form(&a_ij_factor, &x_sqrt_a, &x_sqrt_a)
+            }
+        };
+        let b = match rules.b {
+            CombiningRule::Arithmetic => bilinear_form(&p.b_ij_factor, &(x * &pc.bc), x),
+            _ => bilinear_form(&(rules.b.matrix(&pc.bc) * &p.b_ij_factor), x, x),
+        };
+        let c = volume_shift(p, x);
+        MixtureParameters { a, b, c }
+    }
+
+    fn subset(&self, _: &[usize]) -> Self {
+        self.clone()
+    }
+}
+
+/// Correlation for the binary interaction parameter between water and
+/// another component in the aqueous phase of Søreide and Whitson (1992).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AqueousKij {
+    /// Generalized correlation for hydrocarbons based on the acentric factor.
+    Hydrocarbon,
+    Nitrogen,
+    CarbonDioxide,
+    HydrogenSulfide,
+}
+
+impl AqueousKij {
+    /// Select the correlation for a component by its CAS number or formula.
+    pub fn from_identifier(identifier: &Identifier) -> Self {
+        let is = |cas, formula| {
+            identifier.cas.as_deref() == Some(cas) || identifier.formula.as_deref() == Some(formula)
+        };
+        if is("7727-37-9", "N2") {
+            Self::Nitrogen
+        } else if is("124-38-9", "CO2") {
+            Self::CarbonDioxide
+        } else if is("7783-06-4", "H2S") {
+            Self::HydrogenSulfide
+        } else {
+            Self::Hydrocarbon
+        }
+    }
+
+    /// Binary interaction parameter in the aqueous phase for the reduced temperature
+    /// and acentric factor of the component and the salinity in mol/kg.
+    pub fn k_ij<D: DualNum<f64> + Copy>(&self, tr: D, acentric_factor: f64, salinity: f64) -> D {
+        let cs = salinity;
+        match self {
+            Self::Hydrocarbon => {
+                let w = acentric_factor;
+                let a0 = 1.1120 - 1.7369 * w.powf(-0.1);
+                let a1 = 1.1001 + 0.8360 * w;
+                let a2 = -0.15742 - 1.0988 * w;
+                tr * (tr * a2 * (1.0 + 0.011478 * cs) + a1 * (1.0 + 0.033516 * cs))
+                    + a0 * (1.0 + 0.017407 * cs)
+            }
+            Self::Nitrogen => {
+                tr * 0.44338 * (1.0 + 0.08126 * cs.powf(0.75))
+                    - 1.70235 * (1.0 + 0.025587 * cs.powf(0.75))
+            }
+            Self::CarbonDioxide => {
+                tr * 0.23580 * (1.0 + 0.17837 * cs.powf(0.979))
+                    - (tr * -6.7222 - cs).exp() * 21.2566
+                    - 0.31092 * (1.0 + 0.15587 * cs.powf(0.7505))
+            }
+            Self::HydrogenSulfide => tr * 0.23426 - 0.20441,
+        }
+    }
+}
+
+/// Quadratic mixing rule with the temperature and salinity dependent binary
+/// interaction parameters of Søreide and Whitson (1992) between water and all
+/// other components.
+///
+/// The binary interaction parameters apply to the aqueous phase only. The
+/// non-aqueous phase is described with the quadratic mixing rule and constant
+/// binary interaction parameters, e.g., using [Cubic::soreide_whitson].
+/// Binary interaction parameters between components other than water are
+/// taken from the parameters.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SoreideWhitsonMixing {
+    water: Option<usize>,
+    salinity: f64,
+    k_ij: Vec<AqueousKij>,
+}
+
+impl SoreideWhitsonMixing {
+    /// Mixing rule for water (index `water`) at the given salinity in mol/kg
+    /// with the correlations for the binary interaction parameters of all
+    /// components (the entry for water is ignored).
+    pub fn new(water: usize, salinity: f64, k_ij: Vec<AqueousKij>) -> Self {
+        Self {
+            water: Some(water),
+            salinity,
+            k_ij,
+        }
+    }
+
+    /// Select the correlations of all components from their identifiers.
+    pub fn from_parameters(parameters: &CubicParameters, water: usize, salinity: f64) -> Self {
+        let k_ij = parameters
+            .pure_records
+            .iter()
+            .map(|r| AqueousKij::from_identifier(&r.identifier))
+            .collect();
+        Self::new(water, salinity, k_ij)
+    }
+
+    /// Append the correlation for an additional component.
+    pub(super) fn with_component(&self, identifier: &Identifier) -> Self {
+        let mut k_ij = self.k_ij.clone();
+        k_ij.push(AqueousKij::from_identifier(identifier));
+        Self { k_ij, ..*self }
+    }
+}
+
+impl MixingRuleFunction for SoreideWhitsonMixing {
+    fn apply<D: DualNum<f64> + Copy + ScalarOperand>(
+        &self,
+        cubic: &Cubic,
+        state: &StateHD<D>,
+    ) -> MixtureParameters<D> {
+        let p = &cubic.parameters;
+        let pc = &cubic.critical_parameters;
+        let x = &state.molefracs;
         let tr = p.tc.mapv(|tc| state.temperature / tc);
-        let at = cubic.options.alpha.alpha(&p.acentric_factor, &tr) * &pc.ac;
-        let mut a = D::zero();
-        let mut b = D::zero();
-        for i in 0..n {
-            let xi = state.molefracs[i];
-            let ai = at[i];
-            let bi = pc.bc[i];
-            a += xi * xi * ai;
-            b += xi * xi * bi;
-            for j in i + 1..n {
-                a += xi * state.molefracs[j] * (ai * at[j]).sqrt() * (1.0 - p.k_ij[[i, j]]) * 2.0;
-                b += xi * state.molefracs[j] * (bi + pc.bc[j]) * 0.5 * (1.0 - p.l_ij[[i, j]]) * 2.0;
+        let at = cubic.attractive_parameters(state.temperature);
+        let x_sqrt_a: Array1<D> = at.mapv(|a| a.sqrt()) * x;
+        let mut a = bilinear_form(&p.a_ij_factor, &x_sqrt_a, &x_sqrt_a);
+        if let Some(w) = self.water {
+            // replace the constant binary interaction parameters with water
+            for (i, k_ij) in self.k_ij.iter().enumerate().filter(|&(i, _)| i != w) {
+                let k_iw = k_ij.k_ij(tr[i], p.acentric_factor[i], self.salinity);
+                a += x_sqrt_a[i] * x_sqrt_a[w] * (-k_iw + 1.0 - p.a_ij_factor[[i, w]]) * 2.0;
             }
         }
-        MixtureParameters { a, b, c: D::zero() }
+        let b = bilinear_form(&p.b_ij_factor, &(x * &pc.bc), x);
+        let c = volume_shift(p, x);
+        MixtureParameters { a, b, c }
+    }
+
+    fn validate(&self, parameters: &CubicParameters) -> FeosResult<()> {
+        let n = parameters.tc.len();
+        if self.k_ij.len() != n || self.water.is_some_and(|w| w >= n) {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "Søreide-Whitson mixing rule was initialized for {} components with water as component {:?}, but the equation of state contains {n} components.",
+                self.k_ij.len(),
+                self.water
+            )));
+        }
+        Ok(())
+    }
+
+    fn subset(&self, component_list: &[usize]) -> Self {
+        Self {
+            water: self
+                .water
+                .and_then(|w| component_list.iter().position(|&i| i == w)),
+            salinity: self.salinity,
+            k_ij: component_list.iter().map(|&i| self.k_ij[i]).collect(),
+        }
     }
 }
 
 #[enum_dispatch(MixingRuleFunction)]
-#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
 pub enum MixingRule {
     Quadratic,
+    SoreideWhitsonMixing,
+    Psrk(Box<Psrk>),
 }
@@ -1,24 +1,77 @@
-use alpha::{Alpha, AlphaFunction, PengRobinson1976, RedlichKwong1972};
-use feos_core::FeosResult;
-use feos_core::parameter::Parameter;
-use feos_core::{Components, Residual};
+use alpha::AlphaFunction;
+use cache::AttractionCache;
+use feos_core::cubic::cubic_density_roots;
+use feos_core::parameter::{Parameter, PureRecord};
+use feos_core::{Components, ReducingParameters, Residual};
+use feos_core::{FeosError, FeosResult, Parachor};
 use feos_core::{Molarweight, StateHD};
-use mixing_rules::{MixingRule, MixingRuleFunction, MixtureParameters, Quadratic};
+use mixing_rules::{MixingRuleFunction, MixtureParameters};
 use ndarray::{Array1, ScalarOperand, Zip};
 use num_dual::DualNum;
-use parameters::CubicParameters;
 use quantity::{GRAM, MOL, MolarWeight};
+use serde::{Deserialize, Serialize};
 use std::f64::consts::SQRT_2;
 use std::fmt;
 use std::sync::Arc;
 
 mod alpha;
+mod builder;
+mod cache;
+mod characterization;
+mod combining_rules;
+mod corresponding_states;
+mod critical_point;
+mod electrolyte;
+mod entropy_scaling;
+#[cfg(feature = "kij_database")]
+mod kij_database;
+mod lumping;
 mod mixing_rules;
 mod parameters;
+mod property_package;
+mod psrk;
+mod quality;
+#[cfg(feature = "refprop")]
+mod refprop;
+mod regression;
+mod sensitivity;
+mod simd;
+mod splitting;
+mod water;
+
+pub use alpha::{
+    Alpha, AlphaParameters, GeneralizedTwu, MathiasCopeman, PengRobinson1976, PengRobinson1978,
+    PengRobinson2019, RedlichKwong1972, RedlichKwong2019, Soave, SoreideWhitsonAlpha, Twu,
+};
+pub use builder::CubicBuilder;
+pub use characterization::{CharacterizationCorrelation, PenelouxCorrelation, PseudoComponent};
+pub use combining_rules::{CombiningRule, CombiningRules};
+pub use corresponding_states::{TransportProperty, TransportQuality};
+pub use critical_point::ModelCriticalPoint;
+pub use electrolyte::{DebyeHuckel, Permittivity};
+#[cfg(feature = "kij_database")]
+pub use kij_database::{KijDatabase, KijDatabaseRecord};
+pub use lumping::Lumping;
+pub use mixing_rules::{AqueousKij, MixingRule, Quadratic, SoreideWhitsonMixing};
+pub use parameters::{CubicBinaryRecord, CubicParameters, CubicRecord, IonRecord};
+pub use property_package::{
+    PROPERTY_PACKAGE_VERSION, PackageBinaryRecord, PropertyPackage, ReferenceState,
+    ReferencedIdealGas,
+};
+pub use psrk::{Psrk, PsrkGroupRecord, PsrkInteractionRecord};
+pub use quality::{
+    ComponentScreening, SCREENING_REDUCED_TEMPERATURES, SaturationQuality, ScreeningReport,
+};
+#[cfg(feature = "refprop")]
+pub use refprop::{Ancillary, AncillaryType, Deviations, FldFluid};
+pub use sensitivity::CubicParameter;
+pub use splitting::{PlusFraction, SplitFraction};
+pub use water::{IapwsDeviations, Water};
 
 const KB_A3: f64 = 13806490.0;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(from = "(f64, f64)", into = "(f64, f64)")]
 pub struct Delta {
     d1: f64,
     d2: f64,
@@ -35,6 +88,12 @@ impl From<(f64, f64)> for Delta {
     }
 }
 
+impl From<Delta> for (f64, f64) {
+    fn from(value: Delta) -> Self {
+        (value.d1, value.d2)
+    }
+}
+
 impl Delta {
     // Calculate universal critical constants from universal cubic parameters.
     //
@@ -100,25 +159,34 @@ pub struct CubicOptions {
     pub(crate) alpha: Alpha,
     pub(crate) mixing: MixingRule,
     pub(crate) delta: Delta,
+    pub(crate) electrostatics: Option<DebyeHuckel>,
 }
 
 impl CubicOptions {
     fn subset(&self, component_list: &[usize]) -> Self {
         Self {
             alpha: self.alpha.subset(component_list),
-            mixing: self.mixing.clone(),
+            mixing: self.mixing.subset(component_list),
             delta: self.delta.clone(),
+            electrostatics: self.electrostatics,
         }
     }
 }
 
 /// A generic cubic equation of state.
+///
+/// The model is evaluated through the [Residual] trait and is therefore
+/// restricted to `f64` (and dual numbers based on `f64`). Densities of
+/// the Peng-Robinson equation of state in `f32` are available through
+/// [feos_core::cubic::PengRobinson]; a `Cubic` that is generic over the
+/// floating point type is not part of this crate.
 pub struct Cubic {
     /// Parameters
     pub parameters: Arc<CubicParameters>,
     pub options: CubicOptions,
     /// processed parameters using model and substance critical data
     pub critical_parameters: CriticalParameters,
+    attraction_cache: AttractionCache,
 }
 
 impl Cubic {
@@ -126,10 +194,12 @@ impl Cubic {
     pub fn new(parameters: Arc<CubicParameters>, options: CubicOptions) -> FeosResult<Self> {
         let p = CriticalParameters::new(&parameters, &options.delta);
         options.alpha.validate(&parameters)?;
+        options.mixing.validate(&parameters)?;
         Ok(Self {
             parameters,
             options,
             critical_parameters: p,
+            attraction_cache: AttractionCache::default(),
         })
     }
 
@@ -140,8 +210,12 @@ impl Cubic {
     /// - $\delta_2 = 1 - \sqrt{2}$
     ///
     /// If no options are supplied, the following is used:
-    /// - alpha function: Peng Robinson (1976)
+    /// - alpha function: component specific parameters from the pure records
+    ///   if available, otherwise Peng Robinson (1976)
     /// - mixing rules: quadratic mixing
+    ///
+    /// For heavy components with acentric factors above 0.491, the revised
+    /// polynomial of [PengRobinson1978] is recommended.
     pub fn peng_robinson(
         parameters: Arc<CubicParameters>,
         alpha: Option<Alpha>,
@@ -150,15 +224,21 @@ impl Cubic {
         let delta: Delta = (1.0 + SQRT_2, 1.0 - SQRT_2).into();
         let p = CriticalParameters::new(&parameters, &delta);
         let options = CubicOptions {
-            alpha: alpha.unwrap_or(PengRobinson1976.into()),
-            mixing: mixing.unwrap_or(Quadratic.into()),
+            alpha: match alpha {
+                Some(alpha) => alpha,
+                None => parameters.alpha()?.unwrap_or(PengRobinson1976.into()),
+            },
+            mixing: mixing.unwrap_or(Quadratic::default().into()),
             delta,
+            electrostatics: None,
         };
         options.alpha.validate(&parameters)?;
+        options.mixing.validate(&parameters)?;
         Ok(Self {
             parameters,
             options,
             critical_parameters: p,
+            attraction_cache: AttractionCache::default(),
         })
     }
 
@@ -169,7 +249,8 @@ impl Cubic {
     /// - $\delta_2 = 0$
     ///
     /// If no options are supplied, the following is used:
-    /// - alpha function: Soave (1972)
+    /// - alpha function: component specific parameters from the pure records
+    ///   if available, otherwise Soave (1972)
     /// - mixing rules: quadratic mixing
     pub fn redlich_kwong(
         parameters: Arc<CubicParameters>,
@@ -179,17 +260,109 @@ impl Cubic {
         let delta: Delta = (1.0, 0.0).into();
         let p = CriticalParameters::new(&parameters, &delta);
         let options = CubicOptions {
-            alpha: alpha.unwrap_or(RedlichKwong1972.into()),
-            mixing: mixing.unwrap_or(Quadratic.into()),
+            alpha: match alpha {
+                Some(alpha) => alpha,
+                None => parameters.alpha()?.unwrap_or(RedlichKwong1972.into()),
+            },
+            mixing: mixing.unwrap_or(Quadratic::default().into()),
             delta,
+            electrostatics: None,
         };
         options.alpha.validate(&parameters)?;
+        options.mixing.validate(&parameters)?;
         Ok(Self {
             parameters,
             options,
             critical_parameters: p,
+            attraction_cache: AttractionCache::default(),
         })
     }
+
+    /// Return a new equation of state with an additional component.
+    ///
+    /// `binary_records` contains the binary interaction parameters of the new
+    /// component with each of the existing components (or is empty). The
+    /// universal constants, the mixing rule and generalized alpha functions
+    /// are retained. Component specific alpha functions are rebuilt from the
+    /// alpha function parameters in the extended pure records.
+    pub fn with_component(
+        &self,
+        pure_record: PureRecord<CubicRecord>,
+        binary_records: &[CubicBinaryRecord],
+    ) -> FeosResult<Self> {
+        let mixing = match &self.options.mixing {
+            MixingRule::SoreideWhitsonMixing(mixing) => {
+                mixing.with_component(&pure_record.identifier).into()
+            }
+            mixing => mixing.clone(),
+        };
+        let parameters = Arc::new(
+            self.parameters
+                .with_component(pure_record, binary_records)?,
+        );
+        let alpha = match self.options.alpha {
+            Alpha::Twu(_) | Alpha::MathiasCopeman(_) => parameters.alpha()?.ok_or_else(|| {
+                FeosError::IncompatibleParameters(String::from(
+                    "the alpha function requires component specific parameters for the new component.",
+                ))
+            })?,
+            ref alpha => alpha.clone(),
+        };
+        let options = CubicOptions {
+            alpha,
+            mixing,
+            delta: self.options.delta,
+            electrostatics: self.options.electrostatics,
+        };
+        Self::new(parameters, options)
+    }
+
+    /// Add the electrostatic contribution of Debye and Hückel for the ions
+    /// in the parameters.
+    ///
+    /// The cubic contribution is unchanged, so that parameters of
+    /// the neutral components can be reused. With the electrostatic
+    /// contribution, densities are no longer calculated from the roots of
+    /// the cubic equation.
+    pub fn with_debye_huckel(mut self, debye_huckel: DebyeHuckel) -> FeosResult<Self> {
+        if self.parameters.charge.iter().all(|&z| z == 0.0) {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "the electrostatic contribution requires at least one ion.",
+            )));
+        }
+        self.options.electrostatics = Some(debye_huckel);
+        Ok(self)
+    }
+
+    /// Peng Robinson equation of state with the water-hydrocarbon treatment of
+    /// Søreide and Whitson (1992).
+    ///
+    /// Returns the equations of state for the aqueous and for the non-aqueous
+    /// phase. Both use the salinity dependent alpha function for water
+    /// ([SoreideWhitsonAlpha]) and Peng Robinson (1976) for all other
+    /// components. In the aqueous phase, the binary interaction parameters
+    /// between water and the other components are calculated from the
+    /// correlations of Søreide and Whitson ([SoreideWhitsonMixing]). In the
+    /// non-aqueous phase, the constant binary interaction parameters from
+    /// the parameters are used.
+    ///
+    /// `water` is the index of water and `salinity` the salinity in mol NaCl
+    /// per kg water.
+    pub fn soreide_whitson(
+        parameters: Arc<CubicParameters>,
+        water: usize,
+        salinity: f64,
+    ) -> FeosResult<(Self, Self)> {
+        let alpha = SoreideWhitsonAlpha::new(water, salinity, PengRobinson1976);
+        let mixing = SoreideWhitsonMixing::from_parameters(&parameters, water, salinity);
+        let aqueous = Self::peng_robinson(
+            parameters.clone(),
+            Some(alpha.clone().into()),
+            Some(mixing.into()),
+        )?;
+        let non_aqueous = Self::peng_robinson(parameters, Some(alpha.into()), None)?;
+        Ok((aqueous, non_aqueous))
+    }
 }
 
 impl fmt::Display for Cubic {
@@ -208,6 +381,7 @@ impl Components for Cubic {
             parameters: Arc::new(self.parameters.subset(component_list)),
             options: self.options.subset(component_list),
             critical_parameters: self.critical_parameters.subset(component_list),
+            attraction_cache: AttractionCache::default(),
         }
     }
 }
@@ -215,27 +389,90 @@ impl Components for Cubic {
 impl Residual for Cubic {
     fn compute_max_density(&self, moles: &Array1<f64>) -> f64 {
         let b = (moles * &self.critical_parameters.bc).sum() / moles.sum();
-        0.9 / b
+        let c = (moles * &self.parameters.volume_shift).sum() / moles.sum();
+        0.9 / (b - 0.9 * c)
+    }
+
+    fn density_roots(
+        &self,
+        temperature: f64,
+        pressure: f64,
+        molefracs: &Array1<f64>,
+    ) -> Option<Vec<f64>> {
+        if self.options.electrostatics.is_some() {
+            return None;
+        }
+        let state = StateHD::new(temperature, 1.0, molefracs.clone());
+        let MixtureParameters { a, b, c } = self.options.mixing.apply(self, &state);
+        let Delta { d1, d2, .. } = self.options.delta;
+        let roots = cubic_density_roots(temperature, pressure, a, b, d1, d2);
+        // the volume shift translates the molar volumes of the cubic equation of state
+        Some(roots.into_iter().map(|rho| rho / (1.0 - c * rho)).collect())
+    }
+
+    fn reducing_parameters(&self, molefracs: &Array1<f64>) -> Option<ReducingParameters> {
+        let p = &self.parameters;
+        Some(ReducingParameters::pseudo_critical(molefracs, &p.tc, &p.pc))
     }
 
     fn residual_helmholtz_energy<D: DualNum<f64> + Copy + ScalarOperand>(
         &self,
         state: &StateHD<D>,
     ) -> D {
-        let MixtureParameters { a, b, c: _ } = self.options.mixing.apply(self, state);
-        let n = state.moles.sum();
-        let v = state.volume;
-        let bn = b * n;
-        n * ((v / (v - bn)).ln()
-            - a / (b * self.options.delta.d12 * state.temperature)
-                * ((v + bn * self.options.delta.d1) / (v + bn * self.options.delta.d2)).ln())
+        let mut a = self.cubic_helmholtz_energy(state);
+        if let Some(debye_huckel) = &self.options.electrostatics {
+            a += debye_huckel.helmholtz_energy(&self.parameters, state);
+        }
+        a
     }
 
     fn residual_helmholtz_energy_contributions<D: DualNum<f64> + Copy + ScalarOperand>(
         &self,
         state: &StateHD<D>,
     ) -> Vec<(String, D)> {
-        vec![("cubic".to_string(), self.residual_helmholtz_energy(state))]
+        let mut contributions = vec![("cubic".to_string(), self.cubic_helmholtz_energy(state))];
+        if let Some(debye_huckel) = &self.options.electrostatics {
+            contributions.push((
+                "Debye-Hückel".to_string(),
+                debye_huckel.helmholtz_energy(&self.parameters, state),
+            ));
+        }
+        contributions
+    }
+}
+
+impl Cubic {
+    fn cubic_helmholtz_energy<D: DualNum<f64> + Copy + ScalarOperand>(
+        &self,
+        state: &StateHD<D>,
+    ) -> D {
+        let mixture = self.options.mixing.apply(self, state);
+        self.mixture_helmholtz_energy(state, mixture)
+    }
+
+    /// Residual Helmholtz energy of the cubic equation of state for given mixture parameters.
+    fn mixture_helmholtz_energy<D: DualNum<f64> + Copy>(
+        &self,
+        state: &StateHD<D>,
+        mixture: MixtureParameters<D>,
+    ) -> D {
+        let MixtureParameters { a, b, c } = mixture;
+        let n = state.moles.sum();
+        // volume of the untranslated cubic equation of state
+        let v = state.volume + c * n;
+        let bn = b * n;
+        n * ((state.volume / (v - bn)).ln()
+            - a / (b * self.options.delta.d12 * state.temperature)
+                * ((v + bn * self.options.delta.d1) / (v + bn * self.options.delta.d2)).ln())
+    }
+}
+
+impl Parachor for Cubic {
+    fn parachor(&self) -> FeosResult<Array1<f64>> {
+        self.parameters
+            .parachor
+            .clone()
+            .ok_or_else(|| FeosError::IncompatibleParameters(String::from("missing parachors.")))
     }
 }
 
@@ -247,12 +484,15 @@ impl Molarweight for Cubic {
 
 #[cfg(test)]
 mod tests {
+    use approx::assert_relative_eq;
+    use feos_core::{Contributions, PhaseEquilibrium};
     use feos_core::{
         cubic::{PengRobinson, PengRobinsonParameters, PengRobinsonRecord},
-        parameter::{Identifier, PureRecord},
+        parameter::{BinaryRecord, Identifier, PureRecord},
     };
-    use ndarray::arr1;
+    use ndarray::{Array2, arr1};
     use parameters::CubicRecord;
+    use quantity::{CENTI, KELVIN, METER, MILLI, NEWTON, PASCAL, RGAS};
 
     use super::*;
 
@@ -281,4 +521,420 @@ mod tests {
             eos.residual_helmholtz_energy(&state)
         )
     }
+
+    #[test]
+    fn parachor_surface_tension() -> FeosResult<()> {
+        let propane = PureRecord::new(
+            Identifier::new(None, Some("propane"), None, None, None, None),
+            44.0962,
+            CubicRecord::new(369.96, 4250000.0, 0.153).with_parachor(150.3),
+        );
+        let parameters = Arc::new(CubicParameters::new_pure(propane)?);
+        let eos = Arc::new(Cubic::peng_robinson(parameters, None, None)?);
+        let vle = PhaseEquilibrium::pure(&eos, 300.0 * KELVIN, None, Default::default())?;
+        let density = MOL / (CENTI * METER).powi::<typenum::P3>();
+        let rho_l = vle.liquid().density.convert_to(density);
+        let rho_v = vle.vapor().density.convert_to(density);
+        let sigma = vle.parachor_surface_tension()?;
+        assert_relative_eq!(
+            sigma.convert_to(MILLI * NEWTON / METER),
+            (150.3 * (rho_l - rho_v)).powi(4),
+            max_relative = 1e-12
+        );
+        assert!(sigma > 5.0 * MILLI * NEWTON / METER && sigma < 9.0 * MILLI * NEWTON / METER);
+
+        let parameters = Arc::new(CubicParameters::new_simple(
+            &[369.96],
+            &[4250000.0],
+            &[0.153],
+            &[44.0962],
+        )?);
+        let eos = Arc::new(Cubic::peng_robinson(parameters, None, None)?);
+        let vle = PhaseEquilibrium::pure(&eos, 300.0 * KELVIN, None, Default::default())?;
+        assert!(vle.parachor_surface_tension().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn validation() -> FeosResult<()> {
+        let record = |name, tc, pc, mw, alpha| {
+            let mut record = CubicRecord::new(tc, pc, 0.153);
+            if let Some(alpha) = alpha {
+                record = record.with_alpha(alpha);
+            }
+            PureRecord::new(
+                Identifier::new(None, Some(name), None, None, None, None),
+                mw,
+                record,
+            )
+        };
+        let valid = vec![
+            record("propane", 369.96, 4250000.0, 44.0962, None),
+            record("butane", 425.2, 3800000.0, 58.123, None),
+        ];
+        let parameters = CubicParameters::from_records_validated(valid, None, true)?;
+        assert!(parameters.validate().issues.is_empty());
+
+        let records = vec![
+            record("propane", -369.96, 42.5, 0.0, None),
+            record("propane", 425.2, 3800000.0, 58.123, None),
+            record(
+                "butane",
+                425.2,
+                3800000.0,
+                58.123,
+                Some(AlphaParameters::Twu {
+                    l: -0.1,
+                    m: 0.8,
+                    n: 2.0,
+                }),
+            ),
+        ];
+        let binary = Array2::from_shape_fn((3, 3), |(i, j)| {
+            CubicBinaryRecord::from(if i != j { 0.8 } else { 0.0 })
+        });
+        let report =
+            CubicParameters::from_records(records.clone(), Some(binary.clone()))?.validate();
+        // tc, molar weight, duplicated name
+        assert_eq!(report.errors().count(), 3);
+        // pc, alpha, 3 k_ij
+        assert_eq!(report.warnings().count(), 5);
+        match CubicParameters::from_records_validated(records, Some(binary), false) {
+            Err(FeosError::InvalidParameters(r)) => assert_eq!(r, report),
+            _ => panic!("validation should fail"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn with_component() -> FeosResult<()> {
+        let record = |name, tc, pc, acentric_factor| {
+            PureRecord::new(
+                Identifier::new(None, Some(name), None, None, None, None),
+                44.0,
+                CubicRecord::new(tc, pc, acentric_factor),
+            )
+        };
+        let propane = record("propane", 369.96, 4250000.0, 0.153);
+        let butane = record("butane", 425.2, 3800000.0, 0.199);
+        let pentane = record("pentane", 469.7, 3370000.0, 0.251);
+        let parameters = Arc::new(CubicParameters::from_records(
+            vec![propane.clone(), butane.clone()],
+            None,
+        )?);
+        let eos = Cubic::peng_robinson(parameters, None, None)?;
+        let k_ij = [0.01, 0.02].map(|k| CubicBinaryRecord::new(Some(k), None));
+        let extended = eos.with_component(pentane.clone(), &k_ij)?;
+        assert_eq!(extended.components(), 3);
+        assert_eq!(extended.parameters.k_ij[[2, 1]], 0.02);
+        assert_eq!(extended.parameters.k_ij[[0, 2]], 0.01);
+        assert_eq!(extended.parameters.k_ij[[0, 1]], 0.0);
+        assert!(eos.with_component(pentane.clone(), &k_ij[..1]).is_err());
+
+        // the extended equation of state is identical to one built at once
+        let binary = |id1: &PureRecord<_>, k_ij: &CubicBinaryRecord| {
+            BinaryRecord::new(
+                id1.identifier.clone(),
+                pentane.identifier.clone(),
+                k_ij.clone(),
+            )
+        };
+        let direct = CubicBuilder::new()
+            .pure_records(vec![propane.clone(), butane.clone(), pentane.clone()])
+            .binary_records(vec![binary(&propane, &k_ij[0]), binary(&butane, &k_ij[1])]);
+        #[cfg(feature = "kij_database")]
+        let direct = direct.without_kij_database();
+        let direct = direct.build()?;
+        let state = StateHD::new(300.0, 1e5, arr1(&[0.2, 0.3, 0.5]));
+        assert_relative_eq!(
+            extended.residual_helmholtz_energy(&state),
+            direct.residual_helmholtz_energy(&state),
+            max_relative = 1e-12
+        );
+
+        // component specific alpha functions need parameters for the new component
+        let twu = Twu::new(vec![0.1, 0.2], vec![0.8, 0.9], None);
+        let eos = Cubic::peng_robinson(eos.parameters.clone(), Some(twu.into()), None)?;
+        let ethane = record("ethane", 305.3, 4872000.0, 0.099);
+        assert!(eos.with_component(ethane, &[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn soreide_whitson() -> FeosResult<()> {
+        let record = |name, cas, tc, pc, acentric_factor| {
+            PureRecord::new(
+                Identifier::new(Some(cas), Some(name), None, None, None, None),
+                18.0,
+                CubicRecord::new(tc, pc, acentric_factor),
+            )
+        };
+        let records = vec![
+            record("water", "7732-18-5", 647.096, 22064000.0, 0.3443),
+            record("methane", "74-82-8", 190.564, 4599000.0, 0.0114),
+            record("carbon dioxide", "124-38-9", 304.13, 7377300.0, 0.2239),
+        ];
+        let parameters = Arc::new(CubicParameters::from_records(records.clone(), None)?);
+        let (salinity, t) = (1.0, 320.0);
+        let (aqueous, non_aqueous) = Cubic::soreide_whitson(parameters.clone(), 0, salinity)?;
+
+        // alpha function of water
+        let tr = arr1(&[t / 647.096, t / 190.564]);
+        let acentric_factor = arr1(&[0.3443, 0.0114]);
+        let alpha =
+            SoreideWhitsonAlpha::new(0, salinity, PengRobinson1976).alpha(&acentric_factor, &tr);
+        let sqrt_alpha =
+            1.0 + 0.4530 * (1.0 - tr[0] * (1.0 - 0.0103)) + 0.0034 * (tr[0].powi(-3) - 1.0);
+        assert_relative_eq!(alpha[0], sqrt_alpha.powi(2), max_relative = 1e-14);
+        assert_eq!(alpha[1], PengRobinson1976.alpha(&acentric_factor, &tr)[1]);
+
+        // the aqueous phase corresponds to constant binary interaction parameters at fixed temperature
+        let k_ij = |i: usize| {
+            let r: &PureRecord<CubicRecord> = &records[i];
+            AqueousKij::from_identifier(&r.identifier).k_ij(
+                t / r.model_record.tc,
+                r.model_record.acentric_factor,
+                salinity,
+            )
+        };
+        assert_eq!(
+            AqueousKij::from_identifier(&records[2].identifier),
+            AqueousKij::CarbonDioxide
+        );
+        let binary = Array2::from_shape_fn((3, 3), |(i, j)| match (i, j) {
+            (0, j) if j > 0 => k_ij(j).into(),
+            (i, 0) if i > 0 => k_ij(i).into(),
+            _ => CubicBinaryRecord::default(),
+        });
+        let constant = Arc::new(CubicParameters::from_records(records, Some(binary))?);
+        let alpha = SoreideWhitsonAlpha::new(0, salinity, PengRobinson1976);
+        let constant = Cubic::peng_robinson(constant, Some(alpha.into()), None)?;
+        let state = StateHD::new(t, 1e5, arr1(&[0.9, 0.04, 0.06]));
+        assert_relative_eq!(
+            aqueous.residual_helmholtz_energy(&state),
+            constant.residual_helmholtz_energy(&state),
+            max_relative = 1e-12
+        );
+        let (a_aq, a_na) = (
+            aqueous.residual_helmholtz_energy(&state),
+            non_aqueous.residual_helmholtz_energy(&state),
+        );
+        assert!(((a_aq - a_na) / a_na).abs() > 1e-3);
+
+        // without water the aqueous phase uses the constant parameters
+        let state = StateHD::new(t, 1e5, arr1(&[0.4, 0.6]));
+        assert_eq!(
+            aqueous.subset(&[1, 2]).residual_helmholtz_energy(&state),
+            non_aqueous
+                .subset(&[1, 2])
+                .residual_helmholtz_energy(&state)
+        );
+
+        let mixing = SoreideWhitsonMixing::new(3, salinity, vec![AqueousKij::Hydrocarbon; 3]);
+        assert!(Cubic::peng_robinson(parameters, None, Some(mixing.into())).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn critical_points() -> FeosResult<()> {
+        let records = vec![
+            PureRecord::new(
+                Identifier::new(None, Some("water"), None, None, None, None),
+                18.015,
+                CubicRecord::new(647.096, 22064000.0, 0.3443),
+            ),
+            PureRecord::new(
+                Identifier::new(None, Some("propane"), None, None, None, None),
+                44.0962,
+                CubicRecord::new(369.96, 4250000.0, 0.153),
+            ),
+        ];
+        let parameters = Arc::new(CubicParameters::from_records(records, None)?);
+
+        // the critical point of the Peng-Robinson equation of state is consistent with the records
+        let eos = Arc::new(Cubic::peng_robinson(parameters.clone(), None, None)?);
+        let (critical_points, report) = Cubic::critical_points(&eos, 1e-6, Default::default())?;
+        assert!(report.issues.is_empty());
+        assert_relative_eq!(
+            critical_points[1].temperature,
+            369.96 * KELVIN,
+            max_relative = 1e-8
+        );
+        assert_relative_eq!(
+            critical_points[1].pressure.convert_to(PASCAL),
+            4250000.0,
+            max_relative = 1e-8
+        );
+        let zc = critical_points[1].pressure
+            / (critical_points[1].density * RGAS * critical_points[1].temperature);
+        assert_relative_eq!(zc.into_value(), 0.307401, max_relative = 1e-5);
+
+        // in reduced variables, the critical point is located at τ=1 and δ=1/Zc
+        let x = arr1(&[0.0, 1.0]);
+        let reducing = eos.reducing_parameters(&x).unwrap();
+        let cp = &critical_points[1];
+        let (tau, delta) = reducing.reduce(cp.temperature, cp.density);
+        assert_relative_eq!(tau, 1.0, max_relative = 1e-8);
+        let derivatives = eos.reduced_residual_derivatives(&reducing, tau, delta, &x);
+        assert_relative_eq!(
+            derivatives.compressibility_factor(),
+            zc.into_value(),
+            max_relative = 1e-8
+        );
+        assert_relative_eq!(delta * zc.into_value(), 1.0, max_relative = 1e-8);
+
+        // the salinity dependent alpha function of water is not unity at the critical temperature
+        let alpha = SoreideWhitsonAlpha::new(0, 1.0, PengRobinson1976);
+        let eos = Arc::new(Cubic::peng_robinson(parameters, Some(alpha.into()), None)?);
+        let (critical_points, report) = Cubic::critical_points(&eos, 1e-6, Default::default())?;
+        assert!(critical_points[0].temperature > 647.096 * KELVIN);
+        assert_eq!(report.warnings().count(), 2);
+        assert!(report.warnings().all(|w| w.components == vec![0]));
+        Ok(())
+    }
+
+    #[test]
+    fn saturation_quality() -> FeosResult<()> {
+        let record = |alpha| {
+            let mut record = CubicRecord::new(369.96, 4250000.0, 0.153);
+            if let Some(alpha) = alpha {
+                record = record.with_alpha(alpha);
+            }
+            PureRecord::new(
+                Identifier::new(None, Some("propane"), None, None, None, None),
+                44.0962,
+                record,
+            )
+        };
+
+        // the Peng-Robinson equation of state approximately reproduces the acentric factor
+        let parameters = Arc::new(CubicParameters::new_pure(record(None))?);
+        let eos = Arc::new(Cubic::peng_robinson(parameters, None, None)?);
+        let acentric_factors = Cubic::acentric_factors(&eos, Default::default())?;
+        assert_relative_eq!(acentric_factors[0], 0.153, epsilon = 5e-3);
+        let (quality, report) =
+            Cubic::saturation_quality(&eos, 11, 0.01, 10.0, Default::default())?;
+        assert!(report.issues.is_empty());
+        assert_eq!(quality[0].reduced_temperature.len(), 11);
+        assert_eq!(quality[0].model_acentric_factor, acentric_factors[0]);
+        assert_relative_eq!(quality[0].relative_deviation[10], 0.0, epsilon = 1e-2);
+        assert!(quality[0].aard() <= quality[0].max());
+
+        // badly fitted alpha function parameters
+        let twu = AlphaParameters::Twu {
+            l: 0.5,
+            m: 0.8,
+            n: 2.0,
+        };
+        let parameters = Arc::new(CubicParameters::new_pure(record(Some(twu)))?);
+        let eos = Arc::new(Cubic::peng_robinson(parameters, None, None)?);
+        let (quality, report) =
+            Cubic::saturation_quality(&eos, 11, 0.01, 10.0, Default::default())?;
+        assert_eq!(report.warnings().count(), 2);
+        assert!(quality[0].max() > 10.0);
+        Ok(())
+    }
+
+    #[test]
+    fn screen_parameters() -> FeosResult<()> {
+        let record = |name, mw, record| {
+            PureRecord::new(
+                Identifier::new(None, Some(name), None, None, None, None),
+                mw,
+                record,
+            )
+        };
+        let records = vec![
+            record(
+                "propane",
+                44.0962,
+                CubicRecord::new(369.96, 4250000.0, 0.153),
+            ),
+            record(
+                "n-butane",
+                58.123,
+                CubicRecord::new(425.12, 3796000.0, 0.2).with_volume_shift(1e-5),
+            ),
+            record(
+                "n-pentane",
+                72.15,
+                CubicRecord::new(469.7, 3370000.0, 0.252).with_volume_shift(3e-5),
+            ),
+        ];
+        let parameters = Arc::new(CubicParameters::from_records(records, None)?);
+        let eos = Arc::new(Cubic::peng_robinson(parameters, None, None)?);
+        let report = Cubic::screen_parameters(&eos, None, Default::default());
+
+        // the components with implausible volume shifts are ranked first
+        let ranking: Vec<_> = report.components.iter().map(|c| c.component).collect();
+        assert_eq!(ranking, vec![2, 1, 0]);
+        assert_eq!(report.components[0].name, "n-pentane");
+        assert_eq!(report.suspicious(15.0).count(), 2);
+        assert!(report.components.iter().all(|c| c.failures() == 0));
+        assert_eq!(
+            report.components[2].reduced_temperature.len(),
+            SCREENING_REDUCED_TEMPERATURES.len()
+        );
+        assert!(
+            report
+                .to_string()
+                .lines()
+                .nth(2)
+                .unwrap()
+                .contains("n-pentane")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_critical_data() -> FeosResult<()> {
+        let (tc, pc, acentric_factor) = ([369.96, 425.2], [4250000.0, 3800000.0], [0.153, 0.199]);
+        let parameters =
+            CubicParameters::from_critical_data(&tc, &pc, &acentric_factor, None, None)?;
+        assert!(parameters.molarweight.iter().all(|mw| mw.is_nan()));
+        let eos = Arc::new(Cubic::peng_robinson(Arc::new(parameters), None, None)?);
+        let simple = CubicParameters::new_simple(&tc, &pc, &acentric_factor, &[44.0962, 58.123])?;
+        let simple = Arc::new(Cubic::peng_robinson(Arc::new(simple), None, None)?);
+        let state = StateHD::new(300.0, 1e4, arr1(&[0.3, 0.7]));
+        assert_eq!(
+            eos.residual_helmholtz_energy(&state),
+            simple.residual_helmholtz_energy(&state)
+        );
+        assert!(
+            CubicParameters::from_critical_data(&tc, &pc, &acentric_factor, Some(&[44.0962]), None)
+                .is_err()
+        );
+
+        // the volume shift translates liquid volumes without changing the vapor pressure
+        let c = [-5e-6, 2e-6];
+        let shifted =
+            CubicParameters::from_critical_data(&tc, &pc, &acentric_factor, None, Some(&c))?;
+        let shifted = Arc::new(Cubic::peng_robinson(Arc::new(shifted), None, None)?);
+        let vle = PhaseEquilibrium::pure(
+            &eos.subset(&[0]).into(),
+            300.0 * KELVIN,
+            None,
+            Default::default(),
+        )?;
+        let vle_shifted = PhaseEquilibrium::pure(
+            &shifted.subset(&[0]).into(),
+            300.0 * KELVIN,
+            None,
+            Default::default(),
+        )?;
+        assert_relative_eq!(
+            vle_shifted.vapor().pressure(Contributions::Total),
+            vle.vapor().pressure(Contributions::Total),
+            max_relative = 1e-8
+        );
+        let molar_volume = |vle: &PhaseEquilibrium<Cubic, 2>| {
+            (1.0 / vle.liquid().density).convert_to(METER.powi::<typenum::P3>() / MOL)
+        };
+        assert_relative_eq!(
+            molar_volume(&vle_shifted),
+            molar_volume(&vle) - c[0],
+            max_relative = 1e-8
+        );
+        Ok(())
+    }
 }
@@ -1,8 +1,11 @@
-use feos_core::parameter::{Identifier, Parameter, PureRecord};
-use feos_core::{FeosError, FeosResult};
+use super::alpha::{Alpha, AlphaParameters};
+use feos_core::parameter::{Identifier, Parameter, PureRecord, ValidationReport};
+use feos_core::{FeosError, FeosResult, ReferenceSystem};
 use ndarray::{Array1, Array2};
 use num_traits::Zero;
+use quantity::{METER, MOL};
 use serde::{Deserialize, Serialize};
+use typenum::P3;
 
 /// Cubic parameters for a single substance.
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -13,6 +16,43 @@ pub struct CubicRecord {
     pub(crate) pc: f64,
     /// acentric factor
     pub(crate) acentric_factor: f64,
+    /// component specific alpha function parameters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub(crate) alpha: Option<AlphaParameters>,
+    /// parachor in units of (mN/m)^(1/4) cm³/mol
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub(crate) parachor: Option<f64>,
+    /// Entropy scaling coefficients for the viscosity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub(crate) viscosity: Option<[f64; 4]>,
+    /// Entropy scaling coefficients for the self-diffusion coefficient
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub(crate) diffusion: Option<[f64; 5]>,
+    /// Entropy scaling coefficients for the thermal conductivity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub(crate) thermal_conductivity: Option<[f64; 4]>,
+    /// Charge and diameter of ions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub(crate) ion: Option<IonRecord>,
+    /// Peneloux volume shift in m³/mol
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub(crate) volume_shift: Option<f64>,
+}
+
+/// Parameters of an ion for the electrostatic contribution.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct IonRecord {
+    /// charge number
+    pub charge: f64,
+    /// diameter in units of Angstrom
+    pub diameter: f64,
 }
 
 impl CubicRecord {
@@ -22,15 +62,86 @@ impl CubicRecord {
             tc,
             pc,
             acentric_factor,
+            alpha: None,
+            parachor: None,
+            viscosity: None,
+            diffusion: None,
+            thermal_conductivity: None,
+            ion: None,
+            volume_shift: None,
         }
     }
+
+    /// Set component specific alpha function parameters.
+    pub fn with_alpha(mut self, alpha: AlphaParameters) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+
+    /// Set the parachor used for surface tensions.
+    pub fn with_parachor(mut self, parachor: f64) -> Self {
+        self.parachor = Some(parachor);
+        self
+    }
+
+    /// Set entropy scaling coefficients for the viscosity.
+    pub fn with_viscosity(mut self, viscosity: [f64; 4]) -> Self {
+        self.viscosity = Some(viscosity);
+        self
+    }
+
+    /// Set entropy scaling coefficients for the self-diffusion coefficient.
+    pub fn with_diffusion(mut self, diffusion: [f64; 5]) -> Self {
+        self.diffusion = Some(diffusion);
+        self
+    }
+
+    /// Set entropy scaling coefficients for the thermal conductivity.
+    pub fn with_thermal_conductivity(mut self, thermal_conductivity: [f64; 4]) -> Self {
+        self.thermal_conductivity = Some(thermal_conductivity);
+        self
+    }
+
+    /// Set the charge number and the diameter (in Angstrom) of an ion.
+    pub fn with_ion(mut self, charge: f64, diameter: f64) -> Self {
+        self.ion = Some(IonRecord { charge, diameter });
+        self
+    }
+
+    /// Set the (Peneloux) volume shift in m³/mol.
+    pub fn with_volume_shift(mut self, volume_shift: f64) -> Self {
+        self.volume_shift = Some(volume_shift);
+        self
+    }
 }
 
 impl std::fmt::Display for CubicRecord {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "CubicRecord(tc={} K", self.tc)?;
         write!(f, ", pc={} Pa", self.pc)?;
-        write!(f, ", acentric factor={}", self.acentric_factor)
+        write!(f, ", acentric factor={}", self.acentric_factor)?;
+        if let Some(alpha) = &self.alpha {
+            write!(f, ", alpha={:?}", alpha)?;
+        }
+        if let Some(parachor) = &self.parachor {
+            write!(f, ", parachor={}", parachor)?;
+        }
+        if let Some(viscosity) = &self.viscosity {
+            write!(f, ", viscosity={:?}", viscosity)?;
+        }
+        if let Some(diffusion) = &self.diffusion {
+            write!(f, ", diffusion={:?}", diffusion)?;
+        }
+        if let Some(thermal_conductivity) = &self.thermal_conductivity {
+            write!(f, ", thermal_conductivity={:?}", thermal_conductivity)?;
+        }
+        if let Some(ion) = &self.ion {
+            write!(f, ", charge={}, diameter={} A", ion.charge, ion.diameter)?;
+        }
+        if let Some(volume_shift) = &self.volume_shift {
+            write!(f, ", volume shift={} m³/mol", volume_shift)?;
+        }
+        write!(f, ")")
     }
 }
 
@@ -119,8 +230,26 @@ pub struct CubicParameters {
     pub(super) k_ij: Array2<f64>,
     /// Binary interaction parameter for b
     pub(super) l_ij: Array2<f64>,
+    /// Symmetric factors $1-k_{ij}$ of the quadratic mixing rule for a
+    pub(super) a_ij_factor: Array2<f64>,
+    /// Symmetric factors $1-l_{ij}$ of the quadratic mixing rule for b
+    pub(super) b_ij_factor: Array2<f64>,
     /// Molar weight in units of g/mol
     pub(super) molarweight: Array1<f64>,
+    /// Parachors in units of (mN/m)^(1/4) cm³/mol
+    pub(super) parachor: Option<Array1<f64>>,
+    /// Entropy scaling coefficients for the viscosity
+    pub(super) viscosity: Option<Array2<f64>>,
+    /// Entropy scaling coefficients for the self-diffusion coefficient
+    pub(super) diffusion: Option<Array2<f64>>,
+    /// Entropy scaling coefficients for the thermal conductivity
+    pub(super) thermal_conductivity: Option<Array2<f64>>,
+    /// Charge numbers (0 for neutral components)
+    pub(super) charge: Array1<f64>,
+    /// Diameters of ions in units of Angstrom (0 for neutral components)
+    pub(super) ion_diameter: Array1<f64>,
+    /// Volume shifts in units of Angstrom³ per molecule
+    pub(super) volume_shift: Array1<f64>,
     /// List of pure component records
     pub(super) pure_records: Vec<PureRecord<CubicRecord>>,
     /// List of binary records
@@ -144,27 +273,119 @@ impl CubicParameters {
         acentric_factor: &[f64],
         molarweight: &[f64],
     ) -> Result<Self, FeosError> {
-        if [pc.len(), acentric_factor.len(), molarweight.len()]
-            .iter()
-            .any(|&l| l != tc.len())
+        Self::from_critical_data(tc, pc, acentric_factor, Some(molarweight), None)
+    }
+
+    /// Build a parameter set without binary interaction parameters directly
+    /// from critical temperatures (in K), critical pressures (in Pa) and
+    /// acentric factors.
+    ///
+    /// Molar weights (in g/mol) are only required for mass specific properties
+    /// and are set to NaN if not provided. Volume shifts are given in m³/mol.
+    pub fn from_critical_data(
+        tc: &[f64],
+        pc: &[f64],
+        acentric_factor: &[f64],
+        molarweight: Option<&[f64]>,
+        volume_shift: Option<&[f64]>,
+    ) -> FeosResult<Self> {
+        let n = tc.len();
+        if [pc.len(), acentric_factor.len()]
+            .into_iter()
+            .chain(molarweight.map(|m| m.len()))
+            .chain(volume_shift.map(|c| c.len()))
+            .any(|l| l != n)
         {
             return Err(FeosError::IncompatibleParameters(String::from(
                 "each component has to have parameters.",
             )));
         }
-        let records = (0..tc.len())
+        let records = (0..n)
             .map(|i| {
-                let record = CubicRecord {
-                    tc: tc[i],
-                    pc: pc[i],
-                    acentric_factor: acentric_factor[i],
-                };
-                let id = Identifier::default();
-                PureRecord::new(id, molarweight[i], record)
+                let mut record = CubicRecord::new(tc[i], pc[i], acentric_factor[i]);
+                if let Some(c) = volume_shift {
+                    record = record.with_volume_shift(c[i]);
+                }
+                let molarweight = molarweight.map_or(f64::NAN, |m| m[i]);
+                PureRecord::new(Identifier::default(), molarweight, record)
             })
             .collect();
         CubicParameters::from_records(records, None)
     }
+
+    /// Alpha function from the component specific parameters in the pure records,
+    /// if all records provide them.
+    pub(super) fn alpha(&self) -> FeosResult<Option<Alpha>> {
+        let alpha: Vec<_> = self
+            .pure_records
+            .iter()
+            .filter_map(|r| r.model_record.alpha)
+            .collect();
+        if alpha.is_empty() {
+            Ok(None)
+        } else if alpha.len() == self.pure_records.len() {
+            Alpha::from_parameters(&alpha).map(Some)
+        } else {
+            Err(FeosError::IncompatibleParameters(String::from(
+                "alpha function parameters are only provided for some of the components.",
+            )))
+        }
+    }
+}
+
+// Bounds for plausible parameters used in the validation.
+const MAX_TC: f64 = 2000.0;
+const MIN_PC: f64 = 1e5;
+const MAX_PC: f64 = 1e8;
+const MIN_ACENTRIC_FACTOR: f64 = -0.5;
+const MAX_ACENTRIC_FACTOR: f64 = 2.0;
+const MIN_MOLARWEIGHT: f64 = 1.0;
+const MAX_MOLARWEIGHT: f64 = 5000.0;
+const MAX_BINARY_PARAMETER: f64 = 0.5;
+
+/// Check the component specific alpha function parameters.
+///
+/// Non-finite coefficients are errors, coefficients outside of the range
+/// for which the alpha functions are monotonically decreasing are warnings.
+fn validate_alpha(report: &mut ValidationReport, i: usize, alpha: &AlphaParameters) {
+    if alpha.coefficients().iter().any(|c| !c.is_finite()) {
+        report.error(vec![i], "alpha function coefficients are not finite");
+        return;
+    }
+    let plausible = match *alpha {
+        AlphaParameters::Soave { m } => m >= 0.0,
+        AlphaParameters::Twu { l, m, n } => l >= 0.0 && m > 0.0 && n > 0.0,
+        AlphaParameters::MathiasCopeman { c1, .. } => c1 >= 0.0,
+    };
+    if !plausible {
+        report.warning(
+            vec![i],
+            format!("alpha function coefficients {alpha:?} are out of range"),
+        );
+    }
+}
+
+/// Factors $1-k_{ij}$ from the upper triangle of the binary interaction parameters.
+fn symmetric_factors(k_ij: &Array2<f64>) -> Array2<f64> {
+    Array2::from_shape_fn(k_ij.raw_dim(), |(i, j)| match i.cmp(&j) {
+        std::cmp::Ordering::Less => 1.0 - k_ij[[i, j]],
+        std::cmp::Ordering::Equal => 1.0,
+        std::cmp::Ordering::Greater => 1.0 - k_ij[[j, i]],
+    })
+}
+
+/// Collect entropy scaling coefficients column-wise, if all components provide them.
+fn entropy_scaling_coefficients<const N: usize>(
+    coefficients: &[Option<[f64; N]>],
+) -> Option<Array2<f64>> {
+    if coefficients.iter().any(|c| c.is_none()) {
+        return None;
+    }
+    let mut c = Array2::zeros((N, coefficients.len()));
+    for (i, ci) in coefficients.iter().enumerate() {
+        c.column_mut(i).assign(&Array1::from(ci.unwrap().to_vec()));
+    }
+    Some(c)
 }
 
 impl Parameter for CubicParameters {
@@ -182,6 +403,12 @@ impl Parameter for CubicParameters {
         let mut pc = Array1::zeros(n);
         let mut acentric_factor = Array1::zeros(n);
         let mut molarweight = Array1::zeros(n);
+        let mut viscosity = Vec::with_capacity(n);
+        let mut diffusion = Vec::with_capacity(n);
+        let mut thermal_conductivity = Vec::with_capacity(n);
+        let mut charge = Array1::zeros(n);
+        let mut ion_diameter = Array1::zeros(n);
+        let mut volume_shift = Array1::zeros(n);
 
         for (i, record) in pure_records.iter().enumerate() {
             molarweight[i] = record.molarweight;
@@ -189,11 +416,31 @@ impl Parameter for CubicParameters {
             tc[i] = r.tc;
             pc[i] = r.pc;
             acentric_factor[i] = r.acentric_factor;
+            viscosity.push(r.viscosity);
+            diffusion.push(r.diffusion);
+            thermal_conductivity.push(r.thermal_conductivity);
+            if let Some(ion) = r.ion {
+                charge[i] = ion.charge;
+                ion_diameter[i] = ion.diameter;
+            }
+            if let Some(c) = r.volume_shift {
+                volume_shift[i] = (c * METER.powi::<P3>() / MOL).to_reduced();
+            }
         }
 
+        let parachor = pure_records
+            .iter()
+            .map(|r| r.model_record.parachor)
+            .collect::<Option<Array1<f64>>>();
+        let viscosity = entropy_scaling_coefficients(&viscosity);
+        let diffusion = entropy_scaling_coefficients(&diffusion);
+        let thermal_conductivity = entropy_scaling_coefficients(&thermal_conductivity);
+
         let br = binary_records.as_ref();
         let k_ij = br.map_or_else(|| Array2::zeros([n; 2]), |br| br.mapv(|br| br.k_ij));
         let l_ij = br.map_or_else(|| Array2::zeros([n; 2]), |br| br.mapv(|br| br.l_ij));
+        let a_ij_factor = symmetric_factors(&k_ij);
+        let b_ij_factor = symmetric_factors(&l_ij);
 
         Ok(Self {
             tc,
@@ -201,7 +448,16 @@ impl Parameter for CubicParameters {
             acentric_factor,
             k_ij,
             l_ij,
+            a_ij_factor,
+            b_ij_factor,
             molarweight,
+            parachor,
+            viscosity,
+            diffusion,
+            thermal_conductivity,
+            charge,
+            ion_diameter,
+            volume_shift,
             pure_records,
             binary_records,
         })
@@ -215,4 +471,82 @@ impl Parameter for CubicParameters {
     ) {
         (&self.pure_records, self.binary_records.as_ref())
     }
+
+    /// Check critical properties, molar weights, alpha function parameters,
+    /// binary interaction parameters and identifiers.
+    fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::from_records(&self.pure_records);
+        for (i, record) in self.pure_records.iter().enumerate() {
+            let r = &record.model_record;
+            if !(r.tc.is_finite() && r.tc > 0.0) {
+                report.error(
+                    vec![i],
+                    format!("critical temperature {} K is not positive", r.tc),
+                );
+            } else if r.tc > MAX_TC {
+                report.warning(
+                    vec![i],
+                    format!("critical temperature {} K is implausible", r.tc),
+                );
+            }
+            if !(r.pc.is_finite() && r.pc > 0.0) {
+                report.error(
+                    vec![i],
+                    format!("critical pressure {} Pa is not positive", r.pc),
+                );
+            } else if !(MIN_PC..=MAX_PC).contains(&r.pc) {
+                report.warning(
+                    vec![i],
+                    format!(
+                        "critical pressure {} Pa is implausible (wrong units?)",
+                        r.pc
+                    ),
+                );
+            }
+            if !r.acentric_factor.is_finite() {
+                report.error(vec![i], "acentric factor is not finite");
+            } else if !(MIN_ACENTRIC_FACTOR..=MAX_ACENTRIC_FACTOR).contains(&r.acentric_factor) {
+                report.warning(
+                    vec![i],
+                    format!("acentric factor {} is implausible", r.acentric_factor),
+                );
+            }
+            let mw = record.molarweight;
+            if !(mw.is_finite() && mw > 0.0) {
+                report.error(vec![i], "molar weight is missing or not positive");
+            } else if !(MIN_MOLARWEIGHT..=MAX_MOLARWEIGHT).contains(&mw) {
+                report.warning(vec![i], format!("molar weight {} g/mol is implausible", mw));
+            }
+            if let Some(alpha) = &r.alpha {
+                validate_alpha(&mut report, i, alpha);
+            }
+            if let Some(ion) = &r.ion {
+                if !ion.charge.is_finite() {
+                    report.error(vec![i], "charge number is not finite");
+                }
+                if !(ion.diameter.is_finite() && ion.diameter > 0.0) {
+                    report.error(
+                        vec![i],
+                        format!("ion diameter {} A is not positive", ion.diameter),
+                    );
+                }
+            }
+            if r.volume_shift.is_some_and(|c| !c.is_finite()) {
+                report.error(vec![i], "volume shift is not finite");
+            }
+        }
+        let n = self.pure_records.len();
+        for i in 0..n {
+            for j in i + 1..n {
+                for (name, k) in [("k_ij", self.k_ij[[i, j]]), ("l_ij", self.l_ij[[i, j]])] {
+                    if !k.is_finite() {
+                        report.error(vec![i, j], format!("{name} is not finite"));
+                    } else if k.abs() > MAX_BINARY_PARAMETER {
+                        report.warning(vec![i, j], format!("{name} = {k} is implausibly large"));
+                    }
+                }
+            }
+        }
+        report
+    }
 }
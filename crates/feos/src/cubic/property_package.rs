@@ -0,0 +1,411 @@
+use super::alpha::Alpha;
+use super::electrolyte::DebyeHuckel;
+use super::mixing_rules::MixingRule;
+use super::parameters::{CubicBinaryRecord, CubicParameters, CubicRecord};
+use super::{Cubic, CubicOptions, Delta};
+use crate::ideal_gas::{IdealGasRecord, MixedIdealGas};
+use feos_core::parameter::{Parameter, PureRecord};
+use feos_core::{Components, EquationOfState, FeosError, FeosResult, IdealGas};
+use ndarray::{Array1, Array2};
+use num_dual::{Dual64, DualNum};
+use quantity::{KELVIN, PASCAL, Pressure, Temperature};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Current version of the layout of property packages.
+pub const PROPERTY_PACKAGE_VERSION: u32 = 1;
+
+const BOLTZMANN: f64 = 1.380649e-23;
+
+/// Binary interaction parameters of a pair of components in a property package.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PackageBinaryRecord {
+    /// Index of the first component
+    pub i: usize,
+    /// Index of the second component
+    pub j: usize,
+    /// Binary interaction parameter for a
+    #[serde(default)]
+    pub k_ij: f64,
+    /// Binary interaction parameter for b
+    #[serde(default)]
+    pub l_ij: f64,
+}
+
+/// State at which the enthalpy and the entropy of every pure component in
+/// the ideal gas state are zero.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ReferenceState {
+    /// Temperature in Kelvin
+    pub temperature: f64,
+    /// Pressure in Pascal
+    pub pressure: f64,
+}
+
+impl ReferenceState {
+    pub fn new(temperature: Temperature, pressure: Pressure) -> Self {
+        Self {
+            temperature: temperature.convert_to(KELVIN),
+            pressure: pressure.convert_to(PASCAL),
+        }
+    }
+
+    /// Constants $\alpha_i$ and $\beta_i$ that are added to the logarithm of
+    /// the thermal de Broglie wavelength as $\alpha_i+\beta_i/T$ so that the
+    /// ideal gas enthalpy and entropy of each component vanish in the
+    /// reference state.
+    fn offsets(&self, ideal_gas: &MixedIdealGas) -> (Array1<f64>, Array1<f64>) {
+        let t0 = self.temperature;
+        let ln_rho0 = (self.pressure / (BOLTZMANN * t0) * 1e-30).ln();
+        let ln_lambda3 = ideal_gas.ln_lambda3(Dual64::from(t0).derivative());
+        let beta = ln_lambda3.mapv(|l| -t0 * (1.0 - t0 * l.eps));
+        let alpha = ln_lambda3.mapv(|l| -(ln_rho0 + l.re - 1.0) - t0 * l.eps);
+        (alpha, beta)
+    }
+}
+
+/// Self-contained snapshot of a fully configured cubic equation of state.
+///
+/// The package contains the pure component records, the binary interaction
+/// parameters, the universal constants, the alpha function, the mixing rule
+/// and the electrostatic contribution, and optionally the ideal gas records
+/// together with a reference state. It is written to and read from a single
+/// json file and rebuilds an equation of state that yields identical results.
+///
+/// Packages written by newer versions of the layout are rejected.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PropertyPackage {
+    /// Version of the layout of the package
+    pub version: u32,
+    /// Version of feos that wrote the package
+    pub feos_version: String,
+    /// Universal constants $\delta_1$ and $\delta_2$ of the cubic equation of state
+    pub delta: Delta,
+    pub alpha: Alpha,
+    pub mixing: MixingRule,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub electrostatics: Option<DebyeHuckel>,
+    pub pure_records: Vec<PureRecord<CubicRecord>>,
+    /// Non-zero binary interaction parameters
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub binary_records: Vec<PackageBinaryRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ideal_gas: Option<Vec<PureRecord<IdealGasRecord>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference_state: Option<ReferenceState>,
+}
+
+impl PropertyPackage {
+    /// Snapshot of the residual model.
+    pub fn new(eos: &Cubic) -> Self {
+        let p = &eos.parameters;
+        let binary_records = p
+            .k_ij
+            .indexed_iter()
+            .zip(&p.l_ij)
+            .filter(|&(((i, j), &k_ij), &l_ij)| i != j && (k_ij != 0.0 || l_ij != 0.0))
+            .map(|(((i, j), &k_ij), &l_ij)| PackageBinaryRecord { i, j, k_ij, l_ij })
+            .collect();
+        Self {
+            version: PROPERTY_PACKAGE_VERSION,
+            feos_version: env!("CARGO_PKG_VERSION").into(),
+            delta: eos.options.delta,
+            alpha: eos.options.alpha.clone(),
+            mixing: eos.options.mixing.clone(),
+            electrostatics: eos.options.electrostatics,
+            pure_records: p.pure_records.clone(),
+            binary_records,
+            ideal_gas: None,
+            reference_state: None,
+        }
+    }
+
+    /// Snapshot of the residual and the ideal gas model.
+    pub fn from_equation_of_state(eos: &EquationOfState<MixedIdealGas, Cubic>) -> Self {
+        Self::new(&eos.residual).with_ideal_gas(eos.ideal_gas.records().0.to_vec())
+    }
+
+    /// Add the ideal gas records of all components.
+    pub fn with_ideal_gas(mut self, records: Vec<PureRecord<IdealGasRecord>>) -> Self {
+        self.ideal_gas = Some(records);
+        self
+    }
+
+    /// Set the reference state of the ideal gas model.
+    pub fn with_reference_state(mut self, reference_state: ReferenceState) -> Self {
+        self.reference_state = Some(reference_state);
+        self
+    }
+
+    /// Read a package from a json file.
+    pub fn from_json<P: AsRef<Path>>(file: P) -> FeosResult<Self> {
+        let package: Self = serde_json::from_reader(BufReader::new(File::open(file)?))?;
+        package.check_version()
+    }
+
+    /// Read a package from a json string.
+    pub fn from_json_str(json: &str) -> FeosResult<Self> {
+        let package: Self = serde_json::from_str(json)?;
+        package.check_version()
+    }
+
+    /// Write the package to a json file.
+    pub fn to_json<P: AsRef<Path>>(&self, file: P) -> FeosResult<()> {
+        Ok(serde_json::to_writer_pretty(
+            BufWriter::new(File::create(file)?),
+            self,
+        )?)
+    }
+
+    /// Serialize the package as a json string.
+    pub fn to_json_string(&self) -> FeosResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    fn check_version(self) -> FeosResult<Self> {
+        if self.version > PROPERTY_PACKAGE_VERSION {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "property package version {} (written by feos {}) is not supported (latest: {PROPERTY_PACKAGE_VERSION}).",
+                self.version, self.feos_version
+            )));
+        }
+        Ok(self)
+    }
+
+    /// Rebuild the residual model.
+    pub fn residual(&self) -> FeosResult<Cubic> {
+        let n = self.pure_records.len();
+        let mut binary_matrix = Array2::from_elem((n, n), CubicBinaryRecord::default());
+        for r in &self.binary_records {
+            if r.i >= n || r.j >= n {
+                return Err(FeosError::IncompatibleParameters(format!(
+                    "binary record ({}, {}) of a package with {n} components.",
+                    r.i, r.j
+                )));
+            }
+            binary_matrix[[r.i, r.j]] = CubicBinaryRecord::new(Some(r.k_ij), Some(r.l_ij));
+        }
+        let parameters =
+            CubicParameters::from_records(self.pure_records.clone(), Some(binary_matrix))?;
+        let options = CubicOptions {
+            alpha: self.alpha.clone(),
+            mixing: self.mixing.clone(),
+            delta: self.delta,
+            electrostatics: None,
+        };
+        let cubic = Cubic::new(Arc::new(parameters), options)?;
+        match self.electrostatics {
+            Some(debye_huckel) => cubic.with_debye_huckel(debye_huckel),
+            None => Ok(cubic),
+        }
+    }
+
+    /// Rebuild the ideal gas model including the reference state.
+    pub fn ideal_gas(&self) -> FeosResult<ReferencedIdealGas> {
+        let records = self.ideal_gas.clone().ok_or_else(|| {
+            FeosError::IncompatibleParameters(String::from(
+                "the property package contains no ideal gas records.",
+            ))
+        })?;
+        if records.len() != self.pure_records.len() {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "the property package contains {} ideal gas records for {} components.",
+                records.len(),
+                self.pure_records.len()
+            )));
+        }
+        let ideal_gas = MixedIdealGas::from_records(records, None)?;
+        Ok(ReferencedIdealGas::new(ideal_gas, self.reference_state))
+    }
+
+    /// Rebuild the full equation of state.
+    pub fn equation_of_state(&self) -> FeosResult<EquationOfState<ReferencedIdealGas, Cubic>> {
+        Ok(EquationOfState::new(
+            Arc::new(self.ideal_gas()?),
+            Arc::new(self.residual()?),
+        ))
+    }
+}
+
+/// Ideal gas model of a property package, i.e., a [MixedIdealGas] with an
+/// optional common [ReferenceState] for all components.
+///
+/// Without a reference state, each component uses the reference state of
+/// its model.
+pub struct ReferencedIdealGas {
+    ideal_gas: MixedIdealGas,
+    reference_state: Option<ReferenceState>,
+    alpha: Array1<f64>,
+    beta: Array1<f64>,
+}
+
+impl ReferencedIdealGas {
+    pub fn new(ideal_gas: MixedIdealGas, reference_state: Option<ReferenceState>) -> Self {
+        let (alpha, beta) = match reference_state {
+            Some(reference_state) => reference_state.offsets(&ideal_gas),
+            None => {
+                let n = ideal_gas.components();
+                (Array1::zeros(n), Array1::zeros(n))
+            }
+        };
+        Self {
+            ideal_gas,
+            reference_state,
+            alpha,
+            beta,
+        }
+    }
+
+    /// The reference state of the model.
+    pub fn reference_state(&self) -> Option<ReferenceState> {
+        self.reference_state
+    }
+}
+
+impl Components for ReferencedIdealGas {
+    fn components(&self) -> usize {
+        self.ideal_gas.components()
+    }
+
+    fn subset(&self, component_list: &[usize]) -> Self {
+        Self {
+            ideal_gas: Components::subset(&self.ideal_gas, component_list),
+            reference_state: self.reference_state,
+            alpha: component_list.iter().map(|&i| self.alpha[i]).collect(),
+            beta: component_list.iter().map(|&i| self.beta[i]).collect(),
+        }
+    }
+}
+
+impl IdealGas for ReferencedIdealGas {
+    fn ln_lambda3<D: DualNum<f64> + Copy>(&self, temperature: D) -> Array1<D> {
+        let t_inv = temperature.recip();
+        let mut ln_lambda3 = self.ideal_gas.ln_lambda3(temperature);
+        for ((l, &alpha), &beta) in ln_lambda3.iter_mut().zip(&self.alpha).zip(&self.beta) {
+            *l += t_inv * beta + alpha;
+        }
+        ln_lambda3
+    }
+
+    fn ideal_gas_model(&self) -> String {
+        match self.reference_state {
+            Some(_) => "Ideal gas (mixed, common reference state)".into(),
+            None => self.ideal_gas.ideal_gas_model(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{
+        AqueousKij, PengRobinson1978, Quadratic, SoreideWhitsonAlpha, SoreideWhitsonMixing,
+    };
+    use crate::ideal_gas::JobackRecord;
+    use approx::assert_relative_eq;
+    use feos_core::parameter::Identifier;
+    use feos_core::{Contributions, DensityInitialization, State};
+    use ndarray::arr1;
+    use quantity::{BAR, JOULE, MOL, RGAS};
+
+    fn records() -> Vec<PureRecord<CubicRecord>> {
+        let record = |name, mw, tc, pc, w| {
+            let id = Identifier::new(None, Some(name), None, None, None, None);
+            PureRecord::new(id, mw, CubicRecord::new(tc, pc, w))
+        };
+        vec![
+            record("methane", 16.043, 190.56, 4599000.0, 0.011),
+            record("propane", 44.097, 369.83, 4248000.0, 0.1523),
+            record("water", 18.015, 647.14, 22064000.0, 0.344),
+        ]
+    }
+
+    fn binaries() -> Array2<CubicBinaryRecord> {
+        Array2::from_shape_fn((3, 3), |(i, j)| match (i.min(j), i.max(j)) {
+            (0, 1) => CubicBinaryRecord::new(Some(0.014), Some(-0.003)),
+            (0, 2) => CubicBinaryRecord::from(0.5),
+            (1, 2) => CubicBinaryRecord::from(0.48),
+            _ => CubicBinaryRecord::default(),
+        })
+    }
+
+    #[test]
+    fn round_trip() -> FeosResult<()> {
+        let parameters = Arc::new(CubicParameters::from_records(records(), Some(binaries()))?);
+        let alpha = SoreideWhitsonAlpha::new(2, 1.5, PengRobinson1978);
+        let mixing = SoreideWhitsonMixing::new(
+            2,
+            1.5,
+            vec![
+                AqueousKij::Hydrocarbon,
+                AqueousKij::Hydrocarbon,
+                AqueousKij::Hydrocarbon,
+            ],
+        );
+        let eos = Cubic::peng_robinson(parameters, Some(alpha.into()), Some(mixing.into()))?;
+        let joback = |a| JobackRecord::new(a, 0.05, 1e-5, 0.0, 0.0);
+        let ideal_gas: Vec<_> = records()
+            .into_iter()
+            .zip([19.25, -4.22, 32.24])
+            .map(|(r, a)| PureRecord::new(r.identifier, r.molarweight, joback(a).into()))
+            .collect();
+        let package = PropertyPackage::new(&eos)
+            .with_ideal_gas(ideal_gas)
+            .with_reference_state(ReferenceState::new(298.15 * KELVIN, BAR));
+        let json = package.to_json_string()?;
+        let package = PropertyPackage::from_json_str(&json)?;
+        assert_eq!(package.to_json_string()?, json);
+        assert_eq!(package.binary_records.len(), 6);
+
+        // the residual model is reproduced exactly
+        let rebuilt = package.residual()?;
+        let moles = arr1(&[0.2, 0.3, 0.5]) * MOL;
+        let state = |eos: &Arc<_>| {
+            State::new_npt(
+                eos,
+                350.0 * KELVIN,
+                50.0 * BAR,
+                &moles,
+                DensityInitialization::Liquid,
+            )
+        };
+        let s1 = state(&Arc::new(eos))?;
+        let s2 = state(&Arc::new(rebuilt))?;
+        assert_eq!(s1.density, s2.density);
+        assert_eq!(s1.ln_phi().to_vec(), s2.ln_phi().to_vec());
+
+        // the ideal gas enthalpy and entropy vanish at the ideal gas density
+        // of the reference state
+        let eos = Arc::new(package.equation_of_state()?);
+        let (t0, moles) = (298.15 * KELVIN, arr1(&[1.0, 0.0, 0.0]) * MOL);
+        let reference = State::new_nvt(&eos, t0, MOL * RGAS * t0 / BAR, &moles)?;
+        let h = reference.molar_enthalpy(Contributions::IdealGas);
+        let s = reference.molar_entropy(Contributions::IdealGas);
+        assert_relative_eq!(h.convert_to(JOULE / MOL), 0.0, epsilon = 1e-8);
+        assert_relative_eq!(s.convert_to(JOULE / MOL / KELVIN), 0.0, epsilon = 1e-8);
+
+        // newer versions are rejected
+        let json = json.replacen("\"version\": 1", "\"version\": 2", 1);
+        assert!(PropertyPackage::from_json_str(&json).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn default_mixing_rule() -> FeosResult<()> {
+        let parameters = Arc::new(CubicParameters::from_records(records(), None)?);
+        let eos = Cubic::redlich_kwong(parameters, None, Some(Quadratic::default().into()))?;
+        let package = PropertyPackage::new(&eos);
+        assert!(package.binary_records.is_empty());
+        assert!(package.ideal_gas().is_err());
+        let rebuilt = package.residual()?;
+        assert_eq!(rebuilt.critical_parameters.ac, eos.critical_parameters.ac);
+        assert_eq!(
+            rebuilt.attractive_parameters(300.0),
+            eos.attractive_parameters(300.0)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,395 @@
+use super::Cubic;
+use super::mixing_rules::{MixingRuleFunction, MixtureParameters, volume_shift};
+use super::parameters::CubicParameters;
+use feos_core::parameter::{GroupCountRecord, IdentifierOption, SegmentRecord};
+use feos_core::{FeosError, FeosResult, StateHD};
+use indexmap::IndexMap;
+use ndarray::{Array1, Array2, Axis, ScalarOperand};
+use num_dual::DualNum;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Constant of the MHV1 mixing rule for the Soave-Redlich-Kwong equation of state.
+const Q1: f64 = -0.64663;
+/// Coordination number of the UNIFAC combinatorial part.
+const Z_HALF: f64 = 5.0;
+
+/// Parameters of a UNIFAC subgroup in PSRK.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PsrkGroupRecord {
+    /// Main group, that determines the group interaction parameters.
+    pub main_group: String,
+    /// Relative van der Waals volume.
+    pub r: f64,
+    /// Relative van der Waals surface area.
+    pub q: f64,
+}
+
+impl PsrkGroupRecord {
+    pub fn new(main_group: String, r: f64, q: f64) -> Self {
+        Self { main_group, r, q }
+    }
+}
+
+/// Group interaction parameters between two PSRK main groups.
+///
+/// The interaction parameter from main group $m$ to main group $n$ is
+/// $\Psi_{mn}=\exp\left(-\frac{a_{mn}+b_{mn}T+c_{mn}T^2}{T}\right)$
+/// with $a$ in K, $b$ dimensionless and $c$ in 1/K.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PsrkInteractionRecord {
+    /// Identifier of the first main group.
+    pub id1: String,
+    /// Identifier of the second main group.
+    pub id2: String,
+    pub a12: f64,
+    pub a21: f64,
+    #[serde(default)]
+    pub b12: f64,
+    #[serde(default)]
+    pub b21: f64,
+    #[serde(default)]
+    pub c12: f64,
+    #[serde(default)]
+    pub c21: f64,
+}
+
+impl PsrkInteractionRecord {
+    /// Read a list of interaction records from a json file.
+    pub fn from_json<P: AsRef<Path>>(file: P) -> FeosResult<Vec<Self>> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(file)?))?)
+    }
+}
+
+/// Mixing rule of the predictive Soave-Redlich-Kwong (PSRK) equation of
+/// state of Holderbaum and Gmehling (1991).
+///
+/// The energy parameter is obtained from the MHV1 mixing rule
+///
+/// $$\frac{a}{bRT}=\sum_ix_i\frac{a_i}{b_iRT}+\frac{1}{q_1}\left(\frac{g^E_0}{RT}+\sum_ix_i\ln\frac{b}{b_i}\right)\quad\text{with}\quad b=\sum_ix_ib_i$$
+///
+/// and $q_1=-0.64663$, where the excess Gibbs energy is calculated with
+/// UNIFAC from the group counts of the components and the group and
+/// group interaction parameters of PSRK. Binary interaction parameters in
+/// the cubic parameters are not used.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Psrk {
+    /// Number of each group in each component.
+    nu: Array2<f64>,
+    /// Relative van der Waals volumes of the groups.
+    r: Array1<f64>,
+    /// Relative van der Waals surface areas of the groups.
+    q: Array1<f64>,
+    /// Group interaction parameters.
+    a: Array2<f64>,
+    b: Array2<f64>,
+    c: Array2<f64>,
+}
+
+impl Psrk {
+    /// Create the mixing rule from the group counts of all components and
+    /// the group and group interaction parameters.
+    pub fn new(
+        group_counts: &[GroupCountRecord],
+        groups: &[SegmentRecord<PsrkGroupRecord>],
+        interactions: &[PsrkInteractionRecord],
+    ) -> FeosResult<Self> {
+        let mut group_map: IndexMap<_, _> = IndexMap::new();
+        for record in group_counts {
+            for id in record.segments.keys() {
+                let group = groups
+                    .iter()
+                    .find(|g| &g.identifier == id)
+                    .ok_or_else(|| FeosError::ComponentsNotFound(vec![id.clone()]))?;
+                group_map.insert(id.clone(), &group.model_record);
+            }
+        }
+        group_map.sort_keys();
+
+        let mut nu = Array2::zeros((group_counts.len(), group_map.len()));
+        for (i, record) in group_counts.iter().enumerate() {
+            for (id, &count) in &record.segments {
+                nu[[i, group_map.get_index_of(id).unwrap()]] = count;
+            }
+        }
+        let r = group_map.values().map(|g| g.r).collect();
+        let q = group_map.values().map(|g| g.q).collect();
+
+        let n = group_map.len();
+        let (mut a, mut b, mut c) = (
+            Array2::zeros((n, n)),
+            Array2::zeros((n, n)),
+            Array2::zeros((n, n)),
+        );
+        let mut missing = Vec::new();
+        for (m, gm) in group_map.values().enumerate() {
+            for (k, gk) in group_map.values().enumerate() {
+                if gm.main_group == gk.main_group {
+                    continue;
+                }
+                let (m1, m2) = (&gm.main_group, &gk.main_group);
+                let parameters = interactions.iter().find_map(|i| {
+                    if (&i.id1, &i.id2) == (m1, m2) {
+                        Some((i.a12, i.b12, i.c12))
+                    } else if (&i.id1, &i.id2) == (m2, m1) {
+                        Some((i.a21, i.b21, i.c21))
+                    } else {
+                        None
+                    }
+                });
+                match parameters {
+                    Some(p) => (a[[m, k]], b[[m, k]], c[[m, k]]) = p,
+                    None if m < k => missing.push(format!("{m1}-{m2}")),
+                    None => (),
+                }
+            }
+        }
+        if !missing.is_empty() {
+            missing.sort();
+            missing.dedup();
+            return Err(FeosError::IncompatibleParameters(format!(
+                "no PSRK group interaction parameters for the main groups {}.",
+                missing.join(", ")
+            )));
+        }
+        Ok(Self { nu, r, q, a, b, c })
+    }
+
+    /// Read the group counts of the given substances and the group and group
+    /// interaction parameters from json files.
+    pub fn from_json<P: AsRef<Path>>(
+        substances: &[&str],
+        file_substances: P,
+        file_groups: P,
+        file_interactions: P,
+        identifier_option: IdentifierOption,
+    ) -> FeosResult<Self> {
+        let group_counts =
+            GroupCountRecord::from_json(substances, file_substances, identifier_option)?;
+        let groups = SegmentRecord::from_json(file_groups)?;
+        let interactions = PsrkInteractionRecord::from_json(file_interactions)?;
+        Self::new(&group_counts, &groups, &interactions)
+    }
+
+    /// Logarithms of the residual group activity coefficients for the given
+    /// group mole fractions.
+    fn ln_gamma_groups<D: DualNum<f64> + Copy + ScalarOperand>(
+        &self,
+        psi: &Array2<D>,
+        x: &Array1<D>,
+    ) -> Array1<D> {
+        let qx = x * &self.q;
+        let theta = &qx / qx.sum();
+        let s: Array1<D> = (0..self.q.len())
+            .map(|m| (0..self.q.len()).fold(D::zero(), |acc, n| acc + theta[n] * psi[[n, m]]))
+            .collect();
+        let theta_s = theta / &s;
+        Array1::from_shape_fn(self.q.len(), |k| {
+            let sum = (0..self.q.len()).fold(D::zero(), |acc, m| acc + theta_s[m] * psi[[k, m]]);
+            (-s[k].ln() - sum + 1.0) * self.q[k]
+        })
+    }
+
+    /// Excess Gibbs energy divided by $RT$ from UNIFAC.
+    pub fn excess_gibbs_energy<D: DualNum<f64> + Copy + ScalarOperand>(
+        &self,
+        temperature: D,
+        molefracs: &Array1<D>,
+    ) -> D {
+        let x = molefracs;
+        let ri = self.nu.dot(&self.r);
+        let qi = self.nu.dot(&self.q);
+
+        // combinatorial part
+        let r_mix = (x * &ri).sum();
+        let q_mix = (x * &qi).sum();
+        let mut g_e = D::zero();
+        for i in 0..x.len() {
+            let phi = r_mix.recip() * ri[i];
+            let theta_phi = r_mix / q_mix * (qi[i] / ri[i]);
+            g_e += x[i] * (phi.ln() + theta_phi.ln() * qi[i] * Z_HALF);
+        }
+
+        // residual part
+        let psi = Array2::from_shape_fn(self.a.raw_dim(), |(m, n)| {
+            let u = (temperature * self.c[[m, n]] + self.b[[m, n]]) * temperature + self.a[[m, n]];
+            (-u / temperature).exp()
+        });
+        let x_groups = self
+            .nu
+            .outer_iter()
+            .zip(x)
+            .fold(Array1::<D>::zeros(self.q.len()), |acc, (nu_i, &x_i)| {
+                acc + &nu_i.mapv(|nu| x_i * nu)
+            });
+        let ln_gamma = self.ln_gamma_groups(&psi, &(&x_groups / x_groups.sum()));
+        for (i, nu_i) in self.nu.outer_iter().enumerate() {
+            let x_i = nu_i.mapv(|nu| D::from(nu / nu_i.sum()));
+            let ln_gamma_i = self.ln_gamma_groups(&psi, &x_i);
+            g_e += x[i] * (&(&ln_gamma - &ln_gamma_i) * &nu_i).sum();
+        }
+        g_e
+    }
+}
+
+impl MixingRuleFunction for Psrk {
+    fn apply<D: DualNum<f64> + Copy + ScalarOperand>(
+        &self,
+        cubic: &Cubic,
+        state: &StateHD<D>,
+    ) -> MixtureParameters<D> {
+        let p = &cubic.parameters;
+        let pc = &cubic.critical_parameters;
+        let x = &state.molefracs;
+        let t = state.temperature;
+        let at = cubic.attractive_parameters(t);
+
+        let b = (x * &pc.bc).sum();
+        let mut alpha = self.excess_gibbs_energy(t, x) / Q1;
+        for i in 0..x.len() {
+            alpha += x[i] * (at[i] / (t * pc.bc[i]) + (b / pc.bc[i]).ln() / Q1);
+        }
+        MixtureParameters {
+            a: alpha * b * t,
+            b,
+            c: volume_shift(p, x),
+        }
+    }
+
+    fn validate(&self, parameters: &CubicParameters) -> FeosResult<()> {
+        if self.nu.nrows() != parameters.tc.len() {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "PSRK mixing rule was initialized for {} components, but the equation of state contains {}.",
+                self.nu.nrows(),
+                parameters.tc.len()
+            )));
+        }
+        Ok(())
+    }
+
+    fn subset(&self, component_list: &[usize]) -> Self {
+        Self {
+            nu: self.nu.select(Axis(0), component_list),
+            ..self.clone()
+        }
+    }
+}
+
+impl Cubic {
+    /// Predictive Soave-Redlich-Kwong equation of state (PSRK).
+    ///
+    /// Uses the Soave-Redlich-Kwong equation of state with the [Psrk] mixing
+    /// rule. The alpha function is built from component specific parameters
+    /// in the pure records (e.g., Mathias-Copeman, as in the original PSRK)
+    /// if available, otherwise Soave (1972) is used.
+    pub fn psrk(parameters: Arc<CubicParameters>, psrk: Psrk) -> FeosResult<Self> {
+        Self::redlich_kwong(parameters, None, Some(Box::new(psrk).into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use feos_core::parameter::Parameter;
+    use feos_core::{Components, Contributions, PhaseEquilibrium, Residual};
+    use ndarray::arr1;
+    use quantity::{KELVIN, PASCAL};
+
+    const SUBSTANCES: &str = "../../parameters/cubic/psrk_substances.json";
+    const GROUPS: &str = "../../parameters/cubic/psrk_groups.json";
+    const INTERACTIONS: &str = "../../parameters/cubic/psrk_interactions.json";
+
+    fn load(substances: &[&str]) -> FeosResult<(Arc<CubicParameters>, Psrk)> {
+        let parameters = CubicParameters::from_json(
+            substances.to_vec(),
+            SUBSTANCES,
+            None,
+            IdentifierOption::Name,
+        )?;
+        let psrk = Psrk::from_json(
+            substances,
+            SUBSTANCES,
+            GROUPS,
+            INTERACTIONS,
+            IdentifierOption::Name,
+        )?;
+        Ok((Arc::new(parameters), psrk))
+    }
+
+    #[test]
+    fn unifac() -> FeosResult<()> {
+        let (_, psrk) = load(&["methanol", "water"])?;
+        let g_e = psrk.excess_gibbs_energy(298.15, &arr1(&[0.5, 0.5]));
+        assert_relative_eq!(g_e, 0.14616007839204567, max_relative = 1e-10);
+        let g_e = psrk.excess_gibbs_energy(298.15, &arr1(&[1.0, 0.0]));
+        assert_relative_eq!(g_e, 0.0, epsilon = 1e-14);
+
+        let (_, psrk) = load(&["ethanol", "n-hexane", "water"])?;
+        let g_e = psrk.excess_gibbs_energy(300.0, &arr1(&[0.2, 0.5, 0.3]));
+        assert_relative_eq!(g_e, 1.0476224073027915, max_relative = 1e-10);
+        let subset = psrk.subset(&[0, 2]);
+        let g_e = subset.excess_gibbs_energy(323.15, &arr1(&[0.3, 0.7]));
+        assert_relative_eq!(g_e, 0.2941093221386709, max_relative = 1e-10);
+
+        let missing = Psrk::new(
+            &GroupCountRecord::from_json(
+                &["methanol", "carbon dioxide"],
+                SUBSTANCES,
+                IdentifierOption::Name,
+            )?,
+            &SegmentRecord::from_json(GROUPS)?,
+            &PsrkInteractionRecord::from_json(INTERACTIONS)?,
+        );
+        assert!(missing.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn psrk_vle() -> FeosResult<()> {
+        // pure components are described by Soave-Redlich-Kwong
+        let (parameters, mixing) = load(&["methanol", "water"])?;
+        let eos = Arc::new(Cubic::psrk(parameters.clone(), mixing)?);
+        let srk = Cubic::redlich_kwong(Arc::new(parameters.subset(&[1])), None, None)?;
+        let state = StateHD::new(350.0, 100.0, arr1(&[1.0]));
+        assert_relative_eq!(
+            eos.subset(&[1]).residual_helmholtz_energy(&state),
+            srk.residual_helmholtz_energy(&state),
+            max_relative = 1e-12
+        );
+
+        // bubble point of an equimolar methanol/water mixture at 1 atm
+        let vle = PhaseEquilibrium::bubble_point(
+            &eos,
+            101325.0 * PASCAL,
+            &arr1(&[0.5, 0.5]),
+            Some(350.0 * KELVIN),
+            None,
+            Default::default(),
+        )?;
+        let t = vle.vapor().temperature.convert_to(KELVIN);
+        assert!(t > 345.0 && t < 350.0);
+        assert!(vle.vapor().molefracs[0] > 0.75 && vle.vapor().molefracs[0] < 0.85);
+
+        // gas solubility without binary parameters
+        let (parameters, mixing) = load(&["carbon dioxide", "n-hexane"])?;
+        let eos = Arc::new(Cubic::psrk(parameters, mixing)?);
+        let vle = PhaseEquilibrium::bubble_point(
+            &eos,
+            313.15 * KELVIN,
+            &arr1(&[0.3, 0.7]),
+            None,
+            None,
+            Default::default(),
+        )?;
+        let p = vle
+            .vapor()
+            .pressure(Contributions::Total)
+            .convert_to(PASCAL);
+        assert!(p > 2e6 && p < 4e6);
+        Ok(())
+    }
+}
@@ -0,0 +1,321 @@
+use super::Cubic;
+use feos_core::parameter::{IdentifierOption, ValidationReport};
+use feos_core::{Components, Contributions, FeosResult, PhaseEquilibrium, SolverOptions, State};
+use ndarray::Array1;
+use quantity::{KELVIN, METER, MOL, PASCAL};
+use std::fmt;
+use std::sync::Arc;
+use typenum::P3;
+
+const MIN_REDUCED_TEMPERATURE: f64 = 0.5;
+const ACENTRIC_REDUCED_TEMPERATURE: f64 = 0.7;
+/// Gas constant in J/mol/K.
+const RGAS: f64 = 8.31446261815324;
+/// Reduced temperatures used in [Cubic::screen_parameters].
+pub const SCREENING_REDUCED_TEMPERATURES: [f64; 4] = [0.6, 0.7, 0.8, 0.9];
+
+/// Reduced vapor pressure from the generalized correlation of Lee and Kesler.
+///
+/// See https://doi.org/10.1002/aic.690210313
+fn lee_kesler(reduced_temperature: f64, acentric_factor: f64) -> f64 {
+    let tr = reduced_temperature;
+    let f0 = 5.92714 - 6.09648 / tr - 1.28862 * tr.ln() + 0.169347 * tr.powi(6);
+    let f1 = 15.2518 - 15.6875 / tr - 13.4721 * tr.ln() + 0.43577 * tr.powi(6);
+    (f0 + acentric_factor * f1).exp()
+}
+
+/// Saturated liquid density in mol/m³ from the Rackett equation with the
+/// compressibility factor correlation of Yamada and Gunn.
+///
+/// See https://doi.org/10.1021/je60057a006
+fn rackett(reduced_temperature: f64, tc: f64, pc: f64, acentric_factor: f64) -> f64 {
+    let z_ra = 0.29056 - 0.08775 * acentric_factor;
+    let exponent = 1.0 + (1.0 - reduced_temperature).powf(2.0 / 7.0);
+    pc / (RGAS * tc * z_ra.powf(exponent))
+}
+
+/// Saturation properties of a pure component model compared to its record.
+#[derive(Clone, Debug)]
+pub struct SaturationQuality {
+    /// Acentric factor in the pure record.
+    pub acentric_factor: f64,
+    /// Acentric factor implied by the model.
+    pub model_acentric_factor: f64,
+    /// Reduced temperatures $T/T_c$ with the critical temperature of the record.
+    pub reduced_temperature: Array1<f64>,
+    /// Relative deviations of the vapor pressure from the Lee-Kesler
+    /// correlation in percent.
+    pub relative_deviation: Array1<f64>,
+}
+
+impl SaturationQuality {
+    /// Average absolute relative deviation of the vapor pressure in percent.
+    pub fn aard(&self) -> f64 {
+        self.relative_deviation
+            .mapv(f64::abs)
+            .mean()
+            .unwrap_or(f64::NAN)
+    }
+
+    /// Maximum absolute relative deviation of the vapor pressure in percent.
+    pub fn max(&self) -> f64 {
+        self.relative_deviation
+            .iter()
+            .fold(0.0, |acc, d| f64::max(acc, d.abs()))
+    }
+}
+
+impl Cubic {
+    /// Calculate the acentric factors implied by the equation of state.
+    ///
+    /// The acentric factors are calculated from the vapor pressure at
+    /// $T_r=0.7$, where the critical point of the model is used for
+    /// the reduction.
+    pub fn acentric_factors(eos: &Arc<Self>, options: SolverOptions) -> FeosResult<Array1<f64>> {
+        (0..eos.components())
+            .map(|i| {
+                let pure = Arc::new(eos.subset(&[i]));
+                let cp = State::critical_point(
+                    &pure,
+                    None,
+                    Some(eos.parameters.tc[i] * KELVIN),
+                    options,
+                )?;
+                let t = ACENTRIC_REDUCED_TEMPERATURE * cp.temperature;
+                let vle = PhaseEquilibrium::pure(&pure, t, None, options)?;
+                let pr =
+                    vle.vapor().pressure(Contributions::Total) / cp.pressure(Contributions::Total);
+                Ok(-pr.into_value().log10() - 1.0)
+            })
+            .collect()
+    }
+
+    /// Compare the saturation properties of all pure components with their records.
+    ///
+    /// The vapor pressures are calculated at `npoints` reduced temperatures
+    /// between 0.5 and 1.0 and compared to the correlation of Lee and Kesler
+    /// evaluated with the critical data and acentric factors of the pure
+    /// records. At $T_r=1$, the critical pressure of the model is used.
+    /// Temperatures at which the phase equilibrium calculation fails are
+    /// skipped.
+    ///
+    /// Absolute deviations of the acentric factor larger than
+    /// `max_acentric_factor_deviation` and maximum deviations of the vapor
+    /// pressure larger than `max_vapor_pressure_deviation` (in percent)
+    /// are reported as warnings.
+    pub fn saturation_quality(
+        eos: &Arc<Self>,
+        npoints: usize,
+        max_acentric_factor_deviation: f64,
+        max_vapor_pressure_deviation: f64,
+        options: SolverOptions,
+    ) -> FeosResult<(Vec<SaturationQuality>, ValidationReport)> {
+        let acentric_factors = Self::acentric_factors(eos, options)?;
+        let mut report = ValidationReport::new();
+        let mut quality = Vec::with_capacity(eos.components());
+        for (i, &model_acentric_factor) in acentric_factors.iter().enumerate() {
+            let pure = Arc::new(eos.subset(&[i]));
+            let (tc, pc) = (eos.parameters.tc[i], eos.parameters.pc[i]);
+            let acentric_factor = eos.parameters.acentric_factor[i];
+
+            let cp = State::critical_point(&pure, None, Some(tc * KELVIN), options)?;
+            let mut vle = None;
+            let (reduced_temperature, relative_deviation): (Vec<_>, Vec<_>) =
+                Array1::linspace(MIN_REDUCED_TEMPERATURE, 1.0, npoints)
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(k, tr)| {
+                        let p = if k == npoints - 1 {
+                            cp.pressure(Contributions::Total)
+                        } else {
+                            vle = PhaseEquilibrium::pure(
+                                &pure,
+                                tr * tc * KELVIN,
+                                vle.as_ref(),
+                                options,
+                            )
+                            .ok();
+                            vle.as_ref()?.vapor().pressure(Contributions::Total)
+                        };
+                        let reference = lee_kesler(tr, acentric_factor) * pc;
+                        Some((tr, (p.convert_to(PASCAL) / reference - 1.0) * 100.0))
+                    })
+                    .unzip();
+            let q = SaturationQuality {
+                acentric_factor,
+                model_acentric_factor,
+                reduced_temperature: Array1::from_vec(reduced_temperature),
+                relative_deviation: Array1::from_vec(relative_deviation),
+            };
+
+            if (model_acentric_factor - acentric_factor).abs() > max_acentric_factor_deviation {
+                report.warning(
+                    vec![i],
+                    format!(
+                        "acentric factor of the model {model_acentric_factor} deviates from {acentric_factor}"
+                    ),
+                );
+            }
+            if q.max() > max_vapor_pressure_deviation {
+                report.warning(
+                    vec![i],
+                    format!(
+                        "vapor pressure deviates by up to {:.2}% from the Lee-Kesler correlation",
+                        q.max()
+                    ),
+                );
+            }
+            quality.push(q);
+        }
+        Ok((quality, report))
+    }
+}
+
+/// Screening result of a single component.
+#[derive(Clone, Debug)]
+pub struct ComponentScreening {
+    /// Index of the component in the parameter set.
+    pub component: usize,
+    /// Name (or CAS number) of the component.
+    pub name: String,
+    /// Reduced temperatures $T/T_c$ with the critical temperature of the record.
+    pub reduced_temperature: Array1<f64>,
+    /// Relative deviations of the vapor pressure from the Lee-Kesler
+    /// correlation in percent (`NaN` if the phase equilibrium failed).
+    pub vapor_pressure: Array1<f64>,
+    /// Relative deviations of the saturated liquid density from the Rackett
+    /// equation in percent (`NaN` if the phase equilibrium failed).
+    pub liquid_density: Array1<f64>,
+}
+
+impl ComponentScreening {
+    /// Number of temperatures at which the phase equilibrium calculation failed.
+    pub fn failures(&self) -> usize {
+        self.vapor_pressure.iter().filter(|d| d.is_nan()).count()
+    }
+
+    /// Maximum absolute relative deviation of the vapor pressure and the
+    /// liquid density in percent. Components for which a phase equilibrium
+    /// calculation failed have an infinite score.
+    pub fn score(&self) -> f64 {
+        if self.failures() > 0 {
+            return f64::INFINITY;
+        }
+        self.vapor_pressure
+            .iter()
+            .chain(self.liquid_density.iter())
+            .fold(0.0, |acc, d| f64::max(acc, d.abs()))
+    }
+}
+
+/// Components of a parameter set ranked by their deviations from
+/// correlation references, starting with the most suspicious one.
+#[derive(Clone, Debug)]
+pub struct ScreeningReport {
+    pub components: Vec<ComponentScreening>,
+}
+
+impl ScreeningReport {
+    /// Components with a [score](ComponentScreening::score) above the
+    /// threshold (in percent).
+    pub fn suspicious(&self, threshold: f64) -> impl Iterator<Item = &ComponentScreening> {
+        self.components
+            .iter()
+            .filter(move |c| c.score() > threshold)
+    }
+}
+
+impl fmt::Display for ScreeningReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "rank | component            | max. dev. / % | psat AARD / % | rho_l AARD / % | failures"
+        )?;
+        write!(f, "{:-<91}", "")?;
+        for (rank, c) in self.components.iter().enumerate() {
+            let aard = |d: &Array1<f64>| {
+                let d: Vec<_> = d
+                    .iter()
+                    .filter(|d| d.is_finite())
+                    .map(|d| d.abs())
+                    .collect();
+                d.iter().sum::<f64>() / d.len() as f64
+            };
+            write!(
+                f,
+                "\n{:4} | {:20} | {:13.2} | {:13.2} | {:14.2} | {}",
+                rank + 1,
+                format!("{} ({})", c.name, c.component),
+                c.score(),
+                aard(&c.vapor_pressure),
+                aard(&c.liquid_density),
+                c.failures()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Cubic {
+    /// Screen all components of the parameter set for suspicious parameters.
+    ///
+    /// For every component, the vapor pressure and the saturated liquid
+    /// density are calculated at the given reduced temperatures (by default
+    /// [SCREENING_REDUCED_TEMPERATURES]) and compared to the correlation of
+    /// Lee and Kesler and the Rackett equation, both evaluated with the
+    /// critical data and acentric factor of the pure record. Failed phase
+    /// equilibrium calculations are recorded instead of returned as errors.
+    /// The components are ranked by their [score](ComponentScreening::score).
+    pub fn screen_parameters(
+        eos: &Arc<Self>,
+        reduced_temperatures: Option<&[f64]>,
+        options: SolverOptions,
+    ) -> ScreeningReport {
+        let reduced_temperatures = reduced_temperatures.unwrap_or(&SCREENING_REDUCED_TEMPERATURES);
+        let density = MOL / METER.powi::<P3>();
+        let mut components: Vec<_> = (0..eos.components())
+            .map(|i| {
+                let pure = Arc::new(eos.subset(&[i]));
+                let (tc, pc) = (eos.parameters.tc[i], eos.parameters.pc[i]);
+                let acentric_factor = eos.parameters.acentric_factor[i];
+                let identifier = &eos.parameters.pure_records[i].identifier;
+                let name = identifier
+                    .as_str(IdentifierOption::Name)
+                    .or(identifier.as_str(IdentifierOption::Cas))
+                    .unwrap_or("unknown")
+                    .to_string();
+
+                let mut vle = None;
+                let (vapor_pressure, liquid_density): (Vec<_>, Vec<_>) = reduced_temperatures
+                    .iter()
+                    .map(|&tr| {
+                        vle =
+                            PhaseEquilibrium::pure(&pure, tr * tc * KELVIN, vle.as_ref(), options)
+                                .ok();
+                        let Some(vle) = &vle else {
+                            return (f64::NAN, f64::NAN);
+                        };
+                        let p = vle
+                            .vapor()
+                            .pressure(Contributions::Total)
+                            .convert_to(PASCAL);
+                        let rho = vle.liquid().density.convert_to(density);
+                        (
+                            (p / (lee_kesler(tr, acentric_factor) * pc) - 1.0) * 100.0,
+                            (rho / rackett(tr, tc, pc, acentric_factor) - 1.0) * 100.0,
+                        )
+                    })
+                    .unzip();
+                ComponentScreening {
+                    component: i,
+                    name,
+                    reduced_temperature: Array1::from_vec(reduced_temperatures.to_vec()),
+                    vapor_pressure: Array1::from_vec(vapor_pressure),
+                    liquid_density: Array1::from_vec(liquid_density),
+                }
+            })
+            .collect();
+        components.sort_by(|c1, c2| c2.score().total_cmp(&c1.score()));
+        ScreeningReport { components }
+    }
+}
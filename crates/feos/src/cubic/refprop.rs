@@ -0,0 +1,418 @@
+//! Comparison of model predictions with reference data from REFPROP fluid (.FLD) files.
+//!
+//! Only the header (critical constants, acentric factor, molar mass, triple
+//! point) and the saturation ancillary equations of the fluid files are read.
+//! Supported ancillary equations are
+//! - `PS5`: $\ln\left(\frac{p}{p_r}\right)=\frac{T_r}{T}\sum_iN_i\theta^{t_i}$
+//! - `DL1`: $\frac{\rho}{\rho_r}=1+\sum_iN_i\theta^{t_i}$
+//! - `DV3`: $\ln\left(\frac{\rho}{\rho_r}\right)=\sum_iN_i\theta^{t_i}$
+//!
+//! with $\theta=1-\frac{T}{T_r}$.
+use super::CubicRecord;
+use feos_core::parameter::{Identifier, PureRecord};
+use feos_core::{Contributions, FeosError, FeosResult, PhaseEquilibrium, Residual, SolverOptions};
+use ndarray::Array1;
+use quantity::{KELVIN, METER, MOL, PASCAL};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::Arc;
+use typenum::P3;
+
+/// Type of a saturation ancillary equation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AncillaryType {
+    /// Vapor pressure (`PS5`).
+    VaporPressure,
+    /// Saturated liquid density (`DL1`).
+    LiquidDensity,
+    /// Saturated vapor density (`DV3`).
+    VaporDensity,
+}
+
+/// Saturation ancillary equation read from a REFPROP fluid file.
+#[derive(Clone, Debug)]
+pub struct Ancillary {
+    pub ancillary_type: AncillaryType,
+    /// Reducing temperature in K.
+    pub reducing_temperature: f64,
+    /// Reducing value in Pa or mol/m³.
+    pub reducing_value: f64,
+    /// Coefficients $N_i$.
+    pub coefficients: Vec<f64>,
+    /// Exponents $t_i$.
+    pub exponents: Vec<f64>,
+}
+
+impl Ancillary {
+    /// Evaluate the ancillary equation at the given temperature in K.
+    ///
+    /// Returns the vapor pressure in Pa or the density in mol/m³.
+    pub fn evaluate(&self, temperature: f64) -> f64 {
+        let theta = 1.0 - temperature / self.reducing_temperature;
+        let sum: f64 = self
+            .coefficients
+            .iter()
+            .zip(&self.exponents)
+            .map(|(n, t)| n * theta.powf(*t))
+            .sum();
+        self.reducing_value
+            * match self.ancillary_type {
+                AncillaryType::VaporPressure => {
+                    (self.reducing_temperature / temperature * sum).exp()
+                }
+                AncillaryType::LiquidDensity => 1.0 + sum,
+                AncillaryType::VaporDensity => sum.exp(),
+            }
+    }
+}
+
+/// Pure component data read from a REFPROP fluid file.
+#[derive(Clone, Debug)]
+pub struct FldFluid {
+    pub name: String,
+    pub cas: Option<String>,
+    /// Molar weight in g/mol.
+    pub molarweight: f64,
+    /// Triple point temperature in K.
+    pub triple_point_temperature: Option<f64>,
+    /// Critical temperature in K.
+    pub critical_temperature: f64,
+    /// Critical pressure in Pa.
+    pub critical_pressure: f64,
+    /// Critical density in mol/m³.
+    pub critical_density: f64,
+    pub acentric_factor: f64,
+    pub vapor_pressure: Option<Ancillary>,
+    pub liquid_density: Option<Ancillary>,
+    pub vapor_density: Option<Ancillary>,
+}
+
+/// Split a line of a fluid file into values and comment.
+fn split_comment(line: &str) -> (&str, &str) {
+    line.split_once('!').unwrap_or((line, ""))
+}
+
+fn parse_numbers(line: &str) -> FeosResult<Vec<f64>> {
+    split_comment(line)
+        .0
+        .split_whitespace()
+        .map(|v| {
+            v.parse()
+                .map_err(|_| FeosError::Error(format!("Invalid number '{v}' in fluid file.")))
+        })
+        .collect()
+}
+
+impl FldFluid {
+    /// Read a REFPROP fluid file.
+    pub fn from_file<P: AsRef<Path>>(file: P) -> FeosResult<Self> {
+        Self::from_reader(BufReader::new(File::open(file)?))
+    }
+
+    /// Read a REFPROP fluid file from a reader.
+    pub fn from_reader<R: Read>(mut reader: R) -> FeosResult<Self> {
+        let mut fld = String::new();
+        reader.read_to_string(&mut fld)?;
+        Self::from_fld_str(&fld)
+    }
+
+    /// Read a REFPROP fluid file from a string.
+    pub fn from_fld_str(fld: &str) -> FeosResult<Self> {
+        let lines: Vec<_> = fld.lines().collect();
+        let header = |key: &str| {
+            lines
+                .iter()
+                .map(|l| split_comment(l))
+                .find(|(_, comment)| comment.to_lowercase().contains(key))
+                .map(|(value, _)| value.trim())
+        };
+        let number = |key: &str| -> FeosResult<f64> {
+            let value = header(key)
+                .ok_or_else(|| FeosError::Error(format!("Missing {key} in fluid file.")))?;
+            value
+                .parse()
+                .map_err(|_| FeosError::Error(format!("Invalid {key} '{value}' in fluid file.")))
+        };
+
+        let ancillary = |tag: &str, ancillary_type, unit: f64| -> FeosResult<Option<Ancillary>> {
+            let Some(start) = lines.iter().position(|l| l.trim_start().starts_with(tag)) else {
+                return Ok(None);
+            };
+            let model = lines.get(start + 1).map_or("", |l| l.trim_start());
+            let expected = match ancillary_type {
+                AncillaryType::VaporPressure => "PS5",
+                AncillaryType::LiquidDensity => "DL1",
+                AncillaryType::VaporDensity => "DV3",
+            };
+            if !model.starts_with(expected) {
+                return Err(FeosError::Error(format!(
+                    "Unsupported ancillary equation '{}', expected {expected}.",
+                    model.split_whitespace().next().unwrap_or_default()
+                )));
+            }
+            let reducing = lines[start..]
+                .iter()
+                .position(|l| split_comment(l).1.contains("reducing parameters"))
+                .map(|i| start + i)
+                .ok_or_else(|| {
+                    FeosError::Error(format!("Missing reducing parameters of {tag}."))
+                })?;
+            let r = parse_numbers(lines[reducing])?;
+            let terms = parse_numbers(lines.get(reducing + 1).unwrap_or(&""))?;
+            let (Some(&t_r), Some(&x_r), Some(&n)) = (r.first(), r.get(1), terms.first()) else {
+                return Err(FeosError::Error(format!(
+                    "Invalid ancillary equation {tag}."
+                )));
+            };
+            let mut coefficients = Vec::new();
+            let mut exponents = Vec::new();
+            for l in lines.iter().skip(reducing + 2).take(n as usize) {
+                let values = parse_numbers(l)?;
+                let [c, e] = values[..] else {
+                    return Err(FeosError::Error(format!(
+                        "Invalid term in ancillary equation {tag}."
+                    )));
+                };
+                coefficients.push(c);
+                exponents.push(e);
+            }
+            Ok(Some(Ancillary {
+                ancillary_type,
+                reducing_temperature: t_r,
+                reducing_value: x_r * unit,
+                coefficients,
+                exponents,
+            }))
+        };
+
+        Ok(Self {
+            name: header("short name").unwrap_or_default().to_string(),
+            cas: header("cas number").map(String::from),
+            molarweight: number("molar mass")?,
+            triple_point_temperature: number("triple point temperature").ok(),
+            critical_temperature: number("critical temperature")?,
+            // pressures in kPa and densities in mol/L
+            critical_pressure: number("critical pressure")? * 1e3,
+            critical_density: number("critical density")? * 1e3,
+            acentric_factor: number("acentric factor")?,
+            vapor_pressure: ancillary("#PS", AncillaryType::VaporPressure, 1e3)?,
+            liquid_density: ancillary("#DL", AncillaryType::LiquidDensity, 1e3)?,
+            vapor_density: ancillary("#DV", AncillaryType::VaporDensity, 1e3)?,
+        })
+    }
+
+    /// Pure component record for cubic equations of state.
+    pub fn pure_record(&self) -> PureRecord<CubicRecord> {
+        PureRecord::new(
+            Identifier::new(
+                self.cas.as_deref(),
+                Some(&self.name),
+                None,
+                None,
+                None,
+                None,
+            ),
+            self.molarweight,
+            CubicRecord::new(
+                self.critical_temperature,
+                self.critical_pressure,
+                self.acentric_factor,
+            ),
+        )
+    }
+}
+
+/// Deviation statistics of a single property in percent.
+#[derive(Clone, Debug)]
+pub struct Deviations {
+    pub ancillary_type: AncillaryType,
+    /// Temperatures in K.
+    pub temperature: Array1<f64>,
+    /// Relative deviations in percent.
+    pub relative_deviation: Array1<f64>,
+}
+
+impl Deviations {
+    /// Average absolute relative deviation in percent.
+    pub fn aard(&self) -> f64 {
+        self.relative_deviation
+            .mapv(f64::abs)
+            .mean()
+            .unwrap_or(f64::NAN)
+    }
+
+    /// Maximum absolute relative deviation in percent.
+    pub fn max(&self) -> f64 {
+        self.relative_deviation
+            .iter()
+            .fold(0.0, |acc, d| f64::max(acc, d.abs()))
+    }
+
+    /// Mean relative deviation (bias) in percent.
+    pub fn bias(&self) -> f64 {
+        self.relative_deviation.mean().unwrap_or(f64::NAN)
+    }
+}
+
+impl FldFluid {
+    /// Compare saturation properties of a pure component model with the
+    /// ancillary equations of the fluid file.
+    ///
+    /// `npoints` temperatures are distributed evenly between the triple point
+    /// (or half the critical temperature) and 95% of the critical temperature.
+    /// Temperatures at which the phase equilibrium calculation fails are skipped.
+    pub fn compare<E: Residual>(
+        &self,
+        eos: &Arc<E>,
+        npoints: usize,
+    ) -> FeosResult<Vec<Deviations>> {
+        let tc = self.critical_temperature;
+        let t_min = self
+            .triple_point_temperature
+            .unwrap_or(0.5 * tc)
+            .max(0.5 * tc);
+        let temperatures = Array1::linspace(t_min, 0.95 * tc, npoints);
+        let vles: Vec<_> = temperatures
+            .iter()
+            .filter_map(|&t| {
+                PhaseEquilibrium::pure(eos, t * KELVIN, None, SolverOptions::default())
+                    .ok()
+                    .map(|vle| (t, vle))
+            })
+            .collect();
+        if vles.is_empty() {
+            return Err(FeosError::NotConverged(
+                "phase equilibria for the comparison with the fluid file".into(),
+            ));
+        }
+        let density = MOL / METER.powi::<P3>();
+        let mut deviations = Vec::new();
+        for ancillary in [
+            &self.vapor_pressure,
+            &self.liquid_density,
+            &self.vapor_density,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            let (temperature, relative_deviation) = vles
+                .iter()
+                .map(|(t, vle)| {
+                    let model = match ancillary.ancillary_type {
+                        AncillaryType::VaporPressure => vle
+                            .vapor()
+                            .pressure(Contributions::Total)
+                            .convert_to(PASCAL),
+                        AncillaryType::LiquidDensity => vle.liquid().density.convert_to(density),
+                        AncillaryType::VaporDensity => vle.vapor().density.convert_to(density),
+                    };
+                    let reference = ancillary.evaluate(*t);
+                    (*t, (model / reference - 1.0) * 100.0)
+                })
+                .unzip();
+            deviations.push(Deviations {
+                ancillary_type: ancillary.ancillary_type,
+                temperature: Array1::from_vec(temperature),
+                relative_deviation: Array1::from_vec(relative_deviation),
+            });
+        }
+        Ok(deviations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{Cubic, CubicParameters};
+    use approx::assert_relative_eq;
+    use feos_core::parameter::Parameter;
+
+    const METHANE: &str = "methane            !short name
+74-82-8            !CAS number
+methane            !full name
+CH4                !chemical formula
+R-50               !synonym
+16.0428            !molar mass [g/mol]
+90.6941            !triple point temperature [K]
+111.667            !normal boiling point [K]
+190.564            !critical temperature [K]
+4599.2             !critical pressure [kPa]
+10.139128          !critical density [mol/L]
+0.01142            !acentric factor
+
+#PS         !vapor pressure equation
+PS5  vapor pressure equation
+?
+?```````````````````````````````````````````````````````````````````````````````
+0.                 !
+10000.             !
+0.                 !
+0.                 !
+190.564  4599.2    !reducing parameters
+4 0 0 0 0 0        !number of terms in equation
+-6.036219  1.0
+ 1.409353  1.5
+-0.4945199 2.0
+-1.443048  4.5
+
+#DL         !saturated liquid density equation
+DL1  saturated liquid density equation
+?
+?```````````````````````````````````````````````````````````````````````````````
+0.                 !
+10000.             !
+0.                 !
+0.                 !
+190.564  10.139128 !reducing parameters
+3 0 0 0 0 0        !number of terms in equation
+ 1.9906389   0.354
+-0.78756197  0.5
+ 0.036976723 2.5
+
+#DV         !saturated vapor density equation
+DV3  saturated vapor density equation
+?
+?```````````````````````````````````````````````````````````````````````````````
+0.                 !
+10000.             !
+0.                 !
+0.                 !
+190.564  10.139128 !reducing parameters
+6 0 0 0 0 0        !number of terms in equation
+-1.8802840   0.354
+-2.8526531   0.8333333333
+-3.0006480   1.5
+-5.2511690   2.5
+-13.191859   4.1666666667
+-37.553961   7.8333333333
+";
+
+    #[test]
+    fn read_fld() -> FeosResult<()> {
+        let fluid = FldFluid::from_fld_str(METHANE)?;
+        assert_eq!(fluid.name, "methane");
+        assert_eq!(fluid.cas.as_deref(), Some("74-82-8"));
+        assert_relative_eq!(fluid.critical_pressure, 4599200.0);
+        let psat = fluid.vapor_pressure.as_ref().unwrap();
+        assert_relative_eq!(psat.evaluate(150.0), 1.039943e6, max_relative = 1e-6);
+        let rho_l = fluid.liquid_density.as_ref().unwrap();
+        assert_relative_eq!(rho_l.evaluate(150.0), 18.1347e3, max_relative = 1e-5);
+        Ok(())
+    }
+
+    #[test]
+    fn compare_peng_robinson() -> FeosResult<()> {
+        let fluid = FldFluid::from_fld_str(METHANE)?;
+        let parameters = CubicParameters::new_pure(fluid.pure_record())?;
+        let eos = Arc::new(Cubic::peng_robinson(Arc::new(parameters), None, None)?);
+        let deviations = fluid.compare(&eos, 10)?;
+        assert_eq!(deviations.len(), 3);
+        assert_eq!(deviations[0].ancillary_type, AncillaryType::VaporPressure);
+        assert!(deviations[0].aard() < 3.0);
+        assert!(deviations[2].aard() < 5.0);
+        assert!(deviations[1].max() >= deviations[1].aard());
+        assert!(deviations[1].bias() > 0.0);
+        Ok(())
+    }
+}
@@ -0,0 +1,591 @@
+use super::alpha::{Alpha, AlphaParameters};
+use super::parameters::{CubicBinaryRecord, CubicParameters, CubicRecord};
+use super::{Cubic, CubicOptions, CubicParameter};
+use crate::estimator::{Estimator, RegressionResult};
+use feos_core::parameter::{BinaryRecord, Parameter, PureRecord};
+use feos_core::{Components, FeosError, FeosResult, SolverOptions};
+use ndarray::{Array1, Array2, arr1};
+use std::sync::Arc;
+
+impl Cubic {
+    /// Fit the binary interaction parameter $k_{ij}$ (and optionally $l_{ij}$)
+    /// of a binary mixture to the data stored in `estimator`.
+    ///
+    /// The current binary parameters of the equation of state are used as
+    /// initial values. Returns the fitted binary record together with the
+    /// statistics of the regression.
+    pub fn fit_binary_parameters(
+        &self,
+        estimator: &Estimator<Cubic>,
+        fit_l_ij: bool,
+        options: SolverOptions,
+    ) -> FeosResult<(BinaryRecord<CubicBinaryRecord>, RegressionResult)> {
+        if self.components() != 2 {
+            return Err(FeosError::IncompatibleComponents(self.components(), 2));
+        }
+        let p = &self.parameters;
+        let binary_record = |x: &Array1<f64>| {
+            let l_ij = if fit_l_ij { x[1] } else { p.l_ij[[0, 1]] };
+            CubicBinaryRecord::new(Some(x[0]), Some(l_ij))
+        };
+        let model = |x: &Array1<f64>| {
+            let br = binary_record(x);
+            let binary_records = Array2::from_shape_fn((2, 2), |(i, j)| {
+                if i == j {
+                    CubicBinaryRecord::default()
+                } else {
+                    br.clone()
+                }
+            });
+            let parameters =
+                CubicParameters::from_records(p.pure_records.clone(), Some(binary_records))?;
+            Ok(Arc::new(Cubic::new(
+                Arc::new(parameters),
+                self.options.clone(),
+            )?))
+        };
+
+        let initial = if fit_l_ij {
+            arr1(&[p.k_ij[[0, 1]], p.l_ij[[0, 1]]])
+        } else {
+            arr1(&[p.k_ij[[0, 1]]])
+        };
+        let result = estimator.fit(model, &initial, options)?;
+        let record = BinaryRecord::new(
+            p.pure_records[0].identifier.clone(),
+            p.pure_records[1].identifier.clone(),
+            binary_record(&result.parameters),
+        );
+        Ok((record, result))
+    }
+
+    /// Fit component specific alpha function parameters of a pure substance
+    /// to the data stored in `estimator`, e.g., vapor pressures and
+    /// (optionally) liquid densities.
+    ///
+    /// The type of the alpha function and the initial values are given by
+    /// `initial_alpha`. Returns the pure record including the fitted alpha
+    /// function parameters together with the statistics of the regression.
+    pub fn fit_alpha_parameters(
+        &self,
+        estimator: &Estimator<Cubic>,
+        initial_alpha: AlphaParameters,
+        options: SolverOptions,
+    ) -> FeosResult<(PureRecord<CubicRecord>, RegressionResult)> {
+        if self.components() != 1 {
+            return Err(FeosError::IncompatibleComponents(self.components(), 1));
+        }
+        let pure_record = |x: &Array1<f64>| {
+            let mut record = self.parameters.pure_records[0].clone();
+            record.model_record.alpha =
+                Some(initial_alpha.with_coefficients(x.as_slice().unwrap()));
+            record
+        };
+        let model = |x: &Array1<f64>| {
+            let record = pure_record(x);
+            let alpha = Alpha::from_parameters(&[record.model_record.alpha.unwrap()])?;
+            let options = CubicOptions {
+                alpha,
+                ..self.options.clone()
+            };
+            let parameters = CubicParameters::new_pure(record)?;
+            Ok(Arc::new(Cubic::new(Arc::new(parameters), options)?))
+        };
+
+        let initial = Array1::from_vec(initial_alpha.coefficients());
+        let result = estimator.fit(model, &initial, options)?;
+        Ok((pure_record(&result.parameters), result))
+    }
+
+    /// Fit arbitrary parameters of the equation of state to the data stored
+    /// in `estimator`, e.g., critical properties and acentric factors of
+    /// pseudo-components and binary interaction parameters to the
+    /// measurements of a [PvtReport](crate::estimator::PvtReport).
+    ///
+    /// The current values of the parameters are used as initial values.
+    /// Coefficients of alpha functions cannot be fitted with this method (see
+    /// [fit_alpha_parameters](Cubic::fit_alpha_parameters)). Returns the
+    /// parameters including the fitted values together with the statistics of
+    /// the regression.
+    pub fn fit_parameters(
+        &self,
+        estimator: &Estimator<Cubic>,
+        parameters: &[CubicParameter],
+        options: SolverOptions,
+    ) -> FeosResult<(CubicParameters, RegressionResult)> {
+        let p = &self.parameters;
+        let n = self.components();
+        let initial = parameters
+            .iter()
+            .map(|&parameter| match parameter {
+                CubicParameter::CriticalTemperature(i) if i < n => Ok(p.tc[i]),
+                CubicParameter::CriticalPressure(i) if i < n => Ok(p.pc[i]),
+                CubicParameter::AcentricFactor(i) if i < n => Ok(p.acentric_factor[i]),
+                CubicParameter::Kij(i, j) if i < n && j < n && i != j => Ok(p.k_ij[[i, j]]),
+                CubicParameter::Lij(i, j) if i < n && j < n && i != j => Ok(p.l_ij[[i, j]]),
+                _ => Err(FeosError::IncompatibleParameters(format!(
+                    "{parameter:?} cannot be fitted for the equation of state with {n} components."
+                ))),
+            })
+            .collect::<FeosResult<Array1<f64>>>()?;
+        let cubic_parameters = |x: &Array1<f64>| {
+            let mut pure_records = p.pure_records.clone();
+            let mut k_ij = p.k_ij.clone();
+            let mut l_ij = p.l_ij.clone();
+            for (&parameter, &value) in parameters.iter().zip(x) {
+                match parameter {
+                    CubicParameter::CriticalTemperature(i) => {
+                        pure_records[i].model_record.tc = value
+                    }
+                    CubicParameter::CriticalPressure(i) => pure_records[i].model_record.pc = value,
+                    CubicParameter::AcentricFactor(i) => {
+                        pure_records[i].model_record.acentric_factor = value
+                    }
+                    CubicParameter::Kij(i, j) => {
+                        k_ij[[i, j]] = value;
+                        k_ij[[j, i]] = value;
+                    }
+                    CubicParameter::Lij(i, j) => {
+                        l_ij[[i, j]] = value;
+                        l_ij[[j, i]] = value;
+                    }
+                    CubicParameter::AlphaCoefficient(..) => unreachable!(),
+                }
+            }
+            let binary_records = Array2::from_shape_fn((n, n), |(i, j)| {
+                CubicBinaryRecord::new(Some(k_ij[[i, j]]), Some(l_ij[[i, j]]))
+            });
+            CubicParameters::from_records(pure_records, Some(binary_records))
+        };
+        let model = |x: &Array1<f64>| {
+            let parameters = cubic_parameters(x)?;
+            Ok(Arc::new(Cubic::new(
+                Arc::new(parameters),
+                self.options.clone(),
+            )?))
+        };
+        let result = estimator.fit(model, &initial, options)?;
+        Ok((cubic_parameters(&result.parameters)?, result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::estimator::{
+        BinaryVlePressure, DataSet, Loss, Phase, RegressionCheckpoint, VaporPressure,
+    };
+    use approx::assert_relative_eq;
+    use feos_core::{CancellationToken, PhaseEquilibrium, with_cancellation};
+    use ndarray::Array1;
+    use quantity::{KELVIN, PASCAL, Pressure, Temperature};
+
+    #[test]
+    fn fit_soave_m() -> FeosResult<()> {
+        let omega = 0.153;
+        let parameters =
+            CubicParameters::new_simple(&[369.96], &[4250000.0], &[omega], &[44.0962])?;
+        let eos = Arc::new(Cubic::peng_robinson(Arc::new(parameters), None, None)?);
+
+        // generate synthetic vapor pressure data
+        let t = Array1::linspace(200.0, 350.0, 6);
+        let p = t
+            .iter()
+            .map(|&t| {
+                let vle = PhaseEquilibrium::pure(&eos, t * KELVIN, None, Default::default())?;
+                Ok(vle.vapor().pressure(feos_core::Contributions::Total))
+            })
+            .collect::<FeosResult<Vec<_>>>()?;
+        let data: Arc<dyn DataSet<Cubic>> = Arc::new(VaporPressure::new(
+            Pressure::from_vec(p),
+            t * KELVIN,
+            false,
+            None,
+            None,
+        ));
+        let estimator = Estimator::new(vec![data], vec![1.0], vec![Loss::Linear]);
+
+        let (record, result) = eos.fit_alpha_parameters(
+            &estimator,
+            AlphaParameters::Soave { m: 0.5 },
+            Default::default(),
+        )?;
+        let m = 0.37464 + omega * (1.54226 - omega * 0.26992);
+        assert_eq!(
+            record.model_record.alpha.map(|a| a.coefficients().len()),
+            Some(1)
+        );
+        assert_relative_eq!(result.parameters[0], m, max_relative = 1e-5);
+
+        // the fitted parameters are used if no alpha function is specified
+        let parameters = CubicParameters::new_pure(record)?;
+        let fitted = Arc::new(Cubic::peng_robinson(Arc::new(parameters), None, None)?);
+        assert!(estimator.mean_absolute_relative_difference(&fitted)?[0] < 1e-6);
+        Ok(())
+    }
+
+    #[test]
+    fn fit_k_ij() -> FeosResult<()> {
+        let parameters = CubicParameters::new_simple(
+            &[369.96, 425.2],
+            &[4250000.0, 3800000.0],
+            &[0.153, 0.199],
+            &[44.0962, 58.123],
+        )?;
+        let k_ij = 0.05;
+        let binary_records = Array2::from_shape_fn((2, 2), |(i, j)| {
+            CubicBinaryRecord::from(if i == j { 0.0 } else { k_ij })
+        });
+        let mixture =
+            CubicParameters::from_records(parameters.pure_records.clone(), Some(binary_records))?;
+        let eos = Arc::new(Cubic::peng_robinson(Arc::new(mixture), None, None)?);
+
+        // generate synthetic bubble point data
+        let t = 300.0 * KELVIN;
+        let x = Array1::linspace(0.1, 0.9, 5);
+        let p = x
+            .iter()
+            .map(|&x1| {
+                let vle = PhaseEquilibrium::bubble_point(
+                    &eos,
+                    t,
+                    &arr1(&[x1, 1.0 - x1]),
+                    None,
+                    None,
+                    Default::default(),
+                )?;
+                Ok(vle.vapor().pressure(feos_core::Contributions::Total))
+            })
+            .collect::<FeosResult<Vec<_>>>()?;
+        let data: Arc<dyn DataSet<Cubic>> = Arc::new(BinaryVlePressure::new(
+            Temperature::from_shape_fn(5, |_| t),
+            Pressure::from_vec(p),
+            x,
+            Phase::Liquid,
+        ));
+        let estimator = Estimator::new(vec![data], vec![1.0], vec![Loss::Linear]);
+
+        let initial = Cubic::peng_robinson(Arc::new(parameters), None, None)?;
+        let (record, result) =
+            initial.fit_binary_parameters(&estimator, false, Default::default())?;
+        assert_relative_eq!(record.model_record.k_ij, k_ij, max_relative = 1e-5);
+        assert!(result.mean_absolute_relative_difference[0] < 1e-6);
+
+        // a cancelled regression is aborted
+        let token = CancellationToken::new();
+        token.cancel();
+        let cancelled = with_cancellation(token, || {
+            initial.fit_binary_parameters(&estimator, false, Default::default())
+        });
+        assert!(matches!(cancelled, Err(FeosError::Cancelled)));
+        Ok(())
+    }
+
+    #[test]
+    fn fit_pvt_report() -> FeosResult<()> {
+        use crate::estimator::{PvtExperiment, PvtMeasurement, PvtQuantity, PvtReport};
+
+        // methane, propane and a heavy pseudo-component
+        let pure_records = CubicParameters::new_simple(
+            &[190.56, 369.96, 617.7],
+            &[4599000.0, 4250000.0, 2110000.0],
+            &[0.011, 0.153, 0.49],
+            &[16.043, 44.0962, 142.285],
+        )?
+        .pure_records;
+        let mixture = |tc: f64, k_ij: f64| -> FeosResult<Cubic> {
+            let mut pure_records = pure_records.clone();
+            pure_records[2].model_record.tc = tc;
+            let binary_records = Array2::from_shape_fn((3, 3), |(i, j)| {
+                CubicBinaryRecord::from(if i + j == 2 && i != j { k_ij } else { 0.0 })
+            });
+            let parameters = CubicParameters::from_records(pure_records, Some(binary_records))?;
+            Cubic::peng_robinson(Arc::new(parameters), None, None)
+        };
+
+        // synthetic report of a gas condensate
+        let measurement = |experiment, quantity, pressure: &[f64]| PvtMeasurement {
+            experiment,
+            quantity,
+            pressure: pressure.to_vec(),
+            gas_fraction: vec![],
+            value: vec![1.0; pressure.len().max(1)],
+        };
+        let mut report = PvtReport {
+            fluid: Some("gas condensate".into()),
+            temperature: 350.0,
+            molefracs: vec![0.9, 0.07, 0.03],
+            injection_gas: None,
+            measurements: vec![
+                measurement(
+                    PvtExperiment::ConstantCompositionExpansion,
+                    PvtQuantity::SaturationPressure,
+                    &[],
+                ),
+                measurement(
+                    PvtExperiment::ConstantVolumeDepletion,
+                    PvtQuantity::LiquidVolume,
+                    &[2.0e7, 1.6e7, 1.2e7, 0.8e7],
+                ),
+            ],
+            source: None,
+        };
+        let eos = Arc::new(mixture(617.7, -0.02)?);
+        let values = report
+            .to_datasets::<Cubic>(None)?
+            .iter()
+            .map(|data| data.predict(&eos))
+            .collect::<FeosResult<Vec<_>>>()?;
+        for (m, value) in report.measurements.iter_mut().zip(values) {
+            m.value = value.to_vec();
+        }
+        let datasets = report.to_datasets(None)?;
+        let estimator = Estimator::new(datasets, vec![1.0, 1.0], vec![Loss::Linear; 2]);
+
+        let initial = mixture(600.0, 0.0)?;
+        let fit = [
+            CubicParameter::CriticalTemperature(2),
+            CubicParameter::Kij(0, 2),
+        ];
+        let (parameters, result) = initial.fit_parameters(&estimator, &fit, Default::default())?;
+        assert_relative_eq!(parameters.tc[2], 617.7, max_relative = 1e-4);
+        assert_relative_eq!(parameters.k_ij[[0, 2]], -0.02, max_relative = 1e-3);
+        assert_eq!(parameters.k_ij[[2, 0]], parameters.k_ij[[0, 2]]);
+        assert_eq!(parameters.k_ij[[0, 1]], 0.0);
+        assert!(
+            result
+                .mean_absolute_relative_difference
+                .iter()
+                .all(|&d| d < 1e-6)
+        );
+
+        // alpha function coefficients are not supported
+        let alpha = [CubicParameter::AlphaCoefficient(0, 0)];
+        assert!(
+            initial
+                .fit_parameters(&estimator, &alpha, Default::default())
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn resume_from_checkpoint() -> FeosResult<()> {
+        let omega = 0.153;
+        let parameters =
+            CubicParameters::new_simple(&[369.96], &[4250000.0], &[omega], &[44.0962])?;
+        let eos = Arc::new(Cubic::peng_robinson(Arc::new(parameters), None, None)?);
+        let t = Array1::linspace(200.0, 350.0, 6);
+        let p = t
+            .iter()
+            .map(|&t| {
+                let vle = PhaseEquilibrium::pure(&eos, t * KELVIN, None, Default::default())?;
+                Ok(vle.vapor().pressure(feos_core::Contributions::Total))
+            })
+            .collect::<FeosResult<Vec<_>>>()?;
+        let data: Arc<dyn DataSet<Cubic>> = Arc::new(VaporPressure::new(
+            Pressure::from_vec(p),
+            t * KELVIN,
+            false,
+            None,
+            None,
+        ));
+        let estimator = Estimator::new(vec![data], vec![1.0], vec![Loss::Linear]);
+        let model = |x: &Array1<f64>| {
+            let alpha = Alpha::from_parameters(&[AlphaParameters::Soave { m: x[0] }])?;
+            let parameters =
+                CubicParameters::new_simple(&[369.96], &[4250000.0], &[omega], &[44.0962])?;
+            let options = CubicOptions {
+                alpha,
+                ..eos.options.clone()
+            };
+            Ok(Arc::new(Cubic::new(Arc::new(parameters), options)?))
+        };
+        let initial = arr1(&[0.5]);
+        let reference = estimator.fit(model, &initial, Default::default())?;
+
+        // interrupt the regression after the second iteration
+        let file = std::env::temp_dir().join("feos_regression_checkpoint.json");
+        let interrupted =
+            estimator.fit_with_checkpoints(model, &initial, Default::default(), |c| {
+                c.to_json(&file)?;
+                if c.iterations == 2 {
+                    Err(FeosError::NotConverged("interrupted".into()))
+                } else {
+                    Ok(())
+                }
+            });
+        assert!(interrupted.is_err());
+
+        let checkpoint = RegressionCheckpoint::from_json(&file)?;
+        std::fs::remove_file(&file)?;
+        assert_eq!(checkpoint.iterations, 2);
+        assert!(checkpoint.sum_of_squares.is_finite());
+        let resumed =
+            estimator.fit_from_checkpoint(model, &checkpoint, Default::default(), |_| Ok(()))?;
+        assert_eq!(resumed.iterations, reference.iterations);
+        assert_eq!(resumed.parameters, reference.parameters);
+        Ok(())
+    }
+
+    #[test]
+    fn k_ij_uncertainty() -> FeosResult<()> {
+        let pure_records = CubicParameters::new_simple(
+            &[369.96, 425.2],
+            &[4250000.0, 3800000.0],
+            &[0.153, 0.199],
+            &[44.0962, 58.123],
+        )?
+        .pure_records;
+        let mixture = |k_ij: f64| -> FeosResult<Arc<Cubic>> {
+            let binary_records = Array2::from_shape_fn((2, 2), |(i, j)| {
+                CubicBinaryRecord::from(if i == j { 0.0 } else { k_ij })
+            });
+            let parameters =
+                CubicParameters::from_records(pure_records.clone(), Some(binary_records))?;
+            Ok(Arc::new(Cubic::peng_robinson(
+                Arc::new(parameters),
+                None,
+                None,
+            )?))
+        };
+        let bubble_pressure = |eos: &Arc<Cubic>, x: &Array1<f64>| -> FeosResult<Array1<f64>> {
+            x.iter()
+                .map(|&x1| {
+                    let vle = PhaseEquilibrium::bubble_point(
+                        eos,
+                        300.0 * KELVIN,
+                        &arr1(&[x1, 1.0 - x1]),
+                        None,
+                        None,
+                        Default::default(),
+                    )?;
+                    Ok(
+                        (vle.vapor().pressure(feos_core::Contributions::Total) / PASCAL)
+                            .into_value(),
+                    )
+                })
+                .collect()
+        };
+
+        // synthetic bubble point data with 1 % scatter
+        let x = Array1::linspace(0.1, 0.9, 5);
+        let scatter = arr1(&[1.01, 0.99, 1.01, 0.99, 1.01]);
+        let p = bubble_pressure(&mixture(0.05)?, &x)? * scatter;
+        let data: Arc<dyn DataSet<Cubic>> = Arc::new(BinaryVlePressure::new(
+            Temperature::from_shape_fn(5, |_| 300.0 * KELVIN),
+            p * PASCAL,
+            x.clone(),
+            Phase::Liquid,
+        ));
+        let estimator = Estimator::new(vec![data], vec![1.0], vec![Loss::Linear]);
+        let (_, result) =
+            mixture(0.0)?.fit_binary_parameters(&estimator, false, Default::default())?;
+        let sigma = result.standard_errors()[0];
+        assert!(sigma > 0.0 && sigma < 0.01);
+        assert!((result.parameters[0] - 0.05).abs() < 3.0 * sigma);
+
+        // propagate the uncertainty to the bubble pressure
+        let x = arr1(&[0.5]);
+        let (p, std) =
+            result.propagate_uncertainty(|k_ij| bubble_pressure(&mixture(k_ij[0])?, &x))?;
+        let p_plus = bubble_pressure(&mixture(result.parameters[0] + sigma)?, &x)?;
+        assert_relative_eq!(std[0], (p_plus[0] - p[0]).abs(), max_relative = 1e-2);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "mcmc")]
+    fn k_ij_posterior() -> FeosResult<()> {
+        use crate::estimator::SamplerOptions;
+
+        let pure_records = CubicParameters::new_simple(
+            &[369.96, 425.2],
+            &[4250000.0, 3800000.0],
+            &[0.153, 0.199],
+            &[44.0962, 58.123],
+        )?
+        .pure_records;
+        let mixture = |k_ij: f64| -> FeosResult<Arc<Cubic>> {
+            let binary_records = Array2::from_shape_fn((2, 2), |(i, j)| {
+                CubicBinaryRecord::from(if i == j { 0.0 } else { k_ij })
+            });
+            let parameters =
+                CubicParameters::from_records(pure_records.clone(), Some(binary_records))?;
+            Ok(Arc::new(Cubic::peng_robinson(
+                Arc::new(parameters),
+                None,
+                None,
+            )?))
+        };
+
+        // synthetic bubble point data with 1 % scatter
+        let t = 300.0 * KELVIN;
+        let x = Array1::linspace(0.1, 0.9, 5);
+        let scatter = arr1(&[1.01, 0.99, 1.01, 0.99, 1.01]);
+        let eos = mixture(0.05)?;
+        let p = x
+            .iter()
+            .zip(&scatter)
+            .map(|(&x1, s)| {
+                let vle = PhaseEquilibrium::bubble_point(
+                    &eos,
+                    t,
+                    &arr1(&[x1, 1.0 - x1]),
+                    None,
+                    None,
+                    Default::default(),
+                )?;
+                Ok(vle.vapor().pressure(feos_core::Contributions::Total) * *s)
+            })
+            .collect::<FeosResult<Vec<_>>>()?;
+        let data: Arc<dyn DataSet<Cubic>> = Arc::new(BinaryVlePressure::new(
+            Temperature::from_shape_fn(5, |_| t),
+            Pressure::from_vec(p),
+            x,
+            Phase::Liquid,
+        ));
+        let estimator = Estimator::new(vec![data], vec![1.0], vec![Loss::Linear]);
+        let (_, result) =
+            mixture(0.0)?.fit_binary_parameters(&estimator, false, Default::default())?;
+
+        let options = SamplerOptions {
+            walkers: 8,
+            steps: 300,
+            burn_in: 100,
+            ..Default::default()
+        };
+        let posterior = estimator.sample(|k| mixture(k[0]), &result, &[(-0.2, 0.2)], options)?;
+        assert_eq!(posterior.samples.dim(), (1600, 1));
+        assert!(posterior.acceptance_fraction > 0.2);
+        let sigma = result.standard_errors()[0];
+        assert!((posterior.mean()[0] - result.parameters[0]).abs() < sigma);
+        assert_relative_eq!(posterior.standard_deviation()[0], sigma, max_relative = 0.3);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn parallel_cost() -> FeosResult<()> {
+        let parameters = CubicParameters::new_simple(
+            &[369.96, 425.2],
+            &[4250000.0, 3800000.0],
+            &[0.153, 0.199],
+            &[44.0962, 58.123],
+        )?;
+        let eos = Arc::new(Cubic::peng_robinson(Arc::new(parameters), None, None)?);
+        let x = Array1::linspace(0.05, 0.95, 19);
+        let data: Arc<dyn DataSet<Cubic>> = Arc::new(BinaryVlePressure::new(
+            Temperature::from_shape_fn(19, |_| 300.0 * KELVIN),
+            Pressure::from_shape_fn(19, |_| 1e6 * PASCAL),
+            x,
+            Phase::Liquid,
+        ));
+        let parallel = Estimator::new(vec![data.clone()], vec![1.0], vec![Loss::Linear])
+            .with_num_threads(4)?;
+        let serial =
+            Estimator::new(vec![data], vec![1.0], vec![Loss::Linear]).with_num_threads(1)?;
+        assert_eq!(parallel.cost(&eos)?, serial.cost(&eos)?);
+        Ok(())
+    }
+}
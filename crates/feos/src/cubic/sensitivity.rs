@@ -0,0 +1,501 @@
+use super::alpha::{Alpha, AlphaFunction};
+use super::mixing_rules::{MixingRule, MixtureParameters};
+use super::{Cubic, KB_A3};
+use feos_core::{
+    Components, Contributions, Derivative, FeosError, FeosResult, FlashDerivatives,
+    PhaseEquilibrium, ReferenceSystem, State, StateHD,
+};
+use ndarray::{Array1, Array2, ScalarOperand};
+use num_dual::{DualNum, HyperDual64};
+use quantity::{_Dimensionless, Density, MolarEnergy, Pressure, RGAS};
+
+/// Parameter of a cubic equation of state with respect to which
+/// sensitivities of properties are calculated.
+///
+/// Derivatives are given per unit of the parameter in SI units, i.e.,
+/// per Kelvin for critical temperatures and per Pascal for critical pressures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CubicParameter {
+    /// critical temperature of component `i`
+    CriticalTemperature(usize),
+    /// critical pressure of component `i`
+    CriticalPressure(usize),
+    /// acentric factor of component `i`
+    AcentricFactor(usize),
+    /// binary interaction parameter $k_{ij}=k_{ji}$ of the attractive parameter
+    Kij(usize, usize),
+    /// binary interaction parameter $l_{ij}=l_{ji}$ of the co-volume
+    Lij(usize, usize),
+    /// coefficient `k` of the component specific alpha function of component `i`
+    AlphaCoefficient(usize, usize),
+}
+
+/// # Parameter sensitivities
+impl Cubic {
+    /// Derivatives of the density with respect to model parameters at
+    /// constant temperature, pressure and composition.
+    ///
+    /// The sensitivities follow from $\left(\frac{\partial\rho}{\partial\theta}\right)_{T,p,\mathbf{n}}=-\frac{\left(\frac{\partial p}{\partial\theta}\right)_{T,V,\mathbf{n}}}{\left(\frac{\partial p}{\partial\rho}\right)_{T,\mathbf{n}}}$,
+    /// where the derivative of the pressure with respect to the parameter $\theta$
+    /// is evaluated with dual numbers.
+    pub fn density_sensitivities(
+        state: &State<Self>,
+        parameters: &[CubicParameter],
+    ) -> FeosResult<Density<Array1<f64>>> {
+        let dp_drho = state.dp_drho(Contributions::Total).to_reduced();
+        parameters
+            .iter()
+            .map(|&parameter| {
+                let dp = state.eos.pressure_derivative(state, parameter)?;
+                Ok(-dp / dp_drho)
+            })
+            .collect::<FeosResult<_>>()
+            .map(Density::from_reduced)
+    }
+
+    /// Derivatives of the vapor pressure of a pure substance with respect
+    /// to model parameters at constant temperature.
+    pub fn vapor_pressure_sensitivities(
+        vle: &PhaseEquilibrium<Self, 2>,
+        parameters: &[CubicParameter],
+    ) -> FeosResult<Pressure<Array1<f64>>> {
+        let n = vle.liquid().eos.components();
+        if n != 1 {
+            return Err(FeosError::IncompatibleComponents(1, n));
+        }
+        Self::pressure_sensitivities(vle.liquid(), vle.vapor(), parameters)
+    }
+
+    /// Derivatives of the bubble point pressure with respect to model
+    /// parameters at constant temperature and liquid composition.
+    ///
+    /// The sensitivities follow from the Gibbs-Duhem equation of the vapor phase
+    /// $\left(\frac{\partial p}{\partial\theta}\right)_{T,\mathbf{x}}=\frac{\sum_iy_i\left(\frac{\partial\mu_i^V}{\partial\theta}-\frac{\partial\mu_i^L}{\partial\theta}\right)_{T,p}}{\sum_iy_i\left(v_i^L-v_i^V\right)}$
+    /// with the partial molar volumes $v_i$.
+    pub fn bubble_pressure_sensitivities(
+        vle: &PhaseEquilibrium<Self, 2>,
+        parameters: &[CubicParameter],
+    ) -> FeosResult<Pressure<Array1<f64>>> {
+        Self::pressure_sensitivities(vle.liquid(), vle.vapor(), parameters)
+    }
+
+    /// Derivatives of the dew point pressure with respect to model
+    /// parameters at constant temperature and vapor composition.
+    ///
+    /// Analogous to [Cubic::bubble_pressure_sensitivities] with the roles
+    /// of the liquid and the vapor phase interchanged.
+    pub fn dew_pressure_sensitivities(
+        vle: &PhaseEquilibrium<Self, 2>,
+        parameters: &[CubicParameter],
+    ) -> FeosResult<Pressure<Array1<f64>>> {
+        Self::pressure_sensitivities(vle.vapor(), vle.liquid(), parameters)
+    }
+
+    /// Derivatives of the results of a Tp-flash with respect to model
+    /// parameters at constant temperature, pressure and feed.
+    ///
+    /// The derivatives of the fugacity coefficients with respect to the
+    /// parameters are evaluated with dual numbers and propagated to the vapor
+    /// fraction and the phase compositions with [PhaseEquilibrium::flash_derivatives].
+    pub fn flash_sensitivities(
+        vle: &PhaseEquilibrium<Self, 2>,
+        parameters: &[CubicParameter],
+    ) -> FeosResult<Vec<FlashDerivatives<_Dimensionless>>> {
+        let (liquid, vapor) = (vle.liquid(), vle.vapor());
+        let eos = &liquid.eos;
+        let v_liquid = liquid.partial_molar_volume().to_reduced();
+        let v_vapor = vapor.partial_molar_volume().to_reduced();
+        let dln_phi = |state: &State<Self>, v: &Array1<f64>, parameter| {
+            let dmu = eos.chemical_potential_derivative(state, v, parameter)?;
+            Ok::<_, FeosError>(
+                (MolarEnergy::from_reduced(dmu) / (RGAS * state.temperature)).into_value(),
+            )
+        };
+        parameters
+            .iter()
+            .map(|&parameter| {
+                vle.flash_derivatives(
+                    &dln_phi(liquid, &v_liquid, parameter)?,
+                    &dln_phi(vapor, &v_vapor, parameter)?,
+                )
+            })
+            .collect()
+    }
+
+    /// Pressure sensitivities of a phase equilibrium between a phase with
+    /// fixed composition and an incipient phase.
+    fn pressure_sensitivities(
+        reference: &State<Self>,
+        incipient: &State<Self>,
+        parameters: &[CubicParameter],
+    ) -> FeosResult<Pressure<Array1<f64>>> {
+        let eos = &reference.eos;
+        let w = &incipient.molefracs;
+        let v_ref = reference.partial_molar_volume().to_reduced();
+        let v_inc = incipient.partial_molar_volume().to_reduced();
+        let denominator = w.dot(&(&v_ref - &v_inc));
+        parameters
+            .iter()
+            .map(|&parameter| {
+                let mu_ref = eos.chemical_potential_derivative(reference, &v_ref, parameter)?;
+                let mu_inc = eos.chemical_potential_derivative(incipient, &v_inc, parameter)?;
+                Ok(w.dot(&(mu_inc - mu_ref)) / denominator)
+            })
+            .collect::<FeosResult<_>>()
+            .map(Pressure::from_reduced)
+    }
+
+    /// Reduced derivative of the pressure with respect to a parameter at
+    /// constant temperature, volume and moles.
+    fn pressure_derivative(
+        &self,
+        state: &State<Self>,
+        parameter: CubicParameter,
+    ) -> FeosResult<f64> {
+        Ok(-self.mixed_derivative(state, parameter, Derivative::DV)?)
+    }
+
+    /// Reduced derivatives of the chemical potentials with respect to a parameter
+    /// at constant temperature, pressure and moles.
+    fn chemical_potential_derivative(
+        &self,
+        state: &State<Self>,
+        partial_molar_volume: &Array1<f64>,
+        parameter: CubicParameter,
+    ) -> FeosResult<Array1<f64>> {
+        let dp = self.pressure_derivative(state, parameter)?;
+        (0..self.components())
+            .map(|i| {
+                let dmu = self.mixed_derivative(state, parameter, Derivative::DN(i))?;
+                Ok(dmu - partial_molar_volume[i] * dp)
+            })
+            .collect()
+    }
+
+    /// Mixed second derivative of the reduced residual Helmholtz energy
+    /// with respect to a parameter and a state variable.
+    fn mixed_derivative(
+        &self,
+        state: &State<Self>,
+        parameter: CubicParameter,
+        derivative: Derivative,
+    ) -> FeosResult<f64> {
+        let theta = HyperDual64::from(self.parameter_value(parameter)?).derivative1();
+        let s = state.derive0();
+        let mut t = HyperDual64::from(s.temperature);
+        let mut v = HyperDual64::from(s.volume);
+        let mut n = s.moles.mapv(HyperDual64::from);
+        match derivative {
+            Derivative::DT => t = t.derivative2(),
+            Derivative::DV => v = v.derivative2(),
+            Derivative::DN(i) => n[i] = n[i].derivative2(),
+        }
+        let state = StateHD::new(t, v, n);
+        Ok((self.parameter_helmholtz_energy(&state, parameter, theta) * t).eps1eps2)
+    }
+
+    /// Check the parameter and return its current value.
+    fn parameter_value(&self, parameter: CubicParameter) -> FeosResult<f64> {
+        if !matches!(&self.options.mixing, MixingRule::Quadratic(q) if q.combining_rules.is_van_der_waals())
+        {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "parameter sensitivities are only available for quadratic mixing rules with the default combining rules.",
+            )));
+        }
+        let p = &self.parameters;
+        let n = self.components();
+        let invalid = || {
+            FeosError::IncompatibleParameters(format!(
+                "{parameter:?} is not a parameter of the equation of state with {n} components."
+            ))
+        };
+        let value = match parameter {
+            CubicParameter::CriticalTemperature(i) if i < n => p.tc[i],
+            CubicParameter::CriticalPressure(i) if i < n => p.pc[i],
+            CubicParameter::AcentricFactor(i) if i < n => p.acentric_factor[i],
+            CubicParameter::Kij(i, j) if i < n && j < n && i != j => 1.0 - p.a_ij_factor[[i, j]],
+            CubicParameter::Lij(i, j) if i < n && j < n && i != j => 1.0 - p.b_ij_factor[[i, j]],
+            CubicParameter::AlphaCoefficient(i, k)
+                if i < n
+                    && matches!(self.options.alpha, Alpha::MathiasCopeman(_) | Alpha::Twu(_)) =>
+            {
+                // the alpha function has to be built from the component specific parameters
+                p.pure_records[i]
+                    .model_record
+                    .alpha
+                    .and_then(|alpha| alpha.coefficients().get(k).copied())
+                    .ok_or_else(invalid)?
+            }
+            _ => return Err(invalid()),
+        };
+        Ok(value)
+    }
+
+    /// Residual Helmholtz energy of the cubic contribution with quadratic
+    /// mixing rules in which a single parameter is given as (dual) number.
+    fn parameter_helmholtz_energy<D: DualNum<f64> + Copy + ScalarOperand>(
+        &self,
+        state: &StateHD<D>,
+        parameter: CubicParameter,
+        value: D,
+    ) -> D {
+        let p = &self.parameters;
+        let mut tc = p.tc.mapv(D::from);
+        let mut pc = p.pc.mapv(D::from);
+        let mut acentric_factor = p.acentric_factor.mapv(D::from);
+        let mut a_ij: Array2<D> = p.a_ij_factor.mapv(D::from);
+        let mut b_ij: Array2<D> = p.b_ij_factor.mapv(D::from);
+        match parameter {
+            CubicParameter::CriticalTemperature(i) => tc[i] = value,
+            CubicParameter::CriticalPressure(i) => pc[i] = value,
+            CubicParameter::AcentricFactor(i) => acentric_factor[i] = value,
+            CubicParameter::Kij(i, j) => {
+                a_ij[[i, j]] = -value + 1.0;
+                a_ij[[j, i]] = -value + 1.0;
+            }
+            CubicParameter::Lij(i, j) => {
+                b_ij[[i, j]] = -value + 1.0;
+                b_ij[[j, i]] = -value + 1.0;
+            }
+            CubicParameter::AlphaCoefficient(..) => (),
+        }
+
+        let tr = tc.mapv(|tc| state.temperature / tc);
+        let mut alpha = self.options.alpha.alpha(&acentric_factor, &tr);
+        if let CubicParameter::AlphaCoefficient(i, k) = parameter
+            && let Some(record) = p.pure_records[i].model_record.alpha
+        {
+            let mut coefficients: Vec<D> = record.coefficients().into_iter().map(D::from).collect();
+            coefficients[k] = value;
+            alpha[i] = record.alpha(&coefficients, tr[i]);
+        }
+
+        let cp = &self.critical_parameters;
+        let ac = (&tc * &tc / &pc).mapv(|a| a * cp.omega_a * KB_A3);
+        let bc = (&tc / &pc).mapv(|b| b * cp.omega_b * KB_A3);
+        let x = &state.molefracs;
+        let x_sqrt_a = (alpha * ac).mapv(|a| a.sqrt()) * x;
+        let x_bc = x * &bc;
+        let (mut a, mut b) = (D::zero(), D::zero());
+        for i in 0..x.len() {
+            for j in 0..x.len() {
+                a += x_sqrt_a[i] * x_sqrt_a[j] * a_ij[[i, j]];
+                b += x_bc[i] * x[j] * b_ij[[i, j]];
+            }
+        }
+        let c = x
+            .iter()
+            .zip(&p.volume_shift)
+            .fold(D::zero(), |c, (&x, &ci)| c + x * ci);
+        self.mixture_helmholtz_energy(state, MixtureParameters { a, b, c })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cubic::{AlphaParameters, CubicBinaryRecord, CubicParameters, CubicRecord};
+    use approx::assert_relative_eq;
+    use feos_core::parameter::{Identifier, Parameter, PureRecord};
+    use feos_core::{DensityInitialization, SolverOptions};
+    use ndarray::arr1;
+    use quantity::{BAR, KELVIN, MOL};
+    use std::sync::Arc;
+
+    fn records() -> Vec<PureRecord<CubicRecord>> {
+        [
+            ("methane", 190.564, 4599200.0, 0.01142, 16.0428),
+            ("propane", 369.89, 4251200.0, 0.1521, 44.0956),
+        ]
+        .into_iter()
+        .map(|(name, tc, pc, w, mw)| {
+            let id = Identifier::new(None, Some(name), None, None, None, None);
+            PureRecord::new(id, mw, CubicRecord::new(tc, pc, w))
+        })
+        .collect()
+    }
+
+    /// Peng-Robinson equation of state with a single parameter shifted by `h`.
+    fn eos(
+        records: &[PureRecord<CubicRecord>],
+        parameter: Option<CubicParameter>,
+        h: f64,
+    ) -> FeosResult<Arc<Cubic>> {
+        let mut records = records.to_vec();
+        let n = records.len();
+        let mut binary = Array2::from_elem((n, n), CubicBinaryRecord::default());
+        if n > 1 {
+            binary[[0, 1]].k_ij = 0.01;
+            binary[[0, 1]].l_ij = -0.02;
+        }
+        match parameter {
+            Some(CubicParameter::CriticalTemperature(i)) => records[i].model_record.tc += h,
+            Some(CubicParameter::CriticalPressure(i)) => records[i].model_record.pc += h,
+            Some(CubicParameter::AcentricFactor(i)) => records[i].model_record.acentric_factor += h,
+            Some(CubicParameter::Kij(i, j)) => binary[[i.min(j), i.max(j)]].k_ij += h,
+            Some(CubicParameter::Lij(i, j)) => binary[[i.min(j), i.max(j)]].l_ij += h,
+            Some(CubicParameter::AlphaCoefficient(i, k)) => {
+                let alpha = records[i].model_record.alpha.unwrap();
+                let mut c = alpha.coefficients();
+                c[k] += h;
+                records[i].model_record.alpha = Some(alpha.with_coefficients(&c));
+            }
+            None => (),
+        }
+        let parameters = CubicParameters::from_records(records, Some(binary))?;
+        Ok(Arc::new(Cubic::peng_robinson(
+            Arc::new(parameters),
+            None,
+            None,
+        )?))
+    }
+
+    /// Central finite difference of a property with respect to a parameter.
+    fn finite_difference(
+        records: &[PureRecord<CubicRecord>],
+        parameter: CubicParameter,
+        h: f64,
+        property: impl Fn(&Arc<Cubic>) -> FeosResult<f64>,
+    ) -> FeosResult<f64> {
+        let f_p = property(&eos(records, Some(parameter), h)?)?;
+        let f_m = property(&eos(records, Some(parameter), -h)?)?;
+        Ok((f_p - f_m) / (2.0 * h))
+    }
+
+    #[test]
+    fn density_sensitivities() -> FeosResult<()> {
+        let records = records();
+        let (t, p) = (250.0 * KELVIN, 50.0 * BAR);
+        let moles = arr1(&[0.3, 0.7]) * MOL;
+        let density = |eos: &Arc<Cubic>| {
+            let state = State::new_npt(eos, t, p, &moles, DensityInitialization::Liquid)?;
+            Ok(state.density.to_reduced())
+        };
+        let parameters = [
+            (CubicParameter::CriticalTemperature(1), 1e-3),
+            (CubicParameter::CriticalPressure(0), 1.0),
+            (CubicParameter::AcentricFactor(1), 1e-6),
+            (CubicParameter::Kij(1, 0), 1e-6),
+            (CubicParameter::Lij(0, 1), 1e-6),
+        ];
+        let eos = eos(&records, None, 0.0)?;
+        let state = State::new_npt(&eos, t, p, &moles, DensityInitialization::Liquid)?;
+        let parameter_list: Vec<_> = parameters.iter().map(|&(p, _)| p).collect();
+        let sensitivities = Cubic::density_sensitivities(&state, &parameter_list)?.to_reduced();
+        for (&(parameter, h), &s) in parameters.iter().zip(&sensitivities) {
+            let fd = finite_difference(&records, parameter, h, density)?;
+            assert_relative_eq!(s, fd, max_relative = 1e-5);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn vapor_pressure_sensitivities() -> FeosResult<()> {
+        let mut records = records()[1..].to_vec();
+        records[0].model_record.alpha = Some(AlphaParameters::MathiasCopeman {
+            c1: 0.6,
+            c2: -0.1,
+            c3: 0.2,
+        });
+        let t = 300.0 * KELVIN;
+        let vapor_pressure = |eos: &Arc<Cubic>| {
+            let vle = PhaseEquilibrium::pure(eos, t, None, SolverOptions::default())?;
+            Ok(vle.vapor().pressure(Contributions::Total).to_reduced())
+        };
+        let parameters = [
+            (CubicParameter::CriticalTemperature(0), 1e-3),
+            (CubicParameter::CriticalPressure(0), 1.0),
+            (CubicParameter::AlphaCoefficient(0, 0), 1e-6),
+            (CubicParameter::AlphaCoefficient(0, 2), 1e-6),
+        ];
+        let eos = eos(&records, None, 0.0)?;
+        let vle = PhaseEquilibrium::pure(&eos, t, None, SolverOptions::default())?;
+        let parameter_list: Vec<_> = parameters.iter().map(|&(p, _)| p).collect();
+        let sensitivities =
+            Cubic::vapor_pressure_sensitivities(&vle, &parameter_list)?.to_reduced();
+        for (&(parameter, h), &s) in parameters.iter().zip(&sensitivities) {
+            let fd = finite_difference(&records, parameter, h, vapor_pressure)?;
+            assert_relative_eq!(s, fd, max_relative = 1e-5);
+        }
+
+        // the acentric factor does not enter the component specific alpha function
+        let s = Cubic::vapor_pressure_sensitivities(&vle, &[CubicParameter::AcentricFactor(0)])?;
+        assert_eq!(s.to_reduced()[0], 0.0);
+        assert!(Cubic::vapor_pressure_sensitivities(&vle, &[CubicParameter::Kij(0, 1)]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn flash_sensitivities() -> FeosResult<()> {
+        let records = records();
+        let t = 250.0 * KELVIN;
+        let feed = arr1(&[0.3, 0.7]) * MOL;
+        let flash = |eos: &Arc<Cubic>| {
+            PhaseEquilibrium::tp_flash(eos, t, 30.0 * BAR, &feed, None, Default::default(), None)
+        };
+        let parameters = [
+            (CubicParameter::CriticalTemperature(0), 1e-3),
+            (CubicParameter::AcentricFactor(1), 1e-6),
+            (CubicParameter::Kij(0, 1), 1e-6),
+        ];
+        let parameter_list: Vec<_> = parameters.iter().map(|&(p, _)| p).collect();
+        let eos = eos(&records, None, 0.0)?;
+        let sensitivities = Cubic::flash_sensitivities(&flash(&eos)?, &parameter_list)?;
+        for (&(parameter, h), s) in parameters.iter().zip(&sensitivities) {
+            let vapor_fraction = |eos: &Arc<Cubic>| {
+                let vle = flash(eos)?;
+                let (l, v) = (vle.liquid().total_moles, vle.vapor().total_moles);
+                Ok((v / (l + v)).into_value())
+            };
+            let fd = finite_difference(&records, parameter, h, vapor_fraction)?;
+            assert_relative_eq!(s.vapor_fraction.into_value(), fd, max_relative = 1e-5);
+            let x0 = |eos: &Arc<Cubic>| Ok(flash(eos)?.liquid().molefracs[0]);
+            let fd = finite_difference(&records, parameter, h, x0)?;
+            assert_relative_eq!(
+                s.liquid_molefracs.clone().into_value()[0],
+                fd,
+                max_relative = 1e-5
+            );
+            let y0 = |eos: &Arc<Cubic>| Ok(flash(eos)?.vapor().molefracs[0]);
+            let fd = finite_difference(&records, parameter, h, y0)?;
+            assert_relative_eq!(
+                s.vapor_molefracs.clone().into_value()[0],
+                fd,
+                max_relative = 1e-5
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn bubble_and_dew_pressure_sensitivities() -> FeosResult<()> {
+        let records = records();
+        let t = 250.0 * KELVIN;
+        let z = arr1(&[0.3, 0.7]);
+        let options = (SolverOptions::default(), SolverOptions::default());
+        let bubble_point =
+            |eos: &Arc<Cubic>| PhaseEquilibrium::bubble_point(eos, t, &z, None, None, options);
+        let dew_point =
+            |eos: &Arc<Cubic>| PhaseEquilibrium::dew_point(eos, t, &z, None, None, options);
+        let pressure = |vle: FeosResult<PhaseEquilibrium<Cubic, 2>>| {
+            Ok(vle?.vapor().pressure(Contributions::Total).to_reduced())
+        };
+        let parameters = [
+            (CubicParameter::CriticalTemperature(0), 1e-3),
+            (CubicParameter::AcentricFactor(1), 1e-6),
+            (CubicParameter::Kij(0, 1), 1e-6),
+            (CubicParameter::Lij(0, 1), 1e-6),
+        ];
+        let parameter_list: Vec<_> = parameters.iter().map(|&(p, _)| p).collect();
+        let eos = eos(&records, None, 0.0)?;
+        let bubble = Cubic::bubble_pressure_sensitivities(&bubble_point(&eos)?, &parameter_list)?;
+        let dew = Cubic::dew_pressure_sensitivities(&dew_point(&eos)?, &parameter_list)?;
+        for (i, &(parameter, h)) in parameters.iter().enumerate() {
+            let fd = finite_difference(&records, parameter, h, |e| pressure(bubble_point(e)))?;
+            assert_relative_eq!(bubble.to_reduced()[i], fd, max_relative = 1e-5);
+            let fd = finite_difference(&records, parameter, h, |e| pressure(dew_point(e)))?;
+            assert_relative_eq!(dew.to_reduced()[i], fd, max_relative = 1e-5);
+        }
+        Ok(())
+    }
+}
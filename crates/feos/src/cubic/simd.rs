@@ -0,0 +1,276 @@
+//! Double sums of the mixing rules with a vectorized path for `f64` and
+//! the dual numbers that are used to evaluate state properties.
+use ndarray::{Array1, Array2, ScalarOperand};
+use num_dual::{Dual2_64, Dual3_64, Dual64, DualNum, HyperDual64};
+use std::any::Any;
+use std::borrow::Cow;
+
+/// Evaluate the bilinear form $\sum_{ij}u_iM_{ij}v_j$.
+///
+/// For `f64`, the double sum is evaluated without allocations by a kernel that
+/// is compiled for AVX2 and FMA instructions if the CPU supports them, and with
+/// independent accumulators that can be vectorized by the compiler otherwise.
+/// For dual numbers, the matrix-vector product $w_i=\sum_jM_{ij}v_j$ is linear
+/// in $v$ and is, therefore, evaluated with the same kernel for every `f64`
+/// component (real part and derivatives) of `Dual64`, `Dual2_64`,
+/// `HyperDual64` and `Dual3_64` values. Only the final sum $\sum_iu_iw_i$
+/// requires dual number arithmetic. For small mixtures and other number types,
+/// the double sum is evaluated directly.
+pub(super) fn bilinear_form<D: DualNum<f64> + Copy + ScalarOperand>(
+    matrix: &Array2<f64>,
+    u: &Array1<D>,
+    v: &Array1<D>,
+) -> D {
+    let any = (u as &dyn Any, v as &dyn Any);
+    if let (Some(u), Some(v)) = (any.0.downcast_ref(), any.1.downcast_ref()) {
+        let res: f64 = bilinear_form_f64(matrix, u, v);
+        return *(&res as &dyn Any).downcast_ref::<D>().unwrap();
+    }
+    if v.len() < MIN_VECTORIZED {
+        return u
+            .iter()
+            .zip(matrix.outer_iter())
+            .map(|(&ui, row)| ui * scalar_product(row.iter(), v))
+            .sum();
+    }
+    let w = matrix_vector_product(matrix, v);
+    u.iter().zip(&w).map(|(&ui, &wi)| ui * wi).sum()
+}
+
+fn scalar_product<'a, D: DualNum<f64> + Copy>(
+    row: impl Iterator<Item = &'a f64>,
+    v: &Array1<D>,
+) -> D {
+    let mut acc = D::zero();
+    for (&m, &vj) in row.zip(v) {
+        acc += vj * m;
+    }
+    acc
+}
+
+/// Below this number of components, the overhead of splitting dual numbers
+/// into their components outweighs the gain of the vectorized kernel.
+const MIN_VECTORIZED: usize = 16;
+
+/// Apply [matrix_vector_product_f64] to all components of a dual number.
+macro_rules! matrix_vector_product_dual {
+    ($matrix:expr, $v:expr, $dual:ident, $($component:ident),+) => {
+        if let Some(v) = ($v as &dyn Any).downcast_ref::<Array1<$dual>>() {
+            $(let $component = matrix_vector_product_f64($matrix, &v.mapv(|v| v.$component));)+
+            let w: Array1<$dual> = (0..$matrix.nrows())
+                .map(|i| $dual::new($($component[i]),+))
+                .collect();
+            return *(Box::new(w) as Box<dyn Any>).downcast().unwrap();
+        }
+    };
+}
+
+fn matrix_vector_product<D: DualNum<f64> + Copy>(matrix: &Array2<f64>, v: &Array1<D>) -> Array1<D> {
+    matrix_vector_product_dual!(matrix, v, Dual64, re, eps);
+    matrix_vector_product_dual!(matrix, v, Dual2_64, re, v1, v2);
+    matrix_vector_product_dual!(matrix, v, HyperDual64, re, eps1, eps2, eps1eps2);
+    matrix_vector_product_dual!(matrix, v, Dual3_64, re, v1, v2, v3);
+    matrix
+        .outer_iter()
+        .map(|row| scalar_product(row.iter(), v))
+        .collect()
+}
+
+fn bilinear_form_f64(matrix: &Array2<f64>, u: &Array1<f64>, v: &Array1<f64>) -> f64 {
+    let matrix = matrix.as_standard_layout();
+    let matrix = matrix.as_slice().unwrap();
+    let u = as_slice(u);
+    let v = as_slice(v);
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+        // SAFETY: the required target features are available on this CPU.
+        return unsafe { bilinear_form_avx2(matrix, &u, &v) };
+    }
+    bilinear_form_lanes(matrix, &u, &v)
+}
+
+fn matrix_vector_product_f64(matrix: &Array2<f64>, v: &Array1<f64>) -> Array1<f64> {
+    let matrix = matrix.as_standard_layout();
+    let matrix = matrix.as_slice().unwrap();
+    let v = as_slice(v);
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+        // SAFETY: the required target features are available on this CPU.
+        return unsafe { matrix_vector_product_avx2(matrix, &v) };
+    }
+    matrix_vector_product_lanes(matrix, &v)
+}
+
+fn as_slice(x: &Array1<f64>) -> Cow<'_, [f64]> {
+    x.as_slice()
+        .map_or_else(|| Cow::Owned(x.to_vec()), Cow::Borrowed)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2,fma")]
+fn bilinear_form_avx2(matrix: &[f64], u: &[f64], v: &[f64]) -> f64 {
+    bilinear_form_lanes(matrix, u, v)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2,fma")]
+fn matrix_vector_product_avx2(matrix: &[f64], v: &[f64]) -> Array1<f64> {
+    matrix_vector_product_lanes(matrix, v)
+}
+
+#[inline(always)]
+fn bilinear_form_lanes(matrix: &[f64], u: &[f64], v: &[f64]) -> f64 {
+    let mut res = 0.0;
+    // `chunks_exact` panics for a chunk size of zero
+    if v.is_empty() {
+        return res;
+    }
+    for (row, &ui) in matrix.chunks_exact(v.len()).zip(u) {
+        res += ui * scalar_product_lanes(row, v);
+    }
+    res
+}
+
+#[inline(always)]
+fn matrix_vector_product_lanes(matrix: &[f64], v: &[f64]) -> Array1<f64> {
+    // the (square) matrix of an empty system has no rows
+    if v.is_empty() {
+        return Array1::zeros(0);
+    }
+    matrix
+        .chunks_exact(v.len())
+        .map(|row| scalar_product_lanes(row, v))
+        .collect()
+}
+
+#[inline(always)]
+fn scalar_product_lanes(row: &[f64], v: &[f64]) -> f64 {
+    let mut acc = [0.0; 4];
+    let rows = row.chunks_exact(4);
+    let cols = v.chunks_exact(4);
+    let rest: f64 = rows
+        .remainder()
+        .iter()
+        .zip(cols.remainder())
+        .map(|(m, vj)| m * vj)
+        .sum();
+    for (m, vj) in rows.zip(cols) {
+        for k in 0..4 {
+            acc[k] += m[k] * vj[k];
+        }
+    }
+    acc[0] + acc[1] + acc[2] + acc[3] + rest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use num_dual::Dual2;
+
+    /// Straightforward evaluation of the double sum.
+    fn reference<D: DualNum<f64> + Copy>(matrix: &Array2<f64>, u: &Array1<D>, v: &Array1<D>) -> D {
+        let mut res = D::zero();
+        for ((i, j), &m) in matrix.indexed_iter() {
+            res += u[i] * v[j] * m;
+        }
+        res
+    }
+
+    #[test]
+    fn bilinear_form_paths() {
+        for n in [1, 3, 4, 33, 40] {
+            let matrix = Array2::from_shape_fn((n, n), |(i, j)| 1.0 - 0.01 * (i + j) as f64);
+            let u = Array1::from_shape_fn(n, |i| 0.5 + (i as f64).sin().abs());
+            let v = Array1::from_shape_fn(n, |i| 1.0 / (i as f64 + 1.0));
+            assert_relative_eq!(
+                bilinear_form(&matrix, &u, &v),
+                reference(&matrix, &u, &v),
+                max_relative = 1e-14
+            );
+            assert_relative_eq!(
+                bilinear_form_lanes(matrix.as_slice().unwrap(), &u.to_vec(), &v.to_vec()),
+                reference(&matrix, &u, &v),
+                max_relative = 1e-14
+            );
+            assert_relative_eq!(
+                matrix_vector_product_lanes(matrix.as_slice().unwrap(), &v.to_vec()),
+                matrix.dot(&v),
+                max_relative = 1e-14
+            );
+
+            let ud = u.mapv(|u| Dual64::from(u).derivative());
+            let vd = v.mapv(Dual64::from);
+            let (res, res_ref) = (
+                bilinear_form(&matrix, &ud, &vd),
+                reference(&matrix, &ud, &vd),
+            );
+            assert_relative_eq!(res.re, res_ref.re, max_relative = 1e-14);
+            assert_relative_eq!(res.eps, matrix.dot(&v).sum(), max_relative = 1e-14);
+
+            let ud = u.mapv(|u| Dual2_64::from(u).derivative());
+            let (res, res_ref) = (
+                bilinear_form(&matrix, &ud, &ud),
+                reference(&matrix, &ud, &ud),
+            );
+            for (x, y) in [
+                (res.re, res_ref.re),
+                (res.v1, res_ref.v1),
+                (res.v2, res_ref.v2),
+            ] {
+                assert_relative_eq!(x, y, max_relative = 1e-14);
+            }
+
+            let ud = u.mapv(|u| HyperDual64::from(u).derivative1());
+            let vd = v.mapv(|v| HyperDual64::from(v).derivative2());
+            let (res, res_ref) = (
+                bilinear_form(&matrix, &ud, &vd),
+                reference(&matrix, &ud, &vd),
+            );
+            for (x, y) in [
+                (res.re, res_ref.re),
+                (res.eps1, res_ref.eps1),
+                (res.eps2, res_ref.eps2),
+                (res.eps1eps2, res_ref.eps1eps2),
+            ] {
+                assert_relative_eq!(x, y, max_relative = 1e-14);
+            }
+
+            let ud = u.mapv(|u| Dual3_64::from(u).derivative());
+            let (res, res_ref) = (
+                bilinear_form(&matrix, &ud, &ud),
+                reference(&matrix, &ud, &ud),
+            );
+            for (x, y) in [
+                (res.re, res_ref.re),
+                (res.v1, res_ref.v1),
+                (res.v2, res_ref.v2),
+                (res.v3, res_ref.v3),
+            ] {
+                assert_relative_eq!(x, y, max_relative = 1e-14);
+            }
+
+            // nested dual numbers use the generic path
+            let ud = u.mapv(|u| Dual2::from_re(Dual64::from(u).derivative()).derivative());
+            let (res, res_ref) = (
+                bilinear_form(&matrix, &ud, &ud),
+                reference(&matrix, &ud, &ud),
+            );
+            assert_relative_eq!(res.v2.eps, res_ref.v2.eps, max_relative = 1e-14);
+        }
+    }
+
+    #[test]
+    fn empty_system() {
+        let matrix = Array2::zeros((0, 0));
+        let v: Array1<f64> = Array1::zeros(0);
+        assert_eq!(bilinear_form_lanes(&[], &[], &[]), 0.0);
+        assert_eq!(matrix_vector_product_lanes(&[], &[]).len(), 0);
+        assert_eq!(bilinear_form(&matrix, &v, &v), 0.0);
+        assert_eq!(matrix_vector_product(&matrix, &v).len(), 0);
+
+        let vd = v.mapv(Dual64::from);
+        assert_eq!(bilinear_form(&matrix, &vd, &vd), Dual64::from(0.0));
+        assert_eq!(matrix_vector_product(&matrix, &vd).len(), 0);
+    }
+}
@@ -0,0 +1,243 @@
+use super::characterization::{CharacterizationCorrelation, PseudoComponent};
+use feos_core::{FeosError, FeosResult};
+
+/// Conversion factor from Kelvin to Rankine.
+const RANKINE: f64 = 1.8;
+
+/// Plus fraction (e.g. C7+) of a reservoir fluid.
+#[derive(Clone, Copy, Debug)]
+pub struct PlusFraction {
+    /// mole fraction of the plus fraction in the fluid
+    pub mole_fraction: f64,
+    /// average molar weight in g/mol
+    pub molarweight: f64,
+    /// average specific gravity (60°F/60°F)
+    pub specific_gravity: f64,
+}
+
+/// Single pseudo-component obtained from splitting a plus fraction.
+#[derive(Clone, Copy, Debug)]
+pub struct SplitFraction {
+    /// mole fraction in the fluid
+    pub mole_fraction: f64,
+    /// molar weight in g/mol
+    pub molarweight: f64,
+    /// specific gravity (60°F/60°F)
+    pub specific_gravity: f64,
+    /// normal boiling point in Kelvin
+    pub boiling_temperature: f64,
+}
+
+impl PlusFraction {
+    /// Create a new plus fraction.
+    pub fn new(mole_fraction: f64, molarweight: f64, specific_gravity: f64) -> Self {
+        Self {
+            mole_fraction,
+            molarweight,
+            specific_gravity,
+        }
+    }
+
+    /// Split the plus fraction into `n` pseudo-components with equal mole
+    /// fractions using the three-parameter gamma distribution of
+    /// [Whitson (1983)](https://doi.org/10.2118/12233-PA).
+    ///
+    /// `alpha` is the shape parameter of the distribution (1 corresponds to an
+    /// exponential distribution) and `eta` is the minimum molar weight in g/mol
+    /// (approximately 90 for a C7+ fraction). The molar weights of the
+    /// pseudo-components are the averages of the distribution in each interval,
+    /// so that the total moles and mass of the plus fraction are conserved. The
+    /// specific gravities follow from a constant Watson characterization factor
+    /// that reproduces the specific gravity of the plus fraction.
+    pub fn split_gamma(&self, n: usize, alpha: f64, eta: f64) -> FeosResult<Vec<SplitFraction>> {
+        if n == 0 || alpha <= 0.0 || eta <= 0.0 || eta >= self.molarweight {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "cannot split plus fraction with M = {} g/mol into {n} pseudo-components using alpha = {alpha} and eta = {eta} g/mol.",
+                self.molarweight
+            )));
+        }
+        let beta = (self.molarweight - eta) / alpha;
+
+        // interval boundaries with equal mole fractions
+        let mut y = vec![0.0];
+        for i in 1..n {
+            y.push(inverse_gamma_p(alpha, i as f64 / n as f64)?);
+        }
+        y.push(f64::INFINITY);
+
+        let cdf = |a: f64, y: f64| if y.is_infinite() { 1.0 } else { gamma_p(a, y) };
+        let fractions: Vec<_> = y
+            .windows(2)
+            .map(|y| {
+                let p0 = cdf(alpha, y[1]) - cdf(alpha, y[0]);
+                let p1 = cdf(alpha + 1.0, y[1]) - cdf(alpha + 1.0, y[0]);
+                (p0 * self.mole_fraction, eta + alpha * beta * p1 / p0)
+            })
+            .collect();
+
+        // Watson characterization factor reproducing the specific gravity of the plus fraction
+        let c = |m: f64| (4.5579 * m.powf(0.15178)).powf(1.0 / 0.84573);
+        let mass = self.mole_fraction * self.molarweight;
+        let s: f64 = fractions.iter().map(|&(z, m)| z * m / mass / c(m)).sum();
+        let kw = (1.0 / (self.specific_gravity * s)).powf(0.84573);
+
+        Ok(fractions
+            .into_iter()
+            .map(|(mole_fraction, molarweight)| {
+                let specific_gravity = c(molarweight) * kw.powf(-1.0 / 0.84573);
+                SplitFraction {
+                    mole_fraction,
+                    molarweight,
+                    specific_gravity,
+                    boiling_temperature: (kw * specific_gravity).powi(3) / RANKINE,
+                }
+            })
+            .collect())
+    }
+}
+
+impl SplitFraction {
+    /// Characterize the pseudo-component. The molar weight of the split
+    /// is retained instead of the correlated value.
+    pub fn characterize(
+        &self,
+        correlation: CharacterizationCorrelation,
+    ) -> FeosResult<PseudoComponent> {
+        let mut pseudo_component =
+            PseudoComponent::new(self.boiling_temperature, self.specific_gravity, correlation)?;
+        pseudo_component.molarweight = self.molarweight;
+        Ok(pseudo_component)
+    }
+}
+
+/// Logarithm of the gamma function (Lanczos approximation).
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let series = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .fold(1.000000000190015, |acc, (j, c)| {
+            acc + c / (x + 1.0 + j as f64)
+        });
+    -tmp + (2.5066282746310005 * series / x).ln()
+}
+
+/// Regularized lower incomplete gamma function P(a, x).
+fn gamma_p(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    let prefactor = (-x + a * x.ln() - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        // series representation
+        let (mut sum, mut term, mut ap) = (1.0 / a, 1.0 / a, a);
+        for _ in 0..500 {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        sum * prefactor
+    } else {
+        // continued fraction (modified Lentz)
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..500 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        1.0 - prefactor * h
+    }
+}
+
+/// Inverse of the regularized lower incomplete gamma function with respect to `x`.
+fn inverse_gamma_p(a: f64, p: f64) -> FeosResult<f64> {
+    let (mut lower, mut upper) = (0.0, a.max(1.0));
+    while gamma_p(a, upper) < p {
+        upper *= 2.0;
+    }
+    let mut x = 0.5 * (lower + upper);
+    for _ in 0..100 {
+        // Newton step, safeguarded by bisection
+        let f = gamma_p(a, x) - p;
+        if f > 0.0 {
+            upper = x;
+        } else {
+            lower = x;
+        }
+        let df = ((a - 1.0) * x.ln() - x - ln_gamma(a)).exp();
+        let mut x_new = x - f / df;
+        if !(x_new > lower && x_new < upper) {
+            x_new = 0.5 * (lower + upper);
+        }
+        if (x_new - x).abs() < 1e-12 * x {
+            return Ok(x_new);
+        }
+        x = x_new;
+    }
+    Err(FeosError::NotConverged("inverse gamma distribution".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn split_c7_plus() -> FeosResult<()> {
+        let plus = PlusFraction::new(0.3, 200.0, 0.85);
+        let split = plus.split_gamma(5, 1.0, 90.0)?;
+        let z: f64 = split.iter().map(|s| s.mole_fraction).sum();
+        let mass: f64 = split.iter().map(|s| s.mole_fraction * s.molarweight).sum();
+        let volume: f64 = split
+            .iter()
+            .map(|s| s.mole_fraction * s.molarweight / s.specific_gravity)
+            .sum();
+        assert_relative_eq!(z, 0.3, max_relative = 1e-12);
+        assert_relative_eq!(mass, 0.3 * 200.0, max_relative = 1e-10);
+        assert_relative_eq!(mass / volume, 0.85, max_relative = 1e-10);
+        assert!(
+            split
+                .windows(2)
+                .all(|s| s[0].molarweight < s[1].molarweight)
+        );
+
+        let pseudo_components = split
+            .iter()
+            .map(|s| s.characterize(CharacterizationCorrelation::Twu))
+            .collect::<FeosResult<Vec<_>>>()?;
+        assert!(
+            pseudo_components
+                .windows(2)
+                .all(|p| p[0].tc < p[1].tc && p[0].pc > p[1].pc)
+        );
+        Ok(())
+    }
+}
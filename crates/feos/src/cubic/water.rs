@@ -0,0 +1,381 @@
+//! Water as a convenience component.
+//!
+//! The saturation ancillaries are the vapor pressure equation of
+//! [IAPWS-IF97](http://www.iapws.org/relguide/IF97-Rev.html) (region 4) and
+//! the auxiliary equations for the saturated densities of
+//! [Wagner and Pruß (2002)](https://doi.org/10.1063/1.1461829).
+use super::{Cubic, CubicParameters, CubicRecord};
+use feos_core::parameter::{Identifier, Parameter, PureRecord};
+use feos_core::{
+    Contributions, FeosError, FeosResult, PhaseEquilibrium, ReferenceSystem, Residual,
+    SolverOptions,
+};
+use ndarray::{Array1, arr1};
+use quantity::{Density, KELVIN, METER, MOL, Moles, PASCAL, Pressure, Temperature};
+use std::sync::Arc;
+use typenum::P3;
+
+/// Gas constant in J/mol/K.
+const RGAS: f64 = 8.31446261815324;
+/// Critical temperature in K.
+const TC: f64 = 647.096;
+/// Critical pressure in Pa.
+const PC: f64 = 22.064e6;
+/// Critical density in mol/m³.
+const RHOC: f64 = 322.0 / MW * 1e3;
+/// Acentric factor.
+const ACENTRIC_FACTOR: f64 = 0.3443;
+/// Molar weight in g/mol.
+const MW: f64 = 18.015268;
+/// Rackett compressibility factor.
+const Z_RA: f64 = 0.2338;
+/// Triple point temperature in K.
+const T_TRIPLE: f64 = 273.16;
+/// Minimum temperature of the IF97 saturation line in K.
+const T_MIN: f64 = 273.15;
+/// Minimum pressure of the IF97 saturation line in Pa.
+const P_MIN: f64 = 611.213;
+
+/// Coefficients of the IF97 saturation equation.
+const N: [f64; 10] = [
+    0.11670521452767e4,
+    -0.72421316703206e6,
+    -0.17073846940092e2,
+    0.12020824702470e5,
+    -0.32325550322333e7,
+    0.14915108613530e2,
+    -0.48232657361591e4,
+    0.40511340542057e6,
+    -0.23855557567849,
+    0.65017534844798e3,
+];
+
+/// Coefficients and exponents of the saturated liquid density.
+const LIQUID: [(f64, f64); 6] = [
+    (1.99274064, 1.0 / 3.0),
+    (1.09965342, 2.0 / 3.0),
+    (-0.510839303, 5.0 / 3.0),
+    (-1.75493479, 16.0 / 3.0),
+    (-45.5170352, 43.0 / 3.0),
+    (-6.74694450e5, 110.0 / 3.0),
+];
+
+/// Coefficients and exponents of the saturated vapor density.
+const VAPOR: [(f64, f64); 6] = [
+    (-2.03150240, 2.0 / 6.0),
+    (-2.68302940, 4.0 / 6.0),
+    (-5.38626492, 8.0 / 6.0),
+    (-17.2991605, 18.0 / 6.0),
+    (-44.7586581, 37.0 / 6.0),
+    (-63.9201063, 71.0 / 6.0),
+];
+
+/// Water with IAPWS saturation ancillaries.
+///
+/// The pure record uses the critical data and acentric factor of the IAPWS
+/// formulation and a Peneloux volume shift estimated from the Rackett
+/// compressibility factor. The IF97 vapor pressure is used to initialize
+/// phase equilibrium calculations and the ancillary equations serve as
+/// reference for the sanity checks of a water model.
+pub struct Water;
+
+/// Deviations of a water model from the IAPWS ancillary equations.
+#[derive(Clone, Debug)]
+pub struct IapwsDeviations {
+    /// Temperatures in K.
+    pub temperature: Array1<f64>,
+    /// Relative deviations of the vapor pressure in percent.
+    pub vapor_pressure: Array1<f64>,
+    /// Relative deviations of the saturated liquid density in percent.
+    pub liquid_density: Array1<f64>,
+    /// Relative deviations of the saturated vapor density in percent.
+    pub vapor_density: Array1<f64>,
+}
+
+fn aard(relative_deviation: &Array1<f64>) -> f64 {
+    relative_deviation.mapv(f64::abs).mean().unwrap_or(f64::NAN)
+}
+
+impl IapwsDeviations {
+    /// Average absolute relative deviation of the vapor pressure in percent.
+    pub fn vapor_pressure_aard(&self) -> f64 {
+        aard(&self.vapor_pressure)
+    }
+
+    /// Average absolute relative deviation of the saturated liquid density in percent.
+    pub fn liquid_density_aard(&self) -> f64 {
+        aard(&self.liquid_density)
+    }
+
+    /// Average absolute relative deviation of the saturated vapor density in percent.
+    pub fn vapor_density_aard(&self) -> f64 {
+        aard(&self.vapor_density)
+    }
+}
+
+impl Water {
+    /// Pure component record of water.
+    pub fn pure_record() -> PureRecord<CubicRecord> {
+        let identifier = Identifier::new(
+            Some("7732-18-5"),
+            Some("water"),
+            Some("oxidane"),
+            Some("O"),
+            Some("InChI=1S/H2O/h1H2"),
+            Some("H2O"),
+        );
+        let volume_shift = 0.50033 * (0.25969 - Z_RA) * RGAS * TC / PC;
+        PureRecord::new(
+            identifier,
+            MW,
+            CubicRecord::new(TC, PC, ACENTRIC_FACTOR).with_volume_shift(volume_shift),
+        )
+    }
+
+    /// Peng-Robinson equation of state for pure water.
+    pub fn peng_robinson() -> FeosResult<Cubic> {
+        let parameters = CubicParameters::new_pure(Self::pure_record())?;
+        Cubic::peng_robinson(Arc::new(parameters), None, None)
+    }
+
+    /// Vapor pressure according to IF97 between 273.15 K and the critical temperature.
+    pub fn saturation_pressure(temperature: Temperature) -> FeosResult<Pressure> {
+        let t = temperature.convert_to(KELVIN);
+        if !(T_MIN..=TC).contains(&t) {
+            return Err(FeosError::InvalidState(
+                "IF97 saturation pressure".into(),
+                "T".into(),
+                t,
+            ));
+        }
+        let theta = t + N[8] / (t - N[9]);
+        let a = theta * theta + N[0] * theta + N[1];
+        let b = N[2] * theta * theta + N[3] * theta + N[4];
+        let c = N[5] * theta * theta + N[6] * theta + N[7];
+        let p = (2.0 * c / (-b + (b * b - 4.0 * a * c).sqrt())).powi(4);
+        Ok(p * 1e6 * PASCAL)
+    }
+
+    /// Boiling temperature according to IF97 between 611.213 Pa and the critical pressure.
+    pub fn saturation_temperature(pressure: Pressure) -> FeosResult<Temperature> {
+        let p = pressure.convert_to(PASCAL);
+        if !(P_MIN..=PC).contains(&p) {
+            return Err(FeosError::InvalidState(
+                "IF97 saturation temperature".into(),
+                "p".into(),
+                p,
+            ));
+        }
+        let beta = (p * 1e-6).powf(0.25);
+        let e = beta * beta + N[2] * beta + N[5];
+        let f = N[0] * beta * beta + N[3] * beta + N[6];
+        let g = N[1] * beta * beta + N[4] * beta + N[7];
+        let d = 2.0 * g / (-f - (f * f - 4.0 * e * g).sqrt());
+        let t = 0.5 * (N[9] + d - ((N[9] + d).powi(2) - 4.0 * (N[8] + N[9] * d)).sqrt());
+        Ok(t * KELVIN)
+    }
+
+    /// Saturated liquid and vapor densities according to Wagner and Pruß.
+    pub fn saturated_densities(temperature: Temperature) -> FeosResult<(Density, Density)> {
+        let t = temperature.convert_to(KELVIN);
+        if !(T_MIN..=TC).contains(&t) {
+            return Err(FeosError::InvalidState(
+                "IAPWS saturated densities".into(),
+                "T".into(),
+                t,
+            ));
+        }
+        let tau = 1.0 - t / TC;
+        let series = |coefs: &[(f64, f64)]| -> f64 {
+            coefs.iter().map(|&(c, e)| c * tau.powf(e)).sum::<f64>()
+        };
+        let density = MOL / METER.powi::<P3>();
+        let rho_l = RHOC * (1.0 + series(&LIQUID));
+        let rho_v = RHOC * series(&VAPOR).exp();
+        Ok((rho_l * density, rho_v * density))
+    }
+
+    /// Vapor-liquid equilibrium of pure water at the given temperature
+    /// initialized with the IAPWS ancillary equations.
+    pub fn vle<E: Residual>(
+        eos: &Arc<E>,
+        temperature: Temperature,
+        options: SolverOptions,
+    ) -> FeosResult<PhaseEquilibrium<E, 2>> {
+        if eos.components() != 1 {
+            return Err(FeosError::IncompatibleComponents(eos.components(), 1));
+        }
+        let initial_state = Self::ancillary(eos, temperature)?;
+        PhaseEquilibrium::pure(eos, temperature, Some(&initial_state), options)
+    }
+
+    /// Vapor-liquid equilibrium of pure water at the given pressure
+    /// initialized with the IAPWS ancillary equations.
+    pub fn boiling_point<E: Residual>(
+        eos: &Arc<E>,
+        pressure: Pressure,
+        options: SolverOptions,
+    ) -> FeosResult<PhaseEquilibrium<E, 2>> {
+        if eos.components() != 1 {
+            return Err(FeosError::IncompatibleComponents(eos.components(), 1));
+        }
+        let temperature = Self::saturation_temperature(pressure)?;
+        let initial_state = Self::ancillary(eos, temperature)?;
+        PhaseEquilibrium::pure(eos, pressure, Some(&initial_state), options)
+    }
+
+    fn ancillary<E: Residual>(
+        eos: &Arc<E>,
+        temperature: Temperature,
+    ) -> FeosResult<PhaseEquilibrium<E, 2>> {
+        let pressure = Self::saturation_pressure(temperature)?;
+        let moles = Moles::from_reduced(arr1(&[1.0]));
+        PhaseEquilibrium::new_npt(eos, temperature, pressure, &moles, &moles)
+    }
+
+    /// Compare the saturation properties of a water model with the IAPWS
+    /// ancillary equations.
+    ///
+    /// `npoints` temperatures are distributed evenly between the triple point
+    /// and `max_reduced_temperature` times the critical temperature of water.
+    /// Temperatures at which the phase equilibrium calculation fails are skipped.
+    pub fn iapws_deviations<E: Residual>(
+        eos: &Arc<E>,
+        npoints: usize,
+        max_reduced_temperature: f64,
+        options: SolverOptions,
+    ) -> FeosResult<IapwsDeviations> {
+        let temperatures = Array1::linspace(T_TRIPLE, max_reduced_temperature * TC, npoints);
+        let density = MOL / METER.powi::<P3>();
+        let mut deviations = Vec::with_capacity(npoints);
+        for &t in temperatures.iter() {
+            let Ok(vle) = Self::vle(eos, t * KELVIN, options) else {
+                continue;
+            };
+            let p = Self::saturation_pressure(t * KELVIN)?;
+            let (rho_l, rho_v) = Self::saturated_densities(t * KELVIN)?;
+            let relative = |model: f64, reference: f64| (model / reference - 1.0) * 100.0;
+            deviations.push([
+                t,
+                relative(
+                    vle.vapor()
+                        .pressure(Contributions::Total)
+                        .convert_to(PASCAL),
+                    p.convert_to(PASCAL),
+                ),
+                relative(
+                    vle.liquid().density.convert_to(density),
+                    rho_l.convert_to(density),
+                ),
+                relative(
+                    vle.vapor().density.convert_to(density),
+                    rho_v.convert_to(density),
+                ),
+            ]);
+        }
+        if deviations.is_empty() {
+            return Err(FeosError::NotConverged(
+                "phase equilibria for the comparison with IAPWS".into(),
+            ));
+        }
+        let column = |k: usize| deviations.iter().map(|d| d[k]).collect();
+        Ok(IapwsDeviations {
+            temperature: column(0),
+            vapor_pressure: column(1),
+            liquid_density: column(2),
+            vapor_density: column(3),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use feos_core::WaterContent;
+    use ndarray::arr2;
+    use quantity::{BAR, GRAM, KILOGRAM, MEGA, MILLI};
+
+    #[test]
+    fn if97_verification() -> FeosResult<()> {
+        // verification values from table 35 and 36 of IF97
+        for (t, p) in [
+            (300.0, 0.353658941e-2),
+            (500.0, 0.263889776e1),
+            (600.0, 0.123443146e2),
+        ] {
+            let psat = Water::saturation_pressure(t * KELVIN)?;
+            assert_relative_eq!(psat, p * MEGA * PASCAL, max_relative = 1e-8);
+        }
+        for (p, t) in [(0.1, 372.755919), (1.0, 453.035632), (10.0, 584.149488)] {
+            let tsat = Water::saturation_temperature(p * MEGA * PASCAL)?;
+            assert_relative_eq!(tsat, t * KELVIN, max_relative = 1e-8);
+        }
+        assert!(Water::saturation_pressure(700.0 * KELVIN).is_err());
+        assert!(Water::saturation_temperature(100.0 * PASCAL).is_err());
+
+        // saturated densities at the normal boiling point
+        let (rho_l, rho_v) = Water::saturated_densities(373.124 * KELVIN)?;
+        let kg_m3 = KILOGRAM / METER.powi::<P3>();
+        let mw = MW * 1e-3 * KILOGRAM / MOL;
+        assert_relative_eq!(rho_l * mw, 958.37 * kg_m3, max_relative = 1e-4);
+        assert_relative_eq!(rho_v * mw, 0.5977 * kg_m3, max_relative = 1e-3);
+        Ok(())
+    }
+
+    #[test]
+    fn peng_robinson_water() -> FeosResult<()> {
+        let eos = Arc::new(Water::peng_robinson()?);
+        let vle = Water::boiling_point(&eos, 101325.0 * PASCAL, SolverOptions::default())?;
+        assert_relative_eq!(
+            vle.vapor().temperature,
+            373.124 * KELVIN,
+            max_relative = 1e-2
+        );
+
+        let deviations = Water::iapws_deviations(&eos, 20, 0.95, SolverOptions::default())?;
+        assert_eq!(deviations.temperature.len(), 20);
+        assert!(deviations.vapor_pressure_aard() < 6.0);
+        assert!(deviations.liquid_density_aard() < 10.0);
+        assert!(deviations.vapor_density_aard() < 10.0);
+        Ok(())
+    }
+
+    #[test]
+    fn water_content_soreide_whitson() -> FeosResult<()> {
+        let methane = PureRecord::new(
+            Identifier::new(Some("74-82-8"), Some("methane"), None, None, None, None),
+            16.043,
+            CubicRecord::new(190.564, 4599000.0, 0.0114),
+        );
+        let records = vec![Water::pure_record(), methane];
+        // binary interaction parameter of the non-aqueous phase of Søreide and Whitson
+        let k_ij = 0.485;
+        let binary = arr2(&[[0.0.into(), k_ij.into()], [k_ij.into(), 0.0.into()]]);
+        let parameters = Arc::new(CubicParameters::from_records(records, Some(binary))?);
+        let (aqueous, gas) = Cubic::soreide_whitson(parameters, 0, 0.0)?;
+        let (aqueous, gas) = (Arc::new(aqueous), Arc::new(gas));
+        let (t, p) = (300.0 * KELVIN, 50.0 * BAR);
+
+        // about 650 mg/Sm³ according to the chart of McKetta and Wehe
+        let content = WaterContent::saturated(&gas, Some(&aqueous), t, p, &arr1(&[0.0, 1.0]), 0)?;
+        let mg_sm3 = content
+            .water_content
+            .convert_to(MILLI * GRAM / METER.powi::<P3>());
+        assert_relative_eq!(mg_sm3, 650.0, max_relative = 0.15);
+        assert!(content.aqueous_phase().molefracs[1] < 2e-3);
+
+        let wet = content.gas().molefracs.clone();
+        let dew = WaterContent::dew_point(
+            &gas,
+            Some(&aqueous),
+            p,
+            &wet,
+            0,
+            None,
+            SolverOptions::default(),
+        )?;
+        assert_relative_eq!(dew.temperature, t, max_relative = 1e-8);
+        Ok(())
+    }
+}
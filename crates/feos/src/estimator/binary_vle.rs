@@ -1,9 +1,8 @@
-use super::{DataSet, FeosError, Phase};
+use super::{DataSet, FeosError, Phase, map_datapoints};
 use feos_core::{
     Contributions, DensityInitialization, PhaseDiagram, PhaseEquilibrium, ReferenceSystem,
     Residual, State, TemperatureOrPressure,
 };
-use itertools::izip;
 use ndarray::{arr1, s, Array1, ArrayView1, Axis};
 use quantity::{MolarEnergy, Moles, Pressure, Quantity, Temperature, _Dimensionless, PASCAL, RGAS};
 use std::fmt;
@@ -58,13 +57,9 @@ impl<E: Residual> DataSet<E> for BinaryVleChemicalPotential {
     }
 
     fn predict(&self, eos: &Arc<E>) -> Result<Array1<f64>, FeosError> {
-        let mut prediction = Vec::new();
-        for (&xi, &yi, t, p) in izip!(
-            &self.liquid_molefracs,
-            &self.vapor_molefracs,
-            &self.temperature,
-            &self.pressure
-        ) {
+        let prediction = map_datapoints(self.liquid_molefracs.len(), |i| {
+            let (xi, yi) = (self.liquid_molefracs[i], self.vapor_molefracs[i]);
+            let (t, p) = (self.temperature.get(i), self.pressure.get(i));
             let liquid_moles = Moles::from_reduced(arr1(&[xi, 1.0 - xi]));
             let liquid = State::new_npt(eos, t, p, &liquid_moles, DensityInitialization::Liquid)?;
             let mu_res_liquid = liquid.residual_chemical_potential();
@@ -74,9 +69,14 @@ impl<E: Residual> DataSet<E> for BinaryVleChemicalPotential {
 
             let kt = RGAS * t;
             let rho_frac = (&liquid.partial_density / &vapor.partial_density).into_value();
-            prediction.push(mu_res_liquid.get(0) - mu_res_vapor.get(0) + kt * rho_frac[0].ln());
-            prediction.push(mu_res_liquid.get(1) - mu_res_vapor.get(1) + kt * rho_frac[1].ln());
-        }
+            Ok([
+                mu_res_liquid.get(0) - mu_res_vapor.get(0) + kt * rho_frac[0].ln(),
+                mu_res_liquid.get(1) - mu_res_vapor.get(1) + kt * rho_frac[1].ln(),
+            ])
+        })
+        .into_iter()
+        .collect::<Result<Vec<_>, FeosError>>()?
+        .concat();
         let prediction = (MolarEnergy::from_vec(prediction) / MolarEnergy::from_reduced(500.0))
             .into_value()
             + 1.0;
@@ -149,35 +149,34 @@ impl<E: Residual> DataSet<E> for BinaryVlePressure {
 
     fn predict(&self, eos: &Arc<E>) -> Result<Array1<f64>, FeosError> {
         let options = Default::default();
-        self.molefracs
-            .iter()
-            .enumerate()
-            .map(|(i, &xi)| {
-                let vle = (match self.phase {
-                    Phase::Vapor => PhaseEquilibrium::dew_point(
-                        eos,
-                        self.temperature.get(i),
-                        &arr1(&[xi, 1.0 - xi]),
-                        Some(self.pressure.get(i)),
-                        None,
-                        options,
-                    ),
-                    Phase::Liquid => PhaseEquilibrium::bubble_point(
-                        eos,
-                        self.temperature.get(i),
-                        &arr1(&[xi, 1.0 - xi]),
-                        Some(self.pressure.get(i)),
-                        None,
-                        options,
-                    ),
-                })?;
+        map_datapoints(self.molefracs.len(), |i| {
+            let xi = self.molefracs[i];
+            let vle = (match self.phase {
+                Phase::Vapor => PhaseEquilibrium::dew_point(
+                    eos,
+                    self.temperature.get(i),
+                    &arr1(&[xi, 1.0 - xi]),
+                    Some(self.pressure.get(i)),
+                    None,
+                    options,
+                ),
+                Phase::Liquid => PhaseEquilibrium::bubble_point(
+                    eos,
+                    self.temperature.get(i),
+                    &arr1(&[xi, 1.0 - xi]),
+                    Some(self.pressure.get(i)),
+                    None,
+                    options,
+                ),
+            })?;
 
-                Ok(vle
-                    .vapor()
-                    .pressure(Contributions::Total)
-                    .convert_to(self.unit))
-            })
-            .collect()
+            Ok(vle
+                .vapor()
+                .pressure(Contributions::Total)
+                .convert_to(self.unit))
+        })
+        .into_iter()
+        .collect()
     }
 
     // fn get_input(&self) -> HashMap<String, SIArray1> {
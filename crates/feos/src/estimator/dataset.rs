@@ -5,6 +5,8 @@
 use super::Loss;
 use feos_core::{FeosError, Residual};
 use ndarray::Array1;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use std::fmt;
 use std::sync::Arc;
 
@@ -67,3 +69,18 @@ impl<E: Residual> fmt::Display for dyn DataSet<E> {
         )
     }
 }
+
+/// Evaluate `f` for the data points `0..datapoints`.
+///
+/// If the `rayon` feature is enabled, the data points are evaluated in
+/// parallel on the current thread pool.
+pub(crate) fn map_datapoints<T, F>(datapoints: usize, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize) -> T + Send + Sync,
+{
+    #[cfg(feature = "rayon")]
+    return (0..datapoints).into_par_iter().map(f).collect();
+    #[cfg(not(feature = "rayon"))]
+    return (0..datapoints).map(f).collect();
+}
@@ -0,0 +1,145 @@
+use super::{DataSet, FeosError, Phase, map_datapoints};
+use feos_core::{
+    DensityInitialization, Molarweight, PhaseEquilibrium, ReferenceSystem, Residual, SolverOptions,
+    State,
+};
+use ndarray::{Array1, arr1};
+use quantity::{KILOGRAM, METER, MassDensity, Moles, Pressure, Temperature};
+use std::sync::Arc;
+use typenum::P3;
+
+/// Mass density data of a pure substance as function of temperature and
+/// pressure (pρT data) in an arbitrary phase.
+#[derive(Clone)]
+pub struct Density {
+    /// mass density
+    pub target: Array1<f64>,
+    /// unit of mass density
+    unit: MassDensity,
+    /// temperature
+    temperature: Temperature<Array1<f64>>,
+    /// pressure
+    pressure: Pressure<Array1<f64>>,
+    /// phase of the data points
+    phase: Option<Phase>,
+}
+
+impl Density {
+    /// A new data set for densities with pressures and temperatures as input.
+    ///
+    /// If `phase` is `None`, the density of the stable phase is calculated.
+    pub fn new(
+        target: MassDensity<Array1<f64>>,
+        temperature: Temperature<Array1<f64>>,
+        pressure: Pressure<Array1<f64>>,
+        phase: Option<Phase>,
+    ) -> Self {
+        let unit = KILOGRAM / METER.powi::<P3>();
+        Self {
+            target: (target / unit).to_reduced(),
+            unit,
+            temperature,
+            pressure,
+            phase,
+        }
+    }
+
+    /// Returns temperature of data points.
+    pub fn temperature(&self) -> &Temperature<Array1<f64>> {
+        &self.temperature
+    }
+
+    /// Returns pressure of data points.
+    pub fn pressure(&self) -> &Pressure<Array1<f64>> {
+        &self.pressure
+    }
+}
+
+impl<E: Residual + Molarweight> DataSet<E> for Density {
+    fn target(&self) -> &Array1<f64> {
+        &self.target
+    }
+
+    fn target_str(&self) -> &str {
+        "density"
+    }
+
+    fn input_str(&self) -> Vec<&str> {
+        vec!["temperature", "pressure"]
+    }
+
+    fn predict(&self, eos: &Arc<E>) -> Result<Array1<f64>, FeosError> {
+        let moles = Moles::from_reduced(arr1(&[1.0]));
+        let density_initialization = self.phase.map_or(DensityInitialization::None, Into::into);
+        Ok(map_datapoints(self.target.len(), |i| {
+            let (t, p) = (self.temperature.get(i), self.pressure.get(i));
+            let state = State::new_npt(eos, t, p, &moles, density_initialization);
+            if let Ok(s) = state {
+                (s.mass_density() / self.unit).into_value()
+            } else {
+                f64::NAN
+            }
+        })
+        .into())
+    }
+}
+
+/// Store experimental data of vapor densities calculated for phase equilibria.
+#[derive(Clone)]
+pub struct EquilibriumVaporDensity {
+    pub target: Array1<f64>,
+    /// unit of mass density
+    unit: MassDensity,
+    /// temperature
+    temperature: Temperature<Array1<f64>>,
+    /// options for VLE solver
+    solver_options: SolverOptions,
+}
+
+impl EquilibriumVaporDensity {
+    /// A new data set for saturated vapor densities with temperatures as input.
+    pub fn new(
+        target: MassDensity<Array1<f64>>,
+        temperature: Temperature<Array1<f64>>,
+        vle_options: Option<SolverOptions>,
+    ) -> Self {
+        let unit = KILOGRAM / METER.powi::<P3>();
+        Self {
+            target: (target / unit).to_reduced(),
+            unit,
+            temperature,
+            solver_options: vle_options.unwrap_or_default(),
+        }
+    }
+
+    /// Returns temperature of data points.
+    pub fn temperature(&self) -> &Temperature<Array1<f64>> {
+        &self.temperature
+    }
+}
+
+impl<E: Residual + Molarweight> DataSet<E> for EquilibriumVaporDensity {
+    fn target(&self) -> &Array1<f64> {
+        &self.target
+    }
+
+    fn target_str(&self) -> &str {
+        "equilibrium vapor density"
+    }
+
+    fn input_str(&self) -> Vec<&str> {
+        vec!["temperature"]
+    }
+
+    fn predict(&self, eos: &Arc<E>) -> Result<Array1<f64>, FeosError> {
+        Ok(map_datapoints(self.target.len(), |i| {
+            let t = self.temperature.get(i);
+            if let Ok(state) = PhaseEquilibrium::pure(eos, t, None, self.solver_options) {
+                (state.vapor().mass_density() / self.unit).into_value()
+            } else {
+                f64::NAN
+            }
+        })
+        .into())
+    }
+}
@@ -3,6 +3,8 @@
 use super::{DataSet, FeosError, Loss};
 use feos_core::Residual;
 use ndarray::{arr1, concatenate, Array1, ArrayView1, Axis};
+#[cfg(feature = "rayon")]
+use rayon::{ThreadPool, ThreadPoolBuilder};
 // use quantity::si::SIArray1;
 use std::fmt;
 use std::fmt::Display;
@@ -15,6 +17,8 @@ pub struct Estimator<E: Residual> {
     data: Vec<Arc<dyn DataSet<E>>>,
     weights: Vec<f64>,
     losses: Vec<Loss>,
+    #[cfg(feature = "rayon")]
+    thread_pool: Option<Arc<ThreadPool>>,
 }
 
 impl<E: Residual> Estimator<E> {
@@ -27,9 +31,38 @@ impl<E: Residual> Estimator<E> {
             data,
             weights,
             losses,
+            #[cfg(feature = "rayon")]
+            thread_pool: None,
         }
     }
 
+    /// Use the given thread pool for the parallel evaluation of the data points.
+    ///
+    /// By default, the global thread pool of rayon is used.
+    #[cfg(feature = "rayon")]
+    pub fn with_thread_pool(mut self, thread_pool: Arc<ThreadPool>) -> Self {
+        self.thread_pool = Some(thread_pool);
+        self
+    }
+
+    /// Use a dedicated thread pool with `num_threads` threads for the
+    /// evaluation of the data points. A single thread disables the
+    /// parallelization.
+    #[cfg(feature = "rayon")]
+    pub fn with_num_threads(self, num_threads: usize) -> Result<Self, FeosError> {
+        let thread_pool = ThreadPoolBuilder::new().num_threads(num_threads).build()?;
+        Ok(self.with_thread_pool(Arc::new(thread_pool)))
+    }
+
+    /// Execute `op` within the configured thread pool.
+    fn install<T: Send>(&self, op: impl FnOnce() -> T + Send) -> T {
+        #[cfg(feature = "rayon")]
+        if let Some(thread_pool) = &self.thread_pool {
+            return thread_pool.install(op);
+        }
+        op()
+    }
+
     /// Add a `DataSet` and its weight.
     pub fn add_data(&mut self, data: &Arc<dyn DataSet<E>>, weight: f64, loss: Loss) {
         self.data.push(data.clone());
@@ -42,27 +75,30 @@ impl<E: Residual> Estimator<E> {
     /// Each cost contains the inverse weight.
     pub fn cost(&self, eos: &Arc<E>) -> Result<Array1<f64>, FeosError> {
         let w = arr1(&self.weights) / self.weights.iter().sum::<f64>();
-        let predictions = self
-            .data
-            .iter()
-            .enumerate()
-            .map(|(i, d)| Ok(d.cost(eos, self.losses[i])? * w[i]))
-            .collect::<Result<Vec<_>, FeosError>>()?;
+        let predictions = self.install(|| {
+            self.data
+                .iter()
+                .enumerate()
+                .map(|(i, d)| Ok(d.cost(eos, self.losses[i])? * w[i]))
+                .collect::<Result<Vec<_>, FeosError>>()
+        })?;
         let aview: Vec<ArrayView1<f64>> = predictions.iter().map(|pi| pi.view()).collect();
         Ok(concatenate(Axis(0), &aview)?)
     }
 
     /// Returns the properties as computed by the equation of state for each `DataSet`.
     pub fn predict(&self, eos: &Arc<E>) -> Result<Vec<Array1<f64>>, FeosError> {
-        self.data.iter().map(|d| d.predict(eos)).collect()
+        self.install(|| self.data.iter().map(|d| d.predict(eos)).collect())
     }
 
     /// Returns the relative difference for each `DataSet`.
     pub fn relative_difference(&self, eos: &Arc<E>) -> Result<Vec<Array1<f64>>, FeosError> {
-        self.data
-            .iter()
-            .map(|d| d.relative_difference(eos))
-            .collect()
+        self.install(|| {
+            self.data
+                .iter()
+                .map(|d| d.relative_difference(eos))
+                .collect()
+        })
     }
 
     /// Returns the mean absolute relative difference for each `DataSet`.
@@ -70,10 +106,12 @@ impl<E: Residual> Estimator<E> {
         &self,
         eos: &Arc<E>,
     ) -> Result<Array1<f64>, FeosError> {
-        self.data
-            .iter()
-            .map(|d| d.mean_absolute_relative_difference(eos))
-            .collect()
+        self.install(|| {
+            self.data
+                .iter()
+                .map(|d| d.mean_absolute_relative_difference(eos))
+                .collect()
+        })
     }
 
     /// Returns the stored `DataSet`s.
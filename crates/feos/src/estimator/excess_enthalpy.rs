@@ -0,0 +1,92 @@
+use super::{DataSet, FeosError, map_datapoints};
+use feos_core::{DensityInitialization, ReferenceSystem, Residual, State};
+use ndarray::{Array1, arr1};
+use quantity::{JOULE, MOL, MolarEnergy, Moles, Pressure, Temperature};
+use std::sync::Arc;
+
+/// Store experimental excess enthalpies of binary liquid mixtures.
+///
+/// The excess enthalpy is calculated from residual properties only, because
+/// the ideal gas contributions cancel.
+#[derive(Clone)]
+pub struct BinaryExcessEnthalpy {
+    /// molar excess enthalpy
+    pub target: Array1<f64>,
+    /// unit of molar excess enthalpy
+    unit: MolarEnergy,
+    /// temperature
+    temperature: Temperature<Array1<f64>>,
+    /// pressure
+    pressure: Pressure<Array1<f64>>,
+    /// mole fraction of the first component
+    molefracs: Array1<f64>,
+}
+
+impl BinaryExcessEnthalpy {
+    /// A new data set for excess enthalpies with temperatures, pressures and
+    /// mole fractions of the first component as input.
+    pub fn new(
+        target: MolarEnergy<Array1<f64>>,
+        temperature: Temperature<Array1<f64>>,
+        pressure: Pressure<Array1<f64>>,
+        molefracs: Array1<f64>,
+    ) -> Self {
+        let unit = JOULE / MOL;
+        Self {
+            target: (target / unit).into_value(),
+            unit,
+            temperature,
+            pressure,
+            molefracs,
+        }
+    }
+
+    /// Returns temperature of data points.
+    pub fn temperature(&self) -> &Temperature<Array1<f64>> {
+        &self.temperature
+    }
+
+    /// Returns pressure of data points.
+    pub fn pressure(&self) -> &Pressure<Array1<f64>> {
+        &self.pressure
+    }
+
+    /// Returns mole fractions of the first component.
+    pub fn molefracs(&self) -> &Array1<f64> {
+        &self.molefracs
+    }
+}
+
+impl<E: Residual> DataSet<E> for BinaryExcessEnthalpy {
+    fn target(&self) -> &Array1<f64> {
+        &self.target
+    }
+
+    fn target_str(&self) -> &str {
+        "excess enthalpy"
+    }
+
+    fn input_str(&self) -> Vec<&str> {
+        vec!["temperature", "pressure", "liquid molefracs"]
+    }
+
+    fn predict(&self, eos: &Arc<E>) -> Result<Array1<f64>, FeosError> {
+        let liquid = |t, p, x: f64| -> Result<MolarEnergy, FeosError> {
+            let moles = Moles::from_reduced(arr1(&[x, 1.0 - x]));
+            let state = State::new_npt(eos, t, p, &moles, DensityInitialization::Liquid)?;
+            Ok(state.residual_molar_enthalpy())
+        };
+        Ok(map_datapoints(self.target.len(), |i| {
+            let (t, p, x) = (
+                self.temperature.get(i),
+                self.pressure.get(i),
+                self.molefracs[i],
+            );
+            let h_e = || -> Result<MolarEnergy, FeosError> {
+                Ok(liquid(t, p, x)? - liquid(t, p, 1.0)? * x - liquid(t, p, 0.0)? * (1.0 - x))
+            };
+            h_e().map_or(f64::NAN, |h_e| (h_e / self.unit).into_value())
+        })
+        .into())
+    }
+}
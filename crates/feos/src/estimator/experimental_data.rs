@@ -0,0 +1,290 @@
+//! Typed experimental data that can be read from json files and converted
+//! into [`DataSet`]s.
+use super::{
+    BinaryExcessEnthalpy, BinaryVlePressure, DataSet, Density, EquilibriumLiquidDensity,
+    EquilibriumVaporDensity, FeosError, LiquidDensity, Phase, VaporPressure, WeightedDataSet,
+    Weighting,
+};
+use feos_core::{Molarweight, Residual};
+use ndarray::Array1;
+use quantity::{JOULE, KELVIN, KILOGRAM, METER, MOL, PASCAL};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::Arc;
+use typenum::P3;
+
+/// Experimental data of a single property.
+///
+/// All quantities are given in SI units: temperatures in K, pressures in Pa,
+/// mass densities in kg/m³ and molar enthalpies in J/mol. Mole fractions
+/// refer to the first component of a binary mixture.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "property", rename_all = "snake_case")]
+pub enum ExperimentalProperty {
+    /// Vapor pressures of a pure substance.
+    VaporPressure {
+        temperature: Vec<f64>,
+        pressure: Vec<f64>,
+    },
+    /// Saturated liquid densities of a pure substance.
+    SaturatedLiquidDensity {
+        temperature: Vec<f64>,
+        density: Vec<f64>,
+    },
+    /// Saturated vapor densities of a pure substance.
+    SaturatedVaporDensity {
+        temperature: Vec<f64>,
+        density: Vec<f64>,
+    },
+    /// Liquid densities of a pure substance.
+    LiquidDensity {
+        temperature: Vec<f64>,
+        pressure: Vec<f64>,
+        density: Vec<f64>,
+    },
+    /// pρT data of a pure substance.
+    Density {
+        temperature: Vec<f64>,
+        pressure: Vec<f64>,
+        density: Vec<f64>,
+        #[serde(default)]
+        phase: Option<Phase>,
+    },
+    /// Bubble or dew point pressures of a binary mixture.
+    BinaryVle {
+        temperature: Vec<f64>,
+        pressure: Vec<f64>,
+        molefracs: Vec<f64>,
+        phase: Phase,
+    },
+    /// Excess enthalpies of a binary liquid mixture.
+    ExcessEnthalpy {
+        temperature: Vec<f64>,
+        pressure: Vec<f64>,
+        molefracs: Vec<f64>,
+        excess_enthalpy: Vec<f64>,
+    },
+}
+
+/// Experimental data of a single property together with the uncertainties
+/// of the data points and the weighting scheme used in a regression.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExperimentalData {
+    /// Experimental data points.
+    #[serde(flatten)]
+    pub property: ExperimentalProperty,
+    /// Absolute uncertainties of the target quantity in SI units.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uncertainty: Option<Vec<f64>>,
+    /// Weighting scheme of the data points.
+    #[serde(default)]
+    pub weighting: Weighting,
+    /// Literature source of the data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl ExperimentalData {
+    /// Create experimental data with uniform weights.
+    pub fn new(property: ExperimentalProperty) -> Self {
+        Self {
+            property,
+            uncertainty: None,
+            weighting: Weighting::Uniform,
+            source: None,
+        }
+    }
+
+    /// Read a list of experimental data from a json file.
+    pub fn from_json<P: AsRef<Path>>(file: P) -> Result<Vec<Self>, FeosError> {
+        Self::from_reader(BufReader::new(File::open(file)?))
+    }
+
+    /// Read a list of experimental data from a reader providing json.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Vec<Self>, FeosError> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Returns the number of experimental data points.
+    pub fn datapoints(&self) -> usize {
+        match &self.property {
+            ExperimentalProperty::VaporPressure { temperature, .. }
+            | ExperimentalProperty::SaturatedLiquidDensity { temperature, .. }
+            | ExperimentalProperty::SaturatedVaporDensity { temperature, .. }
+            | ExperimentalProperty::LiquidDensity { temperature, .. }
+            | ExperimentalProperty::Density { temperature, .. }
+            | ExperimentalProperty::BinaryVle { temperature, .. }
+            | ExperimentalProperty::ExcessEnthalpy { temperature, .. } => temperature.len(),
+        }
+    }
+
+    fn check_lengths(&self, inputs: &[&Vec<f64>]) -> Result<(), FeosError> {
+        let n = self.datapoints();
+        if let Some(input) = inputs.iter().find(|i| i.len() != n) {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "inconsistent number of data points ({} and {n}).",
+                input.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Convert the experimental data into a [`DataSet`] that can be used in an
+    /// [`Estimator`](super::Estimator).
+    ///
+    /// If the weighting scheme is not uniform, the data set is wrapped in a
+    /// [`WeightedDataSet`].
+    pub fn to_dataset<E: Residual + Molarweight + 'static>(
+        &self,
+    ) -> Result<Arc<dyn DataSet<E>>, FeosError> {
+        let array = |x: &Vec<f64>| Array1::from_vec(x.clone());
+        let mass_density = KILOGRAM / METER.powi::<P3>();
+        let data: Arc<dyn DataSet<E>> = match &self.property {
+            ExperimentalProperty::VaporPressure {
+                temperature,
+                pressure,
+            } => {
+                self.check_lengths(&[pressure])?;
+                Arc::new(VaporPressure::new(
+                    array(pressure) * PASCAL,
+                    array(temperature) * KELVIN,
+                    false,
+                    None,
+                    None,
+                ))
+            }
+            ExperimentalProperty::SaturatedLiquidDensity {
+                temperature,
+                density,
+            } => {
+                self.check_lengths(&[density])?;
+                Arc::new(EquilibriumLiquidDensity::new(
+                    array(density) * mass_density,
+                    array(temperature) * KELVIN,
+                    None,
+                ))
+            }
+            ExperimentalProperty::SaturatedVaporDensity {
+                temperature,
+                density,
+            } => {
+                self.check_lengths(&[density])?;
+                Arc::new(EquilibriumVaporDensity::new(
+                    array(density) * mass_density,
+                    array(temperature) * KELVIN,
+                    None,
+                ))
+            }
+            ExperimentalProperty::LiquidDensity {
+                temperature,
+                pressure,
+                density,
+            } => {
+                self.check_lengths(&[pressure, density])?;
+                Arc::new(LiquidDensity::new(
+                    array(density) * mass_density,
+                    array(temperature) * KELVIN,
+                    array(pressure) * PASCAL,
+                ))
+            }
+            ExperimentalProperty::Density {
+                temperature,
+                pressure,
+                density,
+                phase,
+            } => {
+                self.check_lengths(&[pressure, density])?;
+                Arc::new(Density::new(
+                    array(density) * mass_density,
+                    array(temperature) * KELVIN,
+                    array(pressure) * PASCAL,
+                    *phase,
+                ))
+            }
+            ExperimentalProperty::BinaryVle {
+                temperature,
+                pressure,
+                molefracs,
+                phase,
+            } => {
+                self.check_lengths(&[pressure, molefracs])?;
+                Arc::new(BinaryVlePressure::new(
+                    array(temperature) * KELVIN,
+                    array(pressure) * PASCAL,
+                    array(molefracs),
+                    *phase,
+                ))
+            }
+            ExperimentalProperty::ExcessEnthalpy {
+                temperature,
+                pressure,
+                molefracs,
+                excess_enthalpy,
+            } => {
+                self.check_lengths(&[pressure, molefracs, excess_enthalpy])?;
+                Arc::new(BinaryExcessEnthalpy::new(
+                    array(excess_enthalpy) * (JOULE / MOL),
+                    array(temperature) * KELVIN,
+                    array(pressure) * PASCAL,
+                    array(molefracs),
+                ))
+            }
+        };
+        if self.weighting == Weighting::Uniform {
+            return Ok(data);
+        }
+        let uncertainty = self.uncertainty.as_ref().map(array);
+        Ok(Arc::new(WeightedDataSet::new(
+            data,
+            &self.weighting,
+            uncertainty.as_ref(),
+        )?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() -> Result<(), FeosError> {
+        let json = r#"[
+            {
+                "property": "vapor_pressure",
+                "temperature": [300.0, 350.0],
+                "pressure": [1e5, 5e5],
+                "uncertainty": [1e3, 2e3],
+                "weighting": "uncertainty"
+            },
+            {
+                "property": "density",
+                "temperature": [300.0],
+                "pressure": [1e5],
+                "density": [500.0],
+                "phase": "liquid",
+                "source": "test"
+            }
+        ]"#;
+        let data = ExperimentalData::from_reader(json.as_bytes())?;
+        assert_eq!(data.len(), 2);
+        assert_eq!(data[0].weighting, Weighting::Uncertainty);
+        assert_eq!(data[0].datapoints(), 2);
+        assert_eq!(data[1].weighting, Weighting::Uniform);
+        assert_eq!(
+            data[1].property,
+            ExperimentalProperty::Density {
+                temperature: vec![300.0],
+                pressure: vec![1e5],
+                density: vec![500.0],
+                phase: Some(Phase::Liquid)
+            }
+        );
+
+        let serialized = serde_json::to_string(&data)?;
+        let data2: Vec<ExperimentalData> = serde_json::from_str(&serialized)?;
+        assert_eq!(data, data2);
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use super::{DataSet, FeosError};
+use super::{DataSet, FeosError, map_datapoints};
 use feos_core::{
     DensityInitialization, Molarweight, PhaseEquilibrium, ReferenceSystem, Residual, SolverOptions,
     State,
@@ -63,19 +63,16 @@ impl<E: Residual + Molarweight> DataSet<E> for LiquidDensity {
 
     fn predict(&self, eos: &Arc<E>) -> Result<Array1<f64>, FeosError> {
         let moles = Moles::from_reduced(arr1(&[1.0]));
-        Ok(self
-            .temperature
-            .into_iter()
-            .zip(&self.pressure)
-            .map(|(t, p)| {
-                let state = State::new_npt(eos, t, p, &moles, DensityInitialization::Liquid);
-                if let Ok(s) = state {
-                    (s.mass_density() / self.unit).into_value()
-                } else {
-                    f64::NAN
-                }
-            })
-            .collect())
+        Ok(map_datapoints(self.target.len(), |i| {
+            let (t, p) = (self.temperature.get(i), self.pressure.get(i));
+            let state = State::new_npt(eos, t, p, &moles, DensityInitialization::Liquid);
+            if let Ok(s) = state {
+                (s.mass_density() / self.unit).into_value()
+            } else {
+                f64::NAN
+            }
+        })
+        .into())
     }
 
     // fn get_input(&self) -> HashMap<String, SIArray1> {
@@ -134,17 +131,15 @@ impl<E: Residual + Molarweight> DataSet<E> for EquilibriumLiquidDensity {
     }
 
     fn predict(&self, eos: &Arc<E>) -> Result<Array1<f64>, FeosError> {
-        Ok(self
-            .temperature
-            .into_iter()
-            .map(|t| {
-                if let Ok(state) = PhaseEquilibrium::pure(eos, t, None, self.solver_options) {
-                    (state.liquid().mass_density() / self.unit).into_value()
-                } else {
-                    f64::NAN
-                }
-            })
-            .collect())
+        Ok(map_datapoints(self.target.len(), |i| {
+            let t = self.temperature.get(i);
+            if let Ok(state) = PhaseEquilibrium::pure(eos, t, None, self.solver_options) {
+                (state.liquid().mass_density() / self.unit).into_value()
+            } else {
+                f64::NAN
+            }
+        })
+        .into())
     }
 
     // fn get_input(&self) -> HashMap<String, SIArray1> {
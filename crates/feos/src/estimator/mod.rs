@@ -1,20 +1,38 @@
 //! Utilities for working with experimental data.
 use feos_core::{DensityInitialization, FeosError};
+use serde::{Deserialize, Serialize};
 mod dataset;
 pub use dataset::DataSet;
+use dataset::map_datapoints;
 #[expect(clippy::module_inception)]
 mod estimator;
 pub use estimator::Estimator;
 mod loss;
 pub use loss::Loss;
+mod regression;
+pub use regression::{RegressionCheckpoint, RegressionResult};
+#[cfg(feature = "mcmc")]
+mod sampling;
+#[cfg(feature = "mcmc")]
+pub use sampling::{PosteriorSamples, SamplerOptions};
+mod weighting;
+pub use weighting::{WeightedDataSet, Weighting};
+mod experimental_data;
+pub use experimental_data::{ExperimentalData, ExperimentalProperty};
+mod pvt_report;
+pub use pvt_report::{PvtData, PvtExperiment, PvtMeasurement, PvtQuantity, PvtReport};
 
 // Properties
 mod vapor_pressure;
 pub use vapor_pressure::VaporPressure;
 mod liquid_density;
 pub use liquid_density::{EquilibriumLiquidDensity, LiquidDensity};
+mod density;
+pub use density::{Density, EquilibriumVaporDensity};
 mod binary_vle;
 pub use binary_vle::{BinaryPhaseDiagram, BinaryVleChemicalPotential, BinaryVlePressure};
+mod excess_enthalpy;
+pub use excess_enthalpy::BinaryExcessEnthalpy;
 mod viscosity;
 pub use viscosity::Viscosity;
 mod thermal_conductivity;
@@ -23,7 +41,8 @@ mod diffusion;
 pub use diffusion::Diffusion;
 
 /// Different phases of experimental data points.
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Phase {
     Vapor,
     Liquid,
@@ -0,0 +1,471 @@
+//! Laboratory PVT reports of reservoir fluids that are used as [`DataSet`]s
+//! with the PVT experiment simulators of `feos-core` as forward models.
+use super::{DataSet, FeosError};
+use feos_core::{
+    ConstantCompositionExpansion, ConstantVolumeDepletion, DifferentialLiberation, Residual,
+    SolverOptions, SwellingTest,
+};
+use ndarray::{Array1, s};
+use quantity::{KELVIN, MOL, PASCAL, Temperature};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::Arc;
+
+/// PVT experiment of a laboratory report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PvtExperiment {
+    /// Constant composition expansion (CCE)
+    ConstantCompositionExpansion,
+    /// Constant volume depletion (CVD)
+    ConstantVolumeDepletion,
+    /// Differential liberation (DL)
+    DifferentialLiberation,
+    /// Swelling test with the injection gas of the report
+    SwellingTest,
+}
+
+/// Quantity reported in a PVT experiment.
+///
+/// Pressures are given in Pa, all other quantities are dimensionless.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PvtQuantity {
+    /// Saturation pressure of the fluid (of the mixtures in a swelling test)
+    SaturationPressure,
+    /// Relative volume of a CCE
+    RelativeVolume,
+    /// Liquid volume relative to the saturation volume (CCE) or the cell volume (CVD)
+    LiquidVolume,
+    /// Compressibility factor of the total fluid (CCE) or two-phase
+    /// compressibility factor (CVD)
+    CompressibilityFactor,
+    /// Cumulative amount of produced gas of a CVD
+    CumulativeProduction,
+    /// Compressibility factor of the produced (CVD) or liberated (DL) gas
+    GasCompressibilityFactor,
+    /// Oil formation volume factor of a DL
+    OilFormationVolumeFactor,
+    /// Solution gas-oil ratio of a DL
+    SolutionGasOilRatio,
+    /// Swelling factor of a swelling test
+    SwellingFactor,
+}
+
+/// Values of a single quantity measured in a PVT experiment.
+///
+/// The values are given at the pressure steps (CCE, CVD and DL) or at the
+/// mole fractions of the injection gas (swelling test). The saturation
+/// pressure of a CCE, CVD or DL is a single value without inputs. The
+/// pressure steps of a CVD or DL have to contain all steps of the
+/// experiment, because the results depend on the depletion path.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PvtMeasurement {
+    /// Experiment in which the quantity was measured
+    pub experiment: PvtExperiment,
+    /// Measured quantity
+    pub quantity: PvtQuantity,
+    /// Pressure steps in Pa
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pressure: Vec<f64>,
+    /// Mole fractions of the injection gas in the mixture
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gas_fraction: Vec<f64>,
+    /// Measured values
+    pub value: Vec<f64>,
+}
+
+/// Laboratory PVT report of a reservoir fluid.
+///
+/// Reports are read from json files, e.g.
+/// ```json
+/// {
+///     "fluid": "gas condensate",
+///     "temperature": 350.0,
+///     "molefracs": [0.9, 0.07, 0.03],
+///     "measurements": [
+///         {
+///             "experiment": "constant_composition_expansion",
+///             "quantity": "saturation_pressure",
+///             "value": [2.43e7]
+///         },
+///         {
+///             "experiment": "constant_volume_depletion",
+///             "quantity": "liquid_volume",
+///             "pressure": [2.0e7, 1.5e7, 1.0e7],
+///             "value": [0.045, 0.064, 0.061]
+///         }
+///     ]
+/// }
+/// ```
+/// or from csv files with the columns `experiment`, `quantity`,
+/// `pressure`, `gas_fraction` and `value` (see [`PvtReport::from_csv_str`]).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PvtReport {
+    /// Name of the fluid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fluid: Option<String>,
+    /// Temperature of the experiments in K
+    pub temperature: f64,
+    /// Mole fractions of the fluid
+    pub molefracs: Vec<f64>,
+    /// Mole fractions of the injection gas of a swelling test
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub injection_gas: Option<Vec<f64>>,
+    /// Measured quantities
+    pub measurements: Vec<PvtMeasurement>,
+    /// Literature source of the data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl PvtReport {
+    /// Read a report from a json file.
+    pub fn from_json<P: AsRef<Path>>(file: P) -> Result<Self, FeosError> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(file)?))?)
+    }
+
+    /// Read the measurements of a report from a csv file.
+    pub fn from_csv<P: AsRef<Path>>(
+        file: P,
+        temperature: f64,
+        molefracs: Vec<f64>,
+        injection_gas: Option<Vec<f64>>,
+    ) -> Result<Self, FeosError> {
+        let mut csv = String::new();
+        BufReader::new(File::open(file)?).read_to_string(&mut csv)?;
+        Self::from_csv_str(&csv, temperature, molefracs, injection_gas)
+    }
+
+    /// Read the measurements of a report from a string in csv format.
+    ///
+    /// The header contains the columns `experiment`, `quantity`, `value`
+    /// and, if required by the measurements, `pressure` and `gas_fraction`.
+    /// Every row contains a single value. Rows with the same experiment and
+    /// quantity are combined into one measurement in the order of the file.
+    pub fn from_csv_str(
+        csv: &str,
+        temperature: f64,
+        molefracs: Vec<f64>,
+        injection_gas: Option<Vec<f64>>,
+    ) -> Result<Self, FeosError> {
+        let error = |msg: String| FeosError::Error(format!("Invalid PVT report: {msg}"));
+        let mut lines = csv.lines().filter(|l| !l.trim().is_empty());
+        let header: Vec<_> = lines
+            .next()
+            .ok_or_else(|| error("empty file.".into()))?
+            .split(',')
+            .map(|c| c.trim().to_lowercase())
+            .collect();
+        let column = |name: &str| header.iter().position(|c| c == name);
+        let required =
+            |name: &str| column(name).ok_or_else(|| error(format!("missing column '{name}'.")));
+        let (experiment, quantity, value) = (
+            required("experiment")?,
+            required("quantity")?,
+            required("value")?,
+        );
+        let (pressure, gas_fraction) = (column("pressure"), column("gas_fraction"));
+
+        let mut measurements: Vec<PvtMeasurement> = Vec::new();
+        for (row, line) in lines.enumerate() {
+            let cells: Vec<_> = line.split(',').map(str::trim).collect();
+            let cell = |i: usize| cells.get(i).copied().unwrap_or_default();
+            let number = |i: usize| {
+                cell(i)
+                    .parse::<f64>()
+                    .map_err(|_| error(format!("invalid number '{}' in row {}.", cell(i), row + 1)))
+            };
+            let name = |i: usize| Value::String(cell(i).to_lowercase());
+            let experiment: PvtExperiment = serde_json::from_value(name(experiment))?;
+            let quantity: PvtQuantity = serde_json::from_value(name(quantity))?;
+            let index = match measurements
+                .iter()
+                .position(|m| m.experiment == experiment && m.quantity == quantity)
+            {
+                Some(index) => index,
+                None => {
+                    measurements.push(PvtMeasurement {
+                        experiment,
+                        quantity,
+                        pressure: Vec::new(),
+                        gas_fraction: Vec::new(),
+                        value: Vec::new(),
+                    });
+                    measurements.len() - 1
+                }
+            };
+            let measurement = &mut measurements[index];
+            if let Some(i) = pressure
+                && !cell(i).is_empty()
+            {
+                measurement.pressure.push(number(i)?);
+            }
+            if let Some(i) = gas_fraction
+                && !cell(i).is_empty()
+            {
+                measurement.gas_fraction.push(number(i)?);
+            }
+            measurement.value.push(number(value)?);
+        }
+        Ok(Self {
+            fluid: None,
+            temperature,
+            molefracs,
+            injection_gas,
+            measurements,
+            source: None,
+        })
+    }
+
+    /// Convert the measurements into [`DataSet`]s that can be used in an
+    /// [`Estimator`](super::Estimator).
+    pub fn to_datasets<E: Residual + 'static>(
+        &self,
+        solver_options: Option<SolverOptions>,
+    ) -> Result<Vec<Arc<dyn DataSet<E>>>, FeosError> {
+        self.measurements
+            .iter()
+            .map(|measurement| {
+                let data = PvtData::new(self, measurement, solver_options)?;
+                Ok(Arc::new(data) as Arc<dyn DataSet<E>>)
+            })
+            .collect()
+    }
+}
+
+/// Store a quantity measured in a PVT experiment.
+///
+/// The experiment is simulated with the corresponding simulator of
+/// `feos-core` to predict the quantity.
+#[derive(Clone)]
+pub struct PvtData {
+    pub target: Array1<f64>,
+    temperature: Temperature,
+    molefracs: Array1<f64>,
+    injection_gas: Option<Array1<f64>>,
+    experiment: PvtExperiment,
+    quantity: PvtQuantity,
+    input: Array1<f64>,
+    solver_options: SolverOptions,
+}
+
+impl PvtData {
+    /// Create a new data set for a quantity measured in a PVT experiment
+    /// that is part of `report`.
+    pub fn new(
+        report: &PvtReport,
+        measurement: &PvtMeasurement,
+        solver_options: Option<SolverOptions>,
+    ) -> Result<Self, FeosError> {
+        use PvtExperiment::*;
+        use PvtQuantity::*;
+        let PvtMeasurement {
+            experiment,
+            quantity,
+            ..
+        } = *measurement;
+        let valid = matches!(
+            (experiment, quantity),
+            (_, SaturationPressure)
+                | (
+                    ConstantCompositionExpansion,
+                    RelativeVolume | LiquidVolume | CompressibilityFactor
+                )
+                | (
+                    ConstantVolumeDepletion,
+                    LiquidVolume
+                        | CompressibilityFactor
+                        | CumulativeProduction
+                        | GasCompressibilityFactor
+                )
+                | (
+                    DifferentialLiberation,
+                    OilFormationVolumeFactor | SolutionGasOilRatio | GasCompressibilityFactor
+                )
+                | (SwellingTest, SwellingFactor)
+        );
+        if !valid {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "{quantity:?} is not reported in a {experiment:?} experiment."
+            )));
+        }
+        let input = match (experiment, quantity) {
+            (SwellingTest, _) => &measurement.gas_fraction,
+            (_, SaturationPressure) => &Vec::new(),
+            _ => &measurement.pressure,
+        };
+        let n = measurement.value.len();
+        let expected = if input.is_empty() && quantity == SaturationPressure {
+            1
+        } else {
+            input.len()
+        };
+        if n != expected {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "inconsistent number of data points ({expected} and {n})."
+            )));
+        }
+        if experiment == SwellingTest && report.injection_gas.is_none() {
+            return Err(FeosError::IncompatibleParameters(
+                "a swelling test requires the composition of the injection gas.".into(),
+            ));
+        }
+        Ok(Self {
+            target: Array1::from_vec(measurement.value.clone()),
+            temperature: report.temperature * KELVIN,
+            molefracs: Array1::from_vec(report.molefracs.clone()),
+            injection_gas: report.injection_gas.clone().map(Array1::from_vec),
+            experiment,
+            quantity,
+            input: Array1::from_vec(input.clone()),
+            solver_options: solver_options.unwrap_or_default(),
+        })
+    }
+}
+
+impl<E: Residual> DataSet<E> for PvtData {
+    fn target(&self) -> &Array1<f64> {
+        &self.target
+    }
+
+    fn target_str(&self) -> &str {
+        match self.quantity {
+            PvtQuantity::SaturationPressure => "saturation pressure",
+            PvtQuantity::RelativeVolume => "relative volume",
+            PvtQuantity::LiquidVolume => "liquid volume",
+            PvtQuantity::CompressibilityFactor => "compressibility factor",
+            PvtQuantity::CumulativeProduction => "cumulative production",
+            PvtQuantity::GasCompressibilityFactor => "gas compressibility factor",
+            PvtQuantity::OilFormationVolumeFactor => "oil formation volume factor",
+            PvtQuantity::SolutionGasOilRatio => "solution gas-oil ratio",
+            PvtQuantity::SwellingFactor => "swelling factor",
+        }
+    }
+
+    fn input_str(&self) -> Vec<&str> {
+        match (self.experiment, self.input.is_empty()) {
+            (PvtExperiment::SwellingTest, _) => vec!["temperature", "gas fraction"],
+            (_, true) => vec!["temperature"],
+            (_, false) => vec!["temperature", "pressure"],
+        }
+    }
+
+    fn predict(&self, eos: &Arc<E>) -> Result<Array1<f64>, FeosError> {
+        let t = self.temperature;
+        let moles = &self.molefracs * MOL;
+        let pressure = &self.input * PASCAL;
+        let options = self.solver_options;
+        // CVD and DL results start with the saturation point
+        let steps = |x: Array1<f64>| x.slice(s![1..]).to_owned();
+        Ok(match (self.experiment, self.quantity) {
+            (PvtExperiment::SwellingTest, quantity) => {
+                let gas = self.injection_gas.as_ref().unwrap() * MOL;
+                let swelling = SwellingTest::new(eos, t, &moles, &gas, &self.input, options)?;
+                match quantity {
+                    PvtQuantity::SwellingFactor => swelling.swelling_factor,
+                    _ => swelling.saturation_pressure.convert_into(PASCAL),
+                }
+            }
+            (PvtExperiment::ConstantVolumeDepletion, quantity)
+                if quantity != PvtQuantity::SaturationPressure =>
+            {
+                let cvd = ConstantVolumeDepletion::new(eos, t, &moles, &pressure, options)?;
+                steps(match quantity {
+                    PvtQuantity::LiquidVolume => cvd.liquid_volume,
+                    PvtQuantity::CompressibilityFactor => cvd.two_phase_compressibility_factor,
+                    PvtQuantity::CumulativeProduction => cvd.cumulative_production,
+                    _ => cvd.gas_compressibility_factor,
+                })
+            }
+            (PvtExperiment::DifferentialLiberation, quantity)
+                if quantity != PvtQuantity::SaturationPressure =>
+            {
+                let dl = DifferentialLiberation::new(eos, t, &moles, &pressure, options)?;
+                steps(match quantity {
+                    PvtQuantity::OilFormationVolumeFactor => dl.oil_formation_volume_factor,
+                    PvtQuantity::SolutionGasOilRatio => dl.solution_gas_oil_ratio,
+                    _ => dl.gas_compressibility_factor,
+                })
+            }
+            (_, quantity) => {
+                let cce = ConstantCompositionExpansion::new(eos, t, &moles, &pressure, options)?;
+                match quantity {
+                    PvtQuantity::RelativeVolume => cce.relative_volume,
+                    PvtQuantity::LiquidVolume => cce.liquid_volume,
+                    PvtQuantity::CompressibilityFactor => cce.compressibility_factor,
+                    _ => Array1::from_elem(1, cce.saturation_pressure.convert_into(PASCAL)),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_report() -> Result<(), FeosError> {
+        let csv = "experiment,quantity,pressure,gas_fraction,value
+            constant_composition_expansion,saturation_pressure,,,2.4e7
+            constant_composition_expansion,relative_volume,3e7,,0.95
+            constant_volume_depletion,liquid_volume,2e7,,0.04
+            constant_composition_expansion,relative_volume,2e7,,1.1
+            constant_volume_depletion,liquid_volume,1e7,,0.06
+            swelling_test,swelling_factor,,0.1,1.05";
+        let report = PvtReport::from_csv_str(csv, 350.0, vec![0.9, 0.1], Some(vec![1.0, 0.0]))?;
+        assert_eq!(report.measurements.len(), 4);
+        let cce = &report.measurements[1];
+        assert_eq!(cce.quantity, PvtQuantity::RelativeVolume);
+        assert_eq!(cce.pressure, vec![3e7, 2e7]);
+        assert_eq!(cce.value, vec![0.95, 1.1]);
+        assert_eq!(report.measurements[3].gas_fraction, vec![0.1]);
+        assert!(report.measurements[0].pressure.is_empty());
+
+        // json round trip
+        let json = serde_json::to_string(&report)?;
+        assert!(!json.contains("gas_fraction\":[]"));
+        let report2: PvtReport = serde_json::from_str(&json)?;
+        assert_eq!(report, report2);
+        assert!(PvtReport::from_csv_str("experiment,value\n", 350.0, vec![1.0], None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "cubic")]
+    fn datasets() -> Result<(), FeosError> {
+        use crate::cubic::Cubic;
+        let json = r#"{
+            "temperature": 350.0,
+            "molefracs": [0.9, 0.1],
+            "injection_gas": [1.0, 0.0],
+            "measurements": [
+                {"experiment": "constant_composition_expansion", "quantity": "saturation_pressure", "value": [2.4e7]},
+                {"experiment": "constant_composition_expansion", "quantity": "relative_volume", "pressure": [3e7, 2e7], "value": [0.95, 1.1]},
+                {"experiment": "constant_volume_depletion", "quantity": "liquid_volume", "pressure": [2e7, 1e7], "value": [0.04, 0.06]},
+                {"experiment": "swelling_test", "quantity": "swelling_factor", "gas_fraction": [0.1], "value": [1.05]}
+            ]
+        }"#;
+        let report: PvtReport = serde_json::from_str(json)?;
+        let datasets = report.to_datasets::<Cubic>(None)?;
+        assert_eq!(datasets.len(), 4);
+        assert_eq!(datasets[2].target_str(), "liquid volume");
+        assert_eq!(datasets[2].input_str(), vec!["temperature", "pressure"]);
+
+        // invalid measurements
+        let mut invalid = report.clone();
+        invalid.measurements[1].quantity = PvtQuantity::SwellingFactor;
+        assert!(invalid.to_datasets::<Cubic>(None).is_err());
+        invalid.measurements[1].quantity = PvtQuantity::RelativeVolume;
+        invalid.measurements[1].value.pop();
+        assert!(invalid.to_datasets::<Cubic>(None).is_err());
+        let mut invalid = report;
+        invalid.injection_gas = None;
+        assert!(invalid.to_datasets::<Cubic>(None).is_err());
+        Ok(())
+    }
+}
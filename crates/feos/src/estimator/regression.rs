@@ -0,0 +1,317 @@
+//! Levenberg-Marquardt regression of model parameters to the [`DataSet`]s
+//! stored in an [`Estimator`].
+use super::{Estimator, FeosError};
+use feos_core::{Residual, SolverOptions, Verbosity, check_cancelled, log_iter, log_result};
+use ndarray::{Array1, Array2, Axis};
+use num_dual::linalg::LU;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::sync::Arc;
+
+const MAX_ITER_REGRESSION: usize = 100;
+const TOL_REGRESSION: f64 = 1e-8;
+const RELATIVE_STEP: f64 = 1e-6;
+
+/// Result of a parameter regression.
+#[derive(Clone, Debug)]
+pub struct RegressionResult {
+    /// Optimal parameters.
+    pub parameters: Array1<f64>,
+    /// Cost of all data points at the optimum.
+    pub residuals: Array1<f64>,
+    /// Mean absolute relative difference of each `DataSet` at the optimum.
+    pub mean_absolute_relative_difference: Array1<f64>,
+    /// Number of iterations.
+    pub iterations: usize,
+    /// Covariance matrix of the parameters, estimated from the Jacobian at
+    /// the optimum. Contains `NAN` if the parameters are not identifiable.
+    pub covariance: Array2<f64>,
+}
+
+impl RegressionResult {
+    /// Sum of squared residuals at the optimum.
+    pub fn sum_of_squares(&self) -> f64 {
+        self.residuals.mapv(|r| r * r).sum()
+    }
+
+    /// Standard errors of the parameters.
+    pub fn standard_errors(&self) -> Array1<f64> {
+        self.covariance.diag().mapv(f64::sqrt)
+    }
+
+    /// Correlation matrix of the parameters.
+    pub fn correlation(&self) -> Array2<f64> {
+        let s = self.standard_errors();
+        Array2::from_shape_fn(self.covariance.dim(), |(i, j)| {
+            self.covariance[[i, j]] / (s[i] * s[j])
+        })
+    }
+
+    /// Propagate the uncertainty of the parameters to a derived prediction.
+    ///
+    /// `prediction` evaluates an arbitrary property (e.g. the pressures
+    /// along a bubble point curve) for a given parameter vector. The
+    /// covariance of the parameters is propagated by linearization with
+    /// the sensitivities of the prediction evaluated using central
+    /// differences. Returns the prediction at the optimum and its standard
+    /// deviation. Confidence bands are obtained by multiplying the standard
+    /// deviation with the quantile of the desired confidence level.
+    pub fn propagate_uncertainty<F>(
+        &self,
+        prediction: F,
+    ) -> Result<(Array1<f64>, Array1<f64>), FeosError>
+    where
+        F: Fn(&Array1<f64>) -> Result<Array1<f64>, FeosError>,
+    {
+        let value = prediction(&self.parameters)?;
+        let mut sensitivities = Array2::zeros((value.len(), self.parameters.len()));
+        for (j, mut column) in sensitivities.axis_iter_mut(Axis(1)).enumerate() {
+            let h = RELATIVE_STEP * self.parameters[j].abs().max(1.0);
+            let mut p = self.parameters.clone();
+            p[j] += h;
+            let forward = prediction(&p)?;
+            p[j] -= 2.0 * h;
+            let backward = prediction(&p)?;
+            column.assign(&((forward - backward) / (2.0 * h)));
+        }
+        let variance = (sensitivities.dot(&self.covariance) * &sensitivities).sum_axis(Axis(1));
+        Ok((value, variance.mapv(|v| v.max(0.0).sqrt())))
+    }
+}
+
+/// State of the Levenberg-Marquardt algorithm after a completed iteration.
+///
+/// Checkpoints are serializable, so that long regressions can be resumed
+/// after an interruption using [`Estimator::fit_from_checkpoint`] and the
+/// best parameters found so far can be inspected during the regression.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RegressionCheckpoint {
+    /// Best parameters found so far.
+    pub parameters: Array1<f64>,
+    /// Sum of squared residuals of the parameters.
+    pub sum_of_squares: f64,
+    /// Damping parameter of the next iteration.
+    pub lambda: f64,
+    /// Number of completed iterations.
+    pub iterations: usize,
+}
+
+impl RegressionCheckpoint {
+    /// Read a checkpoint from a json file.
+    pub fn from_json<P: AsRef<Path>>(file: P) -> Result<Self, FeosError> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(file)?))?)
+    }
+
+    /// Write the checkpoint to a json file.
+    pub fn to_json<P: AsRef<Path>>(&self, file: P) -> Result<(), FeosError> {
+        Ok(serde_json::to_writer(
+            BufWriter::new(File::create(file)?),
+            self,
+        )?)
+    }
+}
+
+impl<E: Residual> Estimator<E> {
+    /// Fit model parameters to the stored `DataSet`s.
+    ///
+    /// The equation of state is created from the parameter vector using the
+    /// `model` closure. The sum of squares of the [`cost`](Estimator::cost)
+    /// is minimized with a Levenberg-Marquardt algorithm. Derivatives with
+    /// respect to the parameters are evaluated using forward differences.
+    ///
+    /// The regression is aborted with [`FeosError::Cancelled`] if the
+    /// [`CancellationToken`](feos_core::CancellationToken) installed for the
+    /// current thread is cancelled.
+    pub fn fit<F>(
+        &self,
+        model: F,
+        initial_parameters: &Array1<f64>,
+        options: SolverOptions,
+    ) -> Result<RegressionResult, FeosError>
+    where
+        F: Fn(&Array1<f64>) -> Result<Arc<E>, FeosError>,
+    {
+        self.fit_with_checkpoints(model, initial_parameters, options, |_| Ok(()))
+    }
+
+    /// Fit model parameters to the stored `DataSet`s and pass a
+    /// [`RegressionCheckpoint`] to `checkpoint` after every iteration.
+    ///
+    /// The callback can be used to store the state of the regression,
+    /// e.g., using [`RegressionCheckpoint::to_json`], or to monitor the
+    /// parameters. An error returned by the callback aborts the regression.
+    pub fn fit_with_checkpoints<F, C>(
+        &self,
+        model: F,
+        initial_parameters: &Array1<f64>,
+        options: SolverOptions,
+        checkpoint: C,
+    ) -> Result<RegressionResult, FeosError>
+    where
+        F: Fn(&Array1<f64>) -> Result<Arc<E>, FeosError>,
+        C: FnMut(&RegressionCheckpoint) -> Result<(), FeosError>,
+    {
+        self.levenberg_marquardt(
+            model,
+            initial_parameters.clone(),
+            1e-3,
+            0,
+            options,
+            checkpoint,
+        )
+    }
+
+    /// Resume a regression from a [`RegressionCheckpoint`].
+    ///
+    /// The maximum number of iterations in `options` includes the iterations
+    /// that were completed before the checkpoint was created. The residuals
+    /// are reevaluated at the parameters of the checkpoint, so the `model` and
+    /// the `DataSet`s have to be the same as in the interrupted regression to
+    /// obtain identical results.
+    pub fn fit_from_checkpoint<F, C>(
+        &self,
+        model: F,
+        checkpoint: &RegressionCheckpoint,
+        options: SolverOptions,
+        callback: C,
+    ) -> Result<RegressionResult, FeosError>
+    where
+        F: Fn(&Array1<f64>) -> Result<Arc<E>, FeosError>,
+        C: FnMut(&RegressionCheckpoint) -> Result<(), FeosError>,
+    {
+        self.levenberg_marquardt(
+            model,
+            checkpoint.parameters.clone(),
+            checkpoint.lambda,
+            checkpoint.iterations,
+            options,
+            callback,
+        )
+    }
+
+    fn levenberg_marquardt<F, C>(
+        &self,
+        model: F,
+        mut parameters: Array1<f64>,
+        mut lambda: f64,
+        iterations: usize,
+        options: SolverOptions,
+        mut checkpoint: C,
+    ) -> Result<RegressionResult, FeosError>
+    where
+        F: Fn(&Array1<f64>) -> Result<Arc<E>, FeosError>,
+        C: FnMut(&RegressionCheckpoint) -> Result<(), FeosError>,
+    {
+        let (max_iter, tol, verbosity) = options.unwrap_or(MAX_ITER_REGRESSION, TOL_REGRESSION);
+        let residuals = |p: &Array1<f64>| -> Result<Array1<f64>, FeosError> {
+            check_cancelled()?;
+            let cost = self.cost(&model(p)?)?;
+            if cost.iter().all(|c| c.is_finite()) {
+                Ok(cost)
+            } else {
+                Err(FeosError::IterationFailed("regression".into()))
+            }
+        };
+
+        let jacobian = |p: &Array1<f64>, res: &Array1<f64>| -> Result<Array2<f64>, FeosError> {
+            // Jacobian using forward differences
+            let mut jacobian = Array2::zeros((res.len(), p.len()));
+            for (j, mut column) in jacobian.axis_iter_mut(Axis(1)).enumerate() {
+                let h = RELATIVE_STEP * p[j].abs().max(1.0);
+                let mut p = p.clone();
+                p[j] += h;
+                column.assign(&((residuals(&p)? - res) / h));
+            }
+            Ok(jacobian)
+        };
+
+        let mut res = residuals(&parameters)?;
+        let mut ssq = res.mapv(|r| r * r).sum();
+
+        log_iter!(
+            verbosity,
+            " iter | sum of squares |    lambda    | parameters"
+        );
+        log_iter!(verbosity, "{:-<60}", "");
+        log_iter!(
+            verbosity,
+            " {:4} | {:14.8e} |              | {:.8}",
+            iterations,
+            ssq,
+            parameters
+        );
+
+        for i in iterations + 1..=max_iter {
+            let jac = jacobian(&parameters, &res)?;
+            let jtj = jac.t().dot(&jac);
+            let gradient = jac.t().dot(&res);
+
+            // adapt the damping until the sum of squares decreases
+            let mut accepted = false;
+            let mut step_norm = 0.0;
+            while lambda < 1e10 {
+                check_cancelled()?;
+                let mut a = jtj.clone();
+                for k in 0..parameters.len() {
+                    a[[k, k]] += lambda * jtj[[k, k]].max(1e-12);
+                }
+                let step = LU::new(a)?.solve(&-&gradient);
+                let trial = &parameters + &step;
+                if let Ok(trial_res) = residuals(&trial) {
+                    let trial_ssq = trial_res.mapv(|r| r * r).sum();
+                    if trial_ssq < ssq {
+                        step_norm = step.mapv(|s| s * s).sum().sqrt();
+                        parameters = trial;
+                        res = trial_res;
+                        ssq = trial_ssq;
+                        lambda = (lambda * 0.1).max(1e-12);
+                        accepted = true;
+                        break;
+                    }
+                }
+                lambda *= 10.0;
+            }
+
+            log_iter!(
+                verbosity,
+                " {:4} | {:14.8e} | {:12.4e} | {:.8}",
+                i,
+                ssq,
+                lambda,
+                parameters
+            );
+            checkpoint(&RegressionCheckpoint {
+                parameters: parameters.clone(),
+                sum_of_squares: ssq,
+                lambda,
+                iterations: i,
+            })?;
+
+            let parameter_norm = parameters.mapv(|p| p * p).sum().sqrt();
+            if !accepted || step_norm < tol * (parameter_norm + tol) {
+                log_result!(verbosity, "Regression converged in {} step(s)\n", i);
+
+                // covariance from the Gauss-Newton approximation of the Hessian
+                let jac = jacobian(&parameters, &res)?;
+                let dof = res.len().saturating_sub(parameters.len()).max(1);
+                let variance = ssq / dof as f64;
+                let n = parameters.len();
+                let covariance = LU::new(jac.t().dot(&jac))
+                    .map_or(Array2::from_elem((n, n), f64::NAN), |lu| {
+                        lu.inverse() * variance
+                    });
+                return Ok(RegressionResult {
+                    mean_absolute_relative_difference: self
+                        .mean_absolute_relative_difference(&model(&parameters)?)?,
+                    parameters,
+                    residuals: res,
+                    iterations: i,
+                    covariance,
+                });
+            }
+        }
+        Err(FeosError::NotConverged("regression".into()))
+    }
+}
@@ -0,0 +1,240 @@
+//! Bayesian parameter estimation using the affine-invariant ensemble sampler
+//! of [Goodman and Weare (2010)](https://doi.org/10.2140/camcos.2010.5.65).
+use super::{Estimator, FeosError, RegressionResult};
+use feos_core::{Residual, Verbosity, log_iter, log_result};
+use ndarray::{Array1, Array2, Axis};
+use std::sync::Arc;
+
+/// Options for the ensemble sampler.
+#[derive(Clone, Copy)]
+pub struct SamplerOptions {
+    /// Number of walkers, has to exceed twice the number of parameters.
+    pub walkers: usize,
+    /// Number of steps per walker.
+    pub steps: usize,
+    /// Number of initial steps that are discarded.
+    pub burn_in: usize,
+    /// Scale parameter of the stretch move.
+    pub stretch: f64,
+    /// Seed of the random number generator.
+    pub seed: u64,
+    /// Verbosity of the output.
+    pub verbosity: Verbosity,
+}
+
+impl Default for SamplerOptions {
+    fn default() -> Self {
+        Self {
+            walkers: 16,
+            steps: 1000,
+            burn_in: 200,
+            stretch: 2.0,
+            seed: 42,
+            verbosity: Verbosity::default(),
+        }
+    }
+}
+
+/// Samples of the posterior distribution of the parameters.
+#[derive(Clone, Debug)]
+pub struct PosteriorSamples {
+    /// Parameter samples of all walkers after the burn-in (one row per sample).
+    pub samples: Array2<f64>,
+    /// Logarithm of the posterior probability of each sample.
+    pub log_probability: Array1<f64>,
+    /// Fraction of accepted proposals.
+    pub acceptance_fraction: f64,
+}
+
+impl PosteriorSamples {
+    /// Posterior mean of the parameters.
+    pub fn mean(&self) -> Array1<f64> {
+        self.samples.mean_axis(Axis(0)).unwrap()
+    }
+
+    /// Posterior standard deviation of the parameters.
+    pub fn standard_deviation(&self) -> Array1<f64> {
+        self.samples.std_axis(Axis(0), 1.0)
+    }
+
+    /// Quantile `q` (between 0 and 1) of the marginal posterior of each parameter.
+    pub fn quantile(&self, q: f64) -> Array1<f64> {
+        self.samples
+            .axis_iter(Axis(1))
+            .map(|s| {
+                let mut s = s.to_vec();
+                s.sort_by(|a, b| a.total_cmp(b));
+                let i = (q.clamp(0.0, 1.0) * (s.len() - 1) as f64).round() as usize;
+                s[i]
+            })
+            .collect()
+    }
+}
+
+impl<E: Residual> Estimator<E> {
+    /// Sample the posterior distribution of model parameters.
+    ///
+    /// The likelihood is a Gaussian in the [`cost`](Estimator::cost) whose
+    /// variance is estimated from the residuals of the least-squares
+    /// solution in `regression`, which is also used to initialize the
+    /// walkers. The prior is uniform within `bounds` (one pair of lower and
+    /// upper bounds per parameter).
+    pub fn sample<F>(
+        &self,
+        model: F,
+        regression: &RegressionResult,
+        bounds: &[(f64, f64)],
+        options: SamplerOptions,
+    ) -> Result<PosteriorSamples, FeosError>
+    where
+        F: Fn(&Array1<f64>) -> Result<Arc<E>, FeosError>,
+    {
+        let n = regression.parameters.len();
+        let SamplerOptions {
+            walkers,
+            steps,
+            burn_in,
+            stretch,
+            seed,
+            verbosity,
+        } = options;
+        if bounds.len() != n || walkers < 2 * n + 1 || burn_in >= steps || stretch <= 1.0 {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "invalid sampler options for {n} parameters: {walkers} walkers, {steps} steps, {burn_in} burn-in steps and a stretch of {stretch}."
+            )));
+        }
+
+        let dof = regression.residuals.len().saturating_sub(n).max(1);
+        let variance = regression.sum_of_squares() / dof as f64;
+        let log_probability = |p: &Array1<f64>| {
+            if p.iter().zip(bounds).any(|(x, (lb, ub))| x < lb || x > ub) {
+                return f64::NEG_INFINITY;
+            }
+            match model(p).and_then(|eos| self.cost(&eos)) {
+                Ok(cost) if cost.iter().all(|c| c.is_finite()) => {
+                    -0.5 * cost.mapv(|c| c * c).sum() / variance
+                }
+                _ => f64::NEG_INFINITY,
+            }
+        };
+
+        // initialize the walkers around the least-squares solution
+        let mut rng = Xoshiro256::new(seed);
+        let scale = regression
+            .standard_errors()
+            .mapv(|s| if s.is_finite() && s > 0.0 { s } else { 1e-4 });
+        let mut positions = Array2::zeros((walkers, n));
+        let mut log_p = Array1::zeros(walkers);
+        for (mut position, lp) in positions.outer_iter_mut().zip(log_p.iter_mut()) {
+            for _ in 0..100 {
+                let p = Array1::from_shape_fn(n, |i| {
+                    regression.parameters[i] + 0.1 * scale[i] * rng.normal()
+                });
+                *lp = log_probability(&p);
+                position.assign(&p);
+                if lp.is_finite() {
+                    break;
+                }
+            }
+        }
+        if !log_p.iter().all(|lp| lp.is_finite()) {
+            return Err(FeosError::IterationFailed("sampler".into()));
+        }
+
+        let mut samples = Array2::zeros(((steps - burn_in) * walkers, n));
+        let mut sample_log_p = Array1::zeros((steps - burn_in) * walkers);
+        let mut accepted = 0;
+        log_iter!(verbosity, " step | acceptance | mean log(p)");
+        log_iter!(verbosity, "{:-<38}", "");
+        for step in 0..steps {
+            for k in 0..walkers {
+                // stretch move using a random partner walker
+                let mut j = rng.index(walkers - 1);
+                if j >= k {
+                    j += 1;
+                }
+                let z = ((stretch - 1.0) * rng.uniform() + 1.0).powi(2) / stretch;
+                let proposal = &positions.row(j) + &((&positions.row(k) - &positions.row(j)) * z);
+                let lp = log_probability(&proposal);
+                let log_ratio = (n as f64 - 1.0) * z.ln() + lp - log_p[k];
+                if rng.uniform().ln() < log_ratio {
+                    positions.row_mut(k).assign(&proposal);
+                    log_p[k] = lp;
+                    accepted += 1;
+                }
+            }
+            if step >= burn_in {
+                let offset = (step - burn_in) * walkers;
+                for k in 0..walkers {
+                    samples.row_mut(offset + k).assign(&positions.row(k));
+                    sample_log_p[offset + k] = log_p[k];
+                }
+            }
+            log_iter!(
+                verbosity,
+                " {:4} | {:10.4} | {:12.4e}",
+                step + 1,
+                accepted as f64 / ((step + 1) * walkers) as f64,
+                log_p.mean().unwrap()
+            );
+        }
+        let acceptance_fraction = accepted as f64 / (steps * walkers) as f64;
+        log_result!(
+            verbosity,
+            "Sampling finished with an acceptance fraction of {:.4}\n",
+            acceptance_fraction
+        );
+        Ok(PosteriorSamples {
+            samples,
+            log_probability: sample_log_p,
+            acceptance_fraction,
+        })
+    }
+}
+
+/// Xoshiro256** pseudo random number generator.
+struct Xoshiro256([u64; 4]);
+
+impl Xoshiro256 {
+    fn new(seed: u64) -> Self {
+        // initialize the state using SplitMix64
+        let mut z = seed;
+        let mut state = [0; 4];
+        for s in state.iter_mut() {
+            z = z.wrapping_add(0x9e3779b97f4a7c15);
+            let mut x = z;
+            x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+            *s = x ^ (x >> 31);
+        }
+        Self(state)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let s = &mut self.0;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// Uniformly distributed number in (0, 1].
+    fn uniform(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniformly distributed index in 0..n.
+    fn index(&mut self, n: usize) -> usize {
+        ((self.uniform() * n as f64) as usize).min(n - 1)
+    }
+
+    /// Standard normal distributed number (Box-Muller transform).
+    fn normal(&mut self) -> f64 {
+        (-2.0 * self.uniform().ln()).sqrt() * (2.0 * std::f64::consts::PI * self.uniform()).cos()
+    }
+}
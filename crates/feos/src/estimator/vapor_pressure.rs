@@ -1,4 +1,4 @@
-use super::{DataSet, FeosError};
+use super::{DataSet, FeosError, map_datapoints};
 use feos_core::{Contributions, PhaseEquilibrium, ReferenceSystem, Residual, SolverOptions, State};
 use ndarray::{arr1, Array1};
 use quantity::{Pressure, Temperature, PASCAL};
@@ -85,18 +85,17 @@ impl<E: Residual> DataSet<E> for VaporPressure {
         let b = (pc / p0).into_value().ln() / (1.0 / tc - 1.0 / t0);
         let a = pc.to_reduced().ln() - (b / tc).into_value();
 
-        Ok((0..self.datapoints)
-            .map(|i| {
-                let t = self.temperature.get(i);
-                if let Some(p) = PhaseEquilibrium::vapor_pressure(eos, t)[0] {
-                    (p / self.unit).into_value()
-                } else if self.extrapolate {
-                    (a + (b / t).into_value()).exp() / self.unit.to_reduced()
-                } else {
-                    f64::NAN
-                }
-            })
-            .collect())
+        Ok(map_datapoints(self.datapoints, |i| {
+            let t = self.temperature.get(i);
+            if let Some(p) = PhaseEquilibrium::vapor_pressure(eos, t)[0] {
+                (p / self.unit).into_value()
+            } else if self.extrapolate {
+                (a + (b / t).into_value()).exp() / self.unit.to_reduced()
+            } else {
+                f64::NAN
+            }
+        })
+        .into())
     }
 
     // fn get_input(&self) -> HashMap<String, SIArray1> {
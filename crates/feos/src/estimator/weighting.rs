@@ -0,0 +1,138 @@
+//! Per-point weighting of the residuals of a [`DataSet`].
+use super::{DataSet, FeosError, Loss};
+use feos_core::Residual;
+use ndarray::Array1;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Scheme used to weight the individual data points of a [`DataSet`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Weighting {
+    /// All data points have the same weight.
+    #[default]
+    Uniform,
+    /// Weights are inversely proportional to the relative uncertainty of
+    /// each data point.
+    Uncertainty,
+    /// User defined weights for each data point.
+    Custom(Vec<f64>),
+}
+
+impl Weighting {
+    /// Calculate the weights of the data points, normalized to a mean of 1.
+    ///
+    /// `uncertainty` is the absolute uncertainty of each data point in the
+    /// same units as `target`. It is required for [`Weighting::Uncertainty`].
+    pub fn weights(
+        &self,
+        target: &Array1<f64>,
+        uncertainty: Option<&Array1<f64>>,
+    ) -> Result<Array1<f64>, FeosError> {
+        let weights = match self {
+            Self::Uniform => return Ok(Array1::ones(target.len())),
+            Self::Uncertainty => {
+                let uncertainty = uncertainty.ok_or(FeosError::InsufficientInformation)?;
+                if uncertainty.len() != target.len() {
+                    return Err(FeosError::IncompatibleParameters(format!(
+                        "{} uncertainties were provided for {} data points.",
+                        uncertainty.len(),
+                        target.len()
+                    )));
+                }
+                (target / uncertainty).mapv(f64::abs)
+            }
+            Self::Custom(weights) => {
+                if weights.len() != target.len() {
+                    return Err(FeosError::IncompatibleParameters(format!(
+                        "{} weights were provided for {} data points.",
+                        weights.len(),
+                        target.len()
+                    )));
+                }
+                Array1::from_vec(weights.clone())
+            }
+        };
+        if !weights.iter().all(|w| w.is_finite() && *w >= 0.0) {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "weights have to be finite and non-negative.",
+            )));
+        }
+        let mean = weights.mean().unwrap_or(1.0);
+        Ok(weights / mean)
+    }
+}
+
+/// A [`DataSet`] with individual weights for each data point.
+///
+/// The weights are applied to the relative differences before the
+/// [`Loss`] is evaluated. All other methods are forwarded to the
+/// underlying `DataSet`.
+pub struct WeightedDataSet<E: Residual> {
+    data: Arc<dyn DataSet<E>>,
+    weights: Array1<f64>,
+}
+
+impl<E: Residual> WeightedDataSet<E> {
+    /// Create a new weighted data set using the given weighting scheme.
+    pub fn new(
+        data: Arc<dyn DataSet<E>>,
+        weighting: &Weighting,
+        uncertainty: Option<&Array1<f64>>,
+    ) -> Result<Self, FeosError> {
+        let weights = weighting.weights(data.target(), uncertainty)?;
+        Ok(Self { data, weights })
+    }
+
+    /// Returns the normalized weights of the data points.
+    pub fn weights(&self) -> &Array1<f64> {
+        &self.weights
+    }
+}
+
+impl<E: Residual> DataSet<E> for WeightedDataSet<E> {
+    fn target(&self) -> &Array1<f64> {
+        self.data.target()
+    }
+
+    fn target_str(&self) -> &str {
+        self.data.target_str()
+    }
+
+    fn input_str(&self) -> Vec<&str> {
+        self.data.input_str()
+    }
+
+    fn predict(&self, eos: &Arc<E>) -> Result<Array1<f64>, FeosError> {
+        self.data.predict(eos)
+    }
+
+    fn cost(&self, eos: &Arc<E>, loss: Loss) -> Result<Array1<f64>, FeosError> {
+        let mut cost = self.relative_difference(eos)? * &self.weights;
+        loss.apply(&mut cost);
+        let datapoints = cost.len();
+        Ok(cost / datapoints as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use ndarray::arr1;
+
+    #[test]
+    fn uncertainty_weights() -> Result<(), FeosError> {
+        let target = arr1(&[100.0, 200.0, 300.0]);
+        let uncertainty = arr1(&[1.0, 4.0, 3.0]);
+        let weights = Weighting::Uncertainty.weights(&target, Some(&uncertainty))?;
+        assert_relative_eq!(weights, arr1(&[1.2, 0.6, 1.2]), max_relative = 1e-14);
+        assert!(Weighting::Uncertainty.weights(&target, None).is_err());
+        assert!(
+            Weighting::Custom(vec![1.0, 2.0])
+                .weights(&target, None)
+                .is_err()
+        );
+        Ok(())
+    }
+}
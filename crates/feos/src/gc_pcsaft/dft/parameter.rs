@@ -71,7 +71,7 @@ impl ParameterHetero for GcPcSaftFunctionalParameters {
             for id in &chemical_record.segments {
                 let segment = segment_map
                     .get(id)
-                    .ok_or_else(|| FeosError::ComponentsNotFound(id.to_string()))?;
+                    .ok_or_else(|| FeosError::ComponentsNotFound(vec![id.to_string()]))?;
                 molarweight[i] += segment.molarweight;
                 component_index.push(i);
                 identifiers.push(id.clone());
@@ -38,7 +38,7 @@ impl DipprRecord {
         Self::DIPPR127([a, b, c, d, e, f, g])
     }
 
-    fn c_p(&self, t: f64) -> f64 {
+    pub(super) fn c_p(&self, t: f64) -> f64 {
         match self {
             Self::DIPPR100(coefs) => coefs.iter().rev().fold(0.0, |acc, c| t * acc + c),
             Self::DIPPR107([a, b, c, d, e]) => {
@@ -114,6 +114,14 @@ impl DipprRecord {
     }
 }
 
+impl DipprRecord {
+    pub(super) fn ln_lambda3<D: DualNum<f64> + Copy>(&self, t: D) -> D {
+        let h = self.c_p_integral(t) - self.c_p_integral(T0);
+        let s = self.c_p_t_integral(t) - self.c_p_t_integral(T0);
+        (h - t * s) / (t * RGAS) + t.ln()
+    }
+}
+
 impl fmt::Display for DipprRecord {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -185,15 +193,9 @@ const T0: f64 = 298.15;
 
 impl IdealGas for Dippr {
     fn ln_lambda3<D: DualNum<f64> + Copy>(&self, temperature: D) -> Array1<D> {
-        let t = temperature;
         self.0
             .iter()
-            .map(|r| {
-                let m = &r.model_record;
-                let h = m.c_p_integral(t) - m.c_p_integral(T0);
-                let s = m.c_p_t_integral(t) - m.c_p_t_integral(T0);
-                (h - t * s) / (t * RGAS) + temperature.ln()
-            })
+            .map(|r| r.model_record.ln_lambda3(temperature))
             .collect()
     }
 
@@ -30,6 +30,31 @@ impl JobackRecord {
     }
 }
 
+impl JobackRecord {
+    /// Isobaric heat capacity in J/mol/K.
+    pub(super) fn c_p(&self, t: f64) -> f64 {
+        self.a + self.b * t + self.c * t.powi(2) + self.d * t.powi(3) + self.e * t.powi(4)
+    }
+
+    pub(super) fn ln_lambda3<D: DualNum<f64> + Copy>(&self, temperature: D) -> D {
+        let t = temperature;
+        let t2 = t * t;
+        let t4 = t2 * t2;
+        let f = (temperature * KB / (P0 * A3)).ln();
+        let h = (t2 - T0_2) * 0.5 * self.b
+            + (t * t2 - T0_3) * self.c / 3.0
+            + (t4 - T0_4) * self.d / 4.0
+            + (t4 * t - T0_5) * self.e / 5.0
+            + (t - T0) * self.a;
+        let s = (t - T0) * self.b
+            + (t2 - T0_2) * 0.5 * self.c
+            + (t2 * t - T0_3) * self.d / 3.0
+            + (t4 - T0_4) * self.e / 4.0
+            + (t / T0).ln() * self.a;
+        (h - t * s) / (t * RGAS) + f
+    }
+}
+
 impl fmt::Display for JobackRecord {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -98,10 +123,7 @@ impl Joback {
         let c_p: f64 = molefracs
             .iter()
             .zip(&self.0)
-            .map(|(x, p)| {
-                let m = &p.model_record;
-                x * (m.a + m.b * t + m.c * t.powi(2) + m.d * t.powi(3) + m.e * t.powi(4))
-            })
+            .map(|(x, p)| x * p.model_record.c_p(t))
             .sum();
         Ok(c_p / RGAS * quantity::RGAS)
     }
@@ -123,24 +145,10 @@ impl Components for Joback {
 
 impl IdealGas for Joback {
     fn ln_lambda3<D: DualNum<f64> + Copy>(&self, temperature: D) -> Array1<D> {
-        let t = temperature;
-        let t2 = t * t;
-        let t4 = t2 * t2;
-        let f = (temperature * KB / (P0 * A3)).ln();
-        Array1::from_shape_fn(self.0.len(), |i| {
-            let j = &self.0[i].model_record;
-            let h = (t2 - T0_2) * 0.5 * j.b
-                + (t * t2 - T0_3) * j.c / 3.0
-                + (t4 - T0_4) * j.d / 4.0
-                + (t4 * t - T0_5) * j.e / 5.0
-                + (t - T0) * j.a;
-            let s = (t - T0) * j.b
-                + (t2 - T0_2) * 0.5 * j.c
-                + (t2 * t - T0_3) * j.d / 3.0
-                + (t4 - T0_4) * j.e / 4.0
-                + (t / T0).ln() * j.a;
-            (h - t * s) / (t * RGAS) + f
-        })
+        self.0
+            .iter()
+            .map(|r| r.model_record.ln_lambda3(temperature))
+            .collect()
     }
 
     fn ideal_gas_model(&self) -> String {
@@ -0,0 +1,271 @@
+use super::tabulated::PiecewiseCp;
+use super::{DipprRecord, JobackRecord, TabulatedCpRecord};
+use feos_core::parameter::{NoBinaryModelRecord, Parameter, PureRecord};
+use feos_core::{Components, FeosResult, IdealGas};
+use ndarray::{Array1, Array2};
+use num_dual::DualNum;
+use quantity::{JOULE, KELVIN, MOL, MolarEntropy, Temperature};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Parameters of any of the available ideal gas models for a single substance.
+///
+/// In json files, the model is given as key of the model record, e.g.,
+/// `"model_record": {"Joback": {"a": ...}}`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum IdealGasRecord {
+    Joback(JobackRecord),
+    Dippr(DipprRecord),
+    Tabulated(TabulatedCpRecord),
+}
+
+impl From<JobackRecord> for IdealGasRecord {
+    fn from(record: JobackRecord) -> Self {
+        Self::Joback(record)
+    }
+}
+
+impl From<DipprRecord> for IdealGasRecord {
+    fn from(record: DipprRecord) -> Self {
+        Self::Dippr(record)
+    }
+}
+
+impl From<TabulatedCpRecord> for IdealGasRecord {
+    fn from(record: TabulatedCpRecord) -> Self {
+        Self::Tabulated(record)
+    }
+}
+
+impl fmt::Display for IdealGasRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Joback(record) => write!(f, "{record}"),
+            Self::Dippr(record) => write!(f, "{record}"),
+            Self::Tabulated(record) => write!(f, "{record}"),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Model {
+    Joback(JobackRecord),
+    Dippr(DipprRecord),
+    Tabulated(PiecewiseCp),
+}
+
+impl Model {
+    fn new(record: &IdealGasRecord) -> FeosResult<Self> {
+        Ok(match record {
+            IdealGasRecord::Joback(record) => Self::Joback(record.clone()),
+            IdealGasRecord::Dippr(record) => Self::Dippr(record.clone()),
+            IdealGasRecord::Tabulated(record) => Self::Tabulated(PiecewiseCp::new(record)?),
+        })
+    }
+
+    /// Isobaric heat capacity in J/mol/K.
+    fn c_p(&self, t: f64) -> f64 {
+        match self {
+            Self::Joback(record) => record.c_p(t),
+            Self::Dippr(record) => record.c_p(t) * 1e-3,
+            Self::Tabulated(table) => table.c_p(t),
+        }
+    }
+
+    fn ln_lambda3<D: DualNum<f64> + Copy>(&self, temperature: D) -> D {
+        match self {
+            Self::Joback(record) => record.ln_lambda3(temperature),
+            Self::Dippr(record) => record.ln_lambda3(temperature),
+            Self::Tabulated(table) => table.ln_lambda3(temperature),
+        }
+    }
+}
+
+/// Ideal gas model that uses a different model for each component, e.g.,
+/// tabulated heat capacities for some components and Joback for the rest.
+///
+/// Each component uses the reference state of its model. Because the reference
+/// states only add component specific constants to the Helmholtz energy,
+/// phase equilibria as well as changes of enthalpy and entropy at constant
+/// composition are not affected.
+pub struct MixedIdealGas {
+    records: Vec<PureRecord<IdealGasRecord>>,
+    models: Vec<Model>,
+}
+
+impl Parameter for MixedIdealGas {
+    type Pure = IdealGasRecord;
+    type Binary = NoBinaryModelRecord;
+
+    fn from_records(
+        pure_records: Vec<PureRecord<Self::Pure>>,
+        _binary_records: Option<Array2<Self::Binary>>,
+    ) -> FeosResult<Self> {
+        let models = pure_records
+            .iter()
+            .map(|r| Model::new(&r.model_record))
+            .collect::<FeosResult<_>>()?;
+        Ok(Self {
+            records: pure_records,
+            models,
+        })
+    }
+
+    fn records(&self) -> (&[PureRecord<Self::Pure>], Option<&Array2<Self::Binary>>) {
+        (&self.records, None)
+    }
+}
+
+impl MixedIdealGas {
+    /// Create the model from records of arbitrary ideal gas models.
+    pub fn from_model_records<M: Into<IdealGasRecord>>(
+        pure_records: Vec<PureRecord<M>>,
+    ) -> FeosResult<Self> {
+        let records = pure_records
+            .into_iter()
+            .map(|r| r.map_model_record(Into::into))
+            .collect();
+        Self::from_records(records, None)
+    }
+
+    /// Combine the records of two parameter sets, e.g., read from
+    /// separate files for different models.
+    pub fn concat(&self, other: &Self) -> Self {
+        Self {
+            records: [self.records.as_slice(), &other.records].concat(),
+            models: [self.models.as_slice(), &other.models].concat(),
+        }
+    }
+
+    /// Directly calculates the molar ideal gas heat capacity of the mixture.
+    pub fn molar_isobaric_heat_capacity(
+        &self,
+        temperature: Temperature,
+        molefracs: &Array1<f64>,
+    ) -> FeosResult<MolarEntropy> {
+        let t = temperature.convert_to(KELVIN);
+        let c_p: f64 = molefracs
+            .iter()
+            .zip(&self.models)
+            .map(|(x, model)| x * model.c_p(t))
+            .sum();
+        Ok(c_p * (JOULE / (MOL * KELVIN)))
+    }
+}
+
+impl Components for MixedIdealGas {
+    fn components(&self) -> usize {
+        self.records.len()
+    }
+
+    fn subset(&self, component_list: &[usize]) -> Self {
+        Self {
+            records: component_list
+                .iter()
+                .map(|&i| self.records[i].clone())
+                .collect(),
+            models: component_list
+                .iter()
+                .map(|&i| self.models[i].clone())
+                .collect(),
+        }
+    }
+}
+
+impl IdealGas for MixedIdealGas {
+    fn ln_lambda3<D: DualNum<f64> + Copy>(&self, temperature: D) -> Array1<D> {
+        self.models
+            .iter()
+            .map(|model| model.ln_lambda3(temperature))
+            .collect()
+    }
+
+    fn ideal_gas_model(&self) -> String {
+        "Ideal gas (mixed)".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ideal_gas::{CpInterpolation, Dippr, Joback, TabulatedCp};
+    use approx::assert_relative_eq;
+    use feos_core::parameter::Identifier;
+    use ndarray::arr1;
+    use num_dual::Dual64;
+
+    fn records() -> (
+        PureRecord<JobackRecord>,
+        PureRecord<DipprRecord>,
+        PureRecord<TabulatedCpRecord>,
+    ) {
+        let joback = JobackRecord::new(19.5, 0.0871, -2.16e-5, -1.2e-8, 0.0);
+        let dippr = DipprRecord::eq107(33363., 26790., 2610.5, 8896., 1169.);
+        let tabulated = TabulatedCpRecord::new(
+            vec![200.0, 300.0, 400.0, 600.0],
+            vec![29.11, 29.12, 29.25, 30.11],
+            CpInterpolation::Spline,
+        );
+        let id = Identifier::default;
+        (
+            PureRecord::new(id(), 0.0, joback),
+            PureRecord::new(id(), 0.0, dippr),
+            PureRecord::new(id(), 0.0, tabulated),
+        )
+    }
+
+    #[test]
+    fn mixed_ideal_gas() -> FeosResult<()> {
+        let (joback, dippr, tabulated) = records();
+        let mixed = MixedIdealGas::from_records(
+            vec![
+                joback.clone().map_model_record(Into::into),
+                dippr.clone().map_model_record(Into::into),
+                tabulated.clone().map_model_record(Into::into),
+            ],
+            None,
+        )?;
+        assert_eq!(mixed.components(), 3);
+
+        // every component is evaluated with its own model
+        let t = Dual64::from(350.0).derivative();
+        let ln_lambda3 = mixed.ln_lambda3(t);
+        let reference = [
+            Joback::new_pure(joback)?.ln_lambda3(t)[0],
+            Dippr::new_pure(dippr)?.ln_lambda3(t)[0],
+            TabulatedCp::new_pure(tabulated.clone())?.ln_lambda3(t)[0],
+        ];
+        for (l, r) in ln_lambda3.iter().zip(reference) {
+            assert_eq!(*l, r);
+        }
+        let subset = Components::subset(&mixed, &[2]);
+        assert_eq!(subset.ln_lambda3(t)[0], reference[2]);
+
+        let x = arr1(&[0.2, 0.3, 0.5]);
+        let c_p = mixed.molar_isobaric_heat_capacity(350.0 * KELVIN, &x)?;
+        let c_p_tabulated = TabulatedCp::new_pure(tabulated)?
+            .molar_isobaric_heat_capacity(350.0 * KELVIN, &arr1(&[1.0]))?;
+        let c_p_dippr = DipprRecord::eq107(33363., 26790., 2610.5, 8896., 1169.).c_p(350.0);
+        let c_p_joback =
+            19.5 + 0.0871 * 350.0 - 2.16e-5 * 350.0f64.powi(2) - 1.2e-8 * 350.0f64.powi(3);
+        assert_relative_eq!(
+            c_p,
+            0.2 * c_p_joback * JOULE / (MOL * KELVIN)
+                + 0.3 * c_p_dippr * JOULE / (1000.0 * MOL * KELVIN)
+                + 0.5 * c_p_tabulated,
+            max_relative = 1e-12
+        );
+
+        // records of different models can be read from the same file
+        let json = r#"[
+            {"identifier": {"name": "a"}, "model_record": {"Joback": {"a": 19.5, "b": 0.0871, "c": -2.16e-5, "d": -1.2e-8, "e": 0.0}}},
+            {"identifier": {"name": "b"}, "model_record": {"Tabulated": {"temperature": [200.0, 300.0], "heat_capacity": [29.1, 29.2]}}}
+        ]"#;
+        let json_records: Vec<PureRecord<IdealGasRecord>> = serde_json::from_str(json)?;
+        let from_json = MixedIdealGas::from_records(json_records, None)?;
+        let concat = MixedIdealGas::from_model_records(vec![records().0])?.concat(&from_json);
+        assert_eq!(concat.components(), 3);
+        assert_eq!(concat.ln_lambda3(t)[0], reference[0]);
+        Ok(())
+    }
+}
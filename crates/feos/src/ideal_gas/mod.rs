@@ -1,5 +1,9 @@
 //! Collection of ideal gas models.
 mod dippr;
 mod joback;
+mod mixed;
+mod tabulated;
 pub use dippr::{Dippr, DipprRecord};
 pub use joback::{Joback, JobackRecord};
+pub use mixed::{IdealGasRecord, MixedIdealGas};
+pub use tabulated::{CpInterpolation, TabulatedCp, TabulatedCpRecord};
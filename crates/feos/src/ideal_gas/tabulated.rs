@@ -0,0 +1,431 @@
+use feos_core::parameter::{NoBinaryModelRecord, Parameter, PureRecord};
+use feos_core::{Components, FeosError, FeosResult, IdealGas};
+use ndarray::{Array1, Array2};
+use num_dual::DualNum;
+use quantity::{JOULE, KELVIN, MOL, MolarEntropy, Temperature};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+const RGAS: f64 = 8.31446261815324;
+const T0: f64 = 298.15;
+
+/// Interpolation between the points of a heat capacity table.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CpInterpolation {
+    /// Piecewise linear interpolation.
+    Linear,
+    /// Natural cubic spline.
+    #[default]
+    Spline,
+}
+
+/// Tabulated isobaric heat capacities of an ideal gas.
+///
+/// Units are $\[T\]=\text{K}$ and $\[c_p\]=\text{J/mol/K}$. Between the points,
+/// the heat capacity is interpolated with a natural cubic spline or linearly.
+/// Outside of the table, the heat capacity is extrapolated linearly with the
+/// slope at the first or last point.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TabulatedCpRecord {
+    pub temperature: Vec<f64>,
+    pub heat_capacity: Vec<f64>,
+    #[serde(default)]
+    pub interpolation: CpInterpolation,
+}
+
+impl TabulatedCpRecord {
+    /// Create a record from temperatures and heat capacities.
+    pub fn new(
+        temperature: Vec<f64>,
+        heat_capacity: Vec<f64>,
+        interpolation: CpInterpolation,
+    ) -> Self {
+        Self {
+            temperature,
+            heat_capacity,
+            interpolation,
+        }
+    }
+
+    /// Read a heat capacity table from a CSV file.
+    ///
+    /// See [TabulatedCpRecord::from_csv_str] for the format.
+    pub fn from_csv<P: AsRef<Path>>(file: P, interpolation: CpInterpolation) -> FeosResult<Self> {
+        Self::from_csv_str(&fs::read_to_string(file)?, interpolation)
+    }
+
+    /// Parse a heat capacity table with the temperature in the first and the
+    /// heat capacity in the second column.
+    ///
+    /// Columns are separated by commas, semicolons or whitespace. Lines
+    /// starting with `#` and a header line are skipped.
+    pub fn from_csv_str(table: &str, interpolation: CpInterpolation) -> FeosResult<Self> {
+        let mut temperature = Vec::new();
+        let mut heat_capacity = Vec::new();
+        for (i, line) in table.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line
+                .split([',', ';', ' ', '\t'])
+                .filter(|f| !f.is_empty())
+                .map(str::parse::<f64>);
+            match (fields.next(), fields.next()) {
+                (Some(Ok(t)), Some(Ok(c_p))) => {
+                    temperature.push(t);
+                    heat_capacity.push(c_p);
+                }
+                _ if temperature.is_empty() => continue,
+                _ => {
+                    return Err(FeosError::Error(format!(
+                        "Line {}: invalid heat capacity table entry '{line}'.",
+                        i + 1
+                    )));
+                }
+            }
+        }
+        Ok(Self::new(temperature, heat_capacity, interpolation))
+    }
+}
+
+impl fmt::Display for TabulatedCpRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "TabulatedCpRecord({} points from {:?} K to {:?} K, interpolation={:?})",
+            self.temperature.len(),
+            self.temperature.first(),
+            self.temperature.last(),
+            self.interpolation
+        )
+    }
+}
+
+/// Cubic polynomial of the heat capacity in $u=T-T_k$ together with the
+/// integrals of $c_p$ and $c_p/T$ from the first point of the table to $T_k$.
+#[derive(Debug, Clone)]
+struct Segment {
+    t: f64,
+    c_p: [f64; 4],
+    h: f64,
+    s: f64,
+}
+
+impl Segment {
+    fn new(t: f64, c_p: [f64; 4]) -> Self {
+        Self {
+            t,
+            c_p,
+            h: 0.0,
+            s: 0.0,
+        }
+    }
+
+    /// Coefficients of $c_p/T=q_0+q_1u+q_2u^2+\frac{r}{T}$.
+    fn quotient(&self) -> ([f64; 3], f64) {
+        let [a, b, c, d] = self.c_p;
+        let q2 = d;
+        let q1 = c - self.t * q2;
+        let q0 = b - self.t * q1;
+        ([q0, q1, q2], a - self.t * q0)
+    }
+
+    fn c_p(&self, t: f64) -> f64 {
+        let u = t - self.t;
+        let [a, b, c, d] = self.c_p;
+        a + u * (b + u * (c + u * d))
+    }
+
+    fn c_p_integral<D: DualNum<f64> + Copy>(&self, t: D) -> D {
+        let u = t - self.t;
+        let [a, b, c, d] = self.c_p;
+        u * (u * (u * (u * (d / 4.0) + c / 3.0) + b / 2.0) + a) + self.h
+    }
+
+    fn c_p_t_integral<D: DualNum<f64> + Copy>(&self, t: D) -> D {
+        let u = t - self.t;
+        let ([q0, q1, q2], r) = self.quotient();
+        u * (u * (u * (q2 / 3.0) + q1 / 2.0) + q0) + (t / self.t).ln() * r + self.s
+    }
+}
+
+/// Piecewise polynomial representation of a heat capacity table.
+#[derive(Debug, Clone)]
+pub(super) struct PiecewiseCp(Vec<Segment>);
+
+impl PiecewiseCp {
+    pub(super) fn new(record: &TabulatedCpRecord) -> FeosResult<Self> {
+        let (x, y) = (&record.temperature, &record.heat_capacity);
+        let n = x.len();
+        if n < 2 || y.len() != n {
+            return Err(FeosError::IncompatibleParameters(format!(
+                "a heat capacity table requires at least two points and the same number of temperatures ({n}) and heat capacities ({}).",
+                y.len()
+            )));
+        }
+        if x[0] <= 0.0 || x.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(FeosError::IncompatibleParameters(String::from(
+                "the temperatures of a heat capacity table have to be positive and strictly increasing.",
+            )));
+        }
+
+        // second derivatives at the points (zero at both ends for a natural spline)
+        let mut m = vec![0.0; n];
+        if record.interpolation == CpInterpolation::Spline && n > 2 {
+            // Thomas algorithm for the tridiagonal system of the inner points
+            let mut diag = vec![0.0; n];
+            let mut rhs = vec![0.0; n];
+            for i in 1..n - 1 {
+                let (h0, h1) = (x[i] - x[i - 1], x[i + 1] - x[i]);
+                diag[i] = 2.0 * (h0 + h1);
+                rhs[i] = 6.0 * ((y[i + 1] - y[i]) / h1 - (y[i] - y[i - 1]) / h0);
+                if i > 1 {
+                    let w = h0 / diag[i - 1];
+                    diag[i] -= w * h0;
+                    rhs[i] -= w * rhs[i - 1];
+                }
+            }
+            for i in (1..n - 1).rev() {
+                m[i] = (rhs[i] - (x[i + 1] - x[i]) * m[i + 1]) / diag[i];
+            }
+        }
+
+        let mut segments = Vec::with_capacity(n + 1);
+        for i in 0..n - 1 {
+            let h = x[i + 1] - x[i];
+            let b = (y[i + 1] - y[i]) / h - h * (2.0 * m[i] + m[i + 1]) / 6.0;
+            let d = (m[i + 1] - m[i]) / (6.0 * h);
+            segments.push(Segment::new(x[i], [y[i], b, m[i] / 2.0, d]));
+        }
+        let first_slope = segments[0].c_p[1];
+        let [_, b, c, d] = segments[n - 2].c_p;
+        let h = x[n - 1] - x[n - 2];
+        let last_slope = b + h * (2.0 * c + 3.0 * h * d);
+        segments.push(Segment::new(x[n - 1], [y[n - 1], last_slope, 0.0, 0.0]));
+        for i in 1..n {
+            let t = segments[i].t;
+            segments[i].h = segments[i - 1].c_p_integral(t);
+            segments[i].s = segments[i - 1].c_p_t_integral(t);
+        }
+        segments.insert(0, Segment::new(x[0], [y[0], first_slope, 0.0, 0.0]));
+        Ok(Self(segments))
+    }
+
+    fn segment(&self, t: f64) -> &Segment {
+        let i = self.0[1..].partition_point(|s| s.t <= t);
+        &self.0[i]
+    }
+
+    pub(super) fn c_p(&self, t: f64) -> f64 {
+        self.segment(t).c_p(t)
+    }
+
+    pub(super) fn ln_lambda3<D: DualNum<f64> + Copy>(&self, t: D) -> D {
+        let (segment, reference) = (self.segment(t.re()), self.segment(T0));
+        let h = segment.c_p_integral(t) - reference.c_p_integral(T0);
+        let s = segment.c_p_t_integral(t) - reference.c_p_t_integral(T0);
+        (h - t * s) / (t * RGAS) + t.ln()
+    }
+}
+
+/// Ideal gas model based on tabulated isobaric heat capacities.
+///
+/// The enthalpy and entropy are obtained by analytic integration of the
+/// interpolated heat capacities.
+pub struct TabulatedCp {
+    records: Vec<PureRecord<TabulatedCpRecord>>,
+    tables: Vec<PiecewiseCp>,
+}
+
+impl Parameter for TabulatedCp {
+    type Pure = TabulatedCpRecord;
+    type Binary = NoBinaryModelRecord;
+
+    fn from_records(
+        pure_records: Vec<PureRecord<Self::Pure>>,
+        _binary_records: Option<Array2<Self::Binary>>,
+    ) -> FeosResult<Self> {
+        let tables = pure_records
+            .iter()
+            .map(|r| PiecewiseCp::new(&r.model_record))
+            .collect::<FeosResult<_>>()?;
+        Ok(Self {
+            records: pure_records,
+            tables,
+        })
+    }
+
+    fn records(&self) -> (&[PureRecord<Self::Pure>], Option<&Array2<Self::Binary>>) {
+        (&self.records, None)
+    }
+}
+
+impl TabulatedCp {
+    /// Directly calculates the molar ideal gas heat capacity from the interpolated tables.
+    pub fn molar_isobaric_heat_capacity(
+        &self,
+        temperature: Temperature,
+        molefracs: &Array1<f64>,
+    ) -> FeosResult<MolarEntropy> {
+        let t = temperature.convert_to(KELVIN);
+        let c_p: f64 = molefracs
+            .iter()
+            .zip(&self.tables)
+            .map(|(x, table)| x * table.c_p(t))
+            .sum();
+        Ok(c_p * (JOULE / (MOL * KELVIN)))
+    }
+}
+
+impl Components for TabulatedCp {
+    fn components(&self) -> usize {
+        self.records.len()
+    }
+
+    fn subset(&self, component_list: &[usize]) -> Self {
+        Self {
+            records: component_list
+                .iter()
+                .map(|&i| self.records[i].clone())
+                .collect(),
+            tables: component_list
+                .iter()
+                .map(|&i| self.tables[i].clone())
+                .collect(),
+        }
+    }
+}
+
+impl IdealGas for TabulatedCp {
+    fn ln_lambda3<D: DualNum<f64> + Copy>(&self, temperature: D) -> Array1<D> {
+        self.tables
+            .iter()
+            .map(|table| table.ln_lambda3(temperature))
+            .collect()
+    }
+
+    fn ideal_gas_model(&self) -> String {
+        "Ideal gas (tabulated cp)".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use feos_core::parameter::Identifier;
+    use feos_core::{Contributions, EquationOfState, NoResidual, State, StateBuilder};
+    use ndarray::arr1;
+    use quantity::{METER, RGAS as R};
+    use std::sync::Arc;
+    use typenum::P3;
+
+    fn state(
+        ideal_gas: &Arc<TabulatedCp>,
+        temperature: f64,
+    ) -> FeosResult<State<EquationOfState<TabulatedCp, NoResidual>>> {
+        let eos = Arc::new(EquationOfState::ideal_gas(ideal_gas.clone()));
+        StateBuilder::new(&eos)
+            .temperature(temperature * KELVIN)
+            .volume(METER.powi::<P3>())
+            .total_moles(MOL)
+            .build()
+    }
+
+    #[test]
+    fn linear_heat_capacity() -> FeosResult<()> {
+        // c_p = 30 + 0.02 T is reproduced exactly by both interpolations
+        let t = vec![200.0, 300.0, 450.0, 700.0];
+        let c_p: Vec<_> = t.iter().map(|t| 30.0 + 0.02 * t).collect();
+        for interpolation in [CpInterpolation::Linear, CpInterpolation::Spline] {
+            let record = TabulatedCpRecord::new(t.clone(), c_p.clone(), interpolation);
+            let record = PureRecord::new(Identifier::default(), 0.0, record);
+            let ideal_gas = Arc::new(TabulatedCp::new_pure(record)?);
+            for temperature in [150.0, 250.0, 500.0, 900.0] {
+                let state = state(&ideal_gas, temperature)?;
+                assert_relative_eq!(
+                    state
+                        .molar_isobaric_heat_capacity(Contributions::IdealGas)
+                        .convert_to(JOULE / MOL / KELVIN),
+                    30.0 + 0.02 * temperature,
+                    max_relative = 1e-10
+                );
+            }
+            let (s1, s2) = (state(&ideal_gas, 250.0)?, state(&ideal_gas, 500.0)?);
+            let dh = (s2.molar_enthalpy(Contributions::IdealGas)
+                - s1.molar_enthalpy(Contributions::IdealGas))
+            .convert_to(JOULE / MOL);
+            assert_relative_eq!(
+                dh,
+                30.0 * 250.0 + 0.01 * (500.0f64.powi(2) - 250.0f64.powi(2)),
+                max_relative = 1e-10
+            );
+            // the states have the same volume, so the entropy difference is that
+            // of an isobaric process corrected for the pressure change
+            let ds = (s2.molar_entropy(Contributions::IdealGas)
+                - s1.molar_entropy(Contributions::IdealGas))
+            .convert_to(JOULE / MOL / KELVIN);
+            let r = R.convert_to(JOULE / MOL / KELVIN);
+            assert_relative_eq!(
+                ds,
+                30.0 * 2.0f64.ln() + 0.02 * 250.0 - r * 2.0f64.ln(),
+                max_relative = 1e-10
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn spline() -> FeosResult<()> {
+        let table = "# nitrogen
+T / K, cp / J/mol/K
+200, 29.11
+300, 29.12
+400, 29.25
+600, 30.11
+800, 31.43
+1000, 32.70
+";
+        let record = TabulatedCpRecord::from_csv_str(table, CpInterpolation::Spline)?;
+        assert_eq!(record.temperature.len(), 6);
+        let pure_record = PureRecord::new(Identifier::default(), 28.0134, record.clone());
+        let ideal_gas = Arc::new(TabulatedCp::new_pure(pure_record)?);
+
+        // the spline passes through all points and the heat capacities of the
+        // equation of state are consistent with the interpolation
+        let x = arr1(&[1.0]);
+        for (&t, &c_p) in record.temperature.iter().zip(&record.heat_capacity) {
+            let c = ideal_gas.molar_isobaric_heat_capacity(t * KELVIN, &x)?;
+            assert_relative_eq!(
+                c.convert_to(JOULE / MOL / KELVIN),
+                c_p,
+                max_relative = 1e-12
+            );
+        }
+        for t in [250.0, 298.15, 555.5, 1200.0] {
+            let state = state(&ideal_gas, t)?;
+            assert_relative_eq!(
+                state.molar_isobaric_heat_capacity(Contributions::IdealGas),
+                ideal_gas.molar_isobaric_heat_capacity(t * KELVIN, &x)?,
+                max_relative = 1e-10
+            );
+        }
+
+        assert!(
+            TabulatedCpRecord::from_csv_str("200, 29.1\n300, x", CpInterpolation::Linear).is_err()
+        );
+        let invalid = TabulatedCpRecord::new(
+            vec![300.0, 200.0],
+            vec![29.1, 29.1],
+            CpInterpolation::Spline,
+        );
+        assert!(
+            TabulatedCp::new_pure(PureRecord::new(Identifier::default(), 0.0, invalid)).is_err()
+        );
+        Ok(())
+    }
+}
@@ -342,12 +342,8 @@ impl PcSaftBinaryRecord {
 
 impl<T: CountType> FromSegmentsBinary<T> for PcSaftBinaryRecord {
     fn from_segments_binary(segments: &[(f64, T, T)]) -> FeosResult<Self> {
-        let (k_ij, n) = segments.iter().fold((0.0, 0.0), |(k_ij, n), (br, n1, n2)| {
-            let nab = n1.apply_count(1.0) * n2.apply_count(1.0);
-            (k_ij + br * nab, n + nab)
-        });
         Ok(Self {
-            k_ij: k_ij / n,
+            k_ij: f64::from_segments_binary(segments)?,
             association: None,
         })
     }
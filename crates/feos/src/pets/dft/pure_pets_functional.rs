@@ -65,7 +65,7 @@ impl FunctionalContribution for PureFMTFunctional {
 
         // Auxiliary variables
         if n3.iter().any(|n3| n3.re() > 1.0) {
-            return Err(FeosError::IterationFailed(String::from("PureFMTFunctional")));
+            return Err(FeosError::IterationFailed("PureFMTFunctional".into()));
         }
         let ln31 = n3.mapv(|n3| (-n3).ln_1p());
         let n3rec = n3.mapv(|n3| n3.recip());
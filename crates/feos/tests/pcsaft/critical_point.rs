@@ -1,7 +1,7 @@
 use approx::assert_relative_eq;
 use feos::pcsaft::{PcSaft, PcSaftParameters};
 use feos_core::parameter::{IdentifierOption, Parameter};
-use feos_core::State;
+use feos_core::{CriticalLocus, PhaseBehaviorType, State};
 use ndarray::arr1;
 use quantity::*;
 use std::error::Error;
@@ -48,3 +48,54 @@ fn test_critical_point_mix() -> Result<(), Box<dyn Error>> {
     );
     Ok(())
 }
+
+#[test]
+fn test_critical_locus_continuous() -> Result<(), Box<dyn Error>> {
+    let params = PcSaftParameters::from_json(
+        vec!["propane", "butane"],
+        "tests/pcsaft/test_parameters.json",
+        None,
+        IdentifierOption::Name,
+    )?;
+    let saft = Arc::new(PcSaft::new(Arc::new(params)));
+    let locus = CriticalLocus::binary(&saft, Some(11), None, Default::default())?;
+    assert_eq!(locus.phase_behavior, PhaseBehaviorType::Continuous);
+    assert_eq!(locus.branches.len(), 1);
+
+    // the critical line connects the pure component critical points
+    let pure = State::critical_point_pure(&saft, None, Default::default())?;
+    let branch = locus.branch(0);
+    let t = branch.temperature();
+    assert_eq!(branch.len(), 11);
+    assert_relative_eq!(t.get(0), pure[0].temperature, max_relative = 1e-10);
+    assert_relative_eq!(t.get(10), pure[1].temperature, max_relative = 1e-10);
+    assert_relative_eq!(branch[5].molefracs[0], 0.5, max_relative = 1e-10);
+    assert_relative_eq!(t.get(5), 407.93481 * KELVIN, max_relative = 1e-6);
+    Ok(())
+}
+
+#[test]
+fn test_critical_locus_interrupted() -> Result<(), Box<dyn Error>> {
+    let params = PcSaftParameters::from_json(
+        vec!["carbon-dioxide", "water_np"],
+        "tests/pcsaft/test_parameters.json",
+        None,
+        IdentifierOption::Name,
+    )?;
+    let saft = Arc::new(PcSaft::new(Arc::new(params)));
+    let p_max = 1000.0 * BAR;
+    let locus = CriticalLocus::binary(&saft, None, Some(p_max), Default::default())?;
+    assert_eq!(locus.phase_behavior, PhaseBehaviorType::TypeIII);
+    assert_eq!(locus.branches.len(), 2);
+
+    // the branch starting at carbon dioxide ends in a critical endpoint
+    let co2 = locus.branch(0);
+    assert!(co2.last().unwrap().molefracs[1] < 0.1);
+
+    // the branch starting at water rises to high pressures
+    let water = locus.branch(1);
+    let p = water.pressure();
+    assert!(p.get(water.len() - 1) < p_max);
+    assert!(p.get(water.len() - 1) > 2.5 * p.get(0));
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use approx::assert_relative_eq;
+use feos::ideal_gas::Joback;
+use feos::pcsaft::{PcSaft, PcSaftParameters};
+use feos_core::EquationOfState;
+use feos_core::diagram::{Diagram, LineKind};
+use feos_core::parameter::{IdentifierOption, Parameter};
+use ndarray::arr1;
+use quantity::*;
+use std::error::Error;
+use std::sync::Arc;
+
+fn eos(components: Vec<&str>) -> Result<Arc<EquationOfState<Joback, PcSaft>>, Box<dyn Error>> {
+    let saft = PcSaftParameters::from_json(
+        components.clone(),
+        "tests/pcsaft/test_parameters.json",
+        None,
+        IdentifierOption::Name,
+    )?;
+    let joback = Joback::from_json(
+        components,
+        "tests/pcsaft/test_parameters_joback.json",
+        None,
+        IdentifierOption::Name,
+    )?;
+    Ok(Arc::new(EquationOfState::new(
+        Arc::new(joback),
+        Arc::new(PcSaft::new(Arc::new(saft))),
+    )))
+}
+
+#[test]
+fn diagram_pure() -> Result<(), Box<dyn Error>> {
+    let eos = eos(vec!["propane"])?;
+    let options = Default::default();
+    let temperatures = Temperature::linspace(200.0 * KELVIN, 400.0 * KELVIN, 21);
+    let pressures = Pressure::linspace(BAR, 50.0 * BAR, 25);
+    let diagram = Diagram::pure(&eos, 200.0 * KELVIN, 21, options)?
+        .with_isotherms(&eos, &(arr1(&[250.0, 300.0]) * KELVIN), &pressures, options)?
+        .with_isobars(&eos, &(arr1(&[1.0, 10.0]) * BAR), &temperatures, options)?
+        .with_isochores(&eos, &(arr1(&[2.0]) * MOL / LITER), &temperatures, options)?
+        .with_isentropes(
+            &eos,
+            &(arr1(&[-50.0]) * JOULE / MOL / KELVIN),
+            &pressures,
+            options,
+        )?;
+
+    let kinds: Vec<_> = diagram.lines.iter().map(|l| l.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            LineKind::BubblePoint,
+            LineKind::DewPoint,
+            LineKind::Isotherm,
+            LineKind::Isotherm,
+            LineKind::Isobar,
+            LineKind::Isobar,
+            LineKind::Isochore,
+            LineKind::Isentrope
+        ]
+    );
+    assert_eq!(diagram.lines[0].value, None);
+    assert_eq!(diagram.lines[2].value, Some(250.0));
+    assert_eq!(diagram.lines[5].value, Some(1e6));
+
+    // the saturation points are part of the isobars
+    let isobar = &diagram.lines[4];
+    assert_eq!(isobar.temperature.len(), 23);
+    assert!(isobar.vapor_fraction.contains(&Some(0.0)));
+    assert!(isobar.vapor_fraction.contains(&Some(1.0)));
+
+    // export to JSON and CSV
+    let json = diagram.to_json()?;
+    let restored: Diagram = serde_json::from_str(&json)?;
+    for (l1, l2) in restored.lines.iter().zip(&diagram.lines) {
+        assert_eq!((l1.kind, l1.value), (l2.kind, l2.value));
+        for (b1, b2) in l1.vapor_fraction.iter().zip(&l2.vapor_fraction) {
+            assert_eq!(b1.is_some(), b2.is_some());
+        }
+        for (h1, h2) in l1.molar_enthalpy.iter().zip(&l2.molar_enthalpy) {
+            assert_relative_eq!(h1, h2, max_relative = 1e-14);
+        }
+    }
+    let csv = diagram.to_csv();
+    let npoints: usize = diagram.lines.iter().map(|l| l.temperature.len()).sum();
+    assert_eq!(csv.lines().count(), npoints + 1);
+    assert!(csv.starts_with("line,kind,value,temperature,pressure"));
+    assert!(
+        csv.lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("0,bubble_point,,200,")
+    );
+    Ok(())
+}
+
+#[test]
+fn diagram_mixture() -> Result<(), Box<dyn Error>> {
+    let eos = eos(vec!["propane", "butane"])?;
+    let moles = arr1(&[0.5, 0.5]) * MOL;
+    let options = Default::default();
+    let temperatures = Temperature::linspace(250.0 * KELVIN, 350.0 * KELVIN, 21);
+    let diagram = Diagram::mixture(&eos, &moles, 250.0 * KELVIN, 21, (options, options))?
+        .with_isobars(&eos, &(arr1(&[5.0]) * BAR), &temperatures, options)?;
+    assert_eq!(diagram.molefracs, [0.5, 0.5]);
+    assert_eq!(diagram.lines.len(), 3);
+
+    // the isobar passes the bubble point, the two-phase region and the dew point
+    let isobar = &diagram.lines[2];
+    assert_eq!(isobar.temperature.len(), 23);
+    let beta = &isobar.vapor_fraction;
+    let i = beta.iter().position(|b| b.is_some()).unwrap();
+    let j = beta.iter().rposition(|b| b.is_some()).unwrap();
+    assert_eq!((beta[i], beta[j]), (Some(0.0), Some(1.0)));
+    assert!(
+        beta[i + 1..j]
+            .iter()
+            .all(|b| b.is_some_and(|b| b > 0.0 && b < 1.0))
+    );
+    assert!(isobar.temperature.windows(2).all(|t| t[1] >= t[0]));
+    assert!(
+        diagram
+            .clone()
+            .with_isochores(&eos, &(arr1(&[2.0]) * MOL / LITER), &temperatures, options)
+            .is_err()
+    );
+    Ok(())
+}
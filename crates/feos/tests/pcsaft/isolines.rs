@@ -0,0 +1,131 @@
+use approx::assert_relative_eq;
+use feos::ideal_gas::Joback;
+use feos::pcsaft::{PcSaft, PcSaftParameters};
+use feos_core::parameter::{IdentifierOption, Parameter};
+use feos_core::{
+    Contributions, DensityInitialization, EquationOfState, Isoline, PhaseEquilibrium, State,
+};
+use ndarray::arr1;
+use quantity::*;
+use std::error::Error;
+use std::sync::Arc;
+
+fn propane() -> Result<Arc<EquationOfState<Joback, PcSaft>>, Box<dyn Error>> {
+    let saft = PcSaftParameters::from_json(
+        vec!["propane"],
+        "tests/pcsaft/test_parameters.json",
+        None,
+        IdentifierOption::Name,
+    )?;
+    let joback = Joback::from_json(
+        vec!["propane"],
+        "tests/pcsaft/test_parameters_joback.json",
+        None,
+        IdentifierOption::Name,
+    )?;
+    Ok(Arc::new(EquationOfState::new(
+        Arc::new(joback),
+        Arc::new(PcSaft::new(Arc::new(saft))),
+    )))
+}
+
+#[test]
+fn isobar() -> Result<(), Box<dyn Error>> {
+    let eos = propane()?;
+    let temperatures = Temperature::linspace(200.0 * KELVIN, 300.0 * KELVIN, 11);
+    let isobar = Isoline::isobar(&eos, BAR, &temperatures, Default::default())?;
+    let vle = PhaseEquilibrium::pure(&eos, BAR, None, Default::default())?;
+    let t_sat = vle.vapor().temperature;
+
+    // the saturated liquid and vapor are inserted at the boiling point
+    assert_eq!(isobar.points.len(), 13);
+    let beta = isobar.vapor_fraction();
+    let i = beta.iter().position(|b| !b.is_nan()).unwrap();
+    assert_eq!((beta[i], beta[i + 1]), (0.0, 1.0));
+    let t = isobar.temperature();
+    assert_relative_eq!(t.get(i), t_sat, max_relative = 1e-10);
+    assert_relative_eq!(t.get(i + 1), t_sat, max_relative = 1e-10);
+    assert!(t.get(i - 1) < t_sat && t.get(i + 2) > t_sat);
+    assert_relative_eq!(isobar.pressure().get(0), BAR, max_relative = 1e-8);
+
+    let h = isobar.molar_enthalpy(Contributions::Total);
+    assert_relative_eq!(
+        h.get(i + 1) - h.get(i),
+        vle.vapor().molar_enthalpy(Contributions::Total)
+            - vle.liquid().molar_enthalpy(Contributions::Total),
+        max_relative = 1e-10
+    );
+    Ok(())
+}
+
+#[test]
+fn isotherm() -> Result<(), Box<dyn Error>> {
+    let eos = propane()?;
+    let pressures = Pressure::linspace(BAR, 20.0 * BAR, 20);
+    let isotherm = Isoline::isotherm(&eos, 300.0 * KELVIN, &pressures, Default::default())?;
+    let vle = PhaseEquilibrium::pure(&eos, 300.0 * KELVIN, None, Default::default())?;
+
+    // with increasing pressure the saturated vapor is followed by the saturated liquid
+    assert_eq!(isotherm.points.len(), 22);
+    let beta = isotherm.vapor_fraction();
+    let i = beta.iter().position(|b| !b.is_nan()).unwrap();
+    assert_eq!((beta[i], beta[i + 1]), (1.0, 0.0));
+    let density = isotherm.density();
+    assert_relative_eq!(density.get(i), vle.vapor().density, max_relative = 1e-10);
+    assert_relative_eq!(
+        density.get(i + 1),
+        vle.liquid().density,
+        max_relative = 1e-10
+    );
+    assert!(density.get(i - 1) < density.get(i));
+    assert!(density.get(i + 2) > density.get(i + 1));
+    Ok(())
+}
+
+#[test]
+fn isochore() -> Result<(), Box<dyn Error>> {
+    let eos = propane()?;
+    let temperatures = Temperature::linspace(250.0 * KELVIN, 400.0 * KELVIN, 16);
+    for (density, beta_sat) in [(2.0 * MOL / LITER, 1.0), (10.0 * MOL / LITER, 0.0)] {
+        let isochore = Isoline::isochore(&eos, density, &temperatures, Default::default())?;
+        assert_eq!(isochore.points.len(), 17);
+        for rho in isochore.density().into_iter() {
+            assert_relative_eq!(rho, density, max_relative = 1e-8);
+        }
+
+        // the isochore leaves the two-phase region at the saturation point
+        let beta = isochore.vapor_fraction();
+        let i = beta.iter().rposition(|b| !b.is_nan()).unwrap();
+        assert_eq!(beta[i], beta_sat);
+        assert!(beta.iter().take(i).all(|&b| b > 0.0 && b < 1.0));
+        assert!(beta.iter().skip(i + 1).all(|b| b.is_nan()));
+    }
+    Ok(())
+}
+
+#[test]
+fn isentrope() -> Result<(), Box<dyn Error>> {
+    let eos = propane()?;
+    let liquid = State::new_npt(
+        &eos,
+        300.0 * KELVIN,
+        30.0 * BAR,
+        &(arr1(&[1.0]) * MOL),
+        DensityInitialization::Liquid,
+    )?;
+    let s = liquid.molar_entropy(Contributions::Total);
+    let pressures = Pressure::linspace(30.0 * BAR, BAR, 30);
+    let isentrope = Isoline::isentrope(&eos, s, &pressures, Default::default())?;
+
+    // the compressed liquid flashes into the two-phase region
+    assert_eq!(isentrope.points.len(), 31);
+    for si in isentrope.molar_entropy(Contributions::Total).into_iter() {
+        assert_relative_eq!(si, s, max_relative = 1e-8);
+    }
+    let beta = isentrope.vapor_fraction();
+    let i = beta.iter().position(|b| !b.is_nan()).unwrap();
+    assert_eq!(beta[i], 0.0);
+    assert!(beta.iter().skip(i + 1).all(|&b| b > 0.0 && b < 1.0));
+    assert!(isentrope.temperature().get(30) < 300.0 * KELVIN);
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use approx::assert_relative_eq;
+use feos::pcsaft::{PcSaft, PcSaftParameters};
+use feos_core::parameter::{IdentifierOption, Parameter};
+use feos_core::{MinimumMiscibilityPressure, SolverOptions};
+use ndarray::arr1;
+use quantity::*;
+use std::error::Error;
+use std::sync::Arc;
+
+#[test]
+fn test_mmp() -> Result<(), Box<dyn Error>> {
+    let params = PcSaftParameters::from_json(
+        vec!["carbon-dioxide", "methane", "hexane"],
+        "tests/pcsaft/test_parameters.json",
+        None,
+        IdentifierOption::Name,
+    )?;
+    let saft = Arc::new(PcSaft::new(Arc::new(params)));
+    let t = 344.0 * KELVIN;
+    let oil = arr1(&[0.0, 0.3, 0.7]) * MOL;
+    let gas = arr1(&[1.0, 0.0, 0.0]) * MOL;
+    let pressures = Pressure::linspace(6.0 * MEGA * PASCAL, 13.0 * MEGA * PASCAL, 3);
+    let mmp = MinimumMiscibilityPressure::multiple_mixing_cell(
+        &saft,
+        t,
+        &oil,
+        &gas,
+        &pressures,
+        Some(8),
+        SolverOptions::default(),
+    )?;
+    let l = &mmp.tie_line_length;
+    assert!(l[0] > l[1] && l[1] > 0.0);
+    assert_eq!(l[2], 0.0);
+    let p = mmp.mmp.unwrap();
+    assert!(p > pressures.get(1) && p < pressures.get(2));
+    assert_relative_eq!(p, 11.266363 * MEGA * PASCAL, max_relative = 1e-6);
+    Ok(())
+}
@@ -1,5 +1,9 @@
 mod critical_point;
 mod dft;
+mod diagram;
+mod isolines;
+mod minimum_miscibility_pressure;
+mod phase_envelope;
 mod properties;
 mod stability_analysis;
 mod state_creation_mixture;
@@ -0,0 +1,68 @@
+use approx::assert_relative_eq;
+use feos::pcsaft::{PcSaft, PcSaftParameters};
+use feos_core::parameter::{IdentifierOption, Parameter};
+use feos_core::{Contributions, PhaseEquilibrium, SolverOptions};
+use ndarray::arr1;
+use quantity::*;
+use std::error::Error;
+use std::sync::Arc;
+
+fn methane_butane() -> Result<Arc<PcSaft>, Box<dyn Error>> {
+    let params = PcSaftParameters::from_json(
+        vec!["methane", "butane"],
+        "tests/pcsaft/test_parameters.json",
+        None,
+        IdentifierOption::Name,
+    )?;
+    Ok(Arc::new(PcSaft::new(Arc::new(params))))
+}
+
+#[test]
+fn test_cricondentherm() -> Result<(), Box<dyn Error>> {
+    let saft = methane_butane()?;
+    let moles = arr1(&[0.6, 0.4]) * MOL;
+    let vle = PhaseEquilibrium::cricondentherm(&saft, &moles, None, SolverOptions::default())?;
+    let t = vle.vapor().temperature;
+    let p = vle.vapor().pressure(Contributions::Total);
+    assert_relative_eq!(t, 372.47270 * KELVIN, max_relative = 1e-6);
+    assert_relative_eq!(p, 7.4466295 * MEGA * PASCAL, max_relative = 1e-6);
+
+    // neighboring dew points have lower temperatures
+    for f in [0.99, 1.01] {
+        let dew = PhaseEquilibrium::dew_point(
+            &saft,
+            f * p,
+            &arr1(&[0.6, 0.4]),
+            Some(t),
+            Some(&vle.liquid().molefracs),
+            Default::default(),
+        )?;
+        assert!(dew.vapor().temperature < t);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_cricondenbar() -> Result<(), Box<dyn Error>> {
+    let saft = methane_butane()?;
+    let moles = arr1(&[0.6, 0.4]) * MOL;
+    let vle = PhaseEquilibrium::cricondenbar(&saft, &moles, None, SolverOptions::default())?;
+    let t = vle.liquid().temperature;
+    let p = vle.liquid().pressure(Contributions::Total);
+    assert_relative_eq!(t, 322.63713 * KELVIN, max_relative = 1e-6);
+    assert_relative_eq!(p, 12.184682 * MEGA * PASCAL, max_relative = 1e-6);
+
+    // neighboring bubble points have lower pressures
+    for f in [0.99, 1.01] {
+        let bubble = PhaseEquilibrium::bubble_point(
+            &saft,
+            f * t,
+            &arr1(&[0.6, 0.4]),
+            Some(p),
+            Some(&vle.vapor().molefracs),
+            Default::default(),
+        )?;
+        assert!(bubble.liquid().pressure(Contributions::Total) < p);
+    }
+    Ok(())
+}
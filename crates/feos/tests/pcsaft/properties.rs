@@ -1,7 +1,7 @@
 use approx::assert_relative_eq;
 use feos::pcsaft::{PcSaft, PcSaftParameters};
 use feos_core::parameter::{IdentifierOption, Parameter};
-use feos_core::{Residual, StateBuilder};
+use feos_core::{Components, Residual, State, StateBuilder};
 use ndarray::*;
 use quantity::*;
 use std::error::Error;
@@ -53,3 +53,114 @@ fn test_virial_is_not_nan() -> Result<(), Box<dyn Error>> {
     assert!(!virial_b.is_nan());
     Ok(())
 }
+
+#[test]
+fn test_cross_second_virial_coefficients() -> Result<(), Box<dyn Error>> {
+    let params = PcSaftParameters::from_json(
+        vec!["propane", "butane"],
+        "tests/pcsaft/test_parameters.json",
+        None,
+        IdentifierOption::Name,
+    )?;
+    let saft = Arc::new(PcSaft::new(Arc::new(params)));
+    let t = 300.0 * KELVIN;
+    let b = saft.cross_second_virial_coefficients(t)?;
+    for i in 0..2 {
+        let pure = saft.subset(&[i]);
+        let b_pure = pure.second_virial_coefficient(t, None)?;
+        assert_relative_eq!(b.get((i, i)), b_pure, max_relative = 1e-12);
+    }
+    assert_eq!(b.get((0, 1)), b.get((1, 0)));
+
+    let x = arr1(&[0.3, 0.7]);
+    let b_mix = saft.second_virial_coefficient(t, Some(&(&x * MOL)))?;
+    let b_sum = (0..2)
+        .flat_map(|i| (0..2).map(move |j| (i, j)))
+        .fold(0.0 * b_mix, |acc, (i, j)| acc + b.get((i, j)) * x[i] * x[j]);
+    assert_relative_eq!(b_mix, b_sum, max_relative = 1e-3);
+    Ok(())
+}
+
+#[test]
+fn test_ln_activity_coefficient_infinite_dilution() -> Result<(), Box<dyn Error>> {
+    let params = PcSaftParameters::from_json(
+        vec!["propane", "butane"],
+        "tests/pcsaft/test_parameters.json",
+        None,
+        IdentifierOption::Name,
+    )?;
+    let saft = Arc::new(PcSaft::new(Arc::new(params)));
+    let t = 300.0 * KELVIN;
+    let p = 20.0 * BAR;
+    let ln_gamma_inf = State::ln_activity_coefficient_infinite_dilution_binary(&saft, t, p)?;
+
+    // compare to a state close to infinite dilution
+    let s = StateBuilder::new(&saft)
+        .temperature(t)
+        .pressure(p)
+        .molefracs(&arr1(&[1e-8, 1.0 - 1e-8]))
+        .liquid()
+        .build()?;
+    let ln_gamma = s.ln_symmetric_activity_coefficient()?[0];
+    assert_relative_eq!(ln_gamma_inf, ln_gamma, max_relative = 1e-6);
+
+    let ln_gamma_inf =
+        State::ln_activity_coefficient_infinite_dilution(&saft, t, p, &arr1(&[1.0, 0.0]))?;
+    assert_eq!(ln_gamma_inf.len(), 1);
+    assert!(
+        State::ln_activity_coefficient_infinite_dilution(&saft, t, p, &arr1(&[0.0, 0.0])).is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_excess_properties() -> Result<(), Box<dyn Error>> {
+    let params = PcSaftParameters::from_json(
+        vec!["propane", "butane"],
+        "tests/pcsaft/test_parameters.json",
+        None,
+        IdentifierOption::Name,
+    )?;
+    let saft = Arc::new(PcSaft::new(Arc::new(params)));
+    let t = 300.0 * KELVIN;
+    let p = 20.0 * BAR;
+    let h = 1e-2 * KELVIN;
+    let state = |t| {
+        StateBuilder::new(&saft)
+            .temperature(t)
+            .pressure(p)
+            .molefracs(&arr1(&[0.3, 0.7]))
+            .liquid()
+            .build()
+    };
+    let s = state(t)?;
+
+    let g_e = s.excess_molar_gibbs_energy()?;
+    let ln_gamma = s.ln_symmetric_activity_coefficient()?;
+    let g_e_gamma = RGAS * t * (&s.molefracs * &ln_gamma).sum();
+    assert_relative_eq!(g_e, g_e_gamma, max_relative = 1e-8);
+
+    // Gibbs-Helmholtz equation
+    let h_e = s.excess_molar_enthalpy()?;
+    let g_e_h = state(t + h)?.excess_molar_gibbs_energy()? / (t + h);
+    let g_e_l = state(t - h)?.excess_molar_gibbs_energy()? / (t - h);
+    assert_relative_eq!(
+        h_e,
+        -t * t * (g_e_h - g_e_l) / (2.0 * h),
+        max_relative = 1e-5
+    );
+
+    let v_e = s.excess_molar_volume()?;
+    let mut v_e_pure = 1.0 / s.density;
+    for (i, &x) in s.molefracs.iter().enumerate() {
+        let eos = Arc::new(saft.subset(&[i]));
+        let pure = StateBuilder::new(&eos)
+            .temperature(t)
+            .pressure(p)
+            .liquid()
+            .build()?;
+        v_e_pure -= x / pure.density;
+    }
+    assert_relative_eq!(v_e, v_e_pure, max_relative = 1e-10);
+    Ok(())
+}
@@ -46,3 +46,47 @@ fn test_stability_analysis() -> Result<(), Box<dyn Error>> {
     assert!(liquid_check.is_empty());
     Ok(())
 }
+
+#[test]
+fn test_global_stability_analysis() -> Result<(), Box<dyn Error>> {
+    let params = PcSaftParameters::from_json(
+        vec!["water_np", "hexane"],
+        "tests/pcsaft/test_parameters.json",
+        None,
+        IdentifierOption::Name,
+    )?;
+    let mix = Arc::new(PcSaft::new(Arc::new(params)));
+    let unstable = State::new_npt(
+        &mix,
+        300.0 * KELVIN,
+        1.0 * BAR,
+        &(arr1(&[0.5, 0.5]) * MOL),
+        DensityInitialization::Liquid,
+    )?;
+    let local = unstable.stability_analysis(Default::default())?;
+    let global = unstable.global_stability_analysis(10, Default::default())?;
+    assert!(global.len() >= local.len());
+    for (l, g) in local.iter().zip(&global) {
+        assert_eq!(l.molefracs, g.molefracs);
+    }
+    assert!(!unstable.is_globally_stable(10, Default::default())?);
+
+    let params = PcSaftParameters::from_json(
+        vec!["propane", "butane"],
+        "tests/pcsaft/test_parameters.json",
+        None,
+        IdentifierOption::Name,
+    )?;
+    let mix = Arc::new(PcSaft::new(Arc::new(params)));
+    let vle = PhaseEquilibrium::bubble_point(
+        &mix,
+        300.0 * KELVIN,
+        &arr1(&[0.5, 0.5]),
+        Some(6.0 * BAR),
+        None,
+        Default::default(),
+    )?;
+    assert!(vle.vapor().is_globally_stable(10, Default::default())?);
+    assert!(vle.liquid().is_globally_stable(10, Default::default())?);
+    Ok(())
+}
@@ -1,7 +1,10 @@
 use approx::assert_relative_eq;
 use feos::pcsaft::{PcSaft, PcSaftParameters};
 use feos_core::parameter::{IdentifierOption, Parameter};
-use feos_core::{Contributions, FeosResult, PhaseEquilibrium, SolverOptions};
+use feos_core::{
+    Contributions, DensityInitialization, FeosError, FeosResult, FlashWorkspace, PhaseEquilibrium,
+    SolverAlgorithm, SolverOptions, State,
+};
 use ndarray::*;
 use quantity::*;
 use std::error::Error;
@@ -60,3 +63,175 @@ fn test_tp_flash() -> Result<(), Box<dyn Error>> {
     );
     Ok(())
 }
+
+#[test]
+fn test_tp_flash_trivial_solution() -> Result<(), Box<dyn Error>> {
+    let mix = Arc::new(PcSaft::new(read_params(vec!["propane", "butane"])?));
+    let t = 500.0 * KELVIN;
+    let p = BAR;
+    let feed = arr1(&[0.5, 0.5]) * MOL;
+    let state = State::new_npt(&mix, t, p, &feed, DensityInitialization::None)?;
+    let init = PhaseEquilibrium::new_npt(&mix, t, p, &feed, &feed)?;
+    let mut workspace = FlashWorkspace::new(2);
+    assert!(matches!(
+        state.tp_flash_(init, SolverOptions::default(), None, &mut workspace),
+        Err(FeosError::TrivialSolution)
+    ));
+    assert!(
+        state
+            .tp_flash(None, SolverOptions::default(), None)
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_tp_flash_workspace() -> Result<(), Box<dyn Error>> {
+    let propane = Arc::new(PcSaft::new(read_params(vec!["propane"])?));
+    let butane = Arc::new(PcSaft::new(read_params(vec!["butane"])?));
+    let mix = Arc::new(PcSaft::new(read_params(vec!["propane", "butane"])?));
+    let t = 250.0 * KELVIN;
+    let p_propane = PhaseEquilibrium::pure(&propane, t, None, Default::default())?
+        .vapor()
+        .pressure(Contributions::Total);
+    let p_butane = PhaseEquilibrium::pure(&butane, t, None, Default::default())?
+        .vapor()
+        .pressure(Contributions::Total);
+    let p = 0.5 * (p_propane + p_butane);
+    let options = SolverOptions::new().max_iter(100).tol(1e-12);
+    let mut workspace = FlashWorkspace::new(2);
+    for z1 in [0.6, 0.7, 0.8] {
+        let feed = arr1(&[z1, 1.0 - z1]) * MOL;
+        let state = State::new_npt(&mix, t, p, &feed, DensityInitialization::None)?;
+        let vle = state.tp_flash_with_workspace(None, options, None, &mut workspace)?;
+        let reference = state.tp_flash(None, options, None)?;
+        assert_relative_eq!(
+            vle.vapor().molefracs,
+            reference.vapor().molefracs,
+            max_relative = 1e-12
+        );
+        assert_relative_eq!(
+            vle.liquid().molefracs,
+            reference.liquid().molefracs,
+            max_relative = 1e-12
+        );
+    }
+    let mut workspace = FlashWorkspace::new(3);
+    let state = State::new_npt(
+        &mix,
+        t,
+        p,
+        &(arr1(&[0.5, 0.5]) * MOL),
+        DensityInitialization::None,
+    )?;
+    assert!(matches!(
+        state.tp_flash_with_workspace(None, options, None, &mut workspace),
+        Err(FeosError::IncompatibleComponents(3, 2))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_tp_flash_successive_substitution() -> Result<(), Box<dyn Error>> {
+    let mix = Arc::new(PcSaft::new(read_params(vec!["propane", "butane"])?));
+    let t = 250.0 * KELVIN;
+    let feed = arr1(&[0.7, 0.3]) * MOL;
+    let vle = PhaseEquilibrium::bubble_point(
+        &mix,
+        t,
+        &arr1(&[0.5, 0.5]),
+        None,
+        None,
+        (SolverOptions::default(), SolverOptions::default()),
+    )?;
+    let p = vle.vapor().pressure(Contributions::Total);
+    let state = State::new_npt(&mix, t, p, &feed, DensityInitialization::None)?;
+    let options = SolverOptions::new().max_iter(500).tol(1e-12);
+    let reference = state.tp_flash(None, options, None)?;
+    let vle = state.tp_flash(
+        None,
+        options.algorithm(SolverAlgorithm::SuccessiveSubstitution),
+        None,
+    )?;
+    assert_relative_eq!(
+        vle.vapor().molefracs,
+        reference.vapor().molefracs,
+        max_relative = 1e-8
+    );
+    assert_relative_eq!(
+        vle.liquid().molefracs,
+        reference.liquid().molefracs,
+        max_relative = 1e-8
+    );
+    let options = options
+        .max_iter(1)
+        .algorithm(SolverAlgorithm::SuccessiveSubstitution);
+    match state.tp_flash(None, options, None) {
+        Err(FeosError::NotConverged(failure)) => {
+            assert_eq!(failure.iterations, Some(1));
+            assert_eq!(failure.molefracs, Some(vec![0.7, 0.3]));
+        }
+        _ => panic!("Tp flash should not converge in a single iteration."),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_bubble_point_algorithms() -> Result<(), Box<dyn Error>> {
+    let mix = Arc::new(PcSaft::new(read_params(vec!["propane", "butane"])?));
+    let t = 250.0 * KELVIN;
+    let x = arr1(&[0.5, 0.5]);
+    let bubble_point = |options: SolverOptions| {
+        PhaseEquilibrium::bubble_point(&mix, t, &x, None, None, (SolverOptions::default(), options))
+    };
+    let reference = bubble_point(SolverOptions::default())?
+        .vapor()
+        .pressure(Contributions::Total);
+    for options in [
+        SolverOptions::new().algorithm(SolverAlgorithm::SuccessiveSubstitution),
+        SolverOptions::new().damping(0.5).max_iter(1000),
+    ] {
+        let p = bubble_point(options)?
+            .vapor()
+            .pressure(Contributions::Total);
+        assert_relative_eq!(p, reference, max_relative = 1e-8);
+    }
+    assert!(bubble_point(SolverOptions::new().damping(1.5)).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_beta_flash() -> Result<(), Box<dyn Error>> {
+    let mix = Arc::new(PcSaft::new(read_params(vec!["propane", "butane"])?));
+    let t = 250.0 * KELVIN;
+    let z = arr1(&[0.6, 0.4]);
+    let options = (SolverOptions::default(), SolverOptions::default());
+    for beta in [0.3, 0.999] {
+        let vle = PhaseEquilibrium::beta_flash(&mix, t, &z, beta, None, options)?;
+        let p = vle.vapor().pressure(Contributions::Total);
+        let state = State::new_npt(&mix, t, p, &(&z * MOL), DensityInitialization::None)?;
+        let flash = state.tp_flash(None, SolverOptions::new().tol(1e-12), None)?;
+        let v = flash.vapor().total_moles;
+        let l = flash.liquid().total_moles;
+        assert_relative_eq!((v / (v + l)).into_value(), beta, max_relative = 1e-6);
+        assert_relative_eq!(
+            vle.vapor().molefracs,
+            flash.vapor().molefracs,
+            max_relative = 1e-6
+        );
+
+        // specified pressure
+        let vle_p = PhaseEquilibrium::beta_flash(&mix, p, &z, beta, Some(240.0 * KELVIN), options)?;
+        assert_relative_eq!(vle_p.vapor().temperature, t, max_relative = 1e-8);
+    }
+
+    let bubble = PhaseEquilibrium::bubble_point(&mix, t, &z, None, None, options)?;
+    let vle = PhaseEquilibrium::beta_flash(&mix, t, &z, 0.0, None, options)?;
+    assert_relative_eq!(
+        vle.vapor().pressure(Contributions::Total),
+        bubble.vapor().pressure(Contributions::Total),
+        max_relative = 1e-10
+    );
+    assert!(PhaseEquilibrium::beta_flash(&mix, t, &z, 1.5, None, options).is_err());
+    Ok(())
+}
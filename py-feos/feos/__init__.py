@@ -9,6 +9,10 @@ from feos.feos import (
     PhaseDiagram,
     PhaseDiagramHetero,
     PhaseEquilibrium,
+    Isoline,
+    CriticalLocus,
+    MinimumMiscibilityPressure,
+    Diagram,
     EquationOfState,
 )
 
@@ -25,5 +29,9 @@ __all__ = [
     "PhaseDiagram",
     "PhaseDiagramHetero",
     "PhaseEquilibrium",
+    "Isoline",
+    "CriticalLocus",
+    "MinimumMiscibilityPressure",
+    "Diagram",
     "EquationOfState",
 ]
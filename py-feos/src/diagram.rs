@@ -0,0 +1,245 @@
+use crate::eos::PyEquationOfState;
+use crate::error::PyFeosError;
+use crate::PyVerbosity;
+use feos_core::diagram::Diagram;
+use feos_core::SolverOptions;
+use ndarray::Array1;
+use pyo3::prelude::*;
+use quantity::{Density, MolarEntropy, Moles, Pressure, Temperature};
+
+/// A thermodynamic diagram consisting of the phase boundary and
+/// families of isolines for plotting.
+///
+/// All properties are stored in SI units (K, Pa, mol/m³, J/mol, J/mol/K).
+/// Isotherms and isobars are available for pure components and mixtures,
+/// isochores and isentropes only for pure components.
+#[pyclass(name = "Diagram")]
+#[derive(Clone)]
+pub struct PyDiagram(Diagram);
+
+#[pymethods]
+impl PyDiagram {
+    /// Create a diagram of a pure component containing the saturation
+    /// dome from `min_temperature` up to the critical point.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// min_temperature : SINumber
+    ///     The lower limit for the temperature.
+    /// npoints : int
+    ///     The number of points on the saturation dome.
+    /// max_iter : int, optional
+    ///     The maximum number of iterations.
+    /// tol: float, optional
+    ///     The solution tolerance.
+    /// verbosity : Verbosity, optional
+    ///     The verbosity.
+    ///
+    /// Returns
+    /// -------
+    /// Diagram
+    #[staticmethod]
+    #[pyo3(
+        text_signature = "(eos, min_temperature, npoints, max_iter=None, tol=None, verbosity=None)"
+    )]
+    #[pyo3(signature = (eos, min_temperature, npoints, max_iter=None, tol=None, verbosity=None))]
+    fn pure(
+        eos: &PyEquationOfState,
+        min_temperature: Temperature,
+        npoints: usize,
+        max_iter: Option<usize>,
+        tol: Option<f64>,
+        verbosity: Option<PyVerbosity>,
+    ) -> PyResult<Self> {
+        Ok(Self(
+            Diagram::pure(
+                &eos.0,
+                min_temperature,
+                npoints,
+                (max_iter, tol, verbosity.map(|v| v.into())).into(),
+            )
+            .map_err(PyFeosError::from)?,
+        ))
+    }
+
+    /// Create a diagram of a mixture with given composition containing
+    /// the bubble and dew point lines from `min_temperature` up to the
+    /// critical point.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// moles : SIArray1
+    ///     The moles of the individual components.
+    /// min_temperature : SINumber
+    ///     The lower limit for the temperature.
+    /// npoints : int
+    ///     The number of points on the bubble and dew point lines.
+    /// max_iter : int, optional
+    ///     The maximum number of iterations.
+    /// tol: float, optional
+    ///     The solution tolerance.
+    /// verbosity : Verbosity, optional
+    ///     The verbosity.
+    ///
+    /// Returns
+    /// -------
+    /// Diagram
+    #[staticmethod]
+    #[pyo3(
+        text_signature = "(eos, moles, min_temperature, npoints, max_iter=None, tol=None, verbosity=None)"
+    )]
+    #[pyo3(signature = (eos, moles, min_temperature, npoints, max_iter=None, tol=None, verbosity=None))]
+    fn mixture(
+        eos: &PyEquationOfState,
+        moles: Moles<Array1<f64>>,
+        min_temperature: Temperature,
+        npoints: usize,
+        max_iter: Option<usize>,
+        tol: Option<f64>,
+        verbosity: Option<PyVerbosity>,
+    ) -> PyResult<Self> {
+        let options: SolverOptions = (max_iter, tol, verbosity.map(|v| v.into())).into();
+        Ok(Self(
+            Diagram::mixture(&eos.0, &moles, min_temperature, npoints, (options, options))
+                .map_err(PyFeosError::from)?,
+        ))
+    }
+
+    /// Return a new diagram with additional isotherms.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// temperatures : SIArray1
+    ///     The temperatures of the isotherms.
+    /// pressures : SIArray1
+    ///     The pressures along each isotherm.
+    ///
+    /// Returns
+    /// -------
+    /// Diagram
+    fn with_isotherms(
+        &self,
+        eos: &PyEquationOfState,
+        temperatures: Temperature<Array1<f64>>,
+        pressures: Pressure<Array1<f64>>,
+    ) -> PyResult<Self> {
+        Ok(Self(
+            self.0
+                .clone()
+                .with_isotherms(&eos.0, &temperatures, &pressures, Default::default())
+                .map_err(PyFeosError::from)?,
+        ))
+    }
+
+    /// Return a new diagram with additional isobars.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// pressures : SIArray1
+    ///     The pressures of the isobars.
+    /// temperatures : SIArray1
+    ///     The temperatures along each isobar.
+    ///
+    /// Returns
+    /// -------
+    /// Diagram
+    fn with_isobars(
+        &self,
+        eos: &PyEquationOfState,
+        pressures: Pressure<Array1<f64>>,
+        temperatures: Temperature<Array1<f64>>,
+    ) -> PyResult<Self> {
+        Ok(Self(
+            self.0
+                .clone()
+                .with_isobars(&eos.0, &pressures, &temperatures, Default::default())
+                .map_err(PyFeosError::from)?,
+        ))
+    }
+
+    /// Return a new diagram with additional isochores (only for pure components).
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// densities : SIArray1
+    ///     The densities of the isochores.
+    /// temperatures : SIArray1
+    ///     The temperatures along each isochore.
+    ///
+    /// Returns
+    /// -------
+    /// Diagram
+    fn with_isochores(
+        &self,
+        eos: &PyEquationOfState,
+        densities: Density<Array1<f64>>,
+        temperatures: Temperature<Array1<f64>>,
+    ) -> PyResult<Self> {
+        Ok(Self(
+            self.0
+                .clone()
+                .with_isochores(&eos.0, &densities, &temperatures, Default::default())
+                .map_err(PyFeosError::from)?,
+        ))
+    }
+
+    /// Return a new diagram with additional isentropes (only for pure components).
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// molar_entropies : SIArray1
+    ///     The molar entropies of the isentropes.
+    /// pressures : SIArray1
+    ///     The pressures along each isentrope.
+    ///
+    /// Returns
+    /// -------
+    /// Diagram
+    fn with_isentropes(
+        &self,
+        eos: &PyEquationOfState,
+        molar_entropies: MolarEntropy<Array1<f64>>,
+        pressures: Pressure<Array1<f64>>,
+    ) -> PyResult<Self> {
+        Ok(Self(
+            self.0
+                .clone()
+                .with_isentropes(&eos.0, &molar_entropies, &pressures, Default::default())
+                .map_err(PyFeosError::from)?,
+        ))
+    }
+
+    /// Serialize the diagram to JSON.
+    ///
+    /// Returns
+    /// -------
+    /// str
+    fn to_json(&self) -> PyResult<String> {
+        Ok(self.0.to_json().map_err(PyFeosError::from)?)
+    }
+
+    /// Serialize the diagram to CSV with one row per point.
+    ///
+    /// Returns
+    /// -------
+    /// str
+    fn to_csv(&self) -> String {
+        self.0.to_csv()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.lines.len()
+    }
+}
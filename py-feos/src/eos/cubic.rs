@@ -0,0 +1,167 @@
+use super::PyEquationOfState;
+use crate::{ideal_gas::IdealGasModel, parameter::PyParameters, residual::ResidualModel};
+use feos::cubic::{
+    Alpha, Cubic, DebyeHuckel, GeneralizedTwu, PengRobinson1976, PengRobinson1978,
+    PengRobinson2019, Permittivity, RedlichKwong1972, RedlichKwong2019,
+};
+use feos_core::{Components, EquationOfState};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::sync::Arc;
+
+fn alpha_function(alpha: Option<&str>, peng_robinson: bool) -> PyResult<Option<Alpha>> {
+    Ok(Some(match alpha {
+        None => return Ok(None),
+        Some("PengRobinson1976") => PengRobinson1976.into(),
+        Some("PengRobinson1978") => PengRobinson1978.into(),
+        Some("PengRobinson2019") => PengRobinson2019.into(),
+        Some("RedlichKwong1972") => RedlichKwong1972.into(),
+        Some("RedlichKwong2019") => RedlichKwong2019.into(),
+        Some("GeneralizedTwu") if peng_robinson => GeneralizedTwu::peng_robinson().into(),
+        Some("GeneralizedTwu") => GeneralizedTwu::redlich_kwong().into(),
+        Some(alpha) => {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "Unknown alpha function '{alpha}'. Valid options are 'PengRobinson1976', 'PengRobinson1978', 'PengRobinson2019', 'RedlichKwong1972', 'RedlichKwong2019' and 'GeneralizedTwu'."
+            )));
+        }
+    }))
+}
+
+#[pymethods]
+impl PyEquationOfState {
+    /// Generic cubic equation of state with Peng-Robinson parameters.
+    ///
+    /// Parameters
+    /// ----------
+    /// parameters : CubicParameters
+    ///     The parameters of the cubic equation of state to use.
+    /// alpha : str, optional
+    ///     The alpha function. Defaults to the component specific alpha
+    ///     function parameters of the pure records if available and
+    ///     'PengRobinson1976' otherwise.
+    ///
+    /// Returns
+    /// -------
+    /// EquationOfState
+    ///     The cubic equation of state that can be used to compute thermodynamic
+    ///     states.
+    #[staticmethod]
+    #[pyo3(signature = (parameters, alpha=None), text_signature = "(parameters, alpha=None)")]
+    fn cubic_peng_robinson(parameters: PyParameters, alpha: Option<&str>) -> PyResult<Self> {
+        let alpha = alpha_function(alpha, true)?;
+        let residual = Arc::new(ResidualModel::Cubic(
+            Cubic::peng_robinson(Arc::new(parameters.try_convert()?), alpha, None)
+                .map_err(crate::error::PyFeosError::from)?,
+        ));
+        let ideal_gas = Arc::new(IdealGasModel::NoModel(residual.components()));
+        Ok(Self(Arc::new(EquationOfState::new(ideal_gas, residual))))
+    }
+
+    /// Generic cubic equation of state with Redlich-Kwong parameters.
+    ///
+    /// Parameters
+    /// ----------
+    /// parameters : CubicParameters
+    ///     The parameters of the cubic equation of state to use.
+    /// alpha : str, optional
+    ///     The alpha function. Defaults to the component specific alpha
+    ///     function parameters of the pure records if available and
+    ///     'RedlichKwong1972' otherwise.
+    ///
+    /// Returns
+    /// -------
+    /// EquationOfState
+    ///     The cubic equation of state that can be used to compute thermodynamic
+    ///     states.
+    #[staticmethod]
+    #[pyo3(signature = (parameters, alpha=None), text_signature = "(parameters, alpha=None)")]
+    fn cubic_redlich_kwong(parameters: PyParameters, alpha: Option<&str>) -> PyResult<Self> {
+        let alpha = alpha_function(alpha, false)?;
+        let residual = Arc::new(ResidualModel::Cubic(
+            Cubic::redlich_kwong(Arc::new(parameters.try_convert()?), alpha, None)
+                .map_err(crate::error::PyFeosError::from)?,
+        ));
+        let ideal_gas = Arc::new(IdealGasModel::NoModel(residual.components()));
+        Ok(Self(Arc::new(EquationOfState::new(ideal_gas, residual))))
+    }
+
+    /// Peng-Robinson equation of state with the water-hydrocarbon treatment
+    /// of Søreide and Whitson (1992).
+    ///
+    /// Parameters
+    /// ----------
+    /// parameters : CubicParameters
+    ///     The parameters of the cubic equation of state to use.
+    /// water : int
+    ///     The index of water.
+    /// salinity : float, optional
+    ///     The salinity in mol NaCl per kg water. Defaults to 0.
+    ///
+    /// Returns
+    /// -------
+    /// (EquationOfState, EquationOfState)
+    ///     The equations of state for the aqueous and the non-aqueous phase.
+    #[staticmethod]
+    #[pyo3(
+        signature = (parameters, water, salinity=0.0),
+        text_signature = "(parameters, water, salinity=0.0)"
+    )]
+    fn cubic_soreide_whitson(
+        parameters: PyParameters,
+        water: usize,
+        salinity: f64,
+    ) -> PyResult<(Self, Self)> {
+        let (aqueous, non_aqueous) =
+            Cubic::soreide_whitson(Arc::new(parameters.try_convert()?), water, salinity)
+                .map_err(crate::error::PyFeosError::from)?;
+        let eos = |cubic| {
+            let residual = Arc::new(ResidualModel::Cubic(cubic));
+            let ideal_gas = Arc::new(IdealGasModel::NoModel(residual.components()));
+            Self(Arc::new(EquationOfState::new(ideal_gas, residual)))
+        };
+        Ok((eos(aqueous), eos(non_aqueous)))
+    }
+
+    /// Peng-Robinson equation of state with the electrostatic contribution
+    /// of Debye and Hückel for electrolyte solutions.
+    ///
+    /// The charges and diameters of the ions are read from the pure records.
+    ///
+    /// Parameters
+    /// ----------
+    /// parameters : CubicParameters
+    ///     The parameters of the cubic equation of state to use.
+    /// permittivity : float, optional
+    ///     The constant relative permittivity of the solvent. Defaults to
+    ///     the temperature dependent relative permittivity of water.
+    /// alpha : str, optional
+    ///     The alpha function. Defaults to the component specific alpha
+    ///     function parameters of the pure records if available and
+    ///     'PengRobinson1976' otherwise.
+    ///
+    /// Returns
+    /// -------
+    /// EquationOfState
+    ///     The cubic equation of state that can be used to compute thermodynamic
+    ///     states.
+    #[staticmethod]
+    #[pyo3(
+        signature = (parameters, permittivity=None, alpha=None),
+        text_signature = "(parameters, permittivity=None, alpha=None)"
+    )]
+    fn cubic_debye_huckel(
+        parameters: PyParameters,
+        permittivity: Option<f64>,
+        alpha: Option<&str>,
+    ) -> PyResult<Self> {
+        let alpha = alpha_function(alpha, true)?;
+        let permittivity = permittivity.map_or(Permittivity::Water, Permittivity::Constant);
+        let residual = Arc::new(ResidualModel::Cubic(
+            Cubic::peng_robinson(Arc::new(parameters.try_convert()?), alpha, None)
+                .and_then(|cubic| cubic.with_debye_huckel(DebyeHuckel::new(permittivity)))
+                .map_err(crate::error::PyFeosError::from)?,
+        ));
+        let ideal_gas = Arc::new(IdealGasModel::NoModel(residual.components()));
+        Ok(Self(Arc::new(EquationOfState::new(ideal_gas, residual))))
+    }
+}
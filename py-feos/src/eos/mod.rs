@@ -3,13 +3,15 @@ use crate::ideal_gas::IdealGasModel;
 use crate::residual::ResidualModel;
 
 use feos_core::*;
-use ndarray::Array1;
+use ndarray::{Array1, Array2};
 use pyo3::prelude::*;
 use quantity::*;
 use std::sync::Arc;
 use typenum::Quot;
 
 mod constructors;
+#[cfg(feature = "cubic")]
+mod cubic;
 #[cfg(feature = "epcsaft")]
 mod epcsaft;
 #[cfg(feature = "gc_pcsaft")]
@@ -74,6 +76,27 @@ impl PyEquationOfState {
             .map_err(PyFeosError::from)?)
     }
 
+    /// Calculate the matrix of (cross) second Virial coefficients B_ij(T).
+    ///
+    /// Parameters
+    /// ----------
+    /// temperature : SINumber
+    ///     The temperature for which B_ij should be computed.
+    ///
+    /// Returns
+    /// -------
+    /// SIArray2
+    #[pyo3(text_signature = "(temperature)")]
+    fn cross_second_virial_coefficients(
+        &self,
+        temperature: Temperature,
+    ) -> PyResult<Quot<Array2<f64>, Density>> {
+        Ok(self
+            .0
+            .cross_second_virial_coefficients(temperature)
+            .map_err(PyFeosError::from)?)
+    }
+
     /// Calculate the third Virial coefficient C(T,x).
     ///
     /// Parameters
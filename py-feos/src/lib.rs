@@ -5,6 +5,7 @@ use pyo3::prelude::*;
 
 #[cfg(feature = "dft")]
 pub(crate) mod dft;
+pub(crate) mod diagram;
 pub(crate) mod eos;
 pub(crate) mod error;
 pub(crate) mod estimator;
@@ -62,6 +63,10 @@ fn feos(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<phase_equilibria::PyPhaseDiagram>()?;
     m.add_class::<phase_equilibria::PyPhaseDiagramHetero>()?;
     m.add_class::<phase_equilibria::PyPhaseEquilibrium>()?;
+    m.add_class::<phase_equilibria::PyIsoline>()?;
+    m.add_class::<phase_equilibria::PyCriticalLocus>()?;
+    m.add_class::<phase_equilibria::PyMinimumMiscibilityPressure>()?;
+    m.add_class::<diagram::PyDiagram>()?;
 
     // Parameter
     m.add_class::<parameter::PyIdentifier>()?;
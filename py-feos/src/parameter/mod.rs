@@ -163,6 +163,73 @@ impl PyParameters {
         }
     }
 
+    /// Creates parameters with an additional component.
+    ///
+    /// The records of the existing components are reused.
+    ///
+    /// Parameters
+    /// ----------
+    /// pure_record : PureRecord
+    ///     The pure component parameters of the new component.
+    /// binary_records : List[BinaryRecord], optional, defaults to []
+    ///     Records for binary interactions of the new component with the
+    ///     existing components.
+    /// identifier_option : IdentifierOption, optional, defaults to IdentifierOption.Name
+    ///     Identifier that is used to search binary records.
+    ///
+    /// Returns
+    /// -------
+    /// Parameters
+    #[pyo3(
+        signature = (pure_record, binary_records=vec![], identifier_option=PyIdentifierOption::Name),
+        text_signature = "($self, pure_record, binary_records=[], identifier_option=IdentifierOption.Name)"
+    )]
+    fn with_component(
+        &self,
+        pure_record: PyPureRecord,
+        binary_records: Vec<PyBinaryRecord>,
+        identifier_option: PyIdentifierOption,
+    ) -> PyResult<Self> {
+        let n = self.pure_records.len();
+        let missing = |i: usize| {
+            PyFeosError::Error(format!(
+                "No {} for pure record {}.",
+                IdentifierOption::from(identifier_option),
+                i
+            ))
+        };
+        let id = pure_record
+            .identifier
+            .as_str(identifier_option)
+            .ok_or_else(|| missing(n))?;
+        let mut new_binary_records = self.binary_records.clone();
+        for br in binary_records {
+            let (id1, id2) = (
+                br.id1.as_str(identifier_option),
+                br.id2.as_str(identifier_option),
+            );
+            let other = match (id1, id2) {
+                (Some(id1), Some(id2)) if id1 == id => id2,
+                (Some(id1), Some(id2)) if id2 == id => id1,
+                _ => continue,
+            };
+            let i = self
+                .pure_records
+                .iter()
+                .position(|p| p.identifier.as_str(identifier_option) == Some(other))
+                .ok_or_else(|| {
+                    PyFeosError::from(FeosError::ComponentsNotFound(vec![other.into()]))
+                })?;
+            new_binary_records.push(([i, n], br.model_record));
+        }
+        let mut pure_records = self.pure_records.clone();
+        pure_records.push(pure_record);
+        Ok(Self {
+            pure_records,
+            binary_records: new_binary_records,
+        })
+    }
+
     /// Creates parameters from json files.
     ///
     /// Parameters
@@ -532,9 +599,12 @@ impl PyGcParameters {
             .map(|identifier| identifier as &str)
             .collect();
         if !queried.is_subset(&available) {
-            let missing: Vec<_> = queried.difference(&available).cloned().collect();
+            let missing = queried
+                .difference(&available)
+                .map(|&id| id.to_owned())
+                .collect();
             return Err(PyFeosError::FeosError(FeosError::ComponentsNotFound(
-                format!("{:?}", missing),
+                missing,
             )))?;
         };
 
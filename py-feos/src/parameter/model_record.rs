@@ -57,7 +57,12 @@ impl PyPureRecord {
         identifier_option: PyIdentifierOption,
     ) -> FeosResult<Vec<Self>> {
         // create list of substances
-        let mut queried: HashSet<_> = substances.iter().map(|s| s.to_string()).collect();
+        let option = IdentifierOption::from(identifier_option);
+        let substances: Vec<_> = substances
+            .iter()
+            .map(|s| option.normalize(s).into_owned())
+            .collect();
+        let mut queried: HashSet<_> = substances.iter().cloned().collect();
         // raise error on duplicate detection
         if queried.len() != substances.len() {
             return Err(FeosError::IncompatibleParameters(
@@ -82,13 +87,14 @@ impl PyPureRecord {
 
         // report missing parameters
         if !queried.is_empty() {
-            return Err(FeosError::ComponentsNotFound(format!("{:?}", queried)));
+            let missing = queried.into_iter().map(|id| id.to_owned()).collect();
+            return Err(FeosError::ComponentsNotFound(missing));
         };
 
         // collect into vec in correct order
         Ok(substances
             .iter()
-            .map(|s| records.get(&s.to_string()).unwrap().clone())
+            .map(|s| records.get(s).unwrap().clone())
             .collect())
     }
 }
@@ -7,10 +7,13 @@ use crate::{
     PyVerbosity,
 };
 use feos_core::{
-    Components, Contributions, EquationOfState, PhaseDiagram, PhaseDiagramHetero, PhaseEquilibrium,
+    Components, Contributions, CriticalLocus, EquationOfState, Isoline, MinimumMiscibilityPressure,
+    PhaseBehaviorType, PhaseDiagram, PhaseDiagramHetero, PhaseEquilibrium,
 };
 use ndarray::Array1;
-use numpy::{PyArray1, PyArrayMethods};
+#[cfg(feature = "rayon")]
+use ndarray::Array2;
+use numpy::{IntoPyArray, PyArray1, PyArray2, PyArrayMethods};
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use quantity::*;
@@ -158,6 +161,75 @@ impl PyPhaseEquilibrium {
         ))
     }
 
+    /// Perform Tp-flash calculations for a batch of specifications in parallel.
+    ///
+    /// A failed point does not abort the batch. Instead, the phase equilibrium
+    /// of the point is None and the error message is returned.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// temperature : SIArray1
+    ///     The temperatures of all points.
+    /// pressure : SIArray1
+    ///     The pressures of all points.
+    /// feed : SIArray2
+    ///     The feeds of all points (units of amount of substance) in rows.
+    /// nthreads : int
+    ///     The number of threads.
+    /// max_iter : int, optional
+    ///     The maximum number of iterations.
+    /// tol: float, optional
+    ///     The solution tolerance.
+    /// verbosity : Verbosity, optional
+    ///     The verbosity.
+    ///
+    /// Returns
+    /// -------
+    /// (list[PhaseEquilibrium | None], list[str | None])
+    ///     The phase equilibria and the error messages of all points.
+    #[cfg(feature = "rayon")]
+    #[staticmethod]
+    #[pyo3(
+        text_signature = "(eos, temperature, pressure, feed, nthreads, max_iter=None, tol=None, verbosity=None)"
+    )]
+    #[pyo3(signature = (eos, temperature, pressure, feed, nthreads, max_iter=None, tol=None, verbosity=None))]
+    #[expect(clippy::too_many_arguments)]
+    #[expect(clippy::type_complexity)]
+    pub(crate) fn flash_many(
+        eos: PyEquationOfState,
+        temperature: Temperature<Array1<f64>>,
+        pressure: Pressure<Array1<f64>>,
+        feed: Moles<Array2<f64>>,
+        nthreads: usize,
+        max_iter: Option<usize>,
+        tol: Option<f64>,
+        verbosity: Option<PyVerbosity>,
+    ) -> PyResult<(Vec<Option<Self>>, Vec<Option<String>>)> {
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(nthreads)
+            .build()
+            .map_err(PyFeosError::from)?;
+        let batch = PhaseEquilibrium::flash_many(
+            &eos.0,
+            &temperature,
+            &pressure,
+            &feed,
+            &thread_pool,
+            (max_iter, tol, verbosity.map(|v| v.into())).into(),
+        )
+        .map_err(PyFeosError::from)?;
+        Ok(batch
+            .results
+            .into_iter()
+            .map(|r| match r {
+                Ok(vle) => (Some(Self(vle)), None),
+                Err(e) => (None, Some(e.to_string())),
+            })
+            .unzip())
+    }
+
     /// Compute a phase equilibrium for given temperature
     /// or pressure and liquid mole fractions.
     ///
@@ -334,6 +406,184 @@ impl PyPhaseEquilibrium {
         }
     }
 
+    /// Compute a phase equilibrium for given temperature
+    /// or pressure, feed mole fractions and vapor fraction.
+    ///
+    /// The temperature or pressure that is not specified is determined
+    /// such that the feed splits into a vapor phase with the given
+    /// vapor fraction and a liquid phase.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// temperature_or_pressure : SINumber
+    ///     The system temperature or pressure.
+    /// feed_molefracs : numpy.ndarray
+    ///     The mole fraction of the feed.
+    /// vapor_fraction : float
+    ///     The molar vapor fraction between 0 and 1.
+    /// tp_init : SINumber, optional
+    ///     The system pressure/temperature used as starting
+    ///     condition for the iteration.
+    /// max_iter_inner : int, optional
+    ///     The maximum number of inner iterations.
+    /// max_iter_outer : int, optional
+    ///     The maximum number of outer iterations.
+    /// tol_inner : float, optional
+    ///     The solution tolerance in the inner loop.
+    /// tol_outer : float, optional
+    ///     The solution tolerance in the outer loop.
+    /// verbosity : Verbosity, optional
+    ///     The verbosity.
+    ///
+    /// Returns
+    /// -------
+    /// PhaseEquilibrium
+    #[staticmethod]
+    #[pyo3(
+        text_signature = "(eos, temperature_or_pressure, feed_molefracs, vapor_fraction, tp_init=None, max_iter_inner=None, max_iter_outer=None, tol_inner=None, tol_outer=None, verbosity=None)"
+    )]
+    #[pyo3(signature = (eos, temperature_or_pressure, feed_molefracs, vapor_fraction, tp_init=None, max_iter_inner=None, max_iter_outer=None, tol_inner=None, tol_outer=None, verbosity=None))]
+    #[expect(clippy::too_many_arguments)]
+    pub(crate) fn beta_flash<'py>(
+        eos: PyEquationOfState,
+        temperature_or_pressure: Bound<'_, PyAny>,
+        feed_molefracs: &Bound<'py, PyArray1<f64>>,
+        vapor_fraction: f64,
+        tp_init: Option<Bound<'_, PyAny>>,
+        max_iter_inner: Option<usize>,
+        max_iter_outer: Option<usize>,
+        tol_inner: Option<f64>,
+        tol_outer: Option<f64>,
+        verbosity: Option<PyVerbosity>,
+    ) -> PyResult<Self> {
+        let options = (
+            (max_iter_inner, tol_inner, verbosity.map(|v| v.into())).into(),
+            (max_iter_outer, tol_outer, verbosity.map(|v| v.into())).into(),
+        );
+        if let Ok(t) = temperature_or_pressure.extract::<Temperature>() {
+            Ok(Self(
+                PhaseEquilibrium::beta_flash(
+                    &eos.0,
+                    t,
+                    &feed_molefracs.to_owned_array(),
+                    vapor_fraction,
+                    tp_init.map(|p| p.extract()).transpose()?,
+                    options,
+                )
+                .map_err(PyFeosError::from)?,
+            ))
+        } else if let Ok(p) = temperature_or_pressure.extract::<Pressure>() {
+            Ok(Self(
+                PhaseEquilibrium::beta_flash(
+                    &eos.0,
+                    p,
+                    &feed_molefracs.to_owned_array(),
+                    vapor_fraction,
+                    tp_init.map(|p| p.extract()).transpose()?,
+                    options,
+                )
+                .map_err(PyFeosError::from)?,
+            ))
+        } else {
+            Err(PyErr::new::<PyValueError, _>(format!(
+                "Wrong units! Expected K or Pa, got {}.",
+                temperature_or_pressure.call_method0("__repr__")?
+            )))
+        }
+    }
+
+    /// Compute the cricondentherm, i.e., the point with the maximum
+    /// temperature on the phase envelope of a mixture.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// moles : SIArray1
+    ///     The moles of the individual components.
+    /// initial_state : PhaseEquilibrium, optional
+    ///     A bubble or dew point used as starting point of the iteration.
+    /// max_iter : int, optional
+    ///     The maximum number of iterations.
+    /// tol: float, optional
+    ///     The solution tolerance.
+    /// verbosity : Verbosity, optional
+    ///     The verbosity.
+    ///
+    /// Returns
+    /// -------
+    /// PhaseEquilibrium
+    #[staticmethod]
+    #[pyo3(
+        text_signature = "(eos, moles, initial_state=None, max_iter=None, tol=None, verbosity=None)"
+    )]
+    #[pyo3(signature = (eos, moles, initial_state=None, max_iter=None, tol=None, verbosity=None))]
+    pub(crate) fn cricondentherm(
+        eos: &PyEquationOfState,
+        moles: Moles<Array1<f64>>,
+        initial_state: Option<&PyPhaseEquilibrium>,
+        max_iter: Option<usize>,
+        tol: Option<f64>,
+        verbosity: Option<PyVerbosity>,
+    ) -> PyResult<Self> {
+        Ok(Self(
+            PhaseEquilibrium::cricondentherm(
+                &eos.0,
+                &moles,
+                initial_state.map(|s| &s.0),
+                (max_iter, tol, verbosity.map(|v| v.into())).into(),
+            )
+            .map_err(PyFeosError::from)?,
+        ))
+    }
+
+    /// Compute the cricondenbar, i.e., the point with the maximum
+    /// pressure on the phase envelope of a mixture.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// moles : SIArray1
+    ///     The moles of the individual components.
+    /// initial_state : PhaseEquilibrium, optional
+    ///     A bubble or dew point used as starting point of the iteration.
+    /// max_iter : int, optional
+    ///     The maximum number of iterations.
+    /// tol: float, optional
+    ///     The solution tolerance.
+    /// verbosity : Verbosity, optional
+    ///     The verbosity.
+    ///
+    /// Returns
+    /// -------
+    /// PhaseEquilibrium
+    #[staticmethod]
+    #[pyo3(
+        text_signature = "(eos, moles, initial_state=None, max_iter=None, tol=None, verbosity=None)"
+    )]
+    #[pyo3(signature = (eos, moles, initial_state=None, max_iter=None, tol=None, verbosity=None))]
+    pub(crate) fn cricondenbar(
+        eos: &PyEquationOfState,
+        moles: Moles<Array1<f64>>,
+        initial_state: Option<&PyPhaseEquilibrium>,
+        max_iter: Option<usize>,
+        tol: Option<f64>,
+        verbosity: Option<PyVerbosity>,
+    ) -> PyResult<Self> {
+        Ok(Self(
+            PhaseEquilibrium::cricondenbar(
+                &eos.0,
+                &moles,
+                initial_state.map(|s| &s.0),
+                (max_iter, tol, verbosity.map(|v| v.into())).into(),
+            )
+            .map_err(PyFeosError::from)?,
+        ))
+    }
+
     /// Creates a new PhaseEquilibrium that contains two states at the
     /// specified temperature, pressure and moles.
     ///
@@ -381,6 +631,42 @@ impl PyPhaseEquilibrium {
         PyState(self.0.liquid().clone())
     }
 
+    /// Return the K-values y_i/x_i.
+    ///
+    /// Returns
+    /// -------
+    /// numpy.ndarray[Float64]
+    fn k_values<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        self.0.k_values().into_pyarray(py)
+    }
+
+    /// Return the relative volatilities K_i/K_j.
+    ///
+    /// Returns
+    /// -------
+    /// numpy.ndarray[Float64]
+    fn relative_volatilities<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        self.0.relative_volatilities().into_pyarray(py)
+    }
+
+    /// Return the molar phase fractions of the vapor and the liquid.
+    ///
+    /// Returns
+    /// -------
+    /// numpy.ndarray[Float64]
+    fn phase_fractions<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        self.0.phase_fractions().into_pyarray(py)
+    }
+
+    /// Return the mass fractions of the vapor and the liquid.
+    ///
+    /// Returns
+    /// -------
+    /// numpy.ndarray[Float64]
+    fn phase_mass_fractions<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        self.0.phase_mass_fractions().into_pyarray(py)
+    }
+
     /// Calculate the pure component vapor-liquid equilibria for all
     /// components in the system.
     ///
@@ -596,6 +882,41 @@ impl PyThreePhaseEquilibrium {
         PyState(self.0.liquid2().clone())
     }
 
+    /// Return the molar phase fractions of the vapor and the two liquids.
+    ///
+    /// Returns
+    /// -------
+    /// numpy.ndarray[Float64]
+    fn phase_fractions<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        self.0.phase_fractions().into_pyarray(py)
+    }
+
+    /// Return the distribution coefficients x_i^(phase)/x_i^(reference)
+    /// between two phases.
+    ///
+    /// Parameters
+    /// ----------
+    /// phase : int
+    ///     Index of the phase (0: vapor, 1: liquid1, 2: liquid2).
+    /// reference : int
+    ///     Index of the reference phase.
+    ///
+    /// Returns
+    /// -------
+    /// numpy.ndarray[Float64]
+    fn distribution_coefficients<'py>(
+        &self,
+        py: Python<'py>,
+        phase: usize,
+        reference: usize,
+    ) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        Ok(self
+            .0
+            .distribution_coefficients(phase, reference)
+            .map_err(PyFeosError::from)?
+            .into_pyarray(py))
+    }
+
     fn _repr_markdown_(&self) -> String {
         self.0._repr_markdown_()
     }
@@ -1457,3 +1778,546 @@ impl PyPhaseDiagramHetero {
         self.0.lle.as_ref().map(|d| PyPhaseDiagram(d.clone()))
     }
 }
+
+/// Isotherms, isobars, isochores and isentropes of a pure component.
+///
+/// Crossings of the phase boundary are handled explicitly by inserting
+/// the saturation points. Points inside the two-phase region are
+/// represented by the coexisting phases and the molar vapor fraction.
+#[pyclass(name = "Isoline")]
+pub struct PyIsoline(Isoline<EquationOfState<IdealGasModel, ResidualModel>>);
+
+#[pymethods]
+impl PyIsoline {
+    /// Calculate an isotherm for the given pressures.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// temperature : SINumber
+    ///     The temperature of the isotherm.
+    /// pressures : SIArray1
+    ///     The pressures along the isotherm.
+    /// max_iter : int, optional
+    ///     The maximum number of iterations.
+    /// tol: float, optional
+    ///     The solution tolerance.
+    /// verbosity : Verbosity, optional
+    ///     The verbosity.
+    ///
+    /// Returns
+    /// -------
+    /// Isoline
+    #[staticmethod]
+    #[pyo3(
+        text_signature = "(eos, temperature, pressures, max_iter=None, tol=None, verbosity=None)"
+    )]
+    #[pyo3(signature = (eos, temperature, pressures, max_iter=None, tol=None, verbosity=None))]
+    fn isotherm(
+        eos: &PyEquationOfState,
+        temperature: Temperature,
+        pressures: Pressure<Array1<f64>>,
+        max_iter: Option<usize>,
+        tol: Option<f64>,
+        verbosity: Option<PyVerbosity>,
+    ) -> PyResult<Self> {
+        Ok(Self(
+            Isoline::isotherm(
+                &eos.0,
+                temperature,
+                &pressures,
+                (max_iter, tol, verbosity.map(|v| v.into())).into(),
+            )
+            .map_err(PyFeosError::from)?,
+        ))
+    }
+
+    /// Calculate an isobar for the given temperatures.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// pressure : SINumber
+    ///     The pressure of the isobar.
+    /// temperatures : SIArray1
+    ///     The temperatures along the isobar.
+    /// max_iter : int, optional
+    ///     The maximum number of iterations.
+    /// tol: float, optional
+    ///     The solution tolerance.
+    /// verbosity : Verbosity, optional
+    ///     The verbosity.
+    ///
+    /// Returns
+    /// -------
+    /// Isoline
+    #[staticmethod]
+    #[pyo3(
+        text_signature = "(eos, pressure, temperatures, max_iter=None, tol=None, verbosity=None)"
+    )]
+    #[pyo3(signature = (eos, pressure, temperatures, max_iter=None, tol=None, verbosity=None))]
+    fn isobar(
+        eos: &PyEquationOfState,
+        pressure: Pressure,
+        temperatures: Temperature<Array1<f64>>,
+        max_iter: Option<usize>,
+        tol: Option<f64>,
+        verbosity: Option<PyVerbosity>,
+    ) -> PyResult<Self> {
+        Ok(Self(
+            Isoline::isobar(
+                &eos.0,
+                pressure,
+                &temperatures,
+                (max_iter, tol, verbosity.map(|v| v.into())).into(),
+            )
+            .map_err(PyFeosError::from)?,
+        ))
+    }
+
+    /// Calculate an isochore for the given temperatures.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// density : SINumber
+    ///     The density of the isochore.
+    /// temperatures : SIArray1
+    ///     The temperatures along the isochore.
+    /// max_iter : int, optional
+    ///     The maximum number of iterations.
+    /// tol: float, optional
+    ///     The solution tolerance.
+    /// verbosity : Verbosity, optional
+    ///     The verbosity.
+    ///
+    /// Returns
+    /// -------
+    /// Isoline
+    #[staticmethod]
+    #[pyo3(
+        text_signature = "(eos, density, temperatures, max_iter=None, tol=None, verbosity=None)"
+    )]
+    #[pyo3(signature = (eos, density, temperatures, max_iter=None, tol=None, verbosity=None))]
+    fn isochore(
+        eos: &PyEquationOfState,
+        density: Density,
+        temperatures: Temperature<Array1<f64>>,
+        max_iter: Option<usize>,
+        tol: Option<f64>,
+        verbosity: Option<PyVerbosity>,
+    ) -> PyResult<Self> {
+        Ok(Self(
+            Isoline::isochore(
+                &eos.0,
+                density,
+                &temperatures,
+                (max_iter, tol, verbosity.map(|v| v.into())).into(),
+            )
+            .map_err(PyFeosError::from)?,
+        ))
+    }
+
+    /// Calculate an isentrope for the given pressures.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// molar_entropy : SINumber
+    ///     The molar entropy of the isentrope.
+    /// pressures : SIArray1
+    ///     The pressures along the isentrope.
+    /// max_iter : int, optional
+    ///     The maximum number of iterations.
+    /// tol: float, optional
+    ///     The solution tolerance.
+    /// verbosity : Verbosity, optional
+    ///     The verbosity.
+    ///
+    /// Returns
+    /// -------
+    /// Isoline
+    #[staticmethod]
+    #[pyo3(
+        text_signature = "(eos, molar_entropy, pressures, max_iter=None, tol=None, verbosity=None)"
+    )]
+    #[pyo3(signature = (eos, molar_entropy, pressures, max_iter=None, tol=None, verbosity=None))]
+    fn isentrope(
+        eos: &PyEquationOfState,
+        molar_entropy: MolarEntropy,
+        pressures: Pressure<Array1<f64>>,
+        max_iter: Option<usize>,
+        tol: Option<f64>,
+        verbosity: Option<PyVerbosity>,
+    ) -> PyResult<Self> {
+        Ok(Self(
+            Isoline::isentrope(
+                &eos.0,
+                molar_entropy,
+                &pressures,
+                (max_iter, tol, verbosity.map(|v| v.into())).into(),
+            )
+            .map_err(PyFeosError::from)?,
+        ))
+    }
+
+    /// Calculate the heating curve of a mixture at constant pressure.
+    ///
+    /// The curve consists of subcooled liquid states up to the bubble
+    /// point, points at evenly spaced vapor fractions between the bubble
+    /// and the dew point, and superheated vapor states above the dew point.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// moles : SIArray1
+    ///     The amount of substance of each component.
+    /// pressure : SINumber
+    ///     The pressure of the heating curve.
+    /// min_temperature : SINumber
+    ///     The temperature of the subcooled liquid.
+    /// max_temperature : SINumber
+    ///     The temperature of the superheated vapor.
+    /// npoints : int, optional
+    ///     The number of points in each single phase segment.
+    ///     Defaults to 10.
+    /// vapor_fractions : int, optional
+    ///     The number of points in the two-phase region.
+    ///     Defaults to 21.
+    /// max_iter : int, optional
+    ///     The maximum number of iterations.
+    /// tol: float, optional
+    ///     The solution tolerance.
+    /// verbosity : Verbosity, optional
+    ///     The verbosity.
+    ///
+    /// Returns
+    /// -------
+    /// Isoline
+    #[staticmethod]
+    #[pyo3(
+        text_signature = "(eos, moles, pressure, min_temperature, max_temperature, npoints=10, vapor_fractions=21, max_iter=None, tol=None, verbosity=None)"
+    )]
+    #[pyo3(signature = (eos, moles, pressure, min_temperature, max_temperature, npoints=10, vapor_fractions=21, max_iter=None, tol=None, verbosity=None))]
+    #[expect(clippy::too_many_arguments)]
+    fn heating_curve(
+        eos: &PyEquationOfState,
+        moles: Moles<Array1<f64>>,
+        pressure: Pressure,
+        min_temperature: Temperature,
+        max_temperature: Temperature,
+        npoints: usize,
+        vapor_fractions: usize,
+        max_iter: Option<usize>,
+        tol: Option<f64>,
+        verbosity: Option<PyVerbosity>,
+    ) -> PyResult<Self> {
+        Ok(Self(
+            Isoline::heating_curve(
+                &eos.0,
+                &moles,
+                pressure,
+                (min_temperature, max_temperature),
+                npoints,
+                vapor_fractions,
+                (max_iter, tol, verbosity.map(|v| v.into())).into(),
+            )
+            .map_err(PyFeosError::from)?,
+        ))
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.points.len()
+    }
+
+    #[getter]
+    fn get_temperature(&self) -> Temperature<Array1<f64>> {
+        self.0.temperature()
+    }
+
+    #[getter]
+    fn get_pressure(&self) -> Pressure<Array1<f64>> {
+        self.0.pressure()
+    }
+
+    #[getter]
+    fn get_density(&self) -> Density<Array1<f64>> {
+        self.0.density()
+    }
+
+    #[getter]
+    fn get_vapor_fraction<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_owned_array(py, self.0.vapor_fraction())
+    }
+
+    /// Return molar enthalpy.
+    ///
+    /// Parameters
+    /// ----------
+    /// contributions: Contributions, optional
+    ///     the contributions of the Helmholtz energy.
+    ///     Defaults to Contributions.Total.
+    ///
+    /// Returns
+    /// -------
+    /// SIArray1
+    #[pyo3(signature = (contributions=PyContributions::Total), text_signature = "($self, contributions)")]
+    fn molar_enthalpy(&self, contributions: PyContributions) -> MolarEnergy<Array1<f64>> {
+        self.0.molar_enthalpy(contributions.into())
+    }
+
+    /// Return molar entropy.
+    ///
+    /// Parameters
+    /// ----------
+    /// contributions: Contributions, optional
+    ///     the contributions of the Helmholtz energy.
+    ///     Defaults to Contributions.Total.
+    ///
+    /// Returns
+    /// -------
+    /// SIArray1
+    #[pyo3(signature = (contributions=PyContributions::Total), text_signature = "($self, contributions)")]
+    fn molar_entropy(&self, contributions: PyContributions) -> MolarEntropy<Array1<f64>> {
+        self.0.molar_entropy(contributions.into())
+    }
+
+    /// Returns the isoline as dictionary.
+    ///
+    /// Parameters
+    /// ----------
+    /// contributions : Contributions, optional
+    ///     The contributions to consider when calculating properties.
+    ///     Defaults to Contributions.Total.
+    ///
+    /// Returns
+    /// -------
+    /// Dict[str, List[float]]
+    ///     Keys: property names. Values: property for each point.
+    ///
+    /// Notes
+    /// -----
+    /// - temperature : K
+    /// - pressure : Pa
+    /// - density : mol / m³
+    /// - molar enthalpy : kJ / mol
+    /// - molar entropy : kJ / mol / K
+    /// - vapor fraction : NaN for single phase states
+    #[pyo3(signature = (contributions=PyContributions::Total), text_signature = "($self, contributions)")]
+    fn to_dict(&self, contributions: PyContributions) -> HashMap<String, Vec<f64>> {
+        let c = Contributions::from(contributions);
+        let mut dict = HashMap::with_capacity(6);
+        dict.insert(
+            String::from("temperature"),
+            self.0
+                .temperature()
+                .convert_to(KELVIN)
+                .into_raw_vec_and_offset()
+                .0,
+        );
+        dict.insert(
+            String::from("pressure"),
+            self.0
+                .pressure()
+                .convert_to(PASCAL)
+                .into_raw_vec_and_offset()
+                .0,
+        );
+        dict.insert(
+            String::from("density"),
+            self.0
+                .density()
+                .convert_to(MOL / METER.powi::<P3>())
+                .into_raw_vec_and_offset()
+                .0,
+        );
+        dict.insert(
+            String::from("molar enthalpy"),
+            self.0
+                .molar_enthalpy(c)
+                .convert_to(KILO * JOULE / MOL)
+                .into_raw_vec_and_offset()
+                .0,
+        );
+        dict.insert(
+            String::from("molar entropy"),
+            self.0
+                .molar_entropy(c)
+                .convert_to(KILO * JOULE / KELVIN / MOL)
+                .into_raw_vec_and_offset()
+                .0,
+        );
+        dict.insert(
+            String::from("vapor fraction"),
+            self.0.vapor_fraction().into_raw_vec_and_offset().0,
+        );
+        dict
+    }
+}
+
+/// The critical locus of a binary mixture.
+///
+/// The critical line is traced from the critical points of the pure
+/// components. If it does not connect both critical points, it consists
+/// of two branches.
+#[pyclass(name = "CriticalLocus")]
+pub struct PyCriticalLocus(CriticalLocus<EquationOfState<IdealGasModel, ResidualModel>>);
+
+#[pymethods]
+impl PyCriticalLocus {
+    /// Trace the critical line of a binary mixture.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// npoints : int, optional
+    ///     The number of points for a continuous critical line (defaults to 51).
+    /// max_pressure : SINumber, optional
+    ///     The pressure at which a branch of the critical line is terminated.
+    /// max_iter : int, optional
+    ///     The maximum number of iterations.
+    /// tol: float, optional
+    ///     The solution tolerance.
+    /// verbosity : Verbosity, optional
+    ///     The verbosity.
+    ///
+    /// Returns
+    /// -------
+    /// CriticalLocus
+    #[staticmethod]
+    #[pyo3(
+        text_signature = "(eos, npoints=None, max_pressure=None, max_iter=None, tol=None, verbosity=None)"
+    )]
+    #[pyo3(signature = (eos, npoints=None, max_pressure=None, max_iter=None, tol=None, verbosity=None))]
+    fn binary(
+        eos: &PyEquationOfState,
+        npoints: Option<usize>,
+        max_pressure: Option<Pressure>,
+        max_iter: Option<usize>,
+        tol: Option<f64>,
+        verbosity: Option<PyVerbosity>,
+    ) -> PyResult<Self> {
+        Ok(Self(
+            CriticalLocus::binary(
+                &eos.0,
+                npoints,
+                max_pressure,
+                (max_iter, tol, verbosity.map(|v| v.into())).into(),
+            )
+            .map_err(PyFeosError::from)?,
+        ))
+    }
+
+    /// The branches of the critical line, each starting at the
+    /// critical point of a pure component.
+    #[getter]
+    fn get_branches(&self) -> Vec<PyStateVec> {
+        (0..self.0.branches.len())
+            .map(|i| self.0.branch(i).into())
+            .collect()
+    }
+
+    /// The phase behavior type of the mixture.
+    ///
+    /// One of 'continuous' (types I and II), 'type III' or 'type IV or V'.
+    #[getter]
+    fn get_phase_behavior(&self) -> &'static str {
+        match self.0.phase_behavior {
+            PhaseBehaviorType::Continuous => "continuous",
+            PhaseBehaviorType::TypeIII => "type III",
+            PhaseBehaviorType::TypeIVOrV => "type IV or V",
+        }
+    }
+}
+
+/// The minimum miscibility pressure (MMP) of an oil and an injection gas.
+///
+/// The MMP is estimated from the lengths of the shortest tie lines in a
+/// sequence of mixing cells.
+#[pyclass(name = "MinimumMiscibilityPressure")]
+pub struct PyMinimumMiscibilityPressure(MinimumMiscibilityPressure);
+
+#[pymethods]
+impl PyMinimumMiscibilityPressure {
+    /// Calculate the MMP at given temperature with the multiple-mixing-cell method.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : EquationOfState
+    ///     The equation of state.
+    /// temperature : SINumber
+    ///     The temperature.
+    /// oil : SIArray1
+    ///     The amount of substance of each component in the oil.
+    /// gas : SIArray1
+    ///     The amount of substance of each component in the injection gas.
+    /// pressures : SIArray1
+    ///     The pressures at which the tie line lengths are calculated.
+    /// contacts : int, optional
+    ///     The number of contacts between gas and oil (defaults to 20).
+    /// max_iter : int, optional
+    ///     The maximum number of iterations of the flash calculations.
+    /// tol: float, optional
+    ///     The solution tolerance of the flash calculations.
+    /// verbosity : Verbosity, optional
+    ///     The verbosity of the flash calculations.
+    ///
+    /// Returns
+    /// -------
+    /// MinimumMiscibilityPressure
+    #[staticmethod]
+    #[pyo3(
+        text_signature = "(eos, temperature, oil, gas, pressures, contacts=None, max_iter=None, tol=None, verbosity=None)"
+    )]
+    #[pyo3(signature = (eos, temperature, oil, gas, pressures, contacts=None, max_iter=None, tol=None, verbosity=None))]
+    #[expect(clippy::too_many_arguments)]
+    fn multiple_mixing_cell(
+        eos: &PyEquationOfState,
+        temperature: Temperature,
+        oil: Moles<Array1<f64>>,
+        gas: Moles<Array1<f64>>,
+        pressures: Pressure<Array1<f64>>,
+        contacts: Option<usize>,
+        max_iter: Option<usize>,
+        tol: Option<f64>,
+        verbosity: Option<PyVerbosity>,
+    ) -> PyResult<Self> {
+        Ok(Self(
+            MinimumMiscibilityPressure::multiple_mixing_cell(
+                &eos.0,
+                temperature,
+                &oil,
+                &gas,
+                &pressures,
+                contacts,
+                (max_iter, tol, verbosity.map(|v| v.into())).into(),
+            )
+            .map_err(PyFeosError::from)?,
+        ))
+    }
+
+    /// The pressures at which the mixing cells are evaluated.
+    #[getter]
+    fn get_pressure(&self) -> Pressure<Array1<f64>> {
+        self.0.pressure.clone()
+    }
+
+    /// The length of the shortest tie line at each pressure.
+    #[getter]
+    fn get_tie_line_length<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_owned_array(py, self.0.tie_line_length.clone())
+    }
+
+    /// The extrapolated minimum miscibility pressure or None, if it
+    /// could not be determined.
+    #[getter]
+    fn get_mmp(&self) -> Option<Pressure> {
+        self.0.mmp
+    }
+}
@@ -33,6 +33,10 @@ pub enum ResidualModel {
     #[implement(molar_weight)]
     PengRobinson(PengRobinson),
 
+    #[cfg(feature = "cubic")]
+    #[implement(molar_weight)]
+    Cubic(feos::cubic::Cubic),
+
     #[implement(molar_weight)]
     Python(crate::user_defined::PyResidual),
 
@@ -3,7 +3,8 @@ use crate::{
     PyVerbosity,
 };
 use feos_core::{
-    Components, Contributions, DensityInitialization, EquationOfState, FeosError, State, StateVec,
+    Components, Contributions, DeadState, DensityInitialization, EquationOfState, FeosError, State,
+    StateExpression, StateVec,
 };
 use ndarray::{Array1, Array2};
 use numpy::{IntoPyArray, PyArray1, PyArray2, PyArrayMethods, ToPyArray};
@@ -98,6 +99,23 @@ impl From<PyContributions> for Contributions {
 #[derive(Clone)]
 pub struct PyState(pub State<EquationOfState<IdealGasModel, ResidualModel>>);
 
+impl PyState {
+    fn dead_state(
+        &self,
+        temperature: Temperature,
+        pressure: Pressure,
+        molefracs: &Bound<'_, PyArray1<f64>>,
+    ) -> PyResult<DeadState<EquationOfState<IdealGasModel, ResidualModel>>> {
+        Ok(DeadState::new(
+            &self.0.eos,
+            temperature,
+            pressure,
+            &molefracs.to_owned_array(),
+        )
+        .map_err(PyFeosError::from)?)
+    }
+}
+
 #[pymethods]
 impl PyState {
     #[new]
@@ -419,6 +437,45 @@ impl PyState {
             .map_err(PyFeosError::from)?)
     }
 
+    /// Performs an exhaustive stability analysis starting from a
+    /// regular lattice of trial compositions.
+    ///
+    /// Parameters
+    /// ----------
+    /// divisions : int, optional
+    ///     The number of intervals of the composition lattice per component.
+    ///     Defaults to 10.
+    /// max_iter : int, optional
+    ///     The maximum number of iterations.
+    /// tol: float, optional
+    ///     The solution tolerance.
+    /// verbosity : Verbosity, optional
+    ///     The verbosity.
+    ///
+    /// Returns
+    /// -------
+    /// List[State]
+    #[pyo3(text_signature = "(divisions=10, max_iter=None, tol=None, verbosity=None)")]
+    #[pyo3(signature = (divisions=10, max_iter=None, tol=None, verbosity=None))]
+    fn global_stability_analysis(
+        &self,
+        divisions: usize,
+        max_iter: Option<usize>,
+        tol: Option<f64>,
+        verbosity: Option<PyVerbosity>,
+    ) -> PyResult<Vec<Self>> {
+        Ok(self
+            .0
+            .global_stability_analysis(
+                divisions,
+                (max_iter, tol, verbosity.map(|v| v.into())).into(),
+            )
+            .map_err(PyFeosError::from)?
+            .into_iter()
+            .map(Self)
+            .collect())
+    }
+
     /// Return pressure.
     ///
     /// Parameters
@@ -460,6 +517,39 @@ impl PyState {
         self.0.compressibility(contributions.into())
     }
 
+    /// Evaluate a user defined quantity given as expression.
+    ///
+    /// Expressions combine the names of state quantities in SI units
+    /// (e.g., `T`, `p`, `rho`, `dp_dT`, `h_res`), the constants `R` and
+    /// `pi`, the functions `sqrt`, `exp`, `ln`, `log10`, `abs`, `pow`,
+    /// `min` and `max`, and the operators `+`, `-`, `*`, `/` and `^`.
+    /// Component specific quantities are indexed, e.g., `x[0]`.
+    /// Caloric quantities are residual properties.
+    ///
+    /// Parameters
+    /// ----------
+    /// expression : str
+    ///     The expression, e.g., 'dp_dT / rho'.
+    ///
+    /// Returns
+    /// -------
+    /// float
+    fn evaluate(&self, expression: &str) -> PyResult<f64> {
+        let expression = StateExpression::new(expression).map_err(PyFeosError::from)?;
+        Ok(expression.evaluate(&self.0).map_err(PyFeosError::from)?)
+    }
+
+    /// Names, units and descriptions of the quantities that can be used
+    /// in expressions.
+    ///
+    /// Returns
+    /// -------
+    /// list[tuple[str, str, str]]
+    #[staticmethod]
+    fn expression_variables() -> Vec<(String, &'static str, &'static str)> {
+        StateExpression::variables()
+    }
+
     /// Return partial derivative of pressure w.r.t. volume.
     ///
     /// Parameters
@@ -679,6 +769,102 @@ impl PyState {
             .into_pyarray(py))
     }
 
+    /// Return molar excess Gibbs energy relative to the pure components
+    /// at the same temperature and pressure.
+    ///
+    /// Returns
+    /// -------
+    /// SINumber
+    fn excess_molar_gibbs_energy(&self) -> PyResult<MolarEnergy> {
+        Ok(self
+            .0
+            .excess_molar_gibbs_energy()
+            .map_err(PyFeosError::from)?)
+    }
+
+    /// Return molar excess enthalpy relative to the pure components
+    /// at the same temperature and pressure.
+    ///
+    /// Returns
+    /// -------
+    /// SINumber
+    fn excess_molar_enthalpy(&self) -> PyResult<MolarEnergy> {
+        Ok(self.0.excess_molar_enthalpy().map_err(PyFeosError::from)?)
+    }
+
+    /// Return molar excess volume relative to the pure components
+    /// at the same temperature and pressure.
+    ///
+    /// Returns
+    /// -------
+    /// SINumber
+    fn excess_molar_volume(&self) -> PyResult<MolarVolume> {
+        Ok(self.0.excess_molar_volume().map_err(PyFeosError::from)?)
+    }
+
+    /// Return the logarithmic activity coefficient at infinite dilution of every
+    /// solute (x_i=0) in a given solvent (x_i>0).
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : Eos
+    ///     The equation of state to use.
+    /// temperature : SINumber
+    ///     Temperature.
+    /// pressure : SINumber
+    ///     Pressure.
+    /// molefracs : np.ndarray[float]
+    ///     Composition of the solvent including x_i=0 for solutes.
+    ///
+    /// Returns
+    /// -------
+    /// numpy.ndarray
+    #[staticmethod]
+    fn ln_activity_coefficient_infinite_dilution<'py>(
+        py: Python<'py>,
+        eos: PyEquationOfState,
+        temperature: Temperature,
+        pressure: Pressure,
+        molefracs: &Bound<'_, PyArray1<f64>>,
+    ) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        Ok(State::ln_activity_coefficient_infinite_dilution(
+            &eos.0,
+            temperature,
+            pressure,
+            &molefracs.to_owned_array(),
+        )
+        .map_err(PyFeosError::from)?
+        .into_pyarray(py))
+    }
+
+    /// Return the logarithmic activity coefficient at infinite dilution of a
+    /// binary system, assuming the first component is the solute and the
+    /// second component is the solvent.
+    ///
+    /// Parameters
+    /// ----------
+    /// eos : Eos
+    ///     The equation of state to use.
+    /// temperature : SINumber
+    ///     Temperature.
+    /// pressure : SINumber
+    ///     Pressure.
+    ///
+    /// Returns
+    /// -------
+    /// float
+    #[staticmethod]
+    fn ln_activity_coefficient_infinite_dilution_binary(
+        eos: PyEquationOfState,
+        temperature: Temperature,
+        pressure: Pressure,
+    ) -> PyResult<f64> {
+        Ok(
+            State::ln_activity_coefficient_infinite_dilution_binary(&eos.0, temperature, pressure)
+                .map_err(PyFeosError::from)?,
+        )
+    }
+
     /// Return Henry's law constant of every solute (x_i=0) for a given solvent (x_i>0).
     ///
     /// Parameters
@@ -762,6 +948,22 @@ impl PyState {
         self.0.thermodynamic_factor().into_pyarray(py)
     }
 
+    /// Return isochoric heat capacity.
+    ///
+    /// Parameters
+    /// ----------
+    /// contributions: Contributions, optional
+    ///     the contributions of the Helmholtz energy.
+    ///     Defaults to Contributions.Total.
+    ///
+    /// Returns
+    /// -------
+    /// SINumber
+    #[pyo3(signature = (contributions=PyContributions::Total), text_signature = "($self, contributions)")]
+    fn isochoric_heat_capacity(&self, contributions: PyContributions) -> Entropy {
+        self.0.isochoric_heat_capacity(contributions.into())
+    }
+
     /// Return molar isochoric heat capacity.
     ///
     /// Parameters
@@ -794,6 +996,22 @@ impl PyState {
         self.0.dc_v_dt(contributions.into())
     }
 
+    /// Return isobaric heat capacity.
+    ///
+    /// Parameters
+    /// ----------
+    /// contributions: Contributions, optional
+    ///     the contributions of the Helmholtz energy.
+    ///     Defaults to Contributions.Total.
+    ///
+    /// Returns
+    /// -------
+    /// SINumber
+    #[pyo3(signature = (contributions=PyContributions::Total), text_signature = "($self, contributions)")]
+    fn isobaric_heat_capacity(&self, contributions: PyContributions) -> Entropy {
+        self.0.isobaric_heat_capacity(contributions.into())
+    }
+
     /// Return molar isobaric heat capacity.
     ///
     /// Parameters
@@ -1040,6 +1258,102 @@ impl PyState {
         self.0.isothermal_compressibility()
     }
 
+    /// Return the phase identification parameter.
+    ///
+    /// States with values larger than 1 are liquid-like,
+    /// states with values smaller than 1 are vapor-like.
+    ///
+    /// Returns
+    /// -------
+    /// float
+    fn phase_identification_parameter(&self) -> f64 {
+        self.0.phase_identification_parameter()
+    }
+
+    /// Return the molar exergy (flow availability) relative to a dead state.
+    ///
+    /// The exergy is the sum of the physical exergy and the chemical exergy,
+    /// which is calculated from the fugacities at the dead state.
+    ///
+    /// Parameters
+    /// ----------
+    /// temperature : SINumber
+    ///     Temperature of the dead state.
+    /// pressure : SINumber
+    ///     Pressure of the dead state.
+    /// molefracs : numpy.ndarray
+    ///     Composition of the environment.
+    ///
+    /// Returns
+    /// -------
+    /// SINumber
+    fn molar_exergy(
+        &self,
+        temperature: Temperature,
+        pressure: Pressure,
+        molefracs: &Bound<'_, PyArray1<f64>>,
+    ) -> PyResult<MolarEnergy> {
+        let dead_state = self.dead_state(temperature, pressure, molefracs)?;
+        Ok(self
+            .0
+            .molar_exergy(&dead_state)
+            .map_err(PyFeosError::from)?)
+    }
+
+    /// Return the molar physical (thermo-mechanical) exergy relative to a dead state.
+    ///
+    /// Parameters
+    /// ----------
+    /// temperature : SINumber
+    ///     Temperature of the dead state.
+    /// pressure : SINumber
+    ///     Pressure of the dead state.
+    /// molefracs : numpy.ndarray
+    ///     Composition of the environment.
+    ///
+    /// Returns
+    /// -------
+    /// SINumber
+    fn molar_physical_exergy(
+        &self,
+        temperature: Temperature,
+        pressure: Pressure,
+        molefracs: &Bound<'_, PyArray1<f64>>,
+    ) -> PyResult<MolarEnergy> {
+        let dead_state = self.dead_state(temperature, pressure, molefracs)?;
+        Ok(self
+            .0
+            .molar_physical_exergy(&dead_state)
+            .map_err(PyFeosError::from)?)
+    }
+
+    /// Return the molar chemical exergy relative to a dead state.
+    ///
+    /// Parameters
+    /// ----------
+    /// temperature : SINumber
+    ///     Temperature of the dead state.
+    /// pressure : SINumber
+    ///     Pressure of the dead state.
+    /// molefracs : numpy.ndarray
+    ///     Composition of the environment.
+    ///
+    /// Returns
+    /// -------
+    /// SINumber
+    fn molar_chemical_exergy(
+        &self,
+        temperature: Temperature,
+        pressure: Pressure,
+        molefracs: &Bound<'_, PyArray1<f64>>,
+    ) -> PyResult<MolarEnergy> {
+        let dead_state = self.dead_state(temperature, pressure, molefracs)?;
+        Ok(self
+            .0
+            .molar_chemical_exergy(&dead_state)
+            .map_err(PyFeosError::from)?)
+    }
+
     /// Return isenthalpic compressibility coefficient.
     ///
     /// Returns
@@ -1112,6 +1426,15 @@ impl PyState {
         self.0.total_mass()
     }
 
+    /// Returns the molar volume.
+    ///
+    /// Returns
+    /// -------
+    /// SINumber
+    fn molar_volume(&self) -> MolarVolume {
+        self.0.molar_volume()
+    }
+
     /// Returns system's mass density.
     ///
     /// Returns